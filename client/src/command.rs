@@ -32,6 +32,7 @@ pub enum Command {
     OutputFormat(Option<String>),
     WriteLineProtocol(String),
     ChangeTenant(String),
+    ReadConsistency(Option<String>),
 }
 
 pub enum OutputFormat {
@@ -101,6 +102,18 @@ impl Command {
                 ctx.set_tenant(tenant.to_owned());
                 Ok(())
             }
+            Self::ReadConsistency(read_consistency) => {
+                if let Some(read_consistency) = read_consistency {
+                    ctx.set_read_consistency(Some(read_consistency.to_owned()));
+                    println!("Read consistency set to {}", read_consistency);
+                } else {
+                    println!(
+                        "Read consistency is {}",
+                        ctx.get_read_consistency().unwrap_or("leader")
+                    );
+                }
+                Ok(())
+            }
         }
     }
 
@@ -118,11 +131,15 @@ impl Command {
             Self::OutputFormat(_) => ("\\pset [NAME [VALUE]]", "set table output option\n(format)"),
             Self::WriteLineProtocol(_) => ("\\w path", "line protocol"),
             Self::ChangeTenant(_) => ("\\change_tenant <TenantName>", "change tenant."),
+            Self::ReadConsistency(_) => (
+                "\\read_consistency (leader|follower(<max_lag>))?",
+                "print or set read consistency",
+            ),
         }
     }
 }
 
-const ALL_COMMANDS: [Command; 11] = [
+const ALL_COMMANDS: [Command; 12] = [
     Command::ConnectDatabase(String::new()),
     Command::ListTables,
     Command::DescribeTable(String::new()),
@@ -134,6 +151,7 @@ const ALL_COMMANDS: [Command; 11] = [
     Command::QuietMode(None),
     Command::OutputFormat(None),
     Command::WriteLineProtocol(String::new()),
+    Command::ReadConsistency(None),
 ];
 
 fn all_commands_info() -> Result<ResultSet> {
@@ -180,6 +198,9 @@ impl FromStr for Command {
             ("pset", None) => Self::OutputFormat(None),
             ("w", Some(path)) => Self::WriteLineProtocol(path.into()),
             ("db", Some(db)) => Self::DescribeDatabase(db.to_string()),
+            ("read_consistency", arg) => {
+                Self::ReadConsistency(arg.map(|read_consistency| read_consistency.trim().into()))
+            }
             _ => return Err(()),
         })
     }
@@ -38,6 +38,7 @@ pub struct SessionConfig {
     pub precision: String,
     pub target_partitions: Option<usize>,
     pub stream_trigger_interval: Option<String>,
+    pub read_consistency: Option<String>,
     pub accept_encoding: Option<Encoding>,
     pub content_encoding: Option<Encoding>,
     pub fmt: PrintFormat,
@@ -62,6 +63,7 @@ impl SessionConfig {
             precision: DEFAULT_PRECISION.to_string(),
             target_partitions: None,
             stream_trigger_interval: None,
+            read_consistency: None,
             accept_encoding: None,
             content_encoding: None,
             config_options,
@@ -113,6 +115,11 @@ impl SessionConfig {
         self
     }
 
+    pub fn with_read_consistency(mut self, read_consistency: Option<String>) -> Self {
+        self.read_consistency = read_consistency;
+        self
+    }
+
     pub fn with_accept_encoding(mut self, accept_encoding: Option<Encoding>) -> Self {
         self.accept_encoding = accept_encoding;
         self
@@ -245,6 +252,14 @@ impl SessionContext {
         self.session_config.tenant = tenant
     }
 
+    pub fn set_read_consistency(&mut self, read_consistency: Option<String>) {
+        self.session_config.read_consistency = read_consistency
+    }
+
+    pub fn get_read_consistency(&self) -> Option<&str> {
+        self.session_config.read_consistency.as_deref()
+    }
+
     pub fn get_database(&self) -> &str {
         self.session_config.database.as_str()
     }
@@ -265,6 +280,7 @@ impl SessionContext {
         let db = self.session_config.database.clone();
         let target_partitions = self.session_config.target_partitions;
         let stream_trigger_interval = self.session_config.stream_trigger_interval.clone();
+        let read_consistency = self.session_config.read_consistency.clone();
         let chunked = self.session_config.chunked;
         let param = SqlParam {
             tenant: Some(tenant),
@@ -272,6 +288,7 @@ impl SessionContext {
             chunked: Some(chunked),
             target_partitions,
             stream_trigger_interval,
+            read_consistency,
         };
 
         // let param = &[("db", &self.session_config.database)];
@@ -353,6 +370,7 @@ impl SessionContext {
             precision: Some(precision),
             tenant: Some(tenant),
             db: Some(db),
+            ..Default::default()
         };
 
         let mut builder = self
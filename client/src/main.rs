@@ -64,6 +64,11 @@ struct CliArgs {
     #[arg(short, long)]
     stream_trigger_interval: Option<String>,
 
+    /// Optionally, read from the followers whose data lag behind the leader by at most max_lag
+    /// raft log entries. e.g. leader, follower(100) .
+    #[arg(long)]
+    read_consistency: Option<String>,
+
     /// Path to your data, default to current directory
     #[arg(long, value_parser = try_parse_data_dir)]
     data_path: Option<String>,
@@ -192,6 +197,7 @@ pub async fn main() -> Result<(), anyhow::Error> {
         .with_database(args.database)
         .with_target_partitions(args.target_partitions)
         .with_stream_trigger_interval(args.stream_trigger_interval)
+        .with_read_consistency(args.read_consistency)
         .with_accept_encoding(args.receive_data_encoding)
         .with_content_encoding(args.send_data_encoding)
         .with_result_format(args.format)
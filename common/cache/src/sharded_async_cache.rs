@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use async_trait::async_trait;
 use futures::future::join_all;
@@ -18,6 +19,8 @@ where
     V: Debug + Clone,
 {
     shard: Vec<Mutex<Box<dyn Cache<K = K, V = V>>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 impl<K, V> ShardedAsyncCache<K, V>
@@ -30,7 +33,11 @@ where
         let shard = (0..NUM_SHARDS)
             .map(|_| Mutex::new(Box::new(LruWrap::new(per_shard)) as Box<dyn Cache<K = K, V = V>>))
             .collect::<Vec<Mutex<Box<dyn Cache<K = K, V = V>>>>>();
-        Self { shard }
+        Self {
+            shard,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
     }
 
     pub async fn pop_shard(&self, key: &K) -> Option<(K, V)> {
@@ -45,6 +52,16 @@ where
         let index = shard(&key, self.shard.len());
         Some(self.shard.get(index)?.lock().await)
     }
+
+    /// Count of `get` calls that found the key.
+    pub fn hit_count(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Count of `get` calls that didn't find the key.
+    pub fn miss_count(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
 }
 
 #[async_trait]
@@ -63,7 +80,13 @@ where
 
     async fn get(&self, key: &Self::K) -> Option<Self::V> {
         let index = shard(&key, self.shard.len());
-        self.shard.get(index)?.lock().await.get(key)
+        let value = self.shard.get(index)?.lock().await.get(key);
+        if value.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        value
     }
 
     async fn remove(&self, key: &Self::K) -> Option<Self::V> {
@@ -4,6 +4,10 @@ pub use reqwest::header::{HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 // header
 // privateKey
 pub const PRIVATE_KEY: &str = "X-CnosDB-PrivateKey";
+// writes with the same key are applied only once
+pub const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+// the gRPC metadata of IDEMPOTENCY_KEY, metadata keys are lowercase
+pub const GRPC_IDEMPOTENCY_KEY: &str = "idempotency-key";
 
 // value
 pub const APPLICATION_PREFIX: &str = "application/";
@@ -11,6 +15,7 @@ pub const APPLICATION_CSV: &str = "application/csv";
 pub const APPLICATION_TSV: &str = "application/tsv";
 pub const APPLICATION_JSON: &str = "application/json";
 pub const APPLICATION_NDJSON: &str = "application/nd-json";
+pub const APPLICATION_PROTOBUF: &str = "application/x-protobuf";
 pub const APPLICATION_TABLE: &str = "application/table";
 pub const APPLICATION_STAR: &str = "application/*";
 pub const STAR_STAR: &str = "*/*";
@@ -24,6 +29,7 @@ pub const TENANT: &str = "tenant";
 pub const DB: &str = "db";
 pub const TARGET_PARTITIONS: &str = "target_partitions";
 pub const STREAM_TRIGGER_INTERVAL: &str = "stream_trigger_interval";
+pub const READ_CONSISTENCY: &str = "read_consistency";
 
 // encoding
 pub const GZIP: &str = "gzip";
@@ -9,14 +9,28 @@ pub struct SqlParam {
     // Number of partitions for query execution. Increasing partitions can increase concurrency.
     pub target_partitions: Option<usize>,
     pub stream_trigger_interval: Option<String>,
+    // `leader` or `follower(max_lag)`
+    pub read_consistency: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub struct WriteParam {
     pub precision: Option<String>,
     pub tenant: Option<String>,
     pub db: Option<String>,
+    // `ndjson` or `csv`, the body is line protocol if neither format nor profile is set
+    pub format: Option<String>,
+    // name of the ingestion profile, the other column-mapping params override its options
+    pub profile: Option<String>,
+    pub table: Option<String>,
+    pub time_column: Option<String>,
+    pub time_format: Option<String>,
+    // comma separated tag columns
+    pub tags: Option<String>,
+    // comma separated field columns, like `temperature:double,humidity:double`
+    pub fields: Option<String>,
+    pub delimiter: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
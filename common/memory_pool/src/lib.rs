@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -82,6 +83,157 @@ impl MemoryPool for GreedyMemoryPool {
     }
 }
 
+/// A [`MemoryPool`] with its own limit that allocates from a parent pool.
+///
+/// Used to build the hierarchy of pools: process -> tenant -> query,
+/// an allocation succeeds only if all the pools on the path have enough capacity.
+#[derive(Debug)]
+pub struct LimitedMemoryPool {
+    name: String,
+    parent: MemoryPoolRef,
+    limit: AtomicUsize,
+    used: AtomicUsize,
+}
+
+impl LimitedMemoryPool {
+    pub fn new(name: impl Into<String>, parent: MemoryPoolRef, limit: usize) -> Self {
+        Self {
+            name: name.into(),
+            parent,
+            limit: AtomicUsize::new(limit),
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    /// The new limit only applies to the following allocations
+    pub fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Ordering::Relaxed)
+    }
+}
+
+impl MemoryPool for LimitedMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.parent.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.parent.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.used.fetch_add(additional, Ordering::Relaxed);
+        self.parent.grow(reservation, additional);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.used.fetch_sub(shrink, Ordering::Relaxed);
+        self.parent.shrink(reservation, shrink);
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        let limit = self.limit();
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                let new_used = used + additional;
+                (new_used <= limit).then_some(new_used)
+            })
+            .map_err(|used| {
+                DataFusionError::ResourcesExhausted(format!(
+                    "Failed to allocate additional {} bytes in memory pool '{}' with {} bytes already allocated - maximum available is {}",
+                    additional,
+                    self.name,
+                    reservation.size(),
+                    limit.saturating_sub(used)
+                ))
+            })?;
+
+        if let Err(err) = self.parent.try_grow(reservation, additional) {
+            self.used.fetch_sub(additional, Ordering::Relaxed);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
+/// The memory pools of the tenants, all of them allocate from the process-wide pool.
+#[derive(Debug)]
+pub struct TenantMemoryPools {
+    root: MemoryPoolRef,
+    tenants: RwLock<HashMap<String, Arc<LimitedMemoryPool>>>,
+}
+
+impl TenantMemoryPools {
+    pub fn new(root: MemoryPoolRef) -> Self {
+        Self {
+            root,
+            tenants: Default::default(),
+        }
+    }
+
+    /// Returns the pool for a new query of the tenant.
+    ///
+    /// The query allocates from the pool of the tenant if `tenant_limit` is set,
+    /// otherwise from the process-wide pool directly.
+    pub fn query_pool(
+        &self,
+        tenant: &str,
+        tenant_limit: Option<usize>,
+        query_limit: Option<usize>,
+    ) -> MemoryPoolRef {
+        let parent = match tenant_limit {
+            Some(limit) => self.tenant_pool(tenant, limit) as MemoryPoolRef,
+            None => {
+                self.tenants.write().remove(tenant);
+                self.root.clone()
+            }
+        };
+
+        match query_limit {
+            Some(limit) => Arc::new(LimitedMemoryPool::new(
+                format!("query of tenant {tenant}"),
+                parent,
+                limit,
+            )),
+            None => parent,
+        }
+    }
+
+    /// Memory reserved by the queries of the tenant, None if the tenant has no memory limit
+    pub fn tenant_reserved(&self, tenant: &str) -> Option<usize> {
+        self.tenants.read().get(tenant).map(|p| p.reserved())
+    }
+
+    fn tenant_pool(&self, tenant: &str, limit: usize) -> Arc<LimitedMemoryPool> {
+        if let Some(pool) = self.tenants.read().get(tenant) {
+            if pool.limit() != limit {
+                pool.set_limit(limit);
+            }
+            return pool.clone();
+        }
+
+        self.tenants
+            .write()
+            .entry(tenant.to_string())
+            .or_insert_with(|| {
+                Arc::new(LimitedMemoryPool::new(
+                    format!("tenant {tenant}"),
+                    self.root.clone(),
+                    limit,
+                ))
+            })
+            .clone()
+    }
+}
+
 fn insufficient_capacity_err(
     reservation: &MemoryReservation,
     additional: usize,
@@ -126,4 +278,65 @@ mod tests {
         a2.try_grow(25).unwrap();
         assert_eq!(pool.reserved(), 25);
     }
+
+    #[test]
+    fn test_limited_memory_pool() {
+        let root = Arc::new(GreedyMemoryPool::new(100)) as MemoryPoolRef;
+        let tenant = Arc::new(LimitedMemoryPool::new("tenant", root.clone(), 60)) as MemoryPoolRef;
+        let query = Arc::new(LimitedMemoryPool::new("query", tenant.clone(), 40)) as MemoryPoolRef;
+
+        let mut q1 = MemoryConsumer::new("q1").register(&query);
+        q1.try_grow(50).unwrap_err();
+        q1.try_grow(40).unwrap();
+        assert_eq!(query.reserved(), 40);
+        assert_eq!(tenant.reserved(), 40);
+        assert_eq!(root.reserved(), 40);
+
+        // exceeds the tenant limit
+        let mut q2 = MemoryConsumer::new("q2").register(&tenant);
+        q2.try_grow(30).unwrap_err();
+        assert_eq!(tenant.reserved(), 40);
+        assert_eq!(root.reserved(), 40);
+
+        // exceeds the process limit
+        let mut other = MemoryConsumer::new("other").register(&root);
+        other.try_grow(50).unwrap();
+        q2.try_grow(20).unwrap_err();
+        assert_eq!(tenant.reserved(), 40);
+        assert_eq!(root.reserved(), 90);
+
+        drop(q1);
+        assert_eq!(query.reserved(), 0);
+        assert_eq!(tenant.reserved(), 0);
+        assert_eq!(root.reserved(), 50);
+        q2.try_grow(20).unwrap();
+        assert_eq!(tenant.reserved(), 20);
+    }
+
+    #[test]
+    fn test_tenant_memory_pools() {
+        let root = Arc::new(GreedyMemoryPool::new(100)) as MemoryPoolRef;
+        let pools = TenantMemoryPools::new(root.clone());
+
+        let q1 = pools.query_pool("t1", Some(50), Some(30));
+        let q2 = pools.query_pool("t1", Some(50), None);
+        let mut c1 = MemoryConsumer::new("c1").register(&q1);
+        let mut c2 = MemoryConsumer::new("c2").register(&q2);
+        c1.try_grow(30).unwrap();
+        c2.try_grow(30).unwrap_err();
+        c2.try_grow(20).unwrap();
+        assert_eq!(pools.tenant_reserved("t1"), Some(50));
+        assert_eq!(pools.tenant_reserved("t2"), None);
+
+        // the limit of the tenant is changed
+        let _q3 = pools.query_pool("t1", Some(60), None);
+        c2.try_grow(10).unwrap();
+        assert_eq!(pools.tenant_reserved("t1"), Some(60));
+
+        let q4 = pools.query_pool("t2", None, None);
+        let mut c4 = MemoryConsumer::new("c4").register(&q4);
+        c4.try_grow(50).unwrap_err();
+        c4.try_grow(40).unwrap();
+        assert_eq!(root.reserved(), 100);
+    }
 }
@@ -7,6 +7,7 @@ pub mod metric;
 pub mod metric_register;
 pub mod metric_type;
 pub mod metric_value;
+pub mod otlp_reporter;
 pub mod prom_reporter;
 pub mod reporter;
 
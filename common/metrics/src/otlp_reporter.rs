@@ -0,0 +1,487 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::common::v1::KeyValue;
+use opentelemetry_proto::tonic::metrics::v1::{
+    metric, number_data_point, AggregationTemporality, Gauge, Histogram, HistogramDataPoint,
+    Metric, NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum,
+};
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use tokio::task::JoinHandle;
+use trace::otlp::{instrumentation_scope, string_attribute, OtlpClient};
+use trace::warn;
+
+use crate::label::Labels;
+use crate::metric_register::MetricsRegister;
+use crate::metric_type::MetricType;
+use crate::metric_value::{MetricValue, ValueBucket};
+use crate::reporter::Reporter;
+
+/// Converts the metrics to the OTLP metrics, the counters and the histograms are cumulative
+/// since `start_time_unix_nano`.
+#[derive(Debug)]
+pub struct OtlpReporter {
+    start_time_unix_nano: u64,
+    time_unix_nano: u64,
+    current: Option<Metric>,
+    metrics: Vec<Metric>,
+}
+
+impl OtlpReporter {
+    pub fn new(start_time_unix_nano: u64) -> Self {
+        Self {
+            start_time_unix_nano,
+            time_unix_nano: unix_nanos_now(),
+            current: None,
+            metrics: vec![],
+        }
+    }
+
+    pub fn into_request(self, resource: Resource) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(resource),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: Some(instrumentation_scope()),
+                    metrics: self.metrics,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn number_point(
+        &self,
+        attributes: Vec<KeyValue>,
+        value: number_data_point::Value,
+    ) -> NumberDataPoint {
+        NumberDataPoint {
+            attributes,
+            start_time_unix_nano: self.start_time_unix_nano,
+            time_unix_nano: self.time_unix_nano,
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    fn histogram_point(
+        &self,
+        attributes: Vec<KeyValue>,
+        buckets: impl IntoIterator<Item = (f64, u64)>,
+        sum: f64,
+    ) -> HistogramDataPoint {
+        let mut explicit_bounds = vec![];
+        let mut bucket_counts = vec![];
+        let mut overflow = false;
+        for (le, count) in buckets {
+            bucket_counts.push(count);
+            if le.is_finite() {
+                explicit_bounds.push(le);
+            } else {
+                overflow = true;
+            }
+        }
+        // The last bucket of OTLP is always the overflow bucket
+        if !overflow {
+            bucket_counts.push(0);
+        }
+
+        HistogramDataPoint {
+            attributes,
+            start_time_unix_nano: self.start_time_unix_nano,
+            time_unix_nano: self.time_unix_nano,
+            count: bucket_counts.iter().sum(),
+            sum: Some(sum),
+            bucket_counts,
+            explicit_bounds,
+            ..Default::default()
+        }
+    }
+
+    fn push_histogram_point(&mut self, point: HistogramDataPoint) {
+        if let Some(metric::Data::Histogram(histogram)) =
+            self.current.as_mut().and_then(|m| m.data.as_mut())
+        {
+            histogram.data_points.push(point);
+        }
+    }
+}
+
+impl Reporter for OtlpReporter {
+    fn start(
+        &mut self,
+        name: Cow<'static, str>,
+        description: Cow<'static, str>,
+        metrics_type: MetricType,
+    ) {
+        let sum = || {
+            metric::Data::Sum(Sum {
+                data_points: vec![],
+                aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                is_monotonic: true,
+            })
+        };
+        let gauge = || {
+            metric::Data::Gauge(Gauge {
+                data_points: vec![],
+            })
+        };
+        let histogram = || {
+            metric::Data::Histogram(Histogram {
+                data_points: vec![],
+                aggregation_temporality: AggregationTemporality::Cumulative as i32,
+            })
+        };
+
+        let (unit, data) = match metrics_type {
+            MetricType::U64Counter => ("", sum()),
+            MetricType::U64Gauge => ("", gauge()),
+            MetricType::U64Histogram => ("", histogram()),
+            MetricType::DurationCounter => ("s", sum()),
+            MetricType::DurationGauge => ("s", gauge()),
+            MetricType::DurationHistogram => ("s", histogram()),
+            MetricType::UnTyped => return,
+        };
+
+        self.current = Some(Metric {
+            name: name.into_owned(),
+            description: description.into_owned(),
+            unit: unit.to_string(),
+            data: Some(data),
+        });
+    }
+
+    fn report(&mut self, labels: &Labels, metrics_value: MetricValue) {
+        let attributes = labels
+            .iter()
+            .map(|(key, value)| string_attribute(*key, value.to_string()))
+            .collect::<Vec<_>>();
+
+        let point = match &metrics_value {
+            MetricValue::U64Counter(v) | MetricValue::U64Gauge(v) => {
+                Some(self.number_point(attributes, number_data_point::Value::AsInt(*v as i64)))
+            }
+            MetricValue::DurationCounter(v) | MetricValue::DurationGauge(v) => {
+                Some(self.number_point(
+                    attributes,
+                    number_data_point::Value::AsDouble(v.as_secs_f64()),
+                ))
+            }
+            MetricValue::U64Histogram(v) => {
+                let histogram_point = self.histogram_point(
+                    attributes,
+                    v.buckets.iter().map(|b| (u64_bound(b), b.count)),
+                    v.total as f64,
+                );
+                self.push_histogram_point(histogram_point);
+                None
+            }
+            MetricValue::DurationHistogram(v) => {
+                let histogram_point = self.histogram_point(
+                    attributes,
+                    v.buckets.iter().map(|b| (duration_bound(b), b.count)),
+                    v.total.as_secs_f64(),
+                );
+                self.push_histogram_point(histogram_point);
+                None
+            }
+            MetricValue::Null => None,
+        };
+
+        let (Some(point), Some(metric)) = (point, self.current.as_mut()) else {
+            return;
+        };
+        match metric.data.as_mut() {
+            Some(metric::Data::Sum(sum)) => sum.data_points.push(point),
+            Some(metric::Data::Gauge(gauge)) => gauge.data_points.push(point),
+            _ => {}
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(metric) = self.current.take() {
+            let is_empty = match &metric.data {
+                Some(metric::Data::Sum(sum)) => sum.data_points.is_empty(),
+                Some(metric::Data::Gauge(gauge)) => gauge.data_points.is_empty(),
+                Some(metric::Data::Histogram(histogram)) => histogram.data_points.is_empty(),
+                _ => true,
+            };
+            if !is_empty {
+                self.metrics.push(metric);
+            }
+        }
+    }
+}
+
+fn u64_bound(bucket: &ValueBucket<u64>) -> f64 {
+    match bucket.le {
+        u64::MAX => f64::INFINITY,
+        le => le as f64,
+    }
+}
+
+fn duration_bound(bucket: &ValueBucket<Duration>) -> f64 {
+    match bucket.le {
+        crate::DURATION_MAX => f64::INFINITY,
+        le => le.as_secs_f64(),
+    }
+}
+
+fn unix_nanos_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// Exports the metrics of the register to the collector at the interval
+pub fn start_otlp_metrics_exporter(
+    register: Arc<MetricsRegister>,
+    client: OtlpClient,
+    resource: Resource,
+    interval: Duration,
+) -> JoinHandle<()> {
+    let start_time_unix_nano = unix_nanos_now();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let mut reporter = OtlpReporter::new(start_time_unix_nano);
+            register.report(&mut reporter);
+            let request = reporter.into_request(resource.clone());
+            if let Err(e) = client.export_metrics(request).await {
+                warn!("Failed to export metrics by otlp: {e}");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use config::{OtlpExporterConfig, OtlpProtocol};
+    use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_server::{
+        MetricsService, MetricsServiceServer,
+    };
+    use opentelemetry_proto::tonic::collector::metrics::v1::{
+        ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+    };
+    use opentelemetry_proto::tonic::metrics::v1::{metric, number_data_point};
+    use prost::Message;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc::{self, UnboundedSender};
+    use tokio_stream::wrappers::TcpListenerStream;
+    use trace::otlp::{otlp_resource, string_attribute, OtlpClient};
+
+    use super::{start_otlp_metrics_exporter, OtlpReporter};
+    use crate::count::U64Counter;
+    use crate::duration::{DurationHistogram, DurationHistogramOptions};
+    use crate::gauge::U64Gauge;
+    use crate::metric::Metric;
+    use crate::metric_register::MetricsRegister;
+    use crate::DURATION_MAX;
+
+    #[test]
+    fn test_otlp_reporter() {
+        let register = MetricsRegister::new([("node_id", "1001")]);
+        let counter: Metric<U64Counter> = register.metric("writes", "points written");
+        counter.recorder([("tenant", "cnosdb")]).inc(3);
+        let gauge: Metric<U64Gauge> = register.metric("cache_size", "");
+        gauge.recorder([("vnode", "1")]).set(10);
+        let _unused: Metric<U64Gauge> = register.metric("unused", "");
+        let histogram: Metric<DurationHistogram> = register.register_metric(
+            "query_duration",
+            "",
+            DurationHistogramOptions::new([Duration::from_secs(1), DURATION_MAX]),
+        );
+        histogram
+            .recorder([("tenant", "cnosdb")])
+            .record(Duration::from_millis(500));
+        histogram
+            .recorder([("tenant", "cnosdb")])
+            .record(Duration::from_secs(2));
+
+        let mut reporter = OtlpReporter::new(1);
+        register.report(&mut reporter);
+        let request = reporter.into_request(otlp_resource([]));
+        let mut metrics = request.resource_metrics[0].scope_metrics[0].metrics.clone();
+        metrics.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            metrics.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(),
+            vec!["cache_size", "query_duration", "writes"]
+        );
+
+        match metrics[2].data.as_ref().unwrap() {
+            metric::Data::Sum(sum) => {
+                assert!(sum.is_monotonic);
+                let point = &sum.data_points[0];
+                assert_eq!(point.start_time_unix_nano, 1);
+                assert_eq!(point.value, Some(number_data_point::Value::AsInt(3)));
+                assert!(point
+                    .attributes
+                    .contains(&string_attribute("tenant", "cnosdb")));
+                assert!(point
+                    .attributes
+                    .contains(&string_attribute("node_id", "1001")));
+            }
+            _ => panic!("writes should be a sum"),
+        }
+
+        match metrics[1].data.as_ref().unwrap() {
+            metric::Data::Histogram(histogram) => {
+                let point = &histogram.data_points[0];
+                assert_eq!(point.explicit_bounds, vec![1.0]);
+                assert_eq!(point.bucket_counts, vec![1, 1]);
+                assert_eq!(point.count, 2);
+                assert_eq!(point.sum, Some(2.5));
+            }
+            _ => panic!("query_duration should be a histogram"),
+        }
+    }
+
+    /// A collector stand-in receiving the metrics by OTLP/gRPC
+    struct MetricsCollector {
+        requests: UnboundedSender<ExportMetricsServiceRequest>,
+    }
+
+    #[tonic::async_trait]
+    impl MetricsService for MetricsCollector {
+        async fn export(
+            &self,
+            request: tonic::Request<ExportMetricsServiceRequest>,
+        ) -> Result<tonic::Response<ExportMetricsServiceResponse>, tonic::Status> {
+            let _ = self.requests.send(request.into_inner());
+            Ok(tonic::Response::new(ExportMetricsServiceResponse::default()))
+        }
+    }
+
+    /// Accepts one http request, responds with 200 and returns the request line,
+    /// the headers and the body of the request
+    async fn receive_one(listener: TcpListener) -> (String, Vec<u8>) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = vec![];
+        let mut buf = [0_u8; 4096];
+        let body_start = loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+        let headers = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+        let content_length = headers
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length:"))
+            .map(|l| l.trim().parse::<usize>().unwrap())
+            .unwrap();
+        while request.len() < body_start + content_length {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+        }
+
+        let response = "HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n";
+        stream.write_all(response.as_bytes()).await.unwrap();
+
+        (headers, request[body_start..].to_vec())
+    }
+
+    fn start_exporter(protocol: OtlpProtocol, addr: SocketAddr) -> tokio::task::JoinHandle<()> {
+        let register = Arc::new(MetricsRegister::new([("node_id", "1001")]));
+        let counter: Metric<U64Counter> = register.metric("writes", "points written");
+        counter.recorder([("tenant", "cnosdb")]).inc(3);
+        let gauge: Metric<U64Gauge> = register.metric("cache_size", "");
+        gauge.recorder([("vnode", "1")]).set(10);
+
+        let config = OtlpExporterConfig {
+            endpoint: format!("http://{addr}"),
+            protocol,
+            ..Default::default()
+        };
+        let client = OtlpClient::try_new(&config).unwrap();
+        let resource = otlp_resource([("service.name".to_string(), "cnosdb".to_string())]);
+        start_otlp_metrics_exporter(register, client, resource, Duration::from_millis(50))
+    }
+
+    fn check_request(request: &ExportMetricsServiceRequest) {
+        let resource_metrics = &request.resource_metrics[0];
+        assert_eq!(
+            resource_metrics.resource.as_ref().unwrap().attributes,
+            vec![string_attribute("service.name", "cnosdb")]
+        );
+
+        let mut metrics = resource_metrics.scope_metrics[0].metrics.clone();
+        metrics.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            metrics.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(),
+            vec!["cache_size", "writes"]
+        );
+        assert_eq!(metrics[1].description, "points written");
+
+        match metrics[0].data.as_ref().unwrap() {
+            metric::Data::Gauge(gauge) => {
+                let point = &gauge.data_points[0];
+                assert_eq!(point.value, Some(number_data_point::Value::AsInt(10)));
+                assert!(point.attributes.contains(&string_attribute("vnode", "1")));
+            }
+            _ => panic!("cache_size should be a gauge"),
+        }
+        match metrics[1].data.as_ref().unwrap() {
+            metric::Data::Sum(sum) => {
+                let point = &sum.data_points[0];
+                assert_eq!(point.value, Some(number_data_point::Value::AsInt(3)));
+                assert!(point
+                    .attributes
+                    .contains(&string_attribute("node_id", "1001")));
+            }
+            _ => panic!("writes should be a sum"),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_export_by_grpc() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(MetricsServiceServer::new(MetricsCollector {
+                    requests: sender,
+                }))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let exporter = start_exporter(OtlpProtocol::Grpc, addr);
+        let request = tokio::time::timeout(Duration::from_secs(10), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        exporter.abort();
+
+        check_request(&request);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_export_by_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let receiver = tokio::spawn(receive_one(listener));
+
+        let exporter = start_exporter(OtlpProtocol::Http, addr);
+        let (headers, body) = tokio::time::timeout(Duration::from_secs(10), receiver)
+            .await
+            .unwrap()
+            .unwrap();
+        exporter.abort();
+
+        assert!(headers.starts_with("post /v1/metrics "));
+        assert!(headers.contains("content-type: application/x-protobuf"));
+        check_request(&ExportMetricsServiceRequest::decode(body.as_slice()).unwrap());
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::meta_data::NodeId;
+
+/// The label holding the name of the alert rule in the alerts.
+pub const ALERT_NAME_LABEL: &str = "alertname";
+/// The label holding the severity of the alert rule in the alerts.
+pub const SEVERITY_LABEL: &str = "severity";
+/// The severity of the alert rules created without SEVERITY option.
+pub const DEFAULT_ALERT_SEVERITY: &str = "warning";
+
+/// An alert rule evaluated periodically, every row returned by the query is an alert.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub tenant: String,
+    pub name: String,
+    pub database: String,
+    /// The name of the user creating the rule, the query runs with the privileges
+    /// the user has when the rule is evaluated
+    pub owner: String,
+    pub query: String,
+    pub every: Duration,
+    /// How long an alert keeps pending before it fires
+    pub for_duration: Duration,
+    pub severity: String,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    /// The webhook receiving the notifications, the default webhook of the server if None
+    pub webhook: Option<String>,
+    /// The rule is evaluated by the node it was created on,
+    /// or by another node while that node is unreachable
+    pub node_id: NodeId,
+}
+
+impl AlertRule {
+    /// The labels shared by all the alerts of the rule.
+    pub fn rule_labels(&self) -> BTreeMap<String, String> {
+        let mut labels = self.labels.clone();
+        labels.insert(ALERT_NAME_LABEL.to_string(), self.name.clone());
+        labels.insert(SEVERITY_LABEL.to_string(), self.severity.clone());
+        labels
+    }
+}
+
+/// The state of an alert, following the state machine of the Prometheus alerting rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertState {
+    /// The alert is active but has not been active for the FOR duration of the rule yet
+    Pending,
+    /// The alert has been active for the FOR duration of the rule
+    Firing,
+    /// The alert was firing and is no longer returned by the query of the rule
+    Resolved,
+}
+
+impl AlertState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Firing => "firing",
+            Self::Resolved => "resolved",
+        }
+    }
+}
+
+impl std::fmt::Display for AlertState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for AlertState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "firing" => Ok(Self::Firing),
+            "resolved" => Ok(Self::Resolved),
+            _ => Err(format!("unknown alert state: {s}")),
+        }
+    }
+}
+
+/// An alert of an alert rule, identified by its labels.
+///
+/// All the timestamps are nanoseconds since the unix epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    pub state: AlertState,
+    pub value: Option<f64>,
+    pub active_at: i64,
+    pub fired_at: Option<i64>,
+    pub resolved_at: Option<i64>,
+    /// When the alert was last delivered to the webhook
+    pub last_sent_at: Option<i64>,
+}
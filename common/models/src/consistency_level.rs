@@ -1,3 +1,8 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum ConsistencyLevel {
@@ -10,3 +15,77 @@ pub enum ConsistencyLevel {
     /// requires all data nodes to acknowledge a write or read.
     All,
 }
+
+/// Which vnode of a replication set serves the reads.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReadConsistency {
+    /// Always read from the leader.
+    #[default]
+    Leader,
+    /// Read from a follower whose raft applied index lags behind the leader's by at most
+    /// `max_lag` log entries, fall back to the leader if there is no such follower.
+    Follower { max_lag: u64 },
+}
+
+impl FromStr for ReadConsistency {
+    type Err = String;
+
+    /// Parse `leader` or `follower(max_lag)`, case insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        if s == "leader" {
+            return Ok(ReadConsistency::Leader);
+        }
+
+        s.strip_prefix("follower")
+            .map(|args| args.trim())
+            .and_then(|args| args.strip_prefix('('))
+            .and_then(|args| args.strip_suffix(')'))
+            .and_then(|max_lag| max_lag.trim().parse::<u64>().ok())
+            .map(|max_lag| ReadConsistency::Follower { max_lag })
+            .ok_or_else(|| {
+                format!(
+                    "invalid read consistency '{}', expected 'leader' or 'follower(max_lag)'",
+                    s
+                )
+            })
+    }
+}
+
+impl Display for ReadConsistency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadConsistency::Leader => write!(f, "leader"),
+            ReadConsistency::Follower { max_lag } => write!(f, "follower({})", max_lag),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::ReadConsistency;
+
+    #[test]
+    fn test_parse_read_consistency() {
+        assert_eq!(
+            ReadConsistency::from_str("leader").unwrap(),
+            ReadConsistency::Leader
+        );
+        assert_eq!(
+            ReadConsistency::from_str(" Follower( 100 ) ").unwrap(),
+            ReadConsistency::Follower { max_lag: 100 }
+        );
+        assert_eq!(
+            ReadConsistency::from_str("follower(0)")
+                .unwrap()
+                .to_string(),
+            "follower(0)"
+        );
+
+        assert!(ReadConsistency::from_str("follower").is_err());
+        assert!(ReadConsistency::from_str("follower(-1)").is_err());
+        assert!(ReadConsistency::from_str("quorum").is_err());
+    }
+}
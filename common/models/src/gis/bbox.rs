@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+/// The axis-aligned bounding box of geometries.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
+pub struct BoundingBox {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl BoundingBox {
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    pub fn from_point(x: f64, y: f64) -> Self {
+        Self::new(x, y, x, y)
+    }
+
+    /// Returns the bounding box of a WKT geometry, only the x and y of the coordinates
+    /// are taken into account.
+    ///
+    /// Returns `None` if the geometry is empty or the text is malformed.
+    pub fn from_wkt(wkt: &str) -> Option<Self> {
+        let mut bbox: Option<Self> = None;
+        let text = wkt.replace(['(', ')'], " ");
+        for coord in text.split(',') {
+            let mut numbers = coord
+                .split_whitespace()
+                .filter_map(|token| token.parse::<f64>().ok());
+            match (numbers.next(), numbers.next()) {
+                (Some(x), Some(y)) => {
+                    let point = Self::from_point(x, y);
+                    bbox = Some(bbox.map_or(point, |b| b.union(&point)));
+                }
+                (Some(_), None) => return None,
+                _ => {}
+            }
+        }
+        bbox
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            self.min_x.min(other.min_x),
+            self.min_y.min(other.min_y),
+            self.max_x.max(other.max_x),
+            self.max_y.max(other.max_y),
+        )
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+
+    /// Returns the bounding box grown by `distance` in all directions.
+    pub fn expand(&self, distance: f64) -> Self {
+        Self::new(
+            self.min_x - distance,
+            self.min_y - distance,
+            self.max_x + distance,
+            self.max_y + distance,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundingBox;
+
+    #[test]
+    fn test_bbox_from_wkt() {
+        assert_eq!(
+            BoundingBox::from_wkt("POINT(1.5 -2)"),
+            Some(BoundingBox::new(1.5, -2.0, 1.5, -2.0))
+        );
+        assert_eq!(
+            BoundingBox::from_wkt("POLYGON((0 0, 4 0, 4 3, 0 3, 0 0), (1 1, 2 1, 2 2, 1 1))"),
+            Some(BoundingBox::new(0.0, 0.0, 4.0, 3.0))
+        );
+        assert_eq!(
+            BoundingBox::from_wkt("MULTIPOINT Z ((1 2 10), (-3 5 20))"),
+            Some(BoundingBox::new(-3.0, 2.0, 1.0, 5.0))
+        );
+        assert_eq!(BoundingBox::from_wkt("POINT EMPTY"), None);
+        assert_eq!(BoundingBox::from_wkt("POINT(1)"), None);
+    }
+
+    #[test]
+    fn test_bbox_intersects() {
+        let a = BoundingBox::new(0.0, 0.0, 2.0, 2.0);
+        assert!(a.intersects(&BoundingBox::new(2.0, 2.0, 3.0, 3.0)));
+        assert!(!a.intersects(&BoundingBox::new(2.5, 0.0, 3.0, 1.0)));
+        assert!(a
+            .expand(0.5)
+            .intersects(&BoundingBox::new(2.5, 0.0, 3.0, 1.0)));
+    }
+}
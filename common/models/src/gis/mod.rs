@@ -1 +1,2 @@
+pub mod bbox;
 pub mod data_type;
@@ -0,0 +1,14 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A named column-mapping spec for writing NDJSON or CSV rows by `/api/v1/write?profile=name`.
+///
+/// The options are the same as the query parameters of `/api/v1/write`,
+/// like `format`, `table`, `time_column`, `tags` and `fields`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestionProfile {
+    pub tenant: String,
+    pub name: String,
+    pub options: BTreeMap<String, String>,
+}
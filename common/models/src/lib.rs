@@ -21,16 +21,19 @@ mod value_type;
 #[macro_use]
 // pub mod error_code;
 pub mod arrow_array;
+pub mod alert;
 pub mod arrow;
 pub mod auth;
 pub mod datafusion;
 pub mod duration;
 pub mod field_value;
 pub mod gis;
+pub mod ingestion;
 pub mod mutable_batch;
 pub mod object_reference;
 pub mod oid;
 pub mod predicate;
+pub mod query_resource;
 pub mod record_batch;
 pub mod runtime;
 pub mod snappy;
@@ -24,6 +24,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use super::transformation::RowExpressionToDomainsVisitor;
 use super::utils::filter_to_time_ranges;
 use super::PlacedSplit;
+use crate::gis::bbox::BoundingBox;
 use crate::schema::{ColumnType, TableColumn, TskvTableSchemaRef};
 use crate::{Error, Result, Timestamp};
 
@@ -1168,6 +1169,28 @@ impl Domain {
             entries,
         })
     }
+    /// Return the values if the domain only matches a finite set of values,
+    /// like `a = 'x'` or `a IN ('x', 'y')`.
+    pub fn equal_values(&self) -> Option<Vec<&ScalarValue>> {
+        match self {
+            Self::Range(range_set) => range_set
+                .low_indexed_ranges
+                .values()
+                .map(|range| match (range.start_bound(), range.end_bound()) {
+                    (std::ops::Bound::Included(low), std::ops::Bound::Included(high))
+                        if low == high =>
+                    {
+                        Some(low)
+                    }
+                    _ => None,
+                })
+                .collect(),
+            Self::Equtable(val_set) if val_set.white_list => {
+                Some(val_set.entries.iter().map(|e| &e.value).collect())
+            }
+            _ => None,
+        }
+    }
     /// Calculates the intersection of two ranges, and returns None if the intersection does not exist
     ///
     /// This method returns the new value without changing the old value
@@ -1635,11 +1658,32 @@ impl<'de> Deserialize<'de> for PhysicalExprNodeWrap {
     }
 }
 
+/// A spatial predicate on a geometry column, only the rows whose geometry's bounding box
+/// intersects `bbox` may match it.
+///
+/// It is extracted from the spatial functions (e.g. `st_within`) of the filter, which
+/// can not be pushed down as physical expressions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpatialFilter {
+    pub column: String,
+    pub bbox: BoundingBox,
+}
+
+impl SpatialFilter {
+    pub fn new(column: impl Into<String>, bbox: BoundingBox) -> Self {
+        Self {
+            column: column.into(),
+            bbox,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedPredicate {
     time_ranges: Arc<TimeRanges>,
     tags_filter: ColumnDomains<String>,
     physical_expr: PhysicalExprNodeWrap,
+    spatial_filters: Vec<SpatialFilter>,
 }
 
 impl ResolvedPredicate {
@@ -1657,13 +1701,28 @@ impl ResolvedPredicate {
             time_ranges,
             tags_filter,
             physical_expr: PhysicalExprNodeWrap(node),
+            spatial_filters: vec![],
         })
     }
 
+    pub fn with_spatial_filters(mut self, spatial_filters: Vec<SpatialFilter>) -> Self {
+        self.spatial_filters = spatial_filters;
+        self
+    }
+
     pub fn time_ranges(&self) -> Arc<TimeRanges> {
         self.time_ranges.clone()
     }
 
+    pub fn with_time_ranges(&self, time_ranges: Arc<TimeRanges>) -> Self {
+        Self {
+            time_ranges,
+            tags_filter: self.tags_filter.clone(),
+            physical_expr: self.physical_expr.clone(),
+            spatial_filters: self.spatial_filters.clone(),
+        }
+    }
+
     pub fn tags_filter(&self) -> &ColumnDomains<String> {
         &self.tags_filter
     }
@@ -1671,6 +1730,19 @@ impl ResolvedPredicate {
     pub fn filter(&self) -> &PhysicalExprNode {
         &self.physical_expr.0
     }
+
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        &self.spatial_filters
+    }
+}
+
+/// How the latest points of the series are read from the last value caches of the vnodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LastValueScan {
+    /// Only the latest row of each series, e.g. for `ORDER BY time DESC LIMIT 1`.
+    Row,
+    /// The last value of each field of each series, e.g. for `last(time, field)`.
+    Fields,
 }
 
 #[derive(Debug)]
@@ -1678,6 +1750,9 @@ pub struct Predicate {
     pushed_down_domains: ColumnDomains<Column>,
     physical_expr: Option<Arc<dyn PhysicalExpr>>,
     limit: Option<usize>,
+    prior_point: Option<Timestamp>,
+    last_value: Option<LastValueScan>,
+    spatial_filters: Vec<SpatialFilter>,
 }
 
 impl Predicate {
@@ -1685,6 +1760,14 @@ impl Predicate {
         self.limit
     }
 
+    pub fn prior_point(&self) -> Option<Timestamp> {
+        self.prior_point
+    }
+
+    pub fn last_value(&self) -> Option<LastValueScan> {
+        self.last_value
+    }
+
     pub fn filter(&self) -> &ColumnDomains<Column> {
         &self.pushed_down_domains
     }
@@ -1693,11 +1776,36 @@ impl Predicate {
         self.physical_expr.as_ref()
     }
 
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        &self.spatial_filters
+    }
+
+    /// Spatial predicates extracted from the filter, which is used to prune the pages
+    /// of the geometry columns by their bounding boxes.
+    pub fn with_spatial_filters(mut self, spatial_filters: Vec<SpatialFilter>) -> Self {
+        self.spatial_filters = spatial_filters;
+        self
+    }
+
     pub fn with_limit(mut self, limit: Option<usize>) -> Self {
         self.limit = limit;
         self
     }
 
+    /// Also read the last point of each series before the timestamp,
+    /// which is not later than the start of the pushed down time ranges
+    pub fn with_prior_point(mut self, prior_point: Option<Timestamp>) -> Self {
+        self.prior_point = prior_point;
+        self
+    }
+
+    /// Read the latest points of the series from the last value caches if possible,
+    /// the series not cached are read from the data as usual
+    pub fn with_last_value(mut self, last_value: Option<LastValueScan>) -> Self {
+        self.last_value = last_value;
+        self
+    }
+
     /// resolve and extract supported filter
     /// convert filter to ColumnDomains and set self
     pub fn push_down_filter(
@@ -1711,6 +1819,9 @@ impl Predicate {
                 pushed_down_domains: ColumnDomains::all(),
                 physical_expr: None,
                 limit,
+                prior_point: None,
+                last_value: None,
+                spatial_filters: vec![],
             }),
             Some(expr) => {
                 let mut push_down_domains = ColumnDomains::all();
@@ -1725,6 +1836,9 @@ impl Predicate {
                     pushed_down_domains: push_down_domains,
                     physical_expr: Some(expr),
                     limit,
+                    prior_point: None,
+                    last_value: None,
+                    spatial_filters: vec![],
                 })
             }
         }
@@ -1750,7 +1864,8 @@ impl Predicate {
             Arc::new(TimeRanges::new(time_ranges)),
             tags_filter,
             self.physical_expr.clone(),
-        )?;
+        )?
+        .with_spatial_filters(self.spatial_filters.clone());
 
         Ok(Arc::new(res))
     }
@@ -2135,6 +2250,29 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_equal_values() {
+        let a = ScalarValue::Utf8(Some("a".to_string()));
+        let b = ScalarValue::Utf8(Some("b".to_string()));
+
+        let domain = Domain::of_ranges(&[
+            Range::eq(&DataType::Utf8, &a),
+            Range::eq(&DataType::Utf8, &b),
+        ])
+        .unwrap();
+        assert_eq!(domain.equal_values(), Some(vec![&a, &b]));
+
+        let domain = Domain::of_values(&DataType::Utf8, true, &[&a]);
+        assert_eq!(domain.equal_values(), Some(vec![&a]));
+
+        let domain = Domain::of_values(&DataType::Utf8, false, &[&a]);
+        assert_eq!(domain.equal_values(), None);
+
+        let domain = Domain::of_ranges(&[Range::gt(&DataType::Utf8, &a)]).unwrap();
+        assert_eq!(domain.equal_values(), None);
+        assert_eq!(Domain::All.equal_values(), None);
+    }
+
     #[test]
     fn test_serialize_physical_expr_node_wrap() {
         let expr = create_physical_expr(
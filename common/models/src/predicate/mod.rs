@@ -4,11 +4,14 @@ use datafusion::physical_expr::PhysicalExpr;
 use datafusion_proto::protobuf::PhysicalExprNode;
 use serde::{Deserialize, Serialize};
 
-use self::domain::{ColumnDomains, PredicateRef, TimeRange, TimeRanges};
+use self::domain::{
+    ColumnDomains, LastValueScan, PredicateRef, SpatialFilter, TimeRange, TimeRanges,
+};
+use crate::consistency_level::ReadConsistency;
 use crate::meta_data::{ReplicationSet, ReplicationSetId, VnodeInfo};
 use crate::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
 use crate::schema::{ColumnType, TskvTableSchemaRef};
-use crate::Result;
+use crate::{Result, Timestamp};
 
 pub mod domain;
 pub mod transformation;
@@ -20,6 +23,11 @@ pub struct Split {
     id: usize,
     predicate: ResolvedPredicateRef,
     limit: Option<usize>,
+    // also read the last point of each series before this timestamp
+    prior_point: Option<Timestamp>,
+    // read the latest points from the last value caches
+    last_value: Option<LastValueScan>,
+    read_consistency: ReadConsistency,
 }
 
 impl Split {
@@ -41,16 +49,18 @@ impl Split {
 
         let limit = predicate.limit();
 
-        let predicate = Arc::new(ResolvedPredicate::new(
-            Arc::new(TimeRanges::new(time_ranges)),
-            tags_filter,
-            filter,
-        )?);
+        let predicate = Arc::new(
+            ResolvedPredicate::new(Arc::new(TimeRanges::new(time_ranges)), tags_filter, filter)?
+                .with_spatial_filters(predicate.spatial_filters().to_vec()),
+        );
 
         Ok(Self {
             id,
             predicate,
             limit,
+            prior_point: None,
+            last_value: None,
+            read_consistency: ReadConsistency::default(),
         })
     }
 
@@ -66,6 +76,10 @@ impl Split {
         self.predicate.tags_filter()
     }
 
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        self.predicate.spatial_filters()
+    }
+
     pub fn filter(&self) -> &PhysicalExprNode {
         self.predicate.filter()
     }
@@ -73,6 +87,18 @@ impl Split {
     pub fn limit(&self) -> Option<usize> {
         self.limit
     }
+
+    pub fn prior_point(&self) -> Option<Timestamp> {
+        self.prior_point
+    }
+
+    pub fn last_value(&self) -> Option<LastValueScan> {
+        self.last_value
+    }
+
+    pub fn read_consistency(&self) -> ReadConsistency {
+        self.read_consistency
+    }
 }
 
 impl From<PlacedSplit> for Split {
@@ -100,6 +126,9 @@ impl PlacedSplit {
             id,
             predicate,
             limit,
+            prior_point: None,
+            last_value: None,
+            read_consistency: ReadConsistency::default(),
         };
 
         Self { split, repl_set }
@@ -121,6 +150,10 @@ impl PlacedSplit {
         self.split.tags_filter()
     }
 
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        self.split.spatial_filters()
+    }
+
     pub fn filter(&self) -> &PhysicalExprNode {
         self.split.filter()
     }
@@ -129,6 +162,42 @@ impl PlacedSplit {
         self.split.limit
     }
 
+    /// If set, the last point of each series before this timestamp should be read too,
+    /// it is used to fill the gaps at the beginning of the time ranges
+    pub fn prior_point(&self) -> Option<Timestamp> {
+        self.split.prior_point
+    }
+
+    pub fn with_prior_point(mut self, prior_point: Option<Timestamp>) -> Self {
+        self.split.prior_point = prior_point;
+        self
+    }
+
+    /// If set, the latest points of the series are read from the last value caches,
+    /// the series not cached are read from the data as usual
+    pub fn last_value(&self) -> Option<LastValueScan> {
+        self.split.last_value
+    }
+
+    pub fn with_last_value(mut self, last_value: Option<LastValueScan>) -> Self {
+        self.split.last_value = last_value;
+        self
+    }
+
+    pub fn read_consistency(&self) -> ReadConsistency {
+        self.split.read_consistency
+    }
+
+    pub fn with_read_consistency(mut self, read_consistency: ReadConsistency) -> Self {
+        self.split.read_consistency = read_consistency;
+        self
+    }
+
+    /// Replaces the vnodes to read, in the order they are tried
+    pub fn set_vnodes(&mut self, vnodes: Vec<VnodeInfo>) {
+        self.repl_set.vnodes = vnodes;
+    }
+
     pub fn pop_front(&mut self) -> Option<VnodeInfo> {
         if self.repl_set.vnodes.is_empty() {
             None
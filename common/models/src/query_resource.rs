@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::meta_data::VnodeId;
+
+/// Accounts the resources used by a running query, shared by the operators of the query.
+#[derive(Debug, Default)]
+pub struct QueryResourceTracker {
+    memory: AtomicUsize,
+    peak_memory: AtomicUsize,
+    vnode_scans: Mutex<BTreeMap<VnodeId, VnodeScan>>,
+}
+
+impl QueryResourceTracker {
+    pub fn grow_memory(&self, additional: usize) {
+        let used = self.memory.fetch_add(additional, Ordering::Relaxed) + additional;
+        self.peak_memory.fetch_max(used, Ordering::Relaxed);
+    }
+
+    pub fn shrink_memory(&self, shrink: usize) {
+        let _ = self
+            .memory
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                Some(used.saturating_sub(shrink))
+            });
+    }
+
+    pub fn record_scan(&self, vnode_id: VnodeId, rows: usize, bytes: usize) {
+        let mut vnode_scans = self.vnode_scans.lock();
+        let scan = vnode_scans.entry(vnode_id).or_insert(VnodeScan {
+            vnode_id,
+            ..Default::default()
+        });
+        scan.rows += rows as u64;
+        scan.bytes += bytes as u64;
+    }
+
+    pub fn usage(&self, cpu_time: Duration) -> QueryResourceUsage {
+        QueryResourceUsage {
+            cpu_time,
+            peak_memory: self.peak_memory.load(Ordering::Relaxed) as u64,
+            vnode_scans: self.vnode_scans.lock().values().cloned().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Rows and bytes read from a vnode
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VnodeScan {
+    pub vnode_id: VnodeId,
+    pub rows: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryResourceUsage {
+    pub cpu_time: Duration,
+    pub peak_memory: u64,
+    pub vnode_scans: Vec<VnodeScan>,
+    /// Times the operators of the query spilled to disk
+    #[serde(default)]
+    pub spill_count: u64,
+    #[serde(default)]
+    pub spilled_bytes: u64,
+}
+
+impl QueryResourceUsage {
+    pub fn rows_scanned(&self) -> u64 {
+        self.vnode_scans.iter().map(|s| s.rows).sum()
+    }
+
+    pub fn bytes_scanned(&self) -> u64 {
+        self.vnode_scans.iter().map(|s| s.bytes).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{QueryResourceTracker, VnodeScan};
+
+    #[test]
+    fn test_query_resource_tracker() {
+        let tracker = QueryResourceTracker::default();
+        tracker.grow_memory(100);
+        tracker.grow_memory(50);
+        tracker.shrink_memory(120);
+        tracker.grow_memory(10);
+        tracker.record_scan(2, 10, 1000);
+        tracker.record_scan(1, 5, 500);
+        tracker.record_scan(2, 1, 100);
+
+        let usage = tracker.usage(Duration::from_millis(3));
+        assert_eq!(usage.cpu_time, Duration::from_millis(3));
+        assert_eq!(usage.peak_memory, 150);
+        assert_eq!(
+            usage.vnode_scans,
+            vec![
+                VnodeScan {
+                    vnode_id: 1,
+                    rows: 5,
+                    bytes: 500
+                },
+                VnodeScan {
+                    vnode_id: 2,
+                    rows: 11,
+                    bytes: 1100
+                },
+            ]
+        );
+        assert_eq!(usage.rows_scanned(), 16);
+        assert_eq!(usage.bytes_scanned(), 1600);
+    }
+}
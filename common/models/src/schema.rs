@@ -16,7 +16,9 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration as StdDuration;
 
-use config::{RequestLimiterConfig, TenantLimiterConfig, TenantObjectLimiterConfig};
+use config::{
+    RequestLimiterConfig, TenantLimiterConfig, TenantObjectLimiterConfig, TenantQueryLimiterConfig,
+};
 use datafusion::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, Schema, SchemaRef, TimeUnit,
 };
@@ -32,11 +34,13 @@ use datafusion::error::{DataFusionError, Result as DataFusionResult};
 use datafusion::prelude::Column;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
+use utils::BkdrHasher;
 
 use crate::codec::Encoding;
 use crate::gis::data_type::Geometry;
 use crate::meta_data::{NodeId, ReplicationSet};
 use crate::oid::{Identifier, Oid};
+use crate::predicate::domain::{utf8_from, ColumnDomains};
 use crate::utils::{
     now_timestamp_nanos, DAY_MICROS, DAY_MILLS, DAY_NANOS, HOUR_MICROS, HOUR_MILLS, HOUR_NANOS,
     MINUTES_MICROS, MINUTES_MILLS, MINUTES_NANOS,
@@ -56,6 +60,8 @@ pub const DEFAULT_DATABASE: &str = "public";
 pub const USAGE_SCHEMA: &str = "usage_schema";
 pub const DEFAULT_CATALOG: &str = "cnosdb";
 pub const DEFAULT_PRECISION: &str = "NS";
+/// Do not prune the vnodes by the partition tags if too many partitions are matched
+const MAX_PRUNED_PARTITIONS: usize = 1024;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ResourceOperator {
@@ -85,6 +91,12 @@ pub enum ResourceOperator {
         Vec<Vec<u8>>,
         Vec<ReplicationSet>,
     ),
+
+    // node_id
+    DecommissionNode(NodeId),
+
+    // tenant_name, db_name
+    ChangeReplica(String, String),
 }
 
 impl fmt::Display for ResourceOperator {
@@ -97,6 +109,8 @@ impl fmt::Display for ResourceOperator {
             ResourceOperator::AddColumn(..) => write!(f, "AddColumn"),
             ResourceOperator::AlterColumn(..) => write!(f, "AlterColumn"),
             ResourceOperator::UpdateTagValue(..) => write!(f, "UpdateTagValue"),
+            ResourceOperator::DecommissionNode(..) => write!(f, "DecommissionNode"),
+            ResourceOperator::ChangeReplica(..) => write!(f, "ChangeReplica"),
         }
     }
 }
@@ -224,6 +238,16 @@ impl ResourceInfo {
     pub fn set_is_new_add(&mut self, is_new_add: bool) {
         self.is_new_add = is_new_add;
     }
+
+    /// Returns the node which is being or has been decommissioned by this task,
+    /// no more vnodes should be placed on it.
+    pub fn decommissioned_node(&self) -> Option<NodeId> {
+        match (&self.operator, &self.status) {
+            (_, ResourceStatus::Cancel) => None,
+            (ResourceOperator::DecommissionNode(node_id), _) => Some(*node_id),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -315,6 +339,65 @@ impl ExternalTableSchema {
     }
 }
 
+/// The options of a table, set by `CREATE TABLE ... WITH ...`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct TableOptions {
+    /// Keep the last value of each field of each series in memory, so that
+    /// the latest-point queries are answered without scanning the data.
+    #[serde(default)]
+    pub last_value_cache: bool,
+    /// The points older than `now - late_arrival_window` are late points,
+    /// they are handled by `late_arrival_policy`.
+    #[serde(default)]
+    pub late_arrival_window: Option<Duration>,
+    #[serde(default)]
+    pub late_arrival_policy: LateArrivalPolicy,
+}
+
+impl Display for TableOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LAST_VALUE_CACHE={}", self.last_value_cache)?;
+        if let Some(window) = &self.late_arrival_window {
+            write!(f, ", LATE_ARRIVAL_WINDOW={}", window)?;
+        }
+        write!(f, ", LATE_ARRIVAL_POLICY={}", self.late_arrival_policy)
+    }
+}
+
+/// How to handle the late points of a table.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LateArrivalPolicy {
+    /// Write the late points as the other points.
+    #[default]
+    Accept,
+    /// Reject the writes containing late points.
+    Reject,
+    /// Write the late points, and flush them to the late tier of delta files,
+    /// which is compacted on a slower schedule.
+    LateTier,
+}
+
+impl LateArrivalPolicy {
+    pub fn new(text: &str) -> Option<Self> {
+        match text.to_uppercase().as_str() {
+            "ACCEPT" => Some(LateArrivalPolicy::Accept),
+            "REJECT" => Some(LateArrivalPolicy::Reject),
+            "LATE_TIER" => Some(LateArrivalPolicy::LateTier),
+            _ => None,
+        }
+    }
+}
+
+impl Display for LateArrivalPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LateArrivalPolicy::Accept => f.write_str("ACCEPT"),
+            LateArrivalPolicy::Reject => f.write_str("REJECT"),
+            LateArrivalPolicy::LateTier => f.write_str("LATE_TIER"),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct TskvTableSchema {
     pub tenant: String,
@@ -326,6 +409,12 @@ pub struct TskvTableSchema {
     columns: Vec<TableColumn>,
     //ColumnName -> ColumnsIndex
     columns_index: HashMap<String, usize>,
+    /// The tags declared by `PARTITION BY`, the series are routed to the vnodes
+    /// by the hash of these tags instead of the whole series key.
+    #[serde(default = "Default::default")]
+    partition_tags: Vec<String>,
+    #[serde(default = "Default::default")]
+    options: TableOptions,
 }
 
 impl PartialOrd for TskvTableSchema {
@@ -350,6 +439,8 @@ impl Default for TskvTableSchema {
             next_column_id: 0,
             columns: Default::default(),
             columns_index: Default::default(),
+            partition_tags: Default::default(),
+            options: Default::default(),
         }
     }
 }
@@ -388,7 +479,105 @@ impl TskvTableSchema {
             next_column_id: columns.len() as ColumnId,
             columns,
             columns_index,
+            partition_tags: vec![],
+            options: Default::default(),
+        }
+    }
+
+    pub fn with_partition_tags(mut self, partition_tags: Vec<String>) -> Self {
+        self.partition_tags = partition_tags;
+        self
+    }
+
+    pub fn partition_tags(&self) -> &[String] {
+        &self.partition_tags
+    }
+
+    pub fn with_options(mut self, options: TableOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &TableOptions {
+        &self.options
+    }
+
+    /// The points written at `write_time` (in nanoseconds) with timestamp less than
+    /// the returned one are late points, returns None if the late arrival window of
+    /// the table is not set.
+    pub fn late_arrival_bound(&self, write_time: Timestamp) -> Option<Timestamp> {
+        let window = self.options.late_arrival_window.as_ref()?;
+        let precision = self.time_column_precision();
+        let now = timestamp_convert(Precision::NS, precision, write_time)?;
+        Some(now.saturating_sub(window.to_precision(precision)))
+    }
+
+    /// The hash id used to locate the vnode of a series, computed by the values of the
+    /// partition tags got by `tag_value`.
+    ///
+    /// Return None if the table is not partitioned by tags, the series are then
+    /// located by the hash of the whole series key.
+    pub fn partition_hash_id<'a>(
+        &self,
+        mut tag_value: impl FnMut(&str) -> Option<&'a str>,
+    ) -> Option<u64> {
+        if self.partition_tags.is_empty() {
+            return None;
+        }
+
+        let mut hasher = BkdrHasher::new();
+        hasher.hash_with(self.name.as_bytes());
+        for tag in &self.partition_tags {
+            if let Some(value) = tag_value(tag) {
+                hasher.hash_with(value.as_bytes());
+            }
+        }
+
+        Some(hasher.number())
+    }
+
+    /// The hash ids of the partitions matched by `tags_filter`, if the filter has
+    /// equality predicates on all of the partition tags, like `region = 'a'`
+    /// or `region IN ('a', 'b')`.
+    pub fn partition_hash_ids(&self, tags_filter: &ColumnDomains<String>) -> Option<Vec<u64>> {
+        if self.partition_tags.is_empty() {
+            return None;
+        }
+        let domains = tags_filter.domains()?;
+
+        let mut partitions: Vec<Vec<&str>> = vec![vec![]];
+        for tag in &self.partition_tags {
+            let values = domains
+                .get(tag)?
+                .equal_values()?
+                .into_iter()
+                .map(utf8_from)
+                .collect::<Option<Vec<_>>>()?;
+            if partitions.len() * values.len() > MAX_PRUNED_PARTITIONS {
+                return None;
+            }
+            partitions = partitions
+                .into_iter()
+                .flat_map(|partition| {
+                    values.iter().map(move |value| {
+                        let mut partition = partition.clone();
+                        partition.push(*value);
+                        partition
+                    })
+                })
+                .collect();
         }
+
+        let hash_ids = partitions
+            .into_iter()
+            .filter_map(|partition| {
+                self.partition_hash_id(|tag| {
+                    let idx = self.partition_tags.iter().position(|t| t == tag)?;
+                    Some(partition[idx])
+                })
+            })
+            .collect();
+        Some(hash_ids)
     }
 
     /// only for mock!!!
@@ -434,6 +623,11 @@ impl TskvTableSchema {
         };
         self.columns_index.remove(col_name);
         self.columns_index.insert(new_column.name.clone(), id);
+        for tag in self.partition_tags.iter_mut() {
+            if tag == col_name {
+                *tag = new_column.name.clone();
+            }
+        }
         self.columns[id] = new_column;
     }
 
@@ -564,6 +758,76 @@ impl TskvTableSchema {
     }
 }
 
+/// Serde of `TskvTableSchema` in the tsm files, used by `#[serde(with = "tsm_layout")]`.
+///
+/// The schema is encoded by bincode in the layout before the partition tags and the
+/// table options are added, so the existing tsm files are still readable. They are
+/// kept in meta only, the schemas read from the tsm files have the default ones.
+pub mod tsm_layout {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{TableColumn, TskvTableSchema};
+    use crate::{ColumnId, SchemaVersion};
+
+    #[derive(Serialize)]
+    struct StoredSchemaRef<'a> {
+        tenant: &'a str,
+        db: &'a str,
+        name: &'a str,
+        schema_version: SchemaVersion,
+        next_column_id: ColumnId,
+        columns: &'a [TableColumn],
+        columns_index: &'a HashMap<String, usize>,
+    }
+
+    #[derive(Deserialize)]
+    struct StoredSchema {
+        tenant: String,
+        db: String,
+        name: String,
+        schema_version: SchemaVersion,
+        next_column_id: ColumnId,
+        columns: Vec<TableColumn>,
+        columns_index: HashMap<String, usize>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        schema: &Arc<TskvTableSchema>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        StoredSchemaRef {
+            tenant: &schema.tenant,
+            db: &schema.db,
+            name: &schema.name,
+            schema_version: schema.schema_version,
+            next_column_id: schema.next_column_id,
+            columns: &schema.columns,
+            columns_index: &schema.columns_index,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<TskvTableSchema>, D::Error> {
+        let stored = StoredSchema::deserialize(deserializer)?;
+        Ok(Arc::new(TskvTableSchema {
+            tenant: stored.tenant,
+            db: stored.db,
+            name: stored.name,
+            schema_version: stored.schema_version,
+            next_column_id: stored.next_column_id,
+            columns: stored.columns,
+            columns_index: stored.columns_index,
+            partition_tags: vec![],
+            options: Default::default(),
+        }))
+    }
+}
+
 pub fn is_time_column(field: &ArrowField) -> bool {
     TIME_FIELD_NAME == field.name()
 }
@@ -1316,6 +1580,13 @@ impl TenantOptions {
         }
     }
 
+    pub fn query_config(&self) -> Option<&TenantQueryLimiterConfig> {
+        match self.limiter_config {
+            Some(ref limit_config) => limit_config.query_config.as_ref(),
+            None => None,
+        }
+    }
+
     pub fn get_tenant_is_hidden(&self) -> bool {
         self.tenant_is_hidden
     }
@@ -1442,3 +1713,144 @@ impl ColumnType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::datatypes::DataType;
+    use datafusion::scalar::ScalarValue;
+
+    use super::*;
+    use crate::predicate::domain::Domain;
+
+    fn partitioned_schema() -> TskvTableSchema {
+        TskvTableSchema::new(
+            "cnosdb".into(),
+            "public".into(),
+            "air".into(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(1, "region".into()),
+                TableColumn::new_tag_column(2, "host".into()),
+            ],
+        )
+        .with_partition_tags(vec!["region".into()])
+    }
+
+    fn utf8(value: &str) -> ScalarValue {
+        ScalarValue::Utf8(Some(value.to_string()))
+    }
+
+    #[test]
+    fn test_partition_hash_id() {
+        let schema = partitioned_schema();
+        let hash_a1 = schema.partition_hash_id(|tag| match tag {
+            "region" => Some("a"),
+            "host" => Some("1"),
+            _ => None,
+        });
+        let hash_a2 = schema.partition_hash_id(|tag| match tag {
+            "region" => Some("a"),
+            "host" => Some("2"),
+            _ => None,
+        });
+        assert!(hash_a1.is_some());
+        assert_eq!(hash_a1, hash_a2);
+
+        assert_eq!(
+            TskvTableSchema::new_test().partition_hash_id(|_| None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_partition_hash_ids() {
+        let schema = partitioned_schema();
+        let hash_a = schema.partition_hash_id(|_| Some("a")).unwrap();
+        let hash_b = schema.partition_hash_id(|_| Some("b")).unwrap();
+
+        let (a, b) = (utf8("a"), utf8("b"));
+        let filter = ColumnDomains::of(
+            "region".to_string(),
+            &Domain::of_values(&DataType::Utf8, true, &[&a]),
+        );
+        assert_eq!(schema.partition_hash_ids(&filter), Some(vec![hash_a]));
+
+        let filter = ColumnDomains::of(
+            "region".to_string(),
+            &Domain::of_values(&DataType::Utf8, true, &[&a, &b]),
+        );
+        let mut hash_ids = schema.partition_hash_ids(&filter).unwrap();
+        hash_ids.sort();
+        let mut expected = vec![hash_a, hash_b];
+        expected.sort();
+        assert_eq!(hash_ids, expected);
+
+        // Not an equality predicate on the partition tags
+        let filter = ColumnDomains::of(
+            "host".to_string(),
+            &Domain::of_values(&DataType::Utf8, true, &[&a]),
+        );
+        assert_eq!(schema.partition_hash_ids(&filter), None);
+        assert_eq!(schema.partition_hash_ids(&ColumnDomains::all()), None);
+    }
+
+    #[test]
+    fn test_tsm_layout() {
+        #[derive(Serialize, Deserialize)]
+        struct Stored(#[serde(with = "tsm_layout")] Arc<TskvTableSchema>);
+
+        // The layout of the schemas in the existing tsm files.
+        #[derive(Serialize)]
+        struct Baseline {
+            tenant: String,
+            db: String,
+            name: String,
+            schema_version: SchemaVersion,
+            next_column_id: ColumnId,
+            columns: Vec<TableColumn>,
+            columns_index: HashMap<String, usize>,
+        }
+
+        let schema = partitioned_schema().with_options(TableOptions {
+            last_value_cache: true,
+            ..Default::default()
+        });
+        let baseline = Baseline {
+            tenant: schema.tenant.clone(),
+            db: schema.db.clone(),
+            name: schema.name.clone(),
+            schema_version: schema.schema_version,
+            next_column_id: schema.next_column_id,
+            columns: schema.columns.clone(),
+            columns_index: schema.columns_index.clone(),
+        };
+        let buf = bincode::serialize(&Stored(Arc::new(schema.clone()))).unwrap();
+        assert_eq!(buf, bincode::serialize(&baseline).unwrap());
+
+        let Stored(decoded) = bincode::deserialize(&buf).unwrap();
+        assert_eq!(decoded.columns(), schema.columns());
+        assert!(decoded.partition_tags().is_empty());
+        assert_eq!(decoded.options(), &TableOptions::default());
+    }
+
+    #[test]
+    fn test_late_arrival_bound() {
+        let schema = TskvTableSchema::new(
+            "cnosdb".into(),
+            "public".into(),
+            "air".into(),
+            vec![TableColumn::new_time_column(0, TimeUnit::Millisecond)],
+        );
+        assert_eq!(schema.late_arrival_bound(now_timestamp_nanos()), None);
+
+        let schema = schema.with_options(TableOptions {
+            late_arrival_window: Duration::new("1h"),
+            late_arrival_policy: LateArrivalPolicy::Reject,
+            ..Default::default()
+        });
+        let bound = schema
+            .late_arrival_bound(10 * HOUR_MILLS * 1_000_000)
+            .unwrap();
+        assert_eq!(bound, 9 * HOUR_MILLS);
+    }
+}
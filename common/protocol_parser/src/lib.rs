@@ -17,6 +17,7 @@ type NextTagRes<'a> = Result<Option<(Vec<(Cow<'a, str>, Cow<'a, str>)>, usize)>>
 pub mod line_protocol;
 pub mod lines_convert;
 pub mod open_tsdb;
+pub mod wide_table;
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
@@ -0,0 +1,608 @@
+//! Converts the rows of NDJSON or CSV straight into record batches by a column-mapping spec.
+//!
+//! The spec is built from the options like `format=csv&table=air&time_column=ts&tags=station&fields=temperature:double`,
+//! which are the query parameters of `/api/v1/write`, or the options of an ingestion profile.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDateTime};
+use datafusion::arrow::array::{
+    Array, ArrayRef, BooleanBuilder, Float64Builder, Int64Builder, StringBuilder,
+    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+    UInt64Builder,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use models::codec::Encoding;
+use models::schema::{ColumnType, TableColumn, TskvTableSchema, TIME_FIELD};
+use models::ValueType;
+
+use crate::{Error, Result};
+
+pub const FORMAT_OPTION: &str = "format";
+pub const TABLE_OPTION: &str = "table";
+pub const TIME_COLUMN_OPTION: &str = "time_column";
+pub const TIME_FORMAT_OPTION: &str = "time_format";
+pub const TAGS_OPTION: &str = "tags";
+pub const FIELDS_OPTION: &str = "fields";
+pub const DELIMITER_OPTION: &str = "delimiter";
+
+/// The format of the rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowFormat {
+    /// One json object per line
+    Ndjson,
+    /// Comma separated values with a header line
+    Csv,
+}
+
+impl FromStr for RowFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ndjson" | "json" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            _ => Err(common_err(format!(
+                "unsupported format '{s}', expected ndjson or csv"
+            ))),
+        }
+    }
+}
+
+/// The format of the values of the time column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// Like `2024-01-01T00:00:00Z`
+    Rfc3339,
+    /// Integers since the unix epoch, the multiplier converts the values to nanoseconds
+    Unix(i64),
+    /// A strftime pattern, like `%Y-%m-%d %H:%M:%S`, the time is in UTC without the time zone
+    Pattern(String),
+}
+
+impl FromStr for TimeFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "rfc3339" => Ok(Self::Rfc3339),
+            "unix" | "unix_s" => Ok(Self::Unix(1_000_000_000)),
+            "unix_ms" => Ok(Self::Unix(1_000_000)),
+            "unix_us" => Ok(Self::Unix(1_000)),
+            "unix_ns" => Ok(Self::Unix(1)),
+            _ if s.contains('%') => Ok(Self::Pattern(s.to_string())),
+            _ => Err(common_err(format!(
+                "unsupported time format '{s}', expected rfc3339, unix_s, unix_ms, unix_us, unix_ns or a strftime pattern"
+            ))),
+        }
+    }
+}
+
+impl TimeFormat {
+    fn parse_nanos(&self, value: &str) -> Option<i64> {
+        match self {
+            Self::Rfc3339 => DateTime::parse_from_rfc3339(value)
+                .ok()?
+                .timestamp_nanos_opt(),
+            Self::Unix(multiplier) => match value.parse::<i64>() {
+                Ok(v) => v.checked_mul(*multiplier),
+                Err(_) => {
+                    let v = value.parse::<f64>().ok()? * *multiplier as f64;
+                    v.is_finite().then_some(v as i64)
+                }
+            },
+            Self::Pattern(pattern) => match DateTime::parse_from_str(value, pattern) {
+                Ok(t) => t.timestamp_nanos_opt(),
+                Err(_) => NaiveDateTime::parse_from_str(value, pattern)
+                    .ok()?
+                    .timestamp_nanos_opt(),
+            },
+        }
+    }
+}
+
+/// A field column of the spec, the type is only required to create the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldColumn {
+    pub name: String,
+    pub value_type: Option<ValueType>,
+}
+
+impl FromStr for FieldColumn {
+    type Err = Error;
+
+    /// Parses `name[:type]`
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, value_type) = match s.split_once(':') {
+            Some((name, value_type)) => (name, Some(parse_value_type(value_type.trim())?)),
+            None => (s, None),
+        };
+        Ok(Self {
+            name: name.trim().to_string(),
+            value_type,
+        })
+    }
+}
+
+fn parse_value_type(s: &str) -> Result<ValueType> {
+    match s.to_ascii_lowercase().as_str() {
+        "double" | "float" | "f64" => Ok(ValueType::Float),
+        "bigint" | "integer" | "int" | "i64" => Ok(ValueType::Integer),
+        "unsigned" | "bigint unsigned" | "u64" => Ok(ValueType::Unsigned),
+        "boolean" | "bool" => Ok(ValueType::Boolean),
+        "string" | "text" => Ok(ValueType::String),
+        _ => Err(common_err(format!(
+            "unsupported field type '{s}', expected double, bigint, unsigned, boolean or string"
+        ))),
+    }
+}
+
+/// The column-mapping spec of the rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WideTableSpec {
+    pub format: RowFormat,
+    pub table: Option<String>,
+    pub time_column: String,
+    pub time_format: TimeFormat,
+    pub tag_columns: Vec<String>,
+    pub field_columns: Vec<FieldColumn>,
+    pub delimiter: u8,
+}
+
+impl WideTableSpec {
+    /// Builds the spec from the options, the later options override the earlier ones.
+    pub fn try_from_options<'a>(
+        options: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self> {
+        let mut format = None;
+        let mut table = None;
+        let mut time_column = TIME_FIELD.to_string();
+        let mut time_format = TimeFormat::Rfc3339;
+        let mut tag_columns = vec![];
+        let mut field_columns = vec![];
+        let mut delimiter = b',';
+
+        for (key, value) in options {
+            match key {
+                FORMAT_OPTION => format = Some(value.parse()?),
+                TABLE_OPTION => table = Some(value.to_string()),
+                TIME_COLUMN_OPTION => time_column = value.to_string(),
+                TIME_FORMAT_OPTION => time_format = value.parse()?,
+                TAGS_OPTION => tag_columns = split_list(value).map(String::from).collect(),
+                FIELDS_OPTION => {
+                    field_columns = split_list(value)
+                        .map(FieldColumn::from_str)
+                        .collect::<Result<_>>()?
+                }
+                DELIMITER_OPTION => match value.as_bytes() {
+                    [delimiter_byte] => delimiter = *delimiter_byte,
+                    _ => {
+                        return Err(common_err(format!(
+                            "delimiter must be a single byte character, but got '{value}'"
+                        )))
+                    }
+                },
+                _ => return Err(common_err(format!("unknown option '{key}'"))),
+            }
+        }
+
+        let format = format.ok_or_else(|| common_err("missing option 'format'".to_string()))?;
+        if field_columns.is_empty() {
+            return Err(common_err("missing option 'fields'".to_string()));
+        }
+
+        Ok(Self {
+            format,
+            table,
+            time_column,
+            time_format,
+            tag_columns,
+            field_columns,
+            delimiter,
+        })
+    }
+
+    pub fn table(&self) -> Result<&str> {
+        self.table
+            .as_deref()
+            .ok_or_else(|| common_err("missing option 'table'".to_string()))
+    }
+
+    /// The columns of the table created for the rows, all the fields must have types.
+    pub fn table_columns(&self, time_unit: TimeUnit) -> Result<Vec<TableColumn>> {
+        let mut columns = vec![TableColumn::new_time_column(0, time_unit)];
+        for tag in &self.tag_columns {
+            columns.push(TableColumn::new_tag_column(
+                columns.len() as u32,
+                tag.clone(),
+            ));
+        }
+        for field in &self.field_columns {
+            let value_type = field.value_type.ok_or_else(|| {
+                common_err(format!(
+                    "the type of field '{}' is required to create the table",
+                    field.name
+                ))
+            })?;
+            columns.push(TableColumn::new(
+                columns.len() as u32,
+                field.name.clone(),
+                ColumnType::Field(value_type),
+                Encoding::Default,
+            ));
+        }
+
+        Ok(columns)
+    }
+
+    /// Converts the rows in `buf` into a record batch of the columns of `table`.
+    pub fn to_record_batch(&self, buf: &[u8], table: &TskvTableSchema) -> Result<RecordBatch> {
+        let mut builder = RowsBuilder::try_new(self, table)?;
+        match self.format {
+            RowFormat::Ndjson => {
+                let text = std::str::from_utf8(buf)
+                    .map_err(|e| common_err(format!("invalid utf-8 sequence: {e}")))?;
+                for (line_no, line) in text.lines().enumerate() {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    let row: serde_json::Map<String, serde_json::Value> =
+                        serde_json::from_str(line).map_err(|e| {
+                            common_err(format!("line {}: invalid json object: {e}", line_no + 1))
+                        })?;
+                    builder
+                        .append_row(|name| json_cell(row.get(name)))
+                        .map_err(|e| common_err(format!("line {}: {e}", line_no + 1)))?;
+                }
+            }
+            RowFormat::Csv => {
+                let mut reader = csv::ReaderBuilder::new()
+                    .delimiter(self.delimiter)
+                    .has_headers(true)
+                    .from_reader(buf);
+                let headers: HashMap<String, usize> = reader
+                    .headers()
+                    .map_err(|e| common_err(format!("invalid csv header: {e}")))?
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, name)| (name.trim().to_string(), idx))
+                    .collect();
+                for (row_no, record) in reader.records().enumerate() {
+                    let record = record
+                        .map_err(|e| common_err(format!("row {}: {e}", row_no + 1)))?;
+                    builder
+                        .append_row(|name| {
+                            let value = record.get(*headers.get(name)?)?;
+                            (!value.is_empty()).then_some(Cow::Borrowed(value))
+                        })
+                        .map_err(|e| common_err(format!("row {}: {e}", row_no + 1)))?;
+                }
+            }
+        }
+
+        builder.finish()
+    }
+}
+
+impl Display for RowFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ndjson => f.write_str("ndjson"),
+            Self::Csv => f.write_str("csv"),
+        }
+    }
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn common_err(content: String) -> Error {
+    Error::Common { content }
+}
+
+/// The text of a json value, objects and arrays are kept as json text.
+fn json_cell(value: Option<&serde_json::Value>) -> Option<Cow<'_, str>> {
+    match value? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(Cow::Borrowed(s.as_str())),
+        value => Some(Cow::Owned(value.to_string())),
+    }
+}
+
+enum ColumnBuilder {
+    Tag(StringBuilder),
+    Float(Float64Builder),
+    Integer(Int64Builder),
+    Unsigned(UInt64Builder),
+    Boolean(BooleanBuilder),
+    String(StringBuilder),
+}
+
+impl ColumnBuilder {
+    fn try_new(column: &TableColumn) -> Result<Self> {
+        let builder = match column.column_type {
+            ColumnType::Tag => Self::Tag(StringBuilder::new()),
+            ColumnType::Field(ValueType::Float) => Self::Float(Float64Builder::new()),
+            ColumnType::Field(ValueType::Integer) => Self::Integer(Int64Builder::new()),
+            ColumnType::Field(ValueType::Unsigned) => Self::Unsigned(UInt64Builder::new()),
+            ColumnType::Field(ValueType::Boolean) => Self::Boolean(BooleanBuilder::new()),
+            ColumnType::Field(ValueType::String) => Self::String(StringBuilder::new()),
+            _ => {
+                return Err(common_err(format!(
+                    "column {} of type {} is not supported",
+                    column.name, column.column_type
+                )))
+            }
+        };
+        Ok(builder)
+    }
+
+    fn append(&mut self, name: &str, value: Option<&str>) -> Result<()> {
+        let parse_err = |value: &str| common_err(format!("invalid value '{value}' of {name}"));
+        match self {
+            Self::Tag(builder) | Self::String(builder) => builder.append_option(value),
+            Self::Float(builder) => builder.append_option(
+                value
+                    .map(|v| v.parse::<f64>().map_err(|_| parse_err(v)))
+                    .transpose()?,
+            ),
+            Self::Integer(builder) => builder.append_option(
+                value
+                    .map(|v| v.parse::<i64>().map_err(|_| parse_err(v)))
+                    .transpose()?,
+            ),
+            Self::Unsigned(builder) => builder.append_option(
+                value
+                    .map(|v| v.parse::<u64>().map_err(|_| parse_err(v)))
+                    .transpose()?,
+            ),
+            Self::Boolean(builder) => builder.append_option(
+                value
+                    .map(|v| match v.to_ascii_lowercase().as_str() {
+                        "true" | "1" => Ok(true),
+                        "false" | "0" => Ok(false),
+                        _ => Err(parse_err(v)),
+                    })
+                    .transpose()?,
+            ),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::Tag(builder) | Self::String(builder) => Arc::new(builder.finish()),
+            Self::Float(builder) => Arc::new(builder.finish()),
+            Self::Integer(builder) => Arc::new(builder.finish()),
+            Self::Unsigned(builder) => Arc::new(builder.finish()),
+            Self::Boolean(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+struct RowsBuilder<'a> {
+    spec: &'a WideTableSpec,
+    time_unit: TimeUnit,
+    times: Vec<i64>,
+    // (column name, builder), the columns of the table except the time column
+    columns: Vec<(&'a str, ColumnBuilder)>,
+    fields: Vec<Field>,
+}
+
+impl<'a> RowsBuilder<'a> {
+    fn try_new(spec: &'a WideTableSpec, table: &'a TskvTableSchema) -> Result<Self> {
+        let time_unit = match table.time_column().column_type {
+            ColumnType::Time(unit) => unit,
+            _ => TimeUnit::Nanosecond,
+        };
+        let mut fields = vec![Field::new(
+            TIME_FIELD,
+            DataType::Timestamp(time_unit.clone(), None),
+            false,
+        )];
+
+        let mut columns = vec![];
+        let names = spec
+            .tag_columns
+            .iter()
+            .map(|tag| (tag.as_str(), true))
+            .chain(spec.field_columns.iter().map(|f| (f.name.as_str(), false)));
+        for (name, is_tag) in names {
+            let column = table.column(name).ok_or_else(|| {
+                common_err(format!("column {} not found in table {}", name, table.name))
+            })?;
+            if column.column_type.is_tag() != is_tag || column.column_type.is_time() {
+                return Err(common_err(format!(
+                    "column {} is a {} of table {}",
+                    name, column.column_type, table.name
+                )));
+            }
+            let builder = ColumnBuilder::try_new(column)?;
+            fields.push(Field::new(
+                name,
+                builder_data_type(&builder),
+                column.nullable(),
+            ));
+            columns.push((column.name.as_str(), builder));
+        }
+
+        Ok(Self {
+            spec,
+            time_unit,
+            times: vec![],
+            columns,
+            fields,
+        })
+    }
+
+    fn append_row<'b>(&mut self, cell: impl Fn(&str) -> Option<Cow<'b, str>>) -> Result<()> {
+        let time = cell(&self.spec.time_column).ok_or_else(|| {
+            common_err(format!("missing time column '{}'", self.spec.time_column))
+        })?;
+        let nanos = self.spec.time_format.parse_nanos(&time).ok_or_else(|| {
+            common_err(format!(
+                "invalid time '{time}' of time format {:?}",
+                self.spec.time_format
+            ))
+        })?;
+        let time = match self.time_unit {
+            TimeUnit::Millisecond => nanos / 1_000_000,
+            TimeUnit::Microsecond => nanos / 1_000,
+            _ => nanos,
+        };
+
+        // The request fails on any invalid value, so the builders needn't be rolled back
+        for (name, builder) in self.columns.iter_mut() {
+            builder.append(name, cell(name).as_deref())?;
+        }
+        self.times.push(time);
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<RecordBatch> {
+        let num_rows = self.times.len();
+        let times = std::mem::take(&mut self.times);
+        let time_array: ArrayRef = match self.time_unit {
+            TimeUnit::Millisecond => Arc::new(TimestampMillisecondArray::from(times)),
+            TimeUnit::Microsecond => Arc::new(TimestampMicrosecondArray::from(times)),
+            _ => Arc::new(TimestampNanosecondArray::from(times)),
+        };
+        let mut arrays = vec![time_array];
+        for (_, builder) in self.columns.iter_mut() {
+            let array = builder.finish();
+            debug_assert_eq!(array.len(), num_rows);
+            arrays.push(array);
+        }
+
+        RecordBatch::try_new(Arc::new(Schema::new(self.fields)), arrays)
+            .map_err(|e| common_err(format!("build record batch error: {e}")))
+    }
+}
+
+fn builder_data_type(builder: &ColumnBuilder) -> DataType {
+    match builder {
+        ColumnBuilder::Tag(_) | ColumnBuilder::String(_) => DataType::Utf8,
+        ColumnBuilder::Float(_) => DataType::Float64,
+        ColumnBuilder::Integer(_) => DataType::Int64,
+        ColumnBuilder::Unsigned(_) => DataType::UInt64,
+        ColumnBuilder::Boolean(_) => DataType::Boolean,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Float64Array, StringArray};
+
+    use super::*;
+
+    fn spec(format: &str) -> WideTableSpec {
+        WideTableSpec::try_from_options([
+            ("format", format),
+            ("table", "air"),
+            ("time_column", "ts"),
+            ("time_format", "unix_ms"),
+            ("tags", "station"),
+            ("fields", "temperature:double, visibility:bigint"),
+        ])
+        .unwrap()
+    }
+
+    fn table(spec: &WideTableSpec) -> TskvTableSchema {
+        TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            spec.table().unwrap().to_string(),
+            spec.table_columns(TimeUnit::Nanosecond).unwrap(),
+        )
+    }
+
+    fn check_batch(batch: &RecordBatch) {
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 4);
+        let times = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(times.value(0), 1_700_000_000_000_000_000);
+        let stations = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(stations.value(1), "b");
+        let temperatures = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(temperatures.value(0), 21.5);
+        assert!(temperatures.is_null(1));
+    }
+
+    #[test]
+    fn test_ndjson_to_record_batch() {
+        let spec = spec("ndjson");
+        let table = table(&spec);
+        let buf = br#"{"ts": 1700000000000, "station": "a", "temperature": 21.5, "visibility": 10}
+
+{"ts": "1700000001000", "station": "b", "temperature": null, "visibility": 9, "other": [1]}"#;
+
+        let batch = spec.to_record_batch(buf, &table).unwrap();
+        check_batch(&batch);
+    }
+
+    #[test]
+    fn test_csv_to_record_batch() {
+        let spec = spec("csv");
+        let table = table(&spec);
+        let buf = b"ts,station,temperature,visibility\n\
+            1700000000000,a,21.5,10\n\
+            1700000001000,b,,9\n";
+
+        let batch = spec.to_record_batch(buf, &table).unwrap();
+        check_batch(&batch);
+    }
+
+    #[test]
+    fn test_invalid_rows() {
+        let spec = spec("ndjson");
+        let table = table(&spec);
+
+        let buf = br#"{"station": "a", "temperature": 21.5}"#;
+        assert!(spec.to_record_batch(buf, &table).is_err());
+
+        let buf = br#"{"ts": 1700000000000, "station": "a", "temperature": "hot"}"#;
+        assert!(spec.to_record_batch(buf, &table).is_err());
+    }
+
+    #[test]
+    fn test_spec_options() {
+        assert!(WideTableSpec::try_from_options([("format", "xml"), ("fields", "a")]).is_err());
+        assert!(WideTableSpec::try_from_options([("format", "csv")]).is_err());
+        assert!(WideTableSpec::try_from_options([("format", "csv"), ("fields", "a:map")]).is_err());
+
+        let spec = WideTableSpec::try_from_options([
+            ("format", "csv"),
+            ("fields", "a"),
+            ("time_format", "%Y-%m-%d %H:%M:%S"),
+            ("delimiter", ";"),
+        ])
+        .unwrap();
+        assert_eq!(spec.delimiter, b';');
+        assert_eq!(
+            spec.time_format.parse_nanos("1970-01-01 00:00:01"),
+            Some(1_000_000_000)
+        );
+        assert!(spec.table().is_err());
+        assert!(spec.table_columns(TimeUnit::Nanosecond).is_err());
+    }
+}
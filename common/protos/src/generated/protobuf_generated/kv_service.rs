@@ -88,10 +88,111 @@ pub struct DestoryRaftGroupRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransferLeaderRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub replica_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub vnode_id: u32,
+}
+/// start an election on the vnode once its log reaches last_log_index
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ElectLeaderRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub replica_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub vnode_id: u32,
+    #[prost(uint64, tag = "4")]
+    pub last_log_index: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KillQueryRequest {
+    #[prost(uint64, tag = "1")]
+    pub query_id: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "3")]
+    pub path: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "3")]
+    pub path: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportFile {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub size: u64,
+    #[prost(string, tag = "3")]
+    pub md5: ::prost::alloc::string::String,
+    #[prost(int64, tag = "4")]
+    pub min_ts: i64,
+    #[prost(int64, tag = "5")]
+    pub max_ts: i64,
+}
+/// register the series of a bulk import, so that they have the same ids on all the replicas
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportSeriesRequest {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub series_keys: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// add the tsm files staged by a bulk import to the vnode
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportFilesRequest {
+    #[prost(string, tag = "1")]
+    pub import_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub files: ::prost::alloc::vec::Vec<ImportFile>,
+}
+/// remove the tsm files staged by a bulk import that is not added to the vnode
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AbortImportRequest {
+    #[prost(string, tag = "1")]
+    pub import_id: ::prost::alloc::string::String,
+}
+/// download the tsm files staged by a bulk import from the source vnode
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchImportFilesRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(uint64, tag = "3")]
+    pub source_node_id: u64,
+    #[prost(uint32, tag = "4")]
+    pub source_vnode_id: u32,
+    #[prost(message, optional, tag = "5")]
+    pub import: ::core::option::Option<ImportFilesRequest>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AdminCommandRequest {
     #[prost(string, tag = "1")]
     pub tenant: ::prost::alloc::string::String,
-    #[prost(oneof = "admin_command_request::Command", tags = "7, 13, 14, 15")]
+    #[prost(oneof = "admin_command_request::Command", tags = "7, 13, 14, 15, 16, 17, 18, 19, 20, 21")]
     pub command: ::core::option::Option<admin_command_request::Command>,
 }
 /// Nested message and enum types in `AdminCommandRequest`.
@@ -107,6 +208,18 @@ pub mod admin_command_request {
         RemoveRaftNode(super::RemoveRaftNodeRequest),
         #[prost(message, tag = "15")]
         DestoryRaftGroup(super::DestoryRaftGroupRequest),
+        #[prost(message, tag = "16")]
+        TransferLeader(super::TransferLeaderRequest),
+        #[prost(message, tag = "17")]
+        KillQuery(super::KillQueryRequest),
+        #[prost(message, tag = "18")]
+        ExportVnode(super::ExportVnodeRequest),
+        #[prost(message, tag = "19")]
+        ImportVnode(super::ImportVnodeRequest),
+        #[prost(message, tag = "20")]
+        FetchImportFiles(super::FetchImportFilesRequest),
+        #[prost(message, tag = "21")]
+        ElectLeader(super::ElectLeaderRequest),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -117,10 +230,25 @@ pub struct FetchVnodeChecksumRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchRaftAppliedIndexRequest {
+    #[prost(uint32, tag = "1")]
+    pub replica_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchQueriesRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchStorageStatsRequest {
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AdminFetchCommandRequest {
     #[prost(string, tag = "1")]
     pub tenant: ::prost::alloc::string::String,
-    #[prost(oneof = "admin_fetch_command_request::Command", tags = "8")]
+    #[prost(oneof = "admin_fetch_command_request::Command", tags = "8, 9, 10, 11")]
     pub command: ::core::option::Option<admin_fetch_command_request::Command>,
 }
 /// Nested message and enum types in `AdminFetchCommandRequest`.
@@ -130,6 +258,12 @@ pub mod admin_fetch_command_request {
     pub enum Command {
         #[prost(message, tag = "8")]
         FetchVnodeChecksum(super::FetchVnodeChecksumRequest),
+        #[prost(message, tag = "9")]
+        FetchRaftAppliedIndex(super::FetchRaftAppliedIndexRequest),
+        #[prost(message, tag = "10")]
+        FetchQueries(super::FetchQueriesRequest),
+        #[prost(message, tag = "11")]
+        FetchStorageStats(super::FetchStorageStatsRequest),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -201,6 +335,10 @@ pub struct WriteDataRequest {
     pub data: ::prost::alloc::vec::Vec<u8>,
     #[prost(uint32, tag = "2")]
     pub precision: u32,
+    /// the time in nanoseconds when the coordinator accepts the write, the late
+    /// points are classified by it, 0 if unknown
+    #[prost(int64, tag = "3")]
+    pub write_time: i64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -249,7 +387,10 @@ pub struct RaftWriteCommand {
     pub db_name: ::prost::alloc::string::String,
     #[prost(uint32, tag = "3")]
     pub replica_id: u32,
-    #[prost(oneof = "raft_write_command::Command", tags = "4, 5, 6, 7, 8")]
+    /// the command is applied only once for the same non-empty key
+    #[prost(string, tag = "9")]
+    pub idempotency_key: ::prost::alloc::string::String,
+    #[prost(oneof = "raft_write_command::Command", tags = "4, 5, 6, 7, 8, 10, 11, 12")]
     pub command: ::core::option::Option<raft_write_command::Command>,
 }
 /// Nested message and enum types in `RaftWriteCommand`.
@@ -267,6 +408,12 @@ pub mod raft_write_command {
         DeleteFromTable(super::DeleteFromTableRequest),
         #[prost(message, tag = "8")]
         UpdateTags(super::UpdateTagsRequest),
+        #[prost(message, tag = "10")]
+        ImportSeries(super::ImportSeriesRequest),
+        #[prost(message, tag = "11")]
+        ImportFiles(super::ImportFilesRequest),
+        #[prost(message, tag = "12")]
+        AbortImport(super::AbortImportRequest),
     }
 }
 /// Generated client implementations.
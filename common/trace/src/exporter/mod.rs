@@ -1,5 +1,6 @@
 pub mod jaeger;
 pub mod log;
+pub mod otlp;
 
 use std::any::Any;
 
@@ -0,0 +1,492 @@
+//! Exports the spans and the metrics to an OpenTelemetry collector by OTLP/gRPC or OTLP/HTTP.
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use config::{OtlpExporterConfig, OtlpProtocol};
+use opentelemetry::trace::TraceError;
+use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_client::MetricsServiceClient;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::trace_service_client::TraceServiceClient;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue};
+pub use opentelemetry_proto::tonic::resource::v1::Resource;
+use opentelemetry_proto::tonic::trace::v1::{span, status, ResourceSpans, ScopeSpans, Status};
+use prost::Message;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tonic::transport::{Channel, Endpoint};
+
+use crate::{warn, MetaValue, Span, SpanStatus, TraceExporter};
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Sends the OTLP requests to the collector
+#[derive(Debug, Clone)]
+pub struct OtlpClient {
+    transport: Transport,
+}
+
+#[derive(Debug, Clone)]
+enum Transport {
+    Grpc {
+        traces: TraceServiceClient<Channel>,
+        metrics: MetricsServiceClient<Channel>,
+    },
+    Http {
+        client: reqwest::Client,
+        endpoint: String,
+    },
+}
+
+impl OtlpClient {
+    pub fn try_new(config: &OtlpExporterConfig) -> Result<Self, TraceError> {
+        let transport = match config.protocol {
+            OtlpProtocol::Grpc => {
+                // Connects on the first export, the collector may start after the node
+                let channel = Endpoint::from_shared(config.endpoint.clone())
+                    .map_err(|e| TraceError::Other(Box::new(e)))?
+                    .timeout(config.timeout)
+                    .connect_lazy();
+                Transport::Grpc {
+                    traces: TraceServiceClient::new(channel.clone()),
+                    metrics: MetricsServiceClient::new(channel),
+                }
+            }
+            OtlpProtocol::Http => {
+                let client = reqwest::Client::builder()
+                    .timeout(config.timeout)
+                    .build()
+                    .map_err(|e| TraceError::Other(Box::new(e)))?;
+                Transport::Http {
+                    client,
+                    endpoint: config.endpoint.trim_end_matches('/').to_string(),
+                }
+            }
+        };
+
+        Ok(Self { transport })
+    }
+
+    pub async fn export_traces(
+        &self,
+        request: ExportTraceServiceRequest,
+    ) -> Result<(), TraceError> {
+        match &self.transport {
+            Transport::Grpc { traces, .. } => {
+                traces
+                    .clone()
+                    .export(request)
+                    .await
+                    .map_err(|e| TraceError::Other(Box::new(e)))?;
+                Ok(())
+            }
+            Transport::Http { client, endpoint } => {
+                post_protobuf(
+                    client,
+                    format!("{endpoint}/v1/traces"),
+                    request.encode_to_vec(),
+                )
+                .await
+            }
+        }
+    }
+
+    pub async fn export_metrics(
+        &self,
+        request: ExportMetricsServiceRequest,
+    ) -> Result<(), TraceError> {
+        match &self.transport {
+            Transport::Grpc { metrics, .. } => {
+                metrics
+                    .clone()
+                    .export(request)
+                    .await
+                    .map_err(|e| TraceError::Other(Box::new(e)))?;
+                Ok(())
+            }
+            Transport::Http { client, endpoint } => {
+                post_protobuf(
+                    client,
+                    format!("{endpoint}/v1/metrics"),
+                    request.encode_to_vec(),
+                )
+                .await
+            }
+        }
+    }
+}
+
+async fn post_protobuf(
+    client: &reqwest::Client,
+    url: String,
+    body: Vec<u8>,
+) -> Result<(), TraceError> {
+    let response = client
+        .post(&url)
+        .header(reqwest::header::CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| TraceError::Other(Box::new(e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(TraceError::from(format!(
+            "export to {url} failed with status {status}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// The resource of the exported spans and metrics, built from the attributes
+pub fn otlp_resource(attributes: impl IntoIterator<Item = (String, String)>) -> Resource {
+    Resource {
+        attributes: attributes
+            .into_iter()
+            .map(|(key, value)| string_attribute(key, value))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+pub fn string_attribute(key: impl Into<String>, value: impl Into<String>) -> KeyValue {
+    KeyValue {
+        key: key.into(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.into())),
+        }),
+    }
+}
+
+pub fn instrumentation_scope() -> InstrumentationScope {
+    InstrumentationScope {
+        name: "cnosdb".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        ..Default::default()
+    }
+}
+
+pub fn otlp_exporter(
+    config: &OtlpExporterConfig,
+    resource: Resource,
+) -> Result<Arc<dyn TraceExporter>, TraceError> {
+    let client = OtlpClient::try_new(config)?;
+    let (sender, receiver) = mpsc::channel(config.max_queue_size.max(1));
+    tokio::spawn(export_spans(
+        client,
+        resource,
+        receiver,
+        config.max_export_batch_size.max(1),
+        config.export_interval,
+    ));
+
+    Ok(Arc::new(OtlpTraceExporter { sender }))
+}
+
+/// Exports the spans in batches, a batch is exported when it's full or at the interval
+async fn export_spans(
+    client: OtlpClient,
+    resource: Resource,
+    mut receiver: Receiver<Span>,
+    max_export_batch_size: usize,
+    export_interval: Duration,
+) {
+    let mut batch = Vec::with_capacity(max_export_batch_size);
+    let mut ticker = tokio::time::interval(export_interval);
+    loop {
+        let closed = tokio::select! {
+            span = receiver.recv() => match span {
+                Some(span) => {
+                    batch.push(span);
+                    if batch.len() < max_export_batch_size {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = ticker.tick() => false,
+        };
+
+        if !batch.is_empty() {
+            let request = spans_request(resource.clone(), std::mem::take(&mut batch));
+            if let Err(e) = client.export_traces(request).await {
+                warn!("Failed to export spans by otlp: {e}");
+            }
+        }
+
+        if closed {
+            return;
+        }
+    }
+}
+
+pub fn spans_request(resource: Resource, spans: Vec<Span>) -> ExportTraceServiceRequest {
+    ExportTraceServiceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: Some(resource),
+            scope_spans: vec![ScopeSpans {
+                scope: Some(instrumentation_scope()),
+                spans: spans.into_iter().map(otlp_span).collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    }
+}
+
+fn otlp_span(span: Span) -> opentelemetry_proto::tonic::trace::v1::Span {
+    let Span {
+        name,
+        ctx,
+        start,
+        end,
+        status,
+        metadata,
+        events,
+    } = span;
+
+    // The root spans are the requests received by the node
+    let kind = match ctx.parent_span_id {
+        Some(_) => span::SpanKind::Internal,
+        None => span::SpanKind::Server,
+    };
+    let status = match status {
+        SpanStatus::Unknown => status::StatusCode::Unset,
+        SpanStatus::Ok => status::StatusCode::Ok,
+        SpanStatus::Err => status::StatusCode::Error,
+    };
+
+    opentelemetry_proto::tonic::trace::v1::Span {
+        trace_id: ctx.trace_id.get().to_be_bytes().to_vec(),
+        span_id: ctx.span_id.get().to_be_bytes().to_vec(),
+        parent_span_id: ctx
+            .parent_span_id
+            .map(|id| id.get().to_be_bytes().to_vec())
+            .unwrap_or_default(),
+        name: name.into_owned(),
+        kind: kind as i32,
+        start_time_unix_nano: unix_nanos(start),
+        end_time_unix_nano: unix_nanos(end),
+        attributes: metadata
+            .into_iter()
+            .map(|(key, value)| KeyValue {
+                key: key.into_owned(),
+                value: Some(any_value(value)),
+            })
+            .collect(),
+        events: events
+            .into_iter()
+            .map(|event| span::Event {
+                time_unix_nano: unix_nanos(Some(event.time)),
+                name: event.msg.into_owned(),
+                ..Default::default()
+            })
+            .collect(),
+        links: ctx
+            .links
+            .into_iter()
+            .map(|(trace_id, span_id)| span::Link {
+                trace_id: trace_id.get().to_be_bytes().to_vec(),
+                span_id: span_id.get().to_be_bytes().to_vec(),
+                ..Default::default()
+            })
+            .collect(),
+        status: Some(Status {
+            code: status as i32,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn any_value(value: MetaValue) -> AnyValue {
+    let value = match value {
+        MetaValue::String(e) => any_value::Value::StringValue(e),
+        MetaValue::Float(e) => any_value::Value::DoubleValue(e),
+        MetaValue::Int(e) => any_value::Value::IntValue(e),
+        MetaValue::Bool(e) => any_value::Value::BoolValue(e),
+        MetaValue::U64(e) => any_value::Value::IntValue(e as i64),
+    };
+    AnyValue { value: Some(value) }
+}
+
+fn unix_nanos(time: Option<DateTime<Utc>>) -> u64 {
+    time.and_then(|t| t.timestamp_nanos_opt())
+        .map(|nanos| nanos.max(0) as u64)
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+struct OtlpTraceExporter {
+    sender: Sender<Span>,
+}
+
+impl TraceExporter for OtlpTraceExporter {
+    fn export(&self, span: Span) {
+        // Drops the span if the exporting can't keep up
+        if let Err(e) = self.sender.try_send(span) {
+            warn!("Failed to queue span for otlp exporting: {e}");
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use config::{OtlpExporterConfig, OtlpProtocol};
+    use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::{
+        TraceService, TraceServiceServer,
+    };
+    use opentelemetry_proto::tonic::collector::trace::v1::{
+        ExportTraceServiceRequest, ExportTraceServiceResponse,
+    };
+    use opentelemetry_proto::tonic::common::v1::any_value;
+    use opentelemetry_proto::tonic::trace::v1::{span, status, Span as OtlpSpan};
+    use prost::Message;
+    use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+    use tokio_stream::wrappers::TcpListenerStream;
+    use warp::Filter;
+
+    use super::{otlp_exporter, otlp_resource, string_attribute};
+    use crate::{Span, SpanRecorder};
+
+    /// A collector stand-in receiving the spans by OTLP/gRPC
+    struct TraceCollector {
+        requests: UnboundedSender<ExportTraceServiceRequest>,
+    }
+
+    #[tonic::async_trait]
+    impl TraceService for TraceCollector {
+        async fn export(
+            &self,
+            request: tonic::Request<ExportTraceServiceRequest>,
+        ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+            let _ = self.requests.send(request.into_inner());
+            Ok(tonic::Response::new(ExportTraceServiceResponse::default()))
+        }
+    }
+
+    fn config(protocol: OtlpProtocol, addr: SocketAddr) -> OtlpExporterConfig {
+        OtlpExporterConfig {
+            endpoint: format!("http://{addr}"),
+            protocol,
+            export_interval: Duration::from_millis(50),
+            ..Default::default()
+        }
+    }
+
+    fn record_spans(config: &OtlpExporterConfig) {
+        let resource = otlp_resource([("service.name".to_string(), "cnosdb".to_string())]);
+        let exporter = otlp_exporter(config, resource).unwrap();
+
+        let mut root = SpanRecorder::new(Some(Span::root("http request", exporter)));
+        root.set_metadata("user", "root");
+        {
+            let mut child = root.child("execute query");
+            child.error("failed");
+        }
+        root.ok("done");
+    }
+
+    /// The spans may be exported in several batches
+    async fn receive_spans(
+        receiver: &mut UnboundedReceiver<ExportTraceServiceRequest>,
+    ) -> Vec<OtlpSpan> {
+        let mut spans = vec![];
+        while spans.len() < 2 {
+            let request = tokio::time::timeout(Duration::from_secs(10), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            let resource_spans = &request.resource_spans[0];
+            assert_eq!(
+                resource_spans.resource.as_ref().unwrap().attributes,
+                vec![string_attribute("service.name", "cnosdb")]
+            );
+            spans.extend(resource_spans.scope_spans[0].spans.iter().cloned());
+        }
+        spans
+    }
+
+    fn check_spans(spans: &[OtlpSpan]) {
+        assert_eq!(spans.len(), 2);
+        let child = spans.iter().find(|s| s.name == "execute query").unwrap();
+        let root = spans.iter().find(|s| s.name == "http request").unwrap();
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_eq!(child.parent_span_id, root.span_id);
+        assert!(root.parent_span_id.is_empty());
+        assert_eq!(root.trace_id.len(), 16);
+        assert_eq!(root.span_id.len(), 8);
+
+        assert_eq!(root.kind, span::SpanKind::Server as i32);
+        assert_eq!(child.kind, span::SpanKind::Internal as i32);
+        assert_eq!(
+            root.status.as_ref().unwrap().code,
+            status::StatusCode::Ok as i32
+        );
+        assert_eq!(
+            child.status.as_ref().unwrap().code,
+            status::StatusCode::Error as i32
+        );
+        assert_eq!(root.events[0].name, "done");
+        assert_eq!(child.events[0].name, "failed");
+        assert!(root.start_time_unix_nano > 0);
+        assert!(root.end_time_unix_nano >= child.end_time_unix_nano);
+
+        assert_eq!(root.attributes[0].key, "user");
+        assert_eq!(
+            root.attributes[0].value.as_ref().unwrap().value,
+            Some(any_value::Value::StringValue("root".to_string()))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_export_by_grpc() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(TraceServiceServer::new(TraceCollector { requests: sender }))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        record_spans(&config(OtlpProtocol::Grpc, addr));
+
+        check_spans(&receive_spans(&mut receiver).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_export_by_http() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let sender = Arc::new(sender);
+        let route = warp::post()
+            .and(warp::path!("v1" / "traces"))
+            .and(warp::header::exact(
+                "content-type",
+                "application/x-protobuf",
+            ))
+            .and(warp::body::bytes())
+            .map(move |body: warp::hyper::body::Bytes| {
+                let request = ExportTraceServiceRequest::decode(body).unwrap();
+                let _ = sender.send(request);
+                warp::reply()
+            });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        record_spans(&config(OtlpProtocol::Http, addr));
+
+        check_spans(&receive_spans(&mut receiver).await);
+    }
+}
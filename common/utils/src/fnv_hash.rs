@@ -0,0 +1,70 @@
+use std::hash::Hasher;
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0100_0000_01b3;
+
+/// 64-bit FNV-1a, the hash is stable across the processes, the nodes and the versions.
+pub struct FnvHasher {
+    number: u64,
+}
+
+impl FnvHasher {
+    pub fn new() -> Self {
+        Self {
+            number: OFFSET_BASIS,
+        }
+    }
+
+    pub fn hash_with(&mut self, bytes: &[u8]) -> &mut Self {
+        self.write(bytes);
+        self
+    }
+
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.number
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for c in bytes {
+            self.number = (self.number ^ *c as u64).wrapping_mul(PRIME);
+        }
+    }
+}
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fnv_hash::FnvHasher;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(FnvHasher::new().number(), 0xcbf2_9ce4_8422_2325);
+        // test vectors of FNV-1a 64
+        assert_eq!(
+            FnvHasher::new().hash_with(b"a").number(),
+            0xaf63_dc4c_8601_ec8c
+        );
+        assert_eq!(
+            FnvHasher::new()
+                .hash_with(b"foo")
+                .hash_with(b"bar")
+                .number(),
+            FnvHasher::new().hash_with(b"foobar").number()
+        );
+        assert_eq!(
+            FnvHasher::new().hash_with(b"foobar").number(),
+            0x8594_4171_f739_67e8
+        );
+    }
+}
@@ -35,6 +35,39 @@ pub struct ClusterConfig {
         default = "ClusterConfig::default_install_snapshot_timeout"
     )]
     pub install_snapshot_timeout: Duration, //ms
+
+    #[serde(default = "ClusterConfig::default_auto_rebalance")]
+    pub auto_rebalance: bool,
+
+    #[serde(
+        with = "duration",
+        default = "ClusterConfig::default_rebalance_interval"
+    )]
+    pub rebalance_interval: Duration,
+
+    /// The maximum number of vnodes moved in a round of rebalancing
+    #[serde(default = "ClusterConfig::default_rebalance_max_moves")]
+    pub rebalance_max_moves: usize,
+
+    /// Vnodes are moved only if the difference of the free disk space between nodes exceeds it
+    #[serde(
+        with = "bytes_num",
+        default = "ClusterConfig::default_rebalance_disk_free_gap"
+    )]
+    pub rebalance_disk_free_gap: u64,
+
+    #[serde(default = "ClusterConfig::default_auto_leader_balance")]
+    pub auto_leader_balance: bool,
+
+    #[serde(
+        with = "duration",
+        default = "ClusterConfig::default_leader_balance_interval"
+    )]
+    pub leader_balance_interval: Duration,
+
+    /// The maximum number of leaders transferred in a round of balancing
+    #[serde(default = "ClusterConfig::default_leader_balance_max_transfers")]
+    pub leader_balance_max_transfers: usize,
 }
 
 impl ClusterConfig {
@@ -57,6 +90,34 @@ impl ClusterConfig {
     fn default_install_snapshot_timeout() -> Duration {
         Duration::from_millis(3_600_000)
     }
+
+    fn default_auto_rebalance() -> bool {
+        false
+    }
+
+    fn default_rebalance_interval() -> Duration {
+        Duration::from_secs(600)
+    }
+
+    fn default_rebalance_max_moves() -> usize {
+        1
+    }
+
+    fn default_rebalance_disk_free_gap() -> u64 {
+        1024 * 1024 * 1024
+    }
+
+    fn default_auto_leader_balance() -> bool {
+        false
+    }
+
+    fn default_leader_balance_interval() -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn default_leader_balance_max_transfers() -> usize {
+        8
+    }
 }
 
 impl OverrideByEnv for ClusterConfig {
@@ -85,6 +146,38 @@ impl OverrideByEnv for ClusterConfig {
             &mut self.install_snapshot_timeout,
             "CNOSDB_CLUSTER_INSTALL_SNAPSHOT_TIMEOUT",
         );
+
+        entry_override(&mut self.auto_rebalance, "CNOSDB_CLUSTER_AUTO_REBALANCE");
+
+        entry_override_to_duration(
+            &mut self.rebalance_interval,
+            "CNOSDB_CLUSTER_REBALANCE_INTERVAL",
+        );
+
+        entry_override(
+            &mut self.rebalance_max_moves,
+            "CNOSDB_CLUSTER_REBALANCE_MAX_MOVES",
+        );
+
+        entry_override(
+            &mut self.rebalance_disk_free_gap,
+            "CNOSDB_CLUSTER_REBALANCE_DISK_FREE_GAP",
+        );
+
+        entry_override(
+            &mut self.auto_leader_balance,
+            "CNOSDB_CLUSTER_AUTO_LEADER_BALANCE",
+        );
+
+        entry_override_to_duration(
+            &mut self.leader_balance_interval,
+            "CNOSDB_CLUSTER_LEADER_BALANCE_INTERVAL",
+        );
+
+        entry_override(
+            &mut self.leader_balance_max_transfers,
+            "CNOSDB_CLUSTER_LEADER_BALANCE_MAX_TRANSFERS",
+        );
     }
 }
 
@@ -96,6 +189,13 @@ impl Default for ClusterConfig {
             heartbeat_interval: ClusterConfig::default_heartbeat_interval(),
             send_append_entries_timeout: ClusterConfig::default_send_append_entries_timeout(),
             install_snapshot_timeout: ClusterConfig::default_install_snapshot_timeout(),
+            auto_rebalance: ClusterConfig::default_auto_rebalance(),
+            rebalance_interval: ClusterConfig::default_rebalance_interval(),
+            rebalance_max_moves: ClusterConfig::default_rebalance_max_moves(),
+            rebalance_disk_free_gap: ClusterConfig::default_rebalance_disk_free_gap(),
+            auto_leader_balance: ClusterConfig::default_auto_leader_balance(),
+            leader_balance_interval: ClusterConfig::default_leader_balance_interval(),
+            leader_balance_max_transfers: ClusterConfig::default_leader_balance_max_transfers(),
         }
     }
 }
@@ -14,6 +14,7 @@ pub use crate::global_config::*;
 pub use crate::limiter_config::*;
 pub use crate::log_config::*;
 pub use crate::meta_config::*;
+pub use crate::metrics_config::*;
 pub use crate::override_by_env::OverrideByEnv;
 pub use crate::query_config::*;
 pub use crate::security_config::*;
@@ -31,6 +32,7 @@ mod global_config;
 mod limiter_config;
 mod log_config;
 mod meta_config;
+mod metrics_config;
 mod override_by_env;
 mod query_config;
 mod security_config;
@@ -95,6 +97,9 @@ pub struct Config {
 
     #[serde(default = "Default::default")]
     pub trace: TraceConfig,
+
+    #[serde(default = "Default::default")]
+    pub metrics: MetricsConfig,
 }
 
 impl Config {
@@ -117,6 +122,7 @@ impl OverrideByEnv for Config {
         self.service.override_by_env();
         self.cluster.override_by_env();
         self.trace.override_by_env();
+        self.metrics.override_by_env();
     }
 }
 
@@ -317,6 +323,10 @@ path = '/var/lib/cnosdb/data'
 ## The maximum size of all files in a compaction.
 # max_compact_size = "2G" # 2,147,483,648 bytes
 
+## The minimum duration that a file of the late tier stays in level 0 before it's compacted,
+## late points are routed to the late tier by the table option LATE_ARRIVAL_POLICY.
+# late_compact_interval = "6h"
+
 ## The maximum concurrent compactions.
 # max_concurrent_compaction = 4
 
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 pub struct TenantLimiterConfig {
     pub object_config: Option<TenantObjectLimiterConfig>,
     pub request_config: Option<RequestLimiterConfig>,
+    pub query_config: Option<TenantQueryLimiterConfig>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -17,6 +18,16 @@ pub struct TenantObjectLimiterConfig {
     pub max_retention_time: Option<usize>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct TenantQueryLimiterConfig {
+    /// memory in bytes of all the queries of the tenant running on a node
+    pub max_memory: Option<u64>,
+    /// memory in bytes of a single query
+    pub max_query_memory: Option<u64>,
+    /// relative share of the cpu used by the queries, 1 if not set
+    pub cpu_shares: Option<u32>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct RateBucketConfig {
     pub max: Option<usize>,
@@ -95,6 +106,11 @@ remote_bucket = {max = 100, initial = 0, refill = 100, interval = 100}
 [request_config.http_writes]
 local_bucket = {max = 100, initial = 0}
 remote_bucket = {max = 100, initial = 0, refill = 100, interval = 100}
+
+[query_config]
+max_memory = 1073741824
+max_query_memory = 268435456
+cpu_shares = 2
 "#;
 
     let config: TenantLimiterConfig = toml::from_str(config_str).unwrap();
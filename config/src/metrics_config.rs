@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::override_by_env::OverrideByEnv;
+use crate::trace::{override_otlp_by_env, OtlpExporterConfig};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Pushes the metrics of the node to an OpenTelemetry collector,
+    /// besides the Prometheus text rendered by `/metrics`
+    pub otlp: Option<OtlpExporterConfig>,
+}
+
+impl OverrideByEnv for MetricsConfig {
+    fn override_by_env(&mut self) {
+        override_otlp_by_env(&mut self.otlp, "CNOSDB_METRICS_OTLP");
+    }
+}
+
+#[test]
+fn test_metrics_config() {
+    let config_str = r#"
+[otlp]
+endpoint = "http://127.0.0.1:4317"
+export_interval = "15s"
+"#;
+    let config: MetricsConfig = toml::from_str(config_str).unwrap();
+    let otlp = config.otlp.unwrap();
+    assert_eq!(otlp.endpoint, "http://127.0.0.1:4317");
+    assert_eq!(otlp.protocol, crate::OtlpProtocol::Grpc);
+    assert_eq!(otlp.export_interval, std::time::Duration::from_secs(15));
+}
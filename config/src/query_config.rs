@@ -25,6 +25,37 @@ pub struct QueryConfig {
     pub stream_trigger_cpu: usize,
     #[serde(default = "QueryConfig::default_stream_executor_cpu")]
     pub stream_executor_cpu: usize,
+    /// Threads of the dedicated executor running the queries, 0 runs the queries on the
+    /// shared runtime. The tenants share the cpu used by the queries by their `cpu_shares`
+    /// in both cases.
+    #[serde(default = "QueryConfig::default_query_executor_cpu")]
+    pub query_executor_cpu: usize,
+    /// Whether the sorts spill to local disk when the memory of the query is exhausted.
+    #[serde(default = "QueryConfig::default_enable_spill")]
+    pub enable_spill: bool,
+    /// Whether the hash joins and the grouped hash aggregates are planned as sort merge joins
+    /// and sorted aggregates, so that they spill through their sorts. They are slower than
+    /// the hash operators even without memory pressure. Only takes effect with `enable_spill`.
+    #[serde(default = "QueryConfig::default_spill_hash_operators")]
+    pub spill_hash_operators: bool,
+    /// The directory of the spill files, empty for the temporary directory of the system.
+    #[serde(default = "QueryConfig::default_spill_dir")]
+    pub spill_dir: String,
+    /// The disk space of the spill files of all the running queries, 0 for no limit.
+    /// The bytes spilled by a query are accounted when its result is polled, the query over the
+    /// quota fails, so each running query may exceed it by the files spilled since its last poll.
+    #[serde(with = "bytes_num", default = "QueryConfig::default_max_spill_size")]
+    pub max_spill_size: u64,
+    /// The webhook receiving the notifications of the alert rules without WEBHOOK option,
+    /// empty for no notification.
+    #[serde(default = "QueryConfig::default_alert_webhook")]
+    pub alert_webhook: String,
+    /// How often the alert rules of this node are reloaded from meta.
+    #[serde(
+        with = "duration",
+        default = "QueryConfig::default_alert_sync_interval"
+    )]
+    pub alert_sync_interval: Duration,
 }
 
 impl QueryConfig {
@@ -59,6 +90,34 @@ impl QueryConfig {
     fn default_stream_executor_cpu() -> usize {
         2
     }
+
+    fn default_query_executor_cpu() -> usize {
+        0
+    }
+
+    fn default_enable_spill() -> bool {
+        true
+    }
+
+    fn default_spill_hash_operators() -> bool {
+        false
+    }
+
+    fn default_spill_dir() -> String {
+        String::new()
+    }
+
+    fn default_max_spill_size() -> u64 {
+        0
+    }
+
+    fn default_alert_webhook() -> String {
+        String::new()
+    }
+
+    fn default_alert_sync_interval() -> Duration {
+        Duration::from_secs(10)
+    }
 }
 
 impl OverrideByEnv for QueryConfig {
@@ -80,6 +139,22 @@ impl OverrideByEnv for QueryConfig {
             &mut self.stream_executor_cpu,
             "CNOSDB_QUERY_STREAM_EXECUTOR_CPU",
         );
+        entry_override(
+            &mut self.query_executor_cpu,
+            "CNOSDB_QUERY_QUERY_EXECUTOR_CPU",
+        );
+        entry_override(&mut self.enable_spill, "CNOSDB_QUERY_ENABLE_SPILL");
+        entry_override(
+            &mut self.spill_hash_operators,
+            "CNOSDB_QUERY_SPILL_HASH_OPERATORS",
+        );
+        entry_override(&mut self.spill_dir, "CNOSDB_QUERY_SPILL_DIR");
+        entry_override(&mut self.max_spill_size, "CNOSDB_QUERY_MAX_SPILL_SIZE");
+        entry_override(&mut self.alert_webhook, "CNOSDB_QUERY_ALERT_WEBHOOK");
+        entry_override_to_duration(
+            &mut self.alert_sync_interval,
+            "CNOSDB_QUERY_ALERT_SYNC_INTERVAL",
+        );
     }
 }
 
@@ -94,6 +169,13 @@ impl Default for QueryConfig {
             write_timeout: Self::default_write_timeout(),
             stream_trigger_cpu: Self::default_stream_trigger_cpu(),
             stream_executor_cpu: Self::default_stream_executor_cpu(),
+            query_executor_cpu: Self::default_query_executor_cpu(),
+            enable_spill: Self::default_enable_spill(),
+            spill_hash_operators: Self::default_spill_hash_operators(),
+            spill_dir: Self::default_spill_dir(),
+            max_spill_size: Self::default_max_spill_size(),
+            alert_webhook: Self::default_alert_webhook(),
+            alert_sync_interval: Self::default_alert_sync_interval(),
         }
     }
 }
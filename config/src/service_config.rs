@@ -20,6 +20,8 @@ pub struct ServiceConfig {
     pub tcp_listen_port: Option<u16>,
     #[serde(default = "ServiceConfig::default_vector_listen_port")]
     pub vector_listen_port: Option<u16>,
+    #[serde(default = "ServiceConfig::default_otlp_listen_port")]
+    pub otlp_listen_port: Option<u16>,
     #[serde(default = "ServiceConfig::default_enable_report")]
     pub enable_report: bool,
 }
@@ -49,6 +51,10 @@ impl ServiceConfig {
         None
     }
 
+    fn default_otlp_listen_port() -> Option<u16> {
+        None
+    }
+
     fn default_enable_report() -> bool {
         true
     }
@@ -63,6 +69,7 @@ impl Default for ServiceConfig {
             flight_rpc_listen_port: ServiceConfig::default_flight_rpc_listen_port(),
             tcp_listen_port: ServiceConfig::default_tcp_listen_port(),
             vector_listen_port: ServiceConfig::default_vector_listen_port(),
+            otlp_listen_port: ServiceConfig::default_otlp_listen_port(),
             enable_report: ServiceConfig::default_enable_report(),
         }
     }
@@ -91,6 +98,10 @@ impl OverrideByEnv for ServiceConfig {
             &mut self.vector_listen_port,
             "CNOSDB_SERVICE_VECTOR_LISTEN_PORT",
         );
+        entry_override_option(
+            &mut self.otlp_listen_port,
+            "CNOSDB_SERVICE_OTLP_LISTEN_PORT",
+        );
         entry_override(&mut self.enable_report, "CNOSDB_SERVICE_ENABLE_REPORT");
     }
 }
@@ -178,13 +189,24 @@ impl CheckConfig for ServiceConfig {
             let default_vector_addr = format!("{}:{}", &config.global.host, port);
             if let Err(e) = default_vector_addr.to_socket_addrs() {
                 ret.add_error(CheckConfigItemResult {
-                    config: config_name,
+                    config: config_name.clone(),
                     item: default_vector_addr,
                     message: format!("Cannot resolve 'vector_listen_addr': {}", e),
                 });
             }
         }
 
+        if let Some(port) = self.otlp_listen_port {
+            let default_otlp_addr = format!("{}:{}", &config.global.host, port);
+            if let Err(e) = default_otlp_addr.to_socket_addrs() {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name,
+                    item: default_otlp_addr,
+                    message: format!("Cannot resolve 'otlp_listen_addr': {}", e),
+                });
+            }
+        }
+
         if ret.is_empty() {
             None
         } else {
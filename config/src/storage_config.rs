@@ -45,6 +45,12 @@ pub struct StorageConfig {
     )]
     pub max_compact_size: u64,
 
+    #[serde(
+        with = "duration",
+        default = "StorageConfig::default_late_compact_interval"
+    )]
+    pub late_compact_interval: Duration,
+
     #[serde(default = "StorageConfig::default_max_concurrent_compaction")]
     pub max_concurrent_compaction: u16,
 
@@ -99,6 +105,10 @@ impl StorageConfig {
         2 * 1024 * 1024 * 1024
     }
 
+    fn default_late_compact_interval() -> Duration {
+        Duration::from_secs(6 * 60 * 60)
+    }
+
     fn default_reserve_space() -> u64 {
         0
     }
@@ -119,6 +129,8 @@ impl StorageConfig {
         // Unit of storage.compact_trigger_cold_duration is seconds
         self.compact_trigger_cold_duration =
             Duration::from_secs(self.compact_trigger_cold_duration.as_secs());
+        // Unit of storage.late_compact_interval is seconds
+        self.late_compact_interval = Duration::from_secs(self.late_compact_interval.as_secs());
     }
 }
 
@@ -151,6 +163,10 @@ impl OverrideByEnv for StorageConfig {
             &mut self.max_compact_size,
             "CNOSDB_STORAGE_MAX_COMPACT_SIZE",
         );
+        entry_override_to_duration(
+            &mut self.late_compact_interval,
+            "CNOSDB_STORAGE_LATE_COMPACT_INTERVAL",
+        );
         entry_override(
             &mut self.max_concurrent_compaction,
             "CNOSDB_STORAGE_MAX_CONCURRENT_COMPACTION",
@@ -175,6 +191,7 @@ impl Default for StorageConfig {
             compact_trigger_file_num: Self::default_compact_trigger_file_num(),
             compact_trigger_cold_duration: Self::default_compact_trigger_cold_duration(),
             max_compact_size: Self::default_max_compact_size(),
+            late_compact_interval: Self::default_late_compact_interval(),
             max_concurrent_compaction: Self::default_max_concurrent_compaction(),
             strict_write: Self::default_strict_write(),
             reserve_space: Self::default_reserve_space(),
@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
-use crate::override_by_env::{entry_override, OverrideByEnv};
+use crate::codec::duration;
+use crate::override_by_env::{entry_override, entry_override_to_duration, OverrideByEnv};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -11,6 +16,7 @@ pub struct TraceConfig {
     pub http: Option<HttpCollectorConfig>,
     pub log: Option<LogCollectorConfig>,
     pub jaeger: Option<JaegerCollectorConfig>,
+    pub otlp: Option<OtlpExporterConfig>,
 }
 
 impl OverrideByEnv for TraceConfig {
@@ -22,6 +28,7 @@ impl OverrideByEnv for TraceConfig {
         // self.http.override_by_env();
         self.log.override_by_env();
         self.jaeger.override_by_env();
+        override_otlp_by_env(&mut self.otlp, "CNOSDB_TRACE_OTLP");
     }
 }
 
@@ -95,9 +102,106 @@ impl Default for JaegerCollectorConfig {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OtlpProtocol {
+    /// OTLP/gRPC, the endpoint is like `http://localhost:4317`
+    #[default]
+    Grpc,
+    /// OTLP/HTTP with protobuf payload, the endpoint is like `http://localhost:4318`
+    Http,
+}
+
+impl FromStr for OtlpProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "grpc" => Ok(Self::Grpc),
+            "http" => Ok(Self::Http),
+            _ => Err(format!("unknown otlp protocol {s}, expected grpc or http")),
+        }
+    }
+}
+
+impl Display for OtlpProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Grpc => write!(f, "grpc"),
+            Self::Http => write!(f, "http"),
+        }
+    }
+}
+
+/// Exports the spans or the metrics to an OpenTelemetry collector by OTLP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtlpExporterConfig {
+    pub endpoint: String,
+    pub protocol: OtlpProtocol,
+    #[serde(with = "duration")]
+    pub timeout: Duration,
+    /// The spans are exported in batches at this interval, the metrics are exported at this interval
+    #[serde(with = "duration")]
+    pub export_interval: Duration,
+    pub max_queue_size: usize,
+    pub max_export_batch_size: usize,
+    /// Added to the resource attributes of the exported spans and metrics
+    pub resource_attributes: BTreeMap<String, String>,
+}
+
+impl Default for OtlpExporterConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4317".into(),
+            protocol: OtlpProtocol::Grpc,
+            timeout: Duration::from_secs(10),
+            export_interval: Duration::from_secs(5),
+            max_queue_size: 4096,
+            max_export_batch_size: 512,
+            resource_attributes: BTreeMap::new(),
+        }
+    }
+}
+
+/// Overrides the otlp exporter config by the environment variables `{prefix}_ENDPOINT`,
+/// `{prefix}_PROTOCOL` and `{prefix}_TIMEOUT`, the exporter is enabled if any of them is set.
+pub(crate) fn override_otlp_by_env(config: &mut Option<OtlpExporterConfig>, prefix: &str) {
+    let is_some = config.is_some();
+    let mut otlp = config.take().unwrap_or_default();
+    let endpoint = entry_override(&mut otlp.endpoint, &format!("{prefix}_ENDPOINT"));
+    let protocol = entry_override(&mut otlp.protocol, &format!("{prefix}_PROTOCOL"));
+    let timeout = entry_override_to_duration(&mut otlp.timeout, &format!("{prefix}_TIMEOUT"));
+    *config = match (is_some, endpoint || protocol || timeout) {
+        (_, true) | (true, false) => Some(otlp),
+        (false, false) => None,
+    };
+}
+
 #[test]
 fn test_serialize() {
     let trace_config = TraceConfig::default();
     let res = toml::to_string_pretty(&trace_config).unwrap();
     println!("{res}");
 }
+
+#[test]
+fn test_otlp_config() {
+    let config_str = r#"
+[otlp]
+endpoint = "http://127.0.0.1:4318"
+protocol = "http"
+timeout = "3s"
+resource_attributes = { "deployment.environment" = "test" }
+"#;
+    let config: TraceConfig = toml::from_str(config_str).unwrap();
+    let otlp = config.otlp.unwrap();
+    assert_eq!(otlp.endpoint, "http://127.0.0.1:4318");
+    assert_eq!(otlp.protocol, OtlpProtocol::Http);
+    assert_eq!(otlp.timeout, Duration::from_secs(3));
+    assert_eq!(otlp.export_interval, Duration::from_secs(5));
+    assert_eq!(
+        otlp.resource_attributes.get("deployment.environment"),
+        Some(&"test".to_string())
+    );
+}
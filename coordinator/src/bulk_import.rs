@@ -0,0 +1,242 @@
+//! Imports the Parquet files exported by `tskv::bulk` into a replication set.
+//!
+//! The import runs on the leader node of the replication set, the delta files are built
+//! out of the raft log, and only the series keys and the list of the staged files are
+//! written to the raft log, so applying the import on a replica is a cheap version edit.
+//! If the import fails after the files are staged, the import is aborted through the
+//! raft log, so the staged files are removed on all the replicas.
+
+use std::time::Duration;
+
+use models::meta_data::VnodeId;
+use models::schema::make_owner;
+use models::utils::now_timestamp_nanos;
+use protos::kv_service::admin_command_request::Command::FetchImportFiles;
+use protos::kv_service::{
+    raft_write_command, AbortImportRequest, AdminCommandRequest, FetchImportFilesRequest,
+    ImportFilesRequest, ImportSeriesRequest, RaftWriteCommand,
+};
+use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
+use tracing::{info, warn};
+use tskv::file_system::file_info;
+use tskv::kv_option::StorageOptions;
+use tskv::vnode_store::VnodeStorage;
+
+use crate::errors::{CoordinatorError, CoordinatorResult};
+use crate::raft::TskvEngineStorage;
+use crate::{get_replica_all_info, get_vnode_all_info, Coordinator};
+
+/// The number of series keys in a raft entry of an import.
+const IMPORT_SERIES_BATCH_SIZE: usize = 10_000;
+
+/// Imports the Parquet files under `path` into the replication set of the vnode, it
+/// must run on the leader node of the replication set, and `path` only needs to be
+/// readable on that node.
+pub async fn import_vnode(
+    coord: &dyn Coordinator,
+    tenant: &str,
+    db_name: &str,
+    vnode_id: VnodeId,
+    path: &str,
+) -> CoordinatorResult<()> {
+    let meta = coord.meta_manager();
+    let vnode_info = get_vnode_all_info(meta.clone(), tenant, vnode_id).await?;
+    let replica = get_replica_all_info(meta, tenant, vnode_info.repl_set_id)
+        .await?
+        .replica_set;
+    if replica.leader_node_id != coord.node_id() {
+        return Err(CoordinatorError::RaftForwardToLeader {
+            replica_id: replica.id,
+            leader_vnode_id: replica.leader_vnode_id,
+        });
+    }
+    let engine = coord
+        .store_engine()
+        .ok_or_else(|| CoordinatorError::CommonError {
+            msg: format!("node {} has no storage engine", coord.node_id()),
+        })?;
+    let vnode = engine
+        .open_tsfamily(tenant, db_name, replica.leader_vnode_id)
+        .await?;
+
+    // 1. Add the series to the index of all the replicas.
+    let series_keys = tskv::bulk::import_series_keys(&vnode, path).await?;
+    for series_keys in series_keys.chunks(IMPORT_SERIES_BATCH_SIZE) {
+        let command = RaftWriteCommand {
+            replica_id: replica.id,
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+            idempotency_key: String::new(),
+            command: Some(raft_write_command::Command::ImportSeries(
+                ImportSeriesRequest {
+                    series_keys: series_keys.iter().map(|key| key.encode()).collect(),
+                },
+            )),
+        };
+        coord
+            .write_replica_by_raft(replica.clone(), command, None)
+            .await?;
+    }
+
+    // 2. Build the delta files on the leader, and copy them to the other replicas.
+    let import_id = format!("{}_{}", replica.leader_vnode_id, now_timestamp_nanos());
+    let report = tskv::bulk::stage_import(&vnode, path, &import_id).await?;
+    let import = ImportFilesRequest {
+        import_id: import_id.clone(),
+        files: report.import_files,
+    };
+    let result = async {
+        let mut requests = vec![];
+        for follower in replica
+            .vnodes
+            .iter()
+            .filter(|v| v.id != replica.leader_vnode_id)
+        {
+            let request = AdminCommandRequest {
+                tenant: tenant.to_string(),
+                command: Some(FetchImportFiles(FetchImportFilesRequest {
+                    db_name: db_name.to_string(),
+                    vnode_id: follower.id,
+                    source_node_id: coord.node_id(),
+                    source_vnode_id: replica.leader_vnode_id,
+                    import: Some(import.clone()),
+                })),
+            };
+            requests.push(coord.exec_admin_command_on_node(follower.node_id, request));
+        }
+        for result in futures::future::join_all(requests).await {
+            result?;
+        }
+
+        // 3. Add the staged files to all the replicas.
+        let command = RaftWriteCommand {
+            replica_id: replica.id,
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+            idempotency_key: String::new(),
+            command: Some(raft_write_command::Command::ImportFiles(import)),
+        };
+        coord
+            .write_replica_by_raft(replica.clone(), command, None)
+            .await
+    }
+    .await;
+    if result.is_err() {
+        // The import entry may still be committed if the write failed after it was
+        // proposed, so the staged files are removed by a raft entry after it instead of
+        // being removed here, and all the replicas remove their staged files.
+        let command = RaftWriteCommand {
+            replica_id: replica.id,
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+            idempotency_key: String::new(),
+            command: Some(raft_write_command::Command::AbortImport(
+                AbortImportRequest {
+                    import_id: import_id.clone(),
+                },
+            )),
+        };
+        if let Err(e) = coord
+            .write_replica_by_raft(replica.clone(), command, None)
+            .await
+        {
+            warn!(
+                "Import: failed to abort import {import_id} of replication set {}: {e}",
+                replica.id
+            );
+        }
+    }
+    result?;
+
+    info!(
+        "Import: imported {path} into replication set {}: {} files, {} series, {} rows",
+        replica.id, report.files, report.series, report.rows
+    );
+
+    Ok(())
+}
+
+/// Downloads the delta files staged by an import from the source vnode into the import
+/// directory of the vnode, the directory is removed if any file fails to download.
+pub async fn fetch_import_files(
+    coord: &dyn Coordinator,
+    tenant: &str,
+    request: &FetchImportFilesRequest,
+) -> CoordinatorResult<()> {
+    let import = request
+        .import
+        .as_ref()
+        .ok_or_else(|| CoordinatorError::CommonError {
+            msg: "fetch import files request has no files".to_string(),
+        })?;
+    let engine = coord
+        .store_engine()
+        .ok_or_else(|| CoordinatorError::CommonError {
+            msg: format!("node {} has no storage engine", coord.node_id()),
+        })?;
+    let vnode = engine
+        .open_tsfamily(tenant, &request.db_name, request.vnode_id)
+        .await?;
+    let owner = make_owner(tenant, &request.db_name);
+    let dir = vnode.ts_family.read().await.storage_opt().import_dir(
+        &owner,
+        request.vnode_id,
+        &import.import_id,
+    );
+    let src_dir =
+        StorageOptions::fmt_import_dir(&owner, request.source_vnode_id, &import.import_id);
+
+    let channel = coord
+        .meta_manager()
+        .get_node_conn(request.source_node_id)
+        .await?;
+    let mut client = tskv_service_time_out_client(
+        channel,
+        Duration::from_secs(60 * 60),
+        DEFAULT_GRPC_SERVER_MESSAGE_LEN,
+        coord.get_config().service.grpc_enable_gzip,
+    );
+    for file in import.files.iter() {
+        let filename = dir.join(&file.name);
+        let src_filename = src_dir.join(&file.name).to_string_lossy().to_string();
+        let result = async {
+            TskvEngineStorage::download_file(&src_filename, &filename, &mut client).await?;
+            let filename = filename.to_string_lossy().to_string();
+            let info = file_info::get_file_info(&filename).await?;
+            if info.md5 != file.md5 {
+                return Err(CoordinatorError::CommonError {
+                    msg: format!("download import file {} md5 not match", file.name),
+                });
+            }
+            Ok(())
+        }
+        .await;
+        if result.is_err() {
+            remove_import_dir(&vnode, &import.import_id).await;
+        }
+        result?;
+    }
+    info!(
+        "Import: downloaded {} files of import {} from node {} to vnode {}",
+        import.files.len(),
+        import.import_id,
+        request.source_node_id,
+        request.vnode_id
+    );
+
+    Ok(())
+}
+
+async fn remove_import_dir(vnode: &VnodeStorage, import_id: &str) {
+    let dir = {
+        let tsf = vnode.ts_family.read().await;
+        tsf.storage_opt()
+            .import_dir(&tsf.tenant_database(), vnode.id, import_id)
+    };
+    if let Err(e) = tokio::fs::remove_dir_all(&dir).await {
+        warn!(
+            "Import: failed to remove import directory {}: {e}",
+            dir.display()
+        );
+    }
+}
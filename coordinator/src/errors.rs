@@ -226,6 +226,16 @@ pub enum CoordinatorError {
     InvalidInitialConfig {
         msg: String,
     },
+
+    #[snafu(display(
+        "Rejected {points} late points of table {table}, the timestamp should not be less than {bound}"
+    ))]
+    #[error_code(code = 32)]
+    LatePointsRejected {
+        table: String,
+        points: u64,
+        bound: i64,
+    },
 }
 
 impl From<PointsError> for CoordinatorError {
@@ -18,15 +18,18 @@ use protos::kv_service::{AdminCommandRequest, RaftWriteCommand, UpdateSetValue};
 use raft::manager::RaftNodesManager;
 use trace::SpanContext;
 use tskv::reader::QueryOption;
+use tskv::stats::StorageStatsKind;
 use tskv::EngineRef;
 
 use crate::errors::CoordinatorResult;
 use crate::service::CoordServiceMetrics;
 
+pub mod bulk_import;
 pub mod errors;
 pub mod metrics;
 pub mod raft;
 pub mod reader;
+pub mod rebalance;
 pub mod resource_manager;
 pub mod service;
 pub mod service_mock;
@@ -49,6 +52,12 @@ pub enum VnodeManagerCmdType {
     RemoveRaftNode(u32),
     /// replica set id
     DestoryRaftGroup(u32),
+    /// replica set id, dst node id
+    TransferLeader(u32, u64),
+    /// vnode id, directory to export the vnode data as parquet files
+    Export(u32, String),
+    /// vnode id, directory of the parquet files to import
+    Import(u32, String),
 }
 
 #[derive(Debug, Clone)]
@@ -98,14 +107,19 @@ pub trait Coordinator: Send + Sync {
         db: &str,
         precision: Precision,
         lines: Vec<Line<'a>>,
+        idempotency_key: Option<&str>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize>;
 
+    /// Write the record batch with the `write_time` of the client request in nanoseconds,
+    /// the record batches of a request take the same write time.
     async fn write_record_batch<'a>(
         &self,
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        write_time: i64,
+        idempotency_key: Option<&str>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize>;
 
@@ -141,6 +155,21 @@ pub trait Coordinator: Send + Sync {
         cmd_type: VnodeSummarizerCmdType,
     ) -> CoordinatorResult<Vec<RecordBatch>>;
 
+    /// Queries running on the other data and query nodes, in the schema of
+    /// `information_schema.queries`.
+    async fn cluster_queries(&self) -> CoordinatorResult<Vec<RecordBatch>>;
+
+    /// Storage statistics of all the data nodes, in the schema of `kind`.
+    ///
+    /// The unreachable nodes are skipped.
+    async fn cluster_storage_stats(
+        &self,
+        kind: StorageStatsKind,
+    ) -> CoordinatorResult<Vec<RecordBatch>>;
+
+    /// Kills the query on the other data and query nodes, returns false if none of them is running it.
+    async fn kill_cluster_query(&self, query_id: u64) -> CoordinatorResult<bool>;
+
     fn metrics(&self) -> &Arc<CoordServiceMetrics>;
 
     async fn update_tags_value(
@@ -8,13 +8,13 @@ use models::meta_data::*;
 use protos::kv_service::*;
 use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use replication::multi_raft::MultiRaft;
-use replication::node_store::NodeStorage;
+use replication::node_store::{NodeStorage, SerializableSnapshot};
 use replication::raft_node::RaftNode;
 use replication::state_store::{RaftNodeSummary, StateStorage};
 use replication::{ApplyStorageRef, EntryStorageRef, RaftNodeId, RaftNodeInfo, ReplicationConfig};
 use tokio::sync::RwLock;
 use tracing::info;
-use tskv::{wal, EngineRef};
+use tskv::{wal, EngineRef, VnodeSnapshot};
 
 use super::TskvEngineStorage;
 use crate::errors::*;
@@ -60,6 +60,15 @@ impl RaftNodesManager {
         }
     }
 
+    /// Returns the local vnode id and the raft applied index of the group
+    pub async fn applied_index(&self, group_id: u32) -> Option<(VnodeId, u64)> {
+        let node = self.raft_nodes.read().await.get_node(group_id)?;
+        let metrics = node.raft_metrics();
+        let applied = metrics.last_applied.map(|log| log.index).unwrap_or(0);
+
+        Some((node.raft_id() as VnodeId, applied))
+    }
+
     pub async fn start_all_raft_node(&self) -> CoordinatorResult<()> {
         let nodes_summary = self.raft_state.all_nodes_summary()?;
         let mut nodes = self.raft_nodes.write().await;
@@ -371,6 +380,92 @@ impl RaftNodesManager {
         Ok(())
     }
 
+    pub async fn transfer_leader(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        vnode_id: VnodeId,
+        replica_id: ReplicationSetId,
+    ) -> CoordinatorResult<()> {
+        let all_info = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+        let replica = all_info.replica_set.clone();
+        let vnode = replica
+            .vnode(vnode_id)
+            .ok_or(CoordinatorError::VnodeNotFound { id: vnode_id })?;
+
+        let raft_node = self.get_node_or_build(tenant, db_name, &replica).await?;
+        self.assert_leader_node(raft_node.clone()).await?;
+
+        // the vnode starts an election after it has received all the logs of the leader
+        let last_log_index = raft_node.raft_metrics().last_log_index.unwrap_or(0);
+        self.elect_remote_raft_node(tenant, db_name, &vnode, replica_id, last_log_index)
+            .await?;
+
+        let to = vnode_id as RaftNodeId;
+        raft_node
+            .wait_condition(
+                |metrics| metrics.current_leader == Some(to),
+                Duration::from_secs(10),
+                format!("group-{} transfer leader to {}", replica_id, vnode_id),
+            )
+            .await?;
+
+        self.meta
+            .tenant_meta(tenant)
+            .await
+            .ok_or(CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            })?
+            .change_repl_set_leader(
+                db_name,
+                all_info.bucket_id,
+                replica_id,
+                vnode.node_id,
+                vnode_id,
+            )
+            .await?;
+
+        info!(
+            "transfer leader of replica set({}) to vnode({}) on node({})",
+            replica_id, vnode_id, vnode.node_id
+        );
+
+        Ok(())
+    }
+
+    /// Starts an election on the vnode of the replication set on this node once its log
+    /// reaches `last_log_index`, the leader asks for it to transfer the leadership.
+    pub async fn elect_leader(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        vnode_id: VnodeId,
+        replica_id: ReplicationSetId,
+        last_log_index: u64,
+    ) -> CoordinatorResult<()> {
+        let all_info = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+        let raft_node = self
+            .get_node_or_build(tenant, db_name, &all_info.replica_set)
+            .await?;
+        if raft_node.raft_id() != vnode_id as RaftNodeId {
+            return Err(CoordinatorError::VnodeNotFound { id: vnode_id });
+        }
+
+        raft_node
+            .wait_condition(
+                |metrics| metrics.last_log_index >= Some(last_log_index),
+                Duration::from_secs(10),
+                format!(
+                    "group-{} vnode {} catch up to log {}",
+                    replica_id, vnode_id, last_log_index
+                ),
+            )
+            .await?;
+        raft_node.raft_elect().await?;
+
+        Ok(())
+    }
+
     async fn open_raft_node(
         &self,
         tenant: &str,
@@ -437,6 +532,12 @@ impl RaftNodesManager {
 
         // 3. recover data...
         let _apply_id = self.raft_state.get_last_applied_log(group_id)?;
+        if let Some(snapshot) = self.raft_state.get_snapshot(group_id)? {
+            // the idempotency keys of the purged raft logs are only in the snapshot
+            let snapshot = bincode::deserialize::<SerializableSnapshot>(&snapshot.data)?;
+            let snapshot = VnodeSnapshot::decode(&snapshot.data)?;
+            vnode_store.restore_idempotency_keys(snapshot.idempotency_keys);
+        }
         raft_logs.recover(&mut vnode_store).await?;
 
         // 4. open raft apply storage
@@ -539,4 +640,47 @@ impl RaftNodesManager {
 
         crate::status_response_to_result(&response)
     }
+
+    async fn elect_remote_raft_node(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        vnode: &VnodeInfo,
+        replica_id: ReplicationSetId,
+        last_log_index: u64,
+    ) -> CoordinatorResult<()> {
+        info!(
+            "elect remote raft node: {}.{}.{} after log {}",
+            vnode.node_id, replica_id, vnode.id, last_log_index
+        );
+
+        let channel = self.meta.get_node_conn(vnode.node_id).await?;
+        let mut client = tskv_service_time_out_client(
+            channel,
+            Duration::from_secs(30),
+            DEFAULT_GRPC_SERVER_MESSAGE_LEN,
+            self.config.service.grpc_enable_gzip,
+        );
+        let cmd = tonic::Request::new(AdminCommandRequest {
+            tenant: tenant.to_string(),
+            command: Some(admin_command_request::Command::ElectLeader(
+                ElectLeaderRequest {
+                    db_name: db_name.to_string(),
+                    replica_id,
+                    vnode_id: vnode.id,
+                    last_log_index,
+                },
+            )),
+        });
+
+        let response = client
+            .exec_admin_command(cmd)
+            .await
+            .map_err(|err| CoordinatorError::GRPCRequest {
+                msg: err.to_string(),
+            })?
+            .into_inner();
+
+        crate::status_response_to_result(&response)
+    }
 }
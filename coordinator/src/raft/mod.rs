@@ -116,7 +116,7 @@ impl TskvEngineStorage {
         Ok(())
     }
 
-    async fn download_file(
+    pub(crate) async fn download_file(
         download: &str,
         filename: &Path,
         client: &mut TskvServiceClient<Timeout<Channel>>,
@@ -165,13 +165,12 @@ impl TskvEngineStorage {
         req: &replication::Request,
     ) -> ReplicationResult<replication::Response> {
         let request = parse_prost_bytes::<RaftWriteCommand>(req)?;
-        if let Some(command) = request.command {
-            self.vnode.apply(ctx, command).await.map_err(|err| {
-                ReplicationError::ApplyEngineErr {
-                    msg: err.to_string(),
-                }
+        self.vnode
+            .apply_request(ctx, request)
+            .await
+            .map_err(|err| ReplicationError::ApplyEngineErr {
+                msg: err.to_string(),
             })?;
-        }
 
         Ok(vec![])
     }
@@ -202,12 +201,19 @@ impl ApplyStorage for TskvEngineStorage {
             }
         })?;
 
-        let data = bincode::serialize(&snapshot)?;
+        let data = snapshot
+            .encode()
+            .map_err(|err| ReplicationError::CreateSnapshotErr {
+                msg: err.to_string(),
+            })?;
         Ok(data)
     }
 
     async fn restore(&mut self, data: &[u8]) -> ReplicationResult<()> {
-        let snapshot = bincode::deserialize::<VnodeSnapshot>(data)?;
+        let snapshot =
+            VnodeSnapshot::decode(data).map_err(|err| ReplicationError::RestoreSnapshotErr {
+                msg: err.to_string(),
+            })?;
         let opt = self.storage.get_storage_options();
         let download_dir = opt.path().join(&snapshot.snapshot_id);
 
@@ -145,6 +145,9 @@ impl RaftWriter {
                 raft_write_command::Command::DropColumn(_request) => {}
                 raft_write_command::Command::UpdateTags(_request) => {}
                 raft_write_command::Command::DeleteFromTable(_request) => {}
+                raft_write_command::Command::ImportSeries(_request) => {}
+                raft_write_command::Command::ImportFiles(_request) => {}
+                raft_write_command::Command::AbortImport(_request) => {}
             }
         }
 
@@ -1,4 +1,5 @@
 pub mod deserialize;
+pub mod replica_selection;
 pub mod table_scan;
 pub mod tag_scan;
 
@@ -16,6 +17,7 @@ use models::meta_data::{VnodeId, VnodeInfo, VnodeStatus};
 use tracing::warn;
 use tskv::reader::QueryOption;
 
+use self::replica_selection::SelectFuture;
 use crate::errors::{CoordinatorError, CoordinatorResult};
 use crate::service::CoordServiceMetrics;
 use crate::SendableCoordinatorRecordBatchStream;
@@ -39,6 +41,7 @@ pub struct CheckedCoordinatorRecordBatchStream<O: VnodeOpener> {
     vnode: VnodeInfo,
     option: QueryOption,
     state: StreamState,
+    selector: Option<SelectFuture>,
 
     coord_data_out: U64Counter,
 }
@@ -49,6 +52,7 @@ impl<O: VnodeOpener> CheckedCoordinatorRecordBatchStream<O> {
         opener: O,
         meta: MetaRef,
         checker: CheckFuture,
+        selector: Option<SelectFuture>,
         metrics: &CoordServiceMetrics,
     ) -> Self {
         let tenant: Arc<String> = option.table_schema.tenant.clone().into();
@@ -63,29 +67,50 @@ impl<O: VnodeOpener> CheckedCoordinatorRecordBatchStream<O> {
             tenant,
             vnode: VnodeInfo::default(),
             state: StreamState::Check(checker),
+            selector,
             coord_data_out,
         }
     }
 
+    fn pop_vnode(&mut self) -> CoordinatorResult<()> {
+        self.vnode = self
+            .option
+            .split
+            .pop_front()
+            .ok_or(CoordinatorError::NoValidReplica {
+                id: self.option.split.replica_id(),
+            })?;
+        self.state = StreamState::Idle;
+
+        Ok(())
+    }
+
     fn poll_inner(&mut self, cx: &mut Context<'_>) -> Poll<Option<CoordinatorResult<RecordBatch>>> {
         loop {
             match &mut self.state {
                 StreamState::Check(checker) => {
                     // TODO record time used
                     match ready!(checker.try_poll_unpin(cx)) {
-                        Ok(_) => {
-                            self.vnode = self.option.split.pop_front().ok_or(
-                                CoordinatorError::NoValidReplica {
-                                    id: self.option.split.replica_id(),
-                                },
-                            )?;
-
-                            self.state = StreamState::Idle;
-                        }
+                        Ok(_) => match self.selector.take() {
+                            Some(selector) => self.state = StreamState::Select(selector),
+                            None => self.pop_vnode()?,
+                        },
 
                         Err(err) => return Poll::Ready(Some(Err(err))),
                     };
                 }
+                StreamState::Select(selector) => {
+                    match ready!(selector.poll_unpin(cx)) {
+                        Ok(vnodes) if !vnodes.is_empty() => self.option.split.set_vnodes(vnodes),
+                        Ok(_) => {}
+                        Err(err) => warn!(
+                            "select replicas of {} failed, read from the leader: {}",
+                            self.option.split.replica_id(),
+                            err
+                        ),
+                    }
+                    self.pop_vnode()?;
+                }
                 StreamState::Idle => {
                     // TODO record time used
                     let future = match self.opener.open(&self.vnode, &self.option) {
@@ -121,6 +146,13 @@ impl<O: VnodeOpener> CheckedCoordinatorRecordBatchStream<O> {
                         Some(res) => match res {
                             Ok(batch) => {
                                 let batch_memory = batch.get_array_memory_size();
+                                if let Some(tracker) = &self.option.resource_tracker {
+                                    tracker.record_scan(
+                                        self.vnode.id,
+                                        batch.num_rows(),
+                                        batch_memory,
+                                    );
+                                }
                                 let meta = self.meta.clone();
                                 let tenant_name = self.tenant.clone();
                                 let future = async move {
@@ -227,6 +259,7 @@ pub async fn change_vnode_to_broken(
 
 enum StreamState {
     Check(CheckFuture),
+    Select(SelectFuture),
     Idle,
     Open(VnodeOpenFuture),
     Scan(SendableCoordinatorRecordBatchStream, ScanState),
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use datafusion::arrow::array::{UInt32Array, UInt64Array};
+use futures::future::BoxFuture;
+use meta::model::MetaRef;
+use models::meta_data::{ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo, VnodeStatus};
+use models::record_batch_decode;
+use protos::kv_service::{
+    admin_fetch_command_request, AdminFetchCommandRequest, FetchRaftAppliedIndexRequest,
+};
+use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
+use tracing::debug;
+
+use crate::errors::{CoordinatorError, CoordinatorResult};
+use crate::get_replica_all_info;
+use crate::raft::manager::RaftNodesManager;
+
+pub type SelectFuture = BoxFuture<'static, CoordinatorResult<Vec<VnodeInfo>>>;
+
+const FETCH_APPLIED_INDEX_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Chooses the vnodes of a replica set to read from for `read_consistency = leader`, with
+/// the leader of the latest replica set.
+pub fn select_leader(meta: MetaRef, tenant: String, replica_id: ReplicationSetId) -> SelectFuture {
+    Box::pin(async move {
+        let replica = get_replica_all_info(meta, &tenant, replica_id)
+            .await?
+            .replica_set;
+
+        Ok(leader_first(&replica))
+    })
+}
+
+/// Chooses the vnodes of a replica set to read from for `read_consistency = follower(max_lag)`
+pub struct FollowerSelector {
+    meta: MetaRef,
+    raft_manager: Arc<RaftNodesManager>,
+    tenant: String,
+    max_lag: u64,
+    grpc_enable_gzip: bool,
+}
+
+impl FollowerSelector {
+    pub fn new(
+        meta: MetaRef,
+        raft_manager: Arc<RaftNodesManager>,
+        tenant: String,
+        max_lag: u64,
+        grpc_enable_gzip: bool,
+    ) -> Self {
+        Self {
+            meta,
+            raft_manager,
+            tenant,
+            max_lag,
+            grpc_enable_gzip,
+        }
+    }
+
+    pub fn select(self, replica_id: ReplicationSetId) -> SelectFuture {
+        Box::pin(async move {
+            let replica = get_replica_all_info(self.meta.clone(), &self.tenant, replica_id)
+                .await?
+                .replica_set;
+
+            let requests = replica
+                .vnodes
+                .iter()
+                .filter(|v| v.status == VnodeStatus::Running)
+                .map(|v| self.applied_index(v.node_id, replica_id));
+            let applied = futures::future::join_all(requests)
+                .await
+                .into_iter()
+                .filter_map(|res| match res {
+                    Ok(applied) => Some(applied),
+                    Err(err) => {
+                        debug!("fetch applied index of replica set {replica_id} failed: {err}");
+                        None
+                    }
+                })
+                .collect::<HashMap<_, _>>();
+
+            Ok(order_by_lag(
+                &replica,
+                &applied,
+                self.meta.node_id(),
+                self.max_lag,
+            ))
+        })
+    }
+
+    async fn applied_index(
+        &self,
+        node_id: u64,
+        replica_id: ReplicationSetId,
+    ) -> CoordinatorResult<(VnodeId, u64)> {
+        if node_id == self.meta.node_id() {
+            return self
+                .raft_manager
+                .applied_index(replica_id)
+                .await
+                .ok_or(CoordinatorError::RaftNodeNotFound { id: replica_id });
+        }
+
+        let channel = self.meta.get_node_conn(node_id).await?;
+        let mut client = tskv_service_time_out_client(
+            channel,
+            FETCH_APPLIED_INDEX_TIMEOUT,
+            DEFAULT_GRPC_SERVER_MESSAGE_LEN,
+            self.grpc_enable_gzip,
+        );
+        let request = tonic::Request::new(AdminFetchCommandRequest {
+            tenant: self.tenant.clone(),
+            command: Some(admin_fetch_command_request::Command::FetchRaftAppliedIndex(
+                FetchRaftAppliedIndexRequest { replica_id },
+            )),
+        });
+        let response = client
+            .exec_admin_fetch_command(request)
+            .await
+            .map_err(tskv::Error::from)?
+            .into_inner();
+        let batch = record_batch_decode(&response.data)?;
+
+        let vnode_id = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .filter(|a| !a.is_empty())
+            .map(|a| a.value(0));
+        let applied = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .filter(|a| !a.is_empty())
+            .map(|a| a.value(0));
+        match (vnode_id, applied) {
+            (Some(vnode_id), Some(applied)) => Ok((vnode_id, applied)),
+            _ => Err(CoordinatorError::CommonError {
+                msg: format!("invalid applied index response of replica set {replica_id}"),
+            }),
+        }
+    }
+}
+
+/// Orders the vnodes of the replica set with the leader first, the followers are only read
+/// if the leader fails.
+pub fn leader_first(replica: &ReplicationSet) -> Vec<VnodeInfo> {
+    let mut vnodes = replica.vnodes.clone();
+    vnodes.sort_by_key(|v| v.id != replica.leader_vnode_id);
+    vnodes
+}
+
+/// Orders the vnodes of the replica set by the preference of reading.
+///
+/// Running followers whose applied index is within `max_lag` of the leader come first,
+/// the ones on the local node and with smaller lag are preferred; the leader is always
+/// the last choice. If the applied index of the leader is unknown, only the leader is read.
+pub fn order_by_lag(
+    replica: &ReplicationSet,
+    applied: &HashMap<VnodeId, u64>,
+    local_node_id: u64,
+    max_lag: u64,
+) -> Vec<VnodeInfo> {
+    let leader = replica
+        .vnodes
+        .iter()
+        .find(|v| v.id == replica.leader_vnode_id)
+        .cloned();
+    let (Some(leader), Some(leader_applied)) = (leader, applied.get(&replica.leader_vnode_id))
+    else {
+        return replica
+            .vnodes
+            .iter()
+            .filter(|v| v.id == replica.leader_vnode_id)
+            .cloned()
+            .collect();
+    };
+
+    let mut followers = replica
+        .vnodes
+        .iter()
+        .filter(|v| v.id != leader.id && v.status == VnodeStatus::Running)
+        .filter_map(|v| {
+            let lag = leader_applied.saturating_sub(*applied.get(&v.id)?);
+            (lag <= max_lag).then_some((v, lag))
+        })
+        .collect::<Vec<_>>();
+    followers.sort_by_key(|(v, lag)| (v.node_id != local_node_id, *lag));
+
+    followers
+        .into_iter()
+        .map(|(v, _)| v.clone())
+        .chain(std::iter::once(leader))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use models::meta_data::{ReplicationSet, VnodeInfo, VnodeStatus};
+
+    use super::{leader_first, order_by_lag};
+
+    fn vnode(id: u32, node_id: u64, status: VnodeStatus) -> VnodeInfo {
+        VnodeInfo {
+            id,
+            node_id,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_leader_first() {
+        let replica = ReplicationSet::new(
+            1,
+            1002,
+            2,
+            vec![
+                vnode(1, 1001, VnodeStatus::Running),
+                vnode(2, 1002, VnodeStatus::Running),
+                vnode(3, 1003, VnodeStatus::Running),
+            ],
+        );
+        let ids = leader_first(&replica)
+            .iter()
+            .map(|v| v.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn test_order_by_lag() {
+        let replica = ReplicationSet::new(
+            1,
+            1001,
+            1,
+            vec![
+                vnode(1, 1001, VnodeStatus::Running),
+                vnode(2, 1002, VnodeStatus::Running),
+                vnode(3, 1003, VnodeStatus::Running),
+                vnode(4, 1004, VnodeStatus::Copying),
+            ],
+        );
+        let ids = |vnodes: Vec<VnodeInfo>| vnodes.iter().map(|v| v.id).collect::<Vec<_>>();
+
+        let applied = HashMap::from([(1, 100), (2, 95), (3, 99), (4, 100)]);
+        assert_eq!(
+            ids(order_by_lag(&replica, &applied, 1005, 10)),
+            vec![3, 2, 1]
+        );
+        // local follower first
+        assert_eq!(
+            ids(order_by_lag(&replica, &applied, 1002, 10)),
+            vec![2, 3, 1]
+        );
+        // lagging followers are skipped
+        assert_eq!(ids(order_by_lag(&replica, &applied, 1005, 2)), vec![3, 1]);
+        assert_eq!(ids(order_by_lag(&replica, &applied, 1005, 0)), vec![1]);
+
+        // unknown follower
+        let applied = HashMap::from([(1, 100), (3, 99)]);
+        assert_eq!(ids(order_by_lag(&replica, &applied, 1005, 10)), vec![3, 1]);
+
+        // unknown leader, fall back to the leader
+        let applied = HashMap::from([(2, 95), (3, 99)]);
+        assert_eq!(ids(order_by_lag(&replica, &applied, 1005, 10)), vec![1]);
+    }
+}
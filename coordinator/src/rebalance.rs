@@ -0,0 +1,793 @@
+//! Moves vnodes between data nodes, to balance the disk usage of the nodes
+//! and to drain the vnodes off the decommissioned nodes.
+//! Transfers the leaders of the replication sets, to even out the leaders per node.
+//! Adds or removes vnodes of the replication sets, to change the replica count of a database.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use config::ClusterConfig;
+use models::meta_data::{
+    NodeId, NodeMetrics, ReplicationSet, ReplicationSetId, VnodeId, VnodeStatus,
+};
+use models::oid::Identifier;
+use models::schema::{ResourceOperator, ResourceStatus};
+use tracing::{error, info};
+
+use crate::errors::{CoordinatorError, CoordinatorResult};
+use crate::{get_replica_all_info, Coordinator, VnodeManagerCmdType};
+
+/// Moves the vnode to another node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VnodeMove {
+    pub tenant: String,
+    pub replica_id: ReplicationSetId,
+    pub vnode_id: VnodeId,
+    pub from: NodeId,
+    pub to: NodeId,
+}
+
+/// Transfers the leadership of the replication set to its vnode on another node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderTransfer {
+    pub tenant: String,
+    pub replica_id: ReplicationSetId,
+    pub from: NodeId,
+    pub to: NodeId,
+}
+
+/// Changes the replica count of the replication set by one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplicaChange {
+    /// Adds a follower on the node, it catches up with the leader by the raft logs or a snapshot.
+    AddFollower {
+        tenant: String,
+        replica_id: ReplicationSetId,
+        to: NodeId,
+    },
+    /// Removes the vnode, which is never the leader.
+    RemoveVnode {
+        tenant: String,
+        replica_id: ReplicationSetId,
+        vnode_id: VnodeId,
+        from: NodeId,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct NodeLoad {
+    disk_free: u64,
+    vnodes: usize,
+    leaders: usize,
+}
+
+/// The placement of the vnodes of all tenants, and the load of the nodes which can hold vnodes.
+#[derive(Debug, Clone)]
+pub struct ClusterLayout {
+    // the healthy nodes which are not decommissioned
+    nodes: BTreeMap<NodeId, NodeLoad>,
+    // tenant name, replication set
+    replicas: Vec<(String, ReplicationSet)>,
+}
+
+impl ClusterLayout {
+    pub fn new(
+        node_metrics: &[NodeMetrics],
+        excluded_nodes: &HashSet<NodeId>,
+        replicas: Vec<(String, ReplicationSet)>,
+    ) -> Self {
+        let mut nodes: BTreeMap<NodeId, NodeLoad> = node_metrics
+            .iter()
+            .filter(|m| m.is_healthy() && !excluded_nodes.contains(&m.id))
+            .map(|m| {
+                let load = NodeLoad {
+                    disk_free: m.disk_free,
+                    vnodes: 0,
+                    leaders: 0,
+                };
+                (m.id, load)
+            })
+            .collect();
+
+        for (_, replica) in replicas.iter() {
+            for vnode in replica.vnodes.iter() {
+                if let Some(load) = nodes.get_mut(&vnode.node_id) {
+                    load.vnodes += 1;
+                }
+            }
+            if let Some(load) = nodes.get_mut(&replica.leader_node_id) {
+                load.leaders += 1;
+            }
+        }
+
+        Self { nodes, replicas }
+    }
+
+    pub async fn load(coord: &dyn Coordinator) -> CoordinatorResult<Self> {
+        let meta = coord.meta_manager();
+        let node_metrics = meta.node_metrics().await?;
+        let excluded_nodes = meta.decommissioned_nodes().await?;
+
+        let mut replicas = vec![];
+        for tenant in meta.tenants().await? {
+            let Some(client) = meta.tenant_meta(tenant.name()).await else {
+                continue;
+            };
+            for (_, db_info) in client.list_databases()? {
+                for bucket in db_info.buckets {
+                    for replica in bucket.shard_group {
+                        replicas.push((tenant.name().to_string(), replica));
+                    }
+                }
+            }
+        }
+
+        Ok(Self::new(&node_metrics, &excluded_nodes, replicas))
+    }
+
+    /// Plans at most `max_moves` moves, each of them moves a vnode from a node with less free disk
+    /// space to a node with more free disk space, if the difference exceeds `disk_free_gap`.
+    ///
+    /// A vnode is moved only if the source node holds at least 2 more vnodes than the target node,
+    /// so the moves never bounce between two nodes. Followers are preferred to leaders to keep the
+    /// leadership of the replication sets.
+    pub fn plan_rebalance(&mut self, max_moves: usize, disk_free_gap: u64) -> Vec<VnodeMove> {
+        let mut moves = vec![];
+        while moves.len() < max_moves {
+            let Some(mv) = self.next_rebalance_move(disk_free_gap) else {
+                break;
+            };
+            self.apply(&mv);
+            moves.push(mv);
+        }
+
+        moves
+    }
+
+    fn next_rebalance_move(&self, disk_free_gap: u64) -> Option<VnodeMove> {
+        let mut by_disk_free = self.nodes.iter().collect::<Vec<_>>();
+        by_disk_free.sort_by_key(|(id, load)| (load.disk_free, **id));
+
+        for (from, from_load) in by_disk_free.iter() {
+            for (to, to_load) in by_disk_free.iter().rev() {
+                if to_load.disk_free.saturating_sub(from_load.disk_free) <= disk_free_gap {
+                    break;
+                }
+                if from_load.vnodes <= to_load.vnodes + 1 {
+                    continue;
+                }
+                if let Some(mv) = self.pick_vnode(**from, **to) {
+                    return Some(mv);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Plans the moves of all the vnodes on the node to the other nodes,
+    /// the nodes holding fewer vnodes are preferred.
+    pub fn plan_decommission(&mut self, node_id: NodeId) -> CoordinatorResult<Vec<VnodeMove>> {
+        let vnodes = self
+            .replicas
+            .iter()
+            .filter_map(|(tenant, replica)| {
+                replica
+                    .by_node_id(node_id)
+                    .map(|v| (tenant.clone(), replica.clone(), v.id))
+            })
+            .collect::<Vec<_>>();
+
+        let mut moves = Vec::with_capacity(vnodes.len());
+        for (tenant, replica, vnode_id) in vnodes {
+            let to = self
+                .nodes
+                .iter()
+                .filter(|(id, _)| **id != node_id && replica.by_node_id(**id).is_none())
+                .min_by_key(|(id, load)| (load.vnodes, u64::MAX - load.disk_free, **id))
+                .map(|(id, _)| *id)
+                .ok_or_else(|| CoordinatorError::CommonError {
+                    msg: format!(
+                        "no node is available for vnode {} of replication set {} on node {}",
+                        vnode_id, replica.id, node_id
+                    ),
+                })?;
+
+            let mv = VnodeMove {
+                tenant,
+                replica_id: replica.id,
+                vnode_id,
+                from: node_id,
+                to,
+            };
+            self.apply(&mv);
+            moves.push(mv);
+        }
+
+        Ok(moves)
+    }
+
+    /// Plans the changes to make each of the tenant's replication sets in `replica_ids` have
+    /// `replica` vnodes. New followers are placed on the nodes holding fewer vnodes, and the
+    /// followers which are not running or on the nodes holding more vnodes are removed first.
+    pub fn plan_replica_change(
+        &mut self,
+        tenant: &str,
+        replica_ids: &HashSet<ReplicationSetId>,
+        replica: usize,
+    ) -> CoordinatorResult<Vec<ReplicaChange>> {
+        let targets = self
+            .replicas
+            .iter()
+            .filter(|(t, r)| t == tenant && replica_ids.contains(&r.id))
+            .map(|(_, r)| r.clone())
+            .collect::<Vec<_>>();
+
+        let mut changes = vec![];
+        for repl_set in targets {
+            let mut holders = repl_set
+                .vnodes
+                .iter()
+                .map(|v| v.node_id)
+                .collect::<HashSet<_>>();
+            for _ in repl_set.vnodes.len()..replica {
+                let to = self
+                    .nodes
+                    .iter()
+                    .filter(|(id, _)| !holders.contains(*id))
+                    .min_by_key(|(id, load)| (load.vnodes, u64::MAX - load.disk_free, **id))
+                    .map(|(id, _)| *id)
+                    .ok_or_else(|| CoordinatorError::CommonError {
+                        msg: format!(
+                            "no node is available for a new vnode of replication set {}",
+                            repl_set.id
+                        ),
+                    })?;
+
+                holders.insert(to);
+                if let Some(load) = self.nodes.get_mut(&to) {
+                    load.vnodes += 1;
+                }
+                changes.push(ReplicaChange::AddFollower {
+                    tenant: tenant.to_string(),
+                    replica_id: repl_set.id,
+                    to,
+                });
+            }
+
+            let mut followers = repl_set
+                .vnodes
+                .iter()
+                .filter(|v| v.id != repl_set.leader_vnode_id)
+                .map(|v| {
+                    let load = self.nodes.get(&v.node_id).map_or(usize::MAX, |l| l.vnodes);
+                    (
+                        v.status == VnodeStatus::Running,
+                        Reverse(load),
+                        v.id,
+                        v.node_id,
+                    )
+                })
+                .collect::<Vec<_>>();
+            followers.sort();
+            let remove_count = repl_set.vnodes.len().saturating_sub(replica.max(1));
+            for (_, _, vnode_id, from) in followers.into_iter().take(remove_count) {
+                if let Some(load) = self.nodes.get_mut(&from) {
+                    load.vnodes = load.vnodes.saturating_sub(1);
+                }
+                changes.push(ReplicaChange::RemoveVnode {
+                    tenant: tenant.to_string(),
+                    replica_id: repl_set.id,
+                    vnode_id,
+                    from,
+                });
+            }
+        }
+
+        Ok(changes)
+    }
+
+    fn pick_vnode(&self, from: NodeId, to: NodeId) -> Option<VnodeMove> {
+        self.replicas
+            .iter()
+            .filter(|(_, replica)| {
+                replica
+                    .vnodes
+                    .iter()
+                    .all(|v| v.status == VnodeStatus::Running)
+                    && replica.by_node_id(to).is_none()
+            })
+            .filter_map(|(tenant, replica)| {
+                replica.by_node_id(from).map(|vnode| {
+                    let is_leader = replica.leader_vnode_id == vnode.id;
+                    (is_leader, vnode.id, tenant, replica.id)
+                })
+            })
+            .min()
+            .map(|(_, vnode_id, tenant, replica_id)| VnodeMove {
+                tenant: tenant.clone(),
+                replica_id,
+                vnode_id,
+                from,
+                to,
+            })
+    }
+
+    /// Plans at most `max_transfers` leader transfers, each of them transfers a leader from the
+    /// node with the most leaders to a node holding at least 2 fewer leaders, which has a vnode of
+    /// the replication set.
+    pub fn plan_leader_balance(&mut self, max_transfers: usize) -> Vec<LeaderTransfer> {
+        let mut transfers = vec![];
+        while transfers.len() < max_transfers {
+            let Some(transfer) = self.next_leader_transfer() else {
+                break;
+            };
+            self.apply_leader_transfer(&transfer);
+            transfers.push(transfer);
+        }
+
+        transfers
+    }
+
+    fn next_leader_transfer(&self) -> Option<LeaderTransfer> {
+        let mut by_leaders = self.nodes.iter().collect::<Vec<_>>();
+        by_leaders.sort_by_key(|(id, load)| (Reverse(load.leaders), **id));
+
+        for (from, from_load) in by_leaders {
+            let candidate = self
+                .replicas
+                .iter()
+                .filter(|(_, replica)| {
+                    replica.leader_node_id == *from
+                        && replica
+                            .vnodes
+                            .iter()
+                            .all(|v| v.status == VnodeStatus::Running)
+                })
+                .flat_map(|(tenant, replica)| {
+                    replica.vnodes.iter().filter_map(move |vnode| {
+                        let load = self.nodes.get(&vnode.node_id)?;
+                        (load.leaders + 1 < from_load.leaders).then_some((
+                            load.leaders,
+                            vnode.node_id,
+                            tenant,
+                            replica.id,
+                        ))
+                    })
+                })
+                .min();
+
+            if let Some((_, to, tenant, replica_id)) = candidate {
+                return Some(LeaderTransfer {
+                    tenant: tenant.clone(),
+                    replica_id,
+                    from: *from,
+                    to,
+                });
+            }
+        }
+
+        None
+    }
+
+    fn apply_leader_transfer(&mut self, transfer: &LeaderTransfer) {
+        if let Some(load) = self.nodes.get_mut(&transfer.from) {
+            load.leaders = load.leaders.saturating_sub(1);
+        }
+        if let Some(load) = self.nodes.get_mut(&transfer.to) {
+            load.leaders += 1;
+        }
+        for (tenant, replica) in self.replicas.iter_mut() {
+            if *tenant == transfer.tenant && replica.id == transfer.replica_id {
+                if let Some(vnode) = replica.by_node_id(transfer.to) {
+                    replica.leader_node_id = vnode.node_id;
+                    replica.leader_vnode_id = vnode.id;
+                }
+            }
+        }
+    }
+
+    fn apply(&mut self, mv: &VnodeMove) {
+        if let Some(load) = self.nodes.get_mut(&mv.from) {
+            load.vnodes = load.vnodes.saturating_sub(1);
+        }
+        if let Some(load) = self.nodes.get_mut(&mv.to) {
+            load.vnodes += 1;
+        }
+        for (tenant, replica) in self.replicas.iter_mut() {
+            if *tenant == mv.tenant && replica.id == mv.replica_id {
+                for vnode in replica.vnodes.iter_mut() {
+                    if vnode.id == mv.vnode_id {
+                        vnode.node_id = mv.to;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Adds a follower on the target node to the replication set, then removes the vnode.
+///
+/// If the vnode is the leader, the leadership is transferred to the new follower first,
+/// a leader can't remove itself from the raft group.
+pub async fn move_vnode(coord: &dyn Coordinator, mv: &VnodeMove) -> CoordinatorResult<()> {
+    let cmd_type = VnodeManagerCmdType::AddRaftFollower(mv.replica_id, mv.to);
+    coord.vnode_manager(&mv.tenant, cmd_type).await?;
+
+    let replica = get_replica_all_info(coord.meta_manager(), &mv.tenant, mv.replica_id)
+        .await?
+        .replica_set;
+    if replica.leader_vnode_id == mv.vnode_id {
+        let cmd_type = VnodeManagerCmdType::TransferLeader(mv.replica_id, mv.to);
+        coord.vnode_manager(&mv.tenant, cmd_type).await?;
+    }
+
+    let cmd_type = VnodeManagerCmdType::RemoveRaftNode(mv.vnode_id);
+    coord.vnode_manager(&mv.tenant, cmd_type).await?;
+
+    Ok(())
+}
+
+pub async fn change_replica(
+    coord: &dyn Coordinator,
+    change: &ReplicaChange,
+) -> CoordinatorResult<()> {
+    match change {
+        ReplicaChange::AddFollower {
+            tenant,
+            replica_id,
+            to,
+        } => {
+            let cmd_type = VnodeManagerCmdType::AddRaftFollower(*replica_id, *to);
+            coord.vnode_manager(tenant, cmd_type).await
+        }
+        ReplicaChange::RemoveVnode {
+            tenant, vnode_id, ..
+        } => {
+            let cmd_type = VnodeManagerCmdType::RemoveRaftNode(*vnode_id);
+            coord.vnode_manager(tenant, cmd_type).await
+        }
+    }
+}
+
+pub async fn transfer_leader(
+    coord: &dyn Coordinator,
+    transfer: &LeaderTransfer,
+) -> CoordinatorResult<()> {
+    let cmd_type = VnodeManagerCmdType::TransferLeader(transfer.replica_id, transfer.to);
+    coord.vnode_manager(&transfer.tenant, cmd_type).await
+}
+
+/// Only the node holding the lock of the resource tasks balances the cluster.
+async fn is_balancer_node(coord: &dyn Coordinator) -> CoordinatorResult<bool> {
+    let meta = coord.meta_manager();
+    if !meta.read_resourceinfos_mark().await?.1 {
+        meta.write_resourceinfos_mark(coord.node_id(), true).await?;
+    }
+    let (id, lock) = meta.read_resourceinfos_mark().await?;
+
+    Ok(id == coord.node_id() && lock)
+}
+
+pub async fn rebalance_service(coord: Arc<dyn Coordinator>) {
+    let config = coord.get_config().cluster;
+    let interval = config.rebalance_interval.max(Duration::from_secs(1));
+    let mut intv = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        intv.tick().await;
+        if let Err(err) = rebalance(coord.clone(), &config).await {
+            error!("failed to rebalance vnodes: {}", err);
+        }
+    }
+}
+
+async fn rebalance(coord: Arc<dyn Coordinator>, config: &ClusterConfig) -> CoordinatorResult<()> {
+    if !is_balancer_node(coord.as_ref()).await? {
+        return Ok(());
+    }
+
+    if is_changing_vnodes(coord.as_ref()).await? {
+        return Ok(());
+    }
+
+    let mut layout = ClusterLayout::load(coord.as_ref()).await?;
+    let moves = layout.plan_rebalance(config.rebalance_max_moves, config.rebalance_disk_free_gap);
+    for mv in moves {
+        info!(
+            "rebalance: move vnode {} of replication set {} from node {} to node {}",
+            mv.vnode_id, mv.replica_id, mv.from, mv.to
+        );
+        move_vnode(coord.as_ref(), &mv).await?;
+    }
+
+    Ok(())
+}
+
+/// Whether the vnodes are being moved by a decommissioning, or added and removed by the
+/// replica change of a database. The balancing is paused until the task is done, a failed
+/// task is retried by the resource manager and does not block it.
+async fn is_changing_vnodes(coord: &dyn Coordinator) -> CoordinatorResult<bool> {
+    let changing = coord
+        .meta_manager()
+        .read_resourceinfos()
+        .await?
+        .iter()
+        .any(|info| {
+            (info.decommissioned_node().is_some()
+                || matches!(info.get_operator(), ResourceOperator::ChangeReplica(..)))
+                && matches!(
+                    info.get_status(),
+                    ResourceStatus::Schedule | ResourceStatus::Executing
+                )
+        });
+
+    Ok(changing)
+}
+
+pub async fn leader_balance_service(coord: Arc<dyn Coordinator>) {
+    let config = coord.get_config().cluster;
+    let interval = config.leader_balance_interval.max(Duration::from_secs(1));
+    let mut intv = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        intv.tick().await;
+        if let Err(err) = balance_leaders(coord.clone(), &config).await {
+            error!("failed to balance leaders: {}", err);
+        }
+    }
+}
+
+async fn balance_leaders(
+    coord: Arc<dyn Coordinator>,
+    config: &ClusterConfig,
+) -> CoordinatorResult<()> {
+    if !is_balancer_node(coord.as_ref()).await? {
+        return Ok(());
+    }
+
+    // the leader of a replication set may be moved to a vnode which is being removed
+    if is_changing_vnodes(coord.as_ref()).await? {
+        return Ok(());
+    }
+
+    let mut layout = ClusterLayout::load(coord.as_ref()).await?;
+    let transfers = layout.plan_leader_balance(config.leader_balance_max_transfers);
+    for transfer in transfers {
+        info!(
+            "balance leaders: transfer leader of replication set {} from node {} to node {}",
+            transfer.replica_id, transfer.from, transfer.to
+        );
+        // the other transfers are independent of the failed one
+        if let Err(err) = transfer_leader(coord.as_ref(), &transfer).await {
+            error!(
+                "failed to transfer leader of replication set {}: {}",
+                transfer.replica_id, err
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use models::meta_data::{NodeMetrics, ReplicationSet, VnodeInfo};
+
+    use super::{ClusterLayout, LeaderTransfer, ReplicaChange, VnodeMove};
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn metrics(id: u64, disk_free: u64) -> NodeMetrics {
+        NodeMetrics {
+            id,
+            disk_free,
+            ..Default::default()
+        }
+    }
+
+    // replication set `id` with the leader on the first node
+    fn replica(id: u32, nodes: &[u64]) -> (String, ReplicationSet) {
+        let vnodes = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| VnodeInfo::new(id * 10 + i as u32, *n))
+            .collect::<Vec<_>>();
+        let leader = vnodes[0].clone();
+        (
+            "cnosdb".to_string(),
+            ReplicationSet::new(id, leader.node_id, leader.id, vnodes),
+        )
+    }
+
+    #[test]
+    fn test_plan_rebalance() {
+        let replicas = vec![
+            replica(1, &[1, 2]),
+            replica(2, &[2, 1]),
+            replica(3, &[1, 2]),
+            replica(4, &[2, 1]),
+        ];
+        // node 3 is newly added
+        let node_metrics = vec![
+            metrics(1, 10 * GB),
+            metrics(2, 20 * GB),
+            metrics(3, 100 * GB),
+        ];
+
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas.clone());
+        let moves = layout.plan_rebalance(10, GB);
+        assert_eq!(
+            moves,
+            vec![
+                // followers first
+                VnodeMove {
+                    tenant: "cnosdb".to_string(),
+                    replica_id: 2,
+                    vnode_id: 21,
+                    from: 1,
+                    to: 3
+                },
+                VnodeMove {
+                    tenant: "cnosdb".to_string(),
+                    replica_id: 4,
+                    vnode_id: 41,
+                    from: 1,
+                    to: 3
+                },
+                VnodeMove {
+                    tenant: "cnosdb".to_string(),
+                    replica_id: 1,
+                    vnode_id: 11,
+                    from: 2,
+                    to: 3
+                },
+            ]
+        );
+
+        // throttled
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas.clone());
+        assert_eq!(layout.plan_rebalance(1, GB).len(), 1);
+
+        // the disk usage is balanced enough
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas);
+        assert!(layout.plan_rebalance(10, 100 * GB).is_empty());
+    }
+
+    #[test]
+    fn test_plan_decommission() {
+        let replicas = vec![
+            replica(1, &[1, 2]),
+            replica(2, &[2, 3]),
+            replica(3, &[3, 1]),
+        ];
+        let node_metrics = vec![
+            metrics(1, 10 * GB),
+            metrics(2, 10 * GB),
+            metrics(3, 20 * GB),
+            metrics(4, 10 * GB),
+        ];
+        let excluded = HashSet::from([1]);
+
+        let mut layout = ClusterLayout::new(&node_metrics, &excluded, replicas.clone());
+        let moves = layout.plan_decommission(1).unwrap();
+        assert_eq!(
+            moves,
+            vec![
+                VnodeMove {
+                    tenant: "cnosdb".to_string(),
+                    replica_id: 1,
+                    vnode_id: 10,
+                    from: 1,
+                    to: 4
+                },
+                VnodeMove {
+                    tenant: "cnosdb".to_string(),
+                    replica_id: 3,
+                    vnode_id: 31,
+                    from: 1,
+                    to: 4
+                },
+            ]
+        );
+
+        // no node is available for the replication set 2
+        let node_metrics = vec![metrics(2, 10 * GB), metrics(3, 20 * GB)];
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas);
+        assert!(layout.plan_decommission(2).is_err());
+    }
+
+    #[test]
+    fn test_plan_replica_change() {
+        let replicas = vec![
+            replica(1, &[1, 2]),
+            replica(2, &[2, 3]),
+            replica(3, &[3, 1]),
+        ];
+        let node_metrics = vec![
+            metrics(1, 10 * GB),
+            metrics(2, 10 * GB),
+            metrics(3, 10 * GB),
+            metrics(4, 20 * GB),
+        ];
+        let add = |replica_id, to| ReplicaChange::AddFollower {
+            tenant: "cnosdb".to_string(),
+            replica_id,
+            to,
+        };
+        let remove = |replica_id, vnode_id, from| ReplicaChange::RemoveVnode {
+            tenant: "cnosdb".to_string(),
+            replica_id,
+            vnode_id,
+            from,
+        };
+
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas.clone());
+        let changes = layout
+            .plan_replica_change("cnosdb", &HashSet::from([1, 2]), 3)
+            .unwrap();
+        assert_eq!(changes, vec![add(1, 4), add(2, 4)]);
+
+        // the leaders are kept
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas.clone());
+        let changes = layout
+            .plan_replica_change("cnosdb", &HashSet::from([1, 2, 3]), 1)
+            .unwrap();
+        assert_eq!(
+            changes,
+            vec![remove(1, 11, 2), remove(2, 21, 3), remove(3, 31, 1)]
+        );
+
+        // unchanged
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas.clone());
+        assert!(layout
+            .plan_replica_change("cnosdb", &HashSet::from([1, 2, 3]), 2)
+            .unwrap()
+            .is_empty());
+
+        // not enough nodes
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas);
+        assert!(layout
+            .plan_replica_change("cnosdb", &HashSet::from([1]), 5)
+            .is_err());
+    }
+
+    #[test]
+    fn test_plan_leader_balance() {
+        // all the leaders are on node 1
+        let replicas = (1..=6)
+            .map(|id| replica(id, &[1, 2, 3]))
+            .collect::<Vec<_>>();
+        let node_metrics = vec![
+            metrics(1, 10 * GB),
+            metrics(2, 10 * GB),
+            metrics(3, 10 * GB),
+        ];
+        let transfer = |replica_id, to| LeaderTransfer {
+            tenant: "cnosdb".to_string(),
+            replica_id,
+            from: 1,
+            to,
+        };
+
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas.clone());
+        let transfers = layout.plan_leader_balance(10);
+        assert_eq!(
+            transfers,
+            vec![
+                transfer(1, 2),
+                transfer(2, 3),
+                transfer(3, 2),
+                transfer(4, 3)
+            ]
+        );
+        // balanced
+        assert!(layout.plan_leader_balance(10).is_empty());
+
+        // throttled
+        let mut layout = ClusterLayout::new(&node_metrics, &HashSet::new(), replicas);
+        assert_eq!(layout.plan_leader_balance(1), vec![transfer(1, 2)]);
+    }
+}
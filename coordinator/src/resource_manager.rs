@@ -1,7 +1,8 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
-use models::meta_data::ReplicationSet;
+use models::meta_data::{NodeId, ReplicationSet};
 use models::schema::{ResourceInfo, ResourceOperator, ResourceStatus, TableSchema};
 use protos::kv_service::{
     raft_write_command, DropColumnRequest, DropTableRequest, RaftWriteCommand, UpdateSetValue,
@@ -11,6 +12,7 @@ use tokio::time::sleep;
 use tracing::{debug, error, info};
 
 use crate::errors::*;
+use crate::rebalance::{change_replica, move_vnode, ClusterLayout};
 use crate::{Coordinator, VnodeManagerCmdType};
 
 #[derive(Clone)]
@@ -54,6 +56,13 @@ impl ResourceManager {
                 )
                 .await
             }
+            ResourceOperator::DecommissionNode(node_id) => {
+                ResourceManager::decommission_node(coord.clone(), &resourceinfo, *node_id).await
+            }
+            ResourceOperator::ChangeReplica(tenant_name, db_name) => {
+                ResourceManager::change_replica(coord.clone(), &resourceinfo, tenant_name, db_name)
+                    .await
+            }
         };
         resourceinfo.set_is_new_add(false);
         let mut status_comment = (ResourceStatus::Successed, String::default());
@@ -164,6 +173,7 @@ impl ResourceManager {
                     replica_id: replica.id,
                     tenant: tenant_name.to_string(),
                     db_name: db_name.to_string(),
+                    idempotency_key: String::new(),
                     command: Some(raft_write_command::Command::DropTable(request)),
                 };
 
@@ -226,6 +236,7 @@ impl ResourceManager {
                             replica_id: replica.id,
                             tenant: tenant_name.to_string(),
                             db_name: table_schema.db.to_string(),
+                            idempotency_key: String::new(),
                             command: Some(raft_write_command::Command::DropColumn(request)),
                         };
 
@@ -286,6 +297,7 @@ impl ResourceManager {
                 replica_id: replica.id,
                 tenant: tenant_name.to_string(),
                 db_name: db_name.to_string(),
+                idempotency_key: String::new(),
                 command: Some(raft_write_command::Command::UpdateTags(
                     update_tags_request.clone(),
                 )),
@@ -302,10 +314,135 @@ impl ResourceManager {
         Ok(true)
     }
 
+    async fn decommission_node(
+        coord: Arc<dyn Coordinator>,
+        resourceinfo: &ResourceInfo,
+        node_id: NodeId,
+    ) -> CoordinatorResult<bool> {
+        // plan with the latest layout, the vnodes moved before a retry are skipped
+        let mut layout = ClusterLayout::load(coord.as_ref()).await?;
+        let moves = layout.plan_decommission(node_id)?;
+
+        let total = moves.len();
+        for (i, mv) in moves.iter().enumerate() {
+            // report the progress in resource status
+            let mut progress = resourceinfo.clone();
+            progress.set_is_new_add(false);
+            progress.set_comment(&format!(
+                "{}/{} vnodes moved, moving vnode {} to node {}",
+                i, total, mv.vnode_id, mv.to
+            ));
+            coord
+                .meta_manager()
+                .write_resourceinfo(progress.get_name(), progress)
+                .await?;
+
+            move_vnode(coord.as_ref(), mv).await?;
+        }
+
+        info!("Decommission node {}, {} vnodes moved", node_id, total);
+        Ok(true)
+    }
+
+    async fn change_replica(
+        coord: Arc<dyn Coordinator>,
+        resourceinfo: &ResourceInfo,
+        tenant_name: &str,
+        db_name: &str,
+    ) -> CoordinatorResult<bool> {
+        let tenant =
+            coord
+                .tenant_meta(tenant_name)
+                .await
+                .ok_or(CoordinatorError::TenantNotFound {
+                    name: tenant_name.to_string(),
+                })?;
+        let db_info = tenant
+            .get_db_info(db_name)?
+            .ok_or(CoordinatorError::CommonError {
+                msg: format!("database not found: {}", db_name),
+            })?;
+
+        // the latest replica count of the database is the target, so a retry or
+        // a later ALTER DATABASE continues from where the previous one stopped
+        let replica = db_info.schema.config.replica_or_default() as usize;
+        let replica_ids = db_info
+            .buckets
+            .iter()
+            .flat_map(|b| b.shard_group.iter().map(|r| r.id))
+            .collect::<HashSet<_>>();
+
+        let mut layout = ClusterLayout::load(coord.as_ref()).await?;
+        let changes = layout.plan_replica_change(tenant_name, &replica_ids, replica)?;
+
+        let total = changes.len();
+        for (i, change) in changes.iter().enumerate() {
+            let mut progress = resourceinfo.clone();
+            progress.set_is_new_add(false);
+            progress.set_comment(&format!(
+                "{}/{} replica changes done, {:?}",
+                i, total, change
+            ));
+            coord
+                .meta_manager()
+                .write_resourceinfo(progress.get_name(), progress)
+                .await?;
+
+            change_replica(coord.as_ref(), change).await?;
+        }
+
+        info!(
+            "Change replica of database {}.{} to {}, {} vnodes changed",
+            tenant_name, db_name, replica, total
+        );
+        Ok(true)
+    }
+
     pub async fn add_resource_task(
         coord: Arc<dyn Coordinator>,
-        mut resourceinfo: ResourceInfo,
+        resourceinfo: ResourceInfo,
     ) -> CoordinatorResult<bool> {
+        match ResourceManager::write_resource_task(coord.as_ref(), resourceinfo).await? {
+            Some(resourceinfo) => {
+                if *resourceinfo.get_status() == ResourceStatus::Executing {
+                    // execute right now, if failed, retry later
+                    ResourceManager::do_operator(coord.clone(), resourceinfo).await?;
+                }
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Like [`ResourceManager::add_resource_task`], but the task is executed in background
+    /// once it is written to meta, for the tasks which may take a long time.
+    /// The progress of the task can be seen in information_schema.resource_status.
+    pub async fn submit_resource_task(
+        coord: Arc<dyn Coordinator>,
+        resourceinfo: ResourceInfo,
+    ) -> CoordinatorResult<bool> {
+        match ResourceManager::write_resource_task(coord.as_ref(), resourceinfo).await? {
+            Some(resourceinfo) => {
+                if *resourceinfo.get_status() == ResourceStatus::Executing {
+                    // if failed, the task is marked as failed in meta and retried later
+                    tokio::spawn(async move {
+                        let name = resourceinfo.get_name().to_string();
+                        if let Err(err) = ResourceManager::do_operator(coord, resourceinfo).await {
+                            error!("resource task {} failed: {}", name, err);
+                        }
+                    });
+                }
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Writes the task to meta, None if the same task is already running
+    async fn write_resource_task(
+        coord: &dyn Coordinator,
+        mut resourceinfo: ResourceInfo,
+    ) -> CoordinatorResult<Option<ResourceInfo>> {
         let opt = coord
             .meta_manager()
             .read_resourceinfo_by_name(resourceinfo.get_name())
@@ -333,14 +470,9 @@ impl ResourceManager {
                 .meta_manager()
                 .write_resourceinfo(resourceinfo.get_name(), resourceinfo.clone())
                 .await?;
-
-            if *resourceinfo.get_status() == ResourceStatus::Executing {
-                // execute right now, if failed, retry later
-                ResourceManager::do_operator(coord.clone(), resourceinfo.clone()).await?;
-            }
-            Ok(true)
+            Ok(Some(resourceinfo))
         } else {
-            Ok(false)
+            Ok(None)
         }
     }
 
@@ -27,18 +27,21 @@ use memory_pool::MemoryPoolRef;
 use meta::error::MetaError;
 use meta::model::{MetaClientRef, MetaRef};
 use metrics::count::U64Counter;
+use metrics::gauge::U64Gauge;
 use metrics::label::Labels;
 use metrics::metric::Metric;
 use metrics::metric_register::MetricsRegister;
+use models::consistency_level::ReadConsistency;
 use models::meta_data::{
-    ExpiredBucketInfo, MetaModifyType, ReplicationSet, ReplicationSetId, VnodeInfo, VnodeStatus,
+    BucketInfo, ExpiredBucketInfo, MetaModifyType, ReplicationSet, ReplicationSetId, VnodeInfo,
+    VnodeStatus,
 };
 use models::object_reference::ResolvedTable;
 use models::oid::Identifier;
 use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef, TimeRange, TimeRanges};
 use models::schema::{
-    timestamp_convert, ColumnType, Precision, ResourceInfo, ResourceOperator, ResourceStatus,
-    TskvTableSchema, TskvTableSchemaRef, DEFAULT_CATALOG, TIME_FIELD,
+    timestamp_convert, ColumnType, LateArrivalPolicy, Precision, ResourceInfo, ResourceOperator,
+    ResourceStatus, TskvTableSchema, TskvTableSchemaRef, DEFAULT_CATALOG, TIME_FIELD,
 };
 use models::utils::now_timestamp_nanos;
 use models::{record_batch_decode, ColumnId, SeriesKey, Tag};
@@ -58,6 +61,7 @@ use tokio_retry::Retry;
 use tonic::transport::Channel;
 use tower::timeout::Timeout;
 use trace::{debug, error, info, SpanContext, SpanExt, SpanRecorder};
+use tskv::stats::StorageStatsKind;
 use tskv::{EngineRef, Error};
 use utils::BkdrHasher;
 
@@ -65,9 +69,11 @@ use crate::errors::*;
 use crate::metrics::LPReporter;
 use crate::raft::manager::RaftNodesManager;
 use crate::raft::writer::RaftWriter;
+use crate::reader::replica_selection::{select_leader, FollowerSelector, SelectFuture};
 use crate::reader::table_scan::opener::TemporaryTableScanOpener;
 use crate::reader::tag_scan::opener::TemporaryTagScanOpener;
 use crate::reader::{CheckFuture, CheckedCoordinatorRecordBatchStream};
+use crate::rebalance::{leader_balance_service, rebalance_service};
 use crate::resource_manager::ResourceManager;
 use crate::{
     get_replica_all_info, get_vnode_all_info, status_response_to_result, Coordinator, QueryOption,
@@ -79,6 +85,9 @@ pub type CoordinatorRef = Arc<dyn Coordinator>;
 use models::schema::USAGE_SCHEMA;
 use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 
+/// Timeout of listing or killing the queries, or fetching the storage statistics on another node
+const CLUSTER_QUERIES_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Clone)]
 pub struct CoordService {
     node_id: u64,
@@ -103,6 +112,12 @@ pub struct CoordServiceMetrics {
     sql_data_in: Metric<U64Counter>,
     sql_write_row: Metric<U64Counter>,
     sql_points_data_in: Metric<U64Counter>,
+
+    query_spill_count: Metric<U64Counter>,
+    query_spilled_bytes: Metric<U64Counter>,
+    query_spill_disk_usage: Metric<U64Gauge>,
+
+    late_points: Metric<U64Counter>,
 }
 
 macro_rules! generate_coord_metrics_gets {
@@ -133,6 +148,24 @@ impl CoordServiceMetrics {
         let sql_write_row = register.metric("sql_write_row", "sql write row");
         let sql_points_data_in = register.metric("sql_points_data_in", "sql points data in");
 
+        let query_spill_count = register.metric(
+            "query_spill_count",
+            "times the operators of the queries spilled to disk",
+        );
+        let query_spilled_bytes = register.metric(
+            "query_spilled_bytes",
+            "bytes spilled to disk by the queries",
+        );
+        let query_spill_disk_usage = register.metric(
+            "query_spill_disk_usage",
+            "disk space of the spill files of the running queries",
+        );
+
+        let late_points = register.metric(
+            "late_points",
+            "points older than the late arrival window of the tables",
+        );
+
         Self {
             coord_data_in,
             coord_data_out,
@@ -142,9 +175,42 @@ impl CoordServiceMetrics {
             sql_data_in,
             sql_write_row,
             sql_points_data_in,
+
+            query_spill_count,
+            query_spilled_bytes,
+            query_spill_disk_usage,
+
+            late_points,
         }
     }
 
+    pub fn query_spill_count(&self, tenant: &str) -> U64Counter {
+        self.query_spill_count.recorder([("tenant", tenant)])
+    }
+
+    pub fn query_spilled_bytes(&self, tenant: &str) -> U64Counter {
+        self.query_spilled_bytes.recorder([("tenant", tenant)])
+    }
+
+    pub fn query_spill_disk_usage(&self) -> U64Gauge {
+        self.query_spill_disk_usage.recorder(Labels::default())
+    }
+
+    pub fn late_points(
+        &self,
+        tenant: &str,
+        db: &str,
+        table: &str,
+        policy: LateArrivalPolicy,
+    ) -> U64Counter {
+        self.late_points.recorder([
+            ("tenant", tenant),
+            ("database", db),
+            ("table", table),
+            ("policy", policy.to_string().as_str()),
+        ])
+    }
+
     pub fn tenant_db_labels<'a>(tenant: &'a str, db: &'a str) -> impl Into<Labels> + 'a {
         [("tenant", tenant), ("database", db)]
     }
@@ -205,6 +271,14 @@ impl CoordService {
             ));
         }
 
+        if config.cluster.auto_rebalance {
+            tokio::spawn(rebalance_service(coord.clone()));
+        }
+
+        if config.cluster.auto_leader_balance {
+            tokio::spawn(leader_balance_service(coord.clone()));
+        }
+
         coord
     }
 
@@ -438,6 +512,7 @@ impl CoordService {
                         Precision::NS,
                         lines.iter().map(|l| l.to_line()).collect::<Vec<_>>(),
                         None,
+                        None,
                     )
                     .await
                 {
@@ -470,23 +545,71 @@ impl CoordService {
         Ok(())
     }
 
-    async fn prune_shards(
+    /// The buckets of the database overlapping the time ranges
+    async fn mapping_buckets(
         &self,
         tenant: &str,
         database: &str,
         time_ranges: &TimeRanges,
-    ) -> Result<Vec<ReplicationSet>, CoordinatorError> {
+    ) -> Result<(MetaClientRef, Vec<BucketInfo>), CoordinatorError> {
         let meta = self.meta_manager().tenant_meta(tenant).await.ok_or(
             CoordinatorError::TenantNotFound {
                 name: tenant.to_string(),
             },
         )?;
         let buckets = meta.mapping_bucket(database, time_ranges.min_ts(), time_ranges.max_ts())?;
+
+        Ok((meta, buckets))
+    }
+
+    async fn prune_shards(
+        &self,
+        tenant: &str,
+        database: &str,
+        time_ranges: &TimeRanges,
+    ) -> Result<Vec<ReplicationSet>, CoordinatorError> {
+        let (_, buckets) = self.mapping_buckets(tenant, database, time_ranges).await?;
         let shards = buckets.into_iter().flat_map(|b| b.shard_group).collect();
 
         Ok(shards)
     }
 
+    /// Prunes the shards like `prune_shards`, if the table is partitioned by tags and the
+    /// predicate has equality predicates on all of them, only the vnodes of the matched
+    /// partitions are kept in each bucket.
+    async fn prune_partitions(
+        &self,
+        table: &ResolvedTable,
+        predicate: &ResolvedPredicate,
+    ) -> Result<Vec<ReplicationSet>, CoordinatorError> {
+        let (meta, buckets) = self
+            .mapping_buckets(table.tenant(), table.database(), &predicate.time_ranges())
+            .await?;
+        let hash_ids = meta
+            .get_tskv_table_schema(table.database(), table.table())?
+            .and_then(|schema| schema.partition_hash_ids(predicate.tags_filter()));
+
+        let Some(hash_ids) = hash_ids else {
+            return Ok(buckets.into_iter().flat_map(|b| b.shard_group).collect());
+        };
+        let mut shards = vec![];
+        for bucket in buckets {
+            for hash_id in hash_ids.iter() {
+                let shard = bucket.vnode_for(*hash_id);
+                if !shards.contains(&shard) {
+                    shards.push(shard);
+                }
+            }
+        }
+        debug!(
+            "Prune table {} to {} shards by partition tags",
+            table,
+            shards.len()
+        );
+
+        Ok(shards)
+    }
+
     fn build_query_checker(&self, tenant: &str) -> CheckFuture {
         let tenant = tenant.to_string();
         let meta = self.meta.clone();
@@ -502,6 +625,24 @@ impl CoordService {
         Box::pin(checker)
     }
 
+    /// Chooses the vnodes of the replica set of the split to read from, by the read
+    /// consistency of the split.
+    fn build_replica_selector(&self, option: &QueryOption) -> SelectFuture {
+        let tenant = option.table_schema.tenant.clone();
+        let replica_id = option.split.replica_id();
+        match option.split.read_consistency() {
+            ReadConsistency::Leader => select_leader(self.meta.clone(), tenant, replica_id),
+            ReadConsistency::Follower { max_lag } => FollowerSelector::new(
+                self.meta.clone(),
+                self.raft_manager.clone(),
+                tenant,
+                max_lag,
+                self.config.service.grpc_enable_gzip,
+            )
+            .select(replica_id),
+        }
+    }
+
     async fn exec_admin_fetch_command_on_node(
         &self,
         node_id: u64,
@@ -534,6 +675,8 @@ impl CoordService {
         precision: Precision,
         info: ReplicationSet,
         points: Arc<Vec<u8>>,
+        write_time: i64,
+        idempotency_key: Option<&'a str>,
         span_ctx: Option<&'a SpanContext>,
     ) -> CoordinatorResult<Vec<impl Future<Output = CoordinatorResult<()>> + Sized + 'a>> {
         {
@@ -561,12 +704,13 @@ impl CoordService {
         let request = WriteDataRequest {
             precision: precision as u32,
             data: Arc::unwrap_or_clone(points),
+            write_time,
         };
         let request = RaftWriteCommand {
             replica_id: info.id,
             db_name: db.to_string(),
             tenant: tenant.to_string(),
-
+            idempotency_key: idempotency_key.unwrap_or_default().to_string(),
             command: Some(raft_write_command::Command::WriteData(request)),
         };
 
@@ -575,6 +719,33 @@ impl CoordService {
 
         Ok(requests)
     }
+
+    /// Count the late points of the tables in metrics, returns error if a table
+    /// with `LateArrivalPolicy::Reject` has late points.
+    fn report_late_points(
+        &self,
+        tenant: &str,
+        db: &str,
+        late_points: impl IntoIterator<Item = (String, LatePoints)>,
+    ) -> CoordinatorResult<()> {
+        let mut result = Ok(());
+        for (table, late) in late_points {
+            if late.points == 0 {
+                continue;
+            }
+            self.metrics
+                .late_points(tenant, db, &table, late.policy)
+                .inc(late.points);
+            if late.policy == LateArrivalPolicy::Reject {
+                result = Err(CoordinatorError::LatePointsRejected {
+                    table,
+                    points: late.points,
+                    bound: late.bound,
+                });
+            }
+        }
+        result
+    }
 }
 
 //***************************** Coordinator Interface ***************************************** */
@@ -606,13 +777,7 @@ impl Coordinator for CoordService {
         predicate: ResolvedPredicateRef,
     ) -> CoordinatorResult<Vec<ReplicationSet>> {
         // 1. 根据传入的过滤条件获取表的分片信息（包括副本）
-        let mut replica_sets = self
-            .prune_shards(
-                table.tenant(),
-                table.database(),
-                predicate.time_ranges().as_ref(),
-            )
-            .await?;
+        let mut replica_sets = self.prune_partitions(table, predicate.as_ref()).await?;
 
         // 2. 选择最优的副本
         for replica_set in replica_sets.iter_mut() {
@@ -697,6 +862,7 @@ impl Coordinator for CoordService {
         db: &str,
         precision: Precision,
         lines: Vec<Line<'a>>,
+        idempotency_key: Option<&str>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         let mut write_bytes: usize = 0;
@@ -723,18 +889,49 @@ impl Coordinator for CoordService {
         }
 
         let db_precision = db_schema.config.precision_or_default();
+        let mut table_schemas: HashMap<String, Option<TskvTableSchemaRef>> = HashMap::new();
+        let mut late_points: HashMap<String, LatePoints> = HashMap::new();
+        // The write time is taken once for the request and replicated in the raft write
+        // commands, so the replicas and the wal replay tell the late points the same way,
+        // and the retry of an applied write is skipped with the write time of the write.
+        let write_time = now_timestamp_nanos();
         for line in lines {
             let ts = timestamp_convert(precision, *db_precision, line.timestamp).ok_or(
                 CoordinatorError::CommonError {
                     msg: "timestamp overflow".to_string(),
                 },
             )?;
+            if !table_schemas.contains_key(line.table.as_ref()) {
+                let schema = meta_client.get_tskv_table_schema(db, &line.table)?;
+                if let Some(late) = schema
+                    .as_deref()
+                    .and_then(|schema| LatePoints::new(schema, write_time))
+                {
+                    late_points.insert(line.table.to_string(), late);
+                }
+                table_schemas.insert(line.table.to_string(), schema);
+            }
+            if let Some(late) = late_points.get_mut(line.table.as_ref()) {
+                late.check(ts);
+            }
+            let hash_id = table_schemas[line.table.as_ref()]
+                .as_ref()
+                .and_then(|schema| {
+                    schema.partition_hash_id(|tag| {
+                        line.tags
+                            .iter()
+                            .find(|(k, _)| k == tag)
+                            .map(|(_, v)| v.as_ref())
+                    })
+                })
+                .unwrap_or(line.hash_id);
             let info = meta_client
-                .locate_replication_set_for_write(db, line.hash_id, ts)
+                .locate_replication_set_for_write(db, hash_id, ts)
                 .await?;
             let lines_entry = map_lines.entry(info.id).or_insert(VnodeLines::new(info));
             lines_entry.add_line(line)
         }
+        self.report_late_points(tenant, db, late_points)?;
 
         let mut requests = Vec::new();
         for lines in map_lines.into_values() {
@@ -745,8 +942,17 @@ impl Coordinator for CoordService {
             let points = Arc::new(mutable_batches_to_point(db, batches));
             write_bytes += points.len();
             requests.extend(
-                self.push_points_to_requests(tenant, db, precision, lines.info, points, span_ctx)
-                    .await?,
+                self.push_points_to_requests(
+                    tenant,
+                    db,
+                    precision,
+                    lines.info,
+                    points,
+                    write_time,
+                    idempotency_key,
+                    span_ctx,
+                )
+                .await?,
             );
         }
         let now = tokio::time::Instant::now();
@@ -767,6 +973,8 @@ impl Coordinator for CoordService {
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        write_time: i64,
+        idempotency_key: Option<&str>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         let mut write_bytes: usize = 0;
@@ -784,6 +992,7 @@ impl Coordinator for CoordService {
         let mut repl_idx: HashMap<ReplicationSet, Vec<u32>> = HashMap::new();
         let schema = record_batch.schema().fields.clone();
         let table_name = table_schema.name.as_str();
+        let mut late_points = LatePoints::new(&table_schema, write_time);
         let columns = record_batch.columns();
         for idx in 0..record_batch.num_rows() {
             let mut hasher = BkdrHasher::new();
@@ -841,12 +1050,27 @@ impl Coordinator for CoordService {
                 });
             }
 
-            let hash = hasher.number();
+            if let Some(late) = late_points.as_mut() {
+                late.check(ts);
+            }
+
+            let hash = table_schema
+                .partition_hash_id(|tag| {
+                    let column_idx = record_batch.schema().index_of(tag).ok()?;
+                    let column = columns[column_idx].as_any().downcast_ref::<StringArray>()?;
+                    column.is_valid(idx).then(|| column.value(idx))
+                })
+                .unwrap_or_else(|| hasher.number());
             let info = meta_client
                 .locate_replication_set_for_write(db, hash, ts)
                 .await?;
             repl_idx.entry(info).or_default().push(idx as u32);
         }
+        self.report_late_points(
+            tenant,
+            db,
+            late_points.map(|late| (table_name.to_string(), late)),
+        )?;
 
         let mut requests = Vec::new();
         for (repl, idxs) in repl_idx {
@@ -869,8 +1093,17 @@ impl Coordinator for CoordService {
             );
             write_bytes += points.len();
             requests.extend(
-                self.push_points_to_requests(tenant, db, precision, repl, points, span_ctx)
-                    .await?,
+                self.push_points_to_requests(
+                    tenant,
+                    db,
+                    precision,
+                    repl,
+                    points,
+                    write_time,
+                    idempotency_key,
+                    span_ctx,
+                )
+                .await?,
             );
         }
         let now = tokio::time::Instant::now();
@@ -892,6 +1125,7 @@ impl Coordinator for CoordService {
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<SendableCoordinatorRecordBatchStream> {
         let checker = self.build_query_checker(&option.table_schema.tenant);
+        let selector = self.build_replica_selector(&option);
 
         let opener = TemporaryTableScanOpener::new(
             self.config.query.clone(),
@@ -907,6 +1141,7 @@ impl Coordinator for CoordService {
             opener,
             self.meta.clone(),
             Box::pin(checker),
+            Some(selector),
             &self.metrics,
         )))
     }
@@ -917,6 +1152,7 @@ impl Coordinator for CoordService {
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<SendableCoordinatorRecordBatchStream> {
         let checker = self.build_query_checker(&option.table_schema.tenant);
+        let selector = self.build_replica_selector(&option);
 
         let opener = TemporaryTagScanOpener::new(
             self.config.query.clone(),
@@ -931,6 +1167,7 @@ impl Coordinator for CoordService {
             opener,
             self.meta.clone(),
             Box::pin(checker),
+            Some(selector),
             &self.metrics,
         )))
     }
@@ -965,6 +1202,7 @@ impl Coordinator for CoordService {
                 replica_id: replica.id,
                 tenant: table.tenant().to_string(),
                 db_name: table.database().to_string(),
+                idempotency_key: String::new(),
                 command: Some(raft_write_command::Command::DeleteFromTable(request)),
             };
 
@@ -1042,6 +1280,68 @@ impl Coordinator for CoordService {
                 )
             }
 
+            VnodeManagerCmdType::TransferLeader(replica_id, node_id) => {
+                let all_info = get_replica_all_info(self.meta.clone(), tenant, replica_id).await?;
+                let replica = all_info.replica_set;
+                let vnode = replica
+                    .by_node_id(node_id)
+                    .ok_or(CoordinatorError::CommonError {
+                        msg: format!("Replication set {} not in node {}", replica_id, node_id),
+                    })?;
+                if replica.leader_vnode_id == vnode.id {
+                    return Ok(());
+                }
+
+                (
+                    AdminCommandRequest {
+                        tenant: tenant.to_string(),
+                        command: Some(TransferLeader(TransferLeaderRequest {
+                            replica_id,
+                            vnode_id: vnode.id,
+                            db_name: all_info.db_name,
+                        })),
+                    },
+                    replica.leader_node_id,
+                )
+            }
+
+            VnodeManagerCmdType::Export(vnode_id, path) => {
+                let all_info = get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
+
+                (
+                    AdminCommandRequest {
+                        tenant: tenant.to_string(),
+                        command: Some(ExportVnode(ExportVnodeRequest {
+                            db_name: all_info.db_name,
+                            vnode_id,
+                            path,
+                        })),
+                    },
+                    all_info.node_id,
+                )
+            }
+
+            VnodeManagerCmdType::Import(vnode_id, path) => {
+                // The import runs on the leader of the replication set, which builds
+                // the files and writes them to the raft group, so that all the replicas
+                // add the same files.
+                let vnode = get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
+                let all_info =
+                    get_replica_all_info(self.meta.clone(), tenant, vnode.repl_set_id).await?;
+
+                (
+                    AdminCommandRequest {
+                        tenant: tenant.to_string(),
+                        command: Some(ImportVnode(ImportVnodeRequest {
+                            db_name: all_info.db_name,
+                            vnode_id,
+                            path,
+                        })),
+                    },
+                    all_info.replica_set.leader_node_id,
+                )
+            }
+
             VnodeManagerCmdType::Compact(vnode_ids) => {
                 // Group vnode ids by node id.
                 let mut node_vnode_ids_map: HashMap<u64, Vec<u32>> = HashMap::new();
@@ -1123,6 +1423,117 @@ impl Coordinator for CoordService {
         }
     }
 
+    async fn cluster_queries(&self) -> CoordinatorResult<Vec<RecordBatch>> {
+        let mut nodes = self.meta.data_nodes().await;
+        nodes.extend(self.meta.query_nodes().await);
+        let requests = nodes
+            .iter()
+            .filter(|node| node.id != self.node_id)
+            .map(|node| async move {
+                let cmd = AdminFetchCommandRequest {
+                    tenant: String::new(),
+                    command: Some(admin_fetch_command_request::Command::FetchQueries(
+                        FetchQueriesRequest {},
+                    )),
+                };
+                let result = tokio::time::timeout(
+                    CLUSTER_QUERIES_TIMEOUT,
+                    self.exec_admin_fetch_command_on_node(node.id, cmd),
+                )
+                .await
+                .map_err(|_| CoordinatorError::CommonError {
+                    msg: format!("fetch queries from node {} timeout", node.id),
+                })
+                .and_then(|r| r);
+                (node.id, result)
+            });
+
+        // An unreachable node should not fail the listing of the others
+        let mut batches = vec![];
+        for (node_id, result) in futures::future::join_all(requests).await {
+            match result {
+                Ok(batch) => batches.push(batch),
+                Err(err) => error!("fetch queries from node {} failed: {}", node_id, err),
+            }
+        }
+
+        Ok(batches)
+    }
+
+    async fn cluster_storage_stats(
+        &self,
+        kind: StorageStatsKind,
+    ) -> CoordinatorResult<Vec<RecordBatch>> {
+        let nodes = self.meta.data_nodes().await;
+        let requests = nodes.iter().map(|node| async move {
+            let result = match (&self.kv_inst, node.id == self.node_id) {
+                (Some(kv_inst), true) => kv_inst
+                    .get_storage_stats(kind)
+                    .await
+                    .map_err(CoordinatorError::from),
+                _ => {
+                    let cmd = AdminFetchCommandRequest {
+                        tenant: String::new(),
+                        command: Some(admin_fetch_command_request::Command::FetchStorageStats(
+                            FetchStorageStatsRequest {
+                                kind: kind.to_string(),
+                            },
+                        )),
+                    };
+                    tokio::time::timeout(
+                        CLUSTER_QUERIES_TIMEOUT,
+                        self.exec_admin_fetch_command_on_node(node.id, cmd),
+                    )
+                    .await
+                    .map_err(|_| CoordinatorError::CommonError {
+                        msg: format!("fetch storage statistics from node {} timeout", node.id),
+                    })
+                    .and_then(|r| r)
+                }
+            };
+            (node.id, result)
+        });
+
+        // An unreachable node should not fail the statistics of the others
+        let mut batches = vec![];
+        for (node_id, result) in futures::future::join_all(requests).await {
+            match result {
+                Ok(batch) => batches.push(batch),
+                Err(err) => error!(
+                    "fetch storage statistics {} from node {} failed: {}",
+                    kind, node_id, err
+                ),
+            }
+        }
+
+        Ok(batches)
+    }
+
+    async fn kill_cluster_query(&self, query_id: u64) -> CoordinatorResult<bool> {
+        let mut nodes = self.meta.data_nodes().await;
+        nodes.extend(self.meta.query_nodes().await);
+        let requests = nodes
+            .iter()
+            .filter(|node| node.id != self.node_id)
+            .map(|node| {
+                let req = AdminCommandRequest {
+                    tenant: String::new(),
+                    command: Some(KillQuery(KillQueryRequest { query_id })),
+                };
+                tokio::time::timeout(
+                    CLUSTER_QUERIES_TIMEOUT,
+                    self.exec_admin_command_on_node(node.id, req),
+                )
+            });
+
+        let killed = futures::future::join_all(requests)
+            .await
+            .into_iter()
+            .any(|result| matches!(result, Ok(Ok(()))));
+
+        Ok(killed)
+    }
+
     fn metrics(&self) -> &Arc<CoordServiceMetrics> {
         &self.metrics
     }
@@ -1228,6 +1639,7 @@ impl Coordinator for CoordService {
                 replica_id: replica.id,
                 tenant: tenant.to_string(),
                 db_name: db.to_string(),
+                idempotency_key: String::new(),
                 command: Some(raft_write_command::Command::UpdateTags(
                     update_tags_request.clone(),
                 )),
@@ -1269,6 +1681,31 @@ impl Coordinator for CoordService {
     }
 }
 
+/// The number of the late points of a table in a write.
+struct LatePoints {
+    policy: LateArrivalPolicy,
+    bound: i64,
+    points: u64,
+}
+
+impl LatePoints {
+    /// Returns None if the late arrival window of the table is not set, `write_time`
+    /// is the same one sent to the vnodes, so the points are classified in the same way.
+    fn new(schema: &TskvTableSchema, write_time: i64) -> Option<Self> {
+        schema.late_arrival_bound(write_time).map(|bound| Self {
+            policy: schema.options().late_arrival_policy,
+            bound,
+            points: 0,
+        })
+    }
+
+    fn check(&mut self, ts: i64) {
+        if ts < self.bound {
+            self.points += 1;
+        }
+    }
+}
+
 struct VnodeLines<'a> {
     pub lines: Vec<Line<'a>>,
     pub info: ReplicationSet,
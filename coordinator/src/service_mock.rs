@@ -9,6 +9,7 @@ use datafusion::arrow::record_batch::RecordBatch;
 use meta::model::meta_admin::AdminMeta;
 use meta::model::meta_tenant::TenantMeta;
 use meta::model::{MetaClientRef, MetaRef};
+use metrics::metric_register::MetricsRegister;
 use models::meta_data::{ReplicationSet, VnodeInfo, VnodeStatus};
 use models::object_reference::ResolvedTable;
 use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
@@ -18,6 +19,7 @@ use protos::kv_service::{AdminCommandRequest, RaftWriteCommand, UpdateSetValue};
 use trace::SpanContext;
 use tskv::engine_mock::MockEngine;
 use tskv::reader::QueryOption;
+use tskv::stats::StorageStatsKind;
 use tskv::EngineRef;
 
 use crate::errors::CoordinatorResult;
@@ -29,8 +31,18 @@ use crate::{
 
 pub const WITH_NONEMPTY_DATABASE_FOR_TEST: &str = "with_nonempty_database";
 
-#[derive(Debug, Default)]
-pub struct MockCoordinator {}
+#[derive(Debug)]
+pub struct MockCoordinator {
+    metrics: Arc<CoordServiceMetrics>,
+}
+
+impl Default for MockCoordinator {
+    fn default() -> Self {
+        Self {
+            metrics: Arc::new(CoordServiceMetrics::new(&MetricsRegister::default())),
+        }
+    }
+}
 
 #[async_trait::async_trait]
 impl Coordinator for MockCoordinator {
@@ -178,6 +190,7 @@ impl Coordinator for MockCoordinator {
         db: &str,
         precision: Precision,
         line: Vec<Line<'a>>,
+        idempotency_key: Option<&str>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         todo!()
@@ -188,6 +201,8 @@ impl Coordinator for MockCoordinator {
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        write_time: i64,
+        idempotency_key: Option<&str>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         todo!()
@@ -234,8 +249,23 @@ impl Coordinator for MockCoordinator {
         Ok(vec![])
     }
 
+    async fn cluster_queries(&self) -> CoordinatorResult<Vec<RecordBatch>> {
+        Ok(vec![])
+    }
+
+    async fn cluster_storage_stats(
+        &self,
+        kind: StorageStatsKind,
+    ) -> CoordinatorResult<Vec<RecordBatch>> {
+        Ok(vec![])
+    }
+
+    async fn kill_cluster_query(&self, query_id: u64) -> CoordinatorResult<bool> {
+        Ok(false)
+    }
+
     fn metrics(&self) -> &Arc<CoordServiceMetrics> {
-        todo!()
+        &self.metrics
     }
 
     async fn update_tags_value(
@@ -2,7 +2,7 @@
 
 use fly_accept_encoding::Encoding;
 use http_protocol::encoding::EncodingExt;
-use http_protocol::header::{HeaderValue, ACCEPT, CONTENT_TYPE};
+use http_protocol::header::{HeaderValue, ACCEPT, CONTENT_TYPE, IDEMPOTENCY_KEY};
 use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
 use reqwest::{Method, StatusCode};
 
@@ -178,6 +178,52 @@ fn test_v1_write_path() {
     assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
 }
 
+#[test]
+fn test_v1_write_idempotency_key() {
+    let client = Client::with_auth("root".to_string(), None);
+    let _ = client
+        .api_v1_sql(
+            "http://127.0.0.1:8902/api/v1/sql?db=public",
+            "CREATE DATABASE IF NOT EXISTS e2e_test_idempotency_key",
+        )
+        .unwrap();
+
+    let url = "http://127.0.0.1:8902/api/v1/write?db=e2e_test_idempotency_key";
+    let write = |body: &str| {
+        client
+            .request_with_auth(Method::POST, url)
+            .header(IDEMPOTENCY_KEY, "test_v1_write_idempotency_key")
+            .body(body.to_string())
+            .send()
+            .unwrap()
+    };
+    let select = || {
+        client
+            .api_v1_sql(
+                "http://127.0.0.1:8902/api/v1/sql?db=e2e_test_idempotency_key",
+                "SELECT time, ta, fa FROM test_idempotency_key ORDER BY time",
+            )
+            .unwrap()
+    };
+
+    let body = "test_idempotency_key,ta=a1 fa=1 1\ntest_idempotency_key,ta=a2 fa=2 2";
+    let resp = write(body);
+    assert_response_is_ok!(resp);
+    let rows = select();
+    assert_eq!(rows.len(), 3);
+
+    // The retry takes a new write time on the coordinator, and is skipped as applied.
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let resp = write(body);
+    assert_response_is_ok!(resp);
+    assert_eq!(select(), rows);
+
+    // Another write reusing the key is rejected.
+    let resp = write("test_idempotency_key,ta=a1 fa=3 1");
+    assert_ne!(resp.status(), StatusCode::OK);
+    assert_eq!(select(), rows);
+}
+
 #[test]
 fn test_v1_ping_path() {
     let url: &str = "http://127.0.0.1:8902/api/v1/ping";
@@ -22,8 +22,11 @@ use arrow_flight::{
 };
 use datafusion::arrow::datatypes::{Schema, SchemaRef, ToByteSlice};
 use futures::Stream;
-use http_protocol::header::{DB, STREAM_TRIGGER_INTERVAL, TARGET_PARTITIONS, TENANT};
+use http_protocol::header::{
+    DB, READ_CONSISTENCY, STREAM_TRIGGER_INTERVAL, TARGET_PARTITIONS, TENANT,
+};
 use models::auth::user::User;
+use models::consistency_level::ReadConsistency;
 use models::oid::UuidGenerator;
 use moka::sync::Cache;
 use prost::bytes::Bytes;
@@ -201,11 +204,18 @@ where
                         STREAM_TRIGGER_INTERVAL, e
                     ))
                 })?;
+        let read_consistency = utils::get_value_from_header(metadata, READ_CONSISTENCY, "")
+            .map(|e| e.parse::<ReadConsistency>())
+            .transpose()
+            .map_err(|e| {
+                Status::invalid_argument(format!("parse {} failed, error: {}", READ_CONSISTENCY, e))
+            })?;
         let ctx = ContextBuilder::new(user)
             .with_tenant(tenant)
             .with_database(db)
             .with_target_partitions(target_partitions)
             .with_stream_trigger_interval(stream_trigger_interval)
+            .with_read_consistency(read_consistency)
             .build();
 
         Ok(ctx)
@@ -7,6 +7,8 @@ pub enum HttpApiType {
     ApiV1OpenTsDBWrite,
     ApiV1OpenTsDBPut,
     ApiV1PromWrite,
+    ApiV1OtlpMetrics,
+    ApiV1OtlpLogs,
 
     ApiV1Sql,
     ApiV1PromRead,
@@ -27,6 +29,12 @@ impl Display for HttpApiType {
             HttpApiType::ApiV1PromWrite => {
                 write!(f, "api/v1/prom/write")
             }
+            HttpApiType::ApiV1OtlpMetrics => {
+                write!(f, "api/v1/otlp/v1/metrics")
+            }
+            HttpApiType::ApiV1OtlpLogs => {
+                write!(f, "api/v1/otlp/v1/logs")
+            }
             HttpApiType::ApiV1Sql => {
                 write!(f, "api/v1/sql")
             }
@@ -44,6 +52,8 @@ pub fn metrics_record_db(api: &HttpApiType) -> bool {
         | HttpApiType::ApiV1OpenTsDBPut
         | HttpApiType::ApiV1OpenTsDBWrite
         | HttpApiType::ApiV1PromWrite
+        | HttpApiType::ApiV1OtlpMetrics
+        | HttpApiType::ApiV1OtlpLogs
         | HttpApiType::ApiV1PromRead => true,
         HttpApiType::ApiV1Sql => false,
     }
@@ -12,6 +12,7 @@ pub struct Header {
     content_encoding: Option<String>,
     authorization: String,
     private_key: Option<String>,
+    idempotency_key: Option<String>,
 }
 
 impl Header {
@@ -27,6 +28,7 @@ impl Header {
             content_encoding,
             authorization,
             private_key: None,
+            idempotency_key: None,
         }
     }
 
@@ -43,9 +45,15 @@ impl Header {
             content_encoding,
             authorization,
             private_key,
+            idempotency_key: None,
         }
     }
 
+    pub fn with_idempotency_key(mut self, idempotency_key: Option<String>) -> Self {
+        self.idempotency_key = idempotency_key;
+        self
+    }
+
     pub fn get_accept(&self) -> &str {
         self.accept.as_deref().unwrap_or(APPLICATION_CSV)
     }
@@ -58,6 +66,10 @@ impl Header {
         self.content_encoding.as_deref()
     }
 
+    pub fn get_idempotency_key(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
+
     pub fn try_get_basic_auth(&self) -> Result<UserInfo, HttpError> {
         let private_key = self
             .private_key
@@ -1,6 +1,6 @@
 #![allow(clippy::too_many_arguments)]
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::Infallible;
 use std::fmt;
 use std::fmt::Display;
@@ -12,9 +12,13 @@ use config::TLSConfig;
 use coordinator::service::CoordinatorRef;
 use fly_accept_encoding::Encoding;
 use http_protocol::encoding::EncodingExt;
-use http_protocol::header::{ACCEPT, APPLICATION_JSON, AUTHORIZATION, PRIVATE_KEY};
+use http_protocol::header::{
+    ACCEPT, APPLICATION_JSON, APPLICATION_PROTOBUF, AUTHORIZATION, CONTENT_TYPE, IDEMPOTENCY_KEY,
+    PRIVATE_KEY,
+};
 use http_protocol::parameter::{DebugParam, DumpParam, SqlParam, WriteParam};
 use http_protocol::response::ErrorResponse;
+use http_protocol::status_code::OK;
 use meta::error::{MetaError, MetaResult};
 use meta::limiter::RequestLimiter;
 use meta::model::MetaRef;
@@ -23,15 +27,22 @@ use metrics::gather_metrics;
 use metrics::metric_register::MetricsRegister;
 use metrics::prom_reporter::PromReporter;
 use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
+use models::consistency_level::ReadConsistency;
 use models::error_code::UnknownCodeWithMessage;
 use models::oid::{Identifier, Oid};
-use models::schema::{Precision, DEFAULT_CATALOG, DEFAULT_DATABASE};
+use models::schema::{Precision, TableSchema, TskvTableSchema, DEFAULT_CATALOG, DEFAULT_DATABASE};
 use models::utils::now_timestamp_nanos;
 use protocol_parser::line_protocol::line_protocol_to_lines;
 use protocol_parser::open_tsdb::open_tsdb_to_lines;
+use protocol_parser::wide_table::{
+    WideTableSpec, DELIMITER_OPTION, FIELDS_OPTION, FORMAT_OPTION, TABLE_OPTION, TAGS_OPTION,
+    TIME_COLUMN_OPTION, TIME_FORMAT_OPTION,
+};
 use protocol_parser::{DataPoint, Line};
 use query::prom::remote_server::PromRemoteSqlServer;
 use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use snafu::ResultExt;
 use spi::query::config::StreamTriggerInterval;
 use spi::server::dbms::DBMSRef;
@@ -56,6 +67,9 @@ use crate::http::metrics::HttpMetrics;
 use crate::http::response::{HttpResponse, ResponseBuilder};
 use crate::http::result_format::{get_result_format_from_header, ResultFormat};
 use crate::http::{meta_err_to_reject, QuerySnafu};
+use crate::otlp::otlp_server::{
+    logs_to_lines, metrics_to_lines, OtlpSignal, OTLP_DATABASE_HEADER, OTLP_TENANT_HEADER,
+};
 use crate::server::ServiceHandle;
 use crate::spi::service::Service;
 use crate::{server, VERSION};
@@ -140,15 +154,22 @@ impl HttpService {
             .and(header::optional::<String>(CONTENT_ENCODING.as_str()))
             .and(header::<String>(AUTHORIZATION.as_str()))
             .and(header::optional::<String>(PRIVATE_KEY))
+            .and(header::optional::<String>(IDEMPOTENCY_KEY))
             .and_then(
-                |accept, accept_encoding, content_encoding, authorization, private_key| async move {
+                |accept,
+                 accept_encoding,
+                 content_encoding,
+                 authorization,
+                 private_key,
+                 idempotency_key| async move {
                     let res: Result<Header, warp::Rejection> = Ok(Header::with_private_key(
                         accept,
                         accept_encoding,
                         content_encoding,
                         authorization,
                         private_key,
-                    ));
+                    )
+                    .with_idempotency_key(idempotency_key));
                     res
                 },
             )
@@ -230,6 +251,7 @@ impl HttpService {
             .or(self.write_open_tsdb())
             .or(self.put_open_tsdb())
             .or(self.write_line_protocol())
+            .or(self.write_otlp())
     }
 
     fn routes_store(
@@ -412,6 +434,9 @@ impl HttpService {
                         })?;
                     }
 
+                    let wide_table = WideTableParam::try_from_write_param(&param);
+
+                    let idempotency_key = header.get_idempotency_key().map(String::from);
                     let ctx = {
                         let mut span_recorder =
                             SpanRecorder::new(span_context.child_span("construct write context"));
@@ -428,34 +453,165 @@ impl HttpService {
 
                     http_limiter_check_write(&coord.meta_manager(), ctx.tenant(), req_len).await?;
 
-                    let precision = Precision::new(ctx.precision()).unwrap_or(Precision::NS);
+                    let resp = match wide_table {
+                        Some(wide_table) => {
+                            coord_write_wide_table_with_span_recorder(
+                                &coord,
+                                ctx.tenant(),
+                                ctx.database(),
+                                wide_table,
+                                &req,
+                                idempotency_key.as_deref(),
+                                span_context,
+                            )
+                            .await
+                        }
+                        None => {
+                            let precision =
+                                Precision::new(ctx.precision()).unwrap_or(Precision::NS);
+
+                            let write_points_lines = {
+                                let mut span_recorder = SpanRecorder::new(
+                                    span_context.child_span("try parse req to lines"),
+                                );
+                                span_recorder.set_metadata("bytes", req.len());
+                                try_parse_req_to_lines(&req).map_err(reject::custom)?
+                            };
+
+                            coord_write_points_with_span_recorder(
+                                &coord,
+                                ctx.tenant(),
+                                ctx.database(),
+                                precision,
+                                write_points_lines,
+                                idempotency_key.as_deref(),
+                                span_context,
+                            )
+                            .await
+                        }
+                    };
+
+                    http_record_write_metrics(
+                        &metrics,
+                        &ctx,
+                        &addr,
+                        req_len,
+                        start,
+                        HttpApiType::ApiV1Write,
+                    );
+                    resp.map(|_| ResponseBuilder::ok()).map_err(reject::custom)
+                },
+            )
+    }
 
-                    let write_points_lines = {
+    fn write_otlp(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "v1" / "otlp" / "v1" / OtlpSignal)
+            .and(warp::post())
+            .and(warp::body::content_length_limit(self.write_body_limit))
+            .and(warp::body::bytes())
+            .and(self.handle_header())
+            .and(header::optional::<String>(CONTENT_TYPE.as_str()))
+            .and(header::optional::<String>(OTLP_TENANT_HEADER))
+            .and(header::optional::<String>(OTLP_DATABASE_HEADER))
+            .and(self.with_dbms())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and(self.handle_span_header())
+            .and_then(
+                |signal: OtlpSignal,
+                 mut req: Bytes,
+                 header: Header,
+                 content_type: Option<String>,
+                 tenant: Option<String>,
+                 db: Option<String>,
+                 dbms: DBMSRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String,
+                 parent_span_ctx: Option<SpanContext>| async move {
+                    let start = Instant::now();
+                    let span_recorder = SpanRecorder::new(
+                        parent_span_ctx.child_span(format!("rest otlp {signal} write")),
+                    );
+                    let span_context = span_recorder.span_ctx();
+
+                    let req_len = req.len();
+                    let content_encoding = get_content_encoding_from_header(&header)?;
+                    if let Some(encoding) = content_encoding {
+                        req = encoding.decode(req).map_err(|e| {
+                            trace::error!("Failed to decode request, err: {}", e);
+                            reject::custom(HttpError::DecodeRequest { source: e })
+                        })?;
+                    }
+
+                    let idempotency_key = header.get_idempotency_key().map(String::from);
+                    let ctx = {
                         let mut span_recorder =
-                            SpanRecorder::new(span_context.child_span("try parse req to lines"));
+                            SpanRecorder::new(span_context.child_span("construct write context"));
+                        let param = WriteParam {
+                            tenant,
+                            db,
+                            ..Default::default()
+                        };
+                        let ctx = construct_write_context_and_check_privilege(
+                            header,
+                            param,
+                            dbms,
+                            coord.clone(),
+                        )
+                        .await
+                        .map_err(reject::custom)?;
+                        span_recorder.record(ctx)
+                    };
+
+                    http_limiter_check_write(&coord.meta_manager(), ctx.tenant(), req_len)
+                        .await
+                        .map_err(reject::custom)?;
+
+                    // OTLP/HTTP requests are protobuf encoded unless the content type is json
+                    let json = content_type
+                        .as_deref()
+                        .is_some_and(|t| t.starts_with(APPLICATION_JSON));
+                    let mut converted = {
+                        let mut span_recorder =
+                            SpanRecorder::new(span_context.child_span("convert otlp to lines"));
                         span_recorder.set_metadata("bytes", req.len());
-                        try_parse_req_to_lines(&req).map_err(reject::custom)?
+                        match signal {
+                            OtlpSignal::Metrics => {
+                                metrics_to_lines(decode_otlp_request(&req, json)?)
+                            }
+                            OtlpSignal::Logs => logs_to_lines(decode_otlp_request(&req, json)?),
+                        }
                     };
 
                     let resp = coord_write_points_with_span_recorder(
                         &coord,
                         ctx.tenant(),
                         ctx.database(),
-                        precision,
-                        write_points_lines,
+                        Precision::NS,
+                        std::mem::take(&mut converted.lines),
+                        idempotency_key.as_deref(),
                         span_context,
                     )
                     .await;
 
-                    http_record_write_metrics(
-                        &metrics,
-                        &ctx,
-                        &addr,
-                        req_len,
-                        start,
-                        HttpApiType::ApiV1Write,
-                    );
-                    resp.map(|_| ResponseBuilder::ok()).map_err(reject::custom)
+                    let api_type = match signal {
+                        OtlpSignal::Metrics => HttpApiType::ApiV1OtlpMetrics,
+                        OtlpSignal::Logs => HttpApiType::ApiV1OtlpLogs,
+                    };
+                    http_record_write_metrics(&metrics, &ctx, &addr, req_len, start, api_type);
+
+                    resp.map_err(reject::custom)?;
+                    let resp = match signal {
+                        OtlpSignal::Metrics => {
+                            encode_otlp_response(&converted.metrics_response(), json)
+                        }
+                        OtlpSignal::Logs => encode_otlp_response(&converted.logs_response(), json),
+                    };
+                    Ok::<_, Rejection>(resp)
                 },
             )
     }
@@ -483,8 +639,7 @@ impl HttpService {
                     );
                     let param = WriteParam {
                         db: Some(db),
-                        precision: None,
-                        tenant: None,
+                        ..Default::default()
                     };
                     let precision = Precision::NS;
 
@@ -506,6 +661,7 @@ impl HttpService {
                         precision,
                         lines,
                         None,
+                        None,
                     )
                     .await;
 
@@ -551,6 +707,7 @@ impl HttpService {
                         })?;
                     }
 
+                    let idempotency_key = header.get_idempotency_key().map(String::from);
                     let ctx = {
                         let mut span_recorder =
                             SpanRecorder::new(span_context.child_span("construct write context"));
@@ -584,6 +741,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         write_points_req,
+                        idempotency_key.as_deref(),
                         span_context,
                     )
                     .await;
@@ -638,6 +796,7 @@ impl HttpService {
                         })?;
                     }
 
+                    let idempotency_key = header.get_idempotency_key().map(String::from);
                     let ctx = {
                         let mut span_recorder =
                             SpanRecorder::new(span_context.child_span("construct write context"));
@@ -671,6 +830,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         write_points_req,
+                        idempotency_key.as_deref(),
                         span_context,
                     )
                     .await;
@@ -921,6 +1081,7 @@ impl HttpService {
                         SpanRecorder::new(parent_span_ctx.child_span("rest prom remote write"));
                     let span_context = span_recorder.span_ctx();
 
+                    let idempotency_key = header.get_idempotency_key().map(String::from);
                     // Parse req、header and param to construct query request
                     let ctx = {
                         let mut span_recorder =
@@ -963,6 +1124,7 @@ impl HttpService {
                         ctx.database(),
                         Precision::NS,
                         write_request,
+                        idempotency_key.as_deref(),
                         span_context,
                     )
                     .await;
@@ -1148,6 +1310,18 @@ async fn construct_read_context(
                 })
                 .transpose()?,
         )
+        .with_read_consistency(
+            param
+                .read_consistency
+                .map(|ref e| {
+                    e.parse::<ReadConsistency>()
+                        .map_err(|reason| HttpError::InvalidParam {
+                            name: "read_consistency".to_string(),
+                            reason,
+                        })
+                })
+                .transpose()?,
+        )
         .build();
 
     Ok(context)
@@ -1257,12 +1431,40 @@ fn construct_write_tsdb_points_json_request(req: &Bytes) -> Result<Vec<Line>, Ht
     Ok(tsdb_datapoints)
 }
 
+fn decode_otlp_request<T>(req: &Bytes, json: bool) -> Result<T, Rejection>
+where
+    T: prost::Message + Default + DeserializeOwned,
+{
+    if json {
+        serde_json::from_slice(req)
+            .map_err(|e| reject::custom(HttpError::ParseOtlpJson { source: e }))
+    } else {
+        T::decode(req.as_ref())
+            .map_err(|e| reject::custom(HttpError::ParseOtlpProtobuf { source: e }))
+    }
+}
+
+/// The response of an OTLP/HTTP request is encoded the same as the request
+fn encode_otlp_response<T>(resp: &T, json: bool) -> Response
+where
+    T: prost::Message + Serialize,
+{
+    if json {
+        ResponseBuilder::new(OK).json(resp)
+    } else {
+        ResponseBuilder::new(OK)
+            .insert_header((CONTENT_TYPE, APPLICATION_PROTOBUF))
+            .build(resp.encode_to_vec())
+    }
+}
+
 async fn coord_write_points_with_span_recorder(
     coord: &CoordinatorRef,
     tenant: &str,
     db: &str,
     precision: Precision,
     write_points_lines: Vec<Line<'_>>,
+    idempotency_key: Option<&str>,
     span_context: Option<&SpanContext>,
 ) -> Result<usize, HttpError> {
     let mut span_recorder = SpanRecorder::new(span_context.child_span("write points"));
@@ -1272,6 +1474,138 @@ async fn coord_write_points_with_span_recorder(
             db,
             precision,
             write_points_lines,
+            idempotency_key,
+            span_recorder.span_ctx(),
+        )
+        .await
+        .map_err(|e| {
+            span_recorder.error(e.to_string());
+            e.into()
+        })
+}
+
+/// The column-mapping params of a NDJSON or CSV write.
+struct WideTableParam {
+    profile: Option<String>,
+    options: Vec<(&'static str, String)>,
+}
+
+impl WideTableParam {
+    /// Returns `None` if the body of the write is line protocol.
+    fn try_from_write_param(param: &WriteParam) -> Option<Self> {
+        if param.format.is_none() && param.profile.is_none() {
+            return None;
+        }
+
+        let options = [
+            (FORMAT_OPTION, &param.format),
+            (TABLE_OPTION, &param.table),
+            (TIME_COLUMN_OPTION, &param.time_column),
+            (TIME_FORMAT_OPTION, &param.time_format),
+            (TAGS_OPTION, &param.tags),
+            (FIELDS_OPTION, &param.fields),
+            (DELIMITER_OPTION, &param.delimiter),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.clone().map(|value| (key, value)))
+        .collect();
+
+        Some(Self {
+            profile: param.profile.clone(),
+            options,
+        })
+    }
+}
+
+async fn coord_write_wide_table_with_span_recorder(
+    coord: &CoordinatorRef,
+    tenant: &str,
+    db: &str,
+    param: WideTableParam,
+    req: &Bytes,
+    idempotency_key: Option<&str>,
+    span_context: Option<&SpanContext>,
+) -> Result<usize, HttpError> {
+    let meta_client = coord
+        .tenant_meta(tenant)
+        .await
+        .ok_or_else(|| HttpError::NotFoundTenant {
+            name: tenant.to_string(),
+        })?;
+    let db_schema = meta_client
+        .get_db_schema(db)?
+        .ok_or_else(|| MetaError::DatabaseNotFound {
+            database: db.to_string(),
+        })?;
+    let db_precision = *db_schema.config.precision_or_default();
+
+    // the options of the profile are overridden by the params
+    let mut options = BTreeMap::new();
+    if let Some(name) = &param.profile {
+        let profile = meta_client.ingestion_profile(name).await?.ok_or_else(|| {
+            MetaError::IngestionProfileNotFound {
+                profile: name.to_string(),
+            }
+        })?;
+        options.extend(profile.options);
+    }
+    options.extend(
+        param
+            .options
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value)),
+    );
+    let spec =
+        WideTableSpec::try_from_options(options.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .map_err(|e| HttpError::ParseWideTable { source: e })?;
+    let table = spec
+        .table()
+        .map_err(|e| HttpError::ParseWideTable { source: e })?;
+
+    let table_schema = match meta_client.get_tskv_table_schema(db, table)? {
+        Some(schema) => schema,
+        None => {
+            let columns = spec
+                .table_columns(db_precision.into())
+                .map_err(|e| HttpError::ParseWideTable { source: e })?;
+            let schema = TskvTableSchema::new(
+                tenant.to_string(),
+                db.to_string(),
+                table.to_string(),
+                columns,
+            );
+            match meta_client
+                .create_table(&TableSchema::TsKvTableSchema(Arc::new(schema)))
+                .await
+            {
+                // created by a concurrent write
+                Ok(()) | Err(MetaError::TableAlreadyExists { .. }) => {}
+                Err(e) => return Err(e.into()),
+            }
+            meta_client
+                .get_tskv_table_schema(db, table)?
+                .ok_or_else(|| MetaError::TableNotFound {
+                    table: table.to_string(),
+                })?
+        }
+    };
+
+    let record_batch = {
+        let mut span_recorder =
+            SpanRecorder::new(span_context.child_span("try parse req to record batch"));
+        span_recorder.set_metadata("bytes", req.len());
+        spec.to_record_batch(req, &table_schema)
+            .map_err(|e| HttpError::ParseWideTable { source: e })?
+    };
+
+    let mut span_recorder = SpanRecorder::new(span_context.child_span("write record batch"));
+    coord
+        .write_record_batch(
+            table_schema,
+            record_batch,
+            db_precision,
+            now_timestamp_nanos(),
+            idempotency_key,
             span_recorder.span_ctx(),
         )
         .await
@@ -109,6 +109,31 @@ pub enum Error {
     InvalidUTF8 {
         source: simdutf8::basic::Utf8Error,
     },
+
+    #[snafu(display("Error parsing otlp protobuf message: {}", source))]
+    #[error_code(code = 16)]
+    ParseOtlpProtobuf {
+        source: prost::DecodeError,
+    },
+
+    #[snafu(display("Error parsing otlp json message: {}", source))]
+    #[error_code(code = 17)]
+    ParseOtlpJson {
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("Error parsing rows: {}", source))]
+    #[error_code(code = 18)]
+    ParseWideTable {
+        source: protocol_parser::Error,
+    },
+
+    #[snafu(display("Invalid parameter '{}': {}", name, reason))]
+    #[error_code(code = 19)]
+    InvalidParam {
+        name: String,
+        reason: String,
+    },
 }
 
 impl From<tskv::Error> for Error {
@@ -182,7 +207,11 @@ impl From<&Error> for Response {
             | Error::TraceHttp { .. }
             | Error::DecodeRequest { .. }
             | Error::ParseOpentsdbProtocol { .. }
-            | Error::ParseOpentsdbJsonProtocol { .. } => ResponseBuilder::bad_request(&error_resp),
+            | Error::ParseOpentsdbJsonProtocol { .. }
+            | Error::ParseOtlpProtobuf { .. }
+            | Error::ParseOtlpJson { .. }
+            | Error::ParseWideTable { .. }
+            | Error::InvalidParam { .. } => ResponseBuilder::bad_request(&error_resp),
             _ => ResponseBuilder::internal_server_error(),
         }
     }
@@ -261,6 +290,21 @@ mod tests {
         assert_eq!(content_type, HeaderValue::from_static(APPLICATION_JSON));
     }
 
+    #[test]
+    fn test_invalid_param_error() {
+        let resp: Response = Error::InvalidParam {
+            name: "read_consistency".to_string(),
+            reason: "test".to_string(),
+        }
+        .into();
+
+        assert_eq!(resp.status(), BAD_REQUEST);
+
+        let content_type = resp.headers().get(CONTENT_TYPE).unwrap();
+
+        assert_eq!(content_type, HeaderValue::from_static(APPLICATION_JSON));
+    }
+
     #[test]
     fn test_parse_auth_error() {
         let resp: Response = Error::ParseAuth {
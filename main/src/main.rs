@@ -1,20 +1,23 @@
 #![allow(dead_code)]
 #![recursion_limit = "256"]
 
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use clap::{command, Args, Parser, Subcommand, ValueEnum};
-use config::{Config, OverrideByEnv, VERSION};
+use config::{Config, OtlpExporterConfig, OverrideByEnv, VERSION};
 use memory_pool::GreedyMemoryPool;
 use metrics::init_tskv_metrics_recorder;
 use metrics::metric_register::MetricsRegister;
+use metrics::otlp_reporter::start_otlp_metrics_exporter;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use tokio::runtime::Runtime;
 use trace::jaeger::jaeger_exporter;
 use trace::log::{CombinationTraceCollector, LogTraceCollector};
+use trace::otlp::{otlp_exporter, otlp_resource, OtlpClient, Resource};
 use trace::{info, init_process_global_tracing, TraceExporter, WorkerGuard};
 use trace_http::ctx::{SpanContextExtractor, TraceHeaderParser};
 
@@ -22,6 +25,7 @@ use crate::report::ReportService;
 
 mod flight_sql;
 mod http;
+mod otlp;
 mod report;
 mod rpc;
 mod server;
@@ -186,15 +190,27 @@ fn main() -> Result<(), std::io::Error> {
     let mem_bytes = run_args.memory.unwrap_or(config.deployment.memory) * 1024 * 1024 * 1024;
     let memory_pool = Arc::new(GreedyMemoryPool::new(mem_bytes));
     runtime.clone().block_on(async move {
+        let metrics_register = Arc::new(MetricsRegister::new([(
+            "node_id",
+            config.global.node_id.to_string(),
+        )]));
+        if let Some(otlp_config) = &config.metrics.otlp {
+            let client = OtlpClient::try_new(otlp_config).expect("build otlp metrics exporter");
+            start_otlp_metrics_exporter(
+                metrics_register.clone(),
+                client,
+                build_otlp_resource(&config, otlp_config),
+                otlp_config.export_interval,
+            );
+            info!("OTLP metrics exporter created");
+        }
+
         let builder = server::ServiceBuilder {
             cpu: config.deployment.cpu,
             config: config.clone(),
             runtime: runtime.clone(),
             memory_pool: memory_pool.clone(),
-            metrics_register: Arc::new(MetricsRegister::new([(
-                "node_id",
-                config.global.node_id.to_string(),
-            )])),
+            metrics_register,
             span_context_extractor: build_span_context_extractor(&config),
         };
 
@@ -305,6 +321,16 @@ fn build_span_context_extractor(config: &Config) -> Arc<SpanContextExtractor> {
         res.push(exporter);
     }
 
+    if let Some(otlp_config) = &config.trace.otlp {
+        let exporter = otlp_exporter(otlp_config, build_otlp_resource(config, otlp_config))
+            .expect("build otlp trace exporter");
+        info!(
+            "OTLP trace exporter created, endpoint: {}",
+            otlp_config.endpoint
+        );
+        res.push(exporter);
+    }
+
     // TODO HttpCollector
     let collector: Option<Arc<dyn TraceExporter>> = if res.is_empty() {
         None
@@ -318,3 +344,28 @@ fn build_span_context_extractor(config: &Config) -> Arc<SpanContextExtractor> {
 
     Arc::new(SpanContextExtractor::new(parser, collector))
 }
+
+/// The resource attributes of the spans and metrics exported by OTLP, the attributes in the
+/// config override the default ones.
+fn build_otlp_resource(config: &Config, otlp_config: &OtlpExporterConfig) -> Resource {
+    let mut attributes = BTreeMap::from([
+        ("service.name".to_string(), "cnosdb".to_string()),
+        ("service.version".to_string(), VERSION.to_string()),
+        (
+            "service.instance.id".to_string(),
+            config.global.node_id.to_string(),
+        ),
+        (
+            "service.namespace".to_string(),
+            config.global.cluster_name.clone(),
+        ),
+        ("host.name".to_string(), config.global.host.clone()),
+        (
+            "cnosdb.deployment.mode".to_string(),
+            config.deployment.mode.clone(),
+        ),
+    ]);
+    attributes.extend(otlp_config.resource_attributes.clone());
+
+    otlp_resource(attributes)
+}
@@ -0,0 +1,2 @@
+pub mod otlp_grpc_service;
+pub mod otlp_server;
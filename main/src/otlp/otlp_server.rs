@@ -0,0 +1,750 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use coordinator::service::CoordinatorRef;
+use http_protocol::header::GRPC_IDEMPOTENCY_KEY;
+use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
+use models::oid::Identifier;
+use models::schema::{Precision, DEFAULT_CATALOG, DEFAULT_DATABASE};
+use models::utils::now_timestamp_nanos;
+use opentelemetry_proto::tonic::collector::logs::v1::logs_service_server::LogsService;
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsPartialSuccess, ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_server::MetricsService;
+use opentelemetry_proto::tonic::collector::metrics::v1::{
+    ExportMetricsPartialSuccess, ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+};
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue};
+use opentelemetry_proto::tonic::metrics::v1::{
+    metric, number_data_point, HistogramDataPoint, NumberDataPoint,
+};
+use protocol_parser::Line;
+use protos::FieldValue;
+use spi::server::dbms::DBMSRef;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status};
+use trace::debug;
+
+use crate::http::header::Header;
+
+/// The metadata (gRPC) or headers (HTTP) of an OTLP request choosing the tenant and database
+pub const OTLP_TENANT_HEADER: &str = "x-cnosdb-tenant";
+pub const OTLP_DATABASE_HEADER: &str = "x-cnosdb-database";
+const AUTHORIZATION_HEADER: &str = "authorization";
+
+pub const OTLP_LOG_TABLE: &str = "__otlp_log";
+const OTLP_LOG_SEVERITY_TAG: &str = "severity";
+const OTLP_LOG_BODY_FIELD: &str = "body";
+const OTLP_LOG_SEVERITY_NUMBER_FIELD: &str = "severity_number";
+const OTLP_LOG_TRACE_ID_FIELD: &str = "trace_id";
+const OTLP_LOG_SPAN_ID_FIELD: &str = "span_id";
+
+const OTLP_SCOPE_NAME_TAG: &str = "otel_scope_name";
+const OTLP_SCOPE_VERSION_TAG: &str = "otel_scope_version";
+const OTLP_TYPE_TAG_KEY: &str = "metric_type";
+const OTLP_LOG_TYPE_TAG_VALUE: &str = "logs";
+
+const INVALID_FIELD_OR_TAG: &str = "time";
+
+/// The signals received by the OTLP/HTTP endpoints `/v1/metrics` and `/v1/logs`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpSignal {
+    Metrics,
+    Logs,
+}
+
+impl FromStr for OtlpSignal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "metrics" => Ok(Self::Metrics),
+            "logs" => Ok(Self::Logs),
+            _ => Err(format!("unsupported otlp signal: {s}")),
+        }
+    }
+}
+
+impl Display for OtlpSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Metrics => write!(f, "metrics"),
+            Self::Logs => write!(f, "logs"),
+        }
+    }
+}
+
+/// Receives the metrics and logs exported by the OpenTelemetry SDKs and collectors
+pub struct OtlpService {
+    coord: CoordinatorRef,
+    dbms: DBMSRef,
+}
+
+impl OtlpService {
+    pub fn new(coord: CoordinatorRef, dbms: DBMSRef) -> Self {
+        Self { coord, dbms }
+    }
+
+    /// Returns the tenant and database of the request if the user may write to the database
+    async fn tenant_db_and_check_privilege(
+        &self,
+        metadata: &MetadataMap,
+    ) -> Result<(String, String), Status> {
+        let get = |key: &str| {
+            metadata
+                .get(key)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let tenant = get(OTLP_TENANT_HEADER).unwrap_or(DEFAULT_CATALOG.to_string());
+        let db = get(OTLP_DATABASE_HEADER).unwrap_or(DEFAULT_DATABASE.to_string());
+        let authorization = get(AUTHORIZATION_HEADER)
+            .ok_or_else(|| Status::unauthenticated("missing authorization"))?;
+
+        let user_info = Header::with(None, None, None, authorization)
+            .try_get_basic_auth()
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+        let user = self
+            .dbms
+            .authenticate(&user_info, &tenant)
+            .await
+            .map_err(|e| Status::permission_denied(e.to_string()))?;
+
+        let tenant_id = *self
+            .coord
+            .tenant_meta(&tenant)
+            .await
+            .ok_or_else(|| Status::invalid_argument(format!("tenant {tenant} not found")))?
+            .tenant()
+            .id();
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(db.clone())),
+            Some(tenant_id),
+        );
+        if !user.check_privilege(&privilege) {
+            return Err(Status::permission_denied(format!(
+                "user {} has no privilege {:?}",
+                user_info.user, privilege
+            )));
+        }
+
+        Ok((tenant, db))
+    }
+
+    async fn write(&self, metadata: &MetadataMap, lines: Vec<Line<'static>>) -> Result<(), Status> {
+        let (tenant, db) = self.tenant_db_and_check_privilege(metadata).await?;
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let idempotency_key = metadata
+            .get(GRPC_IDEMPOTENCY_KEY)
+            .and_then(|v| v.to_str().ok());
+        self.coord
+            .write_lines(&tenant, &db, Precision::NS, lines, idempotency_key, None)
+            .await
+            .map_err(|e| Status::internal(format!("failed to write lines to database {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for OtlpService {
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let (metadata, _, request) = request.into_parts();
+        let mut converted = metrics_to_lines(request);
+        self.write(&metadata, std::mem::take(&mut converted.lines))
+            .await?;
+
+        Ok(Response::new(converted.metrics_response()))
+    }
+}
+
+#[tonic::async_trait]
+impl LogsService for OtlpService {
+    async fn export(
+        &self,
+        request: Request<ExportLogsServiceRequest>,
+    ) -> Result<Response<ExportLogsServiceResponse>, Status> {
+        let (metadata, _, request) = request.into_parts();
+        let mut converted = logs_to_lines(request);
+        self.write(&metadata, std::mem::take(&mut converted.lines))
+            .await?;
+
+        Ok(Response::new(converted.logs_response()))
+    }
+}
+
+/// The lines converted from an OTLP request, and the data points or log records rejected
+#[derive(Debug, Default)]
+pub struct ConvertedLines {
+    pub lines: Vec<Line<'static>>,
+    pub rejected: i64,
+    pub error_message: String,
+}
+
+impl ConvertedLines {
+    fn reject(&mut self, count: usize, reason: String) {
+        debug!("Reject {count} otlp data points: {reason}");
+        self.rejected += count as i64;
+        if self.error_message.is_empty() {
+            self.error_message = reason;
+        }
+    }
+
+    pub fn metrics_response(self) -> ExportMetricsServiceResponse {
+        ExportMetricsServiceResponse {
+            partial_success: (self.rejected > 0).then(|| ExportMetricsPartialSuccess {
+                rejected_data_points: self.rejected,
+                error_message: self.error_message,
+            }),
+        }
+    }
+
+    pub fn logs_response(self) -> ExportLogsServiceResponse {
+        ExportLogsServiceResponse {
+            partial_success: (self.rejected > 0).then(|| ExportLogsPartialSuccess {
+                rejected_log_records: self.rejected,
+                error_message: self.error_message,
+            }),
+        }
+    }
+}
+
+type Tags = BTreeMap<String, String>;
+type Fields = BTreeMap<String, FieldValue>;
+
+/// Every data point is a line of the table named by the metric, the attributes of the resource,
+/// the scope and the data point are the tags.
+pub fn metrics_to_lines(request: ExportMetricsServiceRequest) -> ConvertedLines {
+    let mut converted = ConvertedLines::default();
+    for resource_metrics in request.resource_metrics {
+        let mut resource_tags = Tags::new();
+        if let Some(resource) = resource_metrics.resource {
+            extend_tags(&mut resource_tags, resource.attributes);
+        }
+
+        for scope_metrics in resource_metrics.scope_metrics {
+            let mut scope_tags = resource_tags.clone();
+            if let Some(scope) = scope_metrics.scope {
+                extend_scope_tags(&mut scope_tags, scope);
+            }
+
+            for metric in scope_metrics.metrics {
+                let table = metric.name;
+                match metric.data {
+                    Some(metric::Data::Gauge(gauge)) => {
+                        for point in gauge.data_points {
+                            converted.lines.push(number_point_line(
+                                &table,
+                                &scope_tags,
+                                "gauge",
+                                point,
+                            ));
+                        }
+                    }
+                    Some(metric::Data::Sum(sum)) => {
+                        for point in sum.data_points {
+                            converted.lines.push(number_point_line(
+                                &table,
+                                &scope_tags,
+                                "sum",
+                                point,
+                            ));
+                        }
+                    }
+                    Some(metric::Data::Histogram(histogram)) => {
+                        for point in histogram.data_points {
+                            converted
+                                .lines
+                                .push(histogram_point_line(&table, &scope_tags, point));
+                        }
+                    }
+                    Some(metric::Data::ExponentialHistogram(histogram)) => converted.reject(
+                        histogram.data_points.len(),
+                        format!("exponential histogram {table} is not supported"),
+                    ),
+                    Some(metric::Data::Summary(summary)) => converted.reject(
+                        summary.data_points.len(),
+                        format!("summary {table} is not supported"),
+                    ),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    converted
+}
+
+/// Every log record is a line of [`OTLP_LOG_TABLE`], the attributes of the resource and the
+/// scope are the tags, the attributes of the log record are the fields.
+pub fn logs_to_lines(request: ExportLogsServiceRequest) -> ConvertedLines {
+    let mut converted = ConvertedLines::default();
+    for resource_logs in request.resource_logs {
+        let mut resource_tags = Tags::new();
+        if let Some(resource) = resource_logs.resource {
+            extend_tags(&mut resource_tags, resource.attributes);
+        }
+
+        for scope_logs in resource_logs.scope_logs {
+            let mut scope_tags = resource_tags.clone();
+            if let Some(scope) = scope_logs.scope {
+                extend_scope_tags(&mut scope_tags, scope);
+            }
+
+            for log in scope_logs.log_records {
+                let mut tags = scope_tags.clone();
+                tags.insert(
+                    OTLP_TYPE_TAG_KEY.to_string(),
+                    OTLP_LOG_TYPE_TAG_VALUE.to_string(),
+                );
+                if !log.severity_text.is_empty() {
+                    tags.insert(OTLP_LOG_SEVERITY_TAG.to_string(), log.severity_text);
+                }
+
+                let mut fields = Fields::new();
+                for attribute in log.attributes {
+                    if let Some(value) = attribute.value.and_then(any_value_to_field) {
+                        fields.insert(valid_key(attribute.key, "log"), value);
+                    }
+                }
+                fields.insert(
+                    OTLP_LOG_BODY_FIELD.to_string(),
+                    FieldValue::Str(
+                        log.body
+                            .map(any_value_to_string)
+                            .unwrap_or_default()
+                            .into_bytes(),
+                    ),
+                );
+                fields.insert(
+                    OTLP_LOG_SEVERITY_NUMBER_FIELD.to_string(),
+                    FieldValue::I64(log.severity_number as i64),
+                );
+                if !log.trace_id.is_empty() {
+                    fields.insert(
+                        OTLP_LOG_TRACE_ID_FIELD.to_string(),
+                        FieldValue::Str(hex_string(&log.trace_id).into_bytes()),
+                    );
+                }
+                if !log.span_id.is_empty() {
+                    fields.insert(
+                        OTLP_LOG_SPAN_ID_FIELD.to_string(),
+                        FieldValue::Str(hex_string(&log.span_id).into_bytes()),
+                    );
+                }
+
+                let timestamp = [log.time_unix_nano, log.observed_time_unix_nano]
+                    .into_iter()
+                    .find(|t| *t > 0)
+                    .map(|t| t as i64)
+                    .unwrap_or_else(now_timestamp_nanos);
+                converted
+                    .lines
+                    .push(new_line(OTLP_LOG_TABLE, tags, fields, timestamp));
+            }
+        }
+    }
+
+    converted
+}
+
+fn number_point_line(
+    table: &str,
+    scope_tags: &Tags,
+    metric_type: &str,
+    point: NumberDataPoint,
+) -> Line<'static> {
+    let mut tags = scope_tags.clone();
+    extend_tags(&mut tags, point.attributes);
+    tags.insert(OTLP_TYPE_TAG_KEY.to_string(), metric_type.to_string());
+
+    let value = match point.value {
+        Some(number_data_point::Value::AsInt(v)) => FieldValue::I64(v),
+        Some(number_data_point::Value::AsDouble(v)) => FieldValue::F64(v),
+        None => FieldValue::F64(f64::NAN),
+    };
+    let fields = Fields::from([("value".to_string(), value)]);
+
+    new_line(table, tags, fields, timestamp(point.time_unix_nano))
+}
+
+/// The bucket counts are the fields `bucket_{upper bound}`, the last bucket is `bucket_+Inf`
+fn histogram_point_line(
+    table: &str,
+    scope_tags: &Tags,
+    point: HistogramDataPoint,
+) -> Line<'static> {
+    let mut tags = scope_tags.clone();
+    extend_tags(&mut tags, point.attributes);
+    tags.insert(OTLP_TYPE_TAG_KEY.to_string(), "histogram".to_string());
+
+    let mut fields = Fields::from([("count".to_string(), FieldValue::U64(point.count))]);
+    if let Some(sum) = point.sum {
+        fields.insert("sum".to_string(), FieldValue::F64(sum));
+    }
+    if let Some(min) = point.min {
+        fields.insert("min".to_string(), FieldValue::F64(min));
+    }
+    if let Some(max) = point.max {
+        fields.insert("max".to_string(), FieldValue::F64(max));
+    }
+    for (i, count) in point.bucket_counts.iter().enumerate() {
+        let key = match point.explicit_bounds.get(i) {
+            Some(bound) => format!("bucket_{bound}"),
+            None => "bucket_+Inf".to_string(),
+        };
+        fields.insert(key, FieldValue::U64(*count));
+    }
+
+    new_line(table, tags, fields, timestamp(point.time_unix_nano))
+}
+
+fn new_line(table: &str, tags: Tags, fields: Fields, timestamp: i64) -> Line<'static> {
+    Line::new(
+        Cow::Owned(table.to_string()),
+        tags.into_iter()
+            .map(|(k, v)| (Cow::Owned(k), Cow::Owned(v)))
+            .collect(),
+        fields
+            .into_iter()
+            .map(|(k, v)| (Cow::Owned(k), v))
+            .collect(),
+        timestamp,
+    )
+}
+
+fn extend_scope_tags(tags: &mut Tags, scope: InstrumentationScope) {
+    if !scope.name.is_empty() {
+        tags.insert(OTLP_SCOPE_NAME_TAG.to_string(), scope.name);
+    }
+    if !scope.version.is_empty() {
+        tags.insert(OTLP_SCOPE_VERSION_TAG.to_string(), scope.version);
+    }
+    extend_tags(tags, scope.attributes);
+}
+
+/// The attributes with empty values are ignored, the tags can't be empty
+fn extend_tags(tags: &mut Tags, attributes: Vec<KeyValue>) {
+    for attribute in attributes {
+        let value = attribute.value.map(any_value_to_string).unwrap_or_default();
+        if !value.is_empty() {
+            tags.insert(valid_key(attribute.key, "tag"), value);
+        }
+    }
+}
+
+fn valid_key(key: String, suffix: &str) -> String {
+    if key == INVALID_FIELD_OR_TAG {
+        format!("{key}_{suffix}")
+    } else {
+        key
+    }
+}
+
+fn timestamp(time_unix_nano: u64) -> i64 {
+    if time_unix_nano == 0 {
+        now_timestamp_nanos()
+    } else {
+        time_unix_nano as i64
+    }
+}
+
+fn any_value_to_field(value: AnyValue) -> Option<FieldValue> {
+    match value.value? {
+        any_value::Value::IntValue(v) => Some(FieldValue::I64(v)),
+        any_value::Value::DoubleValue(v) => Some(FieldValue::F64(v)),
+        any_value::Value::BoolValue(v) => Some(FieldValue::Bool(v)),
+        v => Some(FieldValue::Str(
+            any_value_to_string(AnyValue { value: Some(v) }).into_bytes(),
+        )),
+    }
+}
+
+fn any_value_to_string(value: AnyValue) -> String {
+    match value.value {
+        None => String::new(),
+        Some(any_value::Value::StringValue(v)) => v,
+        Some(any_value::Value::BoolValue(v)) => v.to_string(),
+        Some(any_value::Value::IntValue(v)) => v.to_string(),
+        Some(any_value::Value::DoubleValue(v)) => v.to_string(),
+        Some(any_value::Value::BytesValue(v)) => hex_string(&v),
+        Some(any_value::Value::ArrayValue(array)) => {
+            let values = array
+                .values
+                .into_iter()
+                .map(any_value_to_string)
+                .collect::<Vec<_>>();
+            format!("{:?}", values)
+        }
+        Some(any_value::Value::KvlistValue(list)) => {
+            let values = list
+                .values
+                .into_iter()
+                .map(|kv| {
+                    (
+                        kv.key,
+                        kv.value.map(any_value_to_string).unwrap_or_default(),
+                    )
+                })
+                .collect::<BTreeMap<_, _>>();
+            format!("{:?}", values)
+        }
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+    use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+    use opentelemetry_proto::tonic::common::v1::{
+        any_value, AnyValue, InstrumentationScope, KeyValue,
+    };
+    use opentelemetry_proto::tonic::logs::v1::{LogRecord, ResourceLogs, ScopeLogs};
+    use opentelemetry_proto::tonic::metrics::v1::{
+        metric, number_data_point, Gauge, Histogram, HistogramDataPoint, Metric, NumberDataPoint,
+        ResourceMetrics, ScopeMetrics, Sum, Summary, SummaryDataPoint,
+    };
+    use opentelemetry_proto::tonic::resource::v1::Resource;
+    use protos::FieldValue;
+
+    use super::{logs_to_lines, metrics_to_lines, OTLP_LOG_TABLE};
+
+    fn string_value(key: &str, value: &str) -> KeyValue {
+        KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_string())),
+            }),
+        }
+    }
+
+    fn resource() -> Option<Resource> {
+        Some(Resource {
+            attributes: vec![
+                string_value("service.name", "api"),
+                string_value("host.name", "node1"),
+            ],
+            ..Default::default()
+        })
+    }
+
+    fn scope() -> Option<InstrumentationScope> {
+        Some(InstrumentationScope {
+            name: "meter".to_string(),
+            version: "1.0".to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn metrics_request(metrics: Vec<Metric>) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: resource(),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: scope(),
+                    metrics,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn tag<'a>(line: &'a protocol_parser::Line, key: &str) -> Option<&'a str> {
+        line.tags
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_ref())
+    }
+
+    fn field<'a>(line: &'a protocol_parser::Line, key: &str) -> Option<&'a FieldValue> {
+        line.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    #[test]
+    fn test_gauge_and_sum_to_lines() {
+        let gauge = Metric {
+            name: "cpu_usage".to_string(),
+            data: Some(metric::Data::Gauge(Gauge {
+                data_points: vec![NumberDataPoint {
+                    attributes: vec![string_value("cpu", "0"), string_value("host.name", "node2")],
+                    time_unix_nano: 100,
+                    value: Some(number_data_point::Value::AsDouble(0.5)),
+                    ..Default::default()
+                }],
+            })),
+            ..Default::default()
+        };
+        let sum = Metric {
+            name: "requests".to_string(),
+            data: Some(metric::Data::Sum(Sum {
+                data_points: vec![NumberDataPoint {
+                    time_unix_nano: 200,
+                    value: Some(number_data_point::Value::AsInt(42)),
+                    ..Default::default()
+                }],
+                aggregation_temporality: 2,
+                is_monotonic: true,
+            })),
+            ..Default::default()
+        };
+
+        let converted = metrics_to_lines(metrics_request(vec![gauge, sum]));
+        assert_eq!(converted.rejected, 0);
+        assert_eq!(converted.lines.len(), 2);
+
+        let line = &converted.lines[0];
+        assert_eq!(line.table, "cpu_usage");
+        assert_eq!(line.timestamp, 100);
+        assert_eq!(tag(line, "service.name"), Some("api"));
+        // The attributes of the data point override the attributes of the resource
+        assert_eq!(tag(line, "host.name"), Some("node2"));
+        assert_eq!(tag(line, "cpu"), Some("0"));
+        assert_eq!(tag(line, "otel_scope_name"), Some("meter"));
+        assert_eq!(tag(line, "otel_scope_version"), Some("1.0"));
+        assert_eq!(tag(line, "metric_type"), Some("gauge"));
+        assert_eq!(field(line, "value"), Some(&FieldValue::F64(0.5)));
+
+        let line = &converted.lines[1];
+        assert_eq!(line.table, "requests");
+        assert_eq!(tag(line, "metric_type"), Some("sum"));
+        assert_eq!(field(line, "value"), Some(&FieldValue::I64(42)));
+    }
+
+    #[test]
+    fn test_unsupported_metrics_rejected() {
+        let histogram = Metric {
+            name: "latency".to_string(),
+            data: Some(metric::Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    time_unix_nano: 100,
+                    count: 6,
+                    sum: Some(12.5),
+                    bucket_counts: vec![1, 2, 3],
+                    explicit_bounds: vec![0.5, 1.0],
+                    ..Default::default()
+                }],
+                aggregation_temporality: 2,
+            })),
+            ..Default::default()
+        };
+        let summary = Metric {
+            name: "summary".to_string(),
+            data: Some(metric::Data::Summary(Summary {
+                data_points: vec![SummaryDataPoint::default()],
+            })),
+            ..Default::default()
+        };
+
+        let converted = metrics_to_lines(metrics_request(vec![histogram, summary]));
+        assert_eq!(converted.lines.len(), 1);
+        assert_eq!(converted.rejected, 1);
+        assert!(converted
+            .metrics_response()
+            .partial_success
+            .is_some_and(|p| p.rejected_data_points == 1));
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let histogram = Metric {
+            name: "latency".to_string(),
+            data: Some(metric::Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    count: 6,
+                    sum: Some(12.5),
+                    bucket_counts: vec![1, 2, 3],
+                    explicit_bounds: vec![0.5, 1.0],
+                    ..Default::default()
+                }],
+                aggregation_temporality: 2,
+            })),
+            ..Default::default()
+        };
+
+        let converted = metrics_to_lines(metrics_request(vec![histogram]));
+        let line = &converted.lines[0];
+        assert_eq!(line.table, "latency");
+        assert_eq!(tag(line, "metric_type"), Some("histogram"));
+        assert_eq!(field(line, "count"), Some(&FieldValue::U64(6)));
+        assert_eq!(field(line, "sum"), Some(&FieldValue::F64(12.5)));
+        assert_eq!(field(line, "bucket_0.5"), Some(&FieldValue::U64(1)));
+        assert_eq!(field(line, "bucket_1"), Some(&FieldValue::U64(2)));
+        assert_eq!(field(line, "bucket_+Inf"), Some(&FieldValue::U64(3)));
+        assert!(field(line, "min").is_none());
+        // The time of the data point is missing
+        assert!(line.timestamp > 0);
+    }
+
+    #[test]
+    fn test_logs_to_lines() {
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                resource: resource(),
+                scope_logs: vec![ScopeLogs {
+                    scope: scope(),
+                    log_records: vec![LogRecord {
+                        observed_time_unix_nano: 300,
+                        severity_number: 9,
+                        severity_text: "INFO".to_string(),
+                        body: Some(AnyValue {
+                            value: Some(any_value::Value::StringValue("started".to_string())),
+                        }),
+                        attributes: vec![
+                            string_value("time", "now"),
+                            KeyValue {
+                                key: "attempt".to_string(),
+                                value: Some(AnyValue {
+                                    value: Some(any_value::Value::IntValue(3)),
+                                }),
+                            },
+                        ],
+                        trace_id: vec![0xab; 16],
+                        span_id: vec![],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+
+        let converted = logs_to_lines(request);
+        assert_eq!(converted.lines.len(), 1);
+        assert!(converted.logs_response().partial_success.is_none());
+
+        let line = &converted.lines[0];
+        assert_eq!(line.table, OTLP_LOG_TABLE);
+        assert_eq!(line.timestamp, 300);
+        assert_eq!(tag(line, "service.name"), Some("api"));
+        assert_eq!(tag(line, "severity"), Some("INFO"));
+        assert_eq!(tag(line, "metric_type"), Some("logs"));
+        assert_eq!(
+            field(line, "body"),
+            Some(&FieldValue::Str(b"started".to_vec()))
+        );
+        assert_eq!(field(line, "severity_number"), Some(&FieldValue::I64(9)));
+        assert_eq!(field(line, "attempt"), Some(&FieldValue::I64(3)));
+        assert_eq!(
+            field(line, "time_log"),
+            Some(&FieldValue::Str(b"now".to_vec()))
+        );
+        assert_eq!(
+            field(line, "trace_id"),
+            Some(&FieldValue::Str("ab".repeat(16).into_bytes()))
+        );
+        assert!(field(line, "span_id").is_none());
+    }
+}
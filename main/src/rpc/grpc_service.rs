@@ -8,6 +8,7 @@ use protos::kv_service::tskv_service_server::TskvServiceServer;
 use protos::raft_service::raft_service_server::RaftServiceServer;
 use protos::DEFAULT_GRPC_SERVER_MESSAGE_LEN;
 use replication::network_grpc::RaftCBServer;
+use spi::server::dbms::DBMSRef;
 use tokio::runtime::Runtime;
 use tokio::sync::oneshot;
 use tonic::codec::CompressionEncoding;
@@ -24,8 +25,10 @@ use crate::{info, server};
 pub struct GrpcService {
     addr: SocketAddr,
     runtime: Arc<Runtime>,
-    kv_inst: EngineRef,
+    /// None on the query nodes
+    kv_inst: Option<EngineRef>,
     coord: CoordinatorRef,
+    dbms: DBMSRef,
     tls_config: Option<TLSConfig>,
     metrics_register: Arc<MetricsRegister>,
     span_context_extractor: Arc<SpanContextExtractor>,
@@ -36,8 +39,9 @@ pub struct GrpcService {
 impl GrpcService {
     pub fn new(
         runtime: Arc<Runtime>,
-        kv_inst: EngineRef,
+        kv_inst: Option<EngineRef>,
         coord: CoordinatorRef,
+        dbms: DBMSRef,
         addr: SocketAddr,
         tls_config: Option<TLSConfig>,
         metrics_register: Arc<MetricsRegister>,
@@ -49,6 +53,7 @@ impl GrpcService {
             runtime,
             kv_inst,
             coord,
+            dbms,
             tls_config,
             metrics_register,
             span_context_extractor,
@@ -86,6 +91,7 @@ impl Service for GrpcService {
             runtime: self.runtime.clone(),
             kv_inst: self.kv_inst.clone(),
             coord: self.coord.clone(),
+            dbms: self.dbms.clone(),
             metrics_register: self.metrics_register.clone(),
             grpc_enable_gzip: self.enable_gzip,
         })
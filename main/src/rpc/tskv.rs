@@ -4,6 +4,9 @@ use std::sync::Arc;
 use coordinator::errors::{CoordinatorError, CoordinatorResult};
 use coordinator::service::CoordinatorRef;
 use coordinator::{FAILED_RESPONSE_CODE, SUCCESS_RESPONSE_CODE};
+use datafusion::arrow::array::{UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
 use futures::{Stream, TryStreamExt};
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
@@ -14,6 +17,9 @@ use models::schema::TableColumn;
 use protos::kv_service::tskv_service_server::TskvService;
 use protos::kv_service::*;
 use protos::models::{PingBody, PingBodyBuilder};
+use query::metadata::queries_record_batch;
+use spi::server::dbms::DBMSRef;
+use spi::service::protocol::QueryId;
 use tokio::io::AsyncReadExt;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
@@ -24,6 +30,7 @@ use tskv::error::Result as TskvResult;
 use tskv::reader::query_executor::QueryExecutor;
 use tskv::reader::serialize::TonicRecordBatchEncoder;
 use tskv::reader::{QueryOption, SendableTskvRecordBatchStream};
+use tskv::stats::StorageStatsKind;
 use tskv::EngineRef;
 
 type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send>>;
@@ -31,8 +38,10 @@ type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + S
 #[derive(Clone)]
 pub struct TskvServiceImpl {
     pub runtime: Arc<Runtime>,
-    pub kv_inst: EngineRef,
+    /// None on the query nodes, which only serve the admin commands
+    pub kv_inst: Option<EngineRef>,
     pub coord: CoordinatorRef,
+    pub dbms: DBMSRef,
     pub metrics_register: Arc<MetricsRegister>,
     pub grpc_enable_gzip: bool,
 }
@@ -58,6 +67,17 @@ impl TskvServiceImpl {
         tonic::Status::new(tonic::Code::Internal, msg)
     }
 
+    fn kv_inst(&self) -> CoordinatorResult<EngineRef> {
+        self.kv_inst
+            .clone()
+            .ok_or_else(|| CoordinatorError::CommonError {
+                msg: format!(
+                    "node {} is a query node without storage",
+                    self.coord.node_id()
+                ),
+            })
+    }
+
     async fn warp_exec_admin_command(
         &self,
         tenant: &str,
@@ -67,7 +87,7 @@ impl TskvServiceImpl {
             admin_command_request::Command::CompactVnode(command) => {
                 info!("compact vnodes: {:?}", command.vnode_ids);
 
-                self.kv_inst
+                self.kv_inst()?
                     .compact(command.vnode_ids.clone())
                     .await
                     .map_err(|err| err.into())
@@ -103,6 +123,76 @@ impl TskvServiceImpl {
                     .destory_replica_group(tenant, &command.db_name, command.replica_id)
                     .await
             }
+
+            admin_command_request::Command::TransferLeader(command) => {
+                let raft_manager = self.coord.raft_manager();
+                raft_manager
+                    .transfer_leader(
+                        tenant,
+                        &command.db_name,
+                        command.vnode_id,
+                        command.replica_id,
+                    )
+                    .await
+            }
+
+            admin_command_request::Command::ElectLeader(command) => {
+                let raft_manager = self.coord.raft_manager();
+                raft_manager
+                    .elect_leader(
+                        tenant,
+                        &command.db_name,
+                        command.vnode_id,
+                        command.replica_id,
+                        command.last_log_index,
+                    )
+                    .await
+            }
+
+            admin_command_request::Command::ExportVnode(command) => {
+                info!("export vnode {} to {}", command.vnode_id, command.path);
+
+                tskv::bulk::export_vnode(
+                    self.runtime.clone(),
+                    self.kv_inst()?,
+                    tenant,
+                    &command.db_name,
+                    command.vnode_id,
+                    &command.path,
+                )
+                .await
+                .map(|_| ())
+                .map_err(|err| err.into())
+            }
+
+            admin_command_request::Command::ImportVnode(command) => {
+                info!("import {} into vnode {}", command.path, command.vnode_id);
+
+                coordinator::bulk_import::import_vnode(
+                    self.coord.as_ref(),
+                    tenant,
+                    &command.db_name,
+                    command.vnode_id,
+                    &command.path,
+                )
+                .await
+            }
+
+            admin_command_request::Command::FetchImportFiles(command) => {
+                coordinator::bulk_import::fetch_import_files(self.coord.as_ref(), tenant, command)
+                    .await
+            }
+
+            admin_command_request::Command::KillQuery(command) => {
+                let query_id = QueryId::from(command.query_id);
+                if self.dbms.kill_query(&query_id) {
+                    Ok(())
+                } else {
+                    Err(CoordinatorError::CommonError {
+                        msg: format!("query {} not found", query_id),
+                    })
+                }
+            }
         };
 
         info!("admin command: {:?}, result: {:?}", command, result);
@@ -132,7 +222,10 @@ impl TskvServiceImpl {
         _tenant: &str,
         request: &FetchVnodeChecksumRequest,
     ) -> Result<tonic::Response<BatchBytesResponse>, tonic::Status> {
-        match self.kv_inst.get_vnode_hash_tree(request.vnode_id).await {
+        let Ok(kv_inst) = self.kv_inst() else {
+            return self.bytes_response(FAILED_RESPONSE_CODE, vec![]);
+        };
+        match kv_inst.get_vnode_hash_tree(request.vnode_id).await {
             Ok(record) => match record_batch_encode(&record) {
                 Ok(bytes) => self.bytes_response(SUCCESS_RESPONSE_CODE, bytes),
                 Err(_) => self.bytes_response(FAILED_RESPONSE_CODE, vec![]),
@@ -142,8 +235,81 @@ impl TskvServiceImpl {
         }
     }
 
+    async fn admin_fetch_raft_applied_index(
+        &self,
+        _tenant: &str,
+        request: &FetchRaftAppliedIndexRequest,
+    ) -> Result<tonic::Response<BatchBytesResponse>, tonic::Status> {
+        let raft_manager = self.coord.raft_manager();
+        let Some((vnode_id, applied)) = raft_manager.applied_index(request.replica_id).await else {
+            return self.bytes_response(FAILED_RESPONSE_CODE, vec![]);
+        };
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("vnode_id", DataType::UInt32, false),
+            Field::new("applied_index", DataType::UInt64, false),
+        ]));
+        let record = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(UInt32Array::from(vec![vnode_id])),
+                Arc::new(UInt64Array::from(vec![applied])),
+            ],
+        );
+        match record.and_then(|r| record_batch_encode(&r)) {
+            Ok(bytes) => self.bytes_response(SUCCESS_RESPONSE_CODE, bytes),
+            Err(_) => self.bytes_response(FAILED_RESPONSE_CODE, vec![]),
+        }
+    }
+
+    async fn admin_fetch_storage_stats(
+        &self,
+        request: &FetchStorageStatsRequest,
+    ) -> Result<tonic::Response<BatchBytesResponse>, tonic::Status> {
+        let kind = match request.kind.parse::<StorageStatsKind>() {
+            Ok(kind) => kind,
+            Err(err) => {
+                error!("fetch storage statistics failed: {}", err);
+                return self.bytes_response(FAILED_RESPONSE_CODE, vec![]);
+            }
+        };
+        let kv_inst = match self.kv_inst() {
+            Ok(kv_inst) => kv_inst,
+            Err(err) => {
+                error!("fetch storage statistics failed: {}", err);
+                return self.bytes_response(FAILED_RESPONSE_CODE, vec![]);
+            }
+        };
+        match kv_inst.get_storage_stats(kind).await {
+            Ok(record) => match record_batch_encode(&record) {
+                Ok(bytes) => self.bytes_response(SUCCESS_RESPONSE_CODE, bytes),
+                Err(_) => self.bytes_response(FAILED_RESPONSE_CODE, vec![]),
+            },
+            Err(err) => {
+                error!("fetch storage statistics {} failed: {}", kind, err);
+                self.bytes_response(FAILED_RESPONSE_CODE, vec![])
+            }
+        }
+    }
+
+    fn admin_fetch_queries(&self) -> Result<tonic::Response<BatchBytesResponse>, tonic::Status> {
+        let queries = self.dbms.running_queries();
+        let record = match queries_record_batch(self.coord.node_id(), &queries) {
+            Ok(record) => record,
+            Err(err) => {
+                error!("build running queries failed: {}", err);
+                return self.bytes_response(FAILED_RESPONSE_CODE, vec![]);
+            }
+        };
+        match record_batch_encode(&record) {
+            Ok(bytes) => self.bytes_response(SUCCESS_RESPONSE_CODE, bytes),
+            Err(_) => self.bytes_response(FAILED_RESPONSE_CODE, vec![]),
+        }
+    }
+
     fn query_record_batch_exec(
         self,
+        kv_inst: EngineRef,
         args: QueryArgs,
         expr: QueryExpr,
         aggs: Option<Vec<TableColumn>>,
@@ -164,7 +330,7 @@ impl TskvServiceImpl {
             vnodes.push(VnodeInfo::new(*id, node_id))
         }
 
-        let executor = QueryExecutor::new(option, self.runtime.clone(), meta, self.kv_inst.clone());
+        let executor = QueryExecutor::new(option, self.runtime.clone(), meta, kv_inst);
         executor.local_node_executor(vnodes, span_ctx)
     }
 
@@ -337,6 +503,14 @@ impl TskvService for TskvServiceImpl {
                     self.admin_fetch_vnode_checksum(&inner.tenant, command)
                         .await
                 }
+                admin_fetch_command_request::Command::FetchRaftAppliedIndex(command) => {
+                    self.admin_fetch_raft_applied_index(&inner.tenant, command)
+                        .await
+                }
+                admin_fetch_command_request::Command::FetchQueries(_) => self.admin_fetch_queries(),
+                admin_fetch_command_request::Command::FetchStorageStats(command) => {
+                    self.admin_fetch_storage_stats(command).await
+                }
             }
         } else {
             self.bytes_response(FAILED_RESPONSE_CODE, vec![])
@@ -349,7 +523,10 @@ impl TskvService for TskvServiceImpl {
         request: tonic::Request<DownloadFileRequest>,
     ) -> Result<tonic::Response<Self::DownloadFileStream>, tonic::Status> {
         let inner = request.into_inner();
-        let opt = self.kv_inst.get_storage_options();
+        let kv_inst = self
+            .kv_inst()
+            .map_err(|err| self.tonic_status(err.to_string()))?;
+        let opt = kv_inst.get_storage_options();
         let filename = opt.path().join(inner.filename);
         info!("request download file name: {:?}", filename);
 
@@ -401,6 +578,9 @@ impl TskvService for TskvServiceImpl {
             Err(err) => return Err(self.tonic_status(err.to_string())),
         };
 
+        let kv_inst = self
+            .kv_inst()
+            .map_err(|err| self.tonic_status(err.to_string()))?;
         let service = self.clone();
 
         let encoded_stream = {
@@ -408,6 +588,7 @@ impl TskvService for TskvServiceImpl {
 
             let stream = TskvServiceImpl::query_record_batch_exec(
                 service,
+                kv_inst,
                 args,
                 expr,
                 aggs,
@@ -437,6 +618,9 @@ impl TskvService for TskvServiceImpl {
             Err(err) => return Err(self.tonic_status(err.to_string())),
         };
 
+        let kv_inst = self
+            .kv_inst()
+            .map_err(|err| self.tonic_status(err.to_string()))?;
         let stream = {
             let span_recorder = span_recorder.child("RecordBatch encorder stream");
             let stream = TskvServiceImpl::tag_scan_exec(
@@ -444,7 +628,7 @@ impl TskvService for TskvServiceImpl {
                 expr,
                 self.coord.meta_manager(),
                 self.runtime.clone(),
-                self.kv_inst.clone(),
+                kv_inst,
                 span_recorder.span_ctx(),
             )?;
 
@@ -21,6 +21,7 @@ use tskv::{EngineRef, TsKv};
 
 use crate::flight_sql::FlightSqlServiceAdapter;
 use crate::http::http_service::{HttpService, ServerMode};
+use crate::otlp::otlp_grpc_service::OtlpGrpcService;
 use crate::rpc::grpc_service::GrpcService;
 use crate::spi::service::ServiceRef;
 use crate::tcp::tcp_service::TcpService;
@@ -156,7 +157,9 @@ impl ServiceBuilder {
             server.add_service(Box::new(http_service));
         }
 
-        if let Some(grpc_service) = self.create_grpc_if_enabled(kv_inst.clone(), coord.clone()) {
+        if let Some(grpc_service) =
+            self.create_grpc_if_enabled(Some(kv_inst.clone()), coord.clone(), dbms.clone())
+        {
             server.add_service(Box::new(grpc_service));
         }
 
@@ -170,11 +173,16 @@ impl ServiceBuilder {
             server.add_service(Box::new(vector_service));
         }
 
+        if let Some(otlp_service) = self.create_otlp_grpc_if_enabled(coord.clone(), dbms.clone()) {
+            server.add_service(Box::new(otlp_service));
+        }
+
         Some(kv_inst)
     }
 
     pub async fn build_query_server(&self, server: &mut Server) -> Option<EngineRef> {
         let meta = self.create_meta().await;
+        meta.add_query_node().await.unwrap();
         let coord = self
             .create_coord(meta, None, self.memory_pool.clone())
             .await;
@@ -188,6 +196,11 @@ impl ServiceBuilder {
             server.add_service(Box::new(http_service));
         }
 
+        // serves the admin commands of the cluster, such as killing the queries of this node
+        if let Some(grpc_service) = self.create_grpc_if_enabled(None, coord.clone(), dbms.clone()) {
+            server.add_service(Box::new(grpc_service));
+        }
+
         if let Some(flight_sql_service) = self.create_flight_sql_if_enabled(dbms.clone()) {
             server.add_service(Box::new(flight_sql_service));
         }
@@ -219,7 +232,9 @@ impl ServiceBuilder {
             server.add_service(Box::new(http_service));
         }
 
-        if let Some(grpc_service) = self.create_grpc_if_enabled(kv_inst.clone(), coord.clone()) {
+        if let Some(grpc_service) =
+            self.create_grpc_if_enabled(Some(kv_inst.clone()), coord.clone(), dbms.clone())
+        {
             server.add_service(Box::new(grpc_service));
         }
 
@@ -237,6 +252,10 @@ impl ServiceBuilder {
             server.add_service(Box::new(vector_service));
         }
 
+        if let Some(otlp_service) = self.create_otlp_grpc_if_enabled(coord.clone(), dbms.clone()) {
+            server.add_service(Box::new(otlp_service));
+        }
+
         Some(kv_inst)
     }
 
@@ -353,7 +372,12 @@ impl ServiceBuilder {
         ))
     }
 
-    fn create_grpc_if_enabled(&self, kv: EngineRef, coord: CoordinatorRef) -> Option<GrpcService> {
+    fn create_grpc_if_enabled(
+        &self,
+        kv: Option<EngineRef>,
+        coord: CoordinatorRef,
+        dbms: DBMSRef,
+    ) -> Option<GrpcService> {
         let default_grpc_addr = match self.config.service.grpc_listen_port {
             Some(port) => build_default_address(port),
             None => return None,
@@ -377,6 +401,7 @@ impl ServiceBuilder {
             self.runtime.clone(),
             kv,
             coord,
+            dbms,
             addr,
             None,
             self.metrics_register.clone(),
@@ -419,6 +444,40 @@ impl ServiceBuilder {
         ))
     }
 
+    fn create_otlp_grpc_if_enabled(
+        &self,
+        coord: CoordinatorRef,
+        dbms: DBMSRef,
+    ) -> Option<OtlpGrpcService> {
+        let default_otlp_grpc_addr = match self.config.service.otlp_listen_port {
+            Some(port) => build_default_address(port),
+            None => return None,
+        };
+
+        let addr = default_otlp_grpc_addr
+            .to_socket_addrs()
+            .map_err(|e| {
+                format!(
+                    "Cannot resolve otlp_grpc_listen_addr '{}': {}",
+                    default_otlp_grpc_addr, e
+                )
+            })
+            .unwrap()
+            .collect::<Vec<SocketAddr>>()
+            .first()
+            .copied()
+            .expect("Config otlp_grpc_listen_addr cannot be empty.");
+
+        Some(OtlpGrpcService::new(
+            coord,
+            dbms,
+            addr,
+            None,
+            self.metrics_register.clone(),
+            self.span_context_extractor.clone(),
+        ))
+    }
+
     fn create_tcp_if_enabled(&self, coord: CoordinatorRef) -> Option<TcpService> {
         let default_tcp_addr = match self.config.service.tcp_listen_port {
             Some(port) => build_default_address(port),
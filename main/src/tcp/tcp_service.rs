@@ -13,6 +13,68 @@ use crate::server::{Error, ServiceHandle};
 use crate::spi::service::Service;
 
 const MILLISECOND_TIMESTAMP: i64 = 1_000_000_000_000;
+/// `idempotency_key <key>` sets the idempotency key of the put lines following it, the
+/// lines are written as one batch when the next key or the end of the connection is
+/// received, so a batch resent with the same key is applied only once.
+const IDEMPOTENCY_KEY_COMMAND: &str = "idempotency_key";
+
+/// The put lines to write with the same idempotency key.
+#[derive(Default)]
+struct Batch {
+    key: Option<String>,
+    lines: String,
+}
+
+impl Batch {
+    fn push(&mut self, line: &str) {
+        self.lines.push_str(line);
+        self.lines.push_str("\r\n");
+    }
+
+    /// Writes the lines and clears the batch.
+    async fn write(&mut self, coord: &CoordinatorRef, parser: &Parser) -> server::Result<()> {
+        let key = self.key.take();
+        let text = std::mem::take(&mut self.lines);
+        if text.is_empty() {
+            return Ok(());
+        }
+        let (mut lines, _) =
+            parser
+                .parse_tcp_opentsdb(text.as_bytes())
+                .map_err(|e| Error::Common {
+                    reason: format!("open opentsdb parse point failed: {:?}", e),
+                })?;
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        lines.iter_mut().for_each(|line| {
+            let mut bit = line.timestamp / MILLISECOND_TIMESTAMP;
+            while bit > 10 {
+                line.timestamp /= 10;
+                bit = line.timestamp / MILLISECOND_TIMESTAMP;
+            }
+            while bit == 0 {
+                line.timestamp *= 10;
+                bit = line.timestamp / MILLISECOND_TIMESTAMP;
+            }
+        });
+        coord
+            .write_lines(
+                DEFAULT_CATALOG,
+                DEFAULT_DATABASE,
+                Precision::NS,
+                lines,
+                key.as_deref(),
+                None,
+            )
+            .await
+            .map_err(|e| Error::Common {
+                reason: format!("open opentsdb write point failed: {:?}", e),
+            })?;
+        Ok(())
+    }
+}
 
 pub struct TcpService {
     handle: Option<ServiceHandle<server::Result<()>>>,
@@ -46,46 +108,46 @@ impl Service for TcpService {
                 tokio::spawn(async move {
                     let parser = Parser::new(now_timestamp_millis());
                     let mut buffer = Vec::with_capacity(1024);
-                    'inner: loop {
-                        if stream
-                            .read_buf(&mut buffer)
-                            .await
+                    let mut batch = Batch::default();
+                    loop {
+                        let eof =
+                            stream
+                                .read_buf(&mut buffer)
+                                .await
+                                .map_err(|e| Error::Common {
+                                    reason: format!("{:?}", e),
+                                })?
+                                == 0;
+                        // Only the complete lines are handled before the end of the stream.
+                        let end = if eof {
+                            buffer.len()
+                        } else {
+                            match buffer.windows(2).rposition(|w| w == b"\r\n") {
+                                Some(pos) => pos + 2,
+                                None => continue,
+                            }
+                        };
+                        let text = std::str::from_utf8(&buffer[..end])
                             .map_err(|e| Error::Common {
-                                reason: format!("{:?}", e),
+                                reason: format!("Invalid Point: {}", e),
                             })?
-                            == 0
-                        {
-                            break 'inner;
-                        }
-                        if let Ok((mut lines, pos)) = parser.parse_tcp_opentsdb(&buffer) {
-                            if lines.is_empty() {
-                                continue;
-                            }
+                            .to_string();
+                        buffer.drain(..end);
 
-                            lines.iter_mut().for_each(|line| {
-                                let mut bit = line.timestamp / MILLISECOND_TIMESTAMP;
-                                while bit > 10 {
-                                    line.timestamp /= 10;
-                                    bit = line.timestamp / MILLISECOND_TIMESTAMP;
+                        for line in text.split("\r\n").filter(|l| !l.is_empty()) {
+                            match line.strip_prefix(IDEMPOTENCY_KEY_COMMAND) {
+                                Some(key) if key.starts_with(char::is_whitespace) => {
+                                    batch.write(&coord, &parser).await?;
+                                    batch.key = Some(key.trim().to_string());
                                 }
-                                while bit == 0 {
-                                    line.timestamp *= 10;
-                                    bit = line.timestamp / MILLISECOND_TIMESTAMP;
-                                }
-                            });
-                            coord
-                                .write_lines(
-                                    DEFAULT_CATALOG,
-                                    DEFAULT_DATABASE,
-                                    Precision::NS,
-                                    lines,
-                                    None,
-                                )
-                                .await
-                                .map_err(|e| Error::Common {
-                                    reason: format!("open opentsdb write point failed: {:?}", e),
-                                })?;
-                            buffer.drain(..pos);
+                                _ => batch.push(line),
+                            }
+                        }
+                        if batch.key.is_none() || eof {
+                            batch.write(&coord, &parser).await?;
+                        }
+                        if eof {
+                            break;
                         }
                     }
                     Ok::<(), Error>(())
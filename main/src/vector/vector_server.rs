@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use chrono::Utc;
 use coordinator::service::CoordinatorRef;
 use dateparser;
+use http_protocol::header::GRPC_IDEMPOTENCY_KEY;
 use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
 use models::auth::user::{UserInfo, ROOT, ROOT_PWD};
 use models::oid::Identifier;
@@ -169,6 +170,11 @@ impl Vector for VectorService {
         let response = PushEventsResponse {};
 
         let mut lines = String::new();
+        let idempotency_key = request
+            .metadata()
+            .get(GRPC_IDEMPOTENCY_KEY)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
         let request_inner = request.into_inner();
         let event_simple = request_inner.events.first();
         let (tenant, db) = match event_simple {
@@ -186,7 +192,14 @@ impl Vector for VectorService {
             .parse(lines.as_str())
             .map_err(|e| Status::invalid_argument(e.to_string()))?;
         self.coord
-            .write_lines(&tenant, &db, Precision::NS, lines, None)
+            .write_lines(
+                &tenant,
+                &db,
+                Precision::NS,
+                lines,
+                idempotency_key.as_deref(),
+                None,
+            )
             .await
             .map_err(|e| Status::internal(format!("failed to write lines to database {}", e)))?;
         Ok(Response::new(response))
@@ -177,6 +177,22 @@ pub enum MetaError {
     #[error_code(code = 55)]
     #[snafu(display("resourceinfo mark is lock by: {node_id}"))]
     ResourceInfosMarkIsLock { node_id: u64 },
+
+    #[snafu(display("The alert rule {} already exists", rule))]
+    #[error_code(code = 56)]
+    AlertRuleAlreadyExists { rule: String },
+
+    #[snafu(display("The alert rule {} not found", rule))]
+    #[error_code(code = 57)]
+    AlertRuleNotFound { rule: String },
+
+    #[snafu(display("The ingestion profile {} already exists", profile))]
+    #[error_code(code = 58)]
+    IngestionProfileAlreadyExists { profile: String },
+
+    #[snafu(display("The ingestion profile {} not found", profile))]
+    #[error_code(code = 59)]
+    IngestionProfileNotFound { profile: String },
 }
 
 impl MetaError {
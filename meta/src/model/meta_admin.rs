@@ -57,6 +57,7 @@ pub struct AdminMeta {
     users: RwLock<HashMap<String, UserDesc>>,
     conn_map: RwLock<HashMap<u64, Channel>>,
     data_nodes: RwLock<HashMap<u64, NodeInfo>>,
+    query_nodes: RwLock<HashMap<u64, NodeInfo>>,
 
     tenants: RwLock<HashMap<String, Arc<TenantMeta>>>,
     limiters: Arc<LimiterManager>,
@@ -80,6 +81,7 @@ impl AdminMeta {
             users: RwLock::new(HashMap::new()),
             conn_map: RwLock::new(HashMap::new()),
             data_nodes: RwLock::new(HashMap::new()),
+            query_nodes: RwLock::new(HashMap::new()),
             tenants: RwLock::new(HashMap::new()),
             limiters: Arc::new(limiters),
 
@@ -115,6 +117,7 @@ impl AdminMeta {
             users: RwLock::new(HashMap::new()),
             conn_map: RwLock::new(HashMap::new()),
             data_nodes: RwLock::new(HashMap::new()),
+            query_nodes: RwLock::new(HashMap::new()),
             tenants: RwLock::new(HashMap::new()),
             limiters,
             watch_version: AtomicU64::new(0),
@@ -173,6 +176,9 @@ impl AdminMeta {
         if let Some(val) = self.data_nodes.read().get(&id) {
             return Ok(val.clone());
         }
+        if let Some(val) = self.query_nodes.read().get(&id) {
+            return Ok(val.clone());
+        }
 
         Err(MetaError::NotFoundNode { id })
     }
@@ -220,6 +226,16 @@ impl AdminMeta {
             }
         }
 
+        let req = command::ReadCommand::QueryNodes(self.cluster());
+        let resp = self.client.read::<Vec<NodeInfo>>(&req).await?;
+        {
+            let mut nodes = self.query_nodes.write();
+            nodes.clear();
+            for item in resp.iter() {
+                nodes.insert(item.id, item.clone());
+            }
+        }
+
         let req = command::ReadCommand::Users(self.cluster());
         let resp = self.client.read::<Vec<UserDesc>>(&req).await?;
         {
@@ -392,6 +408,7 @@ impl AdminMeta {
                 && (strs[2] == key_path::USERS
                     || strs[2] == key_path::RESOURCE_INFOS
                     || strs[2] == key_path::DATA_NODES
+                    || strs[2] == key_path::QUERY_NODES
                     || strs[2] == key_path::DATA_NODES_METRICS)
             {
                 let _ = self.process_watch_log(entry).await;
@@ -414,6 +431,17 @@ impl AdminMeta {
                     self.conn_map.write().remove(&node_id);
                 }
             }
+        } else if len == 4 && strs[2] == key_path::QUERY_NODES {
+            if let Ok(node_id) = serde_json::from_str::<u64>(strs[3]) {
+                if entry.tye == command::ENTRY_LOG_TYPE_SET {
+                    if let Ok(info) = serde_json::from_str::<NodeInfo>(&entry.val) {
+                        self.query_nodes.write().insert(node_id, info);
+                    }
+                } else if entry.tye == command::ENTRY_LOG_TYPE_DEL {
+                    self.query_nodes.write().remove(&node_id);
+                    self.conn_map.write().remove(&node_id);
+                }
+            }
         } else if len == 4 && strs[2] == key_path::USERS {
             if entry.tye == command::ENTRY_LOG_TYPE_SET {
                 if let Ok(user) = serde_json::from_str::<UserDesc>(&entry.val) {
@@ -489,6 +517,31 @@ impl AdminMeta {
         Ok(())
     }
 
+    /// Registers the query node, so that the other nodes can reach its grpc service.
+    pub async fn add_query_node(&self) -> MetaResult<()> {
+        let grpc_addr = build_address_with_optional_addr(
+            &self.config.global.host,
+            self.config.service.grpc_listen_port,
+        );
+
+        let node = NodeInfo {
+            id: self.config.global.node_id,
+            grpc_addr,
+        };
+
+        let cluster_name = self.config.global.cluster_name.clone();
+        let req = command::WriteCommand::AddQueryNode(cluster_name, node.clone());
+        self.client.write::<()>(&req).await?;
+
+        self.query_nodes.write().insert(node.id, node);
+
+        Ok(())
+    }
+
+    pub async fn query_nodes(&self) -> Vec<NodeInfo> {
+        self.query_nodes.read().values().cloned().collect()
+    }
+
     pub async fn data_nodes(&self) -> Vec<NodeInfo> {
         let mut nodes = vec![];
         for (_, val) in self.data_nodes.read().iter() {
@@ -529,6 +582,24 @@ impl AdminMeta {
 
         self.client.write::<()>(&req).await
     }
+
+    pub async fn node_metrics(&self) -> MetaResult<Vec<NodeMetrics>> {
+        let req = command::ReadCommand::NodeMetrics(self.cluster());
+
+        self.client.read::<Vec<NodeMetrics>>(&req).await
+    }
+
+    /// The nodes which are being or have been decommissioned
+    pub async fn decommissioned_nodes(&self) -> MetaResult<HashSet<NodeId>> {
+        let nodes = self
+            .read_resourceinfos()
+            .await?
+            .iter()
+            .filter_map(|info| info.decommissioned_node())
+            .collect();
+
+        Ok(nodes)
+    }
     /******************** Data Node Operation End *********************/
 
     /******************** User Operation Begin *********************/
@@ -6,9 +6,11 @@ use std::sync::Arc;
 
 use client::MetaHttpClient;
 use config::TenantObjectLimiterConfig;
+use models::alert::{Alert, AlertRule};
 use models::auth::privilege::{DatabasePrivilege, Privilege};
 use models::auth::role::{CustomTenantRole, SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::UserDesc;
+use models::ingestion::IngestionProfile;
 use models::meta_data::*;
 use models::oid::{Identifier, Oid};
 use models::schema::{
@@ -360,6 +362,106 @@ impl TenantMeta {
     }
     // tenant role end
 
+    // tenant alert rule start
+
+    pub async fn create_alert_rule(&self, rule: AlertRule) -> MetaResult<()> {
+        let req =
+            command::WriteCommand::CreateAlertRule(self.cluster.clone(), self.tenant_name(), rule);
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn alert_rules(&self) -> MetaResult<Vec<AlertRule>> {
+        let req = command::ReadCommand::AlertRules(self.cluster.clone(), self.tenant_name());
+
+        self.client.read::<Vec<AlertRule>>(&req).await
+    }
+
+    pub async fn drop_alert_rule(&self, rule_name: &str) -> MetaResult<bool> {
+        let req = command::WriteCommand::DropAlertRule(
+            self.cluster.clone(),
+            self.tenant_name(),
+            rule_name.to_string(),
+        );
+
+        let rsp = self.client.write::<bool>(&req).await;
+        if let Err(MetaError::AlertRuleNotFound { rule: _ }) = rsp {
+            Ok(false)
+        } else {
+            rsp
+        }
+    }
+
+    /// The alerts of the rule saved by the node evaluating it, empty if never saved.
+    pub async fn alert_state(&self, rule_name: &str) -> MetaResult<Vec<Alert>> {
+        let req = command::ReadCommand::AlertState(
+            self.cluster.clone(),
+            self.tenant_name(),
+            rule_name.to_string(),
+        );
+
+        let alerts = self.client.read::<Option<Vec<Alert>>>(&req).await?;
+        Ok(alerts.unwrap_or_default())
+    }
+
+    pub async fn update_alert_state(&self, rule_name: &str, alerts: Vec<Alert>) -> MetaResult<()> {
+        let req = command::WriteCommand::UpdateAlertState(
+            self.cluster.clone(),
+            self.tenant_name(),
+            rule_name.to_string(),
+            alerts,
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    // tenant alert rule end
+
+    // tenant ingestion profile start
+
+    pub async fn create_ingestion_profile(&self, profile: IngestionProfile) -> MetaResult<()> {
+        let req = command::WriteCommand::CreateIngestionProfile(
+            self.cluster.clone(),
+            self.tenant_name(),
+            profile,
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn ingestion_profile(&self, name: &str) -> MetaResult<Option<IngestionProfile>> {
+        let req = command::ReadCommand::IngestionProfile(
+            self.cluster.clone(),
+            self.tenant_name(),
+            name.to_string(),
+        );
+
+        self.client.read::<Option<IngestionProfile>>(&req).await
+    }
+
+    pub async fn ingestion_profiles(&self) -> MetaResult<Vec<IngestionProfile>> {
+        let req = command::ReadCommand::IngestionProfiles(self.cluster.clone(), self.tenant_name());
+
+        self.client.read::<Vec<IngestionProfile>>(&req).await
+    }
+
+    pub async fn drop_ingestion_profile(&self, name: &str) -> MetaResult<bool> {
+        let req = command::WriteCommand::DropIngestionProfile(
+            self.cluster.clone(),
+            self.tenant_name(),
+            name.to_string(),
+        );
+
+        let rsp = self.client.write::<bool>(&req).await;
+        if let Err(MetaError::IngestionProfileNotFound { profile: _ }) = rsp {
+            Ok(false)
+        } else {
+            rsp
+        }
+    }
+
+    // tenant ingestion profile end
+
     async fn write_with_data(&self, req: &command::WriteCommand) -> MetaResult<()> {
         let rsp = self.client.write::<TenantMetaData>(req).await?;
 
@@ -2,9 +2,11 @@
 
 use std::collections::{HashMap, HashSet};
 
+use models::alert::{Alert, AlertRule};
 use models::auth::privilege::DatabasePrivilege;
 use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::{UserDesc, UserOptions};
+use models::ingestion::IngestionProfile;
 use models::meta_data::*;
 use models::oid::Oid;
 use models::schema::{DatabaseSchema, ResourceInfo, TableSchema, Tenant, TenantOptions};
@@ -56,6 +58,8 @@ pub enum WriteCommand {
     UpdateVnode(UpdateVnodeArgs),
     // cluster, node info
     AddDataNode(String, NodeInfo),
+    // cluster, node info
+    AddQueryNode(String, NodeInfo),
 
     //cluster, node metrics
     ReportNodeMetrics(String, NodeMetrics),
@@ -126,6 +130,18 @@ pub enum WriteCommand {
     // cluster, privileges, role_name, tenant_name
     RevokePrivileges(String, Vec<(DatabasePrivilege, String)>, String, String),
 
+    // cluster, tenant_name, rule
+    CreateAlertRule(String, String, AlertRule),
+    // cluster, tenant_name, rule_name
+    DropAlertRule(String, String, String),
+    // cluster, tenant_name, rule_name, alerts
+    UpdateAlertState(String, String, String, Vec<Alert>),
+
+    // cluster, tenant_name, profile
+    CreateIngestionProfile(String, String, IngestionProfile),
+    // cluster, tenant_name, profile_name
+    DropIngestionProfile(String, String, String),
+
     Set {
         key: String,
         value: String,
@@ -146,6 +162,7 @@ pub enum WriteCommand {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ReadCommand {
     DataNodes(String),              //cluster
+    QueryNodes(String),             //cluster
     TenaneMetaData(String, String), // cluster tenant
 
     NodeMetrics(String), //cluster
@@ -157,6 +174,14 @@ pub enum ReadCommand {
     // cluster, tenant_name, user_id
     MemberRole(String, String, Oid),
     // cluster, tenant_name
+    AlertRules(String, String),
+    // cluster, tenant_name, rule_name
+    AlertState(String, String, String),
+    // cluster, tenant_name, profile_name
+    IngestionProfile(String, String, String),
+    // cluster, tenant_name
+    IngestionProfiles(String, String),
+    // cluster, tenant_name
     Members(String, String),
     // cluster, user_name
     User(String, String),
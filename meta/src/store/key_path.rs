@@ -6,8 +6,12 @@ use models::oid::Oid;
 // **    /cluster_name/tenants/tenant/roles/roles ->
 // **    /cluster_name/tenants/tenant/members/user_id ->
 // **    /cluster_name/tenants/tenant/limiter ->
+// **    /cluster_name/tenants/tenant/alert_rules/rule_name -> [AlertRule]
+// **    /cluster_name/tenants/tenant/alert_states/rule_name -> [Vec<Alert>]
+// **    /cluster_name/tenants/tenant/ingestion_profiles/profile_name -> [IngestionProfile]
 // **    /cluster_name/auto_incr_id -> id
 // **    /cluster_name/data_nodes/node_id -> [NodeInfo] 集群、数据节点等信息
+// **    /cluster_name/query_nodes/node_id -> [NodeInfo] 查询节点信息
 
 // **    /cluster_name/tenant_name/dbs/db_name -> [DatabaseInfo] db相关信息、保留策略等
 // **    /cluster_name/tenant_name/dbs/db_name/buckets/id -> [BucketInfo] bucket相关信息
@@ -21,7 +25,11 @@ pub const SCHEMAS: &str = "schemas";
 pub const TENANTS: &str = "tenants";
 pub const MEMBERS: &str = "members";
 pub const LIMITER: &str = "limiter";
+pub const ALERT_RULES: &str = "alert_rules";
+pub const ALERT_STATES: &str = "alert_states";
+pub const INGESTION_PROFILES: &str = "ingestion_profiles";
 pub const DATA_NODES: &str = "data_nodes";
+pub const QUERY_NODES: &str = "query_nodes";
 pub const AUTO_INCR_ID: &str = "auto_incr_id";
 pub const DATA_NODES_METRICS: &str = "data_nodes_metrics";
 pub const RESOURCE_INFOS: &str = "resourceinfos";
@@ -65,6 +73,14 @@ impl KeyPath {
         format!("/{}/data_nodes/{}", cluster, id)
     }
 
+    pub fn query_nodes(cluster: &str) -> String {
+        format!("/{}/query_nodes", cluster)
+    }
+
+    pub fn query_node_id(cluster: &str, id: u64) -> String {
+        format!("/{}/query_nodes/{}", cluster, id)
+    }
+
     pub fn data_nodes_metrics(cluster: &str) -> String {
         format!("/{}/data_nodes_metrics", cluster)
     }
@@ -132,6 +148,26 @@ impl KeyPath {
         format!("/{cluster}/tenants/{tenant_name}/limiter")
     }
 
+    pub fn alert_rule(cluster: &str, tenant_name: &str, rule_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/alert_rules/{rule_name}")
+    }
+
+    pub fn alert_rules(cluster: &str, tenant_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/alert_rules")
+    }
+
+    pub fn alert_state(cluster: &str, tenant_name: &str, rule_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/alert_states/{rule_name}")
+    }
+
+    pub fn ingestion_profile(cluster: &str, tenant_name: &str, profile_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/ingestion_profiles/{profile_name}")
+    }
+
+    pub fn ingestion_profiles(cluster: &str, tenant_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/ingestion_profiles")
+    }
+
     pub fn resourceinfos(cluster: &str, name: &str) -> String {
         format!("/{}/resourceinfos/{}", cluster, name)
     }
@@ -4,9 +4,11 @@ use std::fs;
 use std::path::Path;
 use std::sync::Arc;
 
+use models::alert::{Alert, AlertRule};
 use models::auth::privilege::DatabasePrivilege;
 use models::auth::role::{CustomTenantRole, SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::{UserDesc, UserOptions};
+use models::ingestion::IngestionProfile;
 use models::meta_data::*;
 use models::oid::{Identifier, Oid, UuidGenerator};
 use models::schema::{DatabaseSchema, ResourceInfo, TableSchema, Tenant, TenantOptions};
@@ -371,6 +373,9 @@ impl StateMachine {
             ReadCommand::DataNodes(cluster) => {
                 response_encode(self.process_read_data_nodes(cluster))
             }
+            ReadCommand::QueryNodes(cluster) => {
+                response_encode(self.process_read_query_nodes(cluster))
+            }
             ReadCommand::NodeMetrics(cluster) => {
                 response_encode(self.process_read_node_metrics(cluster))
             }
@@ -388,6 +393,20 @@ impl StateMachine {
                 let path = KeyPath::member(cluster, tenant_name, user_id);
                 response_encode(self.get_struct::<TenantRoleIdentifier>(&path))
             }
+            ReadCommand::AlertRules(cluster, tenant_name) => {
+                response_encode(self.process_read_alert_rules(cluster, tenant_name))
+            }
+            ReadCommand::AlertState(cluster, tenant_name, rule_name) => {
+                let path = KeyPath::alert_state(cluster, tenant_name, rule_name);
+                response_encode(self.get_struct::<Vec<Alert>>(&path))
+            }
+            ReadCommand::IngestionProfile(cluster, tenant_name, profile_name) => {
+                let path = KeyPath::ingestion_profile(cluster, tenant_name, profile_name);
+                response_encode(self.get_struct::<IngestionProfile>(&path))
+            }
+            ReadCommand::IngestionProfiles(cluster, tenant_name) => {
+                response_encode(self.process_read_ingestion_profiles(cluster, tenant_name))
+            }
             ReadCommand::Members(cluster, tenant_name) => {
                 response_encode(self.process_read_members(cluster, tenant_name))
             }
@@ -426,6 +445,15 @@ impl StateMachine {
         Ok((response, ver))
     }
 
+    pub fn process_read_query_nodes(&self, cluster: &str) -> MetaResult<Vec<NodeInfo>> {
+        let response: Vec<NodeInfo> = self
+            .children_data::<NodeInfo>(&KeyPath::query_nodes(cluster))?
+            .into_values()
+            .collect();
+
+        Ok(response)
+    }
+
     pub fn process_read_node_metrics(&self, cluster: &str) -> MetaResult<Vec<NodeMetrics>> {
         let response: Vec<NodeMetrics> = self
             .children_data::<NodeMetrics>(&KeyPath::data_nodes_metrics(cluster))?
@@ -481,6 +509,36 @@ impl StateMachine {
         Ok(roles)
     }
 
+    pub fn process_read_alert_rules(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+    ) -> MetaResult<Vec<AlertRule>> {
+        let path = KeyPath::alert_rules(cluster, tenant_name);
+
+        let rules: Vec<AlertRule> = self
+            .children_data::<AlertRule>(&path)?
+            .into_values()
+            .collect();
+
+        Ok(rules)
+    }
+
+    pub fn process_read_ingestion_profiles(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+    ) -> MetaResult<Vec<IngestionProfile>> {
+        let path = KeyPath::ingestion_profiles(cluster, tenant_name);
+
+        let profiles: Vec<IngestionProfile> = self
+            .children_data::<IngestionProfile>(&path)?
+            .into_values()
+            .collect();
+
+        Ok(profiles)
+    }
+
     pub fn process_read_members(
         &self,
         cluster: &str,
@@ -545,6 +603,9 @@ impl StateMachine {
             WriteCommand::AddDataNode(cluster, node) => {
                 response_encode(self.process_add_date_node(cluster, node))
             }
+            WriteCommand::AddQueryNode(cluster, node) => {
+                response_encode(self.process_add_query_node(cluster, node))
+            }
             WriteCommand::ReportNodeMetrics(cluster, node_metrics) => {
                 response_encode(self.process_add_node_metrics(cluster, node_metrics))
             }
@@ -650,6 +711,30 @@ impl StateMachine {
                     tenant_name,
                 ))
             }
+            WriteCommand::CreateAlertRule(cluster, tenant_name, rule) => {
+                response_encode(self.process_create_alert_rule(cluster, tenant_name, rule))
+            }
+            WriteCommand::DropAlertRule(cluster, tenant_name, rule_name) => {
+                response_encode(self.process_drop_alert_rule(cluster, tenant_name, rule_name))
+            }
+            WriteCommand::UpdateAlertState(cluster, tenant_name, rule_name, alerts) => {
+                response_encode(self.process_update_alert_state(
+                    cluster,
+                    tenant_name,
+                    rule_name,
+                    alerts,
+                ))
+            }
+            WriteCommand::CreateIngestionProfile(cluster, tenant_name, profile) => response_encode(
+                self.process_create_ingestion_profile(cluster, tenant_name, profile),
+            ),
+            WriteCommand::DropIngestionProfile(cluster, tenant_name, profile_name) => {
+                response_encode(self.process_drop_ingestion_profile(
+                    cluster,
+                    tenant_name,
+                    profile_name,
+                ))
+            }
             WriteCommand::RetainID(cluster, count) => {
                 response_encode(self.process_retain_id(cluster, *count))
             }
@@ -815,6 +900,12 @@ impl StateMachine {
         self.insert(&key, &value)
     }
 
+    fn process_add_query_node(&self, cluster: &str, node: &NodeInfo) -> MetaResult<()> {
+        let key = KeyPath::query_node_id(cluster, node.id);
+        let value = value_encode(node)?;
+        self.insert(&key, &value)
+    }
+
     fn process_add_node_metrics(
         &self,
         cluster: &str,
@@ -992,8 +1083,16 @@ impl StateMachine {
             .map(|m| (m.id, m))
             .collect();
 
+        // the decommissioned nodes will be removed, no more vnodes are placed on them
+        let decommissioned_nodes: HashSet<NodeId> = self
+            .process_read_resourceinfos(cluster)?
+            .iter()
+            .filter_map(|info| info.decommissioned_node())
+            .collect();
+
         let mut node_info_list = node_info_list
             .into_iter()
+            .filter(|n| !decommissioned_nodes.contains(&n.id))
             .filter_map(|n| node_metrics_list.get(&n.id).map(|m| (n, m)))
             .filter(|(_, m)| m.is_healthy())
             .collect::<Vec<_>>();
@@ -1259,6 +1358,18 @@ impl StateMachine {
             self.process_drop_role(cluster, role.name(), name)?;
         }
 
+        // drop alert rules in the tenant
+        let rules = self.process_read_alert_rules(cluster, name)?;
+        for rule in rules {
+            self.process_drop_alert_rule(cluster, name, &rule.name)?;
+        }
+
+        // drop ingestion profiles in the tenant
+        let profiles = self.process_read_ingestion_profiles(cluster, name)?;
+        for profile in profiles {
+            self.process_drop_ingestion_profile(cluster, name, &profile.name)?;
+        }
+
         // drop tenant meta
         let key = KeyPath::tenant(cluster, name);
         let limiter_key = KeyPath::limiter(cluster, name);
@@ -1381,6 +1492,96 @@ impl StateMachine {
         Ok(true)
     }
 
+    fn process_create_alert_rule(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        rule: &AlertRule,
+    ) -> MetaResult<()> {
+        let key = KeyPath::alert_rule(cluster, tenant_name, &rule.name);
+
+        if self.contains_key(&key)? {
+            return Err(MetaError::AlertRuleAlreadyExists {
+                rule: rule.name.clone(),
+            });
+        }
+
+        self.insert(&key, &value_encode(rule)?)
+    }
+
+    fn process_drop_alert_rule(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        rule_name: &str,
+    ) -> MetaResult<bool> {
+        let key = KeyPath::alert_rule(cluster, tenant_name, rule_name);
+
+        if !self.contains_key(&key)? {
+            return Err(MetaError::AlertRuleNotFound {
+                rule: rule_name.to_string(),
+            });
+        }
+
+        self.remove(&key)?;
+        Ok(true)
+    }
+
+    fn process_create_ingestion_profile(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        profile: &IngestionProfile,
+    ) -> MetaResult<()> {
+        let key = KeyPath::ingestion_profile(cluster, tenant_name, &profile.name);
+
+        if self.contains_key(&key)? {
+            return Err(MetaError::IngestionProfileAlreadyExists {
+                profile: profile.name.clone(),
+            });
+        }
+
+        self.insert(&key, &value_encode(profile)?)
+    }
+
+    fn process_drop_ingestion_profile(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        profile_name: &str,
+    ) -> MetaResult<bool> {
+        let key = KeyPath::ingestion_profile(cluster, tenant_name, profile_name);
+
+        if !self.contains_key(&key)? {
+            return Err(MetaError::IngestionProfileNotFound {
+                profile: profile_name.to_string(),
+            });
+        }
+
+        self.remove(&key)?;
+        self.remove(&KeyPath::alert_state(cluster, tenant_name, rule_name))?;
+        Ok(true)
+    }
+
+    fn process_update_alert_state(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        rule_name: &str,
+        alerts: &[Alert],
+    ) -> MetaResult<()> {
+        // the state of a dropped rule is not written back
+        let key = KeyPath::alert_rule(cluster, tenant_name, rule_name);
+        if !self.contains_key(&key)? {
+            return Err(MetaError::AlertRuleNotFound {
+                rule: rule_name.to_string(),
+            });
+        }
+
+        let key = KeyPath::alert_state(cluster, tenant_name, rule_name);
+        self.insert(&key, &value_encode(alerts)?)
+    }
+
     fn process_grant_privileges(
         &self,
         cluster: &str,
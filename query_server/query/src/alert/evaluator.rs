@@ -0,0 +1,477 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use datafusion::arrow::array::Array;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::util::display::array_value_to_string;
+use datafusion::common::cast::as_float64_array;
+use datafusion::common::Result as DFResult;
+use meta::model::{MetaClientRef, MetaRef};
+use models::alert::{Alert, AlertRule, AlertState};
+use models::oid::Oid;
+use models::utils::now_timestamp_nanos;
+use spi::query::dispatcher::QueryDispatcher;
+use spi::service::protocol::{ContextBuilder, Query};
+use spi::Result;
+use trace::{debug, warn};
+
+use super::state::{alerts_to_record_batch, record_batches_to_alerts};
+use super::webhook::{AlertmanagerAlert, WebhookSender};
+use crate::stream::state_store::memory::MemoryStateStore;
+use crate::stream::state_store::StateStore;
+
+/// The column of the query holding the value of the alerts, the other columns are the labels.
+pub const ALERT_VALUE_COLUMN: &str = "value";
+/// How often the firing alerts are delivered again, the receivers resolve
+/// the alerts which are not delivered again for a while.
+pub const RESEND_DELAY: Duration = Duration::from_secs(60);
+/// How long the resolved alerts are kept in the state of the rule.
+pub const RESOLVED_RETENTION: Duration = Duration::from_secs(15 * 60);
+
+/// Evaluates an alert rule, runs its query and updates the alerts kept in the state store.
+///
+/// The alerts are also saved in meta whenever their states change, so that they are
+/// restored when the rule is evaluated again after a restart, or by another node.
+pub struct AlertRuleEvaluator {
+    rule: AlertRule,
+    tenant_id: Oid,
+    meta: MetaRef,
+    tenant_meta: MetaClientRef,
+    webhook: Option<String>,
+    dispatcher: Arc<dyn QueryDispatcher>,
+    state_store: Arc<MemoryStateStore>,
+    sender: Arc<WebhookSender>,
+}
+
+impl AlertRuleEvaluator {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        rule: AlertRule,
+        tenant_id: Oid,
+        meta: MetaRef,
+        tenant_meta: MetaClientRef,
+        default_webhook: Option<String>,
+        dispatcher: Arc<dyn QueryDispatcher>,
+        state_store: Arc<MemoryStateStore>,
+        sender: Arc<WebhookSender>,
+    ) -> Self {
+        let webhook = rule.webhook.clone().or(default_webhook);
+        Self {
+            rule,
+            tenant_id,
+            meta,
+            tenant_meta,
+            webhook,
+            dispatcher,
+            state_store,
+            sender,
+        }
+    }
+
+    pub async fn evaluate(&self) -> Result<()> {
+        let rule = &self.rule;
+        // The owner is resolved on every evaluation, so the query is planned with the
+        // current privileges of the owner, and fails once the owner is dropped.
+        let owner = self
+            .meta
+            .user_with_privileges(&rule.owner, &rule.tenant)
+            .await?;
+        let ctx = ContextBuilder::new(owner)
+            .with_tenant(Some(rule.tenant.clone()))
+            .with_database(Some(rule.database.clone()))
+            .build();
+        let query = Query::new(ctx, rule.query.clone());
+        let query_id = self.dispatcher.create_query_id();
+        let batches = self
+            .dispatcher
+            .execute_query(self.tenant_id, query_id, &query, None)
+            .await?
+            .chunk_result()
+            .await?;
+
+        let now = now_timestamp_nanos();
+        let active = active_alerts(rule, &batches)?;
+        let previous = record_batches_to_alerts(&self.state_store.state()?)?;
+        let mut alerts = next_alerts(rule, previous.clone(), active, now);
+        debug!(
+            "Evaluated alert rule {} of tenant {}, {} alerts",
+            rule.name,
+            rule.tenant,
+            alerts.len()
+        );
+
+        if let Some(url) = &self.webhook {
+            let to_send = alerts_to_send(&alerts, now);
+            if !to_send.is_empty() {
+                let payload = to_send
+                    .iter()
+                    .map(|i| AlertmanagerAlert::new(&alerts[*i], now))
+                    .collect::<Vec<_>>();
+                // the alerts failed to deliver are sent again in the next evaluation
+                match self.sender.send(url, &payload).await {
+                    Ok(()) => to_send
+                        .into_iter()
+                        .for_each(|i| alerts[i].last_sent_at = Some(now)),
+                    Err(err) => warn!("Alert rule {}: {}", rule.name, err),
+                }
+            }
+        }
+
+        if state_changed(&previous, &alerts) {
+            self.tenant_meta
+                .update_alert_state(&rule.name, alerts.clone())
+                .await?;
+        }
+        // the committed state is replaced by the alerts of this evaluation
+        self.state_store
+            .put(alerts_to_record_batch(rule, &alerts)?)?;
+        self.state_store.commit()?;
+
+        Ok(())
+    }
+}
+
+/// Whether the alerts changed other than by their values and annotations, which change on
+/// almost every evaluation and are not worth saving in meta.
+pub fn state_changed(previous: &[Alert], alerts: &[Alert]) -> bool {
+    previous.len() != alerts.len()
+        || previous.iter().zip(alerts).any(|(a, b)| {
+            a.labels != b.labels
+                || a.state != b.state
+                || a.active_at != b.active_at
+                || a.fired_at != b.fired_at
+                || a.resolved_at != b.resolved_at
+                || a.last_sent_at != b.last_sent_at
+        })
+}
+
+/// An alert returned by the query of the rule in this evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveAlert {
+    pub labels: BTreeMap<String, String>,
+    pub value: Option<f64>,
+}
+
+/// Every row of the query is an active alert, labeled by the labels of the rule and the
+/// non-null columns of the row, except the value column.
+pub fn active_alerts(rule: &AlertRule, batches: &[RecordBatch]) -> DFResult<Vec<ActiveAlert>> {
+    let rule_labels = rule.rule_labels();
+    let mut alerts = vec![];
+    for batch in batches {
+        let schema = batch.schema();
+        let value_idx = schema
+            .fields()
+            .iter()
+            .position(|f| f.name().eq_ignore_ascii_case(ALERT_VALUE_COLUMN));
+        let values = value_idx
+            .map(|i| cast(batch.column(i), &DataType::Float64))
+            .transpose()?;
+        let values = values
+            .as_ref()
+            .map(|v| as_float64_array(v.as_ref()))
+            .transpose()?;
+
+        for row in 0..batch.num_rows() {
+            let mut labels = BTreeMap::new();
+            for (i, field) in schema.fields().iter().enumerate() {
+                let column = batch.column(i);
+                if Some(i) == value_idx || column.is_null(row) {
+                    continue;
+                }
+                labels.insert(field.name().clone(), array_value_to_string(column, row)?);
+            }
+            // the labels of the rule take precedence
+            labels.extend(rule_labels.clone());
+
+            let value = values.and_then(|v| v.is_valid(row).then(|| v.value(row)));
+            alerts.push(ActiveAlert { labels, value });
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Moves the alerts of the last evaluation to their next state:
+///
+/// - a new active alert is pending, or firing at once if the rule has no FOR duration.
+/// - a pending alert fires once it has been active for the FOR duration.
+/// - a firing alert no longer active is resolved, a pending one is dropped.
+/// - a resolved alert is kept for [`RESOLVED_RETENTION`], unless it becomes active again.
+pub fn next_alerts(
+    rule: &AlertRule,
+    previous: Vec<Alert>,
+    active: Vec<ActiveAlert>,
+    now: i64,
+) -> Vec<Alert> {
+    let for_duration = rule.for_duration.as_nanos() as i64;
+    let mut previous = previous
+        .into_iter()
+        .map(|e| (e.labels.clone(), e))
+        .collect::<BTreeMap<_, _>>();
+    // the rows with the same labels are the same alert
+    let active = active
+        .into_iter()
+        .map(|e| (e.labels, e.value))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut alerts = vec![];
+    for (labels, value) in active {
+        let annotations = rule
+            .annotations
+            .iter()
+            .map(|(k, v)| (k.clone(), expand_template(v, &labels, value)))
+            .collect();
+
+        let mut alert = match previous.remove(&labels) {
+            Some(alert) if alert.state != AlertState::Resolved => alert,
+            _ => Alert {
+                labels,
+                annotations: BTreeMap::new(),
+                state: AlertState::Pending,
+                value: None,
+                active_at: now,
+                fired_at: None,
+                resolved_at: None,
+                last_sent_at: None,
+            },
+        };
+        alert.annotations = annotations;
+        alert.value = value;
+        if alert.state == AlertState::Pending && now - alert.active_at >= for_duration {
+            alert.state = AlertState::Firing;
+            alert.fired_at = Some(now);
+        }
+        alerts.push(alert);
+    }
+
+    for (_, mut alert) in previous {
+        match alert.state {
+            AlertState::Pending => {}
+            AlertState::Firing => {
+                alert.state = AlertState::Resolved;
+                alert.resolved_at = Some(now);
+                alerts.push(alert);
+            }
+            AlertState::Resolved => {
+                let resolved_at = alert.resolved_at.unwrap_or(now);
+                if now - resolved_at < RESOLVED_RETENTION.as_nanos() as i64 {
+                    alerts.push(alert);
+                }
+            }
+        }
+    }
+
+    alerts
+}
+
+/// The indexes of the alerts to deliver: the alerts which just fired or resolved,
+/// and the firing alerts not delivered for [`RESEND_DELAY`].
+pub fn alerts_to_send(alerts: &[Alert], now: i64) -> Vec<usize> {
+    let resend_delay = RESEND_DELAY.as_nanos() as i64;
+    alerts
+        .iter()
+        .enumerate()
+        .filter(|(_, alert)| match (alert.state, alert.last_sent_at) {
+            (AlertState::Pending, _) => false,
+            (_, None) => true,
+            (AlertState::Firing, Some(sent_at)) => {
+                alert.fired_at.map_or(false, |t| sent_at < t) || now - sent_at >= resend_delay
+            }
+            (AlertState::Resolved, Some(sent_at)) => {
+                alert.resolved_at.map_or(false, |t| sent_at < t)
+            }
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Expands `{{ $labels.<name> }}` and `{{ $value }}` in the annotation, other templates are
+/// kept as they are.
+pub fn expand_template(
+    template: &str,
+    labels: &BTreeMap<String, String>,
+    value: Option<f64>,
+) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let expr = rest[start + 2..start + len].trim();
+        if expr == "$value" {
+            if let Some(value) = value {
+                result.push_str(&value.to_string());
+            }
+        } else if let Some(name) = expr.strip_prefix("$labels.") {
+            if let Some(label) = labels.get(name) {
+                result.push_str(label);
+            }
+        } else {
+            result.push_str(&rest[start..start + len + 2]);
+        }
+        rest = &rest[start + len + 2..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, Int64Array, StringArray};
+    use datafusion::arrow::datatypes::{Field, Schema};
+
+    use super::*;
+    use crate::alert::test_alert_rule;
+
+    const SECOND: i64 = 1_000_000_000;
+
+    fn active(host: &str, value: f64) -> ActiveAlert {
+        let mut labels = test_alert_rule(Duration::ZERO).rule_labels();
+        labels.insert("host".to_string(), host.to_string());
+        ActiveAlert {
+            labels,
+            value: Some(value),
+        }
+    }
+
+    #[test]
+    fn test_active_alerts() {
+        let rule = test_alert_rule(Duration::ZERO);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("VALUE", DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec![Some("a"), None])),
+                Arc::new(Int64Array::from(vec![Some(95), None])),
+            ],
+        )
+        .unwrap();
+
+        let alerts = active_alerts(&rule, &[batch]).unwrap();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0], active("a", 95.0));
+        assert_eq!(alerts[1].labels, rule.rule_labels());
+        assert_eq!(alerts[1].value, None);
+
+        // the labels of the rule can not be overwritten
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("alertname", DataType::Utf8, false),
+            Field::new("usage", DataType::Float64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["other"])),
+                Arc::new(Float64Array::from(vec![1.5])),
+            ],
+        )
+        .unwrap();
+        let alerts = active_alerts(&rule, &[batch]).unwrap();
+        assert_eq!(alerts[0].labels["alertname"], "cpu_high");
+        assert_eq!(alerts[0].labels["usage"], "1.5");
+        assert_eq!(alerts[0].value, None);
+    }
+
+    #[test]
+    fn test_pending_firing_resolved() {
+        let rule = test_alert_rule(Duration::from_secs(120));
+
+        // t0: a and b become pending
+        let alerts = next_alerts(&rule, vec![], vec![active("a", 1.0), active("b", 2.0)], 0);
+        assert_eq!(alerts.len(), 2);
+        assert!(alerts.iter().all(|e| e.state == AlertState::Pending));
+        assert!(alerts_to_send(&alerts, 0).is_empty());
+
+        // t60: a is still pending, b is no longer active and dropped
+        let alerts = next_alerts(&rule, alerts, vec![active("a", 3.0)], 60 * SECOND);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].state, AlertState::Pending);
+        assert_eq!(alerts[0].value, Some(3.0));
+
+        // t120: a has been active for FOR duration and fires
+        let mut alerts = next_alerts(&rule, alerts, vec![active("a", 4.0)], 120 * SECOND);
+        assert_eq!(alerts[0].state, AlertState::Firing);
+        assert_eq!(alerts[0].active_at, 0);
+        assert_eq!(alerts[0].fired_at, Some(120 * SECOND));
+        assert_eq!(alerts_to_send(&alerts, 120 * SECOND), vec![0]);
+        alerts[0].last_sent_at = Some(120 * SECOND);
+
+        // t150: a keeps firing, sent again only after the resend delay
+        let alerts = next_alerts(&rule, alerts, vec![active("a", 5.0)], 150 * SECOND);
+        assert_eq!(alerts[0].state, AlertState::Firing);
+        assert!(alerts_to_send(&alerts, 150 * SECOND).is_empty());
+        assert_eq!(alerts_to_send(&alerts, 180 * SECOND), vec![0]);
+
+        // t200: a resolves
+        let alerts = next_alerts(&rule, alerts, vec![], 200 * SECOND);
+        assert_eq!(alerts[0].state, AlertState::Resolved);
+        assert_eq!(alerts[0].resolved_at, Some(200 * SECOND));
+        assert_eq!(alerts_to_send(&alerts, 200 * SECOND), vec![0]);
+
+        // a becomes active again, a new pending alert
+        let alerts = next_alerts(&rule, alerts.clone(), vec![active("a", 6.0)], 260 * SECOND);
+        assert_eq!(alerts[0].state, AlertState::Pending);
+        assert_eq!(alerts[0].active_at, 260 * SECOND);
+
+        // a pending alert no longer active is dropped
+        let alerts = next_alerts(&rule, alerts, vec![], 270 * SECOND);
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_fire_without_for_duration() {
+        let rule = test_alert_rule(Duration::ZERO);
+        let alerts = next_alerts(&rule, vec![], vec![active("a", 1.0)], 0);
+        assert_eq!(alerts[0].state, AlertState::Firing);
+        assert_eq!(alerts[0].fired_at, Some(0));
+
+        let alerts = next_alerts(&rule, alerts, vec![], SECOND);
+        let kept = next_alerts(&rule, alerts.clone(), vec![], 10 * 60 * SECOND);
+        assert_eq!(kept.len(), 1);
+        let dropped = next_alerts(&rule, alerts, vec![], 16 * 60 * SECOND);
+        assert!(dropped.is_empty());
+    }
+
+    #[test]
+    fn test_state_changed() {
+        let rule = test_alert_rule(Duration::from_secs(120));
+        let alerts = next_alerts(&rule, vec![], vec![active("a", 1.0)], 0);
+        assert!(state_changed(&[], &alerts));
+
+        // only the value changes
+        let next = next_alerts(&rule, alerts.clone(), vec![active("a", 2.0)], 60 * SECOND);
+        assert!(!state_changed(&alerts, &next));
+
+        // the alert fires
+        let fired = next_alerts(&rule, next.clone(), vec![active("a", 3.0)], 120 * SECOND);
+        assert!(state_changed(&next, &fired));
+    }
+
+    #[test]
+    fn test_expand_template() {
+        let labels = BTreeMap::from([("host".to_string(), "a".to_string())]);
+        assert_eq!(
+            expand_template(
+                "cpu of {{ $labels.host }} is {{$value}}",
+                &labels,
+                Some(95.5)
+            ),
+            "cpu of a is 95.5"
+        );
+        assert_eq!(
+            expand_template("{{ $labels.region }}{{ other }}{{", &labels, None),
+            "{{ other }}{{"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Evaluates the alert rules created by `CREATE ALERT RULE`.
+//!
+//! Every node evaluates the rules created on it, and the rules of the unreachable nodes are
+//! spread over the other nodes. The rules are scheduled by the stream trigger executor every
+//! `EVERY` interval. The alerts of a rule are kept in its state store and saved in meta, and
+//! delivered to the webhook as the payload of Prometheus Alertmanager.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use meta::model::MetaClientRef;
+use models::alert::{Alert, AlertRule};
+use models::meta_data::{NodeId, NodeMetrics};
+use models::node_info::NodeStatus;
+use models::oid::{Identifier, Oid};
+use models::runtime::executor::{DedicatedExecutor, Job};
+use parking_lot::RwLock;
+use spi::query::config::StreamTriggerInterval;
+use spi::query::dispatcher::QueryDispatcher;
+use spi::Result;
+use trace::{error, info, warn};
+use tskv::kv_option::QueryOptions;
+use utils::FnvHasher;
+
+use self::evaluator::AlertRuleEvaluator;
+use self::state::alerts_to_record_batch;
+use self::webhook::WebhookSender;
+use crate::execution::stream::trigger::executor::TriggerExecutorFactory;
+use crate::stream::state_store::memory::{MemoryStateStore, MemoryStateStoreFactory};
+use crate::stream::state_store::{StateStore, StateStoreFactory};
+
+pub mod evaluator;
+pub mod state;
+pub mod webhook;
+
+pub type AlertManagerRef = Arc<AlertManager>;
+
+struct RunningAlertRule {
+    rule: AlertRule,
+    state_store: Arc<MemoryStateStore>,
+    // stop the evaluation when dropped
+    _job: Job<()>,
+}
+
+pub struct AlertManager {
+    coord: CoordinatorRef,
+    default_webhook: Option<String>,
+    sync_interval: Duration,
+    sender: Arc<WebhookSender>,
+    trigger_executor_factory: TriggerExecutorFactory,
+    runtime: Arc<DedicatedExecutor>,
+    state_store_factory: MemoryStateStoreFactory,
+    // (tenant, rule name) -> rule
+    rules: RwLock<HashMap<(String, String), RunningAlertRule>>,
+}
+
+impl AlertManager {
+    pub fn new(coord: CoordinatorRef, options: &QueryOptions) -> Self {
+        // Only do periodic scheduling, no need for many threads
+        let trigger_executor_factory =
+            TriggerExecutorFactory::new(Arc::new(DedicatedExecutor::new("alert-trigger", 1)));
+        let runtime = Arc::new(DedicatedExecutor::new("alert-executor", 1));
+        let default_webhook =
+            (!options.alert_webhook.is_empty()).then(|| options.alert_webhook.clone());
+
+        Self {
+            coord,
+            default_webhook,
+            sync_interval: options.alert_sync_interval,
+            sender: Arc::new(WebhookSender::default()),
+            trigger_executor_factory,
+            runtime,
+            state_store_factory: MemoryStateStoreFactory::default(),
+            rules: Default::default(),
+        }
+    }
+
+    /// Loads the alert rules of this node from meta every sync interval,
+    /// and evaluates them by running their queries on `dispatcher`.
+    pub fn start(self: &Arc<Self>, dispatcher: Arc<dyn QueryDispatcher>) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(manager.sync_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(err) = manager.sync_rules(&dispatcher).await {
+                    warn!("Failed to load alert rules: {}", err);
+                }
+            }
+        });
+    }
+
+    async fn sync_rules(&self, dispatcher: &Arc<dyn QueryDispatcher>) -> Result<()> {
+        let node_id = self.coord.node_id();
+        let node_metrics = self.coord.meta_manager().node_metrics().await?;
+        let mut rules = HashMap::new();
+        for tenant in self.coord.meta_manager().tenants().await? {
+            let Some(meta) = self.coord.tenant_meta(tenant.name()).await else {
+                continue;
+            };
+            for rule in meta.alert_rules().await? {
+                if evaluating_node(&rule, &node_metrics) == Some(node_id) {
+                    let key = (rule.tenant.clone(), rule.name.clone());
+                    let _ = rules.insert(key, (*tenant.id(), meta.clone(), rule));
+                }
+            }
+        }
+
+        let to_start = {
+            let mut running = self.rules.write();
+            running.retain(|key, e| {
+                let retain = rules.get(key).is_some_and(|(_, _, rule)| *rule == e.rule);
+                if !retain {
+                    info!("Stop alert rule {} of tenant {}", key.1, key.0);
+                    self.state_store_factory
+                        .remove(&state_store_id(&key.0, &key.1), 0, 0);
+                }
+                retain
+            });
+            rules
+                .into_iter()
+                .filter(|(key, _)| !running.contains_key(key))
+                .collect::<Vec<_>>()
+        };
+        for (key, (tenant_id, meta, rule)) in to_start {
+            info!("Start alert rule {} of tenant {}", key.1, key.0);
+            // the alerts saved by the node evaluating the rule before
+            let alerts = meta.alert_state(&rule.name).await?;
+            let running_rule = self.schedule(rule, tenant_id, meta, alerts, dispatcher.clone())?;
+            let _ = self.rules.write().insert(key, running_rule);
+        }
+
+        Ok(())
+    }
+
+    fn schedule(
+        &self,
+        rule: AlertRule,
+        tenant_id: Oid,
+        tenant_meta: MetaClientRef,
+        alerts: Vec<Alert>,
+        dispatcher: Arc<dyn QueryDispatcher>,
+    ) -> Result<RunningAlertRule> {
+        let state_store = self.state_store_factory.get_or_default(
+            state_store_id(&rule.tenant, &rule.name),
+            0,
+            0,
+        )?;
+        if !alerts.is_empty() {
+            state_store.put(alerts_to_record_batch(&rule, &alerts)?)?;
+            state_store.commit()?;
+        }
+        let evaluator = Arc::new(AlertRuleEvaluator::new(
+            rule.clone(),
+            tenant_id,
+            self.coord.meta_manager(),
+            tenant_meta,
+            self.default_webhook.clone(),
+            dispatcher,
+            state_store.clone(),
+            self.sender.clone(),
+        ));
+
+        let trigger_executor = self
+            .trigger_executor_factory
+            .create(&StreamTriggerInterval::Interval(rule.every));
+        let name = rule.name.clone();
+        let job = trigger_executor.schedule(
+            move |_| {
+                let evaluator = evaluator.clone();
+                let name = name.clone();
+                async move {
+                    evaluator.evaluate().await.map_err(|err| {
+                        error!("Evaluate alert rule {name} error: {err}");
+                        err
+                    })
+                }
+            },
+            self.runtime.clone(),
+        );
+
+        Ok(RunningAlertRule {
+            rule,
+            state_store,
+            _job: job,
+        })
+    }
+
+    /// The alert rules of `tenant` evaluated by this node, with their alerts
+    /// in record batches of [`state::ALERTS_SCHEMA`].
+    pub fn alert_states(&self, tenant: &str) -> DFResult<Vec<(AlertRule, Vec<RecordBatch>)>> {
+        let mut states = vec![];
+        for ((rule_tenant, _), running) in self.rules.read().iter() {
+            if rule_tenant == tenant {
+                states.push((running.rule.clone(), running.state_store.state()?));
+            }
+        }
+
+        Ok(states)
+    }
+}
+
+impl Drop for AlertManager {
+    fn drop(&mut self) {
+        self.runtime.shutdown();
+    }
+}
+
+fn state_store_id(tenant: &str, rule_name: &str) -> String {
+    format!("alert_rule.{tenant}.{rule_name}")
+}
+
+/// The node evaluating the rule, which is the node the rule was created on. While that node
+/// is unreachable, the rule is evaluated by one of the other nodes picked by the name of the
+/// rule, so that all the nodes pick the same one.
+fn evaluating_node(rule: &AlertRule, node_metrics: &[NodeMetrics]) -> Option<NodeId> {
+    let unreachable = node_metrics
+        .iter()
+        .any(|m| m.id == rule.node_id && m.status == NodeStatus::Unreachable);
+    if !unreachable {
+        return Some(rule.node_id);
+    }
+
+    let mut nodes = node_metrics
+        .iter()
+        .filter(|m| m.status != NodeStatus::Unreachable)
+        .map(|m| m.id)
+        .collect::<Vec<_>>();
+    if nodes.is_empty() {
+        return None;
+    }
+    nodes.sort_unstable();
+    // the hash is the same on all the nodes
+    let hash = FnvHasher::new()
+        .hash_with(format!("{}.{}", rule.tenant, rule.name).as_bytes())
+        .number();
+    Some(nodes[(hash % nodes.len() as u64) as usize])
+}
+
+#[cfg(test)]
+pub(crate) fn test_alert_rule(for_duration: Duration) -> AlertRule {
+    use std::collections::BTreeMap;
+
+    AlertRule {
+        tenant: "cnosdb".to_string(),
+        name: "cpu_high".to_string(),
+        database: "public".to_string(),
+        owner: "root".to_string(),
+        query: "SELECT host, usage AS value FROM cpu".to_string(),
+        every: Duration::from_secs(60),
+        for_duration,
+        severity: "critical".to_string(),
+        labels: BTreeMap::from([("team".to_string(), "db".to_string())]),
+        annotations: BTreeMap::new(),
+        webhook: None,
+        node_id: 1001,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use models::meta_data::NodeMetrics;
+    use models::node_info::NodeStatus;
+
+    use super::{evaluating_node, test_alert_rule};
+
+    fn metrics(id: u64, status: NodeStatus) -> NodeMetrics {
+        NodeMetrics {
+            id,
+            disk_free: 0,
+            time: 0,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_evaluating_node() {
+        let rule = test_alert_rule(Duration::ZERO);
+        let healthy = vec![
+            metrics(1001, NodeStatus::Healthy),
+            metrics(1002, NodeStatus::Healthy),
+            metrics(1003, NodeStatus::Healthy),
+        ];
+        assert_eq!(evaluating_node(&rule, &healthy), Some(1001));
+        // the nodes without metrics are not known to be unreachable
+        assert_eq!(evaluating_node(&rule, &[]), Some(1001));
+
+        let mut failed = healthy.clone();
+        failed[0].status = NodeStatus::Unreachable;
+        let node = evaluating_node(&rule, &failed).unwrap();
+        assert!(node == 1002 || node == 1003);
+        // the same node is picked whatever the order of the metrics
+        failed.reverse();
+        assert_eq!(evaluating_node(&rule, &failed), Some(node));
+
+        let unreachable = vec![metrics(1001, NodeStatus::Unreachable)];
+        assert_eq!(evaluating_node(&rule, &unreachable), None);
+    }
+}
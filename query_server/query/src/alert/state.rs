@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, Float64Builder, StringBuilder, TimestampNanosecondBuilder,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::cast::{as_float64_array, as_string_array, as_timestamp_nanosecond_array};
+use datafusion::common::{DataFusionError, Result as DFResult};
+use lazy_static::lazy_static;
+use models::alert::{Alert, AlertRule};
+
+pub const ALERTS_TENANT_NAME: &str = "tenant_name";
+pub const ALERTS_RULE_NAME: &str = "rule_name";
+pub const ALERTS_DATABASE_NAME: &str = "database_name";
+pub const ALERTS_SEVERITY: &str = "severity";
+pub const ALERTS_STATE: &str = "state";
+pub const ALERTS_LABELS: &str = "labels";
+pub const ALERTS_ANNOTATIONS: &str = "annotations";
+pub const ALERTS_VALUE: &str = "value";
+pub const ALERTS_ACTIVE_AT: &str = "active_at";
+pub const ALERTS_FIRED_AT: &str = "fired_at";
+pub const ALERTS_RESOLVED_AT: &str = "resolved_at";
+pub const ALERTS_LAST_SENT_AT: &str = "last_sent_at";
+
+lazy_static! {
+    /// The schema of the alerts kept in the state store of an alert rule,
+    /// which is also the schema of `information_schema.ALERTS`.
+    pub static ref ALERTS_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(ALERTS_TENANT_NAME, DataType::Utf8, false),
+        Field::new(ALERTS_RULE_NAME, DataType::Utf8, false),
+        Field::new(ALERTS_DATABASE_NAME, DataType::Utf8, false),
+        Field::new(ALERTS_SEVERITY, DataType::Utf8, false),
+        Field::new(ALERTS_STATE, DataType::Utf8, false),
+        Field::new(ALERTS_LABELS, DataType::Utf8, false),
+        Field::new(ALERTS_ANNOTATIONS, DataType::Utf8, false),
+        Field::new(ALERTS_VALUE, DataType::Float64, true),
+        Field::new(ALERTS_ACTIVE_AT, timestamp_type(), false),
+        Field::new(ALERTS_FIRED_AT, timestamp_type(), true),
+        Field::new(ALERTS_RESOLVED_AT, timestamp_type(), true),
+        Field::new(ALERTS_LAST_SENT_AT, timestamp_type(), true),
+    ]));
+}
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Nanosecond, None)
+}
+
+/// Converts the alerts of `rule` to a record batch of [`ALERTS_SCHEMA`],
+/// the labels and annotations are encoded as json objects.
+pub fn alerts_to_record_batch(rule: &AlertRule, alerts: &[Alert]) -> DFResult<RecordBatch> {
+    let mut tenant_names = StringBuilder::new();
+    let mut rule_names = StringBuilder::new();
+    let mut database_names = StringBuilder::new();
+    let mut severities = StringBuilder::new();
+    let mut states = StringBuilder::new();
+    let mut labels = StringBuilder::new();
+    let mut annotations = StringBuilder::new();
+    let mut values = Float64Builder::new();
+    let mut active_ats = TimestampNanosecondBuilder::new();
+    let mut fired_ats = TimestampNanosecondBuilder::new();
+    let mut resolved_ats = TimestampNanosecondBuilder::new();
+    let mut last_sent_ats = TimestampNanosecondBuilder::new();
+
+    for alert in alerts {
+        tenant_names.append_value(&rule.tenant);
+        rule_names.append_value(&rule.name);
+        database_names.append_value(&rule.database);
+        severities.append_value(&rule.severity);
+        states.append_value(alert.state.as_str());
+        labels.append_value(to_json(&alert.labels)?);
+        annotations.append_value(to_json(&alert.annotations)?);
+        values.append_option(alert.value);
+        active_ats.append_value(alert.active_at);
+        fired_ats.append_option(alert.fired_at);
+        resolved_ats.append_option(alert.resolved_at);
+        last_sent_ats.append_option(alert.last_sent_at);
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(tenant_names.finish()),
+        Arc::new(rule_names.finish()),
+        Arc::new(database_names.finish()),
+        Arc::new(severities.finish()),
+        Arc::new(states.finish()),
+        Arc::new(labels.finish()),
+        Arc::new(annotations.finish()),
+        Arc::new(values.finish()),
+        Arc::new(active_ats.finish()),
+        Arc::new(fired_ats.finish()),
+        Arc::new(resolved_ats.finish()),
+        Arc::new(last_sent_ats.finish()),
+    ];
+
+    Ok(RecordBatch::try_new(ALERTS_SCHEMA.clone(), columns)?)
+}
+
+/// Converts the record batches of [`ALERTS_SCHEMA`] back to the alerts.
+pub fn record_batches_to_alerts(batches: &[RecordBatch]) -> DFResult<Vec<Alert>> {
+    let mut alerts = vec![];
+    for batch in batches {
+        let states = as_string_array(batch.column(4))?;
+        let labels = as_string_array(batch.column(5))?;
+        let annotations = as_string_array(batch.column(6))?;
+        let values = as_float64_array(batch.column(7))?;
+        let active_ats = as_timestamp_nanosecond_array(batch.column(8))?;
+        let fired_ats = as_timestamp_nanosecond_array(batch.column(9))?;
+        let resolved_ats = as_timestamp_nanosecond_array(batch.column(10))?;
+        let last_sent_ats = as_timestamp_nanosecond_array(batch.column(11))?;
+
+        for row in 0..batch.num_rows() {
+            alerts.push(Alert {
+                labels: from_json(labels.value(row))?,
+                annotations: from_json(annotations.value(row))?,
+                state: states
+                    .value(row)
+                    .parse()
+                    .map_err(DataFusionError::Internal)?,
+                value: values.is_valid(row).then(|| values.value(row)),
+                active_at: active_ats.value(row),
+                fired_at: fired_ats.is_valid(row).then(|| fired_ats.value(row)),
+                resolved_at: resolved_ats.is_valid(row).then(|| resolved_ats.value(row)),
+                last_sent_at: last_sent_ats
+                    .is_valid(row)
+                    .then(|| last_sent_ats.value(row)),
+            });
+        }
+    }
+
+    Ok(alerts)
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> DFResult<String> {
+    serde_json::to_string(value).map_err(|e| DataFusionError::External(Box::new(e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(value: &str) -> DFResult<T> {
+    serde_json::from_str(value).map_err(|e| DataFusionError::External(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    use models::alert::AlertState;
+
+    use super::*;
+    use crate::alert::test_alert_rule;
+
+    #[test]
+    fn test_alerts_record_batch_round_trip() {
+        let rule = test_alert_rule(Duration::ZERO);
+        let alerts = vec![
+            Alert {
+                labels: BTreeMap::from([("host".to_string(), "a".to_string())]),
+                annotations: BTreeMap::from([("summary".to_string(), "high".to_string())]),
+                state: AlertState::Firing,
+                value: Some(95.5),
+                active_at: 1,
+                fired_at: Some(2),
+                resolved_at: None,
+                last_sent_at: Some(3),
+            },
+            Alert {
+                labels: BTreeMap::from([("host".to_string(), "b".to_string())]),
+                annotations: BTreeMap::new(),
+                state: AlertState::Pending,
+                value: None,
+                active_at: 4,
+                fired_at: None,
+                resolved_at: None,
+                last_sent_at: None,
+            },
+        ];
+
+        let batch = alerts_to_record_batch(&rule, &alerts).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(record_batches_to_alerts(&[batch]).unwrap(), alerts);
+    }
+}
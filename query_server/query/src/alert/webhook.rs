@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use models::alert::{Alert, AlertState};
+use serde::{Deserialize, Serialize};
+use spi::{QueryError, Result};
+
+use super::evaluator::RESEND_DELAY;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An alert in the payload posted by Prometheus to Alertmanager (`POST /api/v2/alerts`),
+/// the payload is a json array of these alerts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertmanagerAlert {
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    pub starts_at: String,
+    /// The resolved time of the resolved alerts. Like Prometheus, the firing alerts
+    /// end a few resend intervals later, unless they are delivered again.
+    pub ends_at: String,
+}
+
+impl AlertmanagerAlert {
+    pub fn new(alert: &Alert, now: i64) -> Self {
+        let ends_at = match (alert.state, alert.resolved_at) {
+            (AlertState::Resolved, Some(resolved_at)) => resolved_at,
+            _ => now + 4 * RESEND_DELAY.as_nanos() as i64,
+        };
+
+        Self {
+            labels: alert.labels.clone(),
+            annotations: alert.annotations.clone(),
+            starts_at: rfc3339(alert.fired_at.unwrap_or(alert.active_at)),
+            ends_at: rfc3339(ends_at),
+        }
+    }
+}
+
+fn rfc3339(nanos: i64) -> String {
+    Utc.timestamp_nanos(nanos)
+        .to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Posts the alerts to the webhooks as json.
+#[derive(Debug, Default)]
+pub struct WebhookSender {
+    client: reqwest::Client,
+}
+
+impl WebhookSender {
+    pub async fn send(&self, url: &str, alerts: &[AlertmanagerAlert]) -> Result<()> {
+        let err = |reason: String| QueryError::SendAlertNotification {
+            url: url.to_string(),
+            reason,
+        };
+
+        let response = self
+            .client
+            .post(url)
+            .timeout(SEND_TIMEOUT)
+            .json(alerts)
+            .send()
+            .await
+            .map_err(|e| err(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(err(format!("response status {status}")));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Accepts one http request, responds with `status` and returns the body of the request
+    async fn receive_one(listener: TcpListener, status: &str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = vec![];
+        let mut buf = [0_u8; 4096];
+        let body_start = loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+        let headers = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+        let content_length = headers
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length:"))
+            .map(|l| l.trim().parse::<usize>().unwrap())
+            .unwrap();
+        while request.len() < body_start + content_length {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+        }
+
+        let response = format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\n\r\n");
+        stream.write_all(response.as_bytes()).await.unwrap();
+
+        String::from_utf8(request[body_start..].to_vec()).unwrap()
+    }
+
+    fn firing_alert() -> Alert {
+        Alert {
+            labels: BTreeMap::from([
+                ("alertname".to_string(), "cpu_high".to_string()),
+                ("host".to_string(), "a".to_string()),
+            ]),
+            annotations: BTreeMap::from([("summary".to_string(), "cpu of a is 95".to_string())]),
+            state: AlertState::Firing,
+            value: Some(95.0),
+            active_at: 0,
+            fired_at: Some(1_000_000_000),
+            resolved_at: None,
+            last_sent_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_to_local_receiver() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/v2/alerts", listener.local_addr().unwrap());
+        let receiver = tokio::spawn(receive_one(listener, "200 OK"));
+
+        let mut resolved = firing_alert();
+        resolved.state = AlertState::Resolved;
+        resolved.resolved_at = Some(2_000_000_000);
+        let alerts = vec![
+            AlertmanagerAlert::new(&firing_alert(), 1_000_000_000),
+            AlertmanagerAlert::new(&resolved, 2_000_000_000),
+        ];
+        WebhookSender::default().send(&url, &alerts).await.unwrap();
+
+        let body = receiver.await.unwrap();
+        let received: Vec<AlertmanagerAlert> = serde_json::from_str(&body).unwrap();
+        assert_eq!(received, alerts);
+        assert_eq!(received[0].starts_at, "1970-01-01T00:00:01.000Z");
+        assert_eq!(received[0].ends_at, "1970-01-01T00:04:01.000Z");
+        assert_eq!(received[1].ends_at, "1970-01-01T00:00:02.000Z");
+        assert!(body.contains("\"startsAt\""));
+    }
+
+    #[tokio::test]
+    async fn test_send_rejected() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/v2/alerts", listener.local_addr().unwrap());
+        let receiver = tokio::spawn(receive_one(listener, "500 Internal Server Error"));
+
+        let alerts = vec![AlertmanagerAlert::new(&firing_alert(), 1_000_000_000)];
+        let result = WebhookSender::default().send(&url, &alerts).await;
+        receiver.await.unwrap();
+
+        assert!(matches!(
+            result,
+            Err(QueryError::SendAlertNotification { .. })
+        ));
+    }
+}
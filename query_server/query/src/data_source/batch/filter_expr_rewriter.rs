@@ -2,9 +2,12 @@ use datafusion::common::tree_node::{TreeNode, TreeNodeRewriter, TreeNodeVisitor,
 use datafusion::common::{DFSchemaRef, ScalarValue};
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::ExecutionProps;
+use datafusion::logical_expr::expr::ScalarUDF;
 use datafusion::logical_expr::{Expr, Operator};
 use datafusion::optimizer::simplify_expressions::{ExprSimplifier, SimplifyContext};
-use datafusion::optimizer::utils::conjunction;
+use datafusion::optimizer::utils::{conjunction, split_conjunction};
+use models::gis::bbox::BoundingBox;
+use models::predicate::domain::SpatialFilter;
 
 pub fn is_udf_function(expr: &Expr) -> bool {
     matches!(expr, Expr::ScalarUDF(_) | Expr::AggregateUDF(_))
@@ -43,6 +46,57 @@ pub fn rewrite_filters(filters: &[Expr], df_schema: DFSchemaRef) -> Result<Optio
     }
 }
 
+/// Extracts the spatial predicates between a column and a geometry literal from the
+/// conjunctions of the filters, which are removed by `rewrite_filters`, so that
+/// the storage can prune the pages of the geometry column by the bounding boxes:
+///
+/// - `st_within(col, geo)`, `st_intersects(col, geo)`, `st_intersects(geo, col)`
+///   and `st_contains(geo, col)`: the column must intersect the bounding box of `geo`
+/// - `st_dwithin(col, geo, d)` and `st_dwithin(geo, col, d)`: the column must
+///   intersect the bounding box of `geo` expanded by `d`
+pub fn extract_spatial_filters(filters: &[Expr]) -> Vec<SpatialFilter> {
+    filters
+        .iter()
+        .flat_map(split_conjunction)
+        .filter_map(|expr| match expr {
+            Expr::ScalarUDF(ScalarUDF { fun, args }) => {
+                spatial_filter(&fun.name.to_ascii_lowercase(), args)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn spatial_filter(func_name: &str, args: &[Expr]) -> Option<SpatialFilter> {
+    let column_and_bbox = |column: &Expr, geo: &Expr| match (column, geo) {
+        (Expr::Column(c), Expr::Literal(ScalarValue::Utf8(Some(wkt)))) => {
+            BoundingBox::from_wkt(wkt).map(|bbox| (c.name.clone(), bbox))
+        }
+        _ => None,
+    };
+
+    let (column, bbox) = match (func_name, args) {
+        ("st_within", [column, geo]) => column_and_bbox(column, geo)?,
+        ("st_contains", [geo, column]) => column_and_bbox(column, geo)?,
+        ("st_intersects", [l, r]) => column_and_bbox(l, r).or_else(|| column_and_bbox(r, l))?,
+        ("st_dwithin", [l, r, Expr::Literal(distance)]) => {
+            let distance = match distance {
+                ScalarValue::Float64(Some(d)) => *d,
+                ScalarValue::Int64(Some(d)) => *d as f64,
+                _ => return None,
+            };
+            if !distance.is_finite() || distance < 0.0 {
+                return None;
+            }
+            let (column, bbox) = column_and_bbox(l, r).or_else(|| column_and_bbox(r, l))?;
+            (column, bbox.expand(distance))
+        }
+        _ => return None,
+    };
+
+    Some(SpatialFilter::new(column, bbox))
+}
+
 // Visitor expr if has udf expr
 #[derive(Default)]
 pub struct UDFVisitor {
@@ -21,11 +21,13 @@ use datafusion::physical_plan::{project_schema, ExecutionPlan};
 use datafusion::prelude::Column;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
-use models::predicate::domain::{Predicate, PredicateRef, PushedAggregateFunction};
-use models::schema::{TskvTableSchema, TskvTableSchemaRef, TIME_FIELD_NAME};
+use models::predicate::domain::{LastValueScan, Predicate, PredicateRef, PushedAggregateFunction};
+use models::schema::{Precision, TskvTableSchema, TskvTableSchemaRef, TIME_FIELD_NAME};
 use trace::debug;
 
-use crate::data_source::batch::filter_expr_rewriter::{has_udf_function, rewrite_filters};
+use crate::data_source::batch::filter_expr_rewriter::{
+    extract_spatial_filters, has_udf_function, rewrite_filters,
+};
 use crate::data_source::sink::tskv::TskvRecordBatchSinkProvider;
 use crate::data_source::split::tskv::TableLayoutHandle;
 use crate::data_source::split::SplitManagerRef;
@@ -43,6 +45,10 @@ pub struct ClusterTable {
     split_manager: SplitManagerRef,
     _meta: MetaClientRef,
     schema: TskvTableSchemaRef,
+    // also read the last point of each series before this timestamp in nanoseconds
+    prior_point: Option<i64>,
+    // read the latest points of the series from the last value caches
+    last_value: Option<LastValueScan>,
 }
 
 impl ClusterTable {
@@ -231,9 +237,37 @@ impl ClusterTable {
             split_manager,
             _meta: meta,
             schema,
+            prior_point: None,
+            last_value: None,
+        }
+    }
+
+    /// Returns a table which also scans the last point of each series before `before_ns`,
+    /// it is used to fill the gaps at the beginning of the time range of the filters.
+    pub fn with_prior_point(&self, before_ns: i64) -> Self {
+        Self {
+            prior_point: Some(before_ns),
+            ..self.clone()
         }
     }
 
+    pub fn prior_point(&self) -> Option<i64> {
+        self.prior_point
+    }
+
+    /// Returns a table which reads the latest points of the series from the last value
+    /// caches of the vnodes, it is only used by the queries of the latest points.
+    pub fn with_last_value(&self, last_value: LastValueScan) -> Self {
+        Self {
+            last_value: Some(last_value),
+            ..self.clone()
+        }
+    }
+
+    pub fn last_value(&self) -> Option<LastValueScan> {
+        self.last_value
+    }
+
     pub fn table_schema(&self) -> TskvTableSchemaRef {
         self.schema.clone()
     }
@@ -292,11 +326,31 @@ impl TableProvider for ClusterTable {
             (df_schema, arrow_schema)
         };
 
+        // The prior points are read without the filters on fields
+        let prior_point = match self.prior_point {
+            Some(before_ns) if !has_field_filter(&self.schema, filters)? => Some(ceil_timestamp(
+                before_ns,
+                self.schema.time_column_precision(),
+            )),
+            _ => None,
+        };
+        // The cached points are not complete rows, so the filters on fields can't be applied
+        let last_value = match self.last_value {
+            Some(scan) if self.schema.options().last_value_cache => {
+                (!has_field_filter(&self.schema, filters)?).then_some(scan)
+            }
+            _ => None,
+        };
+
+        let spatial_filters = extract_spatial_filters(filters);
         let filters = rewrite_filters(filters, df_schema.clone())?;
         // Generate physical expressions using projected schema
         let filter = Arc::new(
             Predicate::push_down_filter(filters, &df_schema, &arrow_schema, limit)
-                .map_err(|e| DataFusionError::External(Box::new(e)))?,
+                .map_err(|e| DataFusionError::External(Box::new(e)))?
+                .with_prior_point(prior_point)
+                .with_last_value(last_value)
+                .with_spatial_filters(spatial_filters),
         );
 
         if let Some(agg_with_grouping) = agg_with_grouping {
@@ -470,3 +524,33 @@ pub fn valid_project(
 
     Ok(())
 }
+
+/// Check whether the filters contain a column which is neither the time column nor a tag column
+fn has_field_filter(schema: &TskvTableSchema, filters: &[Expr]) -> Result<bool> {
+    for filter in filters {
+        for column in filter.to_columns()? {
+            match schema.column(&column.name) {
+                Some(c) if c.column_type.is_time() || c.column_type.is_tag() => {}
+                _ => return Ok(true),
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Convert the nanosecond timestamp to the precision, rounding up,
+/// so that the timestamps less than the result are all less than `ts_ns`
+fn ceil_timestamp(ts_ns: i64, precision: Precision) -> i64 {
+    let unit = match precision {
+        Precision::NS => return ts_ns,
+        Precision::US => 1_000,
+        Precision::MS => 1_000_000,
+    };
+    let ts = ts_ns.div_euclid(unit);
+    if ts_ns.rem_euclid(unit) == 0 {
+        ts
+    } else {
+        ts + 1
+    }
+}
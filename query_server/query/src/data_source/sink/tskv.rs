@@ -7,6 +7,7 @@ use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::execution::context::TaskContext;
 use datafusion::physical_plan::metrics::{self, Count, ExecutionPlanMetricsSet, MetricBuilder};
 use models::schema::TskvTableSchemaRef;
+use models::utils::now_timestamp_nanos;
 use spi::Result;
 use trace::{SpanContext, SpanExt, SpanRecorder};
 
@@ -16,6 +17,7 @@ pub struct TskvRecordBatchSink {
     coord: CoordinatorRef,
     partition: usize,
     schema: TskvTableSchemaRef,
+    write_time: i64,
 
     metrics: TskvSinkMetrics,
     span_recorder: SpanRecorder,
@@ -67,6 +69,8 @@ impl RecordBatchSink for TskvRecordBatchSink {
                 self.schema.clone(),
                 record_batch,
                 *db_precision,
+                self.write_time,
+                None,
                 span_recorder.span_ctx(),
             )
             .await
@@ -95,11 +99,18 @@ impl RecordBatchSink for TskvRecordBatchSink {
 pub struct TskvRecordBatchSinkProvider {
     coord: CoordinatorRef,
     schema: TskvTableSchemaRef,
+    /// The write time of the statement, the record batches written by all the
+    /// partitions take the same write time.
+    write_time: i64,
 }
 
 impl TskvRecordBatchSinkProvider {
     pub fn new(coord: CoordinatorRef, schema: TskvTableSchemaRef) -> Self {
-        Self { coord, schema }
+        Self {
+            coord,
+            schema,
+            write_time: now_timestamp_nanos(),
+        }
     }
 }
 
@@ -123,6 +134,7 @@ impl RecordBatchSinkProvider for TskvRecordBatchSinkProvider {
             coord: self.coord.clone(),
             partition,
             schema: self.schema.clone(),
+            write_time: self.write_time,
             metrics: TskvSinkMetrics::new(metrics, partition),
             span_recorder,
         })
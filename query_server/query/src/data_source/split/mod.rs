@@ -3,8 +3,11 @@ use std::sync::Arc;
 use coordinator::service::CoordinatorRef;
 use datafusion::execution::context::SessionState;
 use datafusion::sql::TableReference;
+use models::consistency_level::ReadConsistency;
 use models::object_reference::Resolve;
+use models::predicate::domain::{TimeRange, TimeRanges};
 use models::predicate::PlacedSplit;
+use models::Timestamp;
 use spi::{QueryError, Result};
 use trace::debug;
 
@@ -32,7 +35,7 @@ impl SplitManager {
 
     pub async fn splits(
         &self,
-        _ctx: &SessionState,
+        ctx: &SessionState,
         table_layout: TableLayoutHandle,
     ) -> Result<Vec<PlacedSplit>> {
         let TableLayoutHandle {
@@ -47,6 +50,13 @@ impl SplitManager {
         );
 
         let limit = predicate.limit();
+        let prior_point = predicate.prior_point();
+        let last_value = predicate.last_value();
+        let read_consistency = ctx
+            .config()
+            .get_extension::<ReadConsistency>()
+            .map(|e| *e)
+            .unwrap_or_default();
 
         let resolved_predicate =
             predicate
@@ -55,15 +65,35 @@ impl SplitManager {
                     reason: reason.to_string(),
                 })?;
 
+        let time_ranges = resolved_predicate.time_ranges();
+        // The prior points must be earlier than all of the time ranges
+        let prior_point = prior_point
+            .filter(|_| !time_ranges.is_empty())
+            .map(|before| before.min(time_ranges.min_ts()))
+            .filter(|before| *before > Timestamp::MIN);
+        // The prior points may be stored in the vnodes before the time ranges
+        let shards_predicate = if prior_point.is_some() {
+            let widened =
+                TimeRanges::new(vec![TimeRange::new(Timestamp::MIN, time_ranges.max_ts())]);
+            Arc::new(resolved_predicate.with_time_ranges(Arc::new(widened)))
+        } else {
+            resolved_predicate.clone()
+        };
+
         let shards = self
             .coord
-            .table_vnodes(&table_name, resolved_predicate.clone())
+            .table_vnodes(&table_name, shards_predicate)
             .await?;
 
         let splits = shards
             .into_iter()
             .enumerate()
-            .map(|(idx, e)| PlacedSplit::new(idx, resolved_predicate.clone(), limit, e))
+            .map(|(idx, e)| {
+                PlacedSplit::new(idx, resolved_predicate.clone(), limit, e)
+                    .with_prior_point(prior_point)
+                    .with_last_value(last_value)
+                    .with_read_consistency(read_consistency)
+            })
             .collect::<Vec<_>>();
 
         debug!(
@@ -2,23 +2,25 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use coordinator::service::CoordinatorRef;
-use memory_pool::MemoryPoolRef;
+use memory_pool::{MemoryPoolRef, TenantMemoryPools};
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
 use models::oid::Oid;
 use spi::query::ast::ExtStatement;
 use spi::query::datasource::stream::StreamProviderManagerRef;
 use spi::query::dispatcher::{QueryDispatcher, QueryInfo, QueryStatus};
-use spi::query::execution::{Output, QueryStateMachine};
+use spi::query::execution::{Output, QueryExecutionRef, QueryStateMachine};
 use spi::query::function::FuncMetaManagerRef;
 use spi::query::logical_planner::{LogicalPlanner, Plan};
 use spi::query::parser::Parser;
+use spi::query::scheduler::CpuShares;
 use spi::query::session::{SessionCtx, SessionCtxFactory};
 use spi::service::protocol::{ContextBuilder, Query, QueryId};
 use spi::{QueryError, Result};
 use trace::{info, SpanContext, SpanExt, SpanRecorder, TraceExporter};
 
 use super::query_tracker::QueryTracker;
+use crate::alert::AlertManagerRef;
 use crate::data_source::split::SplitManagerRef;
 use crate::execution::factory::QueryExecutionFactoryRef;
 use crate::metadata::{
@@ -34,7 +36,7 @@ pub struct SimpleQueryDispatcher {
     split_manager: SplitManagerRef,
     session_factory: Arc<SessionCtxFactory>,
     // memory pool
-    memory_pool: MemoryPoolRef,
+    memory_pools: Arc<TenantMemoryPools>,
     // query tracker
     query_tracker: Arc<QueryTracker>,
     // parser
@@ -43,6 +45,7 @@ pub struct SimpleQueryDispatcher {
     query_execution_factory: QueryExecutionFactoryRef,
     func_manager: FuncMetaManagerRef,
     stream_provider_manager: StreamProviderManagerRef,
+    alert_manager: AlertManagerRef,
     trace_collector: Option<Arc<dyn TraceExporter>>,
 }
 
@@ -78,6 +81,9 @@ impl QueryDispatcher for SimpleQueryDispatcher {
             }
         }
 
+        // evaluate the alert rules of this node
+        self.alert_manager.start(Arc::new(self.clone()));
+
         Ok(())
     }
 
@@ -168,11 +174,28 @@ impl QueryDispatcher for SimpleQueryDispatcher {
         query: Query,
         span_ctx: Option<&SpanContext>,
     ) -> Result<Arc<QueryStateMachine>> {
+        let tenant = query.context().tenant();
+        let query_config = self
+            .coord
+            .tenant_meta(tenant)
+            .await
+            .and_then(|meta| meta.tenant().options().query_config().copied())
+            .unwrap_or_default();
+        let memory_pool = self.memory_pools.query_pool(
+            tenant,
+            query_config.max_memory.map(|e| e as usize),
+            query_config.max_query_memory.map(|e| e as usize),
+        );
+        let context = query.context().clone().with_cpu_shares(CpuShares {
+            group: tenant.to_string(),
+            shares: query_config.cpu_shares.unwrap_or(1).max(1),
+        });
+
         let session = self.session_factory.create_session_ctx(
             query_id.to_string(),
-            query.context(),
+            &context,
             tenant_id,
-            self.memory_pool.clone(),
+            memory_pool,
             span_ctx.cloned(),
             self.coord.clone(),
         )?;
@@ -202,9 +225,23 @@ impl QueryDispatcher for SimpleQueryDispatcher {
             .collect()
     }
 
+    fn running_queries(&self) -> Vec<QueryExecutionRef> {
+        self.query_tracker.running_queries()
+    }
+
     fn cancel_query(&self, id: &QueryId) {
         self.query_tracker.query(id).map(|e| e.cancel());
     }
+
+    fn kill_query(&self, id: &QueryId) -> bool {
+        match self.query_tracker.expire_query(id) {
+            Some(query) => {
+                let _ = query.cancel();
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl SimpleQueryDispatcher {
@@ -252,6 +289,7 @@ impl SimpleQueryDispatcher {
             self.default_table_provider.clone(),
             self.func_manager.clone(),
             self.query_tracker.clone(),
+            self.alert_manager.clone(),
             session.clone(),
         );
 
@@ -303,6 +341,7 @@ pub struct SimpleQueryDispatcherBuilder {
 
     func_manager: Option<FuncMetaManagerRef>,
     stream_provider_manager: Option<StreamProviderManagerRef>,
+    alert_manager: Option<AlertManagerRef>,
     trace_collector: Option<Arc<dyn TraceExporter>>,
 }
 
@@ -366,6 +405,11 @@ impl SimpleQueryDispatcherBuilder {
         self
     }
 
+    pub fn with_alert_manager(mut self, alert_manager: AlertManagerRef) -> Self {
+        self.alert_manager = Some(alert_manager);
+        self
+    }
+
     pub fn with_trace_collector(mut self, trace_collector: Arc<dyn TraceExporter>) -> Self {
         self.trace_collector = Some(trace_collector);
         self
@@ -430,6 +474,12 @@ impl SimpleQueryDispatcherBuilder {
                     err: "lost of default_table_provider".to_string(),
                 })?;
 
+        let alert_manager = self
+            .alert_manager
+            .ok_or_else(|| QueryError::BuildQueryDispatcher {
+                err: "lost of alert_manager".to_string(),
+            })?;
+
         let trace_collector = self.trace_collector;
 
         Ok(SimpleQueryDispatcher {
@@ -437,12 +487,13 @@ impl SimpleQueryDispatcherBuilder {
             default_table_provider,
             split_manager,
             session_factory,
-            memory_pool,
+            memory_pools: Arc::new(TenantMemoryPools::new(memory_pool)),
             parser,
             query_execution_factory,
             query_tracker,
             func_manager,
             stream_provider_manager,
+            alert_manager,
             trace_collector,
         })
     }
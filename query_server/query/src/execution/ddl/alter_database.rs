@@ -1,9 +1,11 @@
 use async_trait::async_trait;
+use coordinator::resource_manager::ResourceManager;
 use meta::error::MetaError;
-use models::schema::DatabaseOptions;
+use models::schema::{DatabaseOptions, ResourceInfo, ResourceOperator};
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::AlterDatabase;
 use spi::Result;
+use trace::info;
 
 use crate::execution::ddl::DDLDefinitionTask;
 
@@ -42,13 +44,54 @@ impl DDLDefinitionTask for AlterDatabaseTask {
             });
         }
         // .context(spi::MetaSnafu)?;
+        let old_replica = schema.config.replica_or_default();
         build_database_schema(&self.stmt.database_options, &mut schema.config);
         // client
         //     .alter_database(schema)
         //     .context(spi::MetaSnafu)?;
 
+        let new_replica = schema.config.replica_or_default();
+        if new_replica > old_replica {
+            // the new vnodes are placed on the healthy nodes which are not decommissioned
+            let meta = &query_state_machine.meta;
+            let decommissioned_nodes = meta.decommissioned_nodes().await?;
+            let valid_node_num = meta
+                .node_metrics()
+                .await?
+                .iter()
+                .filter(|m| m.is_healthy() && !decommissioned_nodes.contains(&m.id))
+                .count();
+            if new_replica > valid_node_num as u64 {
+                return Err(MetaError::ValidNodeNotEnough {
+                    need: new_replica,
+                    valid_node_num: valid_node_num as u32,
+                }
+                .into());
+            }
+        }
         client.alter_db_schema(schema).await?;
         // .context(spi::MetaSnafu)?;
+
+        if new_replica != old_replica {
+            // apply the replica count to the existing buckets in background, the progress
+            // can be seen in information_schema.resource_status
+            let db_name = self.stmt.database_name.clone();
+            let coord = query_state_machine.coord.clone();
+            let resourceinfo = ResourceInfo::new(
+                (*client.tenant().id(), db_name.clone()),
+                format!("change-replica-{}-{}", tenant, db_name),
+                ResourceOperator::ChangeReplica(tenant.to_string(), db_name.clone()),
+                &None,
+                coord.node_id(),
+            );
+
+            info!(
+                "Change replica of database {}.{} from {} to {}",
+                tenant, db_name, old_replica, new_replica
+            );
+            ResourceManager::submit_resource_task(coord, resourceinfo).await?;
+        }
+
         return Ok(Output::Nil(()));
     }
 }
@@ -111,6 +111,11 @@ impl DDLDefinitionTask for AlterTableTask {
                 alter_schema_func(&mut schema, old_column_name, new_column_name)?;
                 None
             }
+            AlterTableAction::SetOptions { options } => {
+                schema = schema.with_options(options.clone());
+                schema.schema_version += 1;
+                None
+            }
         };
 
         if let Some(info) = operator_info {
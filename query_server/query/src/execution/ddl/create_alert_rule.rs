@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use models::alert::AlertRule;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CreateAlertRule;
+use spi::{QueryError, Result};
+use trace::debug;
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct CreateAlertRuleTask {
+    stmt: CreateAlertRule,
+}
+
+impl CreateAlertRuleTask {
+    pub fn new(stmt: CreateAlertRule) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreateAlertRuleTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let CreateAlertRule {
+            ref tenant_name,
+            ref name,
+            ref if_not_exists,
+            ref database_name,
+            ref owner,
+            ref query,
+            ref every,
+            ref for_duration,
+            ref severity,
+            ref labels,
+            ref annotations,
+            ref webhook,
+        } = self.stmt;
+
+        let meta = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| QueryError::Meta {
+                source: MetaError::TenantNotFound {
+                    tenant: tenant_name.to_string(),
+                },
+            })?;
+
+        // the rule is evaluated by the node it was created on
+        let rule = AlertRule {
+            tenant: tenant_name.clone(),
+            name: name.clone(),
+            database: database_name.clone(),
+            owner: owner.desc().name().to_string(),
+            query: query.clone(),
+            every: *every,
+            for_duration: *for_duration,
+            severity: severity.clone(),
+            labels: labels.clone(),
+            annotations: annotations.clone(),
+            webhook: webhook.clone(),
+            node_id: query_state_machine.coord.node_id(),
+        };
+
+        debug!("Create alert rule {} of tenant {}", name, tenant_name);
+
+        match meta.create_alert_rule(rule).await {
+            // do not create if exists
+            Err(MetaError::AlertRuleAlreadyExists { .. }) if *if_not_exists => Ok(Output::Nil(())),
+            result => {
+                result?;
+                Ok(Output::Nil(()))
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use models::ingestion::IngestionProfile;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CreateIngestionProfile;
+use spi::{QueryError, Result};
+use trace::debug;
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct CreateIngestionProfileTask {
+    stmt: CreateIngestionProfile,
+}
+
+impl CreateIngestionProfileTask {
+    pub fn new(stmt: CreateIngestionProfile) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreateIngestionProfileTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let CreateIngestionProfile {
+            ref tenant_name,
+            ref name,
+            ref if_not_exists,
+            ref options,
+        } = self.stmt;
+
+        let meta = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| QueryError::Meta {
+                source: MetaError::TenantNotFound {
+                    tenant: tenant_name.to_string(),
+                },
+            })?;
+
+        let profile = IngestionProfile {
+            tenant: tenant_name.clone(),
+            name: name.clone(),
+            options: options.clone(),
+        };
+
+        debug!(
+            "Create ingestion profile {} of tenant {}",
+            name, tenant_name
+        );
+
+        match meta.create_ingestion_profile(profile).await {
+            // do not create if exists
+            Err(MetaError::IngestionProfileAlreadyExists { .. }) if *if_not_exists => {
+                Ok(Output::Nil(()))
+            }
+            result => {
+                result?;
+                Ok(Output::Nil(()))
+            }
+        }
+    }
+}
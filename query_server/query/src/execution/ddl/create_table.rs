@@ -58,7 +58,13 @@ async fn create_table(stmt: &CreateTable, machine: QueryStateMachineRef) -> Resu
 }
 
 fn build_schema(stmt: &CreateTable) -> TskvTableSchema {
-    let CreateTable { schema, name, .. } = stmt;
+    let CreateTable {
+        schema,
+        name,
+        partition_tags,
+        options,
+        ..
+    } = stmt;
 
     TskvTableSchema::new(
         name.tenant().to_string(),
@@ -66,4 +72,6 @@ fn build_schema(stmt: &CreateTable) -> TskvTableSchema {
         name.table().to_string(),
         schema.to_owned(),
     )
+    .with_partition_tags(partition_tags.to_owned())
+    .with_options(options.to_owned())
 }
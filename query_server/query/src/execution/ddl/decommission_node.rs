@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use coordinator::resource_manager::ResourceManager;
+use meta::error::MetaError;
+use models::schema::{ResourceInfo, ResourceOperator, ResourceStatus};
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::DecommissionNode;
+use spi::{QueryError, Result};
+use trace::info;
+
+use super::DDLDefinitionTask;
+
+pub struct DecommissionNodeTask {
+    stmt: DecommissionNode,
+}
+
+impl DecommissionNodeTask {
+    #[inline(always)]
+    pub fn new(stmt: DecommissionNode) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for DecommissionNodeTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let node_id = self.stmt.node_id;
+        let meta = query_state_machine.meta.clone();
+        let coord = query_state_machine.coord.clone();
+
+        if !meta.data_nodes().await.iter().any(|n| n.id == node_id) {
+            return Err(QueryError::Meta {
+                source: MetaError::NotFoundNode { id: node_id },
+            });
+        }
+
+        let name = format!("decommission-node-{}", node_id);
+        if let Some(old) = meta.read_resourceinfo_by_name(&name).await? {
+            if matches!(
+                old.get_status(),
+                ResourceStatus::Schedule | ResourceStatus::Executing | ResourceStatus::Failed
+            ) {
+                return Err(QueryError::Semantic {
+                    err: format!("node {} is already being decommissioned", node_id),
+                });
+            }
+        }
+
+        let resourceinfo = ResourceInfo::new(
+            (*query_state_machine.session.tenant_id(), String::new()),
+            name,
+            ResourceOperator::DecommissionNode(node_id),
+            &None,
+            coord.node_id(),
+        );
+
+        // moving vnodes may take a long time, so they are moved in background,
+        // the progress can be seen in information_schema.resource_status
+        info!("Decommission node {}", node_id);
+        ResourceManager::submit_resource_task(coord, resourceinfo).await?;
+
+        Ok(Output::Nil(()))
+    }
+}
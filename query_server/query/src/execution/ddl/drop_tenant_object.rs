@@ -66,6 +66,36 @@ impl DDLDefinitionTask for DropTenantObjectTask {
                 Ok(Output::Nil(()))
             }
 
+            TenantObjectType::AlertRule => {
+                debug!("Drop alert rule {} of tenant {}", name, tenant_name);
+                let success = meta.drop_alert_rule(name).await?;
+
+                if let (false, false) = (if_exist, success) {
+                    return Err(QueryError::Meta {
+                        source: MetaError::AlertRuleNotFound {
+                            rule: name.to_string(),
+                        },
+                    });
+                }
+
+                Ok(Output::Nil(()))
+            }
+
+            TenantObjectType::IngestionProfile => {
+                debug!("Drop ingestion profile {} of tenant {}", name, tenant_name);
+                let success = meta.drop_ingestion_profile(name).await?;
+
+                if let (false, false) = (if_exist, success) {
+                    return Err(QueryError::Meta {
+                        source: MetaError::IngestionProfileNotFound {
+                            profile: name.to_string(),
+                        },
+                    });
+                }
+
+                Ok(Output::Nil(()))
+            }
+
             TenantObjectType::Database => {
                 // 删除租户下的database
                 // tenant_id
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use coordinator::VnodeManagerCmdType;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ExportVnode;
+use spi::Result;
+
+use super::DDLDefinitionTask;
+
+pub struct ExportVnodeTask {
+    stmt: ExportVnode,
+}
+
+impl ExportVnodeTask {
+    #[inline(always)]
+    pub fn new(stmt: ExportVnode) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ExportVnodeTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let vnode_id = self.stmt.vnode_id;
+        let path = self.stmt.path.clone();
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let cmd_type = VnodeManagerCmdType::Export(vnode_id, path);
+        coord.vnode_manager(tenant, cmd_type).await?;
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -7,7 +7,9 @@ use spi::Result;
 
 use self::alter_tenant::AlterTenantTask;
 use self::alter_user::AlterUserTask;
+use self::create_alert_rule::CreateAlertRuleTask;
 use self::create_external_table::CreateExternalTableTask;
+use self::create_ingestion_profile::CreateIngestionProfileTask;
 use self::create_role::CreateRoleTask;
 use self::create_stream_table::CreateStreamTableTask;
 use self::create_table::CreateTableTask;
@@ -25,8 +27,12 @@ use crate::execution::ddl::checksum_group::ChecksumGroupTask;
 use crate::execution::ddl::compact_vnode::CompactVnodeTask;
 use crate::execution::ddl::copy_vnode::CopyVnodeTask;
 use crate::execution::ddl::create_database::CreateDatabaseTask;
+use crate::execution::ddl::decommission_node::DecommissionNodeTask;
 use crate::execution::ddl::drop_vnode::DropVnodeTask;
+use crate::execution::ddl::export_vnode::ExportVnodeTask;
+use crate::execution::ddl::import_vnode::ImportVnodeTask;
 use crate::execution::ddl::move_node::MoveVnodeTask;
+use crate::execution::ddl::transfer_leader::TransferLeaderTask;
 
 mod alter_database;
 mod alter_table;
@@ -35,21 +41,27 @@ mod alter_user;
 mod checksum_group;
 mod compact_vnode;
 mod copy_vnode;
+mod create_alert_rule;
 mod create_database;
 mod create_external_table;
+mod create_ingestion_profile;
 mod create_role;
 mod create_stream_table;
 mod create_table;
 mod create_tenant;
 mod create_user;
+mod decommission_node;
 mod drop_database_object;
 mod drop_global_object;
 mod drop_tenant_object;
 mod drop_vnode;
+mod export_vnode;
 mod grant_revoke;
+mod import_vnode;
 mod move_node;
 mod recover_database;
 mod recover_tenant;
+mod transfer_leader;
 
 /// Traits that DDL tasks should implement
 #[async_trait]
@@ -157,6 +169,12 @@ impl DDLDefinitionTaskFactory {
             DDLPlan::CreateTenant(sub_plan) => Box::new(CreateTenantTask::new(*sub_plan.clone())),
             DDLPlan::CreateUser(sub_plan) => Box::new(CreateUserTask::new(sub_plan.clone())),
             DDLPlan::CreateRole(sub_plan) => Box::new(CreateRoleTask::new(sub_plan.clone())),
+            DDLPlan::CreateAlertRule(sub_plan) => {
+                Box::new(CreateAlertRuleTask::new(sub_plan.clone()))
+            }
+            DDLPlan::CreateIngestionProfile(sub_plan) => {
+                Box::new(CreateIngestionProfileTask::new(sub_plan.clone()))
+            }
             DDLPlan::AlterDatabase(sub_plan) => Box::new(AlterDatabaseTask::new(sub_plan.clone())),
             DDLPlan::AlterTable(sub_plan) => Box::new(AlterTableTask::new(sub_plan.clone())),
             DDLPlan::AlterTenant(sub_plan) => Box::new(AlterTenantTask::new(sub_plan.clone())),
@@ -166,6 +184,14 @@ impl DDLDefinitionTaskFactory {
             DDLPlan::CopyVnode(sub_plan) => Box::new(CopyVnodeTask::new(sub_plan.clone())),
             DDLPlan::MoveVnode(sub_plan) => Box::new(MoveVnodeTask::new(sub_plan.clone())),
             DDLPlan::CompactVnode(sub_plan) => Box::new(CompactVnodeTask::new(sub_plan.clone())),
+            DDLPlan::ExportVnode(sub_plan) => Box::new(ExportVnodeTask::new(sub_plan.clone())),
+            DDLPlan::ImportVnode(sub_plan) => Box::new(ImportVnodeTask::new(sub_plan.clone())),
+            DDLPlan::TransferLeader(sub_plan) => {
+                Box::new(TransferLeaderTask::new(sub_plan.clone()))
+            }
+            DDLPlan::DecommissionNode(sub_plan) => {
+                Box::new(DecommissionNodeTask::new(sub_plan.clone()))
+            }
             DDLPlan::ChecksumGroup(sub_plan) => {
                 Box::new(ChecksumGroupTask::new(sub_plan.clone(), self.plan.schema()))
             }
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::TransferLeader;
+use spi::Result;
+
+use super::DDLDefinitionTask;
+
+pub struct TransferLeaderTask {
+    stmt: TransferLeader,
+}
+
+impl TransferLeaderTask {
+    #[inline(always)]
+    pub fn new(stmt: TransferLeader) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for TransferLeaderTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let (replica_id, node_id) = (self.stmt.replica_id, self.stmt.node_id);
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let cmd_type = coordinator::VnodeManagerCmdType::TransferLeader(replica_id, node_id);
+        coord.vnode_manager(tenant, cmd_type).await?;
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -12,6 +12,7 @@ use tskv::kv_option::QueryOptions;
 
 use super::dml::DMLExecution;
 use super::query::SqlQueryExecution;
+use super::spill::SpillManager;
 use super::stream::trigger::executor::{TriggerExecutorFactory, TriggerExecutorFactoryRef};
 use super::stream::{MicroBatchStreamExecutionBuilder, MicroBatchStreamExecutionDesc};
 use super::sys::SystemExecution;
@@ -24,6 +25,7 @@ use crate::extension::utils::downcast_plan_node;
 pub struct SqlQueryExecutionFactory {
     optimizer: Arc<dyn Optimizer + Send + Sync>,
     scheduler: SchedulerRef,
+    spill_manager: Arc<SpillManager>,
     query_tracker: Arc<QueryTracker>,
     trigger_executor_factory: TriggerExecutorFactoryRef,
     runtime: Arc<DedicatedExecutor>,
@@ -35,6 +37,7 @@ impl SqlQueryExecutionFactory {
     pub fn new(
         optimizer: Arc<dyn Optimizer + Send + Sync>,
         scheduler: SchedulerRef,
+        spill_manager: Arc<SpillManager>,
         query_tracker: Arc<QueryTracker>,
         stream_checker_manager: StreamCheckerManagerRef,
         config: Arc<QueryOptions>,
@@ -55,6 +58,7 @@ impl SqlQueryExecutionFactory {
         Self {
             optimizer,
             scheduler,
+            spill_manager,
             query_tracker,
             trigger_executor_factory,
             runtime,
@@ -87,6 +91,7 @@ impl QueryExecutionFactory for SqlQueryExecutionFactory {
                         query_plan,
                         self.optimizer.clone(),
                         self.scheduler.clone(),
+                        self.spill_manager.clone(),
                     ))),
                     (true, false, true) => {
                         // 流操作
@@ -3,5 +3,6 @@ mod dml;
 pub mod factory;
 mod query;
 pub mod scheduler;
-mod stream;
+pub mod spill;
+pub(crate) mod stream;
 mod sys;
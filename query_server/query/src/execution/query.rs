@@ -1,9 +1,11 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use datafusion::physical_plan::ExecutionPlan;
 use futures::stream::AbortHandle;
 use parking_lot::Mutex;
-use spi::query::dispatcher::{QueryInfo, QueryStatus};
+use spi::query::dispatcher::{QueryInfo, QueryStatus, QueryStatusBuilder};
 use spi::query::execution::{Output, QueryExecution, QueryStateMachineRef};
 use spi::query::logical_planner::QueryPlan;
 use spi::query::optimizer::Optimizer;
@@ -11,13 +13,17 @@ use spi::query::scheduler::SchedulerRef;
 use spi::{QueryError, Result};
 use trace::debug;
 
+use super::spill::{spill_metrics, SpillManager};
+
 pub struct SqlQueryExecution {
     query_state_machine: QueryStateMachineRef,
     plan: QueryPlan,
     optimizer: Arc<dyn Optimizer + Send + Sync>,
     scheduler: SchedulerRef,
+    spill_manager: Arc<SpillManager>,
 
     abort_handle: Mutex<Option<AbortHandle>>,
+    physical_plan: Mutex<Option<Arc<dyn ExecutionPlan>>>,
 }
 
 impl SqlQueryExecution {
@@ -26,13 +32,16 @@ impl SqlQueryExecution {
         plan: QueryPlan,
         optimizer: Arc<dyn Optimizer + Send + Sync>,
         scheduler: SchedulerRef,
+        spill_manager: Arc<SpillManager>,
     ) -> Self {
         Self {
             query_state_machine,
             plan,
             optimizer,
             scheduler,
+            spill_manager,
             abort_handle: Mutex::new(None),
+            physical_plan: Mutex::new(None),
         }
     }
 
@@ -44,6 +53,7 @@ impl SqlQueryExecution {
             .optimize(&self.plan.df_plan, &self.query_state_machine.session)
             .await?;
         self.query_state_machine.end_optimize();
+        *self.physical_plan.lock() = Some(physical_plan.clone());
 
         // begin schedule
         self.query_state_machine.begin_schedule();
@@ -55,6 +65,11 @@ impl SqlQueryExecution {
             )
             .await?
             .stream();
+        let stream = self.spill_manager.wrap_stream(
+            self.query_state_machine.session.tenant(),
+            physical_plan,
+            stream,
+        );
 
         debug!("Success build result stream.");
         self.query_state_machine.end_schedule();
@@ -112,9 +127,38 @@ impl QueryExecution for SqlQueryExecution {
     }
 
     fn status(&self) -> QueryStatus {
-        QueryStatus::new(
+        let mut builder = QueryStatusBuilder::new(
             self.query_state_machine.state().clone(),
             self.query_state_machine.duration(),
-        )
+        );
+        if let Some(tracker) = self.query_state_machine.session.resource_tracker() {
+            let physical_plan = self.physical_plan.lock().clone();
+            let cpu_time = physical_plan
+                .as_ref()
+                .map(|plan| elapsed_compute(plan.as_ref()))
+                .unwrap_or_default();
+            let mut resource_usage = tracker.usage(cpu_time);
+            if let Some(plan) = physical_plan {
+                (resource_usage.spill_count, resource_usage.spilled_bytes) =
+                    spill_metrics(plan.as_ref());
+            }
+            builder = builder.with_resource_usage(resource_usage);
+        }
+
+        builder.build()
     }
 }
+
+/// The total time spent on computing by the operators of the plan
+pub(crate) fn elapsed_compute(plan: &dyn ExecutionPlan) -> Duration {
+    let nanos = plan
+        .metrics()
+        .and_then(|m| m.elapsed_compute())
+        .unwrap_or_default();
+
+    plan.children()
+        .iter()
+        .map(|child| elapsed_compute(child.as_ref()))
+        .sum::<Duration>()
+        + Duration::from_nanos(nanos as u64)
+}
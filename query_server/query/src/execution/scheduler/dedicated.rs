@@ -9,19 +9,26 @@ use datafusion::physical_plan::{execute_stream, ExecutionPlan};
 use futures::Future;
 use models::runtime::cross_rt_stream::CrossRtStream;
 use models::runtime::executor::DedicatedExecutor;
-use spi::query::scheduler::{ExecutionResults, Scheduler};
+use spi::query::scheduler::{CpuShares, ExecutionResults, Scheduler};
 use trace::info;
 
+use super::fair_share::FairShareScheduler;
+
+/// Runs the queries on a dedicated executor, the cpu of the executor is shared between the
+/// groups of [`CpuShares`] found in the session config of the queries.
 pub struct DedicatedScheduler {
     runtime: DedicatedExecutor,
+    fair_share: Arc<FairShareScheduler>,
 }
 
 impl DedicatedScheduler {
-    #[allow(dead_code)]
     pub fn new(num_threads: usize) -> Self {
         info!("Init dedicated executor of query engine.");
         let runtime = DedicatedExecutor::new("query-dedicated-scheduler", num_threads);
-        Self { runtime }
+        Self {
+            runtime,
+            fair_share: Arc::new(FairShareScheduler::default()),
+        }
     }
 
     async fn run<F, T>(&self, f: F) -> Result<T>
@@ -45,9 +52,13 @@ impl Scheduler for DedicatedScheduler {
         plan: Arc<dyn ExecutionPlan>,
         context: Arc<TaskContext>,
     ) -> Result<ExecutionResults> {
-        let stream = self
-            .run(async move { execute_stream(plan, context) })
-            .await?;
+        let cpu_shares = context.session_config().get_extension::<CpuShares>();
+        let stream = {
+            let plan = plan.clone();
+            self.run(async move { execute_stream(plan, context) })
+                .await?
+        };
+        let stream = self.fair_share.share(stream, plan, cpu_shares.as_deref());
 
         let schema = stream.schema();
         let stream = CrossRtStream::new_with_df_error_stream(stream, self.runtime.clone());
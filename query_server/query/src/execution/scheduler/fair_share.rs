@@ -0,0 +1,309 @@
+//! Shares the cpu of a query scheduler between the groups of queries (the tenants) by their
+//! cpu shares.
+//!
+//! Every group has a virtual runtime: the cpu time used by its queries divided by its shares.
+//! A query of a group whose virtual runtime is ahead of the slowest busy group by more than
+//! [`SLICE`] is delayed, so that the cpu time of the busy groups is proportional to their shares.
+//!
+//! A query is only delayed when the root stream of its plan is polled. The operators running
+//! in their own tasks (e.g. `RepartitionExec`) keep running until their buffers are full, so
+//! the shares are approximate for the plans with such operators.
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result;
+use datafusion::physical_plan::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+use futures::{ready, FutureExt, Stream, StreamExt};
+use parking_lot::Mutex;
+use spi::query::scheduler::CpuShares;
+use tokio::time::Sleep;
+
+use crate::execution::query::elapsed_compute;
+
+/// The virtual runtime a group may be ahead of the others, also the time a delayed query waits
+const SLICE: Duration = Duration::from_millis(10);
+/// A group is busy if its queries used cpu within this window
+const BUSY_WINDOW: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+struct Group {
+    shares: u32,
+    /// Nanoseconds of cpu time used by the group divided by its shares
+    vruntime: u64,
+    /// Number of the running queries of the group
+    queries: usize,
+    last_charged: Option<Instant>,
+}
+
+impl Group {
+    fn is_busy(&self, now: Instant) -> bool {
+        self.last_charged
+            .map(|t| now.saturating_duration_since(t) < BUSY_WINDOW)
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FairShareScheduler {
+    groups: Mutex<HashMap<String, Group>>,
+}
+
+impl FairShareScheduler {
+    /// Registers a query of the group, the group is removed when all its queries are finished
+    pub fn register(self: &Arc<Self>, cpu_shares: &CpuShares) -> GroupHandle {
+        let mut groups = self.groups.lock();
+        // A new group starts from the slowest group, so it doesn't take the cpu from the others
+        // until it catches up
+        let min_vruntime = groups
+            .values()
+            .map(|g| g.vruntime)
+            .min()
+            .unwrap_or_default();
+        let group = groups
+            .entry(cpu_shares.group.clone())
+            .or_insert_with(|| Group {
+                shares: 1,
+                vruntime: min_vruntime,
+                queries: 0,
+                last_charged: None,
+            });
+        group.shares = cpu_shares.shares.max(1);
+        group.queries += 1;
+
+        GroupHandle {
+            scheduler: self.clone(),
+            group: cpu_shares.group.clone(),
+        }
+    }
+
+    /// Wraps the stream of the plan to share the cpu with the other groups, the stream is
+    /// returned as is if the query has no [`CpuShares`].
+    pub fn share(
+        self: &Arc<Self>,
+        stream: SendableRecordBatchStream,
+        plan: Arc<dyn ExecutionPlan>,
+        cpu_shares: Option<&CpuShares>,
+    ) -> SendableRecordBatchStream {
+        match cpu_shares {
+            Some(cpu_shares) => {
+                let handle = self.register(cpu_shares);
+                Box::pin(FairShareStream::new(stream, plan, handle))
+            }
+            None => stream,
+        }
+    }
+
+    fn unregister(&self, group: &str) {
+        let mut groups = self.groups.lock();
+        if let Some(g) = groups.get_mut(group) {
+            g.queries = g.queries.saturating_sub(1);
+            if g.queries == 0 {
+                groups.remove(group);
+            }
+        }
+    }
+
+    /// Charges the cpu time used by a query of the group
+    fn charge(&self, group: &str, cpu_time: Duration) {
+        self.charge_at(group, cpu_time, Instant::now())
+    }
+
+    fn charge_at(&self, group: &str, cpu_time: Duration, now: Instant) {
+        let mut groups = self.groups.lock();
+        let min_busy = Self::min_busy_vruntime(&groups, group, now);
+        if let Some(g) = groups.get_mut(group) {
+            // A group waking up from idle doesn't get the cpu time it didn't use
+            if !g.is_busy(now) {
+                if let Some(min_busy) = min_busy {
+                    g.vruntime = g.vruntime.max(min_busy);
+                }
+            }
+            g.vruntime += cpu_time.as_nanos() as u64 / g.shares as u64;
+            g.last_charged = Some(now);
+        }
+    }
+
+    /// How long a query of the group should wait before running, zero if it may run now
+    fn delay(&self, group: &str) -> Duration {
+        self.delay_at(group, Instant::now())
+    }
+
+    fn delay_at(&self, group: &str, now: Instant) -> Duration {
+        let groups = self.groups.lock();
+        let vruntime = match groups.get(group) {
+            Some(g) => g.vruntime,
+            None => return Duration::ZERO,
+        };
+        match Self::min_busy_vruntime(&groups, group, now) {
+            Some(min) if vruntime > min + SLICE.as_nanos() as u64 => SLICE,
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn min_busy_vruntime(
+        groups: &HashMap<String, Group>,
+        except: &str,
+        now: Instant,
+    ) -> Option<u64> {
+        groups
+            .iter()
+            .filter(|(name, g)| name.as_str() != except && g.is_busy(now))
+            .map(|(_, g)| g.vruntime)
+            .min()
+    }
+}
+
+/// A registered query of a group, unregistered on drop
+pub struct GroupHandle {
+    scheduler: Arc<FairShareScheduler>,
+    group: String,
+}
+
+impl Drop for GroupHandle {
+    fn drop(&mut self) {
+        self.scheduler.unregister(&self.group)
+    }
+}
+
+/// Delays the polling of the stream when the group of the query used more than its share,
+/// and charges the cpu time used by the operators of the plan to the group.
+pub struct FairShareStream {
+    inner: SendableRecordBatchStream,
+    plan: Arc<dyn ExecutionPlan>,
+    handle: GroupHandle,
+    charged: Duration,
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl FairShareStream {
+    pub fn new(
+        inner: SendableRecordBatchStream,
+        plan: Arc<dyn ExecutionPlan>,
+        handle: GroupHandle,
+    ) -> Self {
+        Self {
+            inner,
+            plan,
+            handle,
+            charged: Duration::ZERO,
+            delay: None,
+        }
+    }
+
+    fn charge(&mut self) {
+        let elapsed = elapsed_compute(self.plan.as_ref());
+        let delta = elapsed.saturating_sub(self.charged);
+        if !delta.is_zero() {
+            self.charged = elapsed;
+            self.handle.scheduler.charge(&self.handle.group, delta);
+        }
+    }
+}
+
+impl Stream for FairShareStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(delay) = self.delay.as_mut() {
+                ready!(delay.poll_unpin(cx));
+                self.delay = None;
+            }
+
+            let delay = self.handle.scheduler.delay(&self.handle.group);
+            if delay.is_zero() {
+                break;
+            }
+            self.delay = Some(Box::pin(tokio::time::sleep(delay)));
+        }
+
+        let poll = self.inner.poll_next_unpin(cx);
+        self.charge();
+        poll
+    }
+}
+
+impl RecordBatchStream for FairShareStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shares(group: &str, shares: u32) -> CpuShares {
+        CpuShares {
+            group: group.to_string(),
+            shares,
+        }
+    }
+
+    #[test]
+    fn test_vruntime_by_shares() {
+        let scheduler = Arc::new(FairShareScheduler::default());
+        let _a = scheduler.register(&shares("a", 1));
+        let _b = scheduler.register(&shares("b", 2));
+
+        let now = Instant::now();
+        scheduler.charge_at("a", Duration::ZERO, now);
+        scheduler.charge_at("b", Duration::ZERO, now);
+
+        scheduler.charge_at("a", Duration::from_millis(20), now);
+        scheduler.charge_at("b", Duration::from_millis(20), now);
+
+        // b used 20ms with 2 shares, a used 20ms with 1 share
+        assert_eq!(scheduler.delay_at("a", now), Duration::ZERO);
+        assert_eq!(scheduler.delay_at("b", now), Duration::ZERO);
+
+        scheduler.charge_at("a", Duration::from_millis(20), now);
+        assert_eq!(scheduler.delay_at("a", now), SLICE);
+        assert_eq!(scheduler.delay_at("b", now), Duration::ZERO);
+
+        // b catches up
+        scheduler.charge_at("b", Duration::from_millis(60), now);
+        assert_eq!(scheduler.delay_at("a", now), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_idle_group_not_delayed() {
+        let scheduler = Arc::new(FairShareScheduler::default());
+        let _a = scheduler.register(&shares("a", 1));
+        let _b = scheduler.register(&shares("b", 1));
+
+        let now = Instant::now();
+        scheduler.charge_at("a", Duration::from_millis(100), now);
+        // b is idle, a runs alone
+        assert_eq!(scheduler.delay_at("a", now), Duration::ZERO);
+
+        // b wakes up from idle with the vruntime of a
+        let later = now + Duration::from_millis(50);
+        scheduler.charge_at("b", Duration::from_millis(1), later);
+        assert_eq!(scheduler.delay_at("b", later), Duration::ZERO);
+        assert_eq!(scheduler.delay_at("a", later), Duration::ZERO);
+
+        // a is idle after the window
+        let idle = now + BUSY_WINDOW * 2;
+        scheduler.charge_at("b", Duration::from_millis(100), idle);
+        assert_eq!(scheduler.delay_at("b", idle), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_unregister_group() {
+        let scheduler = Arc::new(FairShareScheduler::default());
+        let a1 = scheduler.register(&shares("a", 1));
+        let a2 = scheduler.register(&shares("a", 1));
+        assert_eq!(scheduler.groups.lock().get("a").unwrap().queries, 2);
+
+        drop(a1);
+        assert_eq!(scheduler.groups.lock().get("a").unwrap().queries, 1);
+        drop(a2);
+        assert!(scheduler.groups.lock().get("a").is_none());
+    }
+}
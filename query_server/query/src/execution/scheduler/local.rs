@@ -4,9 +4,16 @@ use async_trait::async_trait;
 use datafusion::error::DataFusionError;
 use datafusion::execution::context::TaskContext;
 use datafusion::physical_plan::{execute_stream, ExecutionPlan};
-use spi::query::scheduler::{ExecutionResults, Scheduler};
+use spi::query::scheduler::{CpuShares, ExecutionResults, Scheduler};
 
-pub struct LocalScheduler {}
+use super::fair_share::FairShareScheduler;
+
+/// Runs the queries on the current runtime, the cpu used by the queries is shared between the
+/// groups of [`CpuShares`] found in the session config of the queries.
+#[derive(Default)]
+pub struct LocalScheduler {
+    fair_share: Arc<FairShareScheduler>,
+}
 
 #[async_trait]
 impl Scheduler for LocalScheduler {
@@ -15,7 +22,9 @@ impl Scheduler for LocalScheduler {
         plan: Arc<dyn ExecutionPlan>,
         context: Arc<TaskContext>,
     ) -> Result<ExecutionResults, DataFusionError> {
-        let stream = execute_stream(plan, context)?;
+        let cpu_shares = context.session_config().get_extension::<CpuShares>();
+        let stream = execute_stream(plan.clone(), context)?;
+        let stream = self.fair_share.share(stream, plan, cpu_shares.as_deref());
 
         Ok(ExecutionResults::new(stream))
     }
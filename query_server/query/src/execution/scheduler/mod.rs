@@ -1,2 +1,3 @@
 pub mod dedicated;
+pub mod fair_share;
 pub mod local;
@@ -0,0 +1,440 @@
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use coordinator::service::CoordServiceMetrics;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::execution::disk_manager::{DiskManager, DiskManagerConfig};
+use datafusion::physical_plan::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+use futures::{Stream, StreamExt};
+use spi::Result;
+use tempfile::TempDir;
+use trace::info;
+use tskv::kv_option::QueryOptions;
+
+/// The disk manager creating the spill files of the queries, and the quota of the disk space
+/// used by the spill files of the running queries.
+///
+/// The sorts are the operators writing the spill files. With `spill_hash_operators` the hash
+/// joins and the grouped hash aggregates are planned as the sort merge joins and the sorted
+/// aggregates to spill through their sorts (see `SpillableJoin` and `SpillableAggregate`).
+/// The result stream of the query charges the bytes spilled by the operators to the quota
+/// as soon as it sees them in the metrics of the plan, and releases them when the query is
+/// done, see `SpillAccountingStream`.
+#[derive(Debug)]
+pub struct SpillManager {
+    disk_manager: Option<Arc<DiskManager>>,
+    /// The temporary directory used if `spill_dir` is not configured, removed on drop
+    _temp_dir: Option<TempDir>,
+    max_spill_size: u64,
+    /// Bytes of the spill files of the running queries
+    spilled_bytes: AtomicU64,
+    metrics: Arc<CoordServiceMetrics>,
+}
+
+impl SpillManager {
+    pub fn try_new(options: &QueryOptions, metrics: Arc<CoordServiceMetrics>) -> Result<Self> {
+        if !options.enable_spill {
+            return Ok(Self {
+                disk_manager: None,
+                _temp_dir: None,
+                max_spill_size: options.max_spill_size,
+                spilled_bytes: AtomicU64::new(0),
+                metrics,
+            });
+        }
+
+        let (spill_dir, temp_dir) = if options.spill_dir.is_empty() {
+            let temp_dir = tempfile::Builder::new().prefix("cnosdb-spill-").tempdir()?;
+            (temp_dir.path().to_path_buf(), Some(temp_dir))
+        } else {
+            let spill_dir = PathBuf::from(&options.spill_dir);
+            std::fs::create_dir_all(&spill_dir)?;
+            (spill_dir, None)
+        };
+        info!("Spill files of the queries are in {}", spill_dir.display());
+        let disk_manager =
+            DiskManager::try_new(DiskManagerConfig::NewSpecified(vec![spill_dir.clone()]))?;
+
+        Ok(Self {
+            disk_manager: Some(disk_manager),
+            _temp_dir: temp_dir,
+            max_spill_size: options.max_spill_size,
+            spilled_bytes: AtomicU64::new(0),
+            metrics,
+        })
+    }
+
+    /// Bytes of the spill files of the running queries
+    pub fn disk_usage(&self) -> u64 {
+        self.spilled_bytes.load(Ordering::Relaxed)
+    }
+
+    /// The disk manager shared by the sessions, disabled if spilling is disabled
+    pub fn disk_manager_config(&self) -> DiskManagerConfig {
+        match &self.disk_manager {
+            Some(disk_manager) => DiskManagerConfig::Existing(disk_manager.clone()),
+            None => DiskManagerConfig::Disabled,
+        }
+    }
+
+    /// Records the spill metrics of the tenant of the query executing the plan, and charges
+    /// the bytes spilled by the query to the quota.
+    pub fn wrap_stream(
+        self: &Arc<Self>,
+        tenant: impl Into<String>,
+        plan: Arc<dyn ExecutionPlan>,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        if self.disk_manager.is_none() {
+            return stream;
+        }
+
+        Box::pin(SpillAccountingStream {
+            inner: stream,
+            plan,
+            manager: self.clone(),
+            tenant: tenant.into(),
+            spill_count: 0,
+            spilled_bytes: 0,
+            quota_exceeded: false,
+        })
+    }
+
+    /// Accounts the bytes written to the spill files, fails if the spill files of the running
+    /// queries exceed the quota.
+    fn spill(&self, bytes: u64) -> DFResult<()> {
+        let used = self.spilled_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.metrics.query_spill_disk_usage().set(used);
+        if self.max_spill_size > 0 && used > self.max_spill_size {
+            return Err(DataFusionError::ResourcesExhausted(format!(
+                "Spill files of the running queries use {} bytes, exceeding the quota of {} bytes",
+                used, self.max_spill_size
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Releases the bytes of the spill files deleted.
+    fn release(&self, bytes: u64) {
+        let used = self
+            .spilled_bytes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                Some(used.saturating_sub(bytes))
+            })
+            .map(|used| used.saturating_sub(bytes))
+            .unwrap_or_default();
+        self.metrics.query_spill_disk_usage().set(used);
+    }
+}
+
+/// Times the operators of the plan spilled, and the bytes spilled
+pub(crate) fn spill_metrics(plan: &dyn ExecutionPlan) -> (u64, u64) {
+    let (count, bytes) = plan
+        .metrics()
+        .map(|m| {
+            (
+                m.spill_count().unwrap_or_default(),
+                m.spilled_bytes().unwrap_or_default(),
+            )
+        })
+        .unwrap_or_default();
+
+    plan.children()
+        .iter()
+        .map(|child| spill_metrics(child.as_ref()))
+        .fold(
+            (count as u64, bytes as u64),
+            |(c, b), (child_c, child_b)| (c + child_c, b + child_b),
+        )
+}
+
+/// Records the times and the bytes the operators of the query spilled, and charges the bytes
+/// to the spill quota.
+///
+/// The operators write the spill files before the bytes are seen in the metrics, so the
+/// spill files may exceed the quota by the files written since the last poll of each running
+/// query. The query fails once the quota is exceeded, its spill files are deleted with the
+/// operators of the query, the bytes are released when the stream is dropped.
+struct SpillAccountingStream {
+    inner: SendableRecordBatchStream,
+    plan: Arc<dyn ExecutionPlan>,
+    manager: Arc<SpillManager>,
+    tenant: String,
+    spill_count: u64,
+    /// Bytes charged to the spill quota
+    spilled_bytes: u64,
+    quota_exceeded: bool,
+}
+
+impl SpillAccountingStream {
+    fn account(&mut self) -> DFResult<()> {
+        let (spill_count, spilled_bytes) = spill_metrics(self.plan.as_ref());
+
+        if spill_count > self.spill_count {
+            self.manager
+                .metrics
+                .query_spill_count(&self.tenant)
+                .inc(spill_count - self.spill_count);
+            self.spill_count = spill_count;
+        }
+
+        if spilled_bytes > self.spilled_bytes {
+            let additional = spilled_bytes - self.spilled_bytes;
+            self.spilled_bytes = spilled_bytes;
+            self.manager
+                .metrics
+                .query_spilled_bytes(&self.tenant)
+                .inc(additional);
+            self.manager.spill(additional)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SpillAccountingStream {
+    fn drop(&mut self) {
+        self.manager.release(self.spilled_bytes);
+    }
+}
+
+impl Stream for SpillAccountingStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.quota_exceeded {
+            return Poll::Ready(None);
+        }
+
+        let poll = self.inner.poll_next_unpin(cx);
+        if let Err(err) = self.account() {
+            self.quota_exceeded = true;
+            return Poll::Ready(Some(Err(err)));
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for SpillAccountingStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use config::Config;
+    use coordinator::service::CoordServiceMetrics;
+    use coordinator::service_mock::MockCoordinator;
+    use datafusion::arrow::array::{Array, Int64Array};
+    use datafusion::arrow::compute::concat_batches;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::datasource::MemTable;
+    use datafusion::error::DataFusionError;
+    use datafusion::execution::memory_pool::GreedyMemoryPool;
+    use datafusion::physical_expr::expressions::col;
+    use datafusion::physical_expr::PhysicalSortExpr;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::sorts::sort::SortExec;
+    use datafusion::physical_plan::{common, execute_stream, ExecutionPlan};
+    use datafusion::prelude::SessionContext;
+    use metrics::metric_register::MetricsRegister;
+    use models::auth::user::{User, UserDesc, UserOptions};
+    use spi::query::physical_planner::PhysicalPlanner;
+    use spi::query::session::{SessionCtx, SessionCtxFactory};
+    use spi::service::protocol::ContextBuilder;
+    use tskv::kv_option::QueryOptions;
+
+    use super::{spill_metrics, SpillManager};
+    use crate::sql::physical::planner::DefaultPhysicalPlanner;
+
+    fn spill_manager(spill_dir: &str, max_spill_size: u64) -> Arc<SpillManager> {
+        let mut config = Config::default();
+        config.query.enable_spill = true;
+        config.query.spill_dir = spill_dir.to_string();
+        config.query.max_spill_size = max_spill_size;
+        let metrics = Arc::new(CoordServiceMetrics::new(&MetricsRegister::default()));
+        Arc::new(SpillManager::try_new(&QueryOptions::from(&config), metrics).unwrap())
+    }
+
+    /// A session with 1 MiB of memory, planning the hash operators to spill
+    fn session_ctx(manager: Arc<SpillManager>, target_partitions: Option<usize>) -> SessionCtx {
+        let user = User::new(
+            UserDesc::new(0_u128, "test".to_string(), UserOptions::default(), false),
+            HashSet::default(),
+            None,
+        );
+        SessionCtxFactory::default()
+            .with_disk_manager(manager.disk_manager_config())
+            .with_spill_hash_operators(true)
+            .create_session_ctx(
+                "",
+                &ContextBuilder::new(user)
+                    .with_target_partitions(target_partitions)
+                    .build(),
+                0_u128,
+                Arc::new(GreedyMemoryPool::new(1024 * 1024)),
+                None,
+                Arc::new(MockCoordinator::default()),
+            )
+            .unwrap()
+    }
+
+    /// 64 batches of 8192 distinct values
+    fn batches() -> (Arc<Schema>, Vec<RecordBatch>) {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batches = (0..64)
+            .map(|i| {
+                let values = (0..8192).map(|j| (j * 64 + i) as i64).collect::<Vec<_>>();
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values))])
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        (schema, batches)
+    }
+
+    /// Sorts 64 batches of 8192 rows with 1 MiB of memory
+    async fn sort_with_spill(manager: Arc<SpillManager>) -> datafusion::common::Result<()> {
+        let (schema, batches) = batches();
+        let input = Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let sort: Arc<dyn ExecutionPlan> = Arc::new(SortExec::new(
+            vec![PhysicalSortExpr {
+                expr: col("a", &schema)?,
+                options: Default::default(),
+            }],
+            input,
+        ));
+
+        let session = session_ctx(manager.clone(), None);
+
+        let stream = execute_stream(sort.clone(), session.inner().task_ctx())?;
+        let stream = manager.wrap_stream("test", sort.clone(), stream);
+        let result = common::collect(stream).await?;
+        let result = concat_batches(&schema, &result)?;
+        let values = result
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(values.len(), 64 * 8192);
+        assert!(values.values().windows(2).all(|w| w[0] <= w[1]));
+
+        let (spill_count, spilled_bytes) = spill_metrics(sort.as_ref());
+        assert!(spill_count > 1);
+        assert!(spilled_bytes > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_spill_quota() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = spill_manager(dir.path().to_str().unwrap(), 100);
+
+        assert!(manager.spill(60).is_ok());
+        assert!(manager.spill(60).is_err());
+        assert_eq!(manager.metrics.query_spill_disk_usage().fetch(), 120);
+
+        manager.release(60);
+        assert_eq!(manager.disk_usage(), 60);
+        assert!(manager.spill(0).is_ok());
+        manager.release(60);
+        assert_eq!(manager.metrics.query_spill_disk_usage().fetch(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sort_spill() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = spill_manager(dir.path().to_str().unwrap(), 0);
+
+        sort_with_spill(manager.clone()).await.unwrap();
+        // the spill files are released after the sort
+        assert_eq!(manager.disk_usage(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sort_spill_over_quota() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = spill_manager(dir.path().to_str().unwrap(), 1);
+
+        let err = sort_with_spill(manager).await.unwrap_err();
+        assert!(err.to_string().contains("quota"), "{err}");
+    }
+
+    /// Plans the query like the query server, on a table of 64 batches of 8192 distinct values
+    async fn sql_with_spill(
+        manager: Arc<SpillManager>,
+        sql: &str,
+    ) -> datafusion::common::Result<i64> {
+        let session = session_ctx(manager.clone(), Some(1));
+        let (schema, batches) = batches();
+        let ctx = SessionContext::with_state(session.inner().clone());
+        ctx.register_table("t", Arc::new(MemTable::try_new(schema, vec![batches])?))?;
+
+        let state = ctx.state();
+        let logical_plan = state.optimize(&state.create_logical_plan(sql).await?)?;
+        let plan = DefaultPhysicalPlanner::default()
+            .create_physical_plan(&logical_plan, &session)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        let stream = execute_stream(plan.clone(), session.inner().task_ctx())?;
+        let stream = manager.wrap_stream("test", plan, stream);
+        let result = common::collect(stream).await?;
+        let result = concat_batches(&result[0].schema(), &result)?;
+        Ok(result
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap()
+            .value(0))
+    }
+
+    #[tokio::test]
+    async fn test_hash_operators_spill() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = spill_manager(dir.path().to_str().unwrap(), 0);
+
+        // 64 * 8192 groups, planned as a sorted aggregate
+        let groups = sql_with_spill(
+            manager.clone(),
+            "SELECT count(*) FROM (SELECT a, count(*) FROM t GROUP BY a)",
+        )
+        .await
+        .unwrap();
+        assert_eq!(groups, 64 * 8192);
+
+        // planned as a sort merge join
+        let rows = sql_with_spill(
+            manager.clone(),
+            "SELECT count(*) FROM t t1 JOIN t t2 ON t1.a = t2.a",
+        )
+        .await
+        .unwrap();
+        assert_eq!(rows, 64 * 8192);
+        assert_eq!(manager.disk_usage(), 0);
+    }
+
+    #[test]
+    fn test_spill_disabled() {
+        let mut config = Config::default();
+        config.query.enable_spill = false;
+        let metrics = Arc::new(CoordServiceMetrics::new(&MetricsRegister::default()));
+        let manager = SpillManager::try_new(&QueryOptions::from(&config), metrics).unwrap();
+
+        assert!(matches!(
+            manager.disk_manager_config(),
+            datafusion::execution::disk_manager::DiskManagerConfig::Disabled
+        ));
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::service::protocol::QueryId;
+use spi::{QueryError, Result};
+
+use super::SystemTask;
+use crate::dispatcher::query_tracker::QueryTracker;
+
+pub struct KillClusterQueryTask {
+    query_tracker: Arc<QueryTracker>,
+
+    query_id: QueryId,
+}
+
+impl KillClusterQueryTask {
+    pub fn new(query_tracker: Arc<QueryTracker>, query_id: QueryId) -> Self {
+        Self {
+            query_tracker,
+            query_id,
+        }
+    }
+}
+
+#[async_trait]
+impl SystemTask for KillClusterQueryTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        if let Some(q) = self.query_tracker.expire_query(&self.query_id) {
+            let _ = q.cancel();
+            return Ok(Output::Nil(()));
+        }
+
+        let killed = query_state_machine
+            .coord
+            .kill_cluster_query(self.query_id.get())
+            .await?;
+        if !killed {
+            return Err(QueryError::QueryNotFound {
+                query_id: self.query_id,
+            });
+        }
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -1,3 +1,4 @@
+mod kill_cluster_query;
 mod kill_query;
 
 use std::sync::Arc;
@@ -8,6 +9,7 @@ use spi::query::execution::{Output, QueryExecution, QueryStateMachineRef};
 use spi::query::logical_planner::SYSPlan;
 use spi::Result;
 
+use self::kill_cluster_query::KillClusterQueryTask;
 use self::kill_query::KillQueryTask;
 use crate::dispatcher::query_tracker::QueryTracker;
 
@@ -92,6 +94,10 @@ impl SystemTaskFactory {
             SYSPlan::KillQuery(query_id) => {
                 Box::new(KillQueryTask::new(self.query_tracker.clone(), *query_id))
             }
+            SYSPlan::KillClusterQuery(query_id) => Box::new(KillClusterQueryTask::new(
+                self.query_tracker.clone(),
+                *query_id,
+            )),
         }
     }
 }
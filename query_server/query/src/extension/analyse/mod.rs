@@ -3,7 +3,9 @@ use datafusion::logical_expr::LogicalPlan;
 
 pub mod initial_plan_checker;
 pub mod stream_checker;
+pub mod transform_asof_join;
 pub mod transform_bottom_func_to_topk_node;
+pub mod transform_event_window;
 pub mod transform_gapfill;
 pub mod transform_time_window;
 pub mod transform_topk_func_to_topk_node;
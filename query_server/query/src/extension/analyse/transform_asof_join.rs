@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::DFSchema;
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::expr::ScalarUDF;
+use datafusion::logical_expr::{
+    BinaryExpr, ExprSchemable, Extension, Join, JoinType, LogicalPlan, Operator,
+};
+use datafusion::optimizer::analyzer::AnalyzerRule;
+use datafusion::optimizer::utils::split_conjunction;
+use datafusion::prelude::Expr;
+use spi::QueryError;
+use trace::debug;
+
+use crate::extension::expr::expr_utils::find_exprs_in_exprs_deeply_nested;
+use crate::extension::expr::ASOF_MATCH_CONDITION;
+use crate::extension::logical::plan_node::asof_join::{AsofJoinNode, AsofMatchOp};
+
+/// Convert the left join generated from `ASOF JOIN ... MATCH_CONDITION(...)` to AsofJoin
+///
+/// The parser rewrites `MATCH_CONDITION(cond)` to a `asof_match_condition(cond)` join filter,
+/// the other conditions of the join must be equalities between a column of each side, usually
+/// on tags. The whole `ON` clause is still in the join filter when the analyzer runs, so the
+/// equalities are moved from the filter to the join keys.
+pub struct TransformAsofJoinRule;
+
+impl AnalyzerRule for TransformAsofJoinRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
+        plan.transform_up(&analyze_internal)
+    }
+
+    fn name(&self) -> &str {
+        "transform_asof_join"
+    }
+}
+
+fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    match &plan {
+        LogicalPlan::Join(join) if join.filter.as_ref().map_or(false, has_match_condition) => {
+            let node = make_asof_join(join).map_err(|e| DataFusionError::External(Box::new(e)))?;
+            debug!("Construct asof join: {:?}", node);
+
+            Ok(Transformed::Yes(LogicalPlan::Extension(Extension {
+                node: Arc::new(node),
+            })))
+        }
+        _ => {
+            let exprs = plan.expressions();
+            if !find_exprs_in_exprs_deeply_nested(&exprs, &is_match_condition).is_empty() {
+                return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                    err: format!("{ASOF_MATCH_CONDITION} can only be used in ASOF JOIN"),
+                })));
+            }
+            Ok(Transformed::No(plan))
+        }
+    }
+}
+
+fn is_match_condition(expr: &Expr) -> bool {
+    matches!(expr, Expr::ScalarUDF(ScalarUDF { fun, .. }) if fun.name == ASOF_MATCH_CONDITION)
+}
+
+fn has_match_condition(expr: &Expr) -> bool {
+    !find_exprs_in_exprs_deeply_nested(&[expr.clone()], &is_match_condition).is_empty()
+}
+
+fn make_asof_join(join: &Join) -> Result<AsofJoinNode, QueryError> {
+    if join.join_type != JoinType::Left {
+        return Err(QueryError::Analyzer {
+            err: format!("ASOF JOIN doesn't support {} join", join.join_type),
+        });
+    }
+
+    let left_schema = join.left.schema();
+    let right_schema = join.right.schema();
+
+    let mut on = join.on.clone();
+    let mut match_condition = None;
+    if let Some(filter) = &join.filter {
+        for expr in split_conjunction(filter) {
+            match expr {
+                Expr::ScalarUDF(ScalarUDF { fun, args })
+                    if fun.name == ASOF_MATCH_CONDITION && match_condition.is_none() =>
+                {
+                    match_condition = args.first();
+                }
+                _ => match equi_join_key(expr, left_schema, right_schema) {
+                    Some(key) => on.push(key),
+                    None => {
+                        return Err(QueryError::Analyzer {
+                            err: format!(
+                                "ASOF JOIN only supports equality conditions besides MATCH_CONDITION, but found {expr}"
+                            ),
+                        })
+                    }
+                },
+            }
+        }
+    }
+
+    let (left_time, op, right_time) = match match_condition {
+        Some(Expr::BinaryExpr(BinaryExpr { left, op, right })) => {
+            let op = AsofMatchOp::try_from_operator(*op).ok_or_else(|| QueryError::Analyzer {
+                err: format!("MATCH_CONDITION only supports >, >=, < and <=, but found {op}"),
+            })?;
+            if belongs_to(left, left_schema) && belongs_to(right, right_schema) {
+                (left.as_ref().clone(), op, right.as_ref().clone())
+            } else if belongs_to(right, left_schema) && belongs_to(left, right_schema) {
+                (right.as_ref().clone(), op.swap(), left.as_ref().clone())
+            } else {
+                return Err(QueryError::Analyzer {
+                    err: format!(
+                        "MATCH_CONDITION must compare a column of each side, but found {left} {op} {right}"
+                    ),
+                });
+            }
+        }
+        other => {
+            return Err(QueryError::Analyzer {
+                err: format!("Invalid MATCH_CONDITION of ASOF JOIN: {other:?}"),
+            })
+        }
+    };
+
+    check_time_type(&left_time, join.left.schema())?;
+    check_time_type(&right_time, join.right.schema())?;
+
+    Ok(AsofJoinNode {
+        left: join.left.clone(),
+        right: join.right.clone(),
+        on,
+        left_time,
+        right_time,
+        op,
+        schema: join.schema.clone(),
+    })
+}
+
+/// The (left, right) join key of a `col = col` condition between a column of each side
+fn equi_join_key(
+    expr: &Expr,
+    left_schema: &DFSchema,
+    right_schema: &DFSchema,
+) -> Option<(Expr, Expr)> {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        }) if matches!(
+            (left.as_ref(), right.as_ref()),
+            (Expr::Column(_), Expr::Column(_))
+        ) =>
+        {
+            if belongs_to(left, left_schema) && belongs_to(right, right_schema) {
+                Some((left.as_ref().clone(), right.as_ref().clone()))
+            } else if belongs_to(right, left_schema) && belongs_to(left, right_schema) {
+                Some((right.as_ref().clone(), left.as_ref().clone()))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn belongs_to(expr: &Expr, schema: &DFSchema) -> bool {
+    match expr.to_columns() {
+        Ok(columns) => {
+            !columns.is_empty() && columns.iter().all(|c| schema.field_from_column(c).is_ok())
+        }
+        Err(_) => false,
+    }
+}
+
+fn check_time_type(expr: &Expr, schema: &DFSchema) -> Result<(), QueryError> {
+    let data_type = expr.get_type(schema)?;
+    if !matches!(data_type, DataType::Timestamp(_, _)) {
+        return Err(QueryError::Analyzer {
+            err: format!("MATCH_CONDITION must compare timestamps, but {expr} is {data_type}"),
+        });
+    }
+    Ok(())
+}
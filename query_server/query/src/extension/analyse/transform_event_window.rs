@@ -0,0 +1,198 @@
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::DFSchemaRef;
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::{expr, LogicalPlan, LogicalPlanBuilder};
+use datafusion::optimizer::analyzer::AnalyzerRule;
+use datafusion::prelude::{col, Expr};
+use datafusion::scalar::ScalarValue;
+use models::schema::TIME_FIELD_NAME;
+use spi::QueryError;
+use trace::debug;
+
+use super::transform_time_window::{parse_duration_arg, simplify_expr, valid_duration};
+use crate::extension::expr::expr_fn::is_not_null;
+use crate::extension::expr::expr_utils::find_exprs_in_exprs_deeply_nested;
+use crate::extension::expr::{COUNT_WINDOW, SESSION_WINDOW, TIME_WINDOW, WINDOW_COL_NAME};
+use crate::extension::logical::logical_plan_builder::LogicalPlanBuilderExt;
+use crate::extension::logical::plan_node::event_window::EventWindowType;
+use crate::extension::logical::plan_node::LogicalPlanExt;
+
+/// Convert the [`SESSION_WINDOW`] and [`COUNT_WINDOW`] functions to EventWindow
+///
+/// The other group expressions of the aggregation are used as the partition keys of the windows,
+/// e.g. `group by session_window(time, interval '5 minutes'), device` computes sessions per device.
+pub struct TransformEventWindowRule;
+
+impl AnalyzerRule for TransformEventWindowRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
+        plan.transform_up(&analyze_internal)
+    }
+
+    fn name(&self) -> &str {
+        "transform_event_window"
+    }
+}
+
+fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    if plan.inputs().len() != 1 {
+        return Ok(Transformed::No(plan));
+    }
+
+    let mut window_expressions = find_window_exprs(&plan, &[SESSION_WINDOW, COUNT_WINDOW]);
+    if window_expressions.is_empty() {
+        return Ok(Transformed::No(plan));
+    }
+
+    // Only support a single window expression for now
+    if window_expressions.len() > 1 || !find_window_exprs(&plan, &[TIME_WINDOW]).is_empty() {
+        return Err(DataFusionError::Plan(format!(
+            "Only support a single window expression for now, but found: {window_expressions:?}"
+        )));
+    }
+
+    let child = plan.inputs()[0];
+    let window_expr = window_expressions.remove(0);
+    let window = make_event_window(window_expr, child.schema().clone())
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+    debug!("Construct event window: {:?}", window);
+
+    let partition_by = match &plan {
+        LogicalPlan::Aggregate(agg) => agg
+            .group_expr
+            .iter()
+            .filter(|e| !is_window_expr(e, &[SESSION_WINDOW, COUNT_WINDOW]))
+            .cloned()
+            .collect(),
+        _ => vec![],
+    };
+
+    let EventWindow {
+        window_alias,
+        window_type,
+        time_column,
+    } = window;
+
+    let window_plan = LogicalPlanBuilder::from(child.clone())
+        .filter(is_not_null(time_column.clone()))?
+        .event_window(window_type, time_column, partition_by)?
+        .build()?;
+
+    debug!("Origin plan: {}", plan.display_indent_schema());
+    debug!("Window_plan: {}", window_plan.display_indent_schema());
+
+    // replace current plan's child
+    let wait_replaced_plan = plan.with_new_inputs(&[window_plan])?;
+
+    // replace new plan's exprs
+    let final_plan = wait_replaced_plan.transform_expressions_down(&|expr: &Expr| {
+        if is_window_expr(expr, &[SESSION_WINDOW, COUNT_WINDOW]) {
+            Some(col(WINDOW_COL_NAME).alias(&window_alias))
+        } else {
+            None
+        }
+    })?;
+
+    debug!("Final plan: {}", final_plan.display_indent_schema());
+
+    Ok(Transformed::Yes(final_plan))
+}
+
+fn is_window_expr(expr: &Expr, names: &[&str]) -> bool {
+    matches!(expr, Expr::ScalarUDF(expr::ScalarUDF {
+        fun,
+        ..
+    }) if names.contains(&fun.name.as_str()))
+}
+
+fn find_window_exprs(plan: &LogicalPlan, names: &[&str]) -> Vec<Expr> {
+    let exprs = plan.expressions();
+    find_exprs_in_exprs_deeply_nested(&exprs, &|nested_expr| is_window_expr(nested_expr, names))
+}
+
+#[derive(Debug)]
+struct EventWindow {
+    window_alias: String,
+    window_type: EventWindowType,
+    time_column: Expr,
+}
+
+fn make_event_window(expr: Expr, schema: DFSchemaRef) -> Result<EventWindow, QueryError> {
+    let window_alias = expr.display_name()?;
+    match expr {
+        Expr::ScalarUDF(expr::ScalarUDF { fun, args }) if fun.name == SESSION_WINDOW => {
+            let mut args = args.into_iter();
+
+            // first arg: time_column
+            let time_column = args.next().ok_or_else(|| QueryError::Internal {
+                reason: format!("Invalid signature of {SESSION_WINDOW}"),
+            })?;
+            // second arg: gap_duration
+            let gap = args.next().ok_or_else(|| QueryError::Internal {
+                reason: format!("Invalid signature of {SESSION_WINDOW}"),
+            })?;
+            let gap = simplify_expr(gap, schema)?;
+            let gap = valid_duration(parse_duration_arg(&gap)?)?;
+
+            Ok(EventWindow {
+                window_alias,
+                window_type: EventWindowType::Session { gap },
+                time_column,
+            })
+        }
+        Expr::ScalarUDF(expr::ScalarUDF { fun, args }) if fun.name == COUNT_WINDOW => {
+            // first arg: window_size
+            let size = args
+                .into_iter()
+                .next()
+                .ok_or_else(|| QueryError::Internal {
+                    reason: format!("Invalid signature of {COUNT_WINDOW}"),
+                })?;
+            let size = simplify_expr(size, schema.clone())?;
+            let size = parse_count_arg(&size)?;
+
+            // count window is always ordered by the time column of the table
+            let time_field = schema
+                .field_with_unqualified_name(TIME_FIELD_NAME)
+                .map_err(|_| QueryError::InvalidTimeWindowParam {
+                    reason: format!("{COUNT_WINDOW} requires a '{TIME_FIELD_NAME}' column"),
+                })?;
+
+            Ok(EventWindow {
+                window_alias,
+                window_type: EventWindowType::Count { size },
+                time_column: Expr::Column(time_field.qualified_column()),
+            })
+        }
+        _ => Err(QueryError::Internal {
+            reason: format!("Expected EventWindow, but found {expr}"),
+        }),
+    }
+}
+
+fn parse_count_arg(expr: &Expr) -> Result<u64, QueryError> {
+    let size = match expr {
+        Expr::Literal(ScalarValue::Int8(Some(v))) => *v as i64,
+        Expr::Literal(ScalarValue::Int16(Some(v))) => *v as i64,
+        Expr::Literal(ScalarValue::Int32(Some(v))) => *v as i64,
+        Expr::Literal(ScalarValue::Int64(Some(v))) => *v,
+        Expr::Literal(ScalarValue::UInt8(Some(v))) => *v as i64,
+        Expr::Literal(ScalarValue::UInt16(Some(v))) => *v as i64,
+        Expr::Literal(ScalarValue::UInt32(Some(v))) => *v as i64,
+        Expr::Literal(ScalarValue::UInt64(Some(v))) => i64::try_from(*v).unwrap_or(i64::MAX),
+        _ => {
+            return Err(QueryError::InvalidTimeWindowParam {
+                reason: format!("Expected integer literal, but found {expr}"),
+            })
+        }
+    };
+
+    if size <= 0 {
+        return Err(QueryError::InvalidTimeWindowParam {
+            reason: format!("Window size must be greater than 0, but found {size}"),
+        });
+    }
+
+    Ok(size as u64)
+}
@@ -7,10 +7,14 @@ use std::collections::{hash_map, HashMap, HashSet};
 use std::ops::{Bound, Range};
 use std::sync::Arc;
 
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::tree_node::{
     RewriteRecursion, Transformed, TreeNode, TreeNodeRewriter, VisitRecursion,
 };
+use datafusion::common::DFSchema;
 use datafusion::config::ConfigOptions;
+use datafusion::datasource::{provider_as_source, source_as_provider};
 use datafusion::error::{DataFusionError, Result};
 use datafusion::logical_expr::expr::ScalarUDF;
 use datafusion::logical_expr::utils::expr_to_columns;
@@ -18,10 +22,16 @@ use datafusion::logical_expr::{
     expr, Aggregate, Extension, GetIndexedField, LogicalPlan, Projection,
 };
 use datafusion::optimizer::analyzer::AnalyzerRule;
+use datafusion::physical_expr::create_physical_expr;
+use datafusion::physical_expr::execution_props::ExecutionProps;
+use datafusion::physical_plan::ColumnarValue;
 use datafusion::prelude::{col, Expr};
+use datafusion::scalar::ScalarValue;
 
+use crate::data_source::batch::tskv::ClusterTable;
 use crate::extension::expr::{
-    INTERPOLATE, LOCF, TIME_WINDOW_GAPFILL, TIME_WINDOW_UDF, WINDOW_START,
+    ceil_sliding_window, DEFAULT_TIME_WINDOW_START, INTERPOLATE, INTERPOLATE_WITH_PRIOR, LOCF,
+    LOCF_WITH_PRIOR, TIME_WINDOW_GAPFILL, TIME_WINDOW_UDF, WINDOW_START,
 };
 use crate::extension::logical::plan_node::gapfill::{FillStrategy, GapFill, GapFillParams};
 
@@ -386,12 +396,17 @@ impl TreeNodeRewriter for TimeWindowGapfillRewriter {
 
 fn udf_to_fill_strategy(name: &str) -> Option<FillStrategy> {
     match name {
-        LOCF => Some(FillStrategy::PrevNullAsMissing),
-        INTERPOLATE => Some(FillStrategy::LinearInterpolate),
+        LOCF | LOCF_WITH_PRIOR => Some(FillStrategy::PrevNullAsMissing),
+        INTERPOLATE | INTERPOLATE_WITH_PRIOR => Some(FillStrategy::LinearInterpolate),
         _ => None,
     }
 }
 
+/// Whether the fill function also uses the last point of each series before the time range
+fn udf_reads_prior_point(name: &str) -> bool {
+    matches!(name, LOCF_WITH_PRIOR | INTERPOLATE_WITH_PRIOR)
+}
+
 fn fill_strategy_to_udf(fs: &FillStrategy) -> Result<&'static str> {
     match fs {
         FillStrategy::PrevNullAsMissing => Ok(LOCF),
@@ -420,16 +435,28 @@ fn handle_projection(proj: &Projection) -> Result<Option<LogicalPlan>> {
 
     let mut fill_fn_rewriter = FillFnRewriter {
         aggr_col_fill_map: HashMap::new(),
+        with_prior_point: false,
+        without_prior_point: false,
     };
     let new_proj_exprs = proj_exprs
         .iter()
         .map(|e| e.clone().rewrite(&mut fill_fn_rewriter))
         .collect::<Result<Vec<Expr>>>()?;
 
-    let FillFnRewriter { aggr_col_fill_map } = fill_fn_rewriter;
+    let FillFnRewriter {
+        aggr_col_fill_map,
+        with_prior_point,
+        without_prior_point,
+    } = fill_fn_rewriter;
     if aggr_col_fill_map.is_empty() {
         return Ok(None);
     }
+    if with_prior_point && without_prior_point {
+        return Err(DataFusionError::Plan(format!(
+            "{LOCF_WITH_PRIOR} and {INTERPOLATE_WITH_PRIOR} can not be used together with \
+            {LOCF} and {INTERPOLATE} in a gap-filling query"
+        )));
+    }
 
     // Clone the existing GapFill node, then modify it in place
     // to reflect the new fill strategy.
@@ -444,6 +471,18 @@ fn handle_projection(proj: &Projection) -> Result<Option<LogicalPlan>> {
         }
     }
 
+    // Fill the gaps at the beginning of the time range with the last points before it,
+    // only if it is asked for by `LOCF_WITH_PRIOR` or `INTERPOLATE_WITH_PRIOR`.
+    // If the first output timestamp can not be evaluated here, the `GapFill` node will
+    // report the error when it is evaluated again in execution.
+    if with_prior_point {
+        if let Ok(Some(first_ts)) = first_output_timestamp(&new_gapfill.params) {
+            if let Some(new_input) = scan_prior_points(&new_gapfill.input, first_ts)? {
+                new_gapfill.input = Arc::new(new_input);
+            }
+        }
+    }
+
     let new_proj = {
         let mut proj = proj.clone();
         proj.expr = new_proj_exprs;
@@ -457,14 +496,93 @@ fn handle_projection(proj: &Projection) -> Result<Option<LogicalPlan>> {
     Ok(Some(new_proj))
 }
 
+/// Evaluates the first timestamp in nanoseconds to be output by the `GapFill` node,
+/// i.e. the start of the first window containing the lower bound of the time range.
+///
+/// The windows of the points before it are all before the first output timestamp,
+/// so these points never contribute to the aggregates of the output rows.
+fn first_output_timestamp(params: &GapFillParams) -> Result<Option<i64>> {
+    let (start, offset) = match &params.time_range.start {
+        Bound::Included(e) => (e, 0),
+        Bound::Excluded(e) => (e, 1),
+        Bound::Unbounded => return Ok(None),
+    };
+
+    let schema = Arc::new(Schema::empty());
+    let batch = RecordBatch::new_empty(schema.clone());
+    let props = ExecutionProps::new();
+    let evaluate =
+        |e: &Expr| create_physical_expr(e, &DFSchema::empty(), &schema, &props)?.evaluate(&batch);
+
+    let start = match evaluate(start)? {
+        ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(Some(v), _)) => v + offset,
+        _ => return Ok(None),
+    };
+    let stride = evaluate(&params.stride)?;
+    let sliding = evaluate(&params.sliding)?;
+    let origin = params
+        .origin
+        .as_ref()
+        .map(evaluate)
+        .transpose()?
+        .unwrap_or(ColumnarValue::Scalar(DEFAULT_TIME_WINDOW_START.clone()));
+
+    Ok(Some(
+        ceil_sliding_window(start, &stride, &sliding, &origin)?.0,
+    ))
+}
+
+/// Makes the tskv scans under the `Aggregate` input of a `GapFill` node also read
+/// the last point of each series before `first_ts`, which are used by `LOCF`
+/// and `INTERPOLATE` to fill the gaps at the beginning of the time range.
+///
+/// Returns `None` if there is no such scan.
+fn scan_prior_points(plan: &LogicalPlan, first_ts: i64) -> Result<Option<LogicalPlan>> {
+    fn rewrite_scan(plan: &LogicalPlan, first_ts: i64) -> Result<Option<LogicalPlan>> {
+        match plan {
+            LogicalPlan::TableScan(scan) => {
+                let Some(table) = source_as_provider(&scan.source)?
+                    .as_any()
+                    .downcast_ref::<ClusterTable>()
+                    .map(|t| t.with_prior_point(first_ts))
+                else {
+                    return Ok(None);
+                };
+                let mut scan = scan.clone();
+                scan.source = provider_as_source(Arc::new(table));
+                Ok(Some(LogicalPlan::TableScan(scan)))
+            }
+            LogicalPlan::Projection(_) | LogicalPlan::Filter(_) | LogicalPlan::SubqueryAlias(_) => {
+                match rewrite_scan(plan.inputs()[0], first_ts)? {
+                    Some(new_input) => Ok(Some(plan.with_new_inputs(&[new_input])?)),
+                    None => Ok(None),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    match plan {
+        LogicalPlan::Aggregate(aggr) => match rewrite_scan(&aggr.input, first_ts)? {
+            Some(new_input) => Ok(Some(plan.with_new_inputs(&[new_input])?)),
+            None => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
 /// Implements `TreeNodeRewriter`:
 /// - Traverses over the expressions in a projection node
-/// - If it finds `locf(col)` or `interpolate(col)`,
+/// - If it finds `locf(col)` or `interpolate(col)`, or their `_with_prior` variants,
 ///   it replaces them with `col AS <original name>`
 /// - Collects into [`Self::aggr_col_fill_map`] which correlates
 ///   aggregate columns to their [`FillStrategy`].
 struct FillFnRewriter {
     aggr_col_fill_map: HashMap<Expr, FillStrategy>,
+    /// Whether a fill function reading the prior points is found
+    with_prior_point: bool,
+    /// Whether a fill function not reading the prior points is found
+    without_prior_point: bool,
 }
 
 impl TreeNodeRewriter for FillFnRewriter {
@@ -488,6 +606,11 @@ impl TreeNodeRewriter for FillFnRewriter {
             }
             Expr::ScalarUDF(ScalarUDF { fun, mut args }) => {
                 let fs = udf_to_fill_strategy(&fun.name).expect("must be a fill fn");
+                if udf_reads_prior_point(&fun.name) {
+                    self.with_prior_point = true;
+                } else {
+                    self.without_prior_point = true;
+                }
                 let arg = args.remove(0);
                 self.add_fill_strategy(arg.clone(), fs)?;
                 Ok(arg.alias(orig_name))
@@ -534,7 +657,7 @@ fn check_node(node: &LogicalPlan) -> Result<()> {
             )));
         }
 
-        for fn_name in [LOCF, INTERPOLATE] {
+        for fn_name in [LOCF, INTERPOLATE, LOCF_WITH_PRIOR, INTERPOLATE_WITH_PRIOR] {
             if count_udf(expr, fn_name)? > 0 {
                 return Err(DataFusionError::Plan(format!(
                     "{fn_name} may only be used in the SELECT list of a gap-filling query"
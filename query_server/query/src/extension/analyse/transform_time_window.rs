@@ -140,7 +140,7 @@ fn make_time_window(expr: Expr, schema: DFSchemaRef) -> Result<TimeWindow, Query
     }
 }
 
-fn valid_duration(dur: Duration) -> Result<Duration, QueryError> {
+pub fn valid_duration(dur: Duration) -> Result<Duration, QueryError> {
     if dur.as_millis() > (365 * DAY).into() || dur.as_millis() == 0 {
         return Err(QueryError::InvalidTimeWindowParam {
             reason: format!("Max duration is (0s, 365d], but found {}s", dur.as_secs()),
@@ -152,7 +152,7 @@ fn valid_duration(dur: Duration) -> Result<Duration, QueryError> {
 
 /// Convert string time duration to [`Duration`] \
 /// Only support [`ScalarValue::IntervalYearMonth`] | [`ScalarValue::IntervalMonthDayNano`] | [`ScalarValue::IntervalDayTime`]
-fn parse_duration_arg(expr: &Expr) -> Result<Duration, QueryError> {
+pub fn parse_duration_arg(expr: &Expr) -> Result<Duration, QueryError> {
     let nano = match expr {
         Expr::Literal(ScalarValue::IntervalYearMonth(val)) => ym_to_nano(val),
         Expr::Literal(ScalarValue::IntervalMonthDayNano(val)) => mdn_to_nano(val),
@@ -407,7 +407,7 @@ fn replace_window_expr(new_expr: Expr, plan: &LogicalPlan) -> Result<LogicalPlan
     })
 }
 
-fn simplify_expr(expr: Expr, schema: DFSchemaRef) -> Result<Expr> {
+pub fn simplify_expr(expr: Expr, schema: DFSchemaRef) -> Result<Expr> {
     let mut execution_props = ExecutionProps::new();
     let ctx = OptimizerContext::new();
     execution_props.query_execution_start_time = ctx.query_execution_start_time();
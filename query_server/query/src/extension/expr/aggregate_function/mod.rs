@@ -7,7 +7,11 @@ mod increase;
 mod last;
 mod mode;
 mod sample;
+mod series;
+mod sketch;
+mod st_makeline;
 mod state_agg;
+mod trajectory;
 
 use std::sync::Arc;
 
@@ -31,8 +35,25 @@ pub const COMPLETENESS_UDF_NAME: &str = "completeness";
 pub const CONSISTENCY_UDF_NAME: &str = "consistency";
 pub const TIMELINESS_UDF_NAME: &str = "timeliness";
 pub const VALIDITY_UDF_NAME: &str = "validity";
+pub const PERCENTILE_AGG_UDAF_NAME: &str = "percentile_agg";
+pub const HLL_AGG_UDAF_NAME: &str = "hll_agg";
+pub const TOPN_AGG_UDAF_NAME: &str = "topn_agg";
+pub const SKETCH_ROLLUP_UDAF_NAME: &str = "sketch_rollup";
+// `rate` is the accessor of gauge_agg
+pub const COUNTER_RATE_UDAF_NAME: &str = "counter_rate";
+pub const IRATE_UDAF_NAME: &str = "irate";
+pub const DERIVATIVE_UDAF_NAME: &str = "derivative";
+pub const NON_NEGATIVE_DERIVATIVE_UDAF_NAME: &str = "non_negative_derivative";
+pub const DIFFERENCE_UDAF_NAME: &str = "difference";
+pub const MOVING_AVERAGE_UDAF_NAME: &str = "moving_average";
+pub const CUMULATIVE_SUM_UDAF_NAME: &str = "cumulative_sum";
+pub const ELAPSED_UDAF_NAME: &str = "elapsed";
+pub const ST_MAKELINE_UDAF_NAME: &str = "st_makeline";
+pub const TRAJECTORY_AGG_UDAF_NAME: &str = "trajectory_agg";
 pub use gauge::GaugeData;
+pub use sketch::SketchData;
 pub use state_agg::StateAggData;
+pub use trajectory::TrajectoryData;
 
 pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> Result<()> {
     // extend function...
@@ -46,6 +67,10 @@ pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> Result<
     mode::register_udaf(func_manager)?;
     increase::register_udaf(func_manager)?;
     data_quality::register_udafs(func_manager)?;
+    sketch::register_udafs(func_manager)?;
+    series::register_udafs(func_manager)?;
+    st_makeline::register_udaf(func_manager)?;
+    trajectory::register_udafs(func_manager)?;
     Ok(())
 }
 
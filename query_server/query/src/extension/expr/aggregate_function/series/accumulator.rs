@@ -0,0 +1,507 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Bound;
+
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array, Int64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::common::cast::{as_float64_array, as_int64_array, as_list_array};
+use datafusion::common::scalar::{dt_to_nano, mdn_to_nano, ym_to_nano};
+use datafusion::common::{downcast_value, DataFusionError, Result as DFResult};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use spi::QueryError;
+
+use super::SeriesFunction;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point {
+    /// nanoseconds
+    ts: i64,
+    val: f64,
+}
+
+/// The time of a point and its arrival sequence, points of the same time are kept in arrival order
+type PointKey = (i64, u64);
+
+/// Keep the points of the series ordered by time, the running `sum` and `increase`
+/// are maintained on each insertion and removal, so that the points arriving out of order
+/// never need to be sorted again.
+///
+/// The keys are also kept in arrival order with the null rows as `None`, so that `retract_batch`
+/// of a sliding window frame can remove the same number of rows as were added.
+#[derive(Debug)]
+pub struct SeriesAccumulator {
+    func: SeriesFunction,
+    /// unit in nanoseconds, or the size of the moving window
+    param: Option<i64>,
+    points: BTreeMap<PointKey, f64>,
+    arrivals: VecDeque<Option<PointKey>>,
+    next_seq: u64,
+    sum: f64,
+    increase: f64,
+}
+
+impl SeriesAccumulator {
+    pub fn new(func: SeriesFunction) -> Self {
+        Self {
+            func,
+            param: None,
+            points: BTreeMap::new(),
+            arrivals: VecDeque::new(),
+            next_seq: 0,
+            sum: 0.0,
+            increase: 0.0,
+        }
+    }
+
+    /// The points before and after `key`, excluding `key`
+    fn neighbors(&self, key: PointKey) -> (Option<Point>, Option<Point>) {
+        let prev = self.points.range(..key).next_back().map(to_point);
+        let next = self
+            .points
+            .range((Bound::Excluded(key), Bound::Unbounded))
+            .next()
+            .map(to_point);
+        (prev, next)
+    }
+
+    /// The change of `increase` made by inserting `p` between `prev` and `next`
+    fn increase_delta(prev: Option<Point>, p: &Point, next: Option<Point>) -> f64 {
+        let mut delta = 0.0;
+        if let Some(prev) = &prev {
+            delta += counter_delta(prev, p);
+        }
+        if let Some(next) = &next {
+            delta += counter_delta(p, next);
+        }
+        if let (Some(prev), Some(next)) = (&prev, &next) {
+            delta -= counter_delta(prev, next);
+        }
+        delta
+    }
+
+    fn push(&mut self, point: Option<Point>) {
+        let key = point.map(|p| {
+            let key = (p.ts, self.next_seq);
+            self.next_seq += 1;
+            let (prev, next) = self.neighbors(key);
+            self.increase += Self::increase_delta(prev, &p, next);
+            self.sum += p.val;
+            self.points.insert(key, p.val);
+            key
+        });
+        self.arrivals.push_back(key);
+    }
+
+    fn pop_front(&mut self) {
+        if let Some(Some(key)) = self.arrivals.pop_front() {
+            if let Some(val) = self.points.remove(&key) {
+                let (prev, next) = self.neighbors(key);
+                self.increase -= Self::increase_delta(prev, &to_point((&key, &val)), next);
+                self.sum -= val;
+            }
+        }
+
+        if self.points.is_empty() {
+            // avoid the accumulated floating point error
+            self.sum = 0.0;
+            self.increase = 0.0;
+        }
+    }
+
+    fn update_param(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        if self.param.is_some() {
+            return Ok(());
+        }
+
+        let array = match self.func.param_index().and_then(|i| values.get(i)) {
+            Some(array) if !array.is_empty() => array,
+            _ => return Ok(()),
+        };
+
+        let scalar = ScalarValue::try_from_array(array.as_ref(), 0)?;
+        let param = match self.func {
+            SeriesFunction::MovingAverage => parse_window_size(&scalar)?,
+            _ => parse_unit(self.func, &scalar)?,
+        };
+        self.param = Some(param);
+
+        Ok(())
+    }
+
+    fn points_from_arrays(&self, values: &[ArrayRef]) -> DFResult<Vec<Option<Point>>> {
+        let len = values[0].len();
+        let (times, vals) = match self.func {
+            SeriesFunction::CumulativeSum => (None, Some(cast_to_f64(&values[0])?)),
+            SeriesFunction::Elapsed => (Some(cast_to_nanos(&values[0])?), None),
+            _ => (
+                Some(cast_to_nanos(&values[0])?),
+                Some(cast_to_f64(&values[1])?),
+            ),
+        };
+
+        let times = times
+            .as_ref()
+            .map(|a| as_int64_array(a.as_ref()))
+            .transpose()?;
+        let vals = vals
+            .as_ref()
+            .map(|a| as_float64_array(a.as_ref()))
+            .transpose()?;
+
+        let points = (0..len)
+            .map(|i| {
+                let ts = match times {
+                    Some(times) if times.is_null(i) => return None,
+                    Some(times) => times.value(i),
+                    None => 0,
+                };
+                let val = match vals {
+                    Some(vals) if vals.is_null(i) => return None,
+                    Some(vals) => vals.value(i),
+                    None => 0.0,
+                };
+                Some(Point { ts, val })
+            })
+            .collect();
+
+        Ok(points)
+    }
+}
+
+impl Accumulator for SeriesAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("update_batch: {:?}", values);
+
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        self.update_param(values)?;
+        for point in self.points_from_arrays(values)? {
+            self.push(point);
+        }
+
+        Ok(())
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("retract_batch: {:?}", values);
+
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        // window frames always retract the earliest rows
+        for _ in 0..values[0].len() {
+            self.pop_front();
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        let count = self.points.len();
+        let first = self.points.iter().next().map(to_point);
+        let mut rev = self.points.iter().rev().map(to_point);
+        let last = rev.next();
+        let prev = rev.next();
+        let (first, last, prev) = (first.as_ref(), last.as_ref(), prev.as_ref());
+
+        let value = match self.func {
+            SeriesFunction::CumulativeSum => {
+                return Ok(ScalarValue::Float64((count > 0).then_some(self.sum)))
+            }
+            SeriesFunction::MovingAverage => {
+                let n = self.param.unwrap_or(1) as usize;
+                if count < n {
+                    None
+                } else {
+                    let sum: f64 = self.points.values().rev().take(n).sum();
+                    Some(sum / n as f64)
+                }
+            }
+            SeriesFunction::Elapsed => {
+                let unit = self.param.unwrap_or(1);
+                let elapsed = match (first, prev.and(last)) {
+                    (Some(first), Some(last)) => Some((last.ts - first.ts) / unit),
+                    _ => None,
+                };
+                return Ok(ScalarValue::Int64(elapsed));
+            }
+            SeriesFunction::Difference => match (first, prev.and(last)) {
+                (Some(first), Some(last)) => Some(last.val - first.val),
+                _ => None,
+            },
+            SeriesFunction::Derivative | SeriesFunction::NonNegativeDerivative => {
+                let unit = self.param.unwrap_or(NANOS_PER_SECOND);
+                let derivative = match (first, prev.and(last)) {
+                    (Some(first), Some(last)) if last.ts > first.ts => {
+                        let units = (last.ts - first.ts) as f64 / unit as f64;
+                        Some((last.val - first.val) / units)
+                    }
+                    _ => None,
+                };
+                match self.func {
+                    SeriesFunction::NonNegativeDerivative => derivative.filter(|d| *d >= 0.0),
+                    _ => derivative,
+                }
+            }
+            SeriesFunction::CounterRate => match (first, prev.and(last)) {
+                (Some(first), Some(last)) if last.ts > first.ts => {
+                    Some(self.increase / seconds_between(first, last))
+                }
+                _ => None,
+            },
+            SeriesFunction::Irate => match (prev, last) {
+                (Some(prev), Some(last)) if last.ts > prev.ts => {
+                    Some(counter_delta(prev, last) / seconds_between(prev, last))
+                }
+                _ => None,
+            },
+        };
+
+        Ok(ScalarValue::Float64(value))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.points.len() * std::mem::size_of::<(PointKey, f64)>()
+            + self.arrivals.capacity() * std::mem::size_of::<Option<PointKey>>()
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let (times, values): (Vec<_>, Vec<_>) = self
+            .points
+            .iter()
+            .map(|((ts, _), val)| {
+                (
+                    ScalarValue::Int64(Some(*ts)),
+                    ScalarValue::Float64(Some(*val)),
+                )
+            })
+            .unzip();
+
+        Ok(vec![
+            ScalarValue::new_list(Some(times), DataType::Int64),
+            ScalarValue::new_list(Some(values), DataType::Float64),
+            ScalarValue::Int64(self.param),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("merge_batch: {:?}", states);
+
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        let time_list_records = as_list_array(states[0].as_ref())?;
+        let value_list_records = as_list_array(states[1].as_ref())?;
+        let params = as_int64_array(states[2].as_ref())?;
+
+        if self.param.is_none() {
+            self.param = params.iter().flatten().next();
+        }
+
+        for (time_list, value_list) in time_list_records
+            .iter()
+            .flatten()
+            .zip(value_list_records.iter().flatten())
+        {
+            let time_list = downcast_value!(time_list.as_ref(), Int64Array);
+            let value_list = downcast_value!(value_list.as_ref(), Float64Array);
+
+            for (ts, val) in time_list.iter().zip(value_list.iter()) {
+                if let (Some(ts), Some(val)) = (ts, val) {
+                    self.push(Some(Point { ts, val }));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn to_point((key, val): (&PointKey, &f64)) -> Point {
+    Point {
+        ts: key.0,
+        val: *val,
+    }
+}
+
+/// The increase of a counter from `prev` to `next`, a decrease means the counter was reset
+fn counter_delta(prev: &Point, next: &Point) -> f64 {
+    if next.val >= prev.val {
+        next.val - prev.val
+    } else {
+        next.val
+    }
+}
+
+fn seconds_between(first: &Point, last: &Point) -> f64 {
+    (last.ts - first.ts) as f64 / NANOS_PER_SECOND as f64
+}
+
+fn cast_to_nanos(array: &ArrayRef) -> DFResult<ArrayRef> {
+    let nanos = cast(array, &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+    Ok(cast(&nanos, &DataType::Int64)?)
+}
+
+fn cast_to_f64(array: &ArrayRef) -> DFResult<ArrayRef> {
+    Ok(cast(array, &DataType::Float64)?)
+}
+
+fn parse_unit(func: SeriesFunction, scalar: &ScalarValue) -> DFResult<i64> {
+    let nanos = match scalar {
+        ScalarValue::IntervalYearMonth(val) => ym_to_nano(val),
+        ScalarValue::IntervalDayTime(val) => dt_to_nano(val),
+        ScalarValue::IntervalMonthDayNano(val) => mdn_to_nano(val),
+        _ => None,
+    };
+
+    match nanos {
+        Some(nanos) if nanos > 0 => Ok(nanos as i64),
+        _ => Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+            err: format!(
+                "The unit of {} must be a positive interval, but found {}",
+                func.name(),
+                scalar
+            ),
+        }))),
+    }
+}
+
+fn parse_window_size(scalar: &ScalarValue) -> DFResult<i64> {
+    let n = match scalar {
+        ScalarValue::Int8(Some(v)) => *v as i64,
+        ScalarValue::Int16(Some(v)) => *v as i64,
+        ScalarValue::Int32(Some(v)) => *v as i64,
+        ScalarValue::Int64(Some(v)) => *v,
+        ScalarValue::UInt8(Some(v)) => *v as i64,
+        ScalarValue::UInt16(Some(v)) => *v as i64,
+        ScalarValue::UInt32(Some(v)) => *v as i64,
+        ScalarValue::UInt64(Some(v)) => i64::try_from(*v).unwrap_or(-1),
+        _ => -1,
+    };
+
+    if n <= 0 {
+        return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+            err: format!(
+                "The window size of moving_average must be a positive integer, but found {}",
+                scalar
+            ),
+        })));
+    }
+
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, TimestampNanosecondArray};
+
+    use super::*;
+
+    fn batch(points: &[(i64, f64)]) -> Vec<ArrayRef> {
+        let times = TimestampNanosecondArray::from_iter_values(
+            points.iter().map(|(t, _)| t * NANOS_PER_SECOND),
+        );
+        let values = Float64Array::from_iter_values(points.iter().map(|(_, v)| *v));
+        vec![Arc::new(times), Arc::new(values)]
+    }
+
+    fn evaluate(func: SeriesFunction, points: &[(i64, f64)]) -> ScalarValue {
+        let mut acc = SeriesAccumulator::new(func);
+        acc.update_batch(&batch(points)).unwrap();
+        acc.evaluate().unwrap()
+    }
+
+    #[test]
+    fn test_counter_reset() {
+        // 10 -> 30 -> reset -> 5 -> 25
+        let points = [(0, 10.0), (10, 30.0), (20, 5.0), (30, 25.0)];
+
+        assert_eq!(
+            evaluate(SeriesFunction::CounterRate, &points),
+            ScalarValue::Float64(Some(45.0 / 30.0))
+        );
+        assert_eq!(
+            evaluate(SeriesFunction::Irate, &points[..3]),
+            ScalarValue::Float64(Some(0.5))
+        );
+        assert_eq!(
+            evaluate(SeriesFunction::Derivative, &points[..3]),
+            ScalarValue::Float64(Some(-0.25))
+        );
+        assert_eq!(
+            evaluate(SeriesFunction::NonNegativeDerivative, &points[..3]),
+            ScalarValue::Float64(None)
+        );
+        assert_eq!(
+            evaluate(SeriesFunction::Difference, &points),
+            ScalarValue::Float64(Some(15.0))
+        );
+    }
+
+    #[test]
+    fn test_unordered_and_merge() {
+        let mut acc = SeriesAccumulator::new(SeriesFunction::CounterRate);
+        acc.update_batch(&batch(&[(20, 5.0), (30, 25.0)])).unwrap();
+
+        let mut other = SeriesAccumulator::new(SeriesFunction::CounterRate);
+        other
+            .update_batch(&batch(&[(0, 10.0), (10, 30.0)]))
+            .unwrap();
+        let states = other
+            .state()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.to_array())
+            .collect::<Vec<_>>();
+        acc.merge_batch(&states).unwrap();
+
+        assert_eq!(
+            acc.evaluate().unwrap(),
+            ScalarValue::Float64(Some(45.0 / 30.0))
+        );
+    }
+
+    #[test]
+    fn test_retract() {
+        let mut acc = SeriesAccumulator::new(SeriesFunction::CounterRate);
+        acc.update_batch(&batch(&[(0, 10.0), (10, 30.0), (20, 5.0)]))
+            .unwrap();
+        acc.retract_batch(&batch(&[(0, 10.0)])).unwrap();
+        acc.update_batch(&batch(&[(30, 25.0)])).unwrap();
+
+        // 30 -> reset -> 5 -> 25
+        assert_eq!(
+            acc.evaluate().unwrap(),
+            ScalarValue::Float64(Some(25.0 / 20.0))
+        );
+    }
+
+    #[test]
+    fn test_unordered_retract() {
+        let mut acc = SeriesAccumulator::new(SeriesFunction::CounterRate);
+        acc.update_batch(&batch(&[(20, 5.0), (0, 10.0), (10, 30.0)]))
+            .unwrap();
+        // 10 -> 30 -> reset -> 5
+        assert_eq!(
+            acc.evaluate().unwrap(),
+            ScalarValue::Float64(Some(25.0 / 20.0))
+        );
+
+        // the earliest arrived row is retracted, not the earliest point
+        acc.retract_batch(&batch(&[(20, 5.0)])).unwrap();
+        assert_eq!(
+            acc.evaluate().unwrap(),
+            ScalarValue::Float64(Some(20.0 / 10.0))
+        );
+    }
+}
@@ -0,0 +1,178 @@
+mod accumulator;
+
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::{DataType, Field, IntervalUnit};
+use datafusion::logical_expr::type_coercion::aggregates::{INTEGERS, NUMERICS, TIMESTAMPS};
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    TypeSignature, Volatility,
+};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryError;
+
+use self::accumulator::SeriesAccumulator;
+use crate::extension::expr::aggregate_function::{
+    COUNTER_RATE_UDAF_NAME, CUMULATIVE_SUM_UDAF_NAME, DERIVATIVE_UDAF_NAME, DIFFERENCE_UDAF_NAME,
+    ELAPSED_UDAF_NAME, IRATE_UDAF_NAME, MOVING_AVERAGE_UDAF_NAME,
+    NON_NEGATIVE_DERIVATIVE_UDAF_NAME,
+};
+
+const INTERVALS: [DataType; 3] = [
+    DataType::Interval(IntervalUnit::YearMonth),
+    DataType::Interval(IntervalUnit::DayTime),
+    DataType::Interval(IntervalUnit::MonthDayNano),
+];
+
+/// Functions computed from the points of a time series ordered by time.
+///
+/// All of them can be used as aggregate functions, e.g. over `time_window` buckets,
+/// and as window functions over the rows ordered by time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesFunction {
+    /// Per-second increase of a counter, counter resets are taken into account
+    CounterRate,
+    /// Per-second increase of a counter between the last two points
+    Irate,
+    /// Change of the value per unit (default 1s) between the first and the last point
+    Derivative,
+    /// Same as `Derivative`, but negative results are null
+    NonNegativeDerivative,
+    /// Change of the value between the first and the last point
+    Difference,
+    /// Average of the last n values
+    MovingAverage,
+    /// Sum of the values
+    CumulativeSum,
+    /// Time elapsed between the first and the last point in unit (default 1ns)
+    Elapsed,
+}
+
+impl SeriesFunction {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::CounterRate => COUNTER_RATE_UDAF_NAME,
+            Self::Irate => IRATE_UDAF_NAME,
+            Self::Derivative => DERIVATIVE_UDAF_NAME,
+            Self::NonNegativeDerivative => NON_NEGATIVE_DERIVATIVE_UDAF_NAME,
+            Self::Difference => DIFFERENCE_UDAF_NAME,
+            Self::MovingAverage => MOVING_AVERAGE_UDAF_NAME,
+            Self::CumulativeSum => CUMULATIVE_SUM_UDAF_NAME,
+            Self::Elapsed => ELAPSED_UDAF_NAME,
+        }
+    }
+
+    fn signature(&self) -> Signature {
+        let time_values = || {
+            TIMESTAMPS
+                .iter()
+                .flat_map(|t| NUMERICS.iter().map(move |v| vec![t.clone(), v.clone()]))
+        };
+
+        let type_signatures = match self {
+            // f(time TIMESTAMP, value NUMERICS)
+            Self::CounterRate | Self::Irate | Self::Difference => {
+                time_values().map(TypeSignature::Exact).collect()
+            }
+            // f(time TIMESTAMP, value NUMERICS [, unit INTERVAL])
+            Self::Derivative | Self::NonNegativeDerivative => time_values()
+                .flat_map(|args| {
+                    let without_unit = TypeSignature::Exact(args.clone());
+                    let with_unit = INTERVALS.iter().map(move |i| {
+                        let mut args = args.clone();
+                        args.push(i.clone());
+                        TypeSignature::Exact(args)
+                    });
+                    std::iter::once(without_unit).chain(with_unit)
+                })
+                .collect(),
+            // moving_average(time TIMESTAMP, value NUMERICS, n BIGINT)
+            Self::MovingAverage => time_values()
+                .flat_map(|args| {
+                    INTEGERS.iter().map(move |i| {
+                        let mut args = args.clone();
+                        args.push(i.clone());
+                        TypeSignature::Exact(args)
+                    })
+                })
+                .collect(),
+            // cumulative_sum(value NUMERICS)
+            Self::CumulativeSum => NUMERICS
+                .iter()
+                .map(|v| TypeSignature::Exact(vec![v.clone()]))
+                .collect(),
+            // elapsed(time TIMESTAMP [, unit INTERVAL])
+            Self::Elapsed => TIMESTAMPS
+                .iter()
+                .flat_map(|t| {
+                    std::iter::once(TypeSignature::Exact(vec![t.clone()])).chain(
+                        INTERVALS
+                            .iter()
+                            .map(|i| TypeSignature::Exact(vec![t.clone(), i.clone()])),
+                    )
+                })
+                .collect(),
+        };
+
+        Signature::one_of(type_signatures, Volatility::Immutable)
+    }
+
+    fn return_type(&self) -> DataType {
+        match self {
+            Self::Elapsed => DataType::Int64,
+            _ => DataType::Float64,
+        }
+    }
+
+    /// The position of the constant argument: the unit or the size of the moving window
+    fn param_index(&self) -> Option<usize> {
+        match self {
+            Self::Derivative | Self::NonNegativeDerivative | Self::MovingAverage => Some(2),
+            Self::Elapsed => Some(1),
+            _ => None,
+        }
+    }
+}
+
+pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> Result<(), QueryError> {
+    for func in [
+        SeriesFunction::CounterRate,
+        SeriesFunction::Irate,
+        SeriesFunction::Derivative,
+        SeriesFunction::NonNegativeDerivative,
+        SeriesFunction::Difference,
+        SeriesFunction::MovingAverage,
+        SeriesFunction::CumulativeSum,
+        SeriesFunction::Elapsed,
+    ] {
+        func_manager.register_udaf(new(func))?;
+    }
+    Ok(())
+}
+
+fn new(func: SeriesFunction) -> AggregateUDF {
+    let return_type = Arc::new(func.return_type());
+    let return_type_func: ReturnTypeFunction = Arc::new(move |_| Ok(return_type.clone()));
+
+    // state: [times, values, param]
+    let state_type_func: StateTypeFunction = Arc::new(move |_, _| {
+        Ok(Arc::new(vec![
+            DataType::List(Arc::new(Field::new("item", DataType::Int64, true))),
+            DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+            DataType::Int64,
+        ]))
+    });
+
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(move |_, _| Ok(Box::new(SeriesAccumulator::new(func))));
+
+    AggregateUDF::new_with_preference(
+        func.name(),
+        &func.signature(),
+        &return_type_func,
+        &accumulator,
+        &state_type_func,
+        true,
+        false,
+    )
+}
@@ -0,0 +1,147 @@
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use serde::{Deserialize, Serialize};
+use utils::FnvHasher;
+
+/// Number of bits used to choose the register, 2^12 registers give ~1.6% standard error
+const PRECISION: u32 = 12;
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// HyperLogLog distinct counter, see <http://algo.inria.fr/flajolet/Publications/FlFuGaMe07.pdf>
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS],
+        }
+    }
+}
+
+impl HyperLogLog {
+    pub fn insert(&mut self, bytes: &[u8]) {
+        let hash = hash64(bytes);
+        let index = (hash >> (64 - PRECISION)) as usize;
+        let rank = ((hash << PRECISION).leading_zeros() + 1).min(64 - PRECISION + 1) as u8;
+        if self.registers[index] < rank {
+            self.registers[index] = rank;
+        }
+    }
+
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (r, o) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *r = (*r).max(*o);
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.registers.len() == NUM_REGISTERS
+    }
+
+    /// Registers encoded by bincode, the intermediate state of `hll_agg`
+    pub fn to_bytes(&self) -> DFResult<Vec<u8>> {
+        bincode::serialize(self)
+            .map_err(|e| DataFusionError::Execution(format!("Serialize hll failed: {e}")))
+    }
+
+    pub fn try_from_bytes(bytes: &[u8]) -> DFResult<Self> {
+        let hll: Self = bincode::deserialize(bytes)
+            .map_err(|e| DataFusionError::Execution(format!("Deserialize hll failed: {e}")))?;
+        if !hll.is_valid() {
+            return Err(DataFusionError::Execution(
+                "Deserialize hll failed: wrong number of registers".to_string(),
+            ));
+        }
+        Ok(hll)
+    }
+
+    /// Estimated number of distinct values
+    pub fn estimate(&self) -> u64 {
+        let m = NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+
+        let mut sum = 0.0;
+        let mut zeros = 0;
+        for r in &self.registers {
+            sum += 2f64.powi(-(*r as i32));
+            if *r == 0 {
+                zeros += 1;
+            }
+        }
+
+        let estimate = alpha * m * m / sum;
+        if estimate <= 2.5 * m && zeros > 0 {
+            // small range correction: linear counting
+            return (m * (m / zeros as f64).ln()).round() as u64;
+        }
+
+        estimate.round() as u64
+    }
+
+    /// Bytes allocated on the heap
+    pub fn heap_size(&self) -> usize {
+        self.registers.capacity()
+    }
+}
+
+/// FNV-1a with the murmur3 finalizer, stable across processes and versions
+/// so that persisted sketches can be merged later.
+fn hash64(bytes: &[u8]) -> u64 {
+    let mut hash = FnvHasher::new().hash_with(bytes).number();
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HyperLogLog;
+
+    #[test]
+    fn test_estimate() {
+        let mut hll = HyperLogLog::default();
+        assert_eq!(hll.estimate(), 0);
+
+        for i in 0..10 {
+            hll.insert(format!("value{}", i % 5).as_bytes());
+        }
+        assert_eq!(hll.estimate(), 5);
+    }
+
+    #[test]
+    fn test_estimate_large() {
+        let mut left = HyperLogLog::default();
+        let mut right = HyperLogLog::default();
+        for i in 0..60_000_i64 {
+            left.insert(&i.to_le_bytes());
+            right.insert(&(i + 40_000).to_le_bytes());
+        }
+        left.merge(&right);
+
+        let estimate = left.estimate() as f64;
+        assert!(
+            (estimate - 100_000.0).abs() / 100_000.0 < 0.05,
+            "estimate: {estimate}"
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        let mut hll = HyperLogLog::default();
+        hll.insert(b"value");
+        let bytes = hll.to_bytes().unwrap();
+        assert_eq!(HyperLogLog::try_from_bytes(&bytes).unwrap(), hll);
+        assert!(HyperLogLog::try_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let short = HyperLogLog {
+            registers: vec![0; 3],
+        };
+        assert!(HyperLogLog::try_from_bytes(&short.to_bytes().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+mod hll;
+mod sketch_agg;
+mod space_saving;
+mod tdigest;
+
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::{as_float64_array, as_string_array};
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::scalar::ScalarValue;
+pub use hll::HyperLogLog;
+use serde::{Deserialize, Serialize};
+pub use space_saving::SpaceSaving;
+use spi::query::function::FunctionMetadataManager;
+use spi::{QueryError, Result};
+pub use tdigest::TDigest;
+
+use super::AggResult;
+
+pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> Result<()> {
+    sketch_agg::register_udafs(func_manager)?;
+    Ok(())
+}
+
+/// A mergeable summary of a set of values.
+///
+/// Sketches are serialized to a string, so that the result of the aggregation
+/// can be stored in a table and re-aggregated later by `sketch_rollup`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "sketch", rename_all = "snake_case")]
+pub enum Sketch {
+    TDigest(TDigest),
+    Hll(HyperLogLog),
+    TopN(SpaceSaving),
+}
+
+impl Sketch {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::TDigest(_) => "tdigest",
+            Self::Hll(_) => "hll",
+            Self::TopN(_) => "topn",
+        }
+    }
+
+    /// Bytes allocated on the heap
+    pub fn heap_size(&self) -> usize {
+        match self {
+            Self::TDigest(digest) => digest.heap_size(),
+            Self::Hll(hll) => hll.heap_size(),
+            Self::TopN(summary) => summary.heap_size(),
+        }
+    }
+
+    /// Add all non-null values of `values` to the sketch
+    pub fn update(&mut self, values: &ArrayRef) -> DFResult<()> {
+        match self {
+            Self::TDigest(digest) => {
+                let values = cast(values, &DataType::Float64)?;
+                as_float64_array(&values)?
+                    .iter()
+                    .flatten()
+                    .for_each(|v| digest.insert(v));
+            }
+            Self::Hll(hll) => {
+                for idx in 0..values.len() {
+                    if values.is_valid(idx) {
+                        let value = ScalarValue::try_from_array(values, idx)?;
+                        hll.insert(&scalar_to_bytes(&value));
+                    }
+                }
+            }
+            Self::TopN(summary) => {
+                for idx in 0..values.len() {
+                    if values.is_valid(idx) {
+                        let value = ScalarValue::try_from_array(values, idx)?;
+                        summary.insert(value.to_string());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn merge(&mut self, other: Sketch) -> DFResult<()> {
+        match (self, other) {
+            (Self::TDigest(l), Self::TDigest(r)) => l.merge(r),
+            (Self::Hll(l), Self::Hll(r)) => l.merge(&r),
+            (Self::TopN(l), Self::TopN(r)) => l.merge(r),
+            (l, r) => {
+                return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                    err: format!("Can't merge {} sketch with {} sketch", l.name(), r.name()),
+                })))
+            }
+        }
+        Ok(())
+    }
+
+    pub fn try_from_str(value: &str) -> DFResult<Self> {
+        let sketch: Self = serde_json::from_str(value).map_err(|e| {
+            DataFusionError::External(Box::new(QueryError::Analyzer {
+                err: format!("Invalid sketch '{value}': {e}"),
+            }))
+        })?;
+
+        if let Self::Hll(hll) = &sketch {
+            if !hll.is_valid() {
+                return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                    err: format!("Invalid sketch '{value}': wrong number of registers"),
+                })));
+            }
+        }
+
+        Ok(sketch)
+    }
+
+    /// Merge all non-null serialized sketches of `values` into `sketch`
+    pub fn merge_array(sketch: &mut Option<Sketch>, values: &ArrayRef) -> DFResult<()> {
+        let values = cast(values, &DataType::Utf8)?;
+        for value in as_string_array(&values)?.iter().flatten() {
+            let other = Self::try_from_str(value)?;
+            match sketch {
+                Some(s) => s.merge(other)?,
+                None => *sketch = Some(other),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AggResult for Sketch {
+    fn to_scalar(mut self) -> DFResult<ScalarValue> {
+        if let Self::TDigest(digest) = &mut self {
+            digest.compress();
+        }
+        let value = serde_json::to_string(&self)
+            .map_err(|e| DataFusionError::Execution(format!("Serialize sketch failed: {e}")))?;
+        Ok(ScalarValue::Utf8(Some(value)))
+    }
+}
+
+/// The result of `percentile_agg`/`hll_agg`/`topn_agg`, null if no value was aggregated
+#[derive(Debug, Clone, PartialEq)]
+pub struct SketchData {
+    sketch: Option<Sketch>,
+}
+
+impl SketchData {
+    pub fn try_from_scalar(scalar: ScalarValue) -> DFResult<Self> {
+        match scalar {
+            ScalarValue::Utf8(value) | ScalarValue::LargeUtf8(value) => {
+                let sketch = value.as_deref().map(Sketch::try_from_str).transpose()?;
+                Ok(Self { sketch })
+            }
+            _ => Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                err: format!("Expected sketch, but found {}", scalar.get_datatype()),
+            }))),
+        }
+    }
+
+    fn mismatch(&self, expected: &str) -> DataFusionError {
+        let found = self.sketch.as_ref().map(|s| s.name()).unwrap_or("null");
+        DataFusionError::External(Box::new(QueryError::Analyzer {
+            err: format!("Expected {expected} sketch, but found {found} sketch"),
+        }))
+    }
+
+    /// Estimated value at `percentile` of a tdigest sketch
+    pub fn approx_percentile(&mut self, percentile: f64) -> DFResult<ScalarValue> {
+        if !(0.0..=1.0).contains(&percentile) {
+            return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                err: format!("Percentile must be in [0, 1], but found {percentile}"),
+            })));
+        }
+        if let Some(Sketch::TDigest(digest)) = &mut self.sketch {
+            return Ok(ScalarValue::Float64(digest.quantile(percentile)));
+        }
+        match &self.sketch {
+            None => Ok(ScalarValue::Float64(None)),
+            Some(_) => Err(self.mismatch("tdigest")),
+        }
+    }
+
+    /// Estimated number of distinct values of a hll sketch
+    pub fn distinct_count(&self) -> DFResult<ScalarValue> {
+        match &self.sketch {
+            None => Ok(ScalarValue::UInt64(None)),
+            Some(Sketch::Hll(hll)) => Ok(ScalarValue::UInt64(Some(hll.estimate()))),
+            Some(_) => Err(self.mismatch("hll")),
+        }
+    }
+
+    /// The most frequent values of a topn sketch, ordered by frequency descending
+    pub fn topn(&self) -> DFResult<ScalarValue> {
+        match &self.sketch {
+            None => Ok(ScalarValue::new_list(None, DataType::Utf8)),
+            Some(Sketch::TopN(summary)) => {
+                let mut summary = summary.clone();
+                let n = summary.n();
+                let values = summary
+                    .top(n)
+                    .iter()
+                    .map(|c| ScalarValue::Utf8(Some(c.value.clone())))
+                    .collect();
+                Ok(ScalarValue::new_list(Some(values), DataType::Utf8))
+            }
+            Some(_) => Err(self.mismatch("topn")),
+        }
+    }
+}
+
+/// Stable byte representation of a value, equal numbers of different integer types
+/// have the same representation.
+fn scalar_to_bytes(value: &ScalarValue) -> Vec<u8> {
+    match value {
+        ScalarValue::Int8(Some(v)) => (*v as i64).to_le_bytes().to_vec(),
+        ScalarValue::Int16(Some(v)) => (*v as i64).to_le_bytes().to_vec(),
+        ScalarValue::Int32(Some(v)) => (*v as i64).to_le_bytes().to_vec(),
+        ScalarValue::Int64(Some(v))
+        | ScalarValue::TimestampSecond(Some(v), _)
+        | ScalarValue::TimestampMillisecond(Some(v), _)
+        | ScalarValue::TimestampMicrosecond(Some(v), _)
+        | ScalarValue::TimestampNanosecond(Some(v), _) => v.to_le_bytes().to_vec(),
+        ScalarValue::UInt8(Some(v)) => (*v as u64).to_le_bytes().to_vec(),
+        ScalarValue::UInt16(Some(v)) => (*v as u64).to_le_bytes().to_vec(),
+        ScalarValue::UInt32(Some(v)) => (*v as u64).to_le_bytes().to_vec(),
+        ScalarValue::UInt64(Some(v)) => v.to_le_bytes().to_vec(),
+        ScalarValue::Float32(Some(v)) => (*v as f64).to_bits().to_le_bytes().to_vec(),
+        ScalarValue::Float64(Some(v)) => v.to_bits().to_le_bytes().to_vec(),
+        ScalarValue::Boolean(Some(v)) => vec![*v as u8],
+        ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => v.as_bytes().to_vec(),
+        other => other.to_string().into_bytes(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::scalar::ScalarValue;
+
+    use super::{Sketch, SketchData, TDigest};
+    use crate::extension::expr::aggregate_function::AggResult;
+
+    #[test]
+    fn test_sketch_serialize() {
+        let mut digest = TDigest::default();
+        (1..=4).for_each(|v| digest.insert(v as f64));
+        let scalar = Sketch::TDigest(digest).to_scalar().unwrap();
+
+        let mut data = SketchData::try_from_scalar(scalar).unwrap();
+        assert_eq!(
+            data.approx_percentile(0.5).unwrap(),
+            ScalarValue::Float64(Some(2.5))
+        );
+        assert!(data.distinct_count().is_err());
+
+        let null = SketchData::try_from_scalar(ScalarValue::Utf8(None)).unwrap();
+        assert_eq!(null.distinct_count().unwrap(), ScalarValue::UInt64(None));
+    }
+}
@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::as_binary_array;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::type_coercion::aggregates::{INTEGERS, NUMERICS};
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    Volatility,
+};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryError;
+
+use super::{HyperLogLog, Sketch, SpaceSaving, TDigest};
+use crate::extension::expr::aggregate_function::{
+    AggResult, HLL_AGG_UDAF_NAME, PERCENTILE_AGG_UDAF_NAME, SKETCH_ROLLUP_UDAF_NAME,
+    TOPN_AGG_UDAF_NAME,
+};
+
+pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> Result<(), QueryError> {
+    // percentile_agg(value DOUBLE) RETURNS tdigest sketch
+    func_manager.register_udaf(new(
+        PERCENTILE_AGG_UDAF_NAME,
+        Signature::uniform(1, NUMERICS.to_vec(), Volatility::Immutable),
+        SketchKind::TDigest,
+    ))?;
+    // hll_agg(value ANY) RETURNS hll sketch
+    func_manager.register_udaf(new(
+        HLL_AGG_UDAF_NAME,
+        Signature::any(1, Volatility::Immutable),
+        SketchKind::Hll,
+    ))?;
+    // topn_agg(n BIGINT, value ANY) RETURNS topn sketch
+    func_manager.register_udaf(new(
+        TOPN_AGG_UDAF_NAME,
+        Signature::any(2, Volatility::Immutable),
+        SketchKind::TopN,
+    ))?;
+    // sketch_rollup(sketch STRING) RETURNS sketch
+    func_manager.register_udaf(new(
+        SKETCH_ROLLUP_UDAF_NAME,
+        Signature::uniform(
+            1,
+            vec![DataType::Utf8, DataType::LargeUtf8],
+            Volatility::Immutable,
+        ),
+        SketchKind::Rollup,
+    ))?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SketchKind {
+    TDigest,
+    Hll,
+    TopN,
+    /// Merge the serialized sketches produced by the other aggregations
+    Rollup,
+}
+
+fn new(name: &str, signature: Signature, kind: SketchKind) -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(move |input| {
+        if let SketchKind::TopN = kind {
+            if !INTEGERS.contains(&input[0]) {
+                return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                    err: format!(
+                        "The first argument of topn_agg must be an integer, but found {}",
+                        input[0]
+                    ),
+                })));
+            }
+        }
+        Ok(Arc::new(DataType::Utf8))
+    });
+
+    // the registers of hll are kept in binary between the aggregation stages, the other
+    // sketches are small enough to be kept in their serialized strings
+    let state_type_func: StateTypeFunction = Arc::new(move |_, _| match kind {
+        SketchKind::Hll => Ok(Arc::new(vec![DataType::Binary])),
+        _ => Ok(Arc::new(vec![DataType::Utf8])),
+    });
+
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(move |_, _| Ok(Box::new(SketchAccumulator::new(kind))));
+
+    AggregateUDF::new(
+        name,
+        &signature,
+        &return_type_func,
+        &accumulator,
+        &state_type_func,
+    )
+}
+
+#[derive(Debug)]
+struct SketchAccumulator {
+    kind: SketchKind,
+    sketch: Option<Sketch>,
+}
+
+impl SketchAccumulator {
+    fn new(kind: SketchKind) -> Self {
+        let sketch = match kind {
+            SketchKind::TDigest => Some(Sketch::TDigest(TDigest::default())),
+            SketchKind::Hll => Some(Sketch::Hll(HyperLogLog::default())),
+            // the size of topn is only known from the arguments
+            SketchKind::TopN | SketchKind::Rollup => None,
+        };
+        Self { kind, sketch }
+    }
+}
+
+impl Accumulator for SketchAccumulator {
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        match (self.kind, &self.sketch) {
+            (SketchKind::Hll, Some(Sketch::Hll(hll))) => {
+                Ok(vec![ScalarValue::Binary(Some(hll.to_bytes()?))])
+            }
+            (SketchKind::Hll, _) => Ok(vec![ScalarValue::Binary(None)]),
+            _ => Ok(vec![self.evaluate()?]),
+        }
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("update_batch: {:?}", values);
+
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        match self.kind {
+            SketchKind::Rollup => Sketch::merge_array(&mut self.sketch, &values[0]),
+            SketchKind::TopN => {
+                debug_assert!(
+                    values.len() == 2,
+                    "topn_agg can only take 2 param, but found {}",
+                    values.len()
+                );
+
+                if values[1].is_empty() {
+                    return Ok(());
+                }
+                if self.sketch.is_none() {
+                    let n = parse_topn_size(&values[0])?;
+                    self.sketch = Some(Sketch::TopN(SpaceSaving::new(n)));
+                }
+                match &mut self.sketch {
+                    Some(sketch) => sketch.update(&values[1]),
+                    None => Ok(()),
+                }
+            }
+            SketchKind::TDigest | SketchKind::Hll => match &mut self.sketch {
+                Some(sketch) => sketch.update(&values[0]),
+                None => Ok(()),
+            },
+        }
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("merge_batch: {:?}", states);
+
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        match self.kind {
+            SketchKind::Hll => merge_hll_states(&mut self.sketch, &states[0]),
+            _ => Sketch::merge_array(&mut self.sketch, &states[0]),
+        }
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        match &self.sketch {
+            Some(sketch) => sketch.clone().to_scalar(),
+            None => Ok(ScalarValue::Utf8(None)),
+        }
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self
+                .sketch
+                .as_ref()
+                .map(|sketch| sketch.heap_size())
+                .unwrap_or_default()
+    }
+}
+
+/// Merge all non-null binary states of `hll_agg` into `sketch`
+fn merge_hll_states(sketch: &mut Option<Sketch>, states: &ArrayRef) -> DFResult<()> {
+    for state in as_binary_array(states)?.iter().flatten() {
+        let other = HyperLogLog::try_from_bytes(state)?;
+        match sketch {
+            Some(s) => s.merge(Sketch::Hll(other))?,
+            None => *sketch = Some(Sketch::Hll(other)),
+        }
+    }
+    Ok(())
+}
+
+fn parse_topn_size(array: &ArrayRef) -> DFResult<usize> {
+    let invalid = || {
+        DataFusionError::External(Box::new(QueryError::Analyzer {
+            err: "The first argument of topn_agg must be a positive integer".to_string(),
+        }))
+    };
+
+    let n = match ScalarValue::try_from_array(array, 0)? {
+        ScalarValue::Int8(Some(v)) => v as i64,
+        ScalarValue::Int16(Some(v)) => v as i64,
+        ScalarValue::Int32(Some(v)) => v as i64,
+        ScalarValue::Int64(Some(v)) => v,
+        ScalarValue::UInt8(Some(v)) => v as i64,
+        ScalarValue::UInt16(Some(v)) => v as i64,
+        ScalarValue::UInt32(Some(v)) => v as i64,
+        ScalarValue::UInt64(Some(v)) => i64::try_from(v).map_err(|_| invalid())?,
+        _ => return Err(invalid()),
+    };
+
+    if n <= 0 {
+        return Err(invalid());
+    }
+
+    Ok(n as usize)
+}
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of counters kept for each requested top value
+const COUNTERS_PER_VALUE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Counter {
+    pub value: String,
+    pub count: u64,
+    /// Upper bound of the over-estimation of `count`
+    pub error: u64,
+}
+
+/// Space-Saving heavy hitters, see <https://www.cs.ucsb.edu/sites/default/files/documents/2005-23.pdf>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpaceSaving {
+    n: usize,
+    capacity: usize,
+    /// A min-heap by count while `index` is built
+    counters: Vec<Counter>,
+    /// The position of each value in `counters`, it's rebuilt by the next insert
+    /// after `counters` is reordered or deserialized.
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+impl PartialEq for SpaceSaving {
+    fn eq(&self, other: &Self) -> bool {
+        self.n == other.n && self.capacity == other.capacity && self.counters == other.counters
+    }
+}
+
+impl Eq for SpaceSaving {}
+
+impl SpaceSaving {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            capacity: n.saturating_mul(COUNTERS_PER_VALUE),
+            counters: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn insert(&mut self, value: String) {
+        self.build_index();
+
+        if let Some(&pos) = self.index.get(&value) {
+            self.counters[pos].count += 1;
+            self.sift_down(pos);
+            return;
+        }
+
+        if self.counters.len() < self.capacity {
+            self.index.insert(value.clone(), self.counters.len());
+            self.counters.push(Counter {
+                value,
+                count: 1,
+                error: 0,
+            });
+            self.sift_up(self.counters.len() - 1);
+            return;
+        }
+
+        // replace the counter with the minimum count, the root of the heap
+        if let Some(min) = self.counters.first_mut() {
+            self.index.remove(&min.value);
+            self.index.insert(value.clone(), 0);
+            min.value = value;
+            min.error = min.count;
+            min.count += 1;
+            self.sift_down(0);
+        }
+    }
+
+    /// Heapify `counters` and index the values if they were reordered
+    fn build_index(&mut self) {
+        if self.index.len() == self.counters.len() {
+            return;
+        }
+        self.index.clear();
+        for pos in (0..self.counters.len() / 2).rev() {
+            self.sift_down(pos);
+        }
+        self.index = self
+            .counters
+            .iter()
+            .enumerate()
+            .map(|(pos, c)| (c.value.clone(), pos))
+            .collect();
+    }
+
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.counters[parent].count <= self.counters[pos].count {
+                break;
+            }
+            self.swap(parent, pos);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut pos: usize) {
+        loop {
+            let mut min = pos;
+            for child in [2 * pos + 1, 2 * pos + 2] {
+                if child < self.counters.len()
+                    && self.counters[child].count < self.counters[min].count
+                {
+                    min = child;
+                }
+            }
+            if min == pos {
+                break;
+            }
+            self.swap(min, pos);
+            pos = min;
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.counters.swap(a, b);
+        for pos in [a, b] {
+            if let Some(p) = self.index.get_mut(&self.counters[pos].value) {
+                *p = pos;
+            }
+        }
+    }
+
+    fn min_count(&self) -> u64 {
+        if self.counters.len() < self.capacity {
+            0
+        } else {
+            self.counters.iter().map(|c| c.count).min().unwrap_or(0)
+        }
+    }
+
+    /// Merge two summaries, a value missing from one side is assumed to have been seen
+    /// as many times as the minimum counter of that side.
+    pub fn merge(&mut self, other: SpaceSaving) {
+        let self_min = self.min_count();
+        let other_min = other.min_count();
+
+        let mut merged = HashMap::with_capacity(self.counters.len() + other.counters.len());
+        for c in self.counters.drain(..) {
+            merged.insert(c.value.clone(), c);
+        }
+        let mut other_values = HashMap::with_capacity(other.counters.len());
+        for c in other.counters {
+            other_values.insert(c.value.clone(), ());
+            match merged.get_mut(&c.value) {
+                Some(m) => {
+                    m.count += c.count;
+                    m.error += c.error;
+                }
+                None => {
+                    merged.insert(
+                        c.value.clone(),
+                        Counter {
+                            value: c.value,
+                            count: c.count + self_min,
+                            error: c.error + self_min,
+                        },
+                    );
+                }
+            }
+        }
+        for (value, c) in merged.iter_mut() {
+            if !other_values.contains_key(value) {
+                c.count += other_min;
+                c.error += other_min;
+            }
+        }
+
+        self.n = self.n.max(other.n);
+        self.capacity = self.capacity.max(other.capacity);
+        self.counters = merged.into_values().collect();
+        self.sort();
+        self.counters.truncate(self.capacity);
+    }
+
+    fn sort(&mut self) {
+        self.counters
+            .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        self.index.clear();
+    }
+
+    /// The `n` most frequent values, ordered by count descending
+    pub fn top(&mut self, n: usize) -> &[Counter] {
+        self.sort();
+        &self.counters[..n.min(self.counters.len())]
+    }
+
+    /// Bytes allocated on the heap
+    pub fn heap_size(&self) -> usize {
+        self.counters.capacity() * std::mem::size_of::<Counter>()
+            + self
+                .counters
+                .iter()
+                .map(|c| c.value.capacity())
+                .sum::<usize>()
+            + self.index.capacity() * std::mem::size_of::<(String, usize)>()
+            + self.index.keys().map(|v| v.capacity()).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpaceSaving;
+
+    #[test]
+    fn test_top() {
+        let mut left = SpaceSaving::new(2);
+        let mut right = SpaceSaving::new(2);
+        for v in ["a", "b", "a", "c", "a", "b"] {
+            left.insert(v.to_string());
+        }
+        for v in ["c", "c", "c", "d"] {
+            right.insert(v.to_string());
+        }
+        left.merge(right);
+
+        let top = left
+            .top(2)
+            .iter()
+            .map(|c| (c.value.as_str(), c.count))
+            .collect::<Vec<_>>();
+        assert_eq!(top, vec![("c", 4), ("a", 3)]);
+    }
+
+    #[test]
+    fn test_top_evict() {
+        let mut summary = SpaceSaving::new(1);
+        for i in 0..100 {
+            summary.insert((i % 20).to_string());
+            summary.insert("hot".to_string());
+        }
+
+        let top = summary.top(1);
+        assert_eq!(top[0].value, "hot");
+        assert!(top[0].count >= 100);
+    }
+
+    #[test]
+    fn test_insert_after_reorder() {
+        let mut summary = SpaceSaving::new(1);
+        for i in 0..10 {
+            for _ in 0..=i {
+                summary.insert(i.to_string());
+            }
+        }
+        assert_eq!(summary.top(1)[0].count, 10);
+
+        // the index is rebuilt after the counters are sorted by top or deserialized
+        let mut summary: SpaceSaving =
+            serde_json::from_str(&serde_json::to_string(&summary).unwrap()).unwrap();
+        for _ in 0..10 {
+            summary.insert("0".to_string());
+        }
+        let top = summary
+            .top(2)
+            .iter()
+            .map(|c| (c.value.as_str(), c.count))
+            .collect::<Vec<_>>();
+        assert_eq!(top, vec![("0", 11), ("9", 10)]);
+
+        let mut summary = SpaceSaving::new(1);
+        for i in 0..100 {
+            summary.insert((i % 20).to_string());
+        }
+        // all counters are 10, one of them is replaced
+        summary.insert("new".to_string());
+        let new = summary.top(10).iter().find(|c| c.value == "new").unwrap();
+        assert_eq!((new.count, new.error), (11, 10));
+    }
+}
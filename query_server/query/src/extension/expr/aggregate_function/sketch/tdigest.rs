@@ -0,0 +1,216 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_COMPRESSION: f64 = 100.0;
+
+/// Max number of buffered points before they are merged into the centroids
+const BUFFER_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Merging t-digest, see <https://arxiv.org/abs/1902.04023>
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    count: f64,
+    min: f64,
+    max: f64,
+    #[serde(skip)]
+    buffer: Vec<Centroid>,
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMPRESSION)
+    }
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression,
+            centroids: vec![],
+            count: 0.0,
+            min: 0.0,
+            max: 0.0,
+            buffer: vec![],
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        (self.count + self.buffer.iter().map(|c| c.weight).sum::<f64>()) as u64
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.buffer.push(Centroid {
+            mean: value,
+            weight: 1.0,
+        });
+        if self.buffer.len() >= BUFFER_SIZE {
+            self.compress();
+        }
+    }
+
+    pub fn merge(&mut self, mut other: TDigest) {
+        other.compress();
+        if other.count == 0.0 {
+            return;
+        }
+
+        self.compress();
+        let (min, max) = if self.count == 0.0 {
+            (other.min, other.max)
+        } else {
+            (self.min.min(other.min), self.max.max(other.max))
+        };
+        self.buffer.extend(other.centroids);
+        self.compress();
+        (self.min, self.max) = (min, max);
+    }
+
+    /// Merge the buffered points into the centroids
+    pub fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut all = std::mem::take(&mut self.centroids);
+        all.append(&mut self.buffer);
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total = all.iter().map(|c| c.weight).sum::<f64>();
+        let mut result = Vec::with_capacity(all.len());
+        let mut iter = all.into_iter();
+        let mut current = match iter.next() {
+            Some(c) => c,
+            None => return,
+        };
+
+        let mut weight_so_far = 0.0;
+        let mut weight_limit = total * self.q_limit(0.0);
+        for c in iter {
+            if weight_so_far + current.weight + c.weight <= weight_limit {
+                let weight = current.weight + c.weight;
+                current.mean += (c.mean - current.mean) * c.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_so_far += current.weight;
+                weight_limit = total * self.q_limit(weight_so_far / total);
+                result.push(current);
+                current = c;
+            }
+        }
+        result.push(current);
+
+        let (min, max) = (result[0].mean, result[result.len() - 1].mean);
+        if self.count == 0.0 {
+            (self.min, self.max) = (min, max);
+        } else {
+            (self.min, self.max) = (self.min.min(min), self.max.max(max));
+        }
+        self.centroids = result;
+        self.count = total;
+    }
+
+    /// Upper bound of the quantile that the centroid starting at `q` may cover,
+    /// using the scale function k(q) = δ / 2π * asin(2q - 1)
+    fn q_limit(&self, q: f64) -> f64 {
+        let k = self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin() + 1.0;
+        ((k * 2.0 * PI / self.compression).min(PI / 2.0).sin() + 1.0) / 2.0
+    }
+
+    /// Estimate the value at quantile `q` (0 <= q <= 1), None if the digest is empty
+    pub fn quantile(&mut self, q: f64) -> Option<f64> {
+        self.compress();
+
+        let centroids = &self.centroids;
+        if centroids.is_empty() {
+            return None;
+        }
+        if centroids.len() == 1 || q <= 0.0 {
+            return Some(if q <= 0.0 {
+                self.min
+            } else {
+                centroids[0].mean
+            });
+        }
+        if q >= 1.0 {
+            return Some(self.max);
+        }
+
+        let target = q * self.count;
+        let mut cumulative = 0.0;
+        for (i, c) in centroids.iter().enumerate() {
+            let mid = cumulative + c.weight / 2.0;
+            if target < mid {
+                if i == 0 {
+                    let fraction = target / mid;
+                    return Some(self.min + (c.mean - self.min) * fraction);
+                }
+                let prev = &centroids[i - 1];
+                let prev_mid = cumulative - prev.weight / 2.0;
+                let fraction = (target - prev_mid) / (mid - prev_mid);
+                return Some(prev.mean + (c.mean - prev.mean) * fraction);
+            }
+            cumulative += c.weight;
+        }
+
+        let last = &centroids[centroids.len() - 1];
+        let last_mid = self.count - last.weight / 2.0;
+        let fraction = ((target - last_mid) / (last.weight / 2.0)).min(1.0);
+        Some(last.mean + (self.max - last.mean) * fraction)
+    }
+
+    /// Bytes allocated on the heap
+    pub fn heap_size(&self) -> usize {
+        (self.centroids.capacity() + self.buffer.capacity()) * std::mem::size_of::<Centroid>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TDigest;
+
+    #[test]
+    fn test_quantile() {
+        let mut digest = TDigest::default();
+        assert_eq!(digest.quantile(0.5), None);
+
+        for i in 1..=5 {
+            digest.insert(i as f64);
+        }
+        assert_eq!(digest.quantile(0.0), Some(1.0));
+        assert_eq!(digest.quantile(0.5), Some(3.0));
+        assert_eq!(digest.quantile(1.0), Some(5.0));
+    }
+
+    #[test]
+    fn test_quantile_large() {
+        let mut left = TDigest::default();
+        let mut right = TDigest::default();
+        for i in 0..50_000 {
+            left.insert(i as f64);
+            right.insert((i + 50_000) as f64);
+        }
+        left.merge(right);
+
+        assert_eq!(left.count(), 100_000);
+        for q in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            let estimate = left.quantile(q).unwrap();
+            let expect = q * 100_000.0;
+            assert!(
+                (estimate - expect).abs() / 100_000.0 < 0.01,
+                "q: {q}, estimate: {estimate}, expect: {expect}"
+            );
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array, Int64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, TimeUnit};
+use datafusion::common::cast::{as_int64_array, as_list_array, as_string_array};
+use datafusion::common::{downcast_value, DataFusionError, Result as DFResult};
+use datafusion::logical_expr::type_coercion::aggregates::TIMESTAMPS;
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    TypeSignature, Volatility,
+};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use geo::{Coord, Geometry, LineString};
+use geozero::ToWkt;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use crate::extension::expr::aggregate_function::ST_MAKELINE_UDAF_NAME;
+use crate::extension::expr::scalar_function::gis::str_to_geo;
+
+pub fn register_udaf(func_manager: &mut dyn FunctionMetadataManager) -> Result<AggregateUDF> {
+    let udf = new();
+    func_manager.register_udaf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+
+    // state: [times, xs, ys]
+    let state_type_func: StateTypeFunction = Arc::new(move |_, _| {
+        Ok(Arc::new(vec![
+            DataType::List(Arc::new(Field::new("item", DataType::Int64, true))),
+            DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+            DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+        ]))
+    });
+
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(|_, _| Ok(Box::<MakeLineAccumulator>::default()));
+
+    // st_makeline(
+    //     time TIMESTAMP,
+    //     point STRING
+    //   )
+    let type_signatures = TIMESTAMPS
+        .iter()
+        .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Utf8]))
+        .collect();
+
+    AggregateUDF::new(
+        ST_MAKELINE_UDAF_NAME,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type_func,
+        &accumulator,
+        &state_type_func,
+    )
+}
+
+/// Collects the points and makes a `LINESTRING` of them ordered by time.
+#[derive(Debug, Default)]
+struct MakeLineAccumulator {
+    /// (nanoseconds, point)
+    points: Vec<(i64, Coord)>,
+}
+
+impl MakeLineAccumulator {
+    fn line_string(&self) -> LineString {
+        let mut points = self.points.clone();
+        points.sort_by_key(|(ts, _)| *ts);
+        points.into_iter().map(|(_, p)| p).collect()
+    }
+}
+
+impl Accumulator for MakeLineAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("update_batch: {:?}", values);
+
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        debug_assert!(
+            values.len() == 2,
+            "st_makeline can only take 2 param, but found {}",
+            values.len()
+        );
+
+        let nanos = cast(&values[0], &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+        let times = cast(&nanos, &DataType::Int64)?;
+        let times = as_int64_array(times.as_ref())?;
+        let points = as_string_array(values[1].as_ref())?;
+
+        for (ts, point) in times.iter().zip(points.iter()) {
+            if let (Some(ts), Some(point)) = (ts, point) {
+                match str_to_geo(point)? {
+                    Geometry::Point(p) => self.points.push((ts, p.0)),
+                    other => {
+                        return Err(DataFusionError::Execution(format!(
+                            "st_makeline expects points, but found {other:?}"
+                        )))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        if self.points.is_empty() {
+            return Ok(ScalarValue::Utf8(None));
+        }
+
+        let wkt = Geometry::LineString(self.line_string())
+            .to_wkt()
+            .map_err(|err| DataFusionError::Execution(err.to_string()))?;
+        Ok(ScalarValue::Utf8(Some(wkt)))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.points.capacity() * std::mem::size_of::<(i64, Coord)>()
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let mut times = Vec::with_capacity(self.points.len());
+        let mut xs = Vec::with_capacity(self.points.len());
+        let mut ys = Vec::with_capacity(self.points.len());
+        for (ts, p) in &self.points {
+            times.push(ScalarValue::Int64(Some(*ts)));
+            xs.push(ScalarValue::Float64(Some(p.x)));
+            ys.push(ScalarValue::Float64(Some(p.y)));
+        }
+
+        Ok(vec![
+            ScalarValue::new_list(Some(times), DataType::Int64),
+            ScalarValue::new_list(Some(xs), DataType::Float64),
+            ScalarValue::new_list(Some(ys), DataType::Float64),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("merge_batch: {:?}", states);
+
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        let time_lists = as_list_array(states[0].as_ref())?;
+        let x_lists = as_list_array(states[1].as_ref())?;
+        let y_lists = as_list_array(states[2].as_ref())?;
+
+        for ((times, xs), ys) in time_lists
+            .iter()
+            .flatten()
+            .zip(x_lists.iter().flatten())
+            .zip(y_lists.iter().flatten())
+        {
+            let times = downcast_value!(times.as_ref(), Int64Array);
+            let xs = downcast_value!(xs.as_ref(), Float64Array);
+            let ys = downcast_value!(ys.as_ref(), Float64Array);
+            for i in 0..times.len() {
+                if times.is_valid(i) && xs.is_valid(i) && ys.is_valid(i) {
+                    let coord = Coord {
+                        x: xs.value(i),
+                        y: ys.value(i),
+                    };
+                    self.points.push((times.value(i), coord));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, StringArray, TimestampNanosecondArray};
+    use datafusion::physical_plan::Accumulator;
+    use datafusion::scalar::ScalarValue;
+
+    use super::MakeLineAccumulator;
+
+    #[test]
+    fn test_makeline() {
+        let mut acc = MakeLineAccumulator::default();
+        let times: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![3, 1, 2]));
+        let points: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("POINT(3 3)"),
+            Some("POINT(1 1)"),
+            None,
+        ]));
+        acc.update_batch(&[times, points]).unwrap();
+
+        let mut other = MakeLineAccumulator::default();
+        let times: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![2]));
+        let points: ArrayRef = Arc::new(StringArray::from(vec!["POINT(2 2)"]));
+        other.update_batch(&[times, points]).unwrap();
+        let state = other
+            .state()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.to_array())
+            .collect::<Vec<_>>();
+        acc.merge_batch(&state).unwrap();
+
+        assert_eq!(
+            acc.evaluate().unwrap(),
+            ScalarValue::Utf8(Some("LINESTRING(1 1,2 2,3 3)".to_string()))
+        );
+
+        let times: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![4]));
+        let points: ArrayRef = Arc::new(StringArray::from(vec!["LINESTRING(0 0,1 1)"]));
+        assert!(acc.update_batch(&[times, points]).is_err());
+
+        assert_eq!(
+            MakeLineAccumulator::default().evaluate().unwrap(),
+            ScalarValue::Utf8(None)
+        );
+    }
+}
@@ -0,0 +1,386 @@
+mod trajectory_agg;
+
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::{DataType, Field, Fields, TimeUnit};
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::scalar::ScalarValue;
+use geo::{Coord, EuclideanLength, Geometry, Intersects, Line, LineString, Point, SimplifyIdx};
+use geozero::ToWkt;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryError;
+
+use super::AggResult;
+use crate::extension::expr::scalar_function::gis::str_to_geo;
+
+const GEOFENCE_ENTER: &str = "enter";
+const GEOFENCE_EXIT: &str = "exit";
+
+pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> Result<(), QueryError> {
+    trajectory_agg::register_udaf(func_manager)?;
+    Ok(())
+}
+
+/// Struct(
+///     "times": List[time, time, ......],
+///     "path": LINESTRING of the points ordered by time
+/// )
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryData {
+    time_data_type: DataType,
+    /// (time in the unit of `time_data_type`, point) ordered by time
+    points: Vec<(i64, Coord)>,
+}
+
+impl TrajectoryData {
+    fn new(time_data_type: DataType, points: Vec<(i64, Coord)>) -> Self {
+        Self {
+            time_data_type,
+            points,
+        }
+    }
+
+    fn nanos_per_unit(&self) -> i64 {
+        match &self.time_data_type {
+            DataType::Timestamp(TimeUnit::Second, _) => 1_000_000_000,
+            DataType::Timestamp(TimeUnit::Millisecond, _) => 1_000_000,
+            DataType::Timestamp(TimeUnit::Microsecond, _) => 1_000,
+            _ => 1,
+        }
+    }
+
+    /// Consecutive point pairs as (nanoseconds elapsed, length of the segment).
+    ///
+    /// The lengths are planar, the SRID of the points is not known here, so the points
+    /// in WGS 84 (4326) give lengths in degrees instead of meters. Such points can be
+    /// projected by `ST_Transform(loc, 4326, 3857)` before `trajectory_agg`.
+    fn segments(&self) -> impl Iterator<Item = (i64, f64)> + '_ {
+        let nanos_per_unit = self.nanos_per_unit();
+        self.points
+            .iter()
+            .zip(self.points.iter().skip(1))
+            .map(move |((t1, p1), (t2, p2))| {
+                let nanos = (t2 - t1) * nanos_per_unit;
+                (nanos, Line::new(*p1, *p2).euclidean_length())
+            })
+    }
+
+    /// The planar length of the path, in the units of the coordinates.
+    pub fn trajectory_distance(&self) -> DFResult<ScalarValue> {
+        if self.points.is_empty() {
+            return Ok(ScalarValue::Float64(None));
+        }
+
+        let distance = self.segments().map(|(_, length)| length).sum();
+        Ok(ScalarValue::Float64(Some(distance)))
+    }
+
+    /// The average planar speed from the first point to the last point, in the units of
+    /// the coordinates per second.
+    pub fn trajectory_speed(&self) -> DFResult<ScalarValue> {
+        let (nanos, distance) = self.segments().fold((0, 0.0), |(nanos, distance), (n, d)| {
+            (nanos + n, distance + d)
+        });
+        if nanos == 0 {
+            return Ok(ScalarValue::Float64(None));
+        }
+
+        Ok(ScalarValue::Float64(Some(distance * 1e9 / nanos as f64)))
+    }
+
+    /// The maximum planar speed between two consecutive points, in the units of the
+    /// coordinates per second.
+    pub fn trajectory_max_speed(&self) -> DFResult<ScalarValue> {
+        let max_speed = self
+            .segments()
+            .filter(|(nanos, _)| *nanos > 0)
+            .map(|(nanos, length)| length * 1e9 / nanos as f64)
+            .reduce(f64::max);
+
+        Ok(ScalarValue::Float64(max_speed))
+    }
+
+    /// The total time spent inside the area, each point lasts until the next point like
+    /// the states of `state_agg`.
+    pub fn dwell_time(&self, area: &Geometry) -> DFResult<ScalarValue> {
+        let nanos_per_unit = self.nanos_per_unit();
+        let nanos: i64 = self
+            .points
+            .iter()
+            .zip(self.points.iter().skip(1))
+            .filter(|((_, p), _)| area.intersects(&Geometry::Point(Point(*p))))
+            .map(|((t1, _), (t2, _))| (t2 - t1) * nanos_per_unit)
+            .sum();
+
+        Ok(ScalarValue::IntervalMonthDayNano(Some(nanos as i128)))
+    }
+
+    /// Simplifies the path by the Douglas-Peucker algorithm, the times of the kept points
+    /// are retained.
+    pub fn simplify(&self, tolerance: f64) -> DFResult<Self> {
+        if !tolerance.is_finite() || tolerance < 0.0 {
+            return Err(DataFusionError::Execution(format!(
+                "The tolerance of trajectory_simplify must be a non-negative number, but found {tolerance}"
+            )));
+        }
+
+        let path = self.points.iter().map(|(_, p)| *p).collect::<LineString>();
+        let points = path
+            .simplify_idx(&tolerance)
+            .into_iter()
+            .map(|idx| self.points[idx])
+            .collect();
+
+        Ok(Self::new(self.time_data_type.clone(), points))
+    }
+
+    /// The fields of the elements returned by `geofence_events`.
+    pub(crate) fn geofence_event_fields(&self) -> Fields {
+        Fields::from([
+            Arc::new(Field::new("time", self.time_data_type.clone(), true)),
+            Arc::new(Field::new("event", DataType::Utf8, true)),
+        ])
+    }
+
+    /// Returns the list of 'enter' and 'exit' events with the time of the first point after
+    /// crossing the boundary of the fence, the first point only decides the initial side.
+    pub fn geofence_events(&self, fence: &Geometry) -> DFResult<ScalarValue> {
+        let fields = self.geofence_event_fields();
+
+        let mut events = vec![];
+        let mut last_inside = None;
+        for (ts, p) in &self.points {
+            let inside = fence.intersects(&Geometry::Point(Point(*p)));
+            if last_inside.is_some_and(|last| last != inside) {
+                let event = if inside {
+                    GEOFENCE_ENTER
+                } else {
+                    GEOFENCE_EXIT
+                };
+                events.push(ScalarValue::Struct(
+                    Some(vec![
+                        timestamp_scalar(*ts, &self.time_data_type)?,
+                        ScalarValue::from(event),
+                    ]),
+                    fields.clone(),
+                ));
+            }
+            last_inside = Some(inside);
+        }
+
+        Ok(ScalarValue::new_list(
+            Some(events),
+            DataType::Struct(fields),
+        ))
+    }
+}
+
+impl AggResult for TrajectoryData {
+    fn to_scalar(self) -> DFResult<ScalarValue> {
+        let times = self
+            .points
+            .iter()
+            .map(|(ts, _)| timestamp_scalar(*ts, &self.time_data_type))
+            .collect::<DFResult<Vec<_>>>()?;
+        let times = ScalarValue::new_list(Some(times), self.time_data_type.clone());
+
+        let path = if self.points.is_empty() {
+            ScalarValue::Utf8(None)
+        } else {
+            let path = self.points.into_iter().map(|(_, p)| p).collect();
+            let wkt = Geometry::LineString(path)
+                .to_wkt()
+                .map_err(|err| DataFusionError::Execution(err.to_string()))?;
+            ScalarValue::Utf8(Some(wkt))
+        };
+
+        let times_data_type = times.get_datatype();
+        Ok(ScalarValue::Struct(
+            Some(vec![times, path]),
+            Fields::from([
+                Arc::new(Field::new("times", times_data_type, true)),
+                Arc::new(Field::new("path", DataType::Utf8, true)),
+            ]),
+        ))
+    }
+}
+
+impl TrajectoryData {
+    pub fn try_from_scalar(scalar: ScalarValue) -> DFResult<Self> {
+        let time_data_type = |fields: &Fields| {
+            let field_names = ["times", "path"];
+            let input_fields = fields.iter().map(|f| f.name().as_str()).collect::<Vec<_>>();
+            let error = || {
+                DataFusionError::External(Box::new(QueryError::Analyzer {
+                    err: format!("Expected TrajectoryData, got {:?}", fields),
+                }))
+            };
+            if !input_fields.eq(&field_names) {
+                return Err(error());
+            }
+            match fields[0].data_type() {
+                DataType::List(f) => Ok(f.data_type().clone()),
+                _ => Err(error()),
+            }
+        };
+
+        match scalar {
+            ScalarValue::Struct(Some(values), fields) => {
+                let time_data_type = time_data_type(&fields)?;
+
+                let times = match &values[0] {
+                    ScalarValue::List(Some(times), _) => times
+                        .iter()
+                        .map(|ts| i64::try_from(ts.clone()))
+                        .collect::<DFResult<Vec<_>>>()?,
+                    _ => vec![],
+                };
+                let coords = match &values[1] {
+                    ScalarValue::Utf8(Some(wkt)) => match str_to_geo(wkt)? {
+                        Geometry::LineString(path) => path.0,
+                        other => {
+                            return Err(DataFusionError::Execution(format!(
+                                "Expected the path of TrajectoryData is a LINESTRING, got {other:?}"
+                            )))
+                        }
+                    },
+                    _ => vec![],
+                };
+                if times.len() != coords.len() {
+                    return Err(DataFusionError::External(Box::new(QueryError::Internal {
+                        reason: format!(
+                            "TrajectoryData has {} times but {} points",
+                            times.len(),
+                            coords.len()
+                        ),
+                    })));
+                }
+
+                Ok(Self::new(
+                    time_data_type,
+                    times.into_iter().zip(coords).collect(),
+                ))
+            }
+            ScalarValue::Struct(None, fields) => Ok(Self::new(time_data_type(&fields)?, vec![])),
+            _ => Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                err: format!("Expected TrajectoryData, got {:?}", scalar),
+            }))),
+        }
+    }
+}
+
+fn timestamp_scalar(ts: i64, data_type: &DataType) -> DFResult<ScalarValue> {
+    match data_type {
+        DataType::Timestamp(TimeUnit::Second, tz) => {
+            Ok(ScalarValue::TimestampSecond(Some(ts), tz.clone()))
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, tz) => {
+            Ok(ScalarValue::TimestampMillisecond(Some(ts), tz.clone()))
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, tz) => {
+            Ok(ScalarValue::TimestampMicrosecond(Some(ts), tz.clone()))
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+            Ok(ScalarValue::TimestampNanosecond(Some(ts), tz.clone()))
+        }
+        _ => Err(DataFusionError::External(Box::new(QueryError::Internal {
+            reason: format!("Expected timestamp type, got {}", data_type),
+        }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::datatypes::{DataType, TimeUnit};
+    use datafusion::scalar::ScalarValue;
+    use geo::Coord;
+
+    use super::TrajectoryData;
+    use crate::extension::expr::aggregate_function::AggResult;
+    use crate::extension::expr::scalar_function::gis::str_to_geo;
+
+    fn trajectory() -> TrajectoryData {
+        // (0 0) -> (0 1) -> (0 2) -> (3 6), one point per second
+        let points = [(0.0, 0.0), (0.0, 1.0), (0.0, 2.0), (3.0, 6.0)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (x, y))| (i as i64 * 1000, Coord { x, y }))
+            .collect();
+        TrajectoryData::new(DataType::Timestamp(TimeUnit::Millisecond, None), points)
+    }
+
+    #[test]
+    fn test_trajectory_data_scalar() {
+        let data = trajectory();
+        let scalar = data.clone().to_scalar().unwrap();
+        assert_eq!(TrajectoryData::try_from_scalar(scalar).unwrap(), data);
+
+        let null = ScalarValue::try_from(data.to_scalar().unwrap().get_datatype()).unwrap();
+        let null = TrajectoryData::try_from_scalar(null).unwrap();
+        assert!(null.points.is_empty());
+        assert_eq!(
+            null.trajectory_distance().unwrap(),
+            ScalarValue::Float64(None)
+        );
+    }
+
+    #[test]
+    fn test_trajectory_distance_and_speed() {
+        let data = trajectory();
+        assert_eq!(
+            data.trajectory_distance().unwrap(),
+            ScalarValue::Float64(Some(7.0))
+        );
+        assert_eq!(
+            data.trajectory_max_speed().unwrap(),
+            ScalarValue::Float64(Some(5.0))
+        );
+        let speed: f64 = data.trajectory_speed().unwrap().try_into().unwrap();
+        assert!((speed - 7.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trajectory_dwell_time_and_geofence_events() {
+        let data = trajectory();
+        let fence = str_to_geo("POLYGON((-1 0.5, 1 0.5, 1 1.5, -1 1.5, -1 0.5))").unwrap();
+        assert_eq!(
+            data.dwell_time(&fence).unwrap(),
+            ScalarValue::IntervalMonthDayNano(Some(1_000_000_000))
+        );
+
+        let events = data.geofence_events(&fence).unwrap();
+        let fields = data.geofence_event_fields();
+        let event = |ts, event: &str| {
+            ScalarValue::Struct(
+                Some(vec![
+                    ScalarValue::TimestampMillisecond(Some(ts), None),
+                    ScalarValue::from(event),
+                ]),
+                fields.clone(),
+            )
+        };
+        assert_eq!(
+            events,
+            ScalarValue::new_list(
+                Some(vec![event(1000, "enter"), event(2000, "exit")]),
+                DataType::Struct(fields)
+            )
+        );
+    }
+
+    #[test]
+    fn test_trajectory_simplify() {
+        let data = trajectory();
+        let simplified = data.simplify(0.1).unwrap();
+        assert_eq!(
+            simplified.points,
+            vec![
+                (0, Coord { x: 0.0, y: 0.0 }),
+                (2000, Coord { x: 0.0, y: 2.0 }),
+                (3000, Coord { x: 3.0, y: 6.0 })
+            ]
+        );
+        assert!(data.simplify(-1.0).is_err());
+    }
+}
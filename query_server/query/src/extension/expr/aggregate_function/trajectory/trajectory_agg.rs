@@ -0,0 +1,235 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array, Int64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::{as_int64_array, as_list_array, as_string_array};
+use datafusion::common::{downcast_value, DataFusionError, Result as DFResult};
+use datafusion::logical_expr::type_coercion::aggregates::TIMESTAMPS;
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    TypeSignature, Volatility,
+};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use geo::{Coord, Geometry};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryError;
+
+use super::TrajectoryData;
+use crate::extension::expr::aggregate_function::{AggResult, TRAJECTORY_AGG_UDAF_NAME};
+use crate::extension::expr::scalar_function::gis::str_to_geo;
+
+pub fn register_udaf(func_manager: &mut dyn FunctionMetadataManager) -> Result<(), QueryError> {
+    func_manager.register_udaf(new())?;
+    Ok(())
+}
+
+fn new() -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(move |input| {
+        let result = TrajectoryData::new(input[0].clone(), vec![]);
+        let date_type = result.to_scalar()?.get_datatype();
+
+        trace::trace!("return_type: {:?}", date_type);
+
+        Ok(Arc::new(date_type))
+    });
+
+    // state: [times, xs, ys]
+    let state_type_func: StateTypeFunction = Arc::new(move |_, _| {
+        Ok(Arc::new(vec![
+            DataType::List(Arc::new(Field::new("item", DataType::Int64, true))),
+            DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+            DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+        ]))
+    });
+
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(|input, _| Ok(Box::new(TrajectoryAggAccumulator::new(input[0].clone()))));
+
+    // trajectory_agg(
+    //     time TIMESTAMP,
+    //     point STRING
+    //   ) RETURNS TrajectoryData
+    let type_signatures = TIMESTAMPS
+        .iter()
+        .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Utf8]))
+        .collect();
+
+    AggregateUDF::new(
+        TRAJECTORY_AGG_UDAF_NAME,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type_func,
+        &accumulator,
+        &state_type_func,
+    )
+}
+
+/// Collects the points of a moving object and builds the trajectory ordered by time.
+#[derive(Debug)]
+struct TrajectoryAggAccumulator {
+    time_data_type: DataType,
+    /// (time in the unit of `time_data_type`, point)
+    points: Vec<(i64, Coord)>,
+}
+
+impl TrajectoryAggAccumulator {
+    fn new(time_data_type: DataType) -> Self {
+        Self {
+            time_data_type,
+            points: vec![],
+        }
+    }
+}
+
+impl Accumulator for TrajectoryAggAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("update_batch: {:?}", values);
+
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        debug_assert!(
+            values.len() == 2,
+            "trajectory_agg can only take 2 param, but found {}",
+            values.len()
+        );
+
+        let times = cast(&values[0], &DataType::Int64)?;
+        let times = as_int64_array(times.as_ref())?;
+        let points = as_string_array(values[1].as_ref())?;
+
+        for (ts, point) in times.iter().zip(points.iter()) {
+            if let (Some(ts), Some(point)) = (ts, point) {
+                match str_to_geo(point)? {
+                    Geometry::Point(p) => self.points.push((ts, p.0)),
+                    other => {
+                        return Err(DataFusionError::Execution(format!(
+                            "trajectory_agg expects points, but found {other:?}"
+                        )))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        let mut points = self.points.clone();
+        points.sort_by_key(|(ts, _)| *ts);
+
+        let result = TrajectoryData::new(self.time_data_type.clone(), points).to_scalar()?;
+
+        trace::trace!("TrajectoryAggAccumulator evaluate result: {:?}", result);
+
+        Ok(result)
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.points.capacity() * std::mem::size_of::<(i64, Coord)>()
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let mut times = Vec::with_capacity(self.points.len());
+        let mut xs = Vec::with_capacity(self.points.len());
+        let mut ys = Vec::with_capacity(self.points.len());
+        for (ts, p) in &self.points {
+            times.push(ScalarValue::Int64(Some(*ts)));
+            xs.push(ScalarValue::Float64(Some(p.x)));
+            ys.push(ScalarValue::Float64(Some(p.y)));
+        }
+
+        Ok(vec![
+            ScalarValue::new_list(Some(times), DataType::Int64),
+            ScalarValue::new_list(Some(xs), DataType::Float64),
+            ScalarValue::new_list(Some(ys), DataType::Float64),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("merge_batch: {:?}", states);
+
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        let time_lists = as_list_array(states[0].as_ref())?;
+        let x_lists = as_list_array(states[1].as_ref())?;
+        let y_lists = as_list_array(states[2].as_ref())?;
+
+        for ((times, xs), ys) in time_lists
+            .iter()
+            .flatten()
+            .zip(x_lists.iter().flatten())
+            .zip(y_lists.iter().flatten())
+        {
+            let times = downcast_value!(times.as_ref(), Int64Array);
+            let xs = downcast_value!(xs.as_ref(), Float64Array);
+            let ys = downcast_value!(ys.as_ref(), Float64Array);
+            for i in 0..times.len() {
+                if times.is_valid(i) && xs.is_valid(i) && ys.is_valid(i) {
+                    let coord = Coord {
+                        x: xs.value(i),
+                        y: ys.value(i),
+                    };
+                    self.points.push((times.value(i), coord));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, StringArray, TimestampMillisecondArray};
+    use datafusion::arrow::datatypes::{DataType, TimeUnit};
+    use datafusion::physical_plan::Accumulator;
+
+    use super::TrajectoryAggAccumulator;
+    use crate::extension::expr::aggregate_function::TrajectoryData;
+
+    #[test]
+    fn test_trajectory_agg() {
+        let time_data_type = DataType::Timestamp(TimeUnit::Millisecond, None);
+        let mut acc = TrajectoryAggAccumulator::new(time_data_type.clone());
+        let times: ArrayRef = Arc::new(TimestampMillisecondArray::from(vec![3000, 1000, 2000]));
+        let points: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("POINT(3 4)"),
+            Some("POINT(0 0)"),
+            None,
+        ]));
+        acc.update_batch(&[times, points]).unwrap();
+
+        let mut other = TrajectoryAggAccumulator::new(time_data_type.clone());
+        let times: ArrayRef = Arc::new(TimestampMillisecondArray::from(vec![2000]));
+        let points: ArrayRef = Arc::new(StringArray::from(vec!["POINT(0 4)"]));
+        other.update_batch(&[times, points]).unwrap();
+        let state = other
+            .state()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.to_array())
+            .collect::<Vec<_>>();
+        acc.merge_batch(&state).unwrap();
+
+        let result = acc.evaluate().unwrap();
+        assert_eq!(result.get_datatype(), {
+            let null = TrajectoryAggAccumulator::new(time_data_type);
+            null.evaluate().unwrap().get_datatype()
+        });
+
+        let data = TrajectoryData::try_from_scalar(result).unwrap();
+        assert_eq!(data.trajectory_distance().unwrap(), 7.0_f64.into());
+        assert_eq!(data.trajectory_max_speed().unwrap(), 4.0_f64.into());
+
+        let times: ArrayRef = Arc::new(TimestampMillisecondArray::from(vec![4000]));
+        let points: ArrayRef = Arc::new(StringArray::from(vec!["LINESTRING(0 0,1 1)"]));
+        assert!(acc.update_batch(&[times, points]).is_err());
+    }
+}
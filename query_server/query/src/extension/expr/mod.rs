@@ -10,14 +10,18 @@ mod session_function;
 mod window;
 
 use datafusion::arrow::datatypes::{DataType, IntervalUnit};
-pub use scalar_function::{INTERPOLATE, LOCF, TIME_WINDOW_GAPFILL};
+pub use scalar_function::{
+    ASOF_MATCH_CONDITION, INTERPOLATE, INTERPOLATE_WITH_PRIOR, LOCF, LOCF_WITH_PRIOR,
+    TIME_WINDOW_GAPFILL,
+};
 pub use selector_function::{BOTTOM, TOPK};
 pub use session_function::register_session_udfs;
 use spi::query::function::FunctionMetadataManager;
 use spi::Result;
 pub use window::{
-    ceil_sliding_window, floor_sliding_window, time_window_signature, DEFAULT_TIME_WINDOW_START,
-    TIME_WINDOW, TIME_WINDOW_UDF, WINDOW_COL_NAME, WINDOW_END, WINDOW_START,
+    ceil_sliding_window, event_window_return_type, floor_sliding_window, time_window_signature,
+    COUNT_WINDOW, DEFAULT_TIME_WINDOW_START, SESSION_WINDOW, TIME_WINDOW, TIME_WINDOW_UDF,
+    WINDOW_COL_NAME, WINDOW_END, WINDOW_START,
 };
 
 pub static INTERVALS: &[DataType] = &[
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_expr::functions::make_scalar_function;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use super::ASOF_MATCH_CONDITION;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    // Generated by the parser from `ASOF JOIN ... MATCH_CONDITION(left.time >= right.time)`
+    let func = |_: &[ArrayRef]| {
+        Err(DataFusionError::Execution(format!(
+            "{} has no specific implementation, should be converted to AsofJoin operator.",
+            ASOF_MATCH_CONDITION
+        )))
+    };
+    let func = make_scalar_function(func);
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Boolean)));
+
+    ScalarUDF::new(
+        ASOF_MATCH_CONDITION,
+        &Signature::exact(vec![DataType::Boolean], Volatility::Immutable),
+        &return_type,
+        &func,
+    )
+}
@@ -1,8 +1,14 @@
 mod st_area;
 mod st_asbinary;
 mod st_binary_op;
+mod st_buffer;
+mod st_centroid;
 mod st_distance;
+mod st_dwithin;
+mod st_geohash;
 mod st_geomfromwkb;
+mod st_length;
+mod st_transform;
 
 use datafusion::error::DataFusionError;
 use geo::Geometry;
@@ -17,6 +23,12 @@ pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> Result<(
     st_asbinary::register_udf(func_manager)?;
     st_area::register_udf(func_manager)?;
     st_binary_op::register_udf(func_manager)?;
+    st_dwithin::register_udf(func_manager)?;
+    st_buffer::register_udf(func_manager)?;
+    st_centroid::register_udf(func_manager)?;
+    st_length::register_udf(func_manager)?;
+    st_geohash::register_udf(func_manager)?;
+    st_transform::register_udf(func_manager)?;
     Ok(())
 }
 
@@ -0,0 +1,195 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    downcast_array, ArrayRef, Float64Array, StringArray, StringBuilder,
+};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_plan::functions::make_scalar_function;
+use geo::{
+    BooleanOps, ConvexHull, Coord, Geometry, LineString, MultiPoint, MultiPolygon, Point, Polygon,
+};
+use geozero::ToWkt;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use super::str_to_geo;
+
+/// The number of segments used to approximate a circle
+const CIRCLE_SEGMENTS: usize = 32;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let fun = make_scalar_function(func);
+
+    // ST_Buffer(geom, radius)
+    let signature = Signature::exact(
+        vec![DataType::Utf8, DataType::Float64],
+        Volatility::Immutable,
+    );
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+
+    ScalarUDF::new("ST_Buffer", &signature, &return_type, &fun)
+}
+
+fn func(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let geo = downcast_array::<StringArray>(args[0].as_ref());
+    let radius = downcast_array::<Float64Array>(args[1].as_ref());
+
+    let mut builder = StringBuilder::new();
+    for (g, r) in geo.iter().zip(radius.iter()) {
+        match (g, r) {
+            (Some(g), Some(r)) => {
+                let wkt = buffer(&str_to_geo(g)?, r)?
+                    .to_wkt()
+                    .map_err(|err| DataFusionError::Execution(err.to_string()))?;
+                builder.append_value(wkt);
+            }
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Returns the polygon covering all points whose distance from the geometry is not
+/// greater than the radius, the round parts are approximated by `CIRCLE_SEGMENTS`
+/// segments per circle.
+fn buffer(geo: &Geometry, radius: f64) -> DFResult<Geometry> {
+    if !radius.is_finite() || radius < 0.0 {
+        return Err(DataFusionError::Execution(format!(
+            "The radius of ST_Buffer must be a non-negative number, but found {radius}"
+        )));
+    }
+    if radius == 0.0 {
+        return Ok(geo.clone());
+    }
+
+    let mut polygons = buffer_polygons(geo, radius).into_iter();
+    let first = match polygons.next() {
+        Some(p) => MultiPolygon::new(vec![p]),
+        None => return Ok(Geometry::MultiPolygon(MultiPolygon::new(vec![]))),
+    };
+    let mut union = polygons.fold(first, |acc, p| acc.union(&MultiPolygon::new(vec![p])));
+
+    if union.0.len() == 1 {
+        Ok(Geometry::Polygon(union.0.remove(0)))
+    } else {
+        Ok(Geometry::MultiPolygon(union))
+    }
+}
+
+/// Returns the polygons whose union is the buffer of the geometry.
+fn buffer_polygons(geo: &Geometry, radius: f64) -> Vec<Polygon> {
+    match geo {
+        Geometry::Point(p) => vec![circle(p.0, radius)],
+        Geometry::MultiPoint(points) => points.iter().map(|p| circle(p.0, radius)).collect(),
+        Geometry::Line(line) => vec![capsule(line.start, line.end, radius)],
+        Geometry::LineString(line_string) => line_string_buffer(line_string, radius),
+        Geometry::MultiLineString(lines) => lines
+            .iter()
+            .flat_map(|l| line_string_buffer(l, radius))
+            .collect(),
+        Geometry::Polygon(polygon) => polygon_buffer(polygon, radius),
+        Geometry::MultiPolygon(polygons) => polygons
+            .iter()
+            .flat_map(|p| polygon_buffer(p, radius))
+            .collect(),
+        Geometry::Rect(rect) => polygon_buffer(&rect.to_polygon(), radius),
+        Geometry::Triangle(triangle) => polygon_buffer(&triangle.to_polygon(), radius),
+        Geometry::GeometryCollection(collection) => collection
+            .iter()
+            .flat_map(|g| buffer_polygons(g, radius))
+            .collect(),
+    }
+}
+
+fn line_string_buffer(line_string: &LineString, radius: f64) -> Vec<Polygon> {
+    let mut polygons = line_string
+        .lines()
+        .map(|l| capsule(l.start, l.end, radius))
+        .collect::<Vec<_>>();
+    if polygons.is_empty() {
+        // a line string of a single point
+        polygons.extend(line_string.coords().map(|c| circle(*c, radius)));
+    }
+    polygons
+}
+
+fn polygon_buffer(polygon: &Polygon, radius: f64) -> Vec<Polygon> {
+    let mut polygons = vec![polygon.clone()];
+    polygons.extend(line_string_buffer(polygon.exterior(), radius));
+    for interior in polygon.interiors() {
+        polygons.extend(line_string_buffer(interior, radius));
+    }
+    polygons
+}
+
+fn circle_points(center: Coord, radius: f64) -> impl Iterator<Item = Point> {
+    (0..CIRCLE_SEGMENTS).map(move |i| {
+        let angle = 2.0 * PI * i as f64 / CIRCLE_SEGMENTS as f64;
+        Point::new(
+            center.x + radius * angle.cos(),
+            center.y + radius * angle.sin(),
+        )
+    })
+}
+
+fn circle(center: Coord, radius: f64) -> Polygon {
+    let exterior = circle_points(center, radius)
+        .map(|p| p.0)
+        .collect::<Vec<_>>();
+    Polygon::new(LineString::new(exterior), vec![])
+}
+
+/// The convex hull of the circles at both ends of the segment.
+fn capsule(start: Coord, end: Coord, radius: f64) -> Polygon {
+    circle_points(start, radius)
+        .chain(circle_points(end, radius))
+        .collect::<MultiPoint>()
+        .convex_hull()
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{point, Area, Contains, Geometry};
+
+    use super::buffer;
+    use crate::extension::expr::scalar_function::gis::str_to_geo;
+
+    #[test]
+    fn test_buffer() {
+        let geo: Geometry = point!(x: 1.0, y: 1.0).into();
+        let circle = buffer(&geo, 2.0).unwrap();
+        let area = circle.unsigned_area();
+        // the area of the inscribed polygon of 32 sides
+        assert!((area - 12.486).abs() < 0.01, "{area}");
+
+        let line = str_to_geo("LINESTRING(0 0, 10 0)").unwrap();
+        match buffer(&line, 1.0).unwrap() {
+            Geometry::Polygon(capsule) => {
+                assert!(capsule.contains(&point!(x: 5.0, y: 0.9)));
+                assert!(capsule.contains(&point!(x: -0.9, y: 0.0)));
+                assert!(!capsule.contains(&point!(x: 5.0, y: 1.1)));
+            }
+            g => panic!("expected polygon, got {g:?}"),
+        }
+
+        let points = str_to_geo("MULTIPOINT((0 0), (10 10))").unwrap();
+        assert!(matches!(
+            buffer(&points, 1.0).unwrap(),
+            Geometry::MultiPolygon(p) if p.0.len() == 2
+        ));
+
+        assert_eq!(buffer(&line, 0.0).unwrap(), line);
+        assert!(buffer(&line, -1.0).is_err());
+    }
+}
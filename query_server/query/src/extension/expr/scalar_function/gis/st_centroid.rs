@@ -0,0 +1,26 @@
+use datafusion::arrow::array::StringBuilder;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::ScalarUDF;
+use geo::{Centroid, Geometry};
+use geozero::ToWkt;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use crate::geometry_unary_op;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = geometry_unary_op!("ST_Centroid", centroid, DataType::Utf8, StringBuilder);
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+/// Returns the geometric center of the geometry, `POINT EMPTY` for an empty geometry.
+fn centroid(geo: &Geometry) -> Result<String, DataFusionError> {
+    match geo.centroid() {
+        Some(point) => Geometry::Point(point)
+            .to_wkt()
+            .map_err(|err| DataFusionError::Execution(err.to_string())),
+        None => Ok("POINT EMPTY".to_string()),
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    downcast_array, ArrayRef, BooleanBuilder, Float64Array, StringArray,
+};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::Result as DFResult;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_plan::functions::make_scalar_function;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use super::st_distance::distance;
+use super::str_to_geo;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let fun = make_scalar_function(func);
+
+    // ST_DWithin(geom1, geom2, distance)
+    let signature = Signature::exact(
+        vec![DataType::Utf8, DataType::Utf8, DataType::Float64],
+        Volatility::Immutable,
+    );
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Boolean)));
+
+    ScalarUDF::new("ST_DWithin", &signature, &return_type, &fun)
+}
+
+/// Returns true if the distance between the geometries is not greater than the distance.
+fn func(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let geo1 = downcast_array::<StringArray>(args[0].as_ref());
+    let geo2 = downcast_array::<StringArray>(args[1].as_ref());
+    let dist = downcast_array::<Float64Array>(args[2].as_ref());
+
+    let mut builder = BooleanBuilder::with_capacity(geo1.len());
+    for ((l, r), d) in geo1.iter().zip(geo2.iter()).zip(dist.iter()) {
+        match (l, r, d) {
+            (Some(l), Some(r), Some(d)) => {
+                let geo_l = str_to_geo(l)?;
+                let geo_r = str_to_geo(r)?;
+                builder.append_value(distance(&geo_l, &geo_r)? <= d);
+            }
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
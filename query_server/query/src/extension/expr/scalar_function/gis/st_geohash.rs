@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    downcast_array, Array, ArrayRef, Int64Array, StringArray, StringBuilder,
+};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarUDF, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_plan::functions::make_scalar_function;
+use geo::{Centroid, Coord, Geometry};
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use super::str_to_geo;
+
+const BASE32: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+const MAX_PRECISION: i64 = 12;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let fun = make_scalar_function(func);
+
+    // ST_GeoHash(geom [, precision])
+    let signature = Signature::one_of(
+        vec![
+            TypeSignature::Exact(vec![DataType::Utf8]),
+            TypeSignature::Exact(vec![DataType::Utf8, DataType::Int64]),
+        ],
+        Volatility::Immutable,
+    );
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+
+    ScalarUDF::new("ST_GeoHash", &signature, &return_type, &fun)
+}
+
+/// Returns the geohash of the point, or of the centroid of other geometries,
+/// the coordinates must be longitude and latitude.
+fn func(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let geo = downcast_array::<StringArray>(args[0].as_ref());
+    let precision = args
+        .get(1)
+        .map(|arr| downcast_array::<Int64Array>(arr.as_ref()));
+
+    let mut builder = StringBuilder::with_capacity(geo.len(), geo.len() * MAX_PRECISION as usize);
+    for (i, g) in geo.iter().enumerate() {
+        let precision = match &precision {
+            Some(arr) if arr.is_null(i) => None,
+            Some(arr) => Some(arr.value(i)),
+            None => Some(MAX_PRECISION),
+        };
+        match (g, precision) {
+            (Some(g), Some(precision)) => {
+                let point = match str_to_geo(g)? {
+                    Geometry::Point(p) => Some(p),
+                    other => other.centroid(),
+                };
+                match point {
+                    Some(p) => builder.append_value(geohash(p.0, precision)?),
+                    None => builder.append_null(),
+                }
+            }
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn geohash(coord: Coord, precision: i64) -> DFResult<String> {
+    if !(1..=MAX_PRECISION).contains(&precision) {
+        return Err(DataFusionError::Execution(format!(
+            "The precision of ST_GeoHash must be between 1 and {MAX_PRECISION}, but found {precision}"
+        )));
+    }
+    let (lon, lat) = (coord.x, coord.y);
+    if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
+        return Err(DataFusionError::Execution(format!(
+            "ST_GeoHash expects longitude and latitude, but found POINT({lon} {lat})"
+        )));
+    }
+
+    let (mut lon_range, mut lat_range) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut hash = String::with_capacity(precision as usize);
+    let mut even = true;
+    for _ in 0..precision {
+        let mut idx = 0;
+        for _ in 0..5 {
+            let (range, value) = if even {
+                (&mut lon_range, lon)
+            } else {
+                (&mut lat_range, lat)
+            };
+            let mid = (range.0 + range.1) / 2.0;
+            idx <<= 1;
+            if value >= mid {
+                idx |= 1;
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            even = !even;
+        }
+        hash.push(BASE32[idx] as char);
+    }
+
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coord;
+
+    use super::geohash;
+
+    #[test]
+    fn test_geohash() {
+        let coord = Coord { x: -126.0, y: 48.0 };
+        assert_eq!(geohash(coord, 5).unwrap(), "c0w3h");
+        assert_eq!(geohash(coord, 12).unwrap(), "c0w3hf1s70w3");
+        let coord = Coord {
+            x: 10.40744,
+            y: 57.64911,
+        };
+        assert_eq!(geohash(coord, 11).unwrap(), "u4pruydqqvj");
+        assert!(geohash(coord, 13).is_err());
+        assert!(geohash(Coord { x: 200.0, y: 0.0 }, 5).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use datafusion::arrow::array::Float64Builder;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::ScalarUDF;
+use geo::{EuclideanLength, Geometry};
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use crate::geometry_unary_op;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = geometry_unary_op!("ST_Length", length, DataType::Float64, Float64Builder);
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+/// Returns the 2D length of the linear geometries, other geometries have no length.
+fn length(geo: &Geometry) -> Result<f64, DataFusionError> {
+    let length = match geo {
+        Geometry::Line(line) => line.euclidean_length(),
+        Geometry::LineString(line_string) => line_string.euclidean_length(),
+        Geometry::MultiLineString(lines) => lines.euclidean_length(),
+        Geometry::GeometryCollection(collection) => collection
+            .iter()
+            .map(length)
+            .sum::<Result<f64, DataFusionError>>()?,
+        _ => 0.0,
+    };
+
+    Ok(length)
+}
@@ -0,0 +1,127 @@
+use std::f64::consts::FRAC_PI_4;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{downcast_array, ArrayRef, Int64Array, StringArray, StringBuilder};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_plan::functions::make_scalar_function;
+use geo::{Coord, Geometry, MapCoords};
+use geozero::ToWkt;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use super::str_to_geo;
+
+/// WGS 84, longitude and latitude in degrees
+const SRID_WGS84: i64 = 4326;
+/// WGS 84 / Pseudo-Mercator, x and y in meters
+const SRID_WEB_MERCATOR: i64 = 3857;
+const EARTH_RADIUS: f64 = 6_378_137.0;
+/// The latitude where the Pseudo-Mercator projection is square
+const MAX_MERCATOR_LATITUDE: f64 = 85.051_128_779_806_59;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let fun = make_scalar_function(func);
+
+    // ST_Transform(geom, from_srid, to_srid)
+    let signature = Signature::exact(
+        vec![DataType::Utf8, DataType::Int64, DataType::Int64],
+        Volatility::Immutable,
+    );
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+
+    ScalarUDF::new("ST_Transform", &signature, &return_type, &fun)
+}
+
+fn func(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let geo = downcast_array::<StringArray>(args[0].as_ref());
+    let from = downcast_array::<Int64Array>(args[1].as_ref());
+    let to = downcast_array::<Int64Array>(args[2].as_ref());
+
+    let mut builder = StringBuilder::new();
+    for ((g, from), to) in geo.iter().zip(from.iter()).zip(to.iter()) {
+        match (g, from, to) {
+            (Some(g), Some(from), Some(to)) => {
+                let geo = transform(&str_to_geo(g)?, from, to)?;
+                let wkt = geo
+                    .to_wkt()
+                    .map_err(|err| DataFusionError::Execution(err.to_string()))?;
+                builder.append_value(wkt);
+            }
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Transforms the coordinates of the geometry between the spatial reference systems,
+/// only WGS 84 (4326) and Pseudo-Mercator (3857) are supported.
+fn transform(geo: &Geometry, from: i64, to: i64) -> DFResult<Geometry> {
+    match (from, to) {
+        (from, to) if from == to => Ok(geo.clone()),
+        (SRID_WGS84, SRID_WEB_MERCATOR) => Ok(geo.map_coords(wgs84_to_web_mercator)),
+        (SRID_WEB_MERCATOR, SRID_WGS84) => Ok(geo.map_coords(web_mercator_to_wgs84)),
+        _ => Err(DataFusionError::Execution(format!(
+            "ST_Transform from SRID {from} to SRID {to} is not supported, \
+             only {SRID_WGS84} and {SRID_WEB_MERCATOR} are supported"
+        ))),
+    }
+}
+
+fn wgs84_to_web_mercator(coord: Coord) -> Coord {
+    let lat = coord
+        .y
+        .clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE)
+        .to_radians();
+    Coord {
+        x: EARTH_RADIUS * coord.x.to_radians(),
+        y: EARTH_RADIUS * (FRAC_PI_4 + lat / 2.0).tan().ln(),
+    }
+}
+
+fn web_mercator_to_wgs84(coord: Coord) -> Coord {
+    Coord {
+        x: (coord.x / EARTH_RADIUS).to_degrees(),
+        y: (2.0 * (coord.y / EARTH_RADIUS).exp().atan() - 2.0 * FRAC_PI_4).to_degrees(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{point, Geometry};
+
+    use super::transform;
+
+    #[test]
+    fn test_transform() {
+        let geo: Geometry = point!(x: 116.391, y: 39.907).into();
+        let mercator = transform(&geo, 4326, 3857).unwrap();
+        match &mercator {
+            Geometry::Point(p) => {
+                assert!((p.x() - 12_956_586.9).abs() < 1.0, "{p:?}");
+                assert!((p.y() - 4_852_437.0).abs() < 1.0, "{p:?}");
+            }
+            g => panic!("expected point, got {g:?}"),
+        }
+
+        match transform(&mercator, 3857, 4326).unwrap() {
+            Geometry::Point(p) => {
+                assert!((p.x() - 116.391).abs() < 1e-9);
+                assert!((p.y() - 39.907).abs() < 1e-9);
+            }
+            g => panic!("expected point, got {g:?}"),
+        }
+
+        assert_eq!(transform(&geo, 4326, 4326).unwrap(), geo);
+        assert!(transform(&geo, 4326, 2000).is_err());
+    }
+}
@@ -7,24 +7,27 @@ use datafusion::logical_expr::{
 use spi::query::function::FunctionMetadataManager;
 use spi::Result;
 
-use super::{unimplemented_scalar_impl, INTERPOLATE};
+use super::{unimplemented_scalar_impl, INTERPOLATE, INTERPOLATE_WITH_PRIOR};
 
 pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
-    let udf = new();
+    let udf = new(INTERPOLATE);
     func_manager.register_udf(udf.clone())?;
+    // The variant also filling the gaps at the beginning of the time range
+    // with the last point of each series before it
+    func_manager.register_udf(new(INTERPOLATE_WITH_PRIOR))?;
     Ok(udf)
 }
 
-fn new() -> ScalarUDF {
+fn new(name: &'static str) -> ScalarUDF {
     let return_type_fn: ReturnTypeFunction = Arc::new(|args| Ok(Arc::new(args[0].clone())));
     let signatures = NUMERICS
         .iter()
         .map(|t| TypeSignature::Exact(vec![t.clone()]))
         .collect();
     ScalarUDF::new(
-        INTERPOLATE,
+        name,
         &Signature::one_of(signatures, Volatility::Volatile),
         &return_type_fn,
-        &unimplemented_scalar_impl(INTERPOLATE),
+        &unimplemented_scalar_impl(name),
     )
 }
@@ -4,20 +4,23 @@ use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatil
 use spi::query::function::FunctionMetadataManager;
 use spi::Result;
 
-use super::{unimplemented_scalar_impl, LOCF};
+use super::{unimplemented_scalar_impl, LOCF, LOCF_WITH_PRIOR};
 
 pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
-    let udf = new();
+    let udf = new(LOCF);
     func_manager.register_udf(udf.clone())?;
+    // The variant also filling the gaps at the beginning of the time range
+    // with the last point of each series before it
+    func_manager.register_udf(new(LOCF_WITH_PRIOR))?;
     Ok(udf)
 }
 
-fn new() -> ScalarUDF {
+fn new(name: &'static str) -> ScalarUDF {
     let return_type_fn: ReturnTypeFunction = Arc::new(|args| Ok(Arc::new(args[0].clone())));
     ScalarUDF::new(
-        LOCF,
+        name,
         &Signature::any(1, Volatility::Volatile),
         &return_type_fn,
-        &unimplemented_scalar_impl(LOCF),
+        &unimplemented_scalar_impl(name),
     )
 }
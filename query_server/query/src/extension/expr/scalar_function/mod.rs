@@ -1,12 +1,15 @@
+mod asof_match_condition;
 mod duration_in;
 #[cfg(test)]
 mod example;
 mod gapfill;
 mod gauge;
-mod gis;
+pub(super) mod gis;
 mod interpolate;
 mod locf;
+mod sketch;
 mod state_at;
+mod trajectory;
 mod utils;
 
 use std::sync::Arc;
@@ -19,8 +22,15 @@ use spi::Result;
 pub const TIME_WINDOW_GAPFILL: &str = "time_window_gapfill";
 pub const LOCF: &str = "locf";
 pub const INTERPOLATE: &str = "interpolate";
+pub const LOCF_WITH_PRIOR: &str = "locf_with_prior";
+pub const INTERPOLATE_WITH_PRIOR: &str = "interpolate_with_prior";
 pub const DURATION_IN: &str = "duration_in";
 pub const STATE_AT: &str = "state_at";
+pub const APPROX_PERCENTILE: &str = "approx_percentile";
+pub const ASOF_MATCH_CONDITION: &str = "asof_match_condition";
+pub const TRAJECTORY_DWELL_TIME: &str = "trajectory_dwell_time";
+pub const TRAJECTORY_SIMPLIFY: &str = "trajectory_simplify";
+pub const GEOFENCE_EVENTS: &str = "geofence_events";
 
 pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> Result<()> {
     // extend function...
@@ -33,6 +43,9 @@ pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> Result<(
     duration_in::register_udf(func_manager)?;
     state_at::register_udf(func_manager)?;
     gis::register_udfs(func_manager)?;
+    sketch::register_udfs(func_manager)?;
+    asof_match_condition::register_udf(func_manager)?;
+    trajectory::register_udfs(func_manager)?;
     Ok(())
 }
 
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::as_float64_array;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::type_coercion::aggregates::NUMERICS;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarUDF, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_expr::functions::make_scalar_function;
+use datafusion::scalar::ScalarValue;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use crate::extension::expr::aggregate_function::SketchData;
+use crate::extension::expr::scalar_function::APPROX_PERCENTILE;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+
+    // approx_percentile(
+    //     percentile DOUBLE,
+    //     sketch STRING
+    //   ) RETURNS DOUBLE
+    let type_signatures = NUMERICS
+        .iter()
+        .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Utf8]))
+        .collect();
+
+    let approx_percentile = make_scalar_function(approx_percentile_implement);
+
+    ScalarUDF::new(
+        APPROX_PERCENTILE,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type_fn,
+        &approx_percentile,
+    )
+}
+
+fn approx_percentile_implement(input: &[ArrayRef]) -> Result<ArrayRef, DataFusionError> {
+    let percentiles = cast(&input[0], &DataType::Float64)?;
+    let percentiles = as_float64_array(&percentiles)?;
+
+    let array_len = input[1].len();
+    let mut res = Vec::with_capacity(array_len);
+    for i in 0..array_len {
+        let sketch = ScalarValue::try_from_array(input[1].as_ref(), i)?;
+        let mut sketch = SketchData::try_from_scalar(sketch)?;
+        let value = if percentiles.is_valid(i) {
+            sketch.approx_percentile(percentiles.value(i))?
+        } else {
+            ScalarValue::Float64(None)
+        };
+        res.push(value);
+    }
+    let array = ScalarValue::iter_to_array(res)?;
+    Ok(array)
+}
@@ -0,0 +1,13 @@
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+mod approx_percentile;
+mod distinct_count;
+mod topn;
+
+pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> Result<()> {
+    approx_percentile::register_udf(func_manager)?;
+    distinct_count::register_udf(func_manager)?;
+    topn::register_udf(func_manager)?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::{DataType, IntervalUnit};
+use datafusion::common::cast::as_string_array;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_expr::functions::make_scalar_function;
+use datafusion::scalar::ScalarValue;
+use spi::query::function::FunctionMetadataManager;
+use spi::{QueryError, Result};
+
+use crate::extension::expr::aggregate_function::TrajectoryData;
+use crate::extension::expr::scalar_function::gis::str_to_geo;
+use crate::extension::expr::scalar_function::TRAJECTORY_DWELL_TIME;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|input| {
+        if input[1] != DataType::Utf8 {
+            return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                err: format!("Expect Geometry type, but found {} type.", &input[1]),
+            })));
+        }
+        Ok(Arc::new(DataType::Interval(IntervalUnit::MonthDayNano)))
+    });
+
+    let dwell_time = make_scalar_function(dwell_time_implement);
+
+    // trajectory_dwell_time(trajectory_agg, area)
+    ScalarUDF::new(
+        TRAJECTORY_DWELL_TIME,
+        &Signature::any(2, Volatility::Immutable),
+        &return_type_fn,
+        &dwell_time,
+    )
+}
+
+fn dwell_time_implement(input: &[ArrayRef]) -> Result<ArrayRef, DataFusionError> {
+    let areas = as_string_array(input[1].as_ref())?;
+    let mut res = Vec::with_capacity(areas.len());
+    for (i, area) in areas.iter().enumerate() {
+        let value = match area {
+            Some(area) => {
+                let trajectory = ScalarValue::try_from_array(input[0].as_ref(), i)?;
+                let trajectory = TrajectoryData::try_from_scalar(trajectory)?;
+                trajectory.dwell_time(&str_to_geo(area)?)?
+            }
+            None => ScalarValue::IntervalMonthDayNano(None),
+        };
+        res.push(value);
+    }
+    let array = ScalarValue::iter_to_array(res)?;
+    Ok(array)
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::as_string_array;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_expr::functions::make_scalar_function;
+use datafusion::scalar::ScalarValue;
+use spi::query::function::FunctionMetadataManager;
+use spi::{QueryError, Result};
+
+use crate::extension::expr::aggregate_function::TrajectoryData;
+use crate::extension::expr::scalar_function::gis::str_to_geo;
+use crate::extension::expr::scalar_function::GEOFENCE_EVENTS;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|input| {
+        if input[1] != DataType::Utf8 {
+            return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                err: format!("Expect Geometry type, but found {} type.", &input[1]),
+            })));
+        }
+        let null_data = TrajectoryData::try_from_scalar(ScalarValue::try_from(&input[0])?)?;
+        let event_data_type = DataType::Struct(null_data.geofence_event_fields());
+        Ok(Arc::new(DataType::List(Arc::new(Field::new(
+            "item",
+            event_data_type,
+            true,
+        )))))
+    });
+
+    let geofence_events = make_scalar_function(geofence_events_implement);
+
+    // geofence_events(trajectory_agg, fence)
+    ScalarUDF::new(
+        GEOFENCE_EVENTS,
+        &Signature::any(2, Volatility::Immutable),
+        &return_type_fn,
+        &geofence_events,
+    )
+}
+
+fn geofence_events_implement(input: &[ArrayRef]) -> Result<ArrayRef, DataFusionError> {
+    let fences = as_string_array(input[1].as_ref())?;
+    let mut res = Vec::with_capacity(fences.len());
+    for (i, fence) in fences.iter().enumerate() {
+        let trajectory = ScalarValue::try_from_array(input[0].as_ref(), i)?;
+        let trajectory = TrajectoryData::try_from_scalar(trajectory)?;
+        let value = match fence {
+            Some(fence) => trajectory.geofence_events(&str_to_geo(fence)?)?,
+            None => {
+                ScalarValue::new_list(None, DataType::Struct(trajectory.geofence_event_fields()))
+            }
+        };
+        res.push(value);
+    }
+    let array = ScalarValue::iter_to_array(res)?;
+    Ok(array)
+}
@@ -0,0 +1,19 @@
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+mod distance;
+mod dwell_time;
+mod geofence_events;
+mod max_speed;
+mod simplify;
+mod speed;
+
+pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> Result<()> {
+    distance::register_udf(func_manager)?;
+    speed::register_udf(func_manager)?;
+    max_speed::register_udf(func_manager)?;
+    dwell_time::register_udf(func_manager)?;
+    simplify::register_udf(func_manager)?;
+    geofence_events::register_udf(func_manager)?;
+    Ok(())
+}
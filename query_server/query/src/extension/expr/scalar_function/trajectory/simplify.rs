@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::as_float64_array;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_expr::functions::make_scalar_function;
+use datafusion::scalar::ScalarValue;
+use spi::query::function::FunctionMetadataManager;
+use spi::{QueryError, Result};
+
+use crate::extension::expr::aggregate_function::{AggResult, TrajectoryData};
+use crate::extension::expr::scalar_function::TRAJECTORY_SIMPLIFY;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|input| {
+        if !input[1].is_numeric() {
+            return Err(DataFusionError::External(Box::new(QueryError::Analyzer {
+                err: format!("Expect numeric type, but found {} type.", &input[1]),
+            })));
+        }
+        // check that the first argument is the result of trajectory_agg
+        TrajectoryData::try_from_scalar(ScalarValue::try_from(&input[0])?)?;
+        Ok(Arc::new(input[0].clone()))
+    });
+
+    let simplify = make_scalar_function(simplify_implement);
+
+    // trajectory_simplify(trajectory_agg, tolerance)
+    ScalarUDF::new(
+        TRAJECTORY_SIMPLIFY,
+        &Signature::any(2, Volatility::Immutable),
+        &return_type_fn,
+        &simplify,
+    )
+}
+
+fn simplify_implement(input: &[ArrayRef]) -> Result<ArrayRef, DataFusionError> {
+    let tolerances = cast(&input[1], &DataType::Float64)?;
+    let tolerances = as_float64_array(tolerances.as_ref())?;
+    let mut res = Vec::with_capacity(tolerances.len());
+    for (i, tolerance) in tolerances.iter().enumerate() {
+        let value = match tolerance {
+            Some(tolerance) => {
+                let trajectory = ScalarValue::try_from_array(input[0].as_ref(), i)?;
+                let trajectory = TrajectoryData::try_from_scalar(trajectory)?;
+                trajectory.simplify(tolerance)?.to_scalar()?
+            }
+            None => ScalarValue::try_from(input[0].data_type())?,
+        };
+        res.push(value);
+    }
+    let array = ScalarValue::iter_to_array(res)?;
+    Ok(array)
+}
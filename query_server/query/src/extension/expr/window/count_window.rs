@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarUDF, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_expr::functions::make_scalar_function;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use super::session_window::event_window_return_type;
+use super::COUNT_WINDOW;
+use crate::extension::expr::INTEGERS;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn signature() -> Signature {
+    // count_window
+    // - windowSize
+    //
+    // group by count_window(100), tag
+    let type_signatures = INTEGERS
+        .iter()
+        .map(|t| TypeSignature::Exact(vec![t.clone()]))
+        .collect();
+
+    Signature::one_of(type_signatures, Volatility::Immutable)
+}
+
+fn new() -> ScalarUDF {
+    let func = |_: &[ArrayRef]| {
+        Err(DataFusionError::Execution(format!(
+            "{} has no specific implementation, should be converted to EventWindow operator.",
+            COUNT_WINDOW
+        )))
+    };
+    let func = make_scalar_function(func);
+
+    // Struct(_start, _end)
+    let return_type: ReturnTypeFunction =
+        Arc::new(move |_| Ok(Arc::new(event_window_return_type())));
+
+    ScalarUDF::new(COUNT_WINDOW, &signature(), &return_type, &func)
+}
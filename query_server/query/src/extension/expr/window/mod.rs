@@ -1,3 +1,5 @@
+mod count_window;
+mod session_window;
 mod time_window;
 
 use spi::query::function::FunctionMetadataManager;
@@ -8,14 +10,19 @@ pub fn register_window_udfs(func_manager: &mut dyn FunctionMetadataManager) -> R
     // eg.
     //   example::register_udf(func_manager)?;
     time_window::register_udf(func_manager)?;
+    session_window::register_udf(func_manager)?;
+    count_window::register_udf(func_manager)?;
     Ok(())
 }
 
 pub const TIME_WINDOW: &str = "TIME_WINDOW";
+pub const SESSION_WINDOW: &str = "SESSION_WINDOW";
+pub const COUNT_WINDOW: &str = "COUNT_WINDOW";
 pub const WINDOW_COL_NAME: &str = "_window";
 pub const WINDOW_START: &str = "start";
 pub const WINDOW_END: &str = "end";
 
+pub use session_window::event_window_return_type;
 pub use time_window::{
     ceil_sliding_window, floor_sliding_window, signature as time_window_signature,
     DEFAULT_TIME_WINDOW_START, TIME_WINDOW_UDF,
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::{DataType, Field, Fields, TimeUnit};
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::type_coercion::aggregates::TIMESTAMPS;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarUDF, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_expr::functions::make_scalar_function;
+use spi::query::function::FunctionMetadataManager;
+use spi::Result;
+
+use super::{SESSION_WINDOW, WINDOW_END, WINDOW_START};
+use crate::extension::expr::INTERVALS;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> Result<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn signature() -> Signature {
+    // session_window
+    // - timeColumn
+    // - gapDuration
+    //
+    // group by session_window(time, interval '10 second'), tag
+    let type_signatures = TIMESTAMPS
+        .iter()
+        .flat_map(|first| {
+            INTERVALS
+                .iter()
+                .map(|second| TypeSignature::Exact(vec![first.clone(), second.clone()]))
+        })
+        .collect();
+
+    Signature::one_of(type_signatures, Volatility::Immutable)
+}
+
+fn new() -> ScalarUDF {
+    let func = |_: &[ArrayRef]| {
+        Err(DataFusionError::Execution(format!(
+            "{} has no specific implementation, should be converted to EventWindow operator.",
+            SESSION_WINDOW
+        )))
+    };
+    let func = make_scalar_function(func);
+
+    // Struct(_start, _end)
+    let return_type: ReturnTypeFunction =
+        Arc::new(move |_| Ok(Arc::new(event_window_return_type())));
+
+    ScalarUDF::new(SESSION_WINDOW, &signature(), &return_type, &func)
+}
+
+/// The window column produced by data-driven windows(session, count),
+/// the bounds are always nanosecond timestamps
+pub fn event_window_return_type() -> DataType {
+    let ns_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+    DataType::Struct(Fields::from(vec![
+        Field::new(WINDOW_START, ns_type.clone(), false),
+        Field::new(WINDOW_END, ns_type, false),
+    ]))
+}
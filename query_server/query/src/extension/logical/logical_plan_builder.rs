@@ -13,6 +13,7 @@ use spi::query::logical_planner::{affected_row_expr, merge_affected_row_expr};
 use spi::QueryError;
 use trace::debug;
 
+use super::plan_node::event_window::{EventWindowNode, EventWindowType};
 use super::plan_node::expand::ExpandNode;
 use super::plan_node::stream_scan::StreamScanPlanNode;
 use super::plan_node::table_writer_merge::TableWriterMergePlanNode;
@@ -23,6 +24,13 @@ use crate::extension::logical::plan_node::table_writer::TableWriterPlanNode;
 pub trait LogicalPlanBuilderExt: Sized {
     /// Apply a expand with specific projections
     fn expand(self, projections: Vec<Vec<Expr>>) -> Result<Self>;
+    /// Assign each row to a session/count window
+    fn event_window(
+        self,
+        window_type: EventWindowType,
+        time_column: Expr,
+        partition_by: Vec<Expr>,
+    ) -> Result<Self>;
     fn watermark(self, watermark: Watermark) -> Result<Self>;
     fn stream_scan(
         table_name: impl Into<OwnedTableReference>,
@@ -47,6 +55,26 @@ impl LogicalPlanBuilderExt for LogicalPlanBuilder {
         Ok(Self::from(plan))
     }
 
+    fn event_window(
+        self,
+        window_type: EventWindowType,
+        time_column: Expr,
+        partition_by: Vec<Expr>,
+    ) -> Result<Self> {
+        let input = Arc::new(self.build()?);
+
+        let event_window = Arc::new(EventWindowNode::try_new(
+            window_type,
+            time_column,
+            partition_by,
+            input,
+        )?);
+
+        let plan = LogicalPlan::Extension(Extension { node: event_window });
+
+        Ok(Self::from(plan))
+    }
+
     fn watermark(self, watermark: Watermark) -> Result<Self> {
         let input = Arc::new(self.build()?);
 
@@ -1,2 +1,3 @@
 pub mod reject_cross_join;
+pub mod rewrite_last_value_scan;
 pub mod rewrite_tag_scan;
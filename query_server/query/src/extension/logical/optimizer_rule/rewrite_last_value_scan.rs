@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use datafusion::datasource::{provider_as_source, source_as_provider};
+use datafusion::error::Result;
+use datafusion::logical_expr::expr::{AggregateFunction, AggregateUDF, Sort as SortExpr};
+use datafusion::logical_expr::{aggregate_function, Aggregate, Limit, LogicalPlan, Sort};
+use datafusion::optimizer::optimizer::ApplyOrder;
+use datafusion::optimizer::{OptimizerConfig, OptimizerRule};
+use datafusion::prelude::Expr;
+use models::predicate::domain::LastValueScan;
+use models::schema::TskvTableSchema;
+
+use crate::data_source::batch::tskv::ClusterTable;
+use crate::extension::expr::aggregate_function::LAST_UDAF_NAME;
+
+/// Read the latest points of the series from the last value caches of the vnodes,
+/// the plan above the table scan is kept, so the result is the same as scanning the data.
+///
+/// Triggering conditions:
+/// 1. The table is created with `LAST_VALUE_CACHE = true`
+/// 2. `ORDER BY time DESC LIMIT 1`, or an aggregation of `last(time, ..)`/`max(time)`
+///    grouped by tags only
+/// 3. There is no filter on the fields, which is checked when the table is scanned
+pub struct RewriteLastValueScan {}
+
+impl OptimizerRule for RewriteLastValueScan {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _optimizer_config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Limit(Limit {
+                skip: 0,
+                fetch: Some(1),
+                input,
+            }) => match input.as_ref() {
+                LogicalPlan::Sort(sort) => match rewrite_latest_row_sort(sort)? {
+                    Some(new_sort) => Ok(Some(plan.with_new_inputs(&[new_sort])?)),
+                    None => Ok(None),
+                },
+                _ => Ok(None),
+            },
+            LogicalPlan::Sort(sort) if sort.fetch == Some(1) => rewrite_latest_row_sort(sort),
+            LogicalPlan::Aggregate(aggregate) => rewrite_last_aggregate(aggregate, plan),
+            _ => Ok(None),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "rewrite_last_value_scan"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+/// `ORDER BY time DESC LIMIT 1` only needs the latest row of each series
+fn rewrite_latest_row_sort(sort: &Sort) -> Result<Option<LogicalPlan>> {
+    let Some(Expr::Sort(SortExpr {
+        expr, asc: false, ..
+    })) = sort.expr.first()
+    else {
+        return Ok(None);
+    };
+    let Expr::Column(column) = expr.as_ref() else {
+        return Ok(None);
+    };
+
+    let is_time =
+        |schema: &TskvTableSchema, columns: &[String]| columns[0] == schema.time_column().name;
+    match rewrite_scan(
+        &sort.input,
+        LastValueScan::Row,
+        vec![column.name.clone()],
+        &is_time,
+    )? {
+        Some(new_input) => Ok(Some(LogicalPlan::Sort(Sort {
+            input: Arc::new(new_input),
+            ..sort.clone()
+        }))),
+        None => Ok(None),
+    }
+}
+
+/// `last(time, ..)` and `max(time)` grouped by tags only need the last value of each field
+/// of each series
+fn rewrite_last_aggregate(
+    aggregate: &Aggregate,
+    plan: &LogicalPlan,
+) -> Result<Option<LogicalPlan>> {
+    if aggregate.aggr_expr.is_empty() {
+        return Ok(None);
+    }
+
+    let mut group_columns = Vec::with_capacity(aggregate.group_expr.len());
+    for expr in aggregate.group_expr.iter() {
+        match expr {
+            Expr::Column(c) => group_columns.push(c.name.clone()),
+            _ => return Ok(None),
+        }
+    }
+
+    // The first column of each aggregate must be the time column
+    let mut time_columns = Vec::with_capacity(aggregate.aggr_expr.len());
+    for expr in aggregate.aggr_expr.iter() {
+        let expr = match expr {
+            Expr::Alias(inner, _) => inner.as_ref(),
+            e => e,
+        };
+        let time = match expr {
+            Expr::AggregateUDF(AggregateUDF {
+                fun,
+                args,
+                filter: None,
+                order_by: None,
+            }) if fun.name == LAST_UDAF_NAME => match args.as_slice() {
+                [Expr::Column(time), Expr::Column(_)] => time,
+                _ => return Ok(None),
+            },
+            Expr::AggregateFunction(AggregateFunction {
+                fun: aggregate_function::AggregateFunction::Max,
+                args,
+                distinct: false,
+                filter: None,
+                order_by: None,
+            }) => match args.as_slice() {
+                [Expr::Column(time)] => time,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        time_columns.push(time.name.clone());
+    }
+
+    let num_groups = group_columns.len();
+    let mut columns = group_columns;
+    columns.append(&mut time_columns);
+    let is_applicable = |schema: &TskvTableSchema, columns: &[String]| {
+        let (groups, times) = columns.split_at(num_groups);
+        groups.iter().all(|c| {
+            schema
+                .column(c)
+                .map(|c| c.column_type.is_tag())
+                .unwrap_or_default()
+        }) && times.iter().all(|c| *c == schema.time_column().name)
+    };
+
+    match rewrite_scan(
+        &aggregate.input,
+        LastValueScan::Fields,
+        columns,
+        &is_applicable,
+    )? {
+        Some(new_input) => Ok(Some(plan.with_new_inputs(&[new_input])?)),
+        None => Ok(None),
+    }
+}
+
+/// Rewrite the table scan under the projections to read from the last value caches,
+/// if the `columns` referenced above resolve to the columns of the table which
+/// satisfy `is_applicable`.
+fn rewrite_scan(
+    plan: &LogicalPlan,
+    scan: LastValueScan,
+    columns: Vec<String>,
+    is_applicable: &dyn Fn(&TskvTableSchema, &[String]) -> bool,
+) -> Result<Option<LogicalPlan>> {
+    match plan {
+        LogicalPlan::TableScan(table_scan) => {
+            let Some(table) = source_as_provider(&table_scan.source)?
+                .as_any()
+                .downcast_ref::<ClusterTable>()
+                .cloned()
+            else {
+                return Ok(None);
+            };
+            let schema = table.table_schema();
+            if !schema.options().last_value_cache
+                || table.last_value().is_some()
+                || table.prior_point().is_some()
+                || table_scan.fetch.is_some()
+                || !is_applicable(&schema, &columns)
+            {
+                return Ok(None);
+            }
+
+            let mut table_scan = table_scan.clone();
+            table_scan.source = provider_as_source(Arc::new(table.with_last_value(scan)));
+            Ok(Some(LogicalPlan::TableScan(table_scan)))
+        }
+        LogicalPlan::Projection(projection) => {
+            // Only the columns passed through by the projection are resolved
+            let mut input_columns = Vec::with_capacity(columns.len());
+            for name in columns {
+                let input_column = projection.expr.iter().find_map(|e| match e {
+                    Expr::Column(c) if c.name == name => Some(c.name.clone()),
+                    Expr::Alias(inner, alias) if *alias == name => match inner.as_ref() {
+                        Expr::Column(c) => Some(c.name.clone()),
+                        _ => None,
+                    },
+                    _ => None,
+                });
+                match input_column {
+                    Some(c) => input_columns.push(c),
+                    None => return Ok(None),
+                }
+            }
+            match rewrite_scan(&projection.input, scan, input_columns, is_applicable)? {
+                Some(new_input) => Ok(Some(plan.with_new_inputs(&[new_input])?)),
+                None => Ok(None),
+            }
+        }
+        LogicalPlan::SubqueryAlias(alias) => {
+            match rewrite_scan(&alias.input, scan, columns, is_applicable)? {
+                Some(new_input) => Ok(Some(plan.with_new_inputs(&[new_input])?)),
+                None => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use datafusion::common::DFSchemaRef;
+use datafusion::logical_expr::{LogicalPlan, Operator, UserDefinedLogicalNodeCore};
+use datafusion::prelude::Expr;
+
+/// The comparison between the time of the left row and the time of the right row
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AsofMatchOp {
+    /// The latest right row strictly before the left row
+    Gt,
+    /// The latest right row before or at the left row
+    GtEq,
+    /// The earliest right row strictly after the left row
+    Lt,
+    /// The earliest right row after or at the left row
+    LtEq,
+}
+
+impl AsofMatchOp {
+    pub fn try_from_operator(op: Operator) -> Option<Self> {
+        match op {
+            Operator::Gt => Some(Self::Gt),
+            Operator::GtEq => Some(Self::GtEq),
+            Operator::Lt => Some(Self::Lt),
+            Operator::LtEq => Some(Self::LtEq),
+            _ => None,
+        }
+    }
+
+    /// The operator after swapping the operands
+    pub fn swap(self) -> Self {
+        match self {
+            Self::Gt => Self::Lt,
+            Self::GtEq => Self::LtEq,
+            Self::Lt => Self::Gt,
+            Self::LtEq => Self::GtEq,
+        }
+    }
+}
+
+impl fmt::Display for AsofMatchOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gt => write!(f, ">"),
+            Self::GtEq => write!(f, ">="),
+            Self::Lt => write!(f, "<"),
+            Self::LtEq => write!(f, "<="),
+        }
+    }
+}
+
+/// Join each row of the left input with the closest row of the right input
+/// that has the same join keys and satisfies `left_time op right_time`.
+///
+/// Left rows without a match are kept, the right columns of them are null.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AsofJoinNode {
+    pub left: Arc<LogicalPlan>,
+    pub right: Arc<LogicalPlan>,
+    /// Equijoin clause expressed as pairs of (left, right) join expressions
+    pub on: Vec<(Expr, Expr)>,
+    pub left_time: Expr,
+    pub right_time: Expr,
+    pub op: AsofMatchOp,
+    /// The schema description of the output, left columns followed by right columns
+    pub schema: DFSchemaRef,
+}
+
+impl Debug for AsofJoinNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNodeCore for AsofJoinNode {
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        let mut exprs = Vec::with_capacity(self.on.len() * 2 + 2);
+        for (l, r) in &self.on {
+            exprs.push(l.clone());
+            exprs.push(r.clone());
+        }
+        exprs.push(self.left_time.clone());
+        exprs.push(self.right_time.clone());
+        exprs
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let on = self
+            .on
+            .iter()
+            .map(|(l, r)| format!("{l} = {r}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(
+            f,
+            "AsofJoin: on=[{on}], match_condition={} {} {}",
+            self.left_time, self.op, self.right_time,
+        )
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert_eq!(inputs.len(), 2, "input size inconsistent");
+        assert_eq!(
+            exprs.len(),
+            self.on.len() * 2 + 2,
+            "expression size inconsistent"
+        );
+
+        let on = exprs[..self.on.len() * 2]
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect();
+
+        Self {
+            left: Arc::new(inputs[0].clone()),
+            right: Arc::new(inputs[1].clone()),
+            on,
+            left_time: exprs[exprs.len() - 2].clone(),
+            right_time: exprs[exprs.len() - 1].clone(),
+            op: self.op,
+            schema: self.schema.clone(),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "AsofJoin"
+    }
+}
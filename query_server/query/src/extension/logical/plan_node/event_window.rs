@@ -0,0 +1,133 @@
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use std::time::Duration;
+
+use datafusion::common::{DFField, DFSchema, DFSchemaRef};
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNodeCore};
+use datafusion::prelude::Expr;
+
+use crate::extension::expr::{event_window_return_type, WINDOW_COL_NAME};
+
+/// Windows whose bounds are decided by the events themselves instead of the wall clock
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EventWindowType {
+    /// A window is closed when no event arrives within `gap` after the last event
+    Session { gap: Duration },
+    /// A window is closed every `size` events
+    Count { size: u64 },
+}
+
+impl fmt::Display for EventWindowType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Session { gap } => write!(f, "session(gap={}ns)", gap.as_nanos()),
+            Self::Count { size } => write!(f, "count(size={size})"),
+        }
+    }
+}
+
+/// Assign each row of the input to a session/count window.
+///
+/// Rows are grouped by `partition_by`, ordered by `time_column`,
+/// and a struct column [`WINDOW_COL_NAME`] (start, end) is prepended to the input columns.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct EventWindowNode {
+    pub window_type: EventWindowType,
+    pub time_column: Expr,
+    pub partition_by: Vec<Expr>,
+    /// The incoming logical plan
+    pub input: Arc<LogicalPlan>,
+    /// The schema description of the output
+    pub schema: DFSchemaRef,
+}
+
+impl EventWindowNode {
+    /// Create a new EventWindowNode
+    pub fn try_new(
+        window_type: EventWindowType,
+        time_column: Expr,
+        partition_by: Vec<Expr>,
+        input: Arc<LogicalPlan>,
+    ) -> Result<Self, DataFusionError> {
+        let input_schema = input.schema();
+
+        let window_field =
+            DFField::new_unqualified(WINDOW_COL_NAME, event_window_return_type(), false);
+        let mut fields = Vec::with_capacity(input_schema.fields().len() + 1);
+        fields.push(window_field);
+        fields.extend(input_schema.fields().iter().cloned());
+
+        let schema = Arc::new(DFSchema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        )?);
+
+        Ok(Self {
+            window_type,
+            time_column,
+            partition_by,
+            input,
+            schema,
+        })
+    }
+}
+
+impl Debug for EventWindowNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNodeCore for EventWindowNode {
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![self.input.as_ref()]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        let mut exprs = Vec::with_capacity(self.partition_by.len() + 1);
+        exprs.push(self.time_column.clone());
+        exprs.extend(self.partition_by.iter().cloned());
+        exprs
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let partition_by = self
+            .partition_by
+            .iter()
+            .map(|e| format!("{e}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(
+            f,
+            "EventWindow: window={}, time_column={}, partition_by=[{partition_by}]",
+            self.window_type, self.time_column,
+        )
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert_eq!(inputs.len(), 1, "input size inconsistent");
+        assert_eq!(
+            exprs.len(),
+            self.partition_by.len() + 1,
+            "expression size inconsistent"
+        );
+
+        Self {
+            window_type: self.window_type.clone(),
+            time_column: exprs[0].clone(),
+            partition_by: exprs[1..].to_vec(),
+            input: Arc::new(inputs[0].clone()),
+            schema: self.schema.clone(),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "EventWindow"
+    }
+}
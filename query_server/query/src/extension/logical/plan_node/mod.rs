@@ -6,6 +6,8 @@ use datafusion::prelude::Expr;
 
 use crate::extension::expr::expr_rewriter::ExprReplacer;
 
+pub mod asof_join;
+pub mod event_window;
 pub mod expand;
 pub mod gapfill;
 pub mod stream_scan;
@@ -8,6 +8,9 @@ use datafusion::physical_optimizer::PhysicalOptimizerRule;
 use datafusion::physical_plan::aggregates::{AggregateExec, AggregateMode};
 use datafusion::physical_plan::{with_new_children_if_necessary, ExecutionPlan};
 
+use crate::extension::physical::plan_node::event_window::{
+    EventWindowExec, StatefulEventWindowExec,
+};
 use crate::extension::physical::plan_node::state_restore::StateRestoreExec;
 use crate::extension::physical::plan_node::state_save::StateSaveExec;
 use crate::extension::utils::downcast_execution_plan;
@@ -40,6 +43,23 @@ where
         _config: &ConfigOptions,
     ) -> DFResult<Arc<dyn ExecutionPlan>> {
         plan.transform_up(&|plan| {
+            if let Some(event_window_exec) =
+                downcast_execution_plan::<EventWindowExec>(plan.as_ref())
+            {
+                // The rows of the windows which are not closed yet
+                // are retained in the state store until the next micro batch
+                let stateful_exec = Arc::new(StatefulEventWindowExec::try_new(
+                    self.watermark_ns,
+                    self.state_store_factory.clone(),
+                    event_window_exec.window_type().clone(),
+                    event_window_exec.time_expr().clone(),
+                    event_window_exec.partition_by().to_vec(),
+                    event_window_exec.input().clone(),
+                )?);
+
+                return Ok(Transformed::Yes(stateful_exec));
+            }
+
             if let Some(aggregate_exec) = downcast_execution_plan::<AggregateExec>(plan.as_ref()) {
                 match aggregate_exec.mode() {
                     AggregateMode::Final | AggregateMode::FinalPartitioned => {
@@ -2,3 +2,4 @@
 pub mod add_assert;
 pub mod add_state_store;
 pub mod add_traced_proxy;
+pub mod spillable_operators;
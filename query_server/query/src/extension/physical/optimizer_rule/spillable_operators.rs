@@ -0,0 +1,301 @@
+use std::sync::Arc;
+
+use datafusion::arrow::compute::SortOptions;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::Result as DFResult;
+use datafusion::config::ConfigOptions;
+use datafusion::logical_expr::JoinType;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::aggregates::{AggregateExec, AggregateMode};
+use datafusion::physical_plan::joins::{HashJoinExec, SortMergeJoinExec};
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::ExecutionPlan;
+use spi::query::session::SqlExecInfo;
+
+use crate::extension::utils::downcast_execution_plan;
+
+/// Replaces the hash joins with sort merge joins, the sorts of the inputs spill and the
+/// join only buffers the rows of the current join key. Only enabled by
+/// `sql_exec_info.spill_hash_operators`, the hash joins are faster without memory pressure.
+///
+/// Runs before `EnforceDistribution` and `EnforceSorting`, which add the repartitions and
+/// the sorts required by the sort merge joins. The joins with a filter or a join type which
+/// is not supported by the sort merge join are kept.
+#[derive(Default)]
+pub struct SpillableJoin {}
+
+impl SpillableJoin {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for SpillableJoin {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if !spill_enabled(config) {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| {
+            let Some(join) = downcast_execution_plan::<HashJoinExec>(plan.as_ref()) else {
+                return Ok(Transformed::No(plan));
+            };
+            let supported = matches!(
+                join.join_type(),
+                JoinType::Inner
+                    | JoinType::Left
+                    | JoinType::Right
+                    | JoinType::Full
+                    | JoinType::LeftSemi
+                    | JoinType::LeftAnti
+            );
+            if !supported || join.filter().is_some() || is_unbounded(plan.as_ref())? {
+                return Ok(Transformed::No(plan));
+            }
+
+            let sort_merge_join = SortMergeJoinExec::try_new(
+                join.left().clone(),
+                join.right().clone(),
+                join.on().to_vec(),
+                *join.join_type(),
+                vec![SortOptions::default(); join.on().len()],
+                join.null_equals_null(),
+            )?;
+
+            Ok(Transformed::Yes(Arc::new(sort_merge_join)))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "spillable_join"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Sorts the inputs of the final grouped aggregates by the group keys, the sorts spill and
+/// the aggregates on the sorted inputs emit the groups as soon as they are complete. Only
+/// enabled by `sql_exec_info.spill_hash_operators`.
+///
+/// Runs after `EnforceSorting`, so the sorts are not removed or moved by it. The partial
+/// aggregates are kept, so the rows are only sorted once below the final stage, the partial
+/// and the final aggregates without a repartition between them are already combined into a
+/// single aggregate by `CombinePartialFinalAggregate`. The aggregates of grouping sets are kept.
+#[derive(Default)]
+pub struct SpillableAggregate {}
+
+impl SpillableAggregate {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for SpillableAggregate {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if !spill_enabled(config) {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| {
+            let Some(aggregate) = downcast_execution_plan::<AggregateExec>(plan.as_ref()) else {
+                return Ok(Transformed::No(plan));
+            };
+            let group_by = aggregate.group_expr();
+            if *aggregate.mode() == AggregateMode::Partial
+                || group_by.expr().is_empty()
+                || !group_by.null_expr().is_empty()
+                || is_unbounded(plan.as_ref())?
+            {
+                return Ok(Transformed::No(plan));
+            }
+
+            let sort_exprs = group_by
+                .expr()
+                .iter()
+                .map(|(expr, _)| PhysicalSortExpr {
+                    expr: expr.clone(),
+                    options: SortOptions::default(),
+                })
+                .collect::<Vec<_>>();
+            let sorted_input = Arc::new(
+                SortExec::new(sort_exprs, aggregate.input().clone())
+                    .with_preserve_partitioning(true),
+            );
+            let sorted_aggregate = AggregateExec::try_new(
+                *aggregate.mode(),
+                group_by.clone(),
+                aggregate.aggr_expr().to_vec(),
+                aggregate.filter_expr().to_vec(),
+                aggregate.order_by_expr().to_vec(),
+                sorted_input,
+                aggregate.input_schema(),
+            )?;
+
+            Ok(Transformed::Yes(Arc::new(sorted_aggregate)))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "spillable_aggregate"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+fn spill_enabled(config: &ConfigOptions) -> bool {
+    config
+        .extensions
+        .get::<SqlExecInfo>()
+        .map(|info| info.spill_hash_operators)
+        .unwrap_or_default()
+}
+
+/// The inputs of the stream queries are never sorted as a whole.
+fn is_unbounded(plan: &dyn ExecutionPlan) -> DFResult<bool> {
+    let children = plan
+        .children()
+        .iter()
+        .map(|child| is_unbounded(child.as_ref()))
+        .collect::<DFResult<Vec<_>>>()?;
+    plan.unbounded_output(&children)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion::config::ConfigOptions;
+    use datafusion::logical_expr::JoinType;
+    use datafusion::physical_expr::expressions::{col, Column, Count};
+    use datafusion::physical_optimizer::PhysicalOptimizerRule;
+    use datafusion::physical_plan::aggregates::{AggregateExec, AggregateMode, PhysicalGroupBy};
+    use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode, SortMergeJoinExec};
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::sorts::sort::SortExec;
+    use datafusion::physical_plan::ExecutionPlan;
+    use spi::query::session::SqlExecInfo;
+
+    use super::{SpillableAggregate, SpillableJoin};
+    use crate::extension::utils::downcast_execution_plan;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]))
+    }
+
+    fn input() -> Arc<dyn ExecutionPlan> {
+        Arc::new(MemoryExec::try_new(&[vec![]], schema(), None).unwrap())
+    }
+
+    fn config(spill_hash_operators: bool) -> ConfigOptions {
+        let mut config = ConfigOptions::new();
+        config.extensions.insert(SqlExecInfo {
+            spill_hash_operators,
+            ..Default::default()
+        });
+        config
+    }
+
+    fn hash_join(join_type: JoinType) -> Arc<dyn ExecutionPlan> {
+        let schema = schema();
+        let on = vec![(
+            Column::new_with_schema("a", &schema).unwrap(),
+            Column::new_with_schema("a", &schema).unwrap(),
+        )];
+        Arc::new(
+            HashJoinExec::try_new(
+                input(),
+                input(),
+                on,
+                None,
+                &join_type,
+                PartitionMode::CollectLeft,
+                false,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn hash_aggregate(mode: AggregateMode) -> Arc<dyn ExecutionPlan> {
+        let schema = schema();
+        let group_by = PhysicalGroupBy::new_single(vec![(col("a", &schema).unwrap(), "a".into())]);
+        let count = Arc::new(Count::new(
+            col("b", &schema).unwrap(),
+            "count",
+            DataType::Int64,
+        ));
+        Arc::new(
+            AggregateExec::try_new(
+                mode,
+                group_by,
+                vec![count],
+                vec![None],
+                vec![None],
+                input(),
+                schema,
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_spillable_join() {
+        let rule = SpillableJoin::new();
+
+        let plan = rule
+            .optimize(hash_join(JoinType::Inner), &config(true))
+            .unwrap();
+        assert!(downcast_execution_plan::<SortMergeJoinExec>(plan.as_ref()).is_some());
+
+        // the hash join is kept by default
+        let plan = rule
+            .optimize(hash_join(JoinType::Inner), &config(false))
+            .unwrap();
+        assert!(downcast_execution_plan::<HashJoinExec>(plan.as_ref()).is_some());
+
+        // not supported by the sort merge join
+        let plan = rule
+            .optimize(hash_join(JoinType::RightSemi), &config(true))
+            .unwrap();
+        assert!(downcast_execution_plan::<HashJoinExec>(plan.as_ref()).is_some());
+    }
+
+    #[test]
+    fn test_spillable_aggregate() {
+        let rule = SpillableAggregate::new();
+
+        for mode in [AggregateMode::Single, AggregateMode::FinalPartitioned] {
+            let plan = rule.optimize(hash_aggregate(mode), &config(true)).unwrap();
+            assert!(downcast_execution_plan::<AggregateExec>(plan.as_ref()).is_some());
+            assert!(downcast_execution_plan::<SortExec>(plan.children()[0].as_ref()).is_some());
+        }
+
+        // the partial aggregate is not sorted
+        let plan = rule
+            .optimize(hash_aggregate(AggregateMode::Partial), &config(true))
+            .unwrap();
+        assert!(downcast_execution_plan::<MemoryExec>(plan.children()[0].as_ref()).is_some());
+
+        // the hash aggregate is kept by default
+        let plan = rule
+            .optimize(hash_aggregate(AggregateMode::Single), &config(false))
+            .unwrap();
+        assert!(downcast_execution_plan::<MemoryExec>(plan.children()[0].as_ref()).is_some());
+    }
+}
@@ -0,0 +1,566 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{new_null_array, Array, ArrayRef, Int64Array, UInt32Builder};
+use datafusion::arrow::compute::kernels::take::take;
+use datafusion::arrow::compute::{cast, concat_batches};
+use datafusion::arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::row::{RowConverter, Rows, SortField};
+use datafusion::common::cast::as_int64_array;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalExpr,
+    SendableRecordBatchStream, Statistics,
+};
+use futures::{StreamExt, TryStreamExt};
+use parking_lot::Mutex;
+use tokio::sync::OnceCell;
+use trace::debug;
+
+use crate::extension::logical::plan_node::asof_join::AsofMatchOp;
+
+type JoinOn = Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>;
+
+/// Execution plan for a AsofJoin
+///
+/// The right input is collected once and its rows are grouped by the join keys and sorted by time,
+/// then each batch of the left input is probed with a binary search on the time.
+/// The memory of the collected right input is reserved from the memory pool of the query.
+/// Series read from tskv are already ordered by time, so the sort is cheap
+/// and no cross product of the inputs is ever built.
+#[derive(Debug)]
+pub struct AsofJoinExec {
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on: JoinOn,
+    left_time: Arc<dyn PhysicalExpr>,
+    right_time: Arc<dyn PhysicalExpr>,
+    op: AsofMatchOp,
+    /// Left columns followed by right columns
+    schema: SchemaRef,
+    /// Encodes the join keys of both inputs
+    key_converter: Arc<KeyConverter>,
+    /// The right input, shared by all partitions of the left input
+    build_side: Arc<OnceCell<Arc<BuildSide>>>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl AsofJoinExec {
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        left_time: Arc<dyn PhysicalExpr>,
+        right_time: Arc<dyn PhysicalExpr>,
+        op: AsofMatchOp,
+        schema: SchemaRef,
+    ) -> Result<Self> {
+        let num_columns = left.schema().fields().len() + right.schema().fields().len();
+        if schema.fields().len() != num_columns {
+            return Err(DataFusionError::Internal(format!(
+                "AsofJoinExec: expect {num_columns} output columns, but found {}",
+                schema.fields().len()
+            )));
+        }
+        let right_schema = right.schema();
+        let key_types = on
+            .iter()
+            .map(|(_, r)| r.data_type(&right_schema))
+            .collect::<Result<Vec<_>>>()?;
+        let key_converter = Arc::new(KeyConverter::try_new(key_types)?);
+
+        Ok(Self {
+            left,
+            right,
+            on,
+            left_time,
+            right_time,
+            op,
+            schema,
+            key_converter,
+            build_side: Arc::new(OnceCell::new()),
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+impl ExecutionPlan for AsofJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.left.output_partitioning()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![
+            Distribution::UnspecifiedDistribution,
+            Distribution::SinglePartition,
+        ]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        // the rows of the left input are emitted in order
+        self.left.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        assert_eq!(children.len(), 2);
+
+        Ok(Arc::new(Self::try_new(
+            children[0].clone(),
+            children[1].clone(),
+            self.on.clone(),
+            self.left_time.clone(),
+            self.right_time.clone(),
+            self.op,
+            self.schema.clone(),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!(
+            "Start AsofJoinExec::execute for partition {} of context session_id {} and task_id {:?}",
+            partition,
+            context.session_id(),
+            context.task_id()
+        );
+
+        let left = self.left.execute(partition, context.clone())?;
+        let right = self.right.clone();
+        let build_side = self.build_side.clone();
+        let right_on = self.on.iter().map(|(_, r)| r.clone()).collect::<Vec<_>>();
+        let right_time = self.right_time.clone();
+        let key_converter = self.key_converter.clone();
+
+        let prober = Prober {
+            left_on: self.on.iter().map(|(l, _)| l.clone()).collect(),
+            left_time: self.left_time.clone(),
+            key_converter: self.key_converter.clone(),
+            op: self.op,
+            schema: self.schema.clone(),
+        };
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+
+        let output = futures::stream::once(async move {
+            let build_side = build_side
+                .get_or_try_init(|| async move {
+                    let mut reservation = MemoryConsumer::new("AsofJoinExec[build_side]")
+                        .register(context.memory_pool());
+                    let mut input = right.execute(0, context)?;
+                    let input_schema = input.schema();
+                    let mut batches = vec![];
+                    while let Some(batch) = input.next().await {
+                        let batch = batch?;
+                        reservation.try_grow(batch.get_array_memory_size())?;
+                        batches.push(batch);
+                    }
+                    let batch = concat_batches(&input_schema, &batches)?;
+                    reservation.try_grow(batch.get_array_memory_size())?;
+                    drop(batches);
+                    reservation.resize(batch.get_array_memory_size());
+
+                    let build_side = BuildSide::try_new(
+                        batch,
+                        &right_on,
+                        &right_time,
+                        &key_converter,
+                        reservation,
+                    )?;
+                    Ok::<_, DataFusionError>(Arc::new(build_side))
+                })
+                .await?
+                .clone();
+
+            Ok::<_, DataFusionError>(left.map(move |batch| {
+                let _timer = baseline_metrics.elapsed_compute().timer();
+                let output = prober.probe(&batch?, &build_side)?;
+                baseline_metrics.record_output(output.num_rows());
+                Ok(output)
+            }))
+        })
+        .try_flatten();
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            output,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(l, r)| format!("({l}, {r})"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "AsofJoinExec: on=[{on}], match_condition={} {} {}",
+                    self.left_time, self.op, self.right_time,
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// The collected right input
+#[derive(Debug)]
+struct BuildSide {
+    batch: RecordBatch,
+    /// Row indices grouped by the encoded join keys, sorted by time
+    groups: HashMap<Vec<u8>, Vec<(i64, u32)>>,
+    /// The memory of the batch and the groups, released once the build side is dropped
+    _reservation: MemoryReservation,
+}
+
+impl BuildSide {
+    fn try_new(
+        batch: RecordBatch,
+        on: &[Arc<dyn PhysicalExpr>],
+        time_expr: &Arc<dyn PhysicalExpr>,
+        key_converter: &KeyConverter,
+        mut reservation: MemoryReservation,
+    ) -> Result<Self> {
+        let num_rows = batch.num_rows();
+        let keys = key_converter.convert(&batch, on)?;
+        let times = evaluate_time(&batch, time_expr)?;
+
+        let mut groups: HashMap<Vec<u8>, Vec<(i64, u32)>> = HashMap::new();
+        let mut groups_size = 0;
+        for idx in 0..num_rows {
+            if let (Some(key), Some(time)) = (keys.key(idx), time_at(&times, idx)) {
+                let rows = groups.entry(key.to_vec()).or_insert_with(|| {
+                    groups_size += key.len() + std::mem::size_of::<(Vec<u8>, Vec<(i64, u32)>)>();
+                    vec![]
+                });
+                rows.push((time, idx as u32));
+                groups_size += std::mem::size_of::<(i64, u32)>();
+            }
+        }
+        reservation.try_grow(groups_size)?;
+        for rows in groups.values_mut() {
+            // stable, rows with the same time keep the input order
+            rows.sort_by_key(|(time, _)| *time);
+        }
+
+        Ok(Self {
+            batch,
+            groups,
+            _reservation: reservation,
+        })
+    }
+
+    fn find(&self, key: &[u8], time: i64, op: AsofMatchOp) -> Option<u32> {
+        let rows = self.groups.get(key)?;
+        let idx = match op {
+            AsofMatchOp::GtEq => {
+                // the last row with right.time <= left.time
+                let pos = rows.partition_point(|(t, _)| *t <= time);
+                pos.checked_sub(1)?
+            }
+            AsofMatchOp::Gt => {
+                let pos = rows.partition_point(|(t, _)| *t < time);
+                pos.checked_sub(1)?
+            }
+            AsofMatchOp::LtEq => {
+                // the first row with right.time >= left.time
+                rows.partition_point(|(t, _)| *t < time)
+            }
+            AsofMatchOp::Lt => rows.partition_point(|(t, _)| *t <= time),
+        };
+        rows.get(idx).map(|(_, row)| *row)
+    }
+}
+
+#[derive(Debug)]
+struct Prober {
+    left_on: Vec<Arc<dyn PhysicalExpr>>,
+    left_time: Arc<dyn PhysicalExpr>,
+    key_converter: Arc<KeyConverter>,
+    op: AsofMatchOp,
+    schema: SchemaRef,
+}
+
+impl Prober {
+    fn probe(&self, batch: &RecordBatch, build_side: &BuildSide) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let keys = self.key_converter.convert(batch, &self.left_on)?;
+        let times = evaluate_time(batch, &self.left_time)?;
+
+        let mut indices = UInt32Builder::with_capacity(num_rows);
+        for idx in 0..num_rows {
+            let matched = match (keys.key(idx), time_at(&times, idx)) {
+                (Some(key), Some(time)) => build_side.find(key, time, self.op),
+                _ => None,
+            };
+            indices.append_option(matched);
+        }
+        let indices = indices.finish();
+
+        let mut columns = batch.columns().to_vec();
+        for column in build_side.batch.columns() {
+            let column = if build_side.batch.num_rows() == 0 {
+                new_null_array(column.data_type(), num_rows)
+            } else {
+                take(column.as_ref(), &indices, None)?
+            };
+            columns.push(column);
+        }
+
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+/// The join keys of each row, encoded so that equal keys have equal bytes
+struct JoinKeys {
+    rows: Option<Rows>,
+    arrays: Vec<ArrayRef>,
+}
+
+impl JoinKeys {
+    /// None if any of the keys is null, null never equals to null
+    fn key(&self, idx: usize) -> Option<&[u8]> {
+        if self.arrays.iter().any(|a| a.is_null(idx)) {
+            return None;
+        }
+        match &self.rows {
+            Some(rows) => Some(rows.row(idx).as_ref()),
+            None => Some(&[]),
+        }
+    }
+}
+
+/// Encodes the join keys of both inputs with one converter, the encoding of a converter
+/// is only comparable with the rows encoded by itself.
+#[derive(Debug)]
+struct KeyConverter {
+    /// The data types of the right join keys, the left join keys are cast to them
+    data_types: Vec<DataType>,
+    converter: Option<Mutex<RowConverter>>,
+}
+
+impl KeyConverter {
+    fn try_new(data_types: Vec<DataType>) -> Result<Self> {
+        let converter = if data_types.is_empty() {
+            None
+        } else {
+            let sort_fields = data_types
+                .iter()
+                .map(|t| SortField::new(t.clone()))
+                .collect();
+            Some(Mutex::new(RowConverter::new(sort_fields)?))
+        };
+        Ok(Self {
+            data_types,
+            converter,
+        })
+    }
+
+    fn convert(&self, batch: &RecordBatch, on: &[Arc<dyn PhysicalExpr>]) -> Result<JoinKeys> {
+        let num_rows = batch.num_rows();
+        let arrays = on
+            .iter()
+            .zip(self.data_types.iter())
+            .map(|(e, data_type)| {
+                let array = e.evaluate(batch)?.into_array(num_rows);
+                if array.data_type() == data_type {
+                    Ok(array)
+                } else {
+                    Ok(cast(&array, data_type)?)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let rows = match &self.converter {
+            Some(converter) => Some(converter.lock().convert_columns(&arrays)?),
+            None => None,
+        };
+
+        Ok(JoinKeys { rows, arrays })
+    }
+}
+
+/// Evaluate the time expression as nanoseconds
+fn evaluate_time(batch: &RecordBatch, time_expr: &Arc<dyn PhysicalExpr>) -> Result<Int64Array> {
+    let array = time_expr.evaluate(batch)?.into_array(batch.num_rows());
+    let array = cast(&array, &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+    let array = cast(&array, &DataType::Int64)?;
+    Ok(as_int64_array(&array)?.clone())
+}
+
+fn time_at(times: &Int64Array, idx: usize) -> Option<i64> {
+    times.is_valid(idx).then(|| times.value(idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Int64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::arrow::util::pretty::pretty_format_batches;
+    use datafusion::error::DataFusionError;
+    use datafusion::execution::memory_pool::GreedyMemoryPool;
+    use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use datafusion::physical_expr::expressions::Column;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::{collect, ExecutionPlan, PhysicalExpr};
+    use datafusion::prelude::{SessionConfig, SessionContext};
+
+    use super::AsofJoinExec;
+    use crate::extension::logical::plan_node::asof_join::AsofMatchOp;
+
+    fn make_input(prefix: &str, rows: Vec<(i64, &str, i64)>) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                format!("{prefix}_time"),
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new(format!("{prefix}_tag"), DataType::Utf8, true),
+            Field::new(format!("{prefix}_value"), DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from(
+                    rows.iter().map(|r| r.0).collect::<Vec<_>>(),
+                )),
+                Arc::new(StringArray::from(
+                    rows.iter().map(|r| r.1).collect::<Vec<_>>(),
+                )),
+                Arc::new(Int64Array::from(
+                    rows.iter().map(|r| r.2).collect::<Vec<_>>(),
+                )),
+            ],
+        )
+        .unwrap();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    fn asof_join_exec(op: AsofMatchOp) -> Arc<dyn ExecutionPlan> {
+        let left = make_input(
+            "l",
+            vec![(5, "a", 1), (10, "a", 2), (10, "b", 3), (1, "c", 4)],
+        );
+        let right = make_input("r", vec![(3, "a", 10), (10, "a", 20), (11, "b", 30)]);
+        let column =
+            |name: &str, idx: usize| -> Arc<dyn PhysicalExpr> { Arc::new(Column::new(name, idx)) };
+
+        let schema = Arc::new(Schema::new(
+            left.schema()
+                .fields()
+                .iter()
+                .chain(right.schema().fields().iter())
+                .map(|f| f.as_ref().clone().with_nullable(true))
+                .collect::<Vec<_>>(),
+        ));
+        Arc::new(
+            AsofJoinExec::try_new(
+                left,
+                right,
+                vec![(column("l_tag", 1), column("r_tag", 1))],
+                column("l_time", 0),
+                column("r_time", 0),
+                op,
+                schema,
+            )
+            .unwrap(),
+        )
+    }
+
+    async fn asof_join(op: AsofMatchOp) -> String {
+        let batches = collect(asof_join_exec(op), SessionContext::new().task_ctx())
+            .await
+            .unwrap();
+        pretty_format_batches(&batches).unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_asof_join_backward() {
+        let expected = vec![
+            "+-------------------------------+-------+---------+-------------------------------+-------+---------+",
+            "| l_time                        | l_tag | l_value | r_time                        | r_tag | r_value |",
+            "+-------------------------------+-------+---------+-------------------------------+-------+---------+",
+            "| 1970-01-01T00:00:00.000000005 | a     | 1       | 1970-01-01T00:00:00.000000003 | a     | 10      |",
+            "| 1970-01-01T00:00:00.000000010 | a     | 2       | 1970-01-01T00:00:00.000000010 | a     | 20      |",
+            "| 1970-01-01T00:00:00.000000010 | b     | 3       |                               |       |         |",
+            "| 1970-01-01T00:00:00.000000001 | c     | 4       |                               |       |         |",
+            "+-------------------------------+-------+---------+-------------------------------+-------+---------+",
+        ];
+        assert_eq!(asof_join(AsofMatchOp::GtEq).await, expected.join("\n"));
+    }
+
+    #[tokio::test]
+    async fn test_asof_join_forward() {
+        let expected = vec![
+            "+-------------------------------+-------+---------+-------------------------------+-------+---------+",
+            "| l_time                        | l_tag | l_value | r_time                        | r_tag | r_value |",
+            "+-------------------------------+-------+---------+-------------------------------+-------+---------+",
+            "| 1970-01-01T00:00:00.000000005 | a     | 1       | 1970-01-01T00:00:00.000000010 | a     | 20      |",
+            "| 1970-01-01T00:00:00.000000010 | a     | 2       |                               |       |         |",
+            "| 1970-01-01T00:00:00.000000010 | b     | 3       | 1970-01-01T00:00:00.000000011 | b     | 30      |",
+            "| 1970-01-01T00:00:00.000000001 | c     | 4       |                               |       |         |",
+            "+-------------------------------+-------+---------+-------------------------------+-------+---------+",
+        ];
+        assert_eq!(asof_join(AsofMatchOp::Lt).await, expected.join("\n"));
+    }
+
+    #[tokio::test]
+    async fn test_asof_join_memory_limit() {
+        let runtime = RuntimeEnv::new(
+            RuntimeConfig::new().with_memory_pool(Arc::new(GreedyMemoryPool::new(64))),
+        )
+        .unwrap();
+        let ctx = SessionContext::with_config_rt(SessionConfig::new(), Arc::new(runtime));
+
+        let err = collect(asof_join_exec(AsofMatchOp::GtEq), ctx.task_ctx())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, DataFusionError::ResourcesExhausted(_)),
+            "unexpected error: {err}"
+        );
+    }
+}
@@ -0,0 +1,875 @@
+use std::any::Any;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+use std::sync::Arc;
+
+use datafusion::arrow;
+use datafusion::arrow::array::{
+    Array, ArrayRef, BooleanArray, StructArray, TimestampNanosecondArray,
+};
+use datafusion::arrow::compute::kernels::take::take;
+use datafusion::arrow::compute::{
+    cast, concat_batches, filter_record_batch, SortColumn, SortOptions,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_expr::{PhysicalSortExpr, PhysicalSortRequirement};
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalExpr,
+    SendableRecordBatchStream, Statistics,
+};
+use futures::{StreamExt, TryStreamExt};
+use trace::debug;
+
+use crate::extension::expr::{event_window_return_type, WINDOW_COL_NAME};
+use crate::extension::logical::plan_node::event_window::EventWindowType;
+use crate::stream::state_store::{StateStore, StateStoreFactory};
+
+/// The operator id of the event window in the state store,
+/// the aggregation state saved by [`StateSaveExec`](super::state_save::StateSaveExec) uses 0
+const EVENT_WINDOW_OPERATOR_ID: usize = 1;
+
+/// Execution plan for a EventWindow
+///
+/// The input is hash partitioned by `partition_by` and sorted by (partition_by, time),
+/// so the windows are assigned batch by batch. The rows of a window are emitted once a later
+/// row shows it is closed, only the rows of the last window are carried to the next batch.
+#[derive(Debug)]
+pub struct EventWindowExec {
+    window_type: EventWindowType,
+    time_expr: Arc<dyn PhysicalExpr>,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    /// The schema once the window column has been prepended
+    schema: SchemaRef,
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl EventWindowExec {
+    pub fn try_new(
+        window_type: EventWindowType,
+        time_expr: Arc<dyn PhysicalExpr>,
+        partition_by: Vec<Arc<dyn PhysicalExpr>>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        let schema = make_window_schema(input.schema().as_ref());
+
+        Ok(Self {
+            window_type,
+            time_expr,
+            partition_by,
+            schema,
+            input,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    pub fn window_type(&self) -> &EventWindowType {
+        &self.window_type
+    }
+
+    pub fn time_expr(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.time_expr
+    }
+
+    pub fn partition_by(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.partition_by
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+}
+
+impl ExecutionPlan for EventWindowExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        // Windows of the same partition key must be seen by the same operator.
+        if self.partition_by.is_empty() {
+            vec![Distribution::SinglePartition]
+        } else {
+            vec![Distribution::HashPartitioned(self.partition_by.clone())]
+        }
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        let sort_exprs = self
+            .partition_by
+            .iter()
+            .chain(std::iter::once(&self.time_expr))
+            .map(|expr| PhysicalSortExpr {
+                expr: expr.clone(),
+                options: SortOptions::default(),
+            })
+            .collect::<Vec<_>>();
+        vec![Some(PhysicalSortRequirement::from_sort_exprs(&sort_exprs))]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        assert_eq!(children.len(), 1);
+
+        Ok(Arc::new(Self::try_new(
+            self.window_type.clone(),
+            self.time_expr.clone(),
+            self.partition_by.clone(),
+            children[0].clone(),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!(
+            "Start EventWindowExec::execute for partition {} of context session_id {} and task_id {:?}",
+            partition,
+            context.session_id(),
+            context.task_id()
+        );
+
+        let input = self.input.execute(partition, context)?;
+        let assigner = WindowAssigner {
+            window_type: self.window_type.clone(),
+            time_expr: self.time_expr.clone(),
+            partition_by: self.partition_by.clone(),
+            schema: self.schema.clone(),
+        };
+        let state = EventWindowState {
+            carry: Some(RecordBatch::new_empty(input.schema())),
+            input,
+            assigner,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        };
+
+        let output = futures::stream::try_unfold(state, |mut state| async move {
+            while let Some(carry) = state.carry.take() {
+                let (windows, input_done) = match state.input.next().await {
+                    Some(batch) => {
+                        let batch = concat_batches(&carry.schema(), [&carry, &batch?])?;
+                        let _timer = state.baseline_metrics.elapsed_compute().timer();
+                        (state.assigner.assign(&batch)?, false)
+                    }
+                    None => {
+                        let _timer = state.baseline_metrics.elapsed_compute().timer();
+                        (state.assigner.assign(&carry)?, true)
+                    }
+                };
+
+                // the last window may be continued by the next batch
+                let num_rows = windows.batch.num_rows();
+                let num_closed = if input_done {
+                    num_rows
+                } else {
+                    let last_window_rows = windows.sizes.last().copied().unwrap_or(0) as usize;
+                    state.carry = Some(
+                        windows
+                            .batch
+                            .slice(num_rows - last_window_rows, last_window_rows),
+                    );
+                    num_rows - last_window_rows
+                };
+                if num_closed > 0 {
+                    let output = state
+                        .assigner
+                        .build_output(&windows, None)?
+                        .slice(0, num_closed);
+                    state.baseline_metrics.record_output(output.num_rows());
+                    return Ok(Some((output, state)));
+                }
+            }
+            Ok::<_, DataFusionError>(None)
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            output,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "EventWindowExec: window={}, time_expr={}, partition_by=[{}]",
+                    self.window_type,
+                    self.time_expr,
+                    fmt_exprs(&self.partition_by),
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Execution plan for a EventWindow in stream query
+///
+/// The rows of windows which are not closed yet are kept in the [`StateStore`] and
+/// restored in the next micro batch:
+/// - session window is closed when the watermark passes `last_event + gap`
+/// - count window is closed when it has collected `size` events
+///
+/// The input keeps the hash partitioning by `partition_by`, each partition saves its
+/// open windows in its own state store.
+#[derive(Debug)]
+pub struct StatefulEventWindowExec<T> {
+    watermark_ns: i64,
+    state_store_factory: Arc<T>,
+    window_type: EventWindowType,
+    time_expr: Arc<dyn PhysicalExpr>,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    schema: SchemaRef,
+    input: Arc<dyn ExecutionPlan>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl<T> StatefulEventWindowExec<T> {
+    pub fn try_new(
+        watermark_ns: i64,
+        state_store_factory: Arc<T>,
+        window_type: EventWindowType,
+        time_expr: Arc<dyn PhysicalExpr>,
+        partition_by: Vec<Arc<dyn PhysicalExpr>>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        let schema = make_window_schema(input.schema().as_ref());
+
+        Ok(Self {
+            watermark_ns,
+            state_store_factory,
+            window_type,
+            time_expr,
+            partition_by,
+            schema,
+            input,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+impl<T> ExecutionPlan for StatefulEventWindowExec<T>
+where
+    T: StateStoreFactory + Send + Sync + Debug + 'static,
+    T::SS: Send + Sync + Debug,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        // Same as EventWindowExec, the state of each input partition is kept separately
+        if self.partition_by.is_empty() {
+            vec![Distribution::SinglePartition]
+        } else {
+            vec![Distribution::HashPartitioned(self.partition_by.clone())]
+        }
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        assert_eq!(children.len(), 1);
+
+        Ok(Arc::new(Self::try_new(
+            self.watermark_ns,
+            self.state_store_factory.clone(),
+            self.window_type.clone(),
+            self.time_expr.clone(),
+            self.partition_by.clone(),
+            children[0].clone(),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let session_id = context.session_id();
+        debug!(
+            "Start StatefulEventWindowExec::execute for partition {} of context session_id {} and task_id {:?}",
+            partition,
+            session_id,
+            context.task_id()
+        );
+
+        let input = self.input.execute(partition, context)?;
+        let state_store = self.state_store_factory.get_or_default(
+            session_id,
+            partition,
+            EVENT_WINDOW_OPERATOR_ID,
+        )?;
+        let assigner = WindowAssigner {
+            window_type: self.window_type.clone(),
+            time_expr: self.time_expr.clone(),
+            partition_by: self.partition_by.clone(),
+            schema: self.schema.clone(),
+        };
+        let watermark_ns = self.watermark_ns;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+
+        let output = futures::stream::once(async move {
+            let input_schema = input.schema();
+            // rows of the windows which are still open in the last micro batch
+            let mut batches = state_store.state()?;
+            batches.extend(input.try_collect::<Vec<_>>().await?);
+            let batch = concat_batches(&input_schema, &batches)?;
+
+            let _timer = baseline_metrics.elapsed_compute().timer();
+            let windows = assigner.assign(&batch)?;
+            let closed = windows.closed(&assigner.window_type, watermark_ns);
+
+            let opened = arrow::compute::not(&closed)?;
+            let remained = filter_record_batch(&windows.batch, &opened)?;
+            if remained.num_rows() > 0 {
+                state_store.put(remained)?;
+            }
+            let _ = state_store.commit()?;
+
+            let output = assigner.build_output(&windows, Some(&closed))?;
+            baseline_metrics.record_output(output.num_rows());
+
+            Ok(output)
+        })
+        .filter(|e| futures::future::ready(!matches!(e, Ok(b) if b.num_rows() == 0)));
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            output,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "StatefulEventWindowExec: watermark={}ns, window={}, time_expr={}, partition_by=[{}]",
+                    self.watermark_ns,
+                    self.window_type,
+                    self.time_expr,
+                    fmt_exprs(&self.partition_by),
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+struct EventWindowState {
+    input: SendableRecordBatchStream,
+    /// The rows of the last window of the previous batches, None once the input is exhausted
+    carry: Option<RecordBatch>,
+    assigner: WindowAssigner,
+    baseline_metrics: BaselineMetrics,
+}
+
+fn fmt_exprs(exprs: &[Arc<dyn PhysicalExpr>]) -> String {
+    exprs
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn make_window_schema(input_schema: &Schema) -> SchemaRef {
+    let mut fields = Vec::with_capacity(input_schema.fields().len() + 1);
+    fields.push(Arc::new(Field::new(
+        WINDOW_COL_NAME,
+        event_window_return_type(),
+        false,
+    )));
+    fields.extend(input_schema.fields().iter().cloned());
+
+    Arc::new(Schema::new_with_metadata(
+        fields,
+        input_schema.metadata().clone(),
+    ))
+}
+
+/// Rows sorted by (partition_by, time) with the window they belong to
+struct AssignedWindows {
+    batch: RecordBatch,
+    /// Window start of each row
+    starts: Vec<i64>,
+    /// Window end of each row
+    ends: Vec<i64>,
+    /// Number of rows in the window of each row
+    sizes: Vec<u64>,
+}
+
+impl AssignedWindows {
+    /// Whether the window of each row will no longer receive new events
+    fn closed(&self, window_type: &EventWindowType, watermark_ns: i64) -> BooleanArray {
+        match window_type {
+            EventWindowType::Session { .. } => self
+                .ends
+                .iter()
+                .map(|end| Some(*end <= watermark_ns))
+                .collect(),
+            EventWindowType::Count { size } => self.sizes.iter().map(|n| Some(n >= size)).collect(),
+        }
+    }
+}
+
+struct WindowAssigner {
+    window_type: EventWindowType,
+    time_expr: Arc<dyn PhysicalExpr>,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    schema: SchemaRef,
+}
+
+impl WindowAssigner {
+    fn assign(&self, batch: &RecordBatch) -> Result<AssignedWindows> {
+        let num_rows = batch.num_rows();
+        let ns_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+
+        let time_array = self.time_expr.evaluate(batch)?.into_array(num_rows);
+        let time_array = cast(&time_array, &ns_type)?;
+        let partition_arrays = self
+            .partition_by
+            .iter()
+            .map(|e| e.evaluate(batch).map(|v| v.into_array(num_rows)))
+            .collect::<Result<Vec<_>>>()?;
+
+        // sort by (partition_by, time)
+        let sort_columns = partition_arrays
+            .iter()
+            .chain(std::iter::once(&time_array))
+            .map(|values| SortColumn {
+                values: values.clone(),
+                options: None,
+            })
+            .collect::<Vec<_>>();
+        let indices = arrow::compute::lexsort_to_indices(&sort_columns, None)?;
+
+        let take_array = |a: &ArrayRef| take(a.as_ref(), &indices, None);
+        let columns = batch
+            .columns()
+            .iter()
+            .map(take_array)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let sorted_batch = RecordBatch::try_new(batch.schema(), columns)?;
+        let time_array = take_array(&time_array)?;
+        let times = time_array
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal("Time column of EventWindow is not timestamp".into())
+            })?;
+        let partition_arrays = partition_arrays
+            .iter()
+            .map(take_array)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let ranges: Vec<Range<usize>> = if partition_arrays.is_empty() || num_rows == 0 {
+            vec![0..num_rows]
+        } else {
+            let sort_columns = partition_arrays
+                .into_iter()
+                .map(|values| SortColumn {
+                    values,
+                    options: None,
+                })
+                .collect::<Vec<_>>();
+            arrow::compute::lexicographical_partition_ranges(&sort_columns)?.collect()
+        };
+
+        let mut windows = AssignedWindows {
+            batch: sorted_batch,
+            starts: Vec::with_capacity(num_rows),
+            ends: Vec::with_capacity(num_rows),
+            sizes: Vec::with_capacity(num_rows),
+        };
+
+        for range in ranges {
+            match &self.window_type {
+                EventWindowType::Session { gap } => {
+                    let gap_ns = gap.as_nanos() as i64;
+                    let mut begin = range.start;
+                    for i in range.start + 1..=range.end {
+                        if i == range.end || times.value(i) - times.value(i - 1) >= gap_ns {
+                            let start = times.value(begin);
+                            let end = times.value(i - 1).saturating_add(gap_ns);
+                            push_window(&mut windows, start, end, i - begin);
+                            begin = i;
+                        }
+                    }
+                }
+                EventWindowType::Count { size } => {
+                    let mut begin = range.start;
+                    while begin < range.end {
+                        let end = (begin + *size as usize).min(range.end);
+                        push_window(
+                            &mut windows,
+                            times.value(begin),
+                            times.value(end - 1),
+                            end - begin,
+                        );
+                        begin = end;
+                    }
+                }
+            }
+        }
+
+        Ok(windows)
+    }
+
+    /// Prepend the window column to the rows, only the rows matching `predicate` are kept
+    fn build_output(
+        &self,
+        windows: &AssignedWindows,
+        predicate: Option<&BooleanArray>,
+    ) -> Result<RecordBatch> {
+        let fields = match event_window_return_type() {
+            DataType::Struct(fields) => fields,
+            _ => Fields::empty(),
+        };
+        let starts = Arc::new(TimestampNanosecondArray::from(windows.starts.clone()));
+        let ends = Arc::new(TimestampNanosecondArray::from(windows.ends.clone()));
+        let window: ArrayRef = Arc::new(StructArray::new(fields, vec![starts, ends], None));
+
+        let mut columns = Vec::with_capacity(windows.batch.num_columns() + 1);
+        columns.push(window);
+        columns.extend(windows.batch.columns().iter().cloned());
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+
+        match predicate {
+            Some(predicate) => Ok(filter_record_batch(&batch, predicate)?),
+            None => Ok(batch),
+        }
+    }
+}
+
+fn push_window(windows: &mut AssignedWindows, start: i64, end: i64, num_rows: usize) {
+    windows
+        .starts
+        .extend(std::iter::repeat(start).take(num_rows));
+    windows.ends.extend(std::iter::repeat(end).take(num_rows));
+    windows
+        .sizes
+        .extend(std::iter::repeat(num_rows as u64).take(num_rows));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use datafusion::arrow::array::{
+        Array, StringArray, StructArray, TimestampNanosecondArray, UInt32Array,
+    };
+    use datafusion::arrow::compute::take;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::physical_plan::expressions::Column;
+
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::repartition::RepartitionExec;
+    use datafusion::physical_plan::{collect, ExecutionPlan, Partitioning, PhysicalExpr};
+    use datafusion::prelude::{SessionConfig, SessionContext};
+
+    use super::{make_window_schema, EventWindowExec, StatefulEventWindowExec, WindowAssigner};
+    use crate::extension::logical::plan_node::event_window::EventWindowType;
+    use crate::stream::state_store::memory::MemoryStateStoreFactory;
+
+    fn test_batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("device", DataType::Utf8, false),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![1, 12, 2, 3, 25, 4, 28])),
+                Arc::new(StringArray::from(vec!["a", "b", "a", "b", "a", "b", "a"])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn assign(window_type: EventWindowType, watermark_ns: i64) -> (Vec<(i64, i64)>, Vec<bool>) {
+        let batch = test_batch();
+        let assigner = WindowAssigner {
+            window_type: window_type.clone(),
+            time_expr: Arc::new(Column::new("time", 0)),
+            partition_by: vec![Arc::new(Column::new("device", 1))],
+            schema: make_window_schema(batch.schema().as_ref()),
+        };
+        let windows = assigner.assign(&batch).unwrap();
+        let output = assigner.build_output(&windows, None).unwrap();
+
+        let window = output
+            .column(0)
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .unwrap();
+        let starts = window
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        let ends = window
+            .column(1)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        let bounds = (0..window.len())
+            .map(|i| (starts.value(i), ends.value(i)))
+            .collect();
+        let closed = windows
+            .closed(&window_type, watermark_ns)
+            .iter()
+            .map(|e| e.unwrap())
+            .collect();
+
+        (bounds, closed)
+    }
+
+    #[test]
+    fn test_session_window() {
+        let (bounds, closed) = assign(
+            EventWindowType::Session {
+                gap: Duration::from_nanos(5),
+            },
+            10,
+        );
+
+        // a: 1, 2, 25, 28 | b: 3, 4, 12
+        assert_eq!(
+            bounds,
+            vec![(1, 7), (1, 7), (25, 33), (25, 33), (3, 9), (3, 9), (12, 17)]
+        );
+        assert_eq!(closed, vec![true, true, false, false, true, true, false]);
+    }
+
+    #[test]
+    fn test_count_window() {
+        let (bounds, closed) = assign(EventWindowType::Count { size: 2 }, 0);
+
+        // a: 1, 2, 25, 28 | b: 3, 4, 12
+        assert_eq!(
+            bounds,
+            vec![(1, 2), (1, 2), (25, 28), (25, 28), (3, 4), (3, 4), (12, 12)]
+        );
+        assert_eq!(closed, vec![true, true, true, true, true, true, false]);
+    }
+
+    #[tokio::test]
+    async fn test_emit_closed_windows() {
+        // sorted by (device, time) and split into batches across the windows
+        let batch = test_batch();
+        let indices = [0_usize, 2, 4, 6, 3, 5, 1];
+        let batches = indices
+            .chunks(2)
+            .map(|chunk| {
+                let indices =
+                    UInt32Array::from(chunk.iter().map(|i| *i as u32).collect::<Vec<_>>());
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(|c| take(c.as_ref(), &indices, None).unwrap())
+                    .collect();
+                RecordBatch::try_new(batch.schema(), columns).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let input = Arc::new(MemoryExec::try_new(&[batches], batch.schema(), None).unwrap());
+
+        let exec = Arc::new(
+            EventWindowExec::try_new(
+                EventWindowType::Session {
+                    gap: Duration::from_nanos(5),
+                },
+                Arc::new(Column::new("time", 0)),
+                vec![Arc::new(Column::new("device", 1))],
+                input,
+            )
+            .unwrap(),
+        );
+        let output = collect(
+            exec as Arc<dyn ExecutionPlan>,
+            SessionContext::new().task_ctx(),
+        )
+        .await
+        .unwrap();
+
+        // a: [1, 2] | [25, 28], b: [3, 4] | [12]
+        let bounds = output
+            .iter()
+            .map(|batch| {
+                let window = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<StructArray>()
+                    .unwrap();
+                let starts = window
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<TimestampNanosecondArray>()
+                    .unwrap();
+                (0..window.len())
+                    .map(|i| starts.value(i))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(bounds, vec![vec![1, 1], vec![25, 25], vec![3, 3], vec![12]]);
+    }
+
+    /// (device, window start) of the output rows, sorted
+    fn device_windows(output: &[RecordBatch]) -> Vec<(String, i64)> {
+        let mut rows = output
+            .iter()
+            .flat_map(|batch| {
+                let window = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<StructArray>()
+                    .unwrap();
+                let starts = window
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<TimestampNanosecondArray>()
+                    .unwrap();
+                let devices = batch
+                    .column(2)
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .unwrap();
+                (0..batch.num_rows())
+                    .map(|i| (devices.value(i).to_string(), starts.value(i)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        rows.sort();
+        rows
+    }
+
+    #[tokio::test]
+    async fn test_stateful_hash_partitioned() {
+        let devices = ["a", "b", "c", "d", "e", "f"];
+        let schema = test_batch().schema();
+        // each device has a closed session [1, 2] and an open session [30]
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from(
+                    devices.iter().flat_map(|_| [1, 2, 30]).collect::<Vec<_>>(),
+                )),
+                Arc::new(StringArray::from(
+                    devices
+                        .iter()
+                        .flat_map(|d| [*d, *d, *d])
+                        .collect::<Vec<_>>(),
+                )),
+            ],
+        )
+        .unwrap();
+
+        let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(3));
+        let factory = Arc::new(MemoryStateStoreFactory::default());
+        let run = |watermark_ns: i64, batches: Vec<RecordBatch>| {
+            let input = Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None).unwrap());
+            let partition_by: Vec<Arc<dyn PhysicalExpr>> = vec![Arc::new(Column::new("device", 1))];
+            let input = Arc::new(
+                RepartitionExec::try_new(input, Partitioning::Hash(partition_by.clone(), 3))
+                    .unwrap(),
+            );
+            let exec = Arc::new(
+                StatefulEventWindowExec::try_new(
+                    watermark_ns,
+                    factory.clone(),
+                    EventWindowType::Session {
+                        gap: Duration::from_nanos(5),
+                    },
+                    Arc::new(Column::new("time", 0)),
+                    partition_by,
+                    input,
+                )
+                .unwrap(),
+            );
+            assert_eq!(exec.output_partitioning().partition_count(), 3);
+            collect(exec as Arc<dyn ExecutionPlan>, ctx.task_ctx())
+        };
+
+        let closed = devices
+            .iter()
+            .flat_map(|d| [(d.to_string(), 1), (d.to_string(), 1)])
+            .collect::<Vec<_>>();
+        let output = run(10, vec![batch]).await.unwrap();
+        assert_eq!(device_windows(&output), closed);
+
+        // the open sessions are restored from the state of every partition
+        let opened = devices
+            .iter()
+            .map(|d| (d.to_string(), 30))
+            .collect::<Vec<_>>();
+        let output = run(100, vec![]).await.unwrap();
+        assert_eq!(device_windows(&output), opened);
+    }
+}
@@ -475,6 +475,7 @@ impl Cursor {
                     RowStatus::Present { ts, .. } | RowStatus::Missing { ts, .. } => {
                         self.times.push(Some(ts))
                     }
+                    RowStatus::Context { .. } => (),
                 }
                 Ok(())
             }
@@ -512,6 +513,7 @@ impl Cursor {
                     | RowStatus::Missing {
                         series_end_offset, ..
                     } => self.take_idxs.push(series_end_offset as u64 - 1),
+                    RowStatus::Context { .. } => (),
                 }
                 Ok(())
             }
@@ -581,6 +583,7 @@ impl Cursor {
                         self.take_idxs.push(Some(offset as u64))
                     }
                     RowStatus::Missing { .. } => self.take_idxs.push(None),
+                    RowStatus::Context { .. } => (),
                 }
                 Ok(())
             }
@@ -627,6 +630,11 @@ impl Cursor {
                         }
                     }
                     RowStatus::Missing { .. } => self.take_idxs.push(self.prev_offset),
+                    RowStatus::Context { offset, .. } => {
+                        if !self.null_as_missing || self.input_aggr_array.is_valid(offset) {
+                            self.prev_offset = Some(offset as u64);
+                        }
+                    }
                 }
                 Ok(())
             }
@@ -767,6 +775,20 @@ impl Cursor {
         }
         let mut next_ts = self.next_ts.unwrap();
 
+        // Rows before the first timestamp to be output only provide
+        // the values to fill the gaps at the beginning of the series.
+        while self.next_input_offset < series_end
+            && input_times.is_valid(self.next_input_offset)
+            && input_times.value(self.next_input_offset) < next_ts
+        {
+            vec_builder.push(RowStatus::Context {
+                series_end_offset: series_end,
+                offset: self.next_input_offset,
+                ts: input_times.value(self.next_input_offset),
+            })?;
+            self.next_input_offset += 1;
+        }
+
         let output_row_count = std::cmp::min(
             params.valid_row_count(next_ts),
             self.remaining_output_batch_size,
@@ -945,6 +967,17 @@ enum RowStatus {
         /// The timestamp corresponding to this row.
         ts: i64,
     },
+    /// This row is before the first timestamp to be output, e.g. the last point
+    /// before the time range scanned from tskv. It does not appear in the output,
+    /// but it is used to fill the gaps at the beginning of the series.
+    Context {
+        /// The exclusive offset of the series end in the input.
+        series_end_offset: usize,
+        /// The offset of the value in the input time array.
+        offset: usize,
+        /// The timestamp of this row in the input.
+        ts: i64,
+    },
 }
 
 /// Implements [`VecBuilder`] for [`FillStrategy::PrevNullAsMissing`],
@@ -1008,6 +1041,11 @@ impl<'a> VecBuilder for StashedAggrBuilder<'a> {
                 self.interleave_idxs.push(Self::buffered_input(offset));
                 self.state = StashedAggrState::PrevSome { offset };
             }
+            RowStatus::Context { offset, .. } => {
+                if self.input_aggr_array.is_valid(offset) {
+                    self.state = StashedAggrState::PrevSome { offset };
+                }
+            }
             RowStatus::Present { .. } | RowStatus::Missing { .. } => match self.state {
                 StashedAggrState::Stashed => self.interleave_idxs.push(Self::STASHED_VALUE),
                 StashedAggrState::PrevNone => self.interleave_idxs.push(Self::STASHED_NULL),
@@ -214,6 +214,22 @@ where
                     .as_ref()
                     .map(|seg| T::Native::interpolate(seg, ts)),
             ),
+            RowStatus::Context {
+                ts,
+                offset,
+                series_end_offset,
+            } => {
+                if self.input_aggr_array.is_valid(offset) {
+                    let end_offset = self.find_end_offset(offset, series_end_offset);
+                    self.segment = end_offset.map(|end_offset| Segment {
+                        start_point: (ts, self.input_aggr_array.value(offset)),
+                        end_point: (
+                            self.input_time_array.value(end_offset),
+                            self.input_aggr_array.value(end_offset),
+                        ),
+                    });
+                }
+            }
         }
         Ok(())
     }
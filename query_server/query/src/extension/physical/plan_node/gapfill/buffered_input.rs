@@ -3,7 +3,7 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
-use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::array::{ArrayRef, TimestampNanosecondArray};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::arrow::row::{RowConverter, Rows, SortField};
 use datafusion::error::{DataFusionError, Result};
@@ -24,6 +24,10 @@ use super::FillStrategy;
 /// [`FillStrategy::LinearInterpolate`]: super::FillStrategy::LinearInterpolate
 /// [`GapFillStream`]: super::stream::GapFillStream
 pub(super) struct BufferedInput {
+    /// Index of the time column in the schema.
+    time_col: usize,
+    /// The first timestamp to be output, see [`GapFillParams::first_ts`].
+    first_ts: Option<i64>,
     /// Indexes of group columns in the schema (not including time).
     group_cols: Vec<usize>,
     /// Indexes of aggregate columns filled via interpolation.
@@ -40,7 +44,7 @@ pub(super) struct BufferedInput {
 }
 
 impl BufferedInput {
-    pub(super) fn new(params: &GapFillParams, group_cols: Vec<usize>) -> Self {
+    pub(super) fn new(params: &GapFillParams, time_col: usize, group_cols: Vec<usize>) -> Self {
         let interpolate_cols = params
             .fill_strategy
             .iter()
@@ -49,6 +53,8 @@ impl BufferedInput {
             })
             .collect::<Vec<usize>>();
         Self {
+            time_col,
+            first_ts: params.first_ts,
             group_cols,
             interpolate_cols,
             batches: vec![],
@@ -73,7 +79,8 @@ impl BufferedInput {
         let record_count: usize = self.batches.iter().map(|rb| rb.num_rows()).sum();
         // min number of rows needed is the number of rows up to and including
         // the last row that may appear in the output, plus one more row.
-        let min_needed = last_output_row_offset + 2;
+        // Rows before the first timestamp don't appear in the output, so more rows are needed.
+        let min_needed = last_output_row_offset + 2 + self.context_row_count();
 
         if record_count < min_needed {
             return Ok(true);
@@ -200,6 +207,25 @@ impl BufferedInput {
         Ok(self.last_output_row.as_ref().expect("cannot be none"))
     }
 
+    /// Return the number of buffered rows before the first timestamp to be output,
+    /// e.g. the last points before the time range scanned from tskv.
+    fn context_row_count(&self) -> usize {
+        let Some(first_ts) = self.first_ts else {
+            return 0;
+        };
+
+        self.batches
+            .iter()
+            .filter_map(|batch| {
+                batch
+                    .column(self.time_col)
+                    .as_any()
+                    .downcast_ref::<TimestampNanosecondArray>()
+            })
+            .map(|times| times.iter().flatten().filter(|ts| *ts < first_ts).count())
+            .sum()
+    }
+
     /// Return the `(batch_idx, row_idx)` of the last buffered row.
     fn last_buffered_row_idx(&self) -> (usize, usize) {
         let last_batch_len = self.batches.last().unwrap().num_rows();
@@ -5,7 +5,9 @@ use datafusion::error::DataFusionError;
 use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, Time};
 
 pub mod aggregate_filter_scan;
+pub mod asof_join;
 pub mod assert;
+pub mod event_window;
 pub mod expand;
 pub mod gapfill;
 pub mod state_restore;
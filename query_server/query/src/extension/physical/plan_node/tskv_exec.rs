@@ -20,6 +20,7 @@ use models::codec::Encoding;
 use models::datafusion::limit_record_batch::limit_record_batch;
 use models::predicate::domain::PredicateRef;
 use models::predicate::PlacedSplit;
+use models::query_resource::QueryResourceTracker;
 use models::schema::{ColumnType, TableColumn, TskvTableSchema, TskvTableSchemaRef, TIME_FIELD};
 use spi::{QueryError, Result};
 use trace::{debug, SpanContext, SpanExt, SpanRecorder};
@@ -124,6 +125,9 @@ impl ExecutionPlan for TskvExec {
         let metrics = TableScanMetrics::new(&self.metrics, partition);
 
         let span_ctx = context.session_config().get_extension::<SpanContext>();
+        let resource_tracker = context
+            .session_config()
+            .get_extension::<QueryResourceTracker>();
 
         let table_stream = TableScanStream::new(
             self.table_schema.clone(),
@@ -133,6 +137,7 @@ impl ExecutionPlan for TskvExec {
             batch_size,
             metrics,
             SpanRecorder::new(span_ctx.child_span(format!("TableScanStream ({partition})"))),
+            resource_tracker,
         )
         .map_err(|err| DataFusionError::External(Box::new(err)))?;
 
@@ -193,7 +198,11 @@ impl<'a> Display for PredicateDisplay<'a> {
             filter.limit(),
             filter.filter(),
             filter.physical_expr().map(|e| e.to_string()),
-        )
+        )?;
+        if !filter.spatial_filters().is_empty() {
+            write!(f, ", spatial_filters={:?}", filter.spatial_filters())?;
+        }
+        Ok(())
     }
 }
 
@@ -220,6 +229,7 @@ impl TableScanStream {
         batch_size: usize,
         metrics: TableScanMetrics,
         span_recorder: SpanRecorder,
+        resource_tracker: Option<Arc<QueryResourceTracker>>,
     ) -> Result<Self> {
         let mut proj_fileds = Vec::with_capacity(proj_schema.fields().len());
         for item in proj_schema.fields().iter() {
@@ -265,7 +275,8 @@ impl TableScanStream {
             None,
             proj_schema.clone(),
             proj_table_schema.into(),
-        );
+        )
+        .with_resource_tracker(resource_tracker);
 
         let span_ctx = span_recorder.span_ctx();
         let iterator = coord.table_scan(option, span_ctx)?;
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_expr::create_physical_expr;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+
+use crate::extension::logical::plan_node::asof_join::AsofJoinNode;
+use crate::extension::physical::plan_node::asof_join::AsofJoinExec;
+use crate::extension::utils::downcast_plan_node;
+
+/// Physical planner for AsofJoin nodes
+#[derive(Default)]
+pub struct AsofJoinPlanner {}
+
+impl AsofJoinPlanner {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl ExtensionPlanner for AsofJoinPlanner {
+    /// Create a physical plan for an extension node
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        session_state: &SessionState,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let asof_join = match downcast_plan_node::<AsofJoinNode>(node) {
+            Some(asof_join) => asof_join,
+            None => return Ok(None),
+        };
+
+        if logical_inputs.len() != 2 || physical_inputs.len() != 2 {
+            return Err(DataFusionError::Internal(
+                "AsofJoinExec: wrong number of inputs".to_string(),
+            ));
+        }
+
+        let execution_props = session_state.execution_props();
+        let left_dfschema = logical_inputs[0].schema().as_ref();
+        let right_dfschema = logical_inputs[1].schema().as_ref();
+        let left = physical_inputs[0].clone();
+        let right = physical_inputs[1].clone();
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+
+        let on = asof_join
+            .on
+            .iter()
+            .map(|(l, r)| {
+                let l =
+                    create_physical_expr(l, left_dfschema, left_schema.as_ref(), execution_props)?;
+                let r = create_physical_expr(
+                    r,
+                    right_dfschema,
+                    right_schema.as_ref(),
+                    execution_props,
+                )?;
+                Ok((l, r))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let left_time = create_physical_expr(
+            &asof_join.left_time,
+            left_dfschema,
+            left_schema.as_ref(),
+            execution_props,
+        )?;
+        let right_time = create_physical_expr(
+            &asof_join.right_time,
+            right_dfschema,
+            right_schema.as_ref(),
+            execution_props,
+        )?;
+
+        Ok(Some(Arc::new(AsofJoinExec::try_new(
+            left,
+            right,
+            on,
+            left_time,
+            right_time,
+            asof_join.op,
+            Arc::new(asof_join.schema.as_ref().into()),
+        )?)))
+    }
+}
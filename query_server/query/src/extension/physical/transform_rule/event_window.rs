@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_expr::create_physical_expr;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+
+use crate::extension::logical::plan_node::event_window::EventWindowNode;
+use crate::extension::physical::plan_node::event_window::EventWindowExec;
+use crate::extension::utils::downcast_plan_node;
+
+/// Physical planner for EventWindow nodes
+#[derive(Default)]
+pub struct EventWindowPlanner {}
+
+impl EventWindowPlanner {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl ExtensionPlanner for EventWindowPlanner {
+    /// Create a physical plan for an extension node
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        session_state: &SessionState,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let event_window = match downcast_plan_node::<EventWindowNode>(node) {
+            Some(event_window) => event_window,
+            None => return Ok(None),
+        };
+
+        if logical_inputs.len() != 1 || physical_inputs.len() != 1 {
+            return Err(DataFusionError::Internal(
+                "EventWindowExec: wrong number of inputs".to_string(),
+            ));
+        }
+
+        let execution_props = session_state.execution_props();
+        let input_dfschema = logical_inputs[0].schema().as_ref();
+        let input_exec = physical_inputs[0].clone();
+        let input_schema = input_exec.schema();
+
+        let time_expr = create_physical_expr(
+            &event_window.time_column,
+            input_dfschema,
+            input_schema.as_ref(),
+            execution_props,
+        )?;
+        let partition_by = event_window
+            .partition_by
+            .iter()
+            .map(|e| {
+                create_physical_expr(e, input_dfschema, input_schema.as_ref(), execution_props)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Arc::new(EventWindowExec::try_new(
+            event_window.window_type.clone(),
+            time_expr,
+            partition_by,
+            input_exec,
+        )?)))
+    }
+}
@@ -1,4 +1,6 @@
 //! logical paln to physical plan transform rule
+pub mod asof_join;
+pub mod event_window;
 pub mod expand;
 pub mod gapfill;
 pub mod stream_scan;
@@ -14,15 +14,16 @@ use spi::query::auth::AccessControlRef;
 use spi::query::datasource::stream::checker::StreamCheckerManager;
 use spi::query::datasource::stream::StreamProviderManager;
 use spi::query::dispatcher::QueryDispatcher;
-use spi::query::execution::QueryStateMachineRef;
+use spi::query::execution::{QueryExecutionRef, QueryStateMachineRef};
 use spi::query::logical_planner::Plan;
-use spi::query::session::SessionCtxFactory;
+use spi::query::scheduler::SchedulerRef;
 use spi::server::dbms::DatabaseManagerSystem;
 use spi::service::protocol::{Query, QueryHandle, QueryId};
 use spi::{AuthSnafu, Result};
 use trace::{debug, SpanContext};
 use tskv::kv_option::Options;
 
+use crate::alert::AlertManager;
 use crate::auth::auth_control::{AccessControlImpl, AccessControlNoCheck};
 use crate::data_source::split::SplitManager;
 use crate::data_source::stream::tskv::factory::{TskvStreamProviderFactory, TSKV_STREAM_PROVIDER};
@@ -30,7 +31,9 @@ use crate::dispatcher::manager::SimpleQueryDispatcherBuilder;
 use crate::dispatcher::persister::LocalQueryPersister;
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::execution::factory::SqlQueryExecutionFactory;
+use crate::execution::scheduler::dedicated::DedicatedScheduler;
 use crate::execution::scheduler::local::LocalScheduler;
+use crate::execution::spill::SpillManager;
 use crate::extension::expr::{load_all_functions, register_session_udfs};
 use crate::extension::variable::load_all_system_vars;
 use crate::function::simple_func_manager::SimpleFunctionMetadataManager;
@@ -147,6 +150,14 @@ where
     fn cancel(&self, query_id: &QueryId) {
         self.query_dispatcher.cancel_query(query_id);
     }
+
+    fn running_queries(&self) -> Vec<QueryExecutionRef> {
+        self.query_dispatcher.running_queries()
+    }
+
+    fn kill_query(&self, query_id: &QueryId) -> bool {
+        self.query_dispatcher.kill_query(query_id)
+    }
 }
 
 impl<D: QueryDispatcher> Cnosdbms<D> {
@@ -176,15 +187,26 @@ pub async fn make_cnosdbms(
 
     let split_manager = Arc::new(SplitManager::new(coord.clone()));
     // TODO session config need load global system config
-    let session_factory = Arc::new(SessionCtxFactory::new(
-        Some(Arc::new(var_manager)),
-        query_dedicated_hidden_dir.clone(),
-        Some(register_session_udfs),
-    ));
+    let spill_manager = Arc::new(SpillManager::try_new(
+        &options.query,
+        coord.metrics().clone(),
+    )?);
+    let session_factory = Arc::new(
+        SessionCtxFactory::new(
+            Some(Arc::new(var_manager)),
+            query_dedicated_hidden_dir.clone(),
+            Some(register_session_udfs),
+        )
+        .with_disk_manager(spill_manager.disk_manager_config())
+        .with_spill_hash_operators(options.query.spill_hash_operators),
+    );
     let parser = Arc::new(DefaultParser::default());
     let optimizer = Arc::new(CascadeOptimizerBuilder::default().build());
-    // TODO wrap, and num_threads configurable
-    let scheduler = Arc::new(LocalScheduler {});
+    let scheduler: SchedulerRef = if options.query.query_executor_cpu > 0 {
+        Arc::new(DedicatedScheduler::new(options.query.query_executor_cpu))
+    } else {
+        Arc::new(LocalScheduler::default())
+    };
 
     // init stream provider manager
     let mut stream_provider_manager = StreamProviderManager::default();
@@ -215,6 +237,7 @@ pub async fn make_cnosdbms(
     let query_execution_factory = Arc::new(SqlQueryExecutionFactory::new(
         optimizer,
         scheduler,
+        spill_manager,
         query_tracker.clone(),
         Arc::new(stream_checker_manager),
         options.query.clone(),
@@ -239,6 +262,8 @@ pub async fn make_cnosdbms(
         stream_provider_manager.clone(),
     ));
 
+    let alert_manager = Arc::new(AlertManager::new(coord.clone(), &options.query));
+
     let query_dispatcher = SimpleQueryDispatcherBuilder::default()
         .with_coord(coord)
         .with_default_table_provider(default_table_provider)
@@ -250,6 +275,7 @@ pub async fn make_cnosdbms(
         .with_query_tracker(query_tracker)
         .with_func_manager(Arc::new(func_manager))
         .with_stream_provider_manager(stream_provider_manager)
+        .with_alert_manager(alert_manager)
         .build()?;
 
     let mut builder = CnosdbmsBuilder::default();
@@ -1,6 +1,7 @@
 #![recursion_limit = "256"]
 extern crate core;
 
+pub mod alert;
 pub mod auth;
 mod data_source;
 pub mod dispatcher;
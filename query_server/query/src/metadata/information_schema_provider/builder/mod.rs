@@ -4,6 +4,7 @@ pub mod databases;
 pub mod enabled_roles;
 pub mod members;
 pub mod queries;
+pub mod replicas;
 pub mod resource_status;
 pub mod roles;
 pub mod tables;
@@ -5,6 +5,8 @@ use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::DataFusionError;
 use lazy_static::lazy_static;
+use models::query_resource::QueryResourceUsage;
+use spi::query::execution::QueryExecutionRef;
 
 lazy_static! {
     pub static ref QUERY_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
@@ -19,6 +21,14 @@ lazy_static! {
         Field::new("duration", DataType::Float64, false),
         Field::new("processed_count", DataType::UInt64, false),
         Field::new("error_count", DataType::UInt64, false),
+        Field::new("node_id", DataType::UInt64, false),
+        Field::new("cpu_time", DataType::Float64, false),
+        Field::new("peak_memory", DataType::UInt64, false),
+        Field::new("rows_scanned", DataType::UInt64, false),
+        Field::new("bytes_scanned", DataType::UInt64, false),
+        Field::new("vnode_scans", DataType::Utf8, false),
+        Field::new("spill_count", DataType::UInt64, false),
+        Field::new("spilled_bytes", DataType::UInt64, false),
     ]));
 }
 
@@ -36,6 +46,14 @@ pub struct InformationSchemaQueriesBuilder {
     durations: Float64Builder,
     processed_counts: UInt64Builder,
     error_counts: UInt64Builder,
+    node_ids: UInt64Builder,
+    cpu_times: Float64Builder,
+    peak_memories: UInt64Builder,
+    rows_scanned: UInt64Builder,
+    bytes_scanned: UInt64Builder,
+    vnode_scans: StringBuilder,
+    spill_counts: UInt64Builder,
+    spilled_bytes: UInt64Builder,
 }
 
 impl InformationSchemaQueriesBuilder {
@@ -53,6 +71,8 @@ impl InformationSchemaQueriesBuilder {
         duration: f64,
         processed_count: u64,
         error_count: u64,
+        node_id: u64,
+        resource_usage: &QueryResourceUsage,
     ) {
         // Note: append_value is actually infallable.
         self.query_ids.append_value(query_id.as_ref());
@@ -66,6 +86,41 @@ impl InformationSchemaQueriesBuilder {
         self.durations.append_value(duration);
         self.processed_counts.append_value(processed_count);
         self.error_counts.append_value(error_count);
+        self.node_ids.append_value(node_id);
+        self.cpu_times
+            .append_value(resource_usage.cpu_time.as_secs_f64());
+        self.peak_memories.append_value(resource_usage.peak_memory);
+        self.rows_scanned
+            .append_value(resource_usage.rows_scanned());
+        self.bytes_scanned
+            .append_value(resource_usage.bytes_scanned());
+        self.vnode_scans
+            .append_value(serde_json::to_string(&resource_usage.vnode_scans).unwrap_or_default());
+        self.spill_counts.append_value(resource_usage.spill_count);
+        self.spilled_bytes
+            .append_value(resource_usage.spilled_bytes);
+    }
+
+    /// Appends a query running on the node `node_id`
+    pub fn append_query(&mut self, node_id: u64, query: &QueryExecutionRef) {
+        let info = query.info();
+        let status = query.status();
+
+        self.append_row(
+            info.query_id().to_string(),
+            query.query_type().to_string(),
+            info.query(),
+            info.user_id().to_string(),
+            info.user_name(),
+            info.tenant_id().to_string(),
+            info.tenant_name(),
+            status.query_state(),
+            status.duration().as_secs_f64(),
+            status.processed_count(),
+            status.error_count(),
+            node_id,
+            status.resource_usage(),
+        );
     }
 }
 
@@ -85,6 +140,14 @@ impl TryFrom<InformationSchemaQueriesBuilder> for RecordBatch {
             mut durations,
             mut processed_counts,
             mut error_counts,
+            mut node_ids,
+            mut cpu_times,
+            mut peak_memories,
+            mut rows_scanned,
+            mut bytes_scanned,
+            mut vnode_scans,
+            mut spill_counts,
+            mut spilled_bytes,
         } = value;
 
         let batch = RecordBatch::try_new(
@@ -101,6 +164,14 @@ impl TryFrom<InformationSchemaQueriesBuilder> for RecordBatch {
                 Arc::new(durations.finish()),
                 Arc::new(processed_counts.finish()),
                 Arc::new(error_counts.finish()),
+                Arc::new(node_ids.finish()),
+                Arc::new(cpu_times.finish()),
+                Arc::new(peak_memories.finish()),
+                Arc::new(rows_scanned.finish()),
+                Arc::new(bytes_scanned.finish()),
+                Arc::new(vnode_scans.finish()),
+                Arc::new(spill_counts.finish()),
+                Arc::new(spilled_bytes.finish()),
             ],
         )?;
 
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{BooleanBuilder, StringBuilder, UInt32Builder, UInt64Builder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+pub const REPLICAS_TENANT_NAME: &str = "tenant_name";
+pub const REPLICAS_DATABASE_NAME: &str = "database_name";
+pub const REPLICAS_BUCKET_ID: &str = "bucket_id";
+pub const REPLICAS_REPLICA_SET_ID: &str = "replica_set_id";
+pub const REPLICAS_VNODE_ID: &str = "vnode_id";
+pub const REPLICAS_NODE_ID: &str = "node_id";
+pub const REPLICAS_VNODE_STATUS: &str = "vnode_status";
+pub const REPLICAS_IS_LEADER: &str = "is_leader";
+
+lazy_static! {
+    pub static ref REPLICAS_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(REPLICAS_TENANT_NAME, DataType::Utf8, false),
+        Field::new(REPLICAS_DATABASE_NAME, DataType::Utf8, false),
+        Field::new(REPLICAS_BUCKET_ID, DataType::UInt32, false),
+        Field::new(REPLICAS_REPLICA_SET_ID, DataType::UInt32, false),
+        Field::new(REPLICAS_VNODE_ID, DataType::UInt32, false),
+        Field::new(REPLICAS_NODE_ID, DataType::UInt64, false),
+        Field::new(REPLICAS_VNODE_STATUS, DataType::Utf8, false),
+        Field::new(REPLICAS_IS_LEADER, DataType::Boolean, false),
+    ]));
+}
+
+/// Builds the `information_schema.REPLICAS` table row by row, one row per vnode
+#[derive(Default)]
+pub struct InformationSchemaReplicasBuilder {
+    tenant_names: StringBuilder,
+    database_names: StringBuilder,
+    bucket_ids: UInt32Builder,
+    replica_set_ids: UInt32Builder,
+    vnode_ids: UInt32Builder,
+    node_ids: UInt64Builder,
+    vnode_statuses: StringBuilder,
+    is_leaders: BooleanBuilder,
+}
+
+impl InformationSchemaReplicasBuilder {
+    #[allow(clippy::too_many_arguments)]
+    pub fn append_row(
+        &mut self,
+        tenant_name: impl AsRef<str>,
+        database_name: impl AsRef<str>,
+        bucket_id: u32,
+        replica_set_id: u32,
+        vnode_id: u32,
+        node_id: u64,
+        vnode_status: impl AsRef<str>,
+        is_leader: bool,
+    ) {
+        self.tenant_names.append_value(tenant_name.as_ref());
+        self.database_names.append_value(database_name.as_ref());
+        self.bucket_ids.append_value(bucket_id);
+        self.replica_set_ids.append_value(replica_set_id);
+        self.vnode_ids.append_value(vnode_id);
+        self.node_ids.append_value(node_id);
+        self.vnode_statuses.append_value(vnode_status.as_ref());
+        self.is_leaders.append_value(is_leader);
+    }
+}
+
+impl TryFrom<InformationSchemaReplicasBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaReplicasBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaReplicasBuilder {
+            mut tenant_names,
+            mut database_names,
+            mut bucket_ids,
+            mut replica_set_ids,
+            mut vnode_ids,
+            mut node_ids,
+            mut vnode_statuses,
+            mut is_leaders,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            REPLICAS_SCHEMA.clone(),
+            vec![
+                Arc::new(tenant_names.finish()),
+                Arc::new(database_names.finish()),
+                Arc::new(bucket_ids.finish()),
+                Arc::new(replica_set_ids.finish()),
+                Arc::new(vnode_ids.finish()),
+                Arc::new(node_ids.finish()),
+                Arc::new(vnode_statuses.finish()),
+                Arc::new(is_leaders.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
@@ -0,0 +1,111 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::Result as DFResult;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+use models::oid::Identifier;
+
+use crate::alert::state::ALERTS_SCHEMA;
+use crate::alert::AlertManagerRef;
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_ALERTS: &str = "ALERTS";
+
+/// This view displays the pending, firing and recently resolved alerts of the alert rules
+/// evaluated by the current node, only for the rules of the databases on which the current
+/// user has Read permission or higher.
+pub struct AlertsFactory {
+    alert_manager: AlertManagerRef,
+}
+
+impl AlertsFactory {
+    pub fn new(alert_manager: AlertManagerRef) -> Self {
+        Self { alert_manager }
+    }
+}
+
+impl InformationSchemaTableFactory for AlertsFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_ALERTS
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationAlertsTable::new(
+            self.alert_manager.clone(),
+            metadata,
+            user.clone(),
+        ))
+    }
+}
+
+pub struct InformationAlertsTable {
+    user: User,
+    alert_manager: AlertManagerRef,
+    metadata: MetaClientRef,
+}
+
+impl InformationAlertsTable {
+    pub fn new(alert_manager: AlertManagerRef, metadata: MetaClientRef, user: User) -> Self {
+        Self {
+            user,
+            alert_manager,
+            metadata,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationAlertsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        ALERTS_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let tenant = self.metadata.tenant();
+        let tenant_id = tenant.id();
+
+        let states = self.alert_manager.alert_states(tenant.name())?;
+        let batches = states
+            .into_iter()
+            // Check if the current user has at least read permission on the db of the rule
+            .filter(|(rule, _)| self.user.can_read_database(*tenant_id, &rule.database))
+            .flat_map(|(_, batches)| batches)
+            .collect::<Vec<_>>();
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[batches],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
@@ -0,0 +1,162 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::array::{BooleanArray, StringArray};
+use datafusion::arrow::compute::filter_record_batch;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+use models::oid::{Identifier, Oid};
+use trace::warn;
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::queries::QUERY_SCHEMA;
+use crate::metadata::information_schema_provider::factory::queries::{
+    filter_running_queries, queries_record_batch,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_CLUSTER_QUERIES: &str = "CLUSTER_QUERIES";
+
+/// This view is the same as `QUERIES`, but shows the queries running on all the data nodes
+/// of the cluster, the node running the query is in the column `node_id`.
+///
+/// The visibility of the records is the same as `QUERIES`.
+pub struct ClusterQueriesFactory {
+    coord: CoordinatorRef,
+}
+
+impl ClusterQueriesFactory {
+    pub fn new(coord: CoordinatorRef) -> Self {
+        Self { coord }
+    }
+}
+
+impl InformationSchemaTableFactory for ClusterQueriesFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_CLUSTER_QUERIES
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationClusterQueriesTable {
+            user: user.clone(),
+            query_tracker,
+            metadata,
+            coord: self.coord.clone(),
+        })
+    }
+}
+
+pub struct InformationClusterQueriesTable {
+    user: User,
+    query_tracker: Arc<QueryTracker>,
+    metadata: MetaClientRef,
+    coord: CoordinatorRef,
+}
+
+#[async_trait]
+impl TableProvider for InformationClusterQueriesTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        QUERY_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let user_id = *self.user.desc().id();
+        let tenant_id = *self.metadata.tenant().id();
+
+        let local_queries = filter_running_queries(
+            user_id,
+            tenant_id,
+            &self.user,
+            self.query_tracker.running_queries(),
+        );
+        let mut batches = vec![queries_record_batch(self.coord.node_id(), &local_queries)?];
+
+        let remote_batches = self
+            .coord
+            .cluster_queries()
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        for batch in remote_batches {
+            if batch.schema().fields() != QUERY_SCHEMA.fields() {
+                warn!("Ignore queries of a node with a different schema");
+                continue;
+            }
+            batches.push(filter_queries_batch(
+                user_id, tenant_id, &self.user, &batch,
+            )?);
+        }
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[batches],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
+
+/// Same as [`filter_running_queries`], but for the rows fetched from other nodes
+fn filter_queries_batch(
+    user_id: Oid,
+    tenant_id: Oid,
+    user: &User,
+    batch: &RecordBatch,
+) -> DFResult<RecordBatch> {
+    if user.desc().is_admin() {
+        return Ok(batch.clone());
+    }
+
+    let string_column = |name: &str| -> DFResult<StringArray> {
+        let index = QUERY_SCHEMA.index_of(name)?;
+        batch
+            .column(index)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .cloned()
+            .ok_or_else(|| DataFusionError::Internal(format!("column {name} is not a string")))
+    };
+    let tenant_ids = string_column("tenant_id")?;
+    let user_ids = string_column("user_id")?;
+
+    // The tenant owner sees all queries of the tenant, others only see their own queries
+    let only_self = !user.can_access_system(tenant_id);
+    let tenant_id = tenant_id.to_string();
+    let user_id = user_id.to_string();
+    let predicate = (0..batch.num_rows())
+        .map(|i| {
+            Some(tenant_ids.value(i) == tenant_id && (!only_self || user_ids.value(i) == user_id))
+        })
+        .collect::<BooleanArray>();
+
+    Ok(filter_record_batch(batch, &predicate)?)
+}
@@ -1,9 +1,13 @@
+pub mod alerts;
+pub mod cluster_queries;
 pub mod columns;
 pub mod database_privileges;
 pub mod databases;
 pub mod enabled_roles;
 pub mod members;
 pub mod queries;
+pub mod replicas;
 pub mod resource_status;
 pub mod roles;
+pub mod storage_stats;
 pub mod tables;
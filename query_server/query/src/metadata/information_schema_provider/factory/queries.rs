@@ -4,6 +4,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
 use datafusion::datasource::{TableProvider, TableType};
 use datafusion::execution::context::SessionState;
 use datafusion::logical_expr::logical_plan::AggWithGrouping;
@@ -28,7 +29,15 @@ pub const INFORMATION_SCHEMA_QUERIES: &str = "QUERIES";
 /// All records of this view are visible to the Owner of the current tenant.
 ///
 /// For non-Owner members, only the SQL submitted by the current member is displayed.
-pub struct QueriesFactory {}
+pub struct QueriesFactory {
+    node_id: u64,
+}
+
+impl QueriesFactory {
+    pub fn new(node_id: u64) -> Self {
+        Self { node_id }
+    }
+}
 
 impl InformationSchemaTableFactory for QueriesFactory {
     fn table_name(&self) -> &'static str {
@@ -42,6 +51,7 @@ impl InformationSchemaTableFactory for QueriesFactory {
         query_tracker: Arc<QueryTracker>,
     ) -> Arc<dyn TableProvider> {
         Arc::new(InformationQueriesTable::new(
+            self.node_id,
             query_tracker,
             metadata,
             user.clone(),
@@ -50,14 +60,21 @@ impl InformationSchemaTableFactory for QueriesFactory {
 }
 
 pub struct InformationQueriesTable {
+    node_id: u64,
     user: User,
     query_tracker: Arc<QueryTracker>,
     metadata: MetaClientRef,
 }
 
 impl InformationQueriesTable {
-    pub fn new(query_tracker: Arc<QueryTracker>, metadata: MetaClientRef, user: User) -> Self {
+    pub fn new(
+        node_id: u64,
+        query_tracker: Arc<QueryTracker>,
+        metadata: MetaClientRef,
+        user: User,
+    ) -> Self {
         Self {
+            node_id,
             user,
             query_tracker,
             metadata,
@@ -87,45 +104,12 @@ impl TableProvider for InformationQueriesTable {
         _agg_with_grouping: Option<&AggWithGrouping>,
         _limit: Option<usize>,
     ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
-        let mut builder = InformationSchemaQueriesBuilder::default();
-
         let user_id = *self.user.desc().id();
         let tenant_id = *self.metadata.tenant().id();
         let all_queries = self.query_tracker.running_queries();
 
         let running_queries = filter_running_queries(user_id, tenant_id, &self.user, all_queries);
-
-        for query in running_queries {
-            let info = query.info();
-            let status = query.status();
-
-            let query_id = info.query_id().to_string();
-            let query_text = info.query();
-            let user_id = info.user_id().to_string();
-            let user_name = info.user_name();
-            let tenant_id = info.tenant_id().to_string();
-            let tenant_name = info.tenant_name();
-
-            let state = status.query_state();
-            let duration = status.duration().as_secs_f64();
-            let processed_count = status.processed_count();
-            let error_count = status.error_count();
-
-            builder.append_row(
-                query_id,
-                query.query_type().to_string(),
-                query_text,
-                user_id,
-                user_name,
-                tenant_id,
-                tenant_name,
-                state,
-                duration,
-                processed_count,
-                error_count,
-            );
-        }
-        let rb: RecordBatch = builder.try_into()?;
+        let rb = queries_record_batch(self.node_id, &running_queries)?;
 
         Ok(Arc::new(MemoryExec::try_new(
             &[vec![rb]],
@@ -135,7 +119,16 @@ impl TableProvider for InformationQueriesTable {
     }
 }
 
-fn filter_running_queries(
+/// Builds the rows of `information_schema.queries` for the queries running on the node `node_id`
+pub fn queries_record_batch(node_id: u64, queries: &[QueryExecutionRef]) -> DFResult<RecordBatch> {
+    let mut builder = InformationSchemaQueriesBuilder::default();
+    for query in queries {
+        builder.append_query(node_id, query);
+    }
+    builder.try_into()
+}
+
+pub(crate) fn filter_running_queries(
     user_id: Oid,
     tenant_id: Oid,
     user: &User,
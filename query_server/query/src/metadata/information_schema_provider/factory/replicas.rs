@@ -0,0 +1,123 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::prelude::Expr;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+use models::oid::Identifier;
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::replicas::{
+    InformationSchemaReplicasBuilder, REPLICAS_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_REPLICAS: &str = "REPLICAS";
+
+/// This view displays the vnodes of the replication sets and their leaders,
+/// only for the databases on which the current user has Read permission or higher.
+pub struct ReplicasFactory {}
+
+impl InformationSchemaTableFactory for ReplicasFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_REPLICAS
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationReplicasTable::new(metadata, user.clone()))
+    }
+}
+
+pub struct InformationReplicasTable {
+    user: User,
+    metadata: MetaClientRef,
+}
+
+impl InformationReplicasTable {
+    pub fn new(metadata: MetaClientRef, user: User) -> Self {
+        Self { user, metadata }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationReplicasTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        REPLICAS_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaReplicasBuilder::default();
+
+        let dbs = self
+            .metadata
+            .list_databases()
+            .map_err(|e| DataFusionError::Internal(format!("Failed to list databases: {}", e)))?;
+        let tenant = self.metadata.tenant();
+        let tenant_id = tenant.id();
+        let tenant_name = tenant.name();
+
+        for (db, info) in dbs {
+            // Check if the current user has at least read permission on this db, skip if not
+            if !self.user.can_read_database(*tenant_id, &db) {
+                continue;
+            }
+
+            if info.is_hidden() {
+                continue;
+            }
+
+            for bucket in info.buckets.iter() {
+                for replica in bucket.shard_group.iter() {
+                    for vnode in replica.vnodes.iter() {
+                        builder.append_row(
+                            tenant_name,
+                            &db,
+                            bucket.id,
+                            replica.id,
+                            vnode.id,
+                            vnode.node_id,
+                            format!("{:?}", vnode.status),
+                            vnode.id == replica.leader_vnode_id,
+                        );
+                    }
+                }
+            }
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
@@ -0,0 +1,181 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::array::{BooleanArray, StringArray};
+use datafusion::arrow::compute::filter_record_batch;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+use models::oid::Identifier;
+use trace::warn;
+use tskv::stats::{StorageStatsKind, STATS_DATABASE_NAME, STATS_TENANT_NAME};
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_VNODE_STORAGE: &str = "VNODE_STORAGE";
+pub const INFORMATION_SCHEMA_TSM_FILES: &str = "TSM_FILES";
+pub const INFORMATION_SCHEMA_COMPACTION_HISTORY: &str = "COMPACTION_HISTORY";
+pub const INFORMATION_SCHEMA_FLUSH_HISTORY: &str = "FLUSH_HISTORY";
+pub const INFORMATION_SCHEMA_VNODE_WALS: &str = "VNODE_WALS";
+pub const INFORMATION_SCHEMA_TSM_READER_CACHE: &str = "TSM_READER_CACHE";
+
+/// These views display the storage statistics collected from the storage engine of all data nodes:
+///
+/// - `VNODE_STORAGE`: disk storage, cache size and file counts of the vnodes.
+/// - `TSM_FILES`: the tsm and delta files of the vnodes per level.
+/// - `COMPACTION_HISTORY`: the latest compactions of the vnodes.
+/// - `FLUSH_HISTORY`: the latest flushes of the vnodes.
+/// - `VNODE_WALS`: count and size of the wal files of the vnodes.
+/// - `TSM_READER_CACHE`: usage and hit rate of the tsm reader cache of the vnodes.
+///
+/// Only the rows of the databases on which the current user has Read permission or higher
+/// are displayed.
+pub struct StorageStatsFactory {
+    table_name: &'static str,
+    kind: StorageStatsKind,
+    coord: CoordinatorRef,
+}
+
+impl StorageStatsFactory {
+    pub fn all(coord: CoordinatorRef) -> Vec<Self> {
+        [
+            (INFORMATION_SCHEMA_VNODE_STORAGE, StorageStatsKind::Vnodes),
+            (INFORMATION_SCHEMA_TSM_FILES, StorageStatsKind::TsmFiles),
+            (
+                INFORMATION_SCHEMA_COMPACTION_HISTORY,
+                StorageStatsKind::Compactions,
+            ),
+            (INFORMATION_SCHEMA_FLUSH_HISTORY, StorageStatsKind::Flushes),
+            (INFORMATION_SCHEMA_VNODE_WALS, StorageStatsKind::Wals),
+            (
+                INFORMATION_SCHEMA_TSM_READER_CACHE,
+                StorageStatsKind::TsmReaderCaches,
+            ),
+        ]
+        .into_iter()
+        .map(|(table_name, kind)| Self {
+            table_name,
+            kind,
+            coord: coord.clone(),
+        })
+        .collect()
+    }
+}
+
+impl InformationSchemaTableFactory for StorageStatsFactory {
+    fn table_name(&self) -> &'static str {
+        self.table_name
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationStorageStatsTable {
+            user: user.clone(),
+            kind: self.kind,
+            metadata,
+            coord: self.coord.clone(),
+        })
+    }
+}
+
+pub struct InformationStorageStatsTable {
+    user: User,
+    kind: StorageStatsKind,
+    metadata: MetaClientRef,
+    coord: CoordinatorRef,
+}
+
+#[async_trait]
+impl TableProvider for InformationStorageStatsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.kind.schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let schema = self.schema();
+        let node_batches = self
+            .coord
+            .cluster_storage_stats(self.kind)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        let mut batches = vec![];
+        for batch in node_batches {
+            if batch.schema().fields() != schema.fields() {
+                warn!(
+                    "Ignore storage statistics {} of a node with a different schema",
+                    self.kind
+                );
+                continue;
+            }
+            batches.push(self.filter_readable_databases(&batch)?);
+        }
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[batches],
+            schema,
+            projection.cloned(),
+        )?))
+    }
+}
+
+impl InformationStorageStatsTable {
+    /// Keeps the rows of the current tenant's databases that the user can read
+    fn filter_readable_databases(&self, batch: &RecordBatch) -> DFResult<RecordBatch> {
+        let string_column = |name: &str| -> DFResult<StringArray> {
+            let index = batch.schema().index_of(name)?;
+            batch
+                .column(index)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .cloned()
+                .ok_or_else(|| DataFusionError::Internal(format!("column {name} is not a string")))
+        };
+        let tenant_names = string_column(STATS_TENANT_NAME)?;
+        let database_names = string_column(STATS_DATABASE_NAME)?;
+
+        let tenant = self.metadata.tenant();
+        let predicate = (0..batch.num_rows())
+            .map(|i| {
+                Some(
+                    tenant_names.value(i) == tenant.name()
+                        && self
+                            .user
+                            .can_read_database(*tenant.id(), database_names.value(i)),
+                )
+            })
+            .collect::<BooleanArray>();
+
+        Ok(filter_record_batch(batch, &predicate)?)
+    }
+}
@@ -14,24 +14,31 @@ pub use builder::tables::{
     TABLES_TABLE_DATABASE, TABLES_TABLE_ENGINE, TABLES_TABLE_NAME, TABLES_TABLE_OPTIONS,
     TABLES_TABLE_TENANT, TABLES_TABLE_TYPE,
 };
+use coordinator::service::CoordinatorRef;
 use datafusion::datasource::TableProvider;
+pub use factory::cluster_queries::INFORMATION_SCHEMA_CLUSTER_QUERIES;
 pub use factory::columns::INFORMATION_SCHEMA_COLUMNS;
 pub use factory::databases::INFORMATION_SCHEMA_DATABASES;
-pub use factory::queries::INFORMATION_SCHEMA_QUERIES;
+pub use factory::queries::{queries_record_batch, INFORMATION_SCHEMA_QUERIES};
 pub use factory::tables::INFORMATION_SCHEMA_TABLES;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
 use models::auth::user::User;
 
+use self::factory::alerts::AlertsFactory;
+use self::factory::cluster_queries::ClusterQueriesFactory;
 use self::factory::columns::ColumnsFactory;
 use self::factory::database_privileges::DatabasePrivilegesFactory;
 use self::factory::databases::DatabasesFactory;
 use self::factory::enabled_roles::EnabledRolesFactory;
 use self::factory::members::MembersFactory;
 use self::factory::queries::QueriesFactory;
+use self::factory::replicas::ReplicasFactory;
 use self::factory::resource_status::InformationSchemaResourceStatusFactory;
 use self::factory::roles::RolesFactory;
+use self::factory::storage_stats::StorageStatsFactory;
 use super::INFORMATION_SCHEMA;
+use crate::alert::AlertManagerRef;
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::metadata::information_schema_provider::factory::tables::TablesFactory;
 
@@ -45,7 +52,11 @@ pub struct InformationSchemaProvider {
 }
 
 impl InformationSchemaProvider {
-    pub fn new(query_tracker: Arc<QueryTracker>) -> Self {
+    pub fn new(
+        query_tracker: Arc<QueryTracker>,
+        coord: CoordinatorRef,
+        alert_manager: AlertManagerRef,
+    ) -> Self {
         let mut provider = Self {
             query_tracker,
             table_factories: Default::default(),
@@ -58,8 +69,14 @@ impl InformationSchemaProvider {
         provider.register_table_factory(Box::new(RolesFactory {}));
         provider.register_table_factory(Box::new(DatabasePrivilegesFactory {}));
         provider.register_table_factory(Box::new(MembersFactory {}));
-        provider.register_table_factory(Box::new(QueriesFactory {}));
+        provider.register_table_factory(Box::new(QueriesFactory::new(coord.node_id())));
+        provider.register_table_factory(Box::new(ClusterQueriesFactory::new(coord.clone())));
         provider.register_table_factory(Box::new(InformationSchemaResourceStatusFactory {}));
+        provider.register_table_factory(Box::new(ReplicasFactory {}));
+        provider.register_table_factory(Box::new(AlertsFactory::new(alert_manager)));
+        for factory in StorageStatsFactory::all(coord) {
+            provider.register_table_factory(Box::new(factory));
+        }
 
         provider
     }
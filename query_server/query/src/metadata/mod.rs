@@ -14,13 +14,13 @@ use datafusion::sql::planner::ContextProvider;
 use datafusion::sql::TableReference;
 use datafusion::variable::{VarProvider, VarType};
 pub use information_schema_provider::{
-    COLUMNS_COLUMN_NAME, COLUMNS_COLUMN_TYPE, COLUMNS_COMPRESSION_CODEC, COLUMNS_DATABASE_NAME,
-    COLUMNS_DATA_TYPE, COLUMNS_TABLE_NAME, DATABASES_DATABASE_NAME, DATABASES_PRECISION,
-    DATABASES_REPLICA, DATABASES_SHARD, DATABASES_TENANT_NAME, DATABASES_TTL,
-    DATABASES_VNODE_DURATION, INFORMATION_SCHEMA_COLUMNS, INFORMATION_SCHEMA_DATABASES,
-    INFORMATION_SCHEMA_QUERIES, INFORMATION_SCHEMA_TABLES, TABLES_TABLE_DATABASE,
-    TABLES_TABLE_ENGINE, TABLES_TABLE_NAME, TABLES_TABLE_OPTIONS, TABLES_TABLE_TENANT,
-    TABLES_TABLE_TYPE,
+    queries_record_batch, COLUMNS_COLUMN_NAME, COLUMNS_COLUMN_TYPE, COLUMNS_COMPRESSION_CODEC,
+    COLUMNS_DATABASE_NAME, COLUMNS_DATA_TYPE, COLUMNS_TABLE_NAME, DATABASES_DATABASE_NAME,
+    DATABASES_PRECISION, DATABASES_REPLICA, DATABASES_SHARD, DATABASES_TENANT_NAME, DATABASES_TTL,
+    DATABASES_VNODE_DURATION, INFORMATION_SCHEMA_CLUSTER_QUERIES, INFORMATION_SCHEMA_COLUMNS,
+    INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_QUERIES, INFORMATION_SCHEMA_TABLES,
+    TABLES_TABLE_DATABASE, TABLES_TABLE_ENGINE, TABLES_TABLE_NAME, TABLES_TABLE_OPTIONS,
+    TABLES_TABLE_TENANT, TABLES_TABLE_TYPE,
 };
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
@@ -34,6 +34,7 @@ use spi::query::session::SessionCtx;
 pub use self::base_table::BaseTableProvider;
 use self::cluster_schema_provider::ClusterSchemaProvider;
 use self::information_schema_provider::InformationSchemaProvider;
+use crate::alert::AlertManagerRef;
 use crate::data_source::table_source::{TableHandle, TableSourceAdapter};
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::metadata::usage_schema_provider::UsageSchemaProvider;
@@ -100,6 +101,7 @@ impl MetadataProvider {
         default_table_provider: TableHandleProviderRef,
         func_manager: FuncMetaManagerRef,
         query_tracker: Arc<QueryTracker>,
+        alert_manager: AlertManagerRef,
         session: SessionCtx,
     ) -> Self {
         Self {
@@ -110,7 +112,11 @@ impl MetadataProvider {
             session,
             meta_client,
             func_manager,
-            information_schema_provider: InformationSchemaProvider::new(query_tracker),
+            information_schema_provider: InformationSchemaProvider::new(
+                query_tracker,
+                coord.clone(),
+                alert_manager,
+            ),
             cluster_schema_provider: ClusterSchemaProvider::new(),
             usage_schema_provider: UsageSchemaProvider::new(default_table_provider),
             access_databases: Default::default(),
@@ -7,7 +7,9 @@ use spi::query::session::SessionCtx;
 use spi::Result;
 
 use crate::extension::analyse::initial_plan_checker::InitialPlanChecker;
+use crate::extension::analyse::transform_asof_join::TransformAsofJoinRule;
 use crate::extension::analyse::transform_bottom_func_to_topk_node::TransformBottomFuncToTopkNodeRule;
+use crate::extension::analyse::transform_event_window::TransformEventWindowRule;
 use crate::extension::analyse::transform_gapfill::TransformGapFill;
 use crate::extension::analyse::transform_time_window::TransformTimeWindowRule;
 use crate::extension::analyse::transform_topk_func_to_topk_node::TransformTopkFuncToTopkNodeRule;
@@ -28,6 +30,8 @@ impl DefaultAnalyzer {
         rules.push(Arc::new(TransformTopkFuncToTopkNodeRule {}));
         rules.push(Arc::new(TransformGapFill::new()));
         rules.push(Arc::new(TransformTimeWindowRule {}));
+        rules.push(Arc::new(TransformEventWindowRule {}));
+        rules.push(Arc::new(TransformAsofJoinRule {}));
 
         Self { inner: analyzer }
     }
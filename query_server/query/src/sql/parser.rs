@@ -6,23 +6,25 @@ use std::str::FromStr;
 use datafusion::common::parsers::CompressionTypeVariant;
 use datafusion::sql::parser::CreateExternalTable;
 use datafusion::sql::sqlparser::ast::{
-    DataType, Expr, Ident, ObjectName, Offset, OrderByExpr, SqlOption, TableFactor,
+    DataType, Expr, Ident, ObjectName, Offset, OrderByExpr, Query, SqlOption, TableFactor,
 };
 use datafusion::sql::sqlparser::dialect::keywords::Keyword;
 use datafusion::sql::sqlparser::dialect::Dialect;
 use datafusion::sql::sqlparser::parser::{IsOptional, Parser, ParserError};
-use datafusion::sql::sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer};
+use datafusion::sql::sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer, Whitespace};
 use models::codec::Encoding;
 use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use snafu::ResultExt;
 use spi::query::ast::{
-    self, parse_string_value, Action, AlterDatabase, AlterTable, AlterTableAction, AlterTenant,
-    AlterTenantOperation, AlterUser, AlterUserOperation, ChecksumGroup, ColumnOption, CompactVnode,
-    CopyIntoLocation, CopyIntoTable, CopyTarget, CopyVnode, CreateDatabase, CreateRole,
-    CreateStream, CreateTable, CreateTenant, CreateUser, DatabaseOptions, DescribeDatabase,
+    self, parse_bool_value, parse_string_value, Action, AlterDatabase, AlterTable,
+    AlterTableAction, AlterTenant, AlterTenantOperation, AlterUser, AlterUserOperation,
+    ChecksumGroup, ColumnOption, CompactVnode, CopyIntoLocation, CopyIntoTable, CopyTarget,
+    CopyVnode, CreateAlertRule, CreateDatabase, CreateIngestionProfile, CreateRole, CreateStream,
+    CreateTable, CreateTenant, CreateUser, DatabaseOptions, DecommissionNode, DescribeDatabase,
     DescribeTable, DropDatabaseObject, DropGlobalObject, DropTenantObject, DropVnode, Explain,
-    ExtStatement, GrantRevoke, MoveVnode, OutputMode, Privilege, RecoverDatabase, RecoverTenant,
-    ShowSeries, ShowTagBody, ShowTagValues, Trigger, UriLocation, With,
+    ExportVnode, ExtStatement, GrantRevoke, ImportVnode, KillClusterQuery, MoveVnode, OutputMode,
+    Privilege, RecoverDatabase, RecoverTenant, ShowSeries, ShowTagBody, ShowTagValues,
+    TableOptions, TransferLeader, Trigger, UriLocation, With,
 };
 use spi::query::logical_planner::{DatabaseObjectType, GlobalObjectType, TenantObjectType};
 use spi::query::parser::Parser as CnosdbParser;
@@ -30,6 +32,7 @@ use spi::ParserSnafu;
 use trace::debug;
 
 use super::dialect::CnosDBDialect;
+use crate::extension::expr::ASOF_MATCH_CONDITION;
 
 // support tag token
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -93,6 +96,10 @@ enum CnosKeyWord {
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     CHECKSUM,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    EXPORT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    IMPORT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     STREAM,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     STREAMS,
@@ -114,6 +121,30 @@ enum CnosKeyWord {
     AFTER,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     RECOVER,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    DECOMMISSION,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    TRANSFER,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    LEADER,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    CLUSTER,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    ALERT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    RULE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    EVERY,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    INGESTION,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    PROFILE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    LAST_VALUE_CACHE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    LATE_ARRIVAL_WINDOW,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    LATE_ARRIVAL_POLICY,
 }
 
 impl FromStr for CnosKeyWord {
@@ -146,6 +177,8 @@ impl FromStr for CnosKeyWord {
             "MOVE" => Ok(CnosKeyWord::MOVE),
             "COMPACT" => Ok(CnosKeyWord::COMPACT),
             "CHECKSUM" => Ok(CnosKeyWord::CHECKSUM),
+            "EXPORT" => Ok(CnosKeyWord::EXPORT),
+            "IMPORT" => Ok(CnosKeyWord::IMPORT),
             "STREAM" => Ok(CnosKeyWord::STREAM),
             "STREAMS" => Ok(CnosKeyWord::STREAMS),
             "TRIGGER" => Ok(CnosKeyWord::TRIGGER),
@@ -157,6 +190,18 @@ impl FromStr for CnosKeyWord {
             "UNSET" => Ok(CnosKeyWord::UNSET),
             "AFTER" => Ok(CnosKeyWord::AFTER),
             "RECOVER" => Ok(CnosKeyWord::RECOVER),
+            "DECOMMISSION" => Ok(CnosKeyWord::DECOMMISSION),
+            "TRANSFER" => Ok(CnosKeyWord::TRANSFER),
+            "LEADER" => Ok(CnosKeyWord::LEADER),
+            "CLUSTER" => Ok(CnosKeyWord::CLUSTER),
+            "ALERT" => Ok(CnosKeyWord::ALERT),
+            "RULE" => Ok(CnosKeyWord::RULE),
+            "EVERY" => Ok(CnosKeyWord::EVERY),
+            "INGESTION" => Ok(CnosKeyWord::INGESTION),
+            "PROFILE" => Ok(CnosKeyWord::PROFILE),
+            "LAST_VALUE_CACHE" => Ok(CnosKeyWord::LAST_VALUE_CACHE),
+            "LATE_ARRIVAL_WINDOW" => Ok(CnosKeyWord::LATE_ARRIVAL_WINDOW),
+            "LATE_ARRIVAL_POLICY" => Ok(CnosKeyWord::LATE_ARRIVAL_POLICY),
             _ => Err(ParserError::ParserError(format!(
                 "fail parse {} to CnosKeyWord",
                 s
@@ -197,7 +242,7 @@ impl<'a> ExtParser<'a> {
     /// Parse the specified tokens with dialect
     fn new_with_dialect(sql: &str, dialect: &'a dyn Dialect) -> Result<Self> {
         let mut tokenizer = Tokenizer::new(dialect, sql);
-        let tokens = tokenizer.tokenize()?;
+        let tokens = rewrite_asof_join(tokenizer.tokenize()?)?;
         Ok(ExtParser {
             parser: Parser::new(dialect).with_tokens(tokens),
         })
@@ -285,6 +330,17 @@ impl<'a> ExtParser<'a> {
                     let update_ast = self.parser.parse_update()?;
                     Ok(ExtStatement::SqlStatement(Box::new(update_ast)))
                 }
+                Keyword::KILL => {
+                    self.parser.next_token();
+                    if self.parse_cnos_keyword(CnosKeyWord::CLUSTER) {
+                        self.parse_kill_cluster_query()
+                    } else {
+                        self.parser.prev_token();
+                        Ok(ExtStatement::SqlStatement(Box::new(
+                            self.parser.parse_statement()?,
+                        )))
+                    }
+                }
                 _ => {
                     if let Ok(word) = CnosKeyWord::from_str(&w.to_string()) {
                         return match word {
@@ -300,6 +356,14 @@ impl<'a> ExtParser<'a> {
                                 self.parser.next_token();
                                 self.parse_checksum()
                             }
+                            CnosKeyWord::EXPORT => {
+                                self.parser.next_token();
+                                self.parse_export()
+                            }
+                            CnosKeyWord::IMPORT => {
+                                self.parser.next_token();
+                                self.parse_import()
+                            }
                             CnosKeyWord::RECOVER => {
                                 self.parser.next_token();
                                 self.parse_recover()
@@ -344,6 +408,12 @@ impl<'a> ExtParser<'a> {
             }
         } else if self.parse_cnos_keyword(CnosKeyWord::QUERIES) {
             self.parse_show_queries()
+        } else if self.parse_cnos_keyword(CnosKeyWord::CLUSTER) {
+            if self.parse_cnos_keyword(CnosKeyWord::QUERIES) {
+                Ok(ExtStatement::ShowClusterQueries)
+            } else {
+                self.expected("QUERIES", self.parser.peek_token())
+            }
         } else if self.parse_cnos_keyword(CnosKeyWord::STREAMS) {
             let verbose = self
                 .parser
@@ -497,6 +567,15 @@ impl<'a> ExtParser<'a> {
         Ok(ExtStatement::ShowQueries)
     }
 
+    /// Parse `KILL CLUSTER QUERY <query_id>`, `KILL CLUSTER` is consumed
+    fn parse_kill_cluster_query(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::QUERY)?;
+        let query_id = self.parse_number::<u64>()?;
+        Ok(ExtStatement::KillClusterQuery(KillClusterQuery {
+            query_id,
+        }))
+    }
+
     fn parse_show_databases(&mut self) -> Result<ExtStatement> {
         Ok(ExtStatement::ShowDatabases())
     }
@@ -544,8 +623,39 @@ impl<'a> ExtParser<'a> {
             self.parse_alter_tenant()
         } else if self.parser.parse_keyword(Keyword::USER) {
             self.parse_alter_user()
+        } else if self.parse_cnos_keyword(CnosKeyWord::NODE) {
+            self.parse_alter_node()
+        } else if self.parse_cnos_keyword(CnosKeyWord::REPLICA) {
+            self.parse_alter_replica_set()
+        } else {
+            self.expected(
+                "TABLE/DATABASE/TENANT/USER/NODE/REPLICA",
+                self.parser.peek_token(),
+            )
+        }
+    }
+
+    /// ALTER REPLICA SET <replica_id> TRANSFER LEADER TO <node_id>
+    fn parse_alter_replica_set(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::SET)?;
+        let replica_id = self.parse_number::<ReplicationSetId>()?;
+        self.expect_cnos_keyword(CnosKeyWord::TRANSFER)?;
+        self.expect_cnos_keyword(CnosKeyWord::LEADER)?;
+        self.parser.expect_keyword(Keyword::TO)?;
+        let node_id = self.parse_number::<NodeId>()?;
+
+        Ok(ExtStatement::TransferLeader(TransferLeader {
+            replica_id,
+            node_id,
+        }))
+    }
+
+    fn parse_alter_node(&mut self) -> Result<ExtStatement> {
+        let node_id = self.parse_number::<NodeId>()?;
+        if self.parse_cnos_keyword(CnosKeyWord::DECOMMISSION) {
+            Ok(ExtStatement::DecommissionNode(DecommissionNode { node_id }))
         } else {
-            self.expected("TABLE/DATABASE/TENANT/USER", self.parser.peek_token())
+            self.expected("DECOMMISSION", self.parser.peek_token())
         }
     }
 
@@ -561,11 +671,31 @@ impl<'a> ExtParser<'a> {
         } else if self.parser.parse_keyword(Keyword::RENAME) {
             let alter_tbl = self.parse_alter_table_rename(table_name)?;
             Ok(ExtStatement::AlterTable(alter_tbl))
+        } else if self.parser.parse_keyword(Keyword::SET) {
+            self.parse_alter_table_set_options(table_name)
         } else {
-            self.expected("ADD or ALTER or DROP or RENAME", self.parser.peek_token())
+            self.expected(
+                "ADD or ALTER or DROP or RENAME or SET",
+                self.parser.peek_token(),
+            )
         }
     }
 
+    fn parse_alter_table_set_options(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
+        let mut options = TableOptions::default();
+        if !self.parse_table_option(&mut options)? {
+            return parser_err!(format!(
+                "expected table option, but found {}",
+                self.parser.peek_token()
+            ));
+        }
+        while self.parse_table_option(&mut options)? {}
+        Ok(ExtStatement::AlterTable(AlterTable {
+            table_name,
+            alter_action: AlterTableAction::SetOptions { options },
+        }))
+    }
+
     fn parse_alter_table_add_column(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
         if self.parse_cnos_keyword(CnosKeyWord::FIELD) {
             let column = self.parse_cnos_field()?;
@@ -866,15 +996,59 @@ impl<'a> ExtParser<'a> {
                 .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
         let table_name = self.parser.parse_object_name()?;
         let columns = self.parse_cnos_columns()?;
+        let partition_by = if self
+            .parser
+            .parse_keywords(&[Keyword::PARTITION, Keyword::BY])
+        {
+            self.parser.expect_token(&Token::LParen)?;
+            let tags = self
+                .parser
+                .parse_comma_separated(Parser::parse_identifier)?;
+            self.parser.expect_token(&Token::RParen)?;
+            tags
+        } else {
+            vec![]
+        };
+        let options = self.parse_table_options()?;
 
         let create = CreateTable {
             name: table_name,
             if_not_exists,
             columns,
+            partition_by,
+            options,
         };
         Ok(ExtStatement::CreateTable(create))
     }
 
+    fn parse_table_options(&mut self) -> Result<TableOptions> {
+        let mut options = TableOptions::default();
+        if self.parser.parse_keyword(Keyword::WITH) {
+            loop {
+                if !self.parse_table_option(&mut options)? {
+                    return Ok(options);
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    fn parse_table_option(&mut self, options: &mut TableOptions) -> Result<bool> {
+        if self.parse_cnos_keyword(CnosKeyWord::LAST_VALUE_CACHE) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.last_value_cache = Some(parse_bool_value(self.parser.parse_value()?)?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::LATE_ARRIVAL_WINDOW) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.late_arrival_window = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::LATE_ARRIVAL_POLICY) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.late_arrival_policy = Some(self.parse_string_value()?);
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
     fn parse_database_options(&mut self) -> Result<DatabaseOptions> {
         if self.parser.parse_keyword(Keyword::WITH) {
             let mut options = DatabaseOptions::default();
@@ -1195,6 +1369,137 @@ impl<'a> ExtParser<'a> {
         }))
     }
 
+    /// e.g.
+    /// CREATE ALERT RULE [IF NOT EXISTS] cpu_high AS
+    ///   SELECT host, avg(usage) AS value FROM cpu WHERE time > now() - interval '5m' GROUP BY host
+    ///   HAVING avg(usage) > 90
+    /// EVERY '1m' [FOR '5m']
+    /// [WITH (
+    ///   SEVERITY = 'critical',
+    ///   LABELS = (team = 'db'),
+    ///   ANNOTATIONS = (summary = 'cpu of {{ $labels.host }} is {{ $value }}'),
+    ///   WEBHOOK = 'http://127.0.0.1:9093/api/v2/alerts'
+    /// )]
+    fn parse_create_alert_rule(&mut self) -> Result<ExtStatement> {
+        self.expect_cnos_keyword(CnosKeyWord::RULE)?;
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parser.parse_identifier()?;
+
+        self.parser.expect_keyword(Keyword::AS)?;
+        let query = self.parse_alert_rule_query()?;
+
+        self.expect_cnos_keyword(CnosKeyWord::EVERY)?;
+        let every = self.parse_string_value()?;
+        let for_duration = if self.parser.parse_keyword(Keyword::FOR) {
+            Some(self.parse_string_value()?)
+        } else {
+            None
+        };
+
+        let mut severity = None;
+        let mut labels = vec![];
+        let mut annotations = vec![];
+        let mut webhook = None;
+        if self.parser.parse_keyword(Keyword::WITH) {
+            self.parser.expect_token(&Token::LParen)?;
+            loop {
+                let option = self.parser.parse_identifier()?;
+                self.parser.expect_token(&Token::Eq)?;
+                match option.value.to_uppercase().as_str() {
+                    "SEVERITY" => severity = Some(self.parse_string_value()?),
+                    "LABELS" => labels = self.parse_string_pairs()?,
+                    "ANNOTATIONS" => annotations = self.parse_string_pairs()?,
+                    "WEBHOOK" => webhook = Some(self.parse_string_value()?),
+                    _ => return self.expected("SEVERITY, LABELS, ANNOTATIONS or WEBHOOK", option),
+                }
+                if !self.parser.consume_token(&Token::Comma) {
+                    break;
+                }
+            }
+            self.parser.expect_token(&Token::RParen)?;
+        }
+
+        Ok(ExtStatement::CreateAlertRule(CreateAlertRule {
+            if_not_exists,
+            name,
+            query,
+            every,
+            for_duration,
+            severity,
+            labels,
+            annotations,
+            webhook,
+        }))
+    }
+
+    /// Parses the query of the alert rule, which ends before the EVERY outside the parentheses.
+    ///
+    /// The tokens are split first, otherwise EVERY would be taken as the alias of the last
+    /// table or column of the query.
+    fn parse_alert_rule_query(&mut self) -> Result<Box<Query>> {
+        let mut tokens = vec![];
+        let mut depth = 0_usize;
+        loop {
+            let token = self.parser.peek_token().token;
+            match &token {
+                Token::EOF => break,
+                Token::SemiColon if depth == 0 => break,
+                token if depth == 0 && is_word(token, "EVERY") => break,
+                Token::LParen => depth += 1,
+                Token::RParen => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            tokens.push(token);
+            self.parser.next_token();
+        }
+
+        let dialect = CnosDBDialect {};
+        let mut parser = Parser::new(&dialect).with_tokens(tokens);
+        let query = parser.parse_query()?;
+        if parser.peek_token() != Token::EOF {
+            return self.expected("EVERY after the query of alert rule", parser.peek_token());
+        }
+
+        Ok(Box::new(query))
+    }
+
+    /// = (key = 'value' [, ...])
+    fn parse_string_pairs(&mut self) -> Result<Vec<(Ident, String)>> {
+        self.parser.expect_token(&Token::LParen)?;
+        let pairs = self.parse_comma_separated(|parser| {
+            let key = parser.parser.parse_identifier()?;
+            parser.parser.expect_token(&Token::Eq)?;
+            let value = parser.parse_string_value()?;
+            Ok((key, value))
+        })?;
+        self.parser.expect_token(&Token::RParen)?;
+
+        Ok(pairs)
+    }
+
+    /// e.g.
+    /// CREATE INGESTION PROFILE [IF NOT EXISTS] sensor_csv
+    /// WITH (format = 'csv', table = 'sensor', time_column = 'ts', tags = 'site,device', fields = 'temp:double')
+    fn parse_create_ingestion_profile(&mut self) -> Result<ExtStatement> {
+        self.expect_cnos_keyword(CnosKeyWord::PROFILE)?;
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parser.parse_identifier()?;
+        self.parser.expect_keyword(Keyword::WITH)?;
+        let options = self.parse_string_pairs()?;
+
+        Ok(ExtStatement::CreateIngestionProfile(
+            CreateIngestionProfile {
+                if_not_exists,
+                name,
+                options,
+            },
+        ))
+    }
+
     /// Parse a SQL CREATE statement
     fn parse_create(&mut self) -> Result<ExtStatement> {
         // Currently only supports the creation of external tables
@@ -1215,6 +1520,10 @@ impl<'a> ExtParser<'a> {
             self.parse_create_role()
         } else if self.parse_cnos_keyword(CnosKeyWord::STREAM) {
             self.parse_create_stream()
+        } else if self.parse_cnos_keyword(CnosKeyWord::ALERT) {
+            self.parse_create_alert_rule()
+        } else if self.parse_cnos_keyword(CnosKeyWord::INGESTION) {
+            self.parse_create_ingestion_profile()
         } else {
             self.expected("an object type after CREATE", self.parser.peek_token())
         }
@@ -1478,9 +1787,29 @@ impl<'a> ExtParser<'a> {
             let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
             let name = self.parser.parse_identifier()?;
             ExtStatement::DropStream(ast::DropStream { if_exist, name })
+        } else if self.parse_cnos_keyword(CnosKeyWord::ALERT) {
+            self.expect_cnos_keyword(CnosKeyWord::RULE)?;
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let object_name = self.parser.parse_identifier()?;
+            ExtStatement::DropTenantObject(DropTenantObject {
+                object_name,
+                if_exist,
+                obj_type: TenantObjectType::AlertRule,
+                after: None,
+            })
+        } else if self.parse_cnos_keyword(CnosKeyWord::INGESTION) {
+            self.expect_cnos_keyword(CnosKeyWord::PROFILE)?;
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let object_name = self.parser.parse_identifier()?;
+            ExtStatement::DropTenantObject(DropTenantObject {
+                object_name,
+                if_exist,
+                obj_type: TenantObjectType::IngestionProfile,
+                after: None,
+            })
         } else {
             return self.expected(
-                "TABLE,DATABASE,TENANT,USER,ROLE,VNODE,STREAM after DROP",
+                "TABLE,DATABASE,TENANT,USER,ROLE,VNODE,STREAM,ALERT RULE,INGESTION PROFILE after DROP",
                 self.parser.peek_token(),
             );
         };
@@ -1517,6 +1846,28 @@ impl<'a> ExtParser<'a> {
         }
     }
 
+    fn parse_export(&mut self) -> Result<ExtStatement> {
+        if self.parse_cnos_keyword(CnosKeyWord::VNODE) {
+            let vnode_id = self.parse_number::<VnodeId>()?;
+            self.parser.expect_keyword(Keyword::TO)?;
+            let path = self.parser.parse_literal_string()?;
+            Ok(ExtStatement::ExportVnode(ExportVnode { vnode_id, path }))
+        } else {
+            parser_err!("expected VNODE, after EXPORT")
+        }
+    }
+
+    fn parse_import(&mut self) -> Result<ExtStatement> {
+        if self.parse_cnos_keyword(CnosKeyWord::VNODE) {
+            let vnode_id = self.parse_number::<VnodeId>()?;
+            self.parser.expect_keyword(Keyword::FROM)?;
+            let path = self.parser.parse_literal_string()?;
+            Ok(ExtStatement::ImportVnode(ImportVnode { vnode_id, path }))
+        } else {
+            parser_err!("expected VNODE, after IMPORT")
+        }
+    }
+
     fn parse_checksum(&mut self) -> Result<ExtStatement> {
         if self.parser.parse_keyword(Keyword::GROUP) {
             let replication_set_id = self.parse_number::<ReplicationSetId>()?;
@@ -1664,6 +2015,173 @@ fn parse_file_type(s: &str) -> Result<String, ParserError> {
     Ok(s.to_uppercase())
 }
 
+/// Rewrite `ASOF JOIN t ON <tags> MATCH_CONDITION(<time condition>)` to
+/// `LEFT JOIN t ON <tags> AND asof_match_condition(<time condition>)`,
+/// the join is converted to AsofJoin by the analyzer.
+///
+/// Only the `ASOF JOIN` following a table factor of a FROM clause is rewritten,
+/// so that `asof` is still usable as an identifier elsewhere.
+fn rewrite_asof_join(tokens: Vec<Token>) -> Result<Vec<Token>> {
+    let mut result = Vec::with_capacity(tokens.len());
+    // Whether the tokens are in a FROM clause, for each level of parentheses
+    let mut in_from = vec![false];
+    // The last token that is not a whitespace
+    let mut prev: Option<&Token> = None;
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let token = &tokens[idx];
+        if in_from.last() == Some(&true)
+            && is_word(token, "ASOF")
+            && ends_table_factor(prev)
+            && is_asof_join(&tokens, idx + 1)
+        {
+            result.push(Token::make_keyword("LEFT"));
+            idx = rewrite_asof_join_constraint(&tokens, idx + 1, &mut result)?;
+            prev = tokens[..idx]
+                .iter()
+                .rev()
+                .find(|t| !matches!(t, Token::Whitespace(_)));
+            continue;
+        }
+        match token {
+            Token::LParen => in_from.push(false),
+            Token::RParen if in_from.len() > 1 => {
+                in_from.pop();
+            }
+            Token::Word(w) if w.quote_style.is_none() => match w.keyword {
+                Keyword::FROM => *in_from.last_mut().expect("at least one level") = true,
+                Keyword::SELECT
+                | Keyword::WHERE
+                | Keyword::GROUP
+                | Keyword::HAVING
+                | Keyword::ORDER
+                | Keyword::LIMIT
+                | Keyword::OFFSET
+                | Keyword::UNION
+                | Keyword::EXCEPT
+                | Keyword::INTERSECT => *in_from.last_mut().expect("at least one level") = false,
+                _ => {}
+            },
+            _ => {}
+        }
+        if !matches!(token, Token::Whitespace(_)) {
+            prev = Some(token);
+        }
+        result.push(token.clone());
+        idx += 1;
+    }
+    Ok(result)
+}
+
+/// Returns false if the token before `ASOF` expects a table name or an alias,
+/// e.g. `FROM asof JOIN t` joins the table `asof`.
+fn ends_table_factor(prev: Option<&Token>) -> bool {
+    match prev {
+        None | Some(Token::Comma) | Some(Token::LParen) | Some(Token::Period) => false,
+        Some(Token::Word(w)) if w.quote_style.is_none() => !matches!(
+            w.keyword,
+            Keyword::FROM | Keyword::JOIN | Keyword::AS | Keyword::LATERAL | Keyword::ON
+        ),
+        Some(_) => true,
+    }
+}
+
+fn is_word(token: &Token, value: &str) -> bool {
+    matches!(token, Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(value))
+}
+
+fn is_asof_join(tokens: &[Token], start: usize) -> bool {
+    tokens[start..]
+        .iter()
+        .find(|t| !matches!(t, Token::Whitespace(_)))
+        .map(|t| matches!(t, Token::Word(w) if w.keyword == Keyword::JOIN))
+        .unwrap_or(false)
+}
+
+/// Copy the tokens of the asof join clause starting at `start` to `result`,
+/// returns the position of the first token after the clause.
+fn rewrite_asof_join_constraint(
+    tokens: &[Token],
+    start: usize,
+    result: &mut Vec<Token>,
+) -> Result<usize> {
+    let mut idx = start;
+    let mut depth = 0;
+    let mut join_seen = false;
+    let mut on_seen = false;
+    let mut match_seen = false;
+
+    while idx < tokens.len() {
+        let token = &tokens[idx];
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 0 => break,
+            Token::RParen => depth -= 1,
+            Token::SemiColon | Token::EOF | Token::Comma if depth == 0 => break,
+            Token::Word(w) if depth == 0 && w.keyword == Keyword::JOIN && !join_seen => {
+                join_seen = true;
+            }
+            Token::Word(w) if depth == 0 && w.keyword == Keyword::ON => {
+                // ON after MATCH_CONDITION
+                result.push(if on_seen {
+                    Token::make_keyword("AND")
+                } else {
+                    token.clone()
+                });
+                on_seen = true;
+                idx += 1;
+                continue;
+            }
+            Token::Word(_) if depth == 0 && is_word(token, "MATCH_CONDITION") => {
+                if match_seen {
+                    return parser_err!("Duplicate MATCH_CONDITION of ASOF JOIN");
+                }
+                result.push(Token::make_keyword(if on_seen { "AND" } else { "ON" }));
+                result.push(Token::Whitespace(Whitespace::Space));
+                result.push(Token::make_word(ASOF_MATCH_CONDITION, None));
+                on_seen = true;
+                match_seen = true;
+                idx += 1;
+                continue;
+            }
+            Token::Word(w)
+                if depth == 0
+                    && matches!(
+                        w.keyword,
+                        Keyword::WHERE
+                            | Keyword::GROUP
+                            | Keyword::HAVING
+                            | Keyword::ORDER
+                            | Keyword::LIMIT
+                            | Keyword::OFFSET
+                            | Keyword::UNION
+                            | Keyword::EXCEPT
+                            | Keyword::INTERSECT
+                            | Keyword::JOIN
+                            | Keyword::INNER
+                            | Keyword::LEFT
+                            | Keyword::RIGHT
+                            | Keyword::FULL
+                            | Keyword::CROSS
+                            | Keyword::NATURAL
+                    ) =>
+            {
+                break
+            }
+            Token::Word(_) if depth == 0 && is_word(token, "ASOF") => break,
+            _ => {}
+        }
+        result.push(token.clone());
+        idx += 1;
+    }
+
+    if !match_seen {
+        return parser_err!("Expected MATCH_CONDITION after ASOF JOIN");
+    }
+
+    Ok(idx)
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::Deref;
@@ -1912,7 +2430,9 @@ mod tests {
                     is_tag: false,
                     data_type: DataType::BigInt(None),
                     encoding: None
-                }]
+                }],
+                partition_by: vec![],
+                options: Default::default(),
             })
         );
 
@@ -1936,6 +2456,7 @@ mod tests {
                 name,
                 if_not_exists,
                 columns,
+                ..
             }) => {
                 assert_eq!(name.to_string(), "test".to_string());
                 assert_eq!(if_not_exists.to_string(), "true".to_string());
@@ -1992,6 +2513,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_table_partition_by() {
+        let sql =
+            "CREATE TABLE test(column1 BIGINT, TAGS(region, host)) PARTITION BY (region, host);";
+        let statement = ExtParser::parse_sql(sql).unwrap().pop_front().unwrap();
+        match statement {
+            ExtStatement::CreateTable(CreateTable { partition_by, .. }) => {
+                assert_eq!(partition_by, vec![Ident::new("region"), Ident::new("host")]);
+            }
+            _ => panic!("expect CreateTable"),
+        }
+
+        let sql = "CREATE TABLE test(column1 BIGINT, TAGS(region)) PARTITION BY ();";
+        ExtParser::parse_sql(sql).err().unwrap();
+    }
+
+    #[test]
+    fn test_create_table_with_options() {
+        let sql = "CREATE TABLE test(column1 BIGINT, TAGS(region)) WITH LAST_VALUE_CACHE = true;";
+        let statement = ExtParser::parse_sql(sql).unwrap().pop_front().unwrap();
+        match statement {
+            ExtStatement::CreateTable(CreateTable { options, .. }) => {
+                assert_eq!(options.last_value_cache, Some(true));
+            }
+            _ => panic!("expect CreateTable"),
+        }
+
+        let sql = "CREATE TABLE test(column1 BIGINT, TAGS(region)) WITH LAST_VALUE_CACHE 'yes';";
+        ExtParser::parse_sql(sql).err().unwrap();
+
+        let sql = "CREATE TABLE test(column1 BIGINT, TAGS(region)) \
+            WITH LATE_ARRIVAL_WINDOW = '1h' LATE_ARRIVAL_POLICY = 'late_tier';";
+        let statement = ExtParser::parse_sql(sql).unwrap().pop_front().unwrap();
+        match statement {
+            ExtStatement::CreateTable(CreateTable { options, .. }) => {
+                assert_eq!(options.late_arrival_window.as_deref(), Some("1h"));
+                assert_eq!(options.late_arrival_policy.as_deref(), Some("late_tier"));
+            }
+            _ => panic!("expect CreateTable"),
+        }
+    }
+
     #[test]
     fn test_insert_values() {
         let sql = "insert public.test(TIME, ta, tb, fa, fb)
@@ -2286,6 +2849,59 @@ mod tests {
                 replication_set_id: 10
             })
         );
+        let sql6 = "alter node 11 decommission";
+        let statement = ExtParser::parse_sql(sql6).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::DecommissionNode(DecommissionNode { node_id: 11 })
+        );
+        assert!(ExtParser::parse_sql("alter node 11").is_err());
+        let sql7 = "alter replica set 12 transfer leader to 2";
+        let statement = ExtParser::parse_sql(sql7).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::TransferLeader(TransferLeader {
+                replica_id: 12,
+                node_id: 2
+            })
+        );
+        assert!(ExtParser::parse_sql("alter replica set 12 transfer leader").is_err());
+        let sql8 = "export vnode 13 to '/tmp/export/13'";
+        let statement = ExtParser::parse_sql(sql8).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ExportVnode(ExportVnode {
+                vnode_id: 13,
+                path: "/tmp/export/13".to_string(),
+            })
+        );
+        let sql9 = "import vnode 14 from '/tmp/export/13'";
+        let statement = ExtParser::parse_sql(sql9).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ImportVnode(ImportVnode {
+                vnode_id: 14,
+                path: "/tmp/export/13".to_string(),
+            })
+        );
+        assert!(ExtParser::parse_sql("import vnode 14 to '/tmp/export/13'").is_err());
+    }
+
+    #[test]
+    fn test_parse_cluster_queries() {
+        let statement = ExtParser::parse_sql("show cluster queries").unwrap();
+        assert_eq!(statement[0], ExtStatement::ShowClusterQueries);
+        assert!(ExtParser::parse_sql("show cluster").is_err());
+
+        let statement = ExtParser::parse_sql("kill cluster query 123").unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::KillClusterQuery(KillClusterQuery { query_id: 123 })
+        );
+        assert!(ExtParser::parse_sql("kill cluster query").is_err());
+
+        let statement = ExtParser::parse_sql("kill query 123").unwrap();
+        assert!(matches!(statement[0], ExtStatement::SqlStatement(_)));
     }
 
     #[test]
@@ -2397,6 +3013,75 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_create_alert_rule() {
+        let result = parse_sql(
+            "CREATE ALERT RULE IF NOT EXISTS cpu_high AS
+            SELECT host, avg(usage) AS value FROM cpu GROUP BY host HAVING avg(usage) > 90
+            EVERY '1m' FOR '5m'
+            WITH (SEVERITY = 'critical', LABELS = (team = 'db'), ANNOTATIONS = (summary = 'cpu of {{ $labels.host }} is high'));",
+        );
+
+        match result {
+            ExtStatement::CreateAlertRule(stmt) => {
+                assert!(stmt.if_not_exists);
+                assert_eq!(stmt.name, Ident::new("cpu_high"));
+                assert_eq!(stmt.every, "1m");
+                assert_eq!(stmt.for_duration.as_deref(), Some("5m"));
+                assert_eq!(stmt.severity.as_deref(), Some("critical"));
+                assert_eq!(stmt.labels, vec![(Ident::new("team"), "db".to_string())]);
+                assert_eq!(stmt.annotations.len(), 1);
+                assert!(stmt.webhook.is_none());
+                assert!(stmt.query.to_string().ends_with("HAVING avg(usage) > 90"));
+            }
+            _ => panic!("expect CreateAlertRule"),
+        }
+    }
+
+    #[test]
+    fn test_drop_alert_rule() {
+        let result = parse_sql("DROP ALERT RULE IF EXISTS cpu_high;");
+
+        match result {
+            ExtStatement::DropTenantObject(stmt) => {
+                assert_eq!(stmt.obj_type, TenantObjectType::AlertRule);
+                assert!(stmt.if_exist);
+                assert_eq!(stmt.object_name, Ident::new("cpu_high"));
+            }
+            _ => panic!("expect DropTenantObject"),
+        }
+    }
+
+    #[test]
+    fn test_create_ingestion_profile() {
+        let result = parse_sql(
+            "CREATE INGESTION PROFILE IF NOT EXISTS sensor_csv
+            WITH (format = 'csv', table = 'sensor', time_column = 'ts', fields = 'temp:double');",
+        );
+
+        match result {
+            ExtStatement::CreateIngestionProfile(stmt) => {
+                assert!(stmt.if_not_exists);
+                assert_eq!(stmt.name, Ident::new("sensor_csv"));
+                assert_eq!(stmt.options.len(), 4);
+                assert_eq!(
+                    stmt.options[3],
+                    (Ident::new("fields"), "temp:double".to_string())
+                );
+            }
+            _ => panic!("expect CreateIngestionProfile"),
+        }
+
+        let result = parse_sql("DROP INGESTION PROFILE sensor_csv;");
+        match result {
+            ExtStatement::DropTenantObject(stmt) => {
+                assert_eq!(stmt.obj_type, TenantObjectType::IngestionProfile);
+                assert!(!stmt.if_exist);
+            }
+            _ => panic!("expect DropTenantObject"),
+        }
+    }
+
     #[test]
     fn test_show_streams() {
         let result = parse_sql("show streams verbose;");
@@ -2489,6 +3174,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_alter_table_set_options() {
+        let statement = parse_sql(
+            "ALTER TABLE TskvTable SET LATE_ARRIVAL_WINDOW='2h' LATE_ARRIVAL_POLICY='late_tier';",
+        );
+
+        match statement {
+            ExtStatement::AlterTable(AlterTable {
+                table_name,
+                alter_action: AlterTableAction::SetOptions { options },
+            }) => {
+                assert_eq!("TskvTable", &table_name.to_string());
+                assert_eq!(options.last_value_cache, None);
+                assert_eq!(options.late_arrival_window.as_deref(), Some("2h"));
+                assert_eq!(options.late_arrival_policy.as_deref(), Some("late_tier"));
+            }
+            _ => panic!("expect SetOptions"),
+        }
+
+        assert!(ExtParser::parse_sql("ALTER TABLE TskvTable SET;").is_err());
+    }
+
+    #[test]
+    fn test_asof_join() {
+        let cases = [
+            (
+                "SELECT * FROM a ASOF JOIN b ON a.t0 = b.t0 MATCH_CONDITION(a.time >= b.time) WHERE a.f0 > 0",
+                "SELECT * FROM a LEFT JOIN b ON a.t0 = b.t0 AND asof_match_condition(a.time >= b.time) WHERE a.f0 > 0",
+            ),
+            (
+                "SELECT * FROM a asof join b match_condition(a.time >= b.time) on a.t0 = b.t0",
+                "SELECT * FROM a LEFT JOIN b ON asof_match_condition(a.time >= b.time) AND a.t0 = b.t0",
+            ),
+            (
+                "SELECT * FROM a ASOF JOIN (SELECT * FROM b) AS b MATCH_CONDITION(a.time <= b.time)",
+                "SELECT * FROM a LEFT JOIN (SELECT * FROM b) AS b ON asof_match_condition(a.time <= b.time)",
+            ),
+        ];
+
+        for (sql, expected) in cases {
+            match parse_sql(sql) {
+                ExtStatement::SqlStatement(ast) => assert_eq!(ast.to_string(), expected),
+                _ => panic!("expect SqlStatement"),
+            }
+        }
+
+        assert!(ExtParser::parse_sql("SELECT * FROM a ASOF JOIN b ON a.t0 = b.t0").is_err());
+
+        // `asof` out of the join position is an identifier
+        let cases = [
+            "SELECT asof FROM a",
+            "SELECT * FROM asof JOIN b ON asof.t0 = b.t0",
+            "SELECT * FROM a AS asof JOIN b ON asof.t0 = b.t0",
+            "SELECT * FROM db.asof JOIN b ON asof.t0 = b.t0",
+            "SELECT * FROM a, asof JOIN b ON asof.t0 = b.t0",
+        ];
+        for sql in cases {
+            match parse_sql(sql) {
+                ExtStatement::SqlStatement(ast) => assert_eq!(ast.to_string(), sql),
+                _ => panic!("expect SqlStatement"),
+            }
+        }
+    }
+
     #[test]
     fn test_update() {
         let statement = parse_sql("UPDATE TskvTable SET tag1 = '1' WHERE tag2 = '2';");
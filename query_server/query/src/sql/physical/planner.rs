@@ -24,6 +24,11 @@ use spi::Result;
 
 use super::optimizer::PhysicalOptimizer;
 use crate::extension::physical::optimizer_rule::add_assert::AddAssertExec;
+use crate::extension::physical::optimizer_rule::spillable_operators::{
+    SpillableAggregate, SpillableJoin,
+};
+use crate::extension::physical::transform_rule::asof_join::AsofJoinPlanner;
+use crate::extension::physical::transform_rule::event_window::EventWindowPlanner;
 use crate::extension::physical::transform_rule::expand::ExpandPlanner;
 use crate::extension::physical::transform_rule::gapfill::GapFillPlanner;
 use crate::extension::physical::transform_rule::table_writer::TableWriterPlanner;
@@ -66,6 +71,8 @@ impl Default for DefaultPhysicalPlanner {
             Arc::new(TagScanPlanner {}),
             Arc::new(ExpandPlanner::new()),
             Arc::new(GapFillPlanner::new()),
+            Arc::new(EventWindowPlanner::new()),
+            Arc::new(AsofJoinPlanner::new()),
         ];
 
         // We need to take care of the rule ordering. They may influence each other.
@@ -77,6 +84,9 @@ impl Default for DefaultPhysicalPlanner {
             // repartitioning and local sorting steps to meet distribution and ordering requirements.
             // Therefore, it should run before EnforceDistribution and EnforceSorting.
             Arc::new(JoinSelection::new()),
+            // CnosDB: if spilling is enabled, the hash joins are replaced with sort merge joins
+            // here, so that the following rules add the repartitions and sorts they require.
+            Arc::new(SpillableJoin::new()),
             // If the query is processing infinite inputs, the PipelineFixer rule applies the
             // necessary transformations to make the query runnable (if it is not already runnable).
             // If the query can not be made runnable, the rule emits an error with a diagnostic message.
@@ -105,6 +115,9 @@ impl Default for DefaultPhysicalPlanner {
             // Note that one should always run this rule after running the EnforceDistribution rule
             // as the latter may break local sorting requirements.
             Arc::new(EnforceSorting::new()),
+            // CnosDB: if spilling is enabled, the inputs of the grouped aggregates are sorted by
+            // the group keys. It runs after EnforceSorting, which would remove the sorts.
+            Arc::new(SpillableAggregate::new()),
             // The CoalesceBatches rule will not influence the distribution and ordering of the
             // whole plan tree. Therefore, to avoid influencing other rules, it should run last.
             Arc::new(CoalesceBatches::new()),
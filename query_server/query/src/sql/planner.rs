@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::option::Option;
 use std::sync::Arc;
 use std::{iter, vec};
@@ -49,6 +49,7 @@ use datafusion::sql::sqlparser::parser::ParserError;
 use datafusion::sql::TableReference;
 use lazy_static::__Deref;
 use meta::error::MetaError;
+use models::alert::DEFAULT_ALERT_SEVERITY;
 use models::auth::privilege::{
     DatabasePrivilege, GlobalPrivilege, Privilege, TenantObjectPrivilege,
 };
@@ -58,12 +59,13 @@ use models::gis::data_type::{Geometry, GeometryType};
 use models::object_reference::{Resolve, ResolvedTable};
 use models::oid::{Identifier, Oid};
 use models::schema::{
-    ColumnType, DatabaseOptions, Duration, Precision, TableColumn, Tenant, TskvTableSchema,
-    TskvTableSchemaRef, Watermark, DEFAULT_CATALOG, TIME_FIELD,
+    ColumnType, DatabaseOptions, Duration, LateArrivalPolicy, Precision, TableColumn, TableOptions,
+    Tenant, TskvTableSchema, TskvTableSchemaRef, Watermark, DEFAULT_CATALOG, TIME_FIELD,
 };
 use models::utils::SeqIdGenerator;
 use models::{ColumnId, ValueType};
 use object_store::ObjectStore;
+use protocol_parser::wide_table::{RowFormat, WideTableSpec, FORMAT_OPTION};
 use spi::query::ast;
 use spi::query::ast::{
     AlterDatabase as ASTAlterDatabase, AlterTable as ASTAlterTable,
@@ -71,9 +73,12 @@ use spi::query::ast::{
     ChecksumGroup as ASTChecksumGroup, ColumnOption, CompactVnode as ASTCompactVnode,
     CopyIntoTable, CopyTarget, CopyVnode as ASTCopyVnode, CreateDatabase as ASTCreateDatabase,
     CreateTable as ASTCreateTable, DatabaseOptions as ASTDatabaseOptions,
-    DescribeDatabase as DescribeDatabaseOptions, DescribeTable as DescribeTableOptions,
-    DropVnode as ASTDropVnode, ExtStatement, MoveVnode as ASTMoveVnode,
-    ShowSeries as ASTShowSeries, ShowTagBody, ShowTagValues as ASTShowTagValues, UriLocation, With,
+    DecommissionNode as ASTDecommissionNode, DescribeDatabase as DescribeDatabaseOptions,
+    DescribeTable as DescribeTableOptions, DropVnode as ASTDropVnode,
+    ExportVnode as ASTExportVnode, ExtStatement, ImportVnode as ASTImportVnode,
+    KillClusterQuery as ASTKillClusterQuery, MoveVnode as ASTMoveVnode,
+    ShowSeries as ASTShowSeries, ShowTagBody, ShowTagValues as ASTShowTagValues,
+    TransferLeader as ASTTransferLeader, UriLocation, With,
 };
 use spi::query::datasource::{self, UriSchema};
 use spi::query::logical_planner::{
@@ -82,11 +87,13 @@ use spi::query::logical_planner::{
     sql_options_to_user_options, unset_option_to_alter_tenant_action, AlterDatabase, AlterTable,
     AlterTableAction, AlterTenant, AlterTenantAction, AlterTenantAddUser, AlterTenantSetUser,
     AlterUser, AlterUserAction, ChecksumGroup, CompactVnode, CopyOptions, CopyOptionsBuilder,
-    CopyVnode, CreateDatabase, CreateRole, CreateStreamTable, CreateTable, CreateTenant,
-    CreateUser, DDLPlan, DMLPlan, DatabaseObjectType, DeleteFromTable, DropDatabaseObject,
-    DropGlobalObject, DropTenantObject, DropVnode, FileFormatOptions, FileFormatOptionsBuilder,
-    GlobalObjectType, GrantRevoke, LogicalPlanner, MoveVnode, Plan, PlanWithPrivileges, QueryPlan,
-    RecoverDatabase, RecoverTenant, SYSPlan, TenantObjectType, TENANT_OPTION_LIMITER,
+    CopyVnode, CreateAlertRule, CreateDatabase, CreateIngestionProfile, CreateRole,
+    CreateStreamTable, CreateTable, CreateTenant, CreateUser, DDLPlan, DMLPlan, DatabaseObjectType,
+    DecommissionNode, DeleteFromTable, DropDatabaseObject, DropGlobalObject, DropTenantObject,
+    DropVnode, ExportVnode, FileFormatOptions, FileFormatOptionsBuilder, GlobalObjectType,
+    GrantRevoke, ImportVnode, LogicalPlanner, MoveVnode, Plan, PlanWithPrivileges, QueryPlan,
+    RecoverDatabase, RecoverTenant, SYSPlan, TenantObjectType, TransferLeader,
+    TENANT_OPTION_LIMITER,
 };
 use spi::query::session::SessionCtx;
 use spi::{QueryError, Result};
@@ -98,13 +105,15 @@ use crate::data_source::stream::{get_event_time_column, get_watermark_delay};
 use crate::data_source::table_source::{TableHandle, TableSourceAdapter, TEMP_LOCATION_TABLE_NAME};
 use crate::extension::logical::logical_plan_builder::LogicalPlanBuilderExt;
 use crate::extension::logical::plan_node::update::UpdateNode;
+use crate::extension::logical::utils::extract_stream_providers;
 use crate::metadata::{
     is_system_database, ContextProviderExtension, DatabaseSet, COLUMNS_COLUMN_NAME,
     COLUMNS_COLUMN_TYPE, COLUMNS_COMPRESSION_CODEC, COLUMNS_DATABASE_NAME, COLUMNS_DATA_TYPE,
     COLUMNS_TABLE_NAME, DATABASES_DATABASE_NAME, DATABASES_PRECISION, DATABASES_REPLICA,
     DATABASES_SHARD, DATABASES_TTL, DATABASES_VNODE_DURATION, INFORMATION_SCHEMA,
-    INFORMATION_SCHEMA_COLUMNS, INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_QUERIES,
-    INFORMATION_SCHEMA_TABLES, TABLES_TABLE_DATABASE, TABLES_TABLE_NAME,
+    INFORMATION_SCHEMA_CLUSTER_QUERIES, INFORMATION_SCHEMA_COLUMNS, INFORMATION_SCHEMA_DATABASES,
+    INFORMATION_SCHEMA_QUERIES, INFORMATION_SCHEMA_TABLES, TABLES_TABLE_DATABASE,
+    TABLES_TABLE_NAME,
 };
 
 /// CnosDB SQL query planner
@@ -185,13 +194,25 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             ExtStatement::GrantRevoke(stmt) => self.grant_revoke_to_plan(stmt, session),
             // system statement
             ExtStatement::ShowQueries => self.show_queries_to_plan(session),
+            ExtStatement::ShowClusterQueries => self.show_cluster_queries_to_plan(session),
+            ExtStatement::KillClusterQuery(stmt) => self.kill_cluster_query_to_plan(stmt),
             ExtStatement::Copy(stmt) => self.copy_to_plan(stmt, session).await,
             // vnode statement
             ExtStatement::DropVnode(stmt) => self.drop_vnode_to_plan(stmt),
             ExtStatement::CopyVnode(stmt) => self.copy_vnode_to_plan(stmt),
             ExtStatement::MoveVnode(stmt) => self.move_vnode_to_plan(stmt),
             ExtStatement::CompactVnode(stmt) => self.compact_vnode_to_plan(stmt),
+            ExtStatement::ExportVnode(stmt) => self.export_vnode_to_plan(stmt),
+            ExtStatement::ImportVnode(stmt) => self.import_vnode_to_plan(stmt),
             ExtStatement::ChecksumGroup(stmt) => self.checksum_group_to_plan(stmt),
+            ExtStatement::TransferLeader(stmt) => self.transfer_leader_to_plan(stmt),
+            ExtStatement::DecommissionNode(stmt) => self.decommission_node_to_plan(stmt),
+            ExtStatement::CreateAlertRule(stmt) => {
+                self.create_alert_rule_to_plan(stmt, session).await
+            }
+            ExtStatement::CreateIngestionProfile(stmt) => {
+                self.create_ingestion_profile_to_plan(stmt, session)
+            }
             ExtStatement::CreateStream(_) => Err(QueryError::NotImplemented {
                 err: "CreateStream Planner.".to_string(),
             }),
@@ -313,6 +334,11 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         let table_ref = normalize_sql_object_name(table_name)?;
         let table_owned_reference = table_ref.to_owned_reference();
         let table_source = self.get_table_source(table_ref)?;
+        // The series are located by the partition tags, they cannot be moved to other vnodes.
+        let partition_tags = match source_downcast_adapter(&table_source)?.table_handle() {
+            TableHandle::Tskv(t) => t.table_schema().partition_tags().to_vec(),
+            _ => vec![],
+        };
 
         let schema = table_source.schema();
         let df_schema = schema.to_dfschema_ref()?;
@@ -330,6 +356,12 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
 
                 // Validate that the assignment target column exists
                 df_schema.field_from_column(&col_name)?;
+                if partition_tags.contains(&col_name.name) {
+                    return Err(QueryError::UpdatePartitionTag {
+                        table: table_owned_reference.to_string(),
+                        column: col_name.name,
+                    });
+                }
 
                 let value_expr =
                     self.df_planner
@@ -652,6 +684,26 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     Privilege::TenantObject(TenantObjectPrivilege::RoleFull, Some(tenant_id)),
                 )
             }
+            TenantObjectType::AlertRule => (
+                DDLPlan::DropTenantObject(DropTenantObject {
+                    tenant_name: tenant_name.to_string(),
+                    name: normalize_ident(object_name),
+                    if_exist,
+                    obj_type: TenantObjectType::AlertRule,
+                    after: after_duration,
+                }),
+                Privilege::TenantObject(TenantObjectPrivilege::System, Some(tenant_id)),
+            ),
+            TenantObjectType::IngestionProfile => (
+                DDLPlan::DropTenantObject(DropTenantObject {
+                    tenant_name: tenant_name.to_string(),
+                    name: normalize_ident(object_name),
+                    if_exist,
+                    obj_type: TenantObjectType::IngestionProfile,
+                    after: after_duration,
+                }),
+                Privilege::TenantObject(TenantObjectPrivilege::System, Some(tenant_id)),
+            ),
         };
 
         Ok(PlanWithPrivileges {
@@ -861,6 +913,8 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             name,
             if_not_exists,
             columns,
+            partition_by,
+            options,
         } = statement;
         let id_generator = SeqIdGenerator::default();
         // all col: time col, tag col, field col
@@ -898,10 +952,27 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             }
         }
 
+        let mut partition_tags: Vec<String> = Vec::with_capacity(partition_by.len());
+        for tag in partition_by {
+            let tag = normalize_ident(tag);
+            if !schema
+                .iter()
+                .any(|col| col.name == tag && col.column_type.is_tag())
+            {
+                return Err(QueryError::InvalidPartitionTag { column: tag });
+            }
+            if partition_tags.contains(&tag) {
+                return Err(QueryError::SameColumnName { column: tag });
+            }
+            partition_tags.push(tag);
+        }
+
         let plan = Plan::DDL(DDLPlan::CreateTable(CreateTable {
             schema,
             name: resolved_table,
             if_not_exists,
+            partition_tags,
+            options: self.make_table_options(options, TableOptions::default())?,
         }));
 
         // privilege
@@ -1149,6 +1220,17 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     new_column_name,
                 }
             }
+            ASTAlterTableAction::SetOptions { options } => {
+                if options.last_value_cache.is_some() {
+                    // The cache is not maintained while it is disabled, so it
+                    // would be stale if it is enabled again.
+                    return Err(QueryError::NotImplemented {
+                        err: "alter LAST_VALUE_CACHE".to_string(),
+                    });
+                }
+                let options = self.make_table_options(options, table_schema.options().clone())?;
+                AlterTableAction::SetOptions { options }
+            }
         };
         let plan = Plan::DDL(DDLPlan::AlterTable(AlterTable {
             table_name,
@@ -1511,6 +1593,41 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         Ok(plan_options)
     }
 
+    /// Applies the options set in the statement onto `base`.
+    fn make_table_options(
+        &self,
+        options: ast::TableOptions,
+        base: TableOptions,
+    ) -> Result<TableOptions> {
+        let mut table_options = base;
+        if let Some(last_value_cache) = options.last_value_cache {
+            table_options.last_value_cache = last_value_cache;
+        }
+        if let Some(window) = options.late_arrival_window {
+            table_options.late_arrival_window = Some(self.str_to_duration(&window)?);
+        }
+        if let Some(policy) = options.late_arrival_policy {
+            table_options.late_arrival_policy =
+                LateArrivalPolicy::new(&policy).ok_or_else(|| QueryError::Parser {
+                    source: ParserError::ParserError(format!(
+                        "{} is not a valid late arrival policy, use like 'accept', 'reject', 'late_tier'",
+                        policy
+                    )),
+                })?;
+        }
+        if table_options.late_arrival_window.is_none()
+            && table_options.late_arrival_policy != LateArrivalPolicy::Accept
+        {
+            return Err(QueryError::Parser {
+                source: ParserError::ParserError(
+                    "LATE_ARRIVAL_POLICY requires LATE_ARRIVAL_WINDOW".to_string(),
+                ),
+            });
+        }
+
+        Ok(table_options)
+    }
+
     fn str_to_duration(&self, text: &str) -> Result<Duration> {
         Duration::new(text).ok_or_else(|| QueryError::Parser {
             source: ParserError::ParserError(format!("{} is not a valid duration", text)),
@@ -1660,6 +1777,135 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    async fn create_alert_rule_to_plan(
+        &self,
+        stmt: ast::CreateAlertRule,
+        session: &SessionCtx,
+    ) -> Result<PlanWithPrivileges> {
+        let ast::CreateAlertRule {
+            if_not_exists,
+            name,
+            query,
+            every,
+            for_duration,
+            severity,
+            labels,
+            annotations,
+            webhook,
+        } = stmt;
+
+        let name = normalize_ident(name);
+        let invalid = |reason: String| QueryError::InvalidAlertRule {
+            name: name.clone(),
+            reason,
+        };
+
+        let every = duration_str::parse_std(&every)
+            .map_err(|err| invalid(format!("invalid EVERY '{every}': {err}")))?;
+        if every.is_zero() {
+            return Err(invalid("EVERY must be greater than 0".to_string()));
+        }
+        let for_duration = for_duration
+            .map(|e| {
+                duration_str::parse_std(&e)
+                    .map_err(|err| invalid(format!("invalid FOR '{e}': {err}")))
+            })
+            .transpose()?
+            .unwrap_or_default();
+        if let Some(url) = &webhook {
+            Url::parse(url).map_err(|err| invalid(format!("invalid WEBHOOK '{url}': {err}")))?;
+        }
+
+        // the query must be a valid query, and the owner must be able to read its tables
+        let query_sql = query.to_string();
+        let PlanWithPrivileges {
+            plan: query_plan,
+            mut privileges,
+        } = self
+            .df_sql_to_plan(Statement::Query(query), session)
+            .await?;
+        match query_plan {
+            Plan::Query(query_plan) if !extract_stream_providers(&query_plan).is_empty() => {
+                return Err(invalid("stream tables can not be queried".to_string()));
+            }
+            Plan::Query(_) => {}
+            _ => return Err(invalid("only SELECT is supported".to_string())),
+        }
+
+        let tenant_id = *session.tenant_id();
+        privileges.push(Privilege::TenantObject(
+            TenantObjectPrivilege::System,
+            Some(tenant_id),
+        ));
+
+        let plan = Plan::DDL(DDLPlan::CreateAlertRule(CreateAlertRule {
+            tenant_name: session.tenant().to_string(),
+            name,
+            if_not_exists,
+            database_name: session.default_database().to_string(),
+            owner: session.user().clone(),
+            query: query_sql,
+            every,
+            for_duration,
+            severity: severity.unwrap_or_else(|| DEFAULT_ALERT_SEVERITY.to_string()),
+            labels: labels
+                .into_iter()
+                .map(|(k, v)| (normalize_ident(k), v))
+                .collect(),
+            annotations: annotations
+                .into_iter()
+                .map(|(k, v)| (normalize_ident(k), v))
+                .collect(),
+            webhook,
+        }));
+
+        Ok(PlanWithPrivileges { plan, privileges })
+    }
+
+    fn create_ingestion_profile_to_plan(
+        &self,
+        stmt: ast::CreateIngestionProfile,
+        session: &SessionCtx,
+    ) -> Result<PlanWithPrivileges> {
+        let ast::CreateIngestionProfile {
+            if_not_exists,
+            name,
+            options,
+        } = stmt;
+
+        let name = normalize_ident(name);
+        let options = options
+            .into_iter()
+            .map(|(k, v)| (normalize_ident(k), v))
+            .collect::<BTreeMap<_, _>>();
+
+        // a profile may leave the format to the write request, so only the mapping is checked
+        let mut checked = options.clone();
+        checked
+            .entry(FORMAT_OPTION.to_string())
+            .or_insert_with(|| RowFormat::Ndjson.to_string());
+        WideTableSpec::try_from_options(checked.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .map_err(|err| QueryError::InvalidIngestionProfile {
+                name: name.clone(),
+                reason: err.to_string(),
+            })?;
+
+        let plan = Plan::DDL(DDLPlan::CreateIngestionProfile(CreateIngestionProfile {
+            tenant_name: session.tenant().to_string(),
+            name,
+            if_not_exists,
+            options,
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::System,
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     async fn construct_alter_tenant_action_with_privilege(
         &self,
         tenant: Tenant,
@@ -1887,8 +2133,9 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
     }
 
     fn show_queries_to_plan(&self, session: &SessionCtx) -> Result<PlanWithPrivileges> {
-        // QUERY_SCHEMA: query_id, query_type, query_text, user_name, tenant_name, state, duration
-        let projections = vec![0, 1, 2, 4, 6, 7, 8];
+        // QUERY_SCHEMA: query_id, query_type, query_text, user_name, tenant_name, state, duration,
+        // spill_count, spilled_bytes
+        let projections = vec![0, 1, 2, 4, 6, 7, 8, 17, 18];
 
         let table_ref = TableReference::partial(INFORMATION_SCHEMA, INFORMATION_SCHEMA_QUERIES);
 
@@ -1911,6 +2158,43 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn show_cluster_queries_to_plan(&self, session: &SessionCtx) -> Result<PlanWithPrivileges> {
+        // QUERY_SCHEMA: query_id, query_type, query_text, user_name, tenant_name, state, duration,
+        // node_id, cpu_time, peak_memory, rows_scanned, bytes_scanned, spill_count, spilled_bytes
+        let projections = vec![0, 1, 2, 4, 6, 7, 8, 11, 12, 13, 14, 15, 17, 18];
+
+        let table_ref =
+            TableReference::partial(INFORMATION_SCHEMA, INFORMATION_SCHEMA_CLUSTER_QUERIES);
+
+        let table_source = self.get_table_source(table_ref.clone())?;
+
+        let df_plan =
+            LogicalPlanBuilder::scan(table_ref, table_source, Some(projections))?.build()?;
+
+        let plan = Plan::Query(QueryPlan { df_plan });
+
+        // privileges
+        let tenant_id = *session.tenant_id();
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Read, None),
+            Some(tenant_id),
+        );
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![privilege],
+        })
+    }
+
+    fn kill_cluster_query_to_plan(&self, stmt: ASTKillClusterQuery) -> Result<PlanWithPrivileges> {
+        let ASTKillClusterQuery { query_id } = stmt;
+
+        let plan = Plan::SYSTEM(SYSPlan::KillClusterQuery(query_id.into()));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
     fn drop_vnode_to_plan(&self, stmt: ASTDropVnode) -> Result<PlanWithPrivileges> {
         let ASTDropVnode { vnode_id } = stmt;
 
@@ -1951,6 +2235,26 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn export_vnode_to_plan(&self, stmt: ASTExportVnode) -> Result<PlanWithPrivileges> {
+        let ASTExportVnode { vnode_id, path } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::ExportVnode(ExportVnode { vnode_id, path }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn import_vnode_to_plan(&self, stmt: ASTImportVnode) -> Result<PlanWithPrivileges> {
+        let ASTImportVnode { vnode_id, path } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::ImportVnode(ImportVnode { vnode_id, path }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
     fn checksum_group_to_plan(&self, stmt: ASTChecksumGroup) -> Result<PlanWithPrivileges> {
         let ASTChecksumGroup { replication_set_id } = stmt;
 
@@ -1961,6 +2265,32 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn transfer_leader_to_plan(&self, stmt: ASTTransferLeader) -> Result<PlanWithPrivileges> {
+        let ASTTransferLeader {
+            replica_id,
+            node_id,
+        } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::TransferLeader(TransferLeader {
+            replica_id,
+            node_id,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn decommission_node_to_plan(&self, stmt: ASTDecommissionNode) -> Result<PlanWithPrivileges> {
+        let ASTDecommissionNode { node_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::DecommissionNode(DecommissionNode { node_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
     fn create_stream_table_to_plan(
         &self,
         stmt: Statement,
@@ -2796,6 +3126,7 @@ mod tests {
     use coordinator::service_mock::MockCoordinator;
     use datafusion::arrow::datatypes::TimeUnit::Nanosecond;
     use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion::common::tree_node::{TreeNode, VisitRecursion};
     use datafusion::datasource::TableProvider;
     use datafusion::error::Result;
     use datafusion::execution::memory_pool::UnboundedMemoryPool;
@@ -2810,13 +3141,18 @@ mod tests {
     use models::codec::Encoding;
     use models::schema::{ColumnType, Tenant};
     use models::ValueType;
+    use spi::query::analyzer::Analyzer;
     use spi::query::session::SessionCtxFactory;
     use spi::service::protocol::ContextBuilder;
 
     use super::*;
     use crate::data_source::table_source::TableSourceAdapter;
+    use crate::extension::expr::load_all_functions;
+    use crate::extension::logical::plan_node::asof_join::AsofJoinNode;
     use crate::extension::logical::plan_node::table_writer::TableWriterPlanNode;
+    use crate::function::simple_func_manager::SimpleFunctionMetadataManager;
     use crate::metadata::ContextProviderExtension;
+    use crate::sql::analyzer::DefaultAnalyzer;
     use crate::sql::parser::ExtParser;
 
     #[derive(Debug)]
@@ -2849,6 +3185,11 @@ mod tests {
                     Field::new("field_int", DataType::Int32, false),
                     Field::new("field_string", DataType::Utf8, false),
                 ])),
+                "test_ts" => Ok(Schema::new(vec![
+                    Field::new("time", DataType::Timestamp(Nanosecond, None), false),
+                    Field::new("tag", DataType::Utf8, true),
+                    Field::new("value", DataType::Float64, true),
+                ])),
                 _ => {
                     unimplemented!("use test_tb for test")
                 }
@@ -2874,6 +3215,11 @@ mod tests {
                     Field::new("field_int", DataType::Int32, false),
                     Field::new("field_string", DataType::Utf8, false),
                 ])),
+                "test_ts" => Ok(Schema::new(vec![
+                    Field::new("time", DataType::Timestamp(Nanosecond, None), false),
+                    Field::new("tag", DataType::Utf8, true),
+                    Field::new("value", DataType::Float64, true),
+                ])),
                 _ => {
                     unimplemented!("use test_tb for test")
                 }
@@ -2884,8 +3230,10 @@ mod tests {
             }
         }
 
-        fn get_function_meta(&self, _name: &str) -> Option<Arc<ScalarUDF>> {
-            unimplemented!()
+        fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+            let mut func_manager = SimpleFunctionMetadataManager::default();
+            load_all_functions(&mut func_manager).ok()?;
+            func_manager.udf(name).ok()
         }
 
         fn get_aggregate_meta(&self, _name: &str) -> Option<Arc<AggregateUDF>> {
@@ -2968,7 +3316,7 @@ mod tests {
                 0_u128,
                 Arc::new(pool),
                 None,
-                Arc::new(MockCoordinator {}),
+                Arc::new(MockCoordinator::default()),
             )
             .unwrap()
     }
@@ -3066,6 +3414,8 @@ mod tests {
                         .resolve_object("cnosdb", "default_schema")
                         .unwrap(),
                     if_not_exists: true,
+                    partition_tags: vec![],
+                    options: Default::default(),
                 }
             );
         } else {
@@ -3073,6 +3423,34 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_create_table_partition_by() {
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+
+        let sql = "CREATE TABLE test(column1 BIGINT, TAGS(region, host)) PARTITION BY (region)";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session())
+            .await
+            .unwrap();
+        if let Plan::DDL(DDLPlan::CreateTable(create)) = plan.plan {
+            assert_eq!(create.partition_tags, vec!["region".to_string()]);
+        } else {
+            panic!("expected create table plan")
+        }
+
+        let sql = "CREATE TABLE test(column1 BIGINT, TAGS(region)) PARTITION BY (column1)";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let result = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session())
+            .await;
+        assert!(matches!(
+            result,
+            Err(QueryError::InvalidPartitionTag { .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_create_database() {
         let sql = "CREATE DATABASE test WITH TTL '10' SHARD 5 VNODE_DURATION '3d' REPLICA 10 PRECISION 'us';";
@@ -3176,6 +3554,8 @@ mod tests {
                     .resolve_object("cnosdb", "public")
                     .unwrap(),
                 if_not_exists: false,
+                partition_tags: vec![],
+                options: Default::default(),
             };
 
             assert_eq!(expected, create)
@@ -3219,4 +3599,78 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_asof_join_on_tags() {
+        let sql = "select a.time, b.value from test_ts a \
+            asof join test_ts b on a.tag = b.tag match_condition(a.time >= b.time)";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(statements.len(), 1);
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session())
+            .await
+            .unwrap();
+        let df_plan = match plan.plan {
+            Plan::Query(QueryPlan { df_plan }) => df_plan,
+            _ => panic!("expected query plan"),
+        };
+
+        let plan = DefaultAnalyzer::default()
+            .analyze(&df_plan, &session())
+            .unwrap();
+        let mut asof_join = None;
+        plan.apply(&mut |plan| {
+            if let LogicalPlan::Extension(Extension { node }) = plan {
+                if let Some(node) = node.as_any().downcast_ref::<AsofJoinNode>() {
+                    asof_join = Some(node.clone());
+                    return Ok(VisitRecursion::Stop);
+                }
+            }
+            Ok(VisitRecursion::Continue)
+        })
+        .unwrap();
+
+        let asof_join = asof_join.expect("expected asof join");
+        assert_eq!(
+            asof_join.on,
+            vec![(
+                Expr::Column(Column::new(Some("a"), "tag")),
+                Expr::Column(Column::new(Some("b"), "tag"))
+            )]
+        );
+        assert_eq!(
+            asof_join.left_time,
+            Expr::Column(Column::new(Some("a"), "time"))
+        );
+        assert_eq!(
+            asof_join.right_time,
+            Expr::Column(Column::new(Some("b"), "time"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_asof_join_with_non_equality_condition() {
+        let sql = "select a.time, b.value from test_ts a \
+            asof join test_ts b on a.value > b.value match_condition(a.time >= b.time)";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session())
+            .await
+            .unwrap();
+        let df_plan = match plan.plan {
+            Plan::Query(QueryPlan { df_plan }) => df_plan,
+            _ => panic!("expected query plan"),
+        };
+
+        let err = DefaultAnalyzer::default()
+            .analyze(&df_plan, &session())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("ASOF JOIN only supports equality conditions"));
+    }
 }
@@ -38,6 +38,16 @@ impl StateStoreFactory for MemoryStateStoreFactory {
     }
 }
 
+impl MemoryStateStoreFactory {
+    /// Drops the state uniquely identified by query_id, partition_id, operator_id
+    pub fn remove(&self, query_id: &str, partition_id: usize, operator_id: usize) {
+        let _ =
+            self.state_store_map
+                .write()
+                .remove(&(query_id.to_string(), partition_id, operator_id));
+    }
+}
+
 #[derive(Debug, Default)]
 struct Container {
     committed: RwLock<Arc<RwLock<Vec<RecordBatch>>>>,
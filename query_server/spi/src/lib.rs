@@ -532,6 +532,47 @@ pub enum QueryError {
     ForbiddenCreateSystemRole {
         role: String,
     },
+
+    #[snafu(display("Invalid alert rule {}: {}", name, reason))]
+    #[error_code(code = 78)]
+    InvalidAlertRule {
+        name: String,
+        reason: String,
+    },
+
+    #[snafu(display("Failed to send alert notification to {}: {}", url, reason))]
+    #[error_code(code = 79)]
+    SendAlertNotification {
+        url: String,
+        reason: String,
+    },
+
+    #[snafu(display(
+        "Semantic error: Partition column {} must be a tag of the table.",
+        column
+    ))]
+    #[error_code(code = 80)]
+    InvalidPartitionTag {
+        column: String,
+    },
+
+    #[snafu(display("Invalid ingestion profile {}: {}", name, reason))]
+    #[error_code(code = 81)]
+    InvalidIngestionProfile {
+        name: String,
+        reason: String,
+    },
+
+    #[snafu(display(
+        "Semantic error: Partition tag {} of table {} cannot be updated.",
+        column,
+        table
+    ))]
+    #[error_code(code = 82)]
+    UpdatePartitionTag {
+        table: String,
+        column: String,
+    },
 }
 
 impl From<ParserError> for QueryError {
@@ -2,8 +2,8 @@ use std::fmt;
 
 use datafusion::sql::parser::CreateExternalTable;
 use datafusion::sql::sqlparser::ast::{
-    AnalyzeFormat, DataType, Expr, Ident, ObjectName, Offset, OrderByExpr, SqlOption, Statement,
-    TableFactor, Value,
+    AnalyzeFormat, DataType, Expr, Ident, ObjectName, Offset, OrderByExpr, Query, SqlOption,
+    Statement, TableFactor, Value,
 };
 use datafusion::sql::sqlparser::parser::ParserError;
 use models::codec::Encoding;
@@ -32,6 +32,9 @@ pub enum ExtStatement {
     DropStream(DropStream),
     ShowStreams(ShowStreams),
 
+    CreateAlertRule(CreateAlertRule),
+    CreateIngestionProfile(CreateIngestionProfile),
+
     DropDatabaseObject(DropDatabaseObject),
     DropTenantObject(DropTenantObject),
     DropGlobalObject(DropGlobalObject),
@@ -48,6 +51,8 @@ pub enum ExtStatement {
 
     // system cmd
     ShowQueries,
+    ShowClusterQueries,
+    KillClusterQuery(KillClusterQuery),
     AlterDatabase(AlterDatabase),
     AlterTable(AlterTable),
     AlterTenant(AlterTenant),
@@ -58,7 +63,13 @@ pub enum ExtStatement {
     CopyVnode(CopyVnode),
     MoveVnode(MoveVnode),
     CompactVnode(CompactVnode),
+    ExportVnode(ExportVnode),
+    ImportVnode(ImportVnode),
     ChecksumGroup(ChecksumGroup),
+    TransferLeader(TransferLeader),
+
+    // node cmd
+    DecommissionNode(DecommissionNode),
 
     // recover cmd
     RecoverTenant(RecoverTenant),
@@ -70,11 +81,39 @@ pub struct ChecksumGroup {
     pub replication_set_id: ReplicationSetId,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KillClusterQuery {
+    pub query_id: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecommissionNode {
+    pub node_id: NodeId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferLeader {
+    pub replica_id: ReplicationSetId,
+    pub node_id: NodeId,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompactVnode {
     pub vnode_ids: Vec<VnodeId>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportVnode {
+    pub vnode_id: VnodeId,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportVnode {
+    pub vnode_id: VnodeId,
+    pub path: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MoveVnode {
     pub vnode_id: VnodeId,
@@ -155,6 +194,10 @@ pub enum AlterTableAction {
         old_column_name: Ident,
         new_column_name: Ident,
     },
+    /// `SET <table_option> [, <table_option>]*`
+    SetOptions {
+        options: TableOptions,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -295,6 +338,9 @@ pub struct CreateTable {
     pub name: ObjectName,
     pub if_not_exists: bool,
     pub columns: Vec<ColumnOption>,
+    /// The tags of `PARTITION BY (tag1[, tag2])`
+    pub partition_by: Vec<Ident>,
+    pub options: TableOptions,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -339,6 +385,14 @@ pub struct DatabaseOptions {
     pub precision: Option<String>,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TableOptions {
+    // keep the last values of the series in memory
+    pub last_value_cache: Option<bool>,
+    pub late_arrival_window: Option<String>,
+    pub late_arrival_policy: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DescribeTable {
     pub table_name: ObjectName,
@@ -423,6 +477,28 @@ pub struct CreateStream {
     pub statement: Box<Statement>,
 }
 
+/// CREATE ALERT RULE [IF NOT EXISTS] name AS query EVERY 'interval' [FOR 'duration'] [WITH (...)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateAlertRule {
+    pub if_not_exists: bool,
+    pub name: Ident,
+    pub query: Box<Query>,
+    pub every: String,
+    pub for_duration: Option<String>,
+    pub severity: Option<String>,
+    pub labels: Vec<(Ident, String)>,
+    pub annotations: Vec<(Ident, String)>,
+    pub webhook: Option<String>,
+}
+
+/// CREATE INGESTION PROFILE [IF NOT EXISTS] name WITH (key = 'value' [, ...])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateIngestionProfile {
+    pub if_not_exists: bool,
+    pub name: Ident,
+    pub options: Vec<(Ident, String)>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DropStream {
     pub if_exist: bool,
@@ -463,6 +539,16 @@ pub fn parse_string_value(value: Value) -> std::result::Result<String, ParserErr
     }
 }
 
+pub fn parse_bool_value(value: Value) -> std::result::Result<bool, ParserError> {
+    match value {
+        Value::Boolean(b) => Ok(b),
+        _ => Err(ParserError::ParserError(format!(
+            "expected boolean value, but found : {}",
+            value
+        ))),
+    }
+}
+
 pub fn parse_char_value(value: Value) -> std::result::Result<char, ParserError> {
     let token = parse_string_value(value)?;
     match token.len() {
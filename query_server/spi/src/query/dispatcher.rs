@@ -4,11 +4,12 @@ use std::time::Duration;
 use async_trait::async_trait;
 use models::auth::user::User;
 use models::oid::{Identifier, Oid};
+use models::query_resource::QueryResourceUsage;
 use serde::{Deserialize, Serialize};
 use trace::SpanContext;
 
 use super::execution::QueryState;
-use crate::query::execution::{Output, QueryStateMachine};
+use crate::query::execution::{Output, QueryExecutionRef, QueryStateMachine};
 use crate::query::logical_planner::Plan;
 use crate::service::protocol::{Query, QueryId};
 use crate::Result;
@@ -54,7 +55,12 @@ pub trait QueryDispatcher: Send + Sync {
 
     fn running_query_status(&self) -> Vec<QueryStatus>;
 
+    fn running_queries(&self) -> Vec<QueryExecutionRef>;
+
     fn cancel_query(&self, id: &QueryId);
+
+    /// Cancels the query and stops tracking it, returns false if the query is not running
+    fn kill_query(&self, id: &QueryId) -> bool;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -126,6 +132,7 @@ pub struct QueryStatus {
     duration: Duration,
     processed_count: u64,
     error_count: u64,
+    resource_usage: QueryResourceUsage,
 }
 
 impl QueryStatus {
@@ -135,6 +142,7 @@ impl QueryStatus {
             duration,
             processed_count: 0,
             error_count: 0,
+            resource_usage: QueryResourceUsage::default(),
         }
     }
 
@@ -153,6 +161,10 @@ impl QueryStatus {
     pub fn error_count(&self) -> u64 {
         self.error_count
     }
+
+    pub fn resource_usage(&self) -> &QueryResourceUsage {
+        &self.resource_usage
+    }
 }
 
 pub struct QueryStatusBuilder {
@@ -160,6 +172,7 @@ pub struct QueryStatusBuilder {
     duration: Duration,
     processed_count: u64,
     error_count: u64,
+    resource_usage: QueryResourceUsage,
 }
 
 impl QueryStatusBuilder {
@@ -169,6 +182,7 @@ impl QueryStatusBuilder {
             duration,
             processed_count: 0,
             error_count: 0,
+            resource_usage: QueryResourceUsage::default(),
         }
     }
 
@@ -182,12 +196,18 @@ impl QueryStatusBuilder {
         self
     }
 
+    pub fn with_resource_usage(mut self, resource_usage: QueryResourceUsage) -> Self {
+        self.resource_usage = resource_usage;
+        self
+    }
+
     pub fn build(self) -> QueryStatus {
         QueryStatus {
             state: self.state,
             duration: self.duration,
             processed_count: self.processed_count,
             error_count: self.error_count,
+            resource_usage: self.resource_usage,
         }
     }
 }
@@ -214,10 +214,10 @@ impl QueryStateMachine {
                     0,
                     Arc::new(UnboundedMemoryPool::default()),
                     span_context,
-                    Arc::new(MockCoordinator {}),
+                    Arc::new(MockCoordinator::default()),
                 )
                 .expect("create test session ctx"),
-            Arc::new(MockCoordinator {}),
+            Arc::new(MockCoordinator::default()),
         )
     }
 
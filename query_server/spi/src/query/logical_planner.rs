@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 use std::sync::Arc;
 
@@ -21,12 +21,13 @@ use datafusion::sql::sqlparser::parser::ParserError;
 use lazy_static::lazy_static;
 use models::auth::privilege::{DatabasePrivilege, GlobalPrivilege, Privilege};
 use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
-use models::auth::user::{UserOptions, UserOptionsBuilder};
+use models::auth::user::{User, UserOptions, UserOptionsBuilder};
 use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use models::object_reference::ResolvedTable;
 use models::oid::{Identifier, Oid};
 use models::schema::{
-    DatabaseOptions, Duration, TableColumn, Tenant, TenantOptions, TenantOptionsBuilder, Watermark,
+    DatabaseOptions, Duration, TableColumn, TableOptions, Tenant, TenantOptions,
+    TenantOptionsBuilder, Watermark,
 };
 use snafu::ResultExt;
 use tempfile::NamedTempFile;
@@ -131,6 +132,10 @@ pub enum DDLPlan {
 
     CreateRole(CreateRole),
 
+    CreateAlertRule(CreateAlertRule),
+
+    CreateIngestionProfile(CreateIngestionProfile),
+
     AlterDatabase(AlterDatabase),
 
     AlterTable(AlterTable),
@@ -149,8 +154,16 @@ pub enum DDLPlan {
 
     CompactVnode(CompactVnode),
 
+    ExportVnode(ExportVnode),
+
+    ImportVnode(ImportVnode),
+
     ChecksumGroup(ChecksumGroup),
 
+    TransferLeader(TransferLeader),
+
+    DecommissionNode(DecommissionNode),
+
     RecoverDatabase(RecoverDatabase),
 
     RecoverTenant(RecoverTenant),
@@ -173,11 +186,34 @@ pub struct ChecksumGroup {
     pub replication_set_id: ReplicationSetId,
 }
 
+#[derive(Debug, Clone)]
+pub struct DecommissionNode {
+    pub node_id: NodeId,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferLeader {
+    pub replica_id: ReplicationSetId,
+    pub node_id: NodeId,
+}
+
 #[derive(Debug, Clone)]
 pub struct CompactVnode {
     pub vnode_ids: Vec<VnodeId>,
 }
 
+#[derive(Debug, Clone)]
+pub struct ExportVnode {
+    pub vnode_id: VnodeId,
+    pub path: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportVnode {
+    pub vnode_id: VnodeId,
+    pub path: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct MoveVnode {
     pub vnode_id: VnodeId,
@@ -216,6 +252,8 @@ pub struct DeleteFromTable {
 #[derive(Debug, Clone)]
 pub enum SYSPlan {
     KillQuery(QueryId),
+    /// Kill the query on whichever node of the cluster is running it
+    KillClusterQuery(QueryId),
 }
 
 impl SYSPlan {
@@ -253,6 +291,8 @@ pub struct DropTenantObject {
 pub enum TenantObjectType {
     Role,
     Database,
+    AlertRule,
+    IngestionProfile,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -304,6 +344,9 @@ pub struct CreateTable {
     pub name: ResolvedTable,
     /// Option to not error if table already exists
     pub if_not_exists: bool,
+    /// The tags to route the series to the vnodes
+    pub partition_tags: Vec<String>,
+    pub options: TableOptions,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -509,6 +552,30 @@ pub struct CreateRole {
     pub inherit_tenant_role: SystemTenantRole,
 }
 
+#[derive(Debug, Clone)]
+pub struct CreateAlertRule {
+    pub tenant_name: String,
+    pub name: String,
+    pub if_not_exists: bool,
+    pub database_name: String,
+    pub owner: User,
+    pub query: String,
+    pub every: std::time::Duration,
+    pub for_duration: std::time::Duration,
+    pub severity: String,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    pub webhook: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateIngestionProfile {
+    pub tenant_name: String,
+    pub name: String,
+    pub if_not_exists: bool,
+    pub options: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GrantRevoke {
     pub is_grant: bool,
@@ -584,6 +651,9 @@ pub enum AlterTableAction {
         old_column_name: String,
         new_column_name: String,
     },
+    SetOptions {
+        options: TableOptions,
+    },
 }
 
 #[async_trait]
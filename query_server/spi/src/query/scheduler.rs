@@ -20,6 +20,15 @@ pub trait Scheduler {
     ) -> Result<ExecutionResults>;
 }
 
+/// The group sharing the cpu of the [`Scheduler`] with the others, by the relative `shares`.
+///
+/// Found in the session config of the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuShares {
+    pub group: String,
+    pub shares: u32,
+}
+
 pub struct ExecutionResults {
     stream: SendableRecordBatchStream,
 }
@@ -6,15 +6,19 @@ use coordinator::Coordinator;
 use datafusion::common::extensions_options;
 use datafusion::config::ConfigExtension;
 use datafusion::execution::context::SessionState;
-use datafusion::execution::memory_pool::MemoryPool;
+use datafusion::execution::disk_manager::DiskManagerConfig;
+use datafusion::execution::memory_pool::{MemoryConsumer, MemoryPool, MemoryReservation};
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use datafusion::prelude::{SessionConfig, SessionContext};
 use datafusion::variable::VarType;
 use models::auth::user::User;
+use models::consistency_level::ReadConsistency;
 use models::oid::Oid;
+use models::query_resource::QueryResourceTracker;
 use trace::{SpanContext, SpanExt, SpanRecorder};
 
 use super::config::StreamTriggerInterval;
+use super::scheduler::CpuShares;
 use super::variable::VarProviderRef;
 use crate::service::protocol::Context;
 use crate::Result;
@@ -22,6 +26,10 @@ use crate::Result;
 extensions_options! {
     pub struct SqlExecInfo {
         pub copyinto_trigger_flush_size: u64, default = 128 * 1024 * 1024 // 128MB
+        /// Plans the hash joins and hash aggregates as the operators spilling through sorts.
+        /// Off by default, the sort merge joins and the sorted aggregates are slower than the
+        /// hash operators even without memory pressure, see `query.spill_hash_operators`.
+        pub spill_hash_operators: bool, default = false
     }
 }
 impl ConfigExtension for SqlExecInfo {
@@ -76,6 +84,10 @@ impl SessionCtx {
     pub fn get_child_span_recorder(&self, name: &'static str) -> SpanRecorder {
         SpanRecorder::new(self.get_span_ctx().child_span(name))
     }
+
+    pub fn resource_tracker(&self) -> Option<Arc<QueryResourceTracker>> {
+        self.inner.config().get_extension::<QueryResourceTracker>()
+    }
 }
 
 #[derive(Clone)]
@@ -91,11 +103,19 @@ pub struct SessionCtxDesc {
     query_dedicated_hidden_dir: PathBuf,
 }
 
-#[derive(Default)]
 pub struct SessionCtxFactory {
     sys_var_provider: Option<VarProviderRef>,
     query_dedicated_hidden_dir: PathBuf,
     session_function_register: Option<fn(df_session_ctx: &SessionContext, context: &Context)>,
+    disk_manager: DiskManagerConfig,
+    /// Off unless enabled by the config, as `SqlExecInfo::spill_hash_operators`
+    spill_hash_operators: bool,
+}
+
+impl Default for SessionCtxFactory {
+    fn default() -> Self {
+        Self::new(None, PathBuf::default(), None)
+    }
 }
 
 impl SessionCtxFactory {
@@ -108,9 +128,26 @@ impl SessionCtxFactory {
             sys_var_provider,
             query_dedicated_hidden_dir,
             session_function_register,
+            disk_manager: DiskManagerConfig::default(),
+            spill_hash_operators: false,
         }
     }
 
+    /// The disk manager creating the spill files of the sorts, spilling is disabled
+    /// with `DiskManagerConfig::Disabled`
+    pub fn with_disk_manager(mut self, disk_manager: DiskManagerConfig) -> Self {
+        self.disk_manager = disk_manager;
+        self
+    }
+
+    /// Plans the hash joins and the grouped hash aggregates as the sort merge joins and the
+    /// sorted aggregates if spilling is enabled, they are slower than the hash operators but
+    /// spill through their sorts.
+    pub fn with_spill_hash_operators(mut self, spill_hash_operators: bool) -> Self {
+        self.spill_hash_operators = spill_hash_operators;
+        self
+    }
+
     pub fn create_session_ctx(
         &self,
         session_id: impl Into<String>,
@@ -158,8 +195,22 @@ impl SessionCtxFactory {
             "sql_exec_info.copyinto_trigger_flush_size",
             coord.get_config().storage.copyinto_trigger_flush_size,
         );
+        // the spillable operators are only planned if they can spill
+        config = config.set_bool(
+            "sql_exec_info.spill_hash_operators",
+            self.spill_hash_operators && !matches!(self.disk_manager, DiskManagerConfig::Disabled),
+        );
+        // account the resources used by the query, the operators find it in the session config
+        let tracker = Arc::new(QueryResourceTracker::default());
+        config = config.with_extension(tracker.clone());
+        let memory_pool = Arc::new(QueryMemoryPool {
+            inner: memory_pool,
+            tracker,
+        });
 
-        let rt_config = RuntimeConfig::new().with_memory_pool(memory_pool);
+        let rt_config = RuntimeConfig::new()
+            .with_memory_pool(memory_pool)
+            .with_disk_manager(self.disk_manager.clone());
         let rt = RuntimeEnv::new(rt_config)?;
         let df_session_state =
             SessionState::with_config_rt(config, Arc::new(rt)).with_session_id(session_id.into());
@@ -175,6 +226,47 @@ impl SessionCtxFactory {
     }
 }
 
+/// Allocates from the shared pool, and accounts the memory used by the query
+#[derive(Debug)]
+struct QueryMemoryPool {
+    inner: Arc<dyn MemoryPool>,
+    tracker: Arc<QueryResourceTracker>,
+}
+
+impl MemoryPool for QueryMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.inner.grow(reservation, additional);
+        self.tracker.grow_memory(additional);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.inner.shrink(reservation, shrink);
+        self.tracker.shrink_memory(shrink);
+    }
+
+    fn try_grow(
+        &self,
+        reservation: &MemoryReservation,
+        additional: usize,
+    ) -> datafusion::common::Result<()> {
+        self.inner.try_grow(reservation, additional)?;
+        self.tracker.grow_memory(additional);
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.inner.reserved()
+    }
+}
+
 #[derive(Clone)]
 pub struct CnosSessionConfig {
     inner: SessionConfig,
@@ -211,4 +303,15 @@ impl CnosSessionConfig {
         self.inner = self.inner.with_extension(Arc::new(interval));
         self
     }
+
+    /// Which vnode of the replication sets serves the reads of the session
+    pub fn with_read_consistency(mut self, read_consistency: ReadConsistency) -> Self {
+        self.inner = self.inner.with_extension(Arc::new(read_consistency));
+        self
+    }
+
+    pub fn with_cpu_shares(mut self, cpu_shares: CpuShares) -> Self {
+        self.inner = self.inner.with_extension(Arc::new(cpu_shares));
+        self
+    }
 }
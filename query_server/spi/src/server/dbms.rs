@@ -6,9 +6,9 @@ use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
 use models::auth::role::UserRole;
 use models::auth::user::{User, UserDesc, UserInfo, UserOptionsBuilder};
-use trace::SpanContext;
+use trace::{debug, SpanContext};
 
-use crate::query::execution::{Output, QueryStateMachine, QueryStateMachineRef};
+use crate::query::execution::{Output, QueryExecutionRef, QueryStateMachine, QueryStateMachineRef};
 use crate::query::logical_planner::Plan;
 use crate::query::recordbatch::RecordBatchStreamWrapper;
 use crate::service::protocol::{Query, QueryHandle, QueryId};
@@ -41,6 +41,8 @@ pub trait DatabaseManagerSystem {
     ) -> Result<QueryHandle>;
     fn metrics(&self) -> String;
     fn cancel(&self, query_id: &QueryId);
+    fn running_queries(&self) -> Vec<QueryExecutionRef>;
+    fn kill_query(&self, query_id: &QueryId) -> bool;
 }
 
 pub struct DatabaseManagerSystemMock {}
@@ -138,4 +140,13 @@ impl DatabaseManagerSystem for DatabaseManagerSystemMock {
     fn cancel(&self, query_id: &QueryId) {
         println!("DatabaseManagerSystemMock::cancel({:?})", query_id);
     }
+
+    fn running_queries(&self) -> Vec<QueryExecutionRef> {
+        vec![]
+    }
+
+    fn kill_query(&self, query_id: &QueryId) -> bool {
+        debug!("DatabaseManagerSystemMock::kill_query({:?})", query_id);
+        false
+    }
 }
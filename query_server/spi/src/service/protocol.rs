@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use models::auth::user::User;
+use models::consistency_level::ReadConsistency;
 use models::oid::uuid_u64;
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE, DEFAULT_PRECISION};
 use serde::{Deserialize, Serialize};
@@ -8,6 +9,7 @@ use trace::{SpanRecorder, SpanRecorderExt};
 
 use crate::query::config::StreamTriggerInterval;
 use crate::query::execution::Output;
+use crate::query::scheduler::CpuShares;
 use crate::query::session::CnosSessionConfig;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -98,6 +100,11 @@ impl Context {
     pub fn chunked(&self) -> bool {
         self.chunked
     }
+
+    pub fn with_cpu_shares(mut self, cpu_shares: CpuShares) -> Self {
+        self.session_config = self.session_config.with_cpu_shares(cpu_shares);
+        self
+    }
 }
 
 impl SpanRecorderExt for Context {
@@ -169,6 +176,13 @@ impl ContextBuilder {
         self
     }
 
+    pub fn with_read_consistency(mut self, read_consistency: Option<ReadConsistency>) -> Self {
+        if let Some(read_consistency) = read_consistency {
+            self.session_config = self.session_config.with_read_consistency(read_consistency);
+        }
+        self
+    }
+
     pub fn with_chunked(mut self, chunked: Option<bool>) -> Self {
         if let Some(chunked) = chunked {
             self.chunked = chunked;
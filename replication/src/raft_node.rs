@@ -134,6 +134,19 @@ impl RaftNode {
         Ok(())
     }
 
+    /// Starts an election on this node, it wins the election if its log is up to date.
+    pub async fn raft_elect(&self) -> ReplicationResult<()> {
+        self.raft
+            .trigger()
+            .elect()
+            .await
+            .map_err(|err| ReplicationError::RaftInternalErr {
+                msg: format!("Elect raft execute failed: {}", err),
+            })?;
+
+        Ok(())
+    }
+
     pub async fn shutdown(&self) -> ReplicationResult<()> {
         self.raft
             .shutdown()
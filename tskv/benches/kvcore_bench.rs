@@ -88,6 +88,7 @@ fn big_write(c: &mut Criterion) {
                 let request = WriteDataRequest {
                     data: points,
                     precision: Precision::NS as u32,
+                    write_time: 0,
                 };
 
                 tskv_write(
@@ -117,6 +118,7 @@ fn run(c: &mut Criterion) {
     let request = WriteDataRequest {
         data: points,
         precision: Precision::NS as u32,
+        write_time: 0,
     };
 
     // maybe 500 us
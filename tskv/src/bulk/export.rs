@@ -0,0 +1,270 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{as_primitive_array, UInt32Array};
+use datafusion::arrow::compute::{cast, take};
+use datafusion::arrow::datatypes::{DataType, Field, Int64Type, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::basic::Compression;
+use datafusion::parquet::file::properties::WriterProperties;
+use futures::StreamExt;
+use models::meta_data::{ReplicationSet, VnodeId};
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRanges};
+use models::predicate::PlacedSplit;
+use models::schema::TskvTableSchemaRef;
+use tokio::runtime::Runtime;
+use trace::{info, SpanRecorder};
+
+use super::day_duration;
+use crate::reader::iterator::{self, QueryOption};
+use crate::{EngineRef, Error, Result};
+
+const EXPORT_BATCH_SIZE: usize = 4096;
+
+#[derive(Debug, Default)]
+pub struct ExportReport {
+    pub vnode_id: VnodeId,
+    pub tables: u64,
+    pub rows: u64,
+    pub files: Vec<PathBuf>,
+}
+
+/// Exports all series of the vnode into Parquet files under `dir`, the data is read
+/// by `reader::iterator`, so the caches and tombstones are taken into account.
+pub async fn export_vnode(
+    runtime: Arc<Runtime>,
+    engine: EngineRef,
+    tenant: &str,
+    database: &str,
+    vnode_id: VnodeId,
+    dir: impl AsRef<Path>,
+) -> Result<ExportReport> {
+    let dir = dir.as_ref();
+    if engine
+        .get_db_version(tenant, database, vnode_id)
+        .await?
+        .is_none()
+    {
+        return Err(Error::VnodeNotFound { vnode_id });
+    }
+    let vnode = engine.open_tsfamily(tenant, database, vnode_id).await?;
+    let schemas = vnode.db.read().await.get_schemas();
+
+    let mut report = ExportReport {
+        vnode_id,
+        ..Default::default()
+    };
+    for table in schemas.list_tables().await? {
+        let table_schema = match schemas.get_table_schema(&table).await? {
+            Some(schema) => schema,
+            None => continue,
+        };
+        let (rows, files) = export_table(
+            runtime.clone(),
+            engine.clone(),
+            table_schema,
+            vnode_id,
+            &dir.join(&table),
+        )
+        .await?;
+        report.tables += 1;
+        report.rows += rows;
+        report.files.extend(files);
+    }
+    info!(
+        "Export: exported vnode {vnode_id} to {}: {report:?}",
+        dir.display()
+    );
+
+    Ok(report)
+}
+
+async fn export_table(
+    runtime: Arc<Runtime>,
+    engine: EngineRef,
+    table_schema: TskvTableSchemaRef,
+    vnode_id: VnodeId,
+    table_dir: &Path,
+) -> Result<(u64, Vec<PathBuf>)> {
+    let predicate = ResolvedPredicate::new(Arc::new(TimeRanges::all()), ColumnDomains::all(), None)
+        .map_err(|e| Error::CommonError {
+            reason: e.to_string(),
+        })?;
+    let split = PlacedSplit::new(
+        0,
+        Arc::new(predicate),
+        None,
+        ReplicationSet::new(0, 0, vnode_id, vec![]),
+    );
+    let option = QueryOption::new(
+        EXPORT_BATCH_SIZE,
+        split,
+        None,
+        table_schema.to_arrow_schema(),
+        table_schema.clone(),
+    );
+    let mut stream =
+        iterator::execute(runtime, engine, option, vnode_id, SpanRecorder::default()).await?;
+
+    let time_column = table_schema.time_column().name;
+    let day = day_duration(&TimeUnit::from(table_schema.time_column_precision()));
+    let mut writers: BTreeMap<i64, (PathBuf, ArrowWriter<File>)> = BTreeMap::new();
+    let mut rows = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = dictionary_encode_tags(&table_schema, batch?)?;
+        rows += batch.num_rows() as u64;
+        for (day_start, day_batch) in split_by_day(&batch, &time_column, day)? {
+            let (_, writer) = match writers.entry(day_start) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    std::fs::create_dir_all(table_dir)?;
+                    let path = table_dir.join(format!("{day_start}_{vnode_id}.parquet"));
+                    let props = WriterProperties::builder()
+                        .set_compression(Compression::SNAPPY)
+                        .build();
+                    let writer = ArrowWriter::try_new(
+                        File::create(&path)?,
+                        day_batch.schema(),
+                        Some(props),
+                    )?;
+                    entry.insert((path, writer))
+                }
+            };
+            writer.write(&day_batch)?;
+        }
+    }
+
+    let mut files = Vec::with_capacity(writers.len());
+    for (_, (path, writer)) in writers {
+        writer.close()?;
+        files.push(path);
+    }
+
+    Ok((rows, files))
+}
+
+/// Casts the tag columns of the batch into `Dictionary(Int32, Utf8)`.
+fn dictionary_encode_tags(
+    table_schema: &TskvTableSchemaRef,
+    batch: RecordBatch,
+) -> Result<RecordBatch> {
+    let dictionary_type = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let is_tag = table_schema
+            .column(field.name())
+            .map(|c| c.column_type.is_tag())
+            .unwrap_or(false);
+        if is_tag {
+            fields.push(
+                Field::new(field.name(), dictionary_type.clone(), true)
+                    .with_metadata(field.metadata().clone()),
+            );
+            columns.push(cast(column, &dictionary_type)?);
+        } else {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+        }
+    }
+    let schema: SchemaRef = Arc::new(Schema::new(fields));
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// Splits the batch by the day of the time column, returns the start of each day
+/// and the rows in it.
+fn split_by_day(
+    batch: &RecordBatch,
+    time_column: &str,
+    day: i64,
+) -> Result<Vec<(i64, RecordBatch)>> {
+    let time_array = batch
+        .column_by_name(time_column)
+        .ok_or_else(|| Error::ColumnNotFound {
+            column: time_column.to_string(),
+        })?;
+    let time_array = cast(time_array, &DataType::Int64)?;
+    let time_array = as_primitive_array::<Int64Type>(&time_array);
+
+    let mut day_rows: BTreeMap<i64, Vec<u32>> = BTreeMap::new();
+    for (i, ts) in time_array.values().iter().enumerate() {
+        day_rows
+            .entry(ts.div_euclid(day) * day)
+            .or_default()
+            .push(i as u32);
+    }
+    if day_rows.len() == 1 {
+        let day_start = day_rows.keys().next().copied().unwrap_or_default();
+        return Ok(vec![(day_start, batch.clone())]);
+    }
+
+    let mut batches = Vec::with_capacity(day_rows.len());
+    for (day_start, rows) in day_rows {
+        let indices = UInt32Array::from(rows);
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|c| take(c.as_ref(), &indices, None))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        batches.push((day_start, RecordBatch::try_new(batch.schema(), columns)?));
+    }
+
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Int64Array, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+
+    use super::split_by_day;
+
+    #[test]
+    fn test_split_by_day() {
+        let day = 86_400_000_000_000;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("f1", DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![1, day + 1, 2, -1])),
+                Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            ],
+        )
+        .unwrap();
+
+        let batches = split_by_day(&batch, "time", day).unwrap();
+        let days = batches.iter().map(|(d, _)| *d).collect::<Vec<_>>();
+        assert_eq!(days, vec![-day, 0, day]);
+        let rows = batches
+            .iter()
+            .map(|(_, b)| {
+                b.column(1)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![vec![4], vec![1, 3], vec![2]]);
+
+        let batches = split_by_day(&batch.slice(0, 1), "time", day).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].0, 0);
+    }
+}
@@ -0,0 +1,752 @@
+use std::cmp::max;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use datafusion::arrow::array::{
+    as_boolean_array, as_primitive_array, as_string_array, Array, ArrayRef,
+};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{
+    DataType as ArrowDataType, Float64Type, Int64Type, SchemaRef, UInt64Type,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use datafusion::parquet::arrow::ProjectionMask;
+use minivec::MiniVec;
+use models::field_value::FieldVal;
+use models::meta_data::VnodeId;
+use models::schema::{PhysicalCType, TableColumn, TskvTableSchema, TskvTableSchemaRef};
+use models::{tag, PhysicalDType, SeriesId, SeriesKey, Tag};
+use protos::kv_service::ImportFile;
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+use trace::{info, warn};
+use utils::BloomFilter;
+
+use crate::compaction::CompactTask;
+use crate::file_system::file_info;
+use crate::file_utils;
+use crate::schema::schemas::DBschemas;
+use crate::summary::{CompactMetaBuilder, SummaryTask, VersionEdit};
+use crate::tseries_family::TseriesFamily;
+use crate::tsm::reader::TsmReader;
+use crate::tsm::writer::{Column, DataBlock, TsmWriter};
+use crate::vnode_store::VnodeStorage;
+use crate::{ColumnFileId, Error, Result};
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub vnode_id: VnodeId,
+    pub files: u64,
+    pub series: u64,
+    pub rows: u64,
+    /// Delta files that are built from the Parquet files.
+    pub import_files: Vec<ImportFile>,
+}
+
+/// Returns the series keys of the Parquet files under `dir` (in the layout of
+/// `export_vnode`), only the tag columns are read. The tables and columns of the
+/// Parquet files must exist in the database.
+pub async fn import_series_keys(
+    vnode: &VnodeStorage,
+    dir: impl AsRef<Path>,
+) -> Result<Vec<SeriesKey>> {
+    let schemas = vnode.db.read().await.get_schemas();
+
+    let mut series_keys: HashSet<SeriesKey> = HashSet::new();
+    for (table, path) in list_parquet_files(dir.as_ref())? {
+        let table_schema = get_table_schema(&schemas, &table).await?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path)?)?;
+        check_columns(&table_schema, builder.schema())?;
+
+        let tag_indices = builder
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| {
+                table_schema
+                    .column(f.name())
+                    .map(|c| c.column_type.is_tag())
+                    .unwrap_or(false)
+            })
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        if tag_indices.is_empty() {
+            // All the rows of the table belong to the same series.
+            if builder.metadata().file_metadata().num_rows() > 0 {
+                series_keys.insert(SeriesKey {
+                    tags: vec![],
+                    table: table_schema.name.clone(),
+                });
+            }
+            continue;
+        }
+
+        let mask = ProjectionMask::roots(builder.parquet_schema(), tag_indices);
+        for batch in builder.with_projection(mask).build()? {
+            series_keys.extend(batch_series_keys(&table_schema, &batch?)?);
+        }
+    }
+
+    Ok(series_keys.into_iter().collect())
+}
+
+/// Builds the Parquet files under `dir` into delta files in the import directory
+/// `import_id` of the vnode, one delta file is built from each Parquet file. The files
+/// are read batch by batch, and the rows of each batch are written to the delta file
+/// before the next batch is read.
+///
+/// The series of the Parquet files must have been added to the index of the vnode, and
+/// the rows of each series must be sorted by time in a Parquet file. The delta files
+/// are numbered in the import directory, they get their file ids when they are added to
+/// the vnode by `apply_import`. The import directory is removed if the build fails.
+pub async fn stage_import(
+    vnode: &VnodeStorage,
+    dir: impl AsRef<Path>,
+    import_id: &str,
+) -> Result<ImportReport> {
+    let dir = dir.as_ref();
+    let (owner, storage_opt) = {
+        let tsf = vnode.ts_family.read().await;
+        (tsf.tenant_database(), tsf.storage_opt())
+    };
+    let import_dir = storage_opt.import_dir(&owner, vnode.id, import_id);
+
+    let mut report = ImportReport {
+        vnode_id: vnode.id,
+        ..Default::default()
+    };
+    if let Err(e) = build_import_files(vnode, dir, &import_dir, &mut report).await {
+        if let Err(e) = tokio::fs::remove_dir_all(&import_dir).await {
+            warn!(
+                "Import: failed to remove import directory {}: {e}",
+                import_dir.display()
+            );
+        }
+        return Err(e);
+    }
+    info!(
+        "Import: staged {} for vnode {} in {}: {report:?}",
+        dir.display(),
+        vnode.id,
+        import_dir.display()
+    );
+
+    Ok(report)
+}
+
+async fn build_import_files(
+    vnode: &VnodeStorage,
+    dir: &Path,
+    import_dir: &Path,
+    report: &mut ImportReport,
+) -> Result<()> {
+    let schemas = vnode.db.read().await.get_schemas();
+    for (table, path) in list_parquet_files(dir)? {
+        let table_schema = get_table_schema(&schemas, &table).await?;
+
+        // The last timestamp of each series in the Parquet file.
+        let mut last_ts: HashMap<SeriesKey, i64> = HashMap::new();
+        let mut writer: Option<TsmWriter> = None;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path)?)?.build()?;
+        for batch in reader {
+            let mut series: HashMap<SeriesKey, SeriesRows> = HashMap::new();
+            report.rows += append_batch(&table_schema, &batch?, &mut last_ts, &mut series)?;
+            if series.is_empty() {
+                continue;
+            }
+
+            if writer.is_none() {
+                let file_id = report.import_files.len() as u64 + 1;
+                writer = Some(TsmWriter::open(&import_dir, file_id, 0, true).await?);
+            }
+            let writer = writer.as_mut().expect("delta file writer is opened");
+            for (series_key, rows) in series {
+                let series_id = vnode
+                    .ts_index
+                    .get_series_id(&series_key)
+                    .await?
+                    .ok_or_else(|| Error::CommonError {
+                        reason: format!(
+                            "series '{}' of the import is not in the index",
+                            series_key.string()
+                        ),
+                    })?;
+                let data_block = rows.into_data_block(table_schema.clone());
+                let len = data_block.len();
+                let mut start = 0;
+                while start < len {
+                    let end = len.min(start + TseriesFamily::MAX_DATA_BLOCK_SIZE as usize);
+                    writer
+                        .write_datablock(
+                            series_id,
+                            series_key.clone(),
+                            data_block.chunk(start, end)?,
+                        )
+                        .await?;
+                    start = end;
+                }
+            }
+        }
+        report.files += 1;
+        report.series += last_ts.len() as u64;
+        let mut writer = match writer {
+            Some(writer) => writer,
+            None => continue,
+        };
+        writer.finish().await?;
+
+        let name = file_utils::make_delta_file_name(writer.file_id());
+        let file_path = import_dir.join(&name).to_string_lossy().to_string();
+        let file_info = file_info::get_file_info(&file_path).await?;
+        report.import_files.push(ImportFile {
+            name,
+            size: file_info.size,
+            md5: file_info.md5,
+            min_ts: writer.min_ts(),
+            max_ts: writer.max_ts(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Adds the delta files staged in the import directory `import_id` to the vnode, the
+/// files are checked against `files` and the series ids in the files are checked
+/// against the index of the vnode. The `VersionEdit` of the import is written with
+/// `seq_no`, and the import directory is removed after the edit is applied.
+///
+/// The memcache of the vnode must have been flushed, so that the imported files are
+/// not older than the flushed data of the same `seq_no`.
+pub async fn apply_import(
+    vnode: &VnodeStorage,
+    import_id: &str,
+    files: &[ImportFile],
+    seq_no: u64,
+) -> Result<()> {
+    let (owner, storage_opt) = {
+        let tsf = vnode.ts_family.read().await;
+        (tsf.tenant_database(), tsf.storage_opt())
+    };
+    let import_dir = storage_opt.import_dir(&owner, vnode.id, import_id);
+    for file in files {
+        let path = import_dir.join(&file.name);
+        let size = match tokio::fs::metadata(&path).await {
+            Ok(meta) => meta.len(),
+            Err(e) => {
+                return Err(Error::CommonError {
+                    reason: format!("staged import file {} is not found: {e}", path.display()),
+                })
+            }
+        };
+        if size != file.size {
+            return Err(Error::CommonError {
+                reason: format!(
+                    "staged import file {} has {size} bytes, expected {}",
+                    path.display(),
+                    file.size
+                ),
+            });
+        }
+    }
+
+    let delta_dir = storage_opt.delta_dir(&owner, vnode.id);
+    let mut linked_files = vec![];
+    if let Err(e) = add_import_files(
+        vnode,
+        &owner,
+        &import_dir,
+        &delta_dir,
+        files,
+        seq_no,
+        &mut linked_files,
+    )
+    .await
+    {
+        for path in linked_files {
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                warn!(
+                    "Import: failed to remove delta file {}: {e}",
+                    path.display()
+                );
+            }
+        }
+        return Err(e);
+    }
+
+    if let Err(e) = tokio::fs::remove_dir_all(&import_dir).await {
+        warn!(
+            "Import: failed to remove import directory {}: {e}",
+            import_dir.display()
+        );
+    }
+    info!(
+        "Import: added {} files of import {import_id} to vnode {}",
+        files.len(),
+        vnode.id
+    );
+
+    Ok(())
+}
+
+/// Removes the import directory `import_id` of the vnode, the import is aborted on all
+/// the replicas when `AbortImportRequest` is applied. The abort is ordered after the
+/// `ImportFilesRequest` of the import in the raft log, so if the import is committed
+/// anyway, it is added to the vnode before the directory is removed.
+pub async fn abort_import(vnode: &VnodeStorage, import_id: &str) -> Result<()> {
+    let import_dir = {
+        let tsf = vnode.ts_family.read().await;
+        tsf.storage_opt()
+            .import_dir(&tsf.tenant_database(), vnode.id, import_id)
+    };
+    match tokio::fs::remove_dir_all(&import_dir).await {
+        Ok(()) => {
+            info!("Import: aborted import {import_id} of vnode {}", vnode.id);
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn add_import_files(
+    vnode: &VnodeStorage,
+    owner: &str,
+    import_dir: &Path,
+    delta_dir: &Path,
+    files: &[ImportFile],
+    seq_no: u64,
+    linked_files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    tokio::fs::create_dir_all(delta_dir).await?;
+
+    let version = vnode.ts_family.read().await.version();
+    let compact_meta_builder = CompactMetaBuilder::new(vnode.id);
+    let mut edit = VersionEdit::new_update_vnode(vnode.id, owner.to_string(), seq_no);
+    let mut file_metas: HashMap<ColumnFileId, Arc<BloomFilter>> = HashMap::new();
+    let mut max_level_ts = version.max_level_ts();
+    let mut loaded_series: HashSet<SeriesId> = HashSet::new();
+    for file in files {
+        // The staged file is linked into the delta directory, so it is still in the
+        // import directory if the edit is not applied.
+        let file_id = vnode.ctx.global_ctx.file_id_next();
+        let path = file_utils::make_delta_file(delta_dir, file_id);
+        tokio::fs::hard_link(import_dir.join(&file.name), &path).await?;
+        linked_files.push(path.clone());
+
+        let reader = TsmReader::open(&path).await?;
+        for (series_id, chunk) in reader.chunk() {
+            let index_series_id = vnode.ts_index.get_series_id(chunk.series_key()).await?;
+            if index_series_id != Some(*series_id) {
+                return Err(Error::CommonError {
+                    reason: format!(
+                        "series '{}' is {series_id} in import file {}, but {index_series_id:?} in the index",
+                        chunk.series_key().string(),
+                        file.name
+                    ),
+                });
+            }
+            loaded_series.insert(*series_id);
+        }
+        file_metas.insert(
+            file_id,
+            Arc::new(reader.footer().series().bloom_filter().clone()),
+        );
+
+        let delta_meta =
+            compact_meta_builder.build(file_id, file.size, 0, file.min_ts, file.max_ts);
+        max_level_ts = max(max_level_ts, delta_meta.max_ts);
+        edit.add_file(delta_meta, max_level_ts);
+    }
+
+    let (task_state_sender, task_state_receiver) = oneshot::channel();
+    let task = SummaryTask::new(
+        vnode.ts_family.clone(),
+        edit,
+        Some(file_metas),
+        None,
+        task_state_sender,
+    );
+    vnode
+        .ctx
+        .summary_task_sender
+        .send(task)
+        .await
+        .map_err(|e| Error::CommonError {
+            reason: format!("failed to send summary task: {e}"),
+        })?;
+    match timeout(Duration::from_secs(10), task_state_receiver).await {
+        Ok(Ok(res)) => res?,
+        Ok(Err(e)) => {
+            return Err(Error::CommonError {
+                reason: format!("failed to receive summary task result: {e}"),
+            })
+        }
+        Err(_) => {
+            return Err(Error::CommonError {
+                reason: "failed to receive summary task result in 10 seconds".to_string(),
+            })
+        }
+    }
+    // The imported points are not written into the last value cache.
+    vnode
+        .ts_family
+        .read()
+        .await
+        .invalidate_last_values(&loaded_series.into_iter().collect::<Vec<_>>(), max_level_ts);
+
+    if let Err(e) = vnode
+        .ctx
+        .compact_task_sender
+        .send(CompactTask { tsf_id: vnode.id })
+        .await
+    {
+        warn!(
+            "Import: failed to send compact task for vnode {}: {e}",
+            vnode.id
+        );
+    }
+
+    Ok(())
+}
+
+async fn get_table_schema(schemas: &DBschemas, table: &str) -> Result<TskvTableSchemaRef> {
+    schemas
+        .get_table_schema(table)
+        .await?
+        .ok_or_else(|| Error::TableNotFound {
+            table: table.to_string(),
+        })
+}
+
+/// Checks that the columns of the Parquet file exist in the table.
+fn check_columns(table_schema: &TskvTableSchema, schema: &SchemaRef) -> Result<()> {
+    for field in schema.fields() {
+        if table_schema.column(field.name()).is_none() {
+            return Err(Error::ColumnNotFound {
+                column: format!("{}.{}", table_schema.name, field.name()),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns (table, path) of the Parquet files in `<dir>/<table>/`.
+fn list_parquet_files(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = vec![];
+    for entry in walkdir::WalkDir::new(dir)
+        .min_depth(2)
+        .max_depth(2)
+        .sort_by_file_name()
+        .into_iter()
+    {
+        let entry = entry.map_err(|e| Error::CommonError {
+            reason: e.to_string(),
+        })?;
+        let path = entry.path();
+        if !entry.file_type().is_file()
+            || path.extension().and_then(|e| e.to_str()) != Some("parquet")
+        {
+            continue;
+        }
+        let table = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str());
+        if let Some(table) = table {
+            files.push((table.to_string(), path.to_path_buf()));
+        }
+    }
+
+    Ok(files)
+}
+
+/// The rows of a series, columns are in the order of `TskvTableSchema::fields`.
+struct SeriesRows {
+    ts: Column,
+    fields: Vec<Column>,
+}
+
+impl SeriesRows {
+    fn new(table_schema: &TskvTableSchema) -> Result<Self> {
+        let ts = Column::empty(table_schema.time_column().column_type.to_physical_type())?;
+        let fields = table_schema
+            .fields()
+            .iter()
+            .map(|c| Column::empty(c.column_type.to_physical_type()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { ts, fields })
+    }
+
+    fn into_data_block(self, table_schema: TskvTableSchemaRef) -> DataBlock {
+        DataBlock::new(
+            table_schema.clone(),
+            self.ts,
+            table_schema.time_column(),
+            self.fields,
+            table_schema.fields(),
+        )
+    }
+}
+
+/// Appends the rows of the batch to the series they belong to, returns the number of rows.
+///
+/// `last_ts` holds the last timestamp of each series in the former batches, it is
+/// used to check that the rows of a series are sorted by time across batches.
+fn append_batch(
+    table_schema: &TskvTableSchema,
+    batch: &RecordBatch,
+    last_ts: &mut HashMap<SeriesKey, i64>,
+    series: &mut HashMap<SeriesKey, SeriesRows>,
+) -> Result<u64> {
+    check_columns(table_schema, &batch.schema())?;
+
+    let time_column = table_schema.time_column();
+    let time_array =
+        batch
+            .column_by_name(&time_column.name)
+            .ok_or_else(|| Error::ColumnNotFound {
+                column: format!("{}.{}", table_schema.name, time_column.name),
+            })?;
+    if time_array.null_count() > 0 {
+        return Err(Error::CommonError {
+            reason: format!("time column of table {} has null values", table_schema.name),
+        });
+    }
+    let time_array = cast(
+        &cast(time_array, &time_column.column_type.clone().into())?,
+        &ArrowDataType::Int64,
+    )?;
+    let time_array = as_primitive_array::<Int64Type>(&time_array);
+
+    let mut field_arrays: Vec<Option<ArrayRef>> = vec![];
+    for column in table_schema.fields() {
+        let array = match batch.column_by_name(&column.name) {
+            Some(array) => Some(cast(array, &column.column_type.clone().into())?),
+            None => None,
+        };
+        field_arrays.push(array);
+    }
+    let field_types = table_schema
+        .fields()
+        .iter()
+        .map(|c| c.column_type.to_physical_type())
+        .collect::<Vec<_>>();
+
+    let series_keys = batch_series_keys(table_schema, batch)?;
+    for (row, series_key) in series_keys.into_iter().enumerate() {
+        let ts = time_array.value(row);
+        match last_ts.get_mut(&series_key) {
+            Some(last_ts) => {
+                if ts <= *last_ts {
+                    return Err(Error::CommonError {
+                        reason: format!(
+                            "rows of series '{}' are not sorted by time: {ts} after {last_ts}",
+                            series_key.string()
+                        ),
+                    });
+                }
+                *last_ts = ts;
+            }
+            None => {
+                last_ts.insert(series_key.clone(), ts);
+            }
+        }
+        let rows = match series.entry(series_key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(SeriesRows::new(table_schema)?),
+        };
+        rows.ts.push(Some(FieldVal::Integer(ts)));
+        for ((column, array), field_type) in rows
+            .fields
+            .iter_mut()
+            .zip(field_arrays.iter())
+            .zip(field_types.iter())
+        {
+            let value = match array {
+                Some(array) => field_value(array, field_type, row),
+                None => None,
+            };
+            column.push(value);
+        }
+    }
+
+    Ok(batch.num_rows() as u64)
+}
+
+/// Returns the series key of each row of the batch.
+fn batch_series_keys(
+    table_schema: &TskvTableSchema,
+    batch: &RecordBatch,
+) -> Result<Vec<SeriesKey>> {
+    let mut tag_arrays: Vec<(&TableColumn, ArrayRef)> = vec![];
+    for column in table_schema
+        .columns()
+        .iter()
+        .filter(|c| c.column_type.is_tag())
+    {
+        if let Some(array) = batch.column_by_name(&column.name) {
+            tag_arrays.push((column, cast(array, &ArrowDataType::Utf8)?));
+        }
+    }
+
+    let mut series_keys = Vec::with_capacity(batch.num_rows());
+    for row in 0..batch.num_rows() {
+        let mut tags = Vec::with_capacity(tag_arrays.len());
+        for (column, array) in tag_arrays.iter() {
+            if array.is_valid(row) {
+                let value = as_string_array(array).value(row);
+                tags.push(Tag::new_with_column_id(
+                    column.id,
+                    value.as_bytes().to_vec(),
+                ));
+            }
+        }
+        tag::sort_tags(&mut tags);
+        series_keys.push(SeriesKey {
+            tags,
+            table: table_schema.name.clone(),
+        });
+    }
+
+    Ok(series_keys)
+}
+
+fn field_value(array: &ArrayRef, field_type: &PhysicalCType, row: usize) -> Option<FieldVal> {
+    if array.is_null(row) {
+        return None;
+    }
+    match field_type {
+        PhysicalCType::Field(PhysicalDType::Float) => Some(FieldVal::Float(
+            as_primitive_array::<Float64Type>(array).value(row),
+        )),
+        PhysicalCType::Field(PhysicalDType::Integer) => Some(FieldVal::Integer(
+            as_primitive_array::<Int64Type>(array).value(row),
+        )),
+        PhysicalCType::Field(PhysicalDType::Unsigned) => Some(FieldVal::Unsigned(
+            as_primitive_array::<UInt64Type>(array).value(row),
+        )),
+        PhysicalCType::Field(PhysicalDType::Boolean) => {
+            Some(FieldVal::Boolean(as_boolean_array(array).value(row)))
+        }
+        PhysicalCType::Field(PhysicalDType::String) => Some(FieldVal::Bytes(MiniVec::from(
+            as_string_array(array).value(row).as_bytes(),
+        ))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{DictionaryArray, Float64Array, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Int32Type, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::codec::Encoding;
+    use models::schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::ValueType;
+
+    use super::append_batch;
+
+    fn table_schema() -> TskvTableSchema {
+        TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "air".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(1, "station".to_string()),
+                TableColumn::new(
+                    2,
+                    "visibility".to_string(),
+                    ColumnType::Field(ValueType::Float),
+                    Encoding::default(),
+                ),
+            ],
+        )
+    }
+
+    fn batch(ts: Vec<i64>, stations: Vec<&str>, visibility: Vec<Option<f64>>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new(
+                "station",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                true,
+            ),
+            Field::new("visibility", DataType::Float64, true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(ts)),
+                Arc::new(stations.into_iter().collect::<DictionaryArray<Int32Type>>()),
+                Arc::new(Float64Array::from(visibility)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_append_batch() {
+        let schema = table_schema();
+        let mut last_ts = HashMap::new();
+        let mut series = HashMap::new();
+        let rows = append_batch(
+            &schema,
+            &batch(
+                vec![1, 1, 2, 3],
+                vec!["a", "b", "a", "b"],
+                vec![Some(1.0), Some(2.0), None, Some(4.0)],
+            ),
+            &mut last_ts,
+            &mut series,
+        )
+        .unwrap();
+        assert_eq!(rows, 4);
+        assert_eq!(series.len(), 2);
+        for (key, rows) in series.iter() {
+            assert_eq!(key.table(), "air");
+            assert_eq!(rows.ts.len(), 2);
+            assert_eq!(rows.fields.len(), 1);
+            assert_eq!(rows.fields[0].len(), 2);
+        }
+
+        // The next batch only holds its own rows.
+        let mut series = HashMap::new();
+        let rows = append_batch(
+            &schema,
+            &batch(vec![4], vec!["b"], vec![Some(5.0)]),
+            &mut last_ts,
+            &mut series,
+        )
+        .unwrap();
+        assert_eq!(rows, 1);
+        assert_eq!(series.len(), 1);
+        assert_eq!(last_ts.len(), 2);
+
+        // Rows of series 'a' are not sorted by time across the batches.
+        let res = append_batch(
+            &schema,
+            &batch(vec![1], vec!["a"], vec![Some(1.0)]),
+            &mut last_ts,
+            &mut HashMap::new(),
+        );
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,38 @@
+//! Bulk export and import of the raw data of a vnode.
+//!
+//! A vnode is exported as a directory of Parquet files, one sub-directory for each
+//! table and one file for each day of data: `<dir>/<table>/<day_start>_<vnode_id>.parquet`.
+//! Tag columns are written as dictionary columns.
+//!
+//! The same layout can be imported into a vnode, TSM files are built from the Parquet
+//! files directly and added to the vnode by a `VersionEdit`, so the WAL and the
+//! memcache are bypassed. The import runs on the leader of the replication set:
+//! - the series of the files are written to the raft log by `ImportSeriesRequest`, so
+//!   they get the same ids on all the replicas;
+//! - the delta files are built into the import directory of the leader vnode by
+//!   `stage_import`, and downloaded into the import directory of the other replicas;
+//! - the staged files are written to the raft log by `ImportFilesRequest`, and each
+//!   vnode of the set adds them to its version by `apply_import` when it applies the
+//!   command;
+//! - if the import fails after the files are staged, `AbortImportRequest` is written to
+//!   the raft log, and each vnode of the set removes its staged files by `abort_import`.
+//!
+//! So the directory of the Parquet files only needs to be readable on the leader node.
+
+mod export;
+mod load;
+
+pub use export::{export_vnode, ExportReport};
+pub use load::{abort_import, apply_import, import_series_keys, stage_import, ImportReport};
+
+use datafusion::arrow::datatypes::TimeUnit;
+
+/// Returns the duration of a day in the time unit.
+fn day_duration(unit: &TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => 86_400,
+        TimeUnit::Millisecond => 86_400_000,
+        TimeUnit::Microsecond => 86_400_000_000,
+        TimeUnit::Nanosecond => 86_400_000_000_000,
+    }
+}
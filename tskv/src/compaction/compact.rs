@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use models::predicate::domain::TimeRange;
 use models::schema::TskvTableSchemaRef;
+use models::utils::now_timestamp_nanos;
 use models::{SeriesId, SeriesKey};
 use trace::{info, trace};
 use utils::BloomFilter;
@@ -786,6 +787,7 @@ pub async fn run_compaction_job(
     let max_file_size = request.storage_opt.level_max_file_size(request.out_level);
     let mut tsm_writer =
         TsmWriter::open(&tsm_dir, kernel.file_id_next(), max_file_size, false).await?;
+    tsm_writer.set_level(request.out_level);
     // let mut tsm_writer = tsm::new_tsm_writer(&tsm_dir, kernel.file_id_next(), false, 0).await?;
     info!(
         "Compaction: File: {} been created (level: {}).",
@@ -818,6 +820,7 @@ pub async fn run_compaction_job(
                     tsm_writer =
                         TsmWriter::open(&tsm_dir, kernel.file_id_next(), max_file_size, false)
                             .await?;
+                    tsm_writer.set_level(request.out_level);
                 }
             }
         }
@@ -851,6 +854,7 @@ pub async fn run_compaction_job(
             {
                 tsm_writer =
                     TsmWriter::open(&tsm_dir, kernel.file_id_next(), max_file_size, false).await?;
+                tsm_writer.set_level(request.out_level);
             }
         }
     }
@@ -928,6 +932,8 @@ fn new_compact_meta(
         min_ts: tsm_writer.min_ts(),
         max_ts: tsm_writer.max_ts(),
         is_delta: false,
+        is_late: false,
+        create_time: now_timestamp_nanos(),
     }
 }
 
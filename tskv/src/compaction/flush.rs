@@ -2,7 +2,7 @@ use std::cmp::max;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use parking_lot::RwLock;
 use tokio::sync::oneshot;
@@ -14,6 +14,7 @@ use crate::compaction::{CompactTask, FlushReq};
 use crate::context::GlobalContext;
 use crate::error::Result;
 use crate::memcache::MemCache;
+use crate::stats::FlushRecord;
 use crate::summary::{CompactMetaBuilder, SummaryTask, VersionEdit};
 use crate::tseries_family::Version;
 use crate::tsm::writer::TsmWriter;
@@ -57,10 +58,12 @@ impl FlushTask {
     ) -> Result<HashMap<u64, Arc<BloomFilter>>> {
         let mut tsm_writer = None;
         let mut delta_writer = None;
+        let mut late_writer = None;
         let mut file_metas = HashMap::new();
 
         for memcache in self.mem_caches {
-            let (group, delta_group) = memcache.read().to_chunk_group(version.clone())?;
+            let (group, delta_group, late_group) =
+                memcache.read().to_chunk_group(version.clone())?;
             if tsm_writer.is_none() && !group.is_empty() {
                 tsm_writer = Some(
                     TsmWriter::open(&self.path_tsm, self.global_context.file_id_next(), 0, false)
@@ -78,12 +81,26 @@ impl FlushTask {
                     .await?,
                 );
             }
+            if late_writer.is_none() && !late_group.is_empty() {
+                let mut writer = TsmWriter::open(
+                    &self.path_delta,
+                    self.global_context.file_id_next(),
+                    0,
+                    true,
+                )
+                .await?;
+                writer.set_late(true);
+                late_writer = Some(writer);
+            }
             if let Some(tsm_writer) = tsm_writer.as_mut() {
                 tsm_writer.write_data(group).await?;
             }
             if let Some(delta_writer) = delta_writer.as_mut() {
                 delta_writer.write_data(delta_group).await?;
             }
+            if let Some(late_writer) = late_writer.as_mut() {
+                late_writer.write_data(late_group).await?;
+            }
         }
 
         let compact_meta_builder = CompactMetaBuilder::new(self.ts_family_id);
@@ -125,6 +142,26 @@ impl FlushTask {
             edit.add_file(delta_meta, max_level_ts);
         }
 
+        if let Some(mut late_writer) = late_writer {
+            late_writer.finish().await?;
+            file_metas.insert(
+                late_writer.file_id(),
+                Arc::new(late_writer.series_bloom_filter().clone()),
+            );
+
+            let mut late_meta = compact_meta_builder.build(
+                late_writer.file_id(),
+                late_writer.size() as u64,
+                0,
+                late_writer.min_ts(),
+                late_writer.max_ts(),
+            );
+            late_meta.is_late = true;
+
+            max_level_ts = max(max_level_ts, late_meta.max_ts);
+            edit.add_file(late_meta, max_level_ts);
+        }
+
         Ok(file_metas)
     }
 }
@@ -173,11 +210,28 @@ pub async fn run_flush_memtable_job(
         path_delta,
     );
 
+    let start = Instant::now();
+    let start_time = models::utils::now_timestamp_nanos();
     let mut version_edit =
         VersionEdit::new_update_vnode(req.ts_family_id, req.owner, req.high_seq_no);
-    if let Ok(fm) = flush_task.run(version.clone(), &mut version_edit).await {
-        file_metas = fm;
-    }
+    let flush_error = match flush_task.run(version.clone(), &mut version_edit).await {
+        Ok(fm) => {
+            file_metas = fm;
+            None
+        }
+        Err(e) => Some(e.to_string()),
+    };
+    ctx.storage_history.record_flush(FlushRecord {
+        owner: database.clone(),
+        vnode_id: req.ts_family_id,
+        start_time,
+        duration: start.elapsed().as_secs_f64(),
+        low_seq_no: req.low_seq_no,
+        high_seq_no: req.high_seq_no,
+        output_files: version_edit.add_files.len() as u64,
+        output_bytes: version_edit.add_files.iter().map(|f| f.file_size).sum(),
+        error: flush_error,
+    });
 
     tsf.read().await.update_last_modified().await;
     if trigger_compact {
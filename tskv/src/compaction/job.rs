@@ -9,6 +9,7 @@ use tokio::sync::{oneshot, RwLock, RwLockWriteGuard, Semaphore};
 use trace::{error, info};
 
 use crate::compaction::{flush, CompactTask, FlushReq, LevelCompactionPicker, Picker};
+use crate::stats::CompactionRecord;
 use crate::summary::SummaryTask;
 use crate::{TsKvContext, TseriesFamilyId};
 
@@ -158,7 +159,6 @@ impl CompactJobInner {
                         .await;
                     if let Some(tsf) = ts_family {
                         info!("Starting compaction on ts_family {}", vnode_id);
-                        let start = Instant::now();
                         if !tsf.read().await.can_compaction() {
                             info!("forbidden compaction on moving vnode {}", vnode_id);
                             return;
@@ -170,6 +170,8 @@ impl CompactJobInner {
                             let database = req.database.clone();
                             let compact_ts_family = req.ts_family_id;
                             let out_level = req.out_level;
+                            let input_files = req.files.len() as u64;
+                            let input_bytes = req.files.iter().map(|f| f.size()).sum::<u64>();
 
                             // Method acquire_owned() will return AcquireError if the semaphore has been closed.
                             let permit = compaction_limit.clone().acquire_owned().await.unwrap();
@@ -188,9 +190,33 @@ impl CompactJobInner {
                                     running_compaction.fetch_sub(1, atomic::Ordering::SeqCst);
                                 }));
 
+                                // Time the job from when it holds the permit, so waiting for
+                                // other compactions is not counted in its duration.
+                                let start = Instant::now();
+                                let start_time = models::utils::now_timestamp_nanos();
+                                let mut record = CompactionRecord {
+                                    owner: database.clone(),
+                                    vnode_id: compact_ts_family,
+                                    out_level,
+                                    start_time,
+                                    duration: 0.0,
+                                    input_files,
+                                    input_bytes,
+                                    output_files: 0,
+                                    output_bytes: 0,
+                                    error: None,
+                                };
                                 match super::run_compaction_job(req, ctx.global_ctx.clone()).await {
                                     Ok(Some((version_edit, file_metas))) => {
                                         metrics::incr_compaction_success();
+                                        record.output_files = version_edit.add_files.len() as u64;
+                                        record.output_bytes = version_edit
+                                            .add_files
+                                            .iter()
+                                            .map(|f| f.file_size)
+                                            .sum();
+                                        record.duration = start.elapsed().as_secs_f64();
+                                        ctx.storage_history.record_compaction(record);
                                         let (summary_tx, _summary_rx) = oneshot::channel();
                                         let _ = ctx
                                             .summary_task_sender
@@ -217,6 +243,9 @@ impl CompactJobInner {
                                     Err(e) => {
                                         metrics::incr_compaction_failed();
                                         error!("Compaction job failed: {:?}", e);
+                                        record.duration = start.elapsed().as_secs_f64();
+                                        record.error = Some(e.to_string());
+                                        ctx.storage_history.record_compaction(record);
                                     }
                                 }
                                 drop(permit);
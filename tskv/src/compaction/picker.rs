@@ -112,6 +112,10 @@ impl Picker for LevelCompactionPicker {
             if file.is_compacting() || !file.time_range().overlaps(&picking_time_range) {
                 continue;
             }
+            // The late tier is compacted on a slower schedule.
+            if file.is_late() && !file.is_older_than(storage_opt.late_compact_interval) {
+                continue;
+            }
             if !file.mark_compacting() {
                 // If file already compacting, continue to next file.
                 continue;
@@ -300,6 +304,7 @@ mod test {
     use memory_pool::{GreedyMemoryPool, MemoryPoolRef};
     use metrics::metric_register::MetricsRegister;
     use models::predicate::domain::TimeRange;
+    use models::utils::now_timestamp_nanos;
 
     use crate::compaction::test::create_options;
     use crate::compaction::{LevelCompactionPicker, Picker};
@@ -324,10 +329,13 @@ mod test {
     ///   - Timestamp_end
     ///   - size
     ///   - being_compact
+    ///
+    /// The files in late_files are of the late tier, with the given create time.
     fn create_tseries_family(
         database: Arc<String>,
         opt: Arc<Options>,
         levels_sketch: LevelsSketch,
+        late_files: &[(u64, i64)],
     ) -> TseriesFamily {
         let ts_family_id = 0;
         let mut level_infos =
@@ -340,7 +348,7 @@ mod test {
             let mut cur_size = 0_u64;
             for (file_id, fts_min, fts_max, file_size, compacting) in column_files_sketch {
                 cur_size += file_size;
-                let col = ColumnFile::new(
+                let mut col = ColumnFile::new(
                     file_id,
                     level,
                     TimeRange::new(fts_min, fts_max),
@@ -348,6 +356,9 @@ mod test {
                     level == 0,
                     make_tsm_file(tsm_dir, file_id),
                 );
+                if let Some((_, create_time)) = late_files.iter().find(|(id, _)| *id == file_id) {
+                    col.set_late(*create_time);
+                }
                 if compacting {
                     col.mark_compacting();
                 }
@@ -423,10 +434,50 @@ mod test {
         ];
 
         let storage_opt = opt.storage.clone();
-        let tsf = create_tseries_family(Arc::new("dba".to_string()), opt, levels_sketch);
+        let tsf = create_tseries_family(Arc::new("dba".to_string()), opt, levels_sketch, &[]);
         let picker = LevelCompactionPicker::new(storage_opt);
         let compact_req = picker.pick_compaction(tsf.version()).unwrap();
         assert_eq!(compact_req.out_level, 2);
         assert_eq!(compact_req.files.len(), 2);
     }
+
+    #[test]
+    fn test_pick_late_tier() {
+        //! The level 0 files of the late tier are picked only if they were
+        //! written at least late_compact_interval ago.
+        let dir = "/tmp/test/pick/late_tier";
+        let opt = create_options(dir.to_string());
+        let late_compact_interval = opt.storage.late_compact_interval.as_nanos() as i64;
+        let now = now_timestamp_nanos();
+
+        #[rustfmt::skip]
+        let levels_sketch: LevelsSketch = vec![
+            (0_u32, 1_i64, 3000_i64, vec![
+                (1_u64, 1_i64, 1000_i64, 1000_u64, false),
+                (2, 1001, 2000, 1000, false),
+                (3, 2001, 3000, 1000, false),
+            ]),
+            (1, 1, 4000, vec![
+                (5, 1, 1000, 1000, false),
+                (6, 1001, 2000, 1000, false),
+                (7, 2001, 3000, 1000, false),
+                (8, 3001, 4000, 1000, false),
+            ]),
+        ];
+        let late_files = [(2, now), (3, now - late_compact_interval)];
+
+        let storage_opt = opt.storage.clone();
+        let tsf =
+            create_tseries_family(Arc::new("dba".to_string()), opt, levels_sketch, &late_files);
+        let picker = LevelCompactionPicker::new(storage_opt);
+        let compact_req = picker.pick_compaction(tsf.version()).unwrap();
+        assert_eq!(compact_req.out_level, 2);
+        let mut file_ids = compact_req
+            .files
+            .iter()
+            .map(|f| f.file_id())
+            .collect::<Vec<_>>();
+        file_ids.sort();
+        assert_eq!(file_ids, vec![1, 3, 5, 6, 7, 8]);
+    }
 }
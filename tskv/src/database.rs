@@ -8,7 +8,9 @@ use memory_pool::MemoryPoolRef;
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
 use models::predicate::domain::TimeRange;
-use models::schema::{DatabaseSchema, Precision, TskvTableSchema, TskvTableSchemaRef};
+use models::schema::{
+    DatabaseSchema, LateArrivalPolicy, Precision, TskvTableSchema, TskvTableSchemaRef,
+};
 use models::{SeriesId, SeriesKey};
 use protos::models::{Column, ColumnType, FieldType, Table};
 use snafu::ResultExt;
@@ -17,6 +19,7 @@ use tokio::sync::{oneshot, RwLock};
 use trace::error;
 
 use crate::error::{Result, SchemaSnafu};
+use crate::idempotency::IdempotencyKeys;
 use crate::index::{self, IndexResult};
 use crate::kv_option::Options;
 use crate::memcache::{OrderedRowsData, RowData, RowGroup};
@@ -40,6 +43,7 @@ pub struct Database {
     ts_indexes: HashMap<TseriesFamilyId, Arc<index::ts_index::TSIndex>>,
     ts_families: HashMap<TseriesFamilyId, Arc<RwLock<TseriesFamily>>>,
     tsf_factory: TsfFactory,
+    idempotency_keys: Arc<parking_lot::Mutex<IdempotencyKeys>>,
 }
 
 #[derive(Debug)]
@@ -102,14 +106,16 @@ impl Database {
             ts_indexes: HashMap::new(),
             ts_families: HashMap::new(),
             tsf_factory,
+            idempotency_keys: Default::default(),
         };
 
         Ok(db)
     }
 
-    pub fn open_tsfamily(&mut self, ver: Arc<Version>) {
+    pub async fn open_tsfamily(&mut self, ver: Arc<Version>) {
         let tf_id = ver.tf_id();
         let tf = self.tsf_factory.create_tsf(tf_id, ver.clone());
+        tf.recover_last_values(self.schemas.clone());
         self.ts_families
             .insert(ver.tf_id(), Arc::new(RwLock::new(tf)));
     }
@@ -199,11 +205,13 @@ impl Database {
         }
 
         let _ = task_state_receiver.await;
+        tf.read().await.recover_last_values(self.schemas.clone());
 
         Ok(tf)
     }
 
     pub async fn del_tsfamily(&mut self, tf_id: u32, summary_task_sender: Sender<SummaryTask>) {
+        self.idempotency_keys.lock().remove_vnode(tf_id);
         if let Some(tf) = self.ts_families.remove(&tf_id) {
             let owner = tf.read().await.tenant_database();
             let seq = tf.read().await.version().last_seq();
@@ -218,14 +226,18 @@ impl Database {
         }
     }
 
+    /// Build the row groups of the series, the late points of the tables with
+    /// `LateArrivalPolicy::LateTier` are put in separate groups by `write_time`,
+    /// no point is late if `write_time` is 0.
     pub async fn build_write_group(
         &self,
         precision: Precision,
+        write_time: i64,
         tables: FlatBufferTable<'_>,
         ts_index: Arc<index::ts_index::TSIndex>,
         recover_from_wal: bool,
         strict_write: Option<bool>,
-    ) -> Result<HashMap<SeriesId, (SeriesKey, RowGroup)>> {
+    ) -> Result<HashMap<SeriesId, (SeriesKey, Vec<RowGroup>)>> {
         let strict_write = strict_write.unwrap_or(self.opt.storage.strict_write);
 
         // (series id, schema id) -> RowGroup
@@ -267,6 +279,7 @@ impl Database {
                 schema.clone(),
                 &mut map,
                 precision,
+                write_time,
                 &sids,
             )?;
         }
@@ -278,10 +291,17 @@ impl Database {
         fb_schema: &FbSchema<'_>,
         columns: &Vector<ForwardsUOffset<Column>>,
         table_schema: TskvTableSchemaRef,
-        map: &mut HashMap<SeriesId, (SeriesKey, RowGroup)>,
+        map: &mut HashMap<SeriesId, (SeriesKey, Vec<RowGroup>)>,
         precision: Precision,
+        write_time: i64,
         sids: &[(u32, SeriesKey)],
     ) -> Result<()> {
+        let late_bound = match table_schema.options().late_arrival_policy {
+            LateArrivalPolicy::LateTier if write_time > 0 => {
+                table_schema.late_arrival_bound(write_time)
+            }
+            _ => None,
+        };
         let mut sid_map: HashMap<u32, (SeriesKey, Vec<usize>)> = HashMap::new();
         for (row_count, (sid, series_key)) in sids.iter().enumerate() {
             let buf_and_row_idx = sid_map.entry(*sid).or_default();
@@ -298,18 +318,29 @@ impl Database {
                 fb_schema,
                 row_idx,
             )?;
-            let mut row_group = RowGroup {
+            // The groups of the points in time and the late points
+            let mut row_groups: [RowGroup; 2] = std::array::from_fn(|i| RowGroup {
                 schema: table_schema.clone(),
                 rows: OrderedRowsData::new(),
                 range: TimeRange::none(),
                 size: size_of::<RowGroup>(),
-            };
+                late: i == 1,
+            });
             for row in rows {
+                let row_group = if late_bound.is_some_and(|bound| row.ts < bound) {
+                    &mut row_groups[1]
+                } else {
+                    &mut row_groups[0]
+                };
                 row_group.range.merge(&TimeRange::new(row.ts, row.ts));
                 row_group.size += row.size();
                 row_group.rows.insert(row);
             }
-            let res = map.insert(sid, (series_key_buf, row_group));
+            let row_groups = row_groups
+                .into_iter()
+                .filter(|g| !g.rows.get_ref_rows().is_empty())
+                .collect();
+            let res = map.insert(sid, (series_key_buf, row_groups));
             // every sid of different table is different
             debug_assert!(res.is_none())
         }
@@ -467,6 +498,10 @@ impl Database {
     pub fn db_name(&self) -> Arc<String> {
         self.db_name.clone()
     }
+
+    pub fn idempotency_keys(&self) -> Arc<parking_lot::Mutex<IdempotencyKeys>> {
+        self.idempotency_keys.clone()
+    }
 }
 
 #[cfg(test)]
@@ -11,6 +11,7 @@ use models::{SeriesId, SeriesKey};
 
 use crate::error::Result;
 use crate::kv_option::StorageOptions;
+use crate::stats::StorageStatsKind;
 use crate::tseries_family::SuperVersion;
 use crate::vnode_store::VnodeStorage;
 use crate::{Engine, TseriesFamilyId};
@@ -104,5 +105,9 @@ impl Engine for MockEngine {
         todo!()
     }
 
+    async fn get_storage_stats(&self, kind: StorageStatsKind) -> Result<RecordBatch> {
+        Ok(RecordBatch::new_empty(kind.schema()))
+    }
+
     async fn close(&self) {}
 }
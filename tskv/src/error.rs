@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use datafusion::arrow::error::ArrowError;
 use datafusion::error::DataFusionError;
+use datafusion::parquet::errors::ParquetError;
 use error_code::{ErrorCode, ErrorCoder};
 use http_protocol::response::ErrorResponse;
 use meta::error::MetaError;
@@ -342,6 +343,11 @@ pub enum Error {
     Deserialize {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+
+    #[snafu(display("Parquet error: {}", source))]
+    Parquet {
+        source: ParquetError,
+    },
 }
 
 impl From<PointsError> for Error {
@@ -383,6 +389,12 @@ impl From<ArrowError> for Error {
     }
 }
 
+impl From<ParquetError> for Error {
+    fn from(source: ParquetError) -> Self {
+        Error::Parquet { source }
+    }
+}
+
 impl From<DataFusionError> for Error {
     fn from(source: DataFusionError) -> Self {
         match source {
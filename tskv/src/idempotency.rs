@@ -0,0 +1,179 @@
+use std::collections::{HashMap, VecDeque};
+
+use models::meta_data::VnodeId;
+use serde::{Deserialize, Serialize};
+
+/// How long a write is remembered after it is applied, in nanoseconds of the write time.
+pub const IDEMPOTENCY_KEY_TTL: i64 = 60 * 60 * 1_000_000_000;
+
+/// A write applied with an idempotency key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AppliedWrite {
+    /// The checksum of the write without the write time, so a retry of the write
+    /// taking a new write time is told from another write reusing the key.
+    pub checksum: u32,
+    /// The write time of the write, in nanoseconds.
+    pub write_time: i64,
+    /// The result returned when the write was applied, returned again to the retries.
+    pub result: Vec<u8>,
+}
+
+/// The idempotency keys of the recently applied writes of a database.
+///
+/// A client request is split into a write for each of the vnodes it goes into, all with
+/// the same key, so the writes are kept for each vnode. The writes are recorded when the
+/// raft write commands are applied, and a write is forgotten when a write applied on the
+/// same vnode is `IDEMPOTENCY_KEY_TTL` later than it, so all the replicas of a replication
+/// set see the same keys. The writes of a vnode are saved in the vnode snapshots, so they
+/// are restored with the snapshot after the wal is purged, and the writes in the wal are
+/// recovered on open.
+#[derive(Debug, Default)]
+pub struct IdempotencyKeys {
+    keys: HashMap<String, HashMap<VnodeId, AppliedWrite>>,
+    vnodes: HashMap<VnodeId, VnodeKeys>,
+}
+
+/// The keys applied on a vnode, from the oldest to the latest.
+#[derive(Debug, Default)]
+struct VnodeKeys {
+    latest_write_time: i64,
+    order: VecDeque<String>,
+}
+
+impl IdempotencyKeys {
+    /// Returns the write applied on the vnode with the key, if the write is not expired
+    /// at `write_time`.
+    pub fn get(&self, key: &str, vnode_id: VnodeId, write_time: i64) -> Option<&AppliedWrite> {
+        self.keys
+            .get(key)
+            .and_then(|writes| writes.get(&vnode_id))
+            .filter(|applied| write_time.saturating_sub(applied.write_time) <= IDEMPOTENCY_KEY_TTL)
+    }
+
+    pub fn insert(&mut self, key: String, vnode_id: VnodeId, applied: AppliedWrite) {
+        let vnode = self.vnodes.entry(vnode_id).or_default();
+        vnode.latest_write_time = vnode.latest_write_time.max(applied.write_time);
+        let writes = self.keys.entry(key.clone()).or_default();
+        if writes.insert(vnode_id, applied).is_none() {
+            vnode.order.push_back(key);
+        }
+        self.purge(vnode_id);
+    }
+
+    /// Forget the writes of the vnode expired at the latest write time of the vnode.
+    fn purge(&mut self, vnode_id: VnodeId) {
+        let Some(vnode) = self.vnodes.get_mut(&vnode_id) else {
+            return;
+        };
+        while let Some(oldest) = vnode.order.front() {
+            let Some(writes) = self.keys.get_mut(oldest) else {
+                vnode.order.pop_front();
+                continue;
+            };
+            let expired = writes.get(&vnode_id).map_or(true, |applied| {
+                vnode.latest_write_time.saturating_sub(applied.write_time) > IDEMPOTENCY_KEY_TTL
+            });
+            if !expired {
+                break;
+            }
+            writes.remove(&vnode_id);
+            if writes.is_empty() {
+                self.keys.remove(oldest);
+            }
+            vnode.order.pop_front();
+        }
+    }
+
+    /// Returns the keys and the writes applied on the vnode from the oldest to the latest.
+    pub fn vnode_keys(&self, vnode_id: VnodeId) -> Vec<(String, AppliedWrite)> {
+        let Some(vnode) = self.vnodes.get(&vnode_id) else {
+            return vec![];
+        };
+        vnode
+            .order
+            .iter()
+            .filter_map(|key| {
+                let applied = self.keys.get(key)?.get(&vnode_id)?;
+                Some((key.clone(), applied.clone()))
+            })
+            .collect()
+    }
+
+    /// Replace the writes applied on the vnode with the ones saved in a snapshot.
+    pub fn restore(&mut self, vnode_id: VnodeId, keys: Vec<(String, AppliedWrite)>) {
+        self.remove_vnode(vnode_id);
+        for (key, applied) in keys {
+            self.insert(key, vnode_id, applied);
+        }
+    }
+
+    /// Forget the writes applied on the vnode.
+    pub fn remove_vnode(&mut self, vnode_id: VnodeId) {
+        let Some(vnode) = self.vnodes.remove(&vnode_id) else {
+            return;
+        };
+        for key in vnode.order {
+            if let Some(writes) = self.keys.get_mut(&key) {
+                writes.remove(&vnode_id);
+                if writes.is_empty() {
+                    self.keys.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of the keys of all the vnodes.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AppliedWrite, IdempotencyKeys, IDEMPOTENCY_KEY_TTL};
+
+    fn applied(checksum: u32, write_time: i64) -> AppliedWrite {
+        AppliedWrite {
+            checksum,
+            write_time,
+            result: checksum.to_be_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_idempotency_keys() {
+        let mut keys = IdempotencyKeys::default();
+        keys.insert("a".to_string(), 1, applied(1, 100));
+        keys.insert("a".to_string(), 2, applied(2, 100));
+        keys.insert("b".to_string(), 1, applied(3, 200));
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys.get("a", 1, 300), Some(&applied(1, 100)));
+        assert_eq!(keys.get("a", 2, 300), Some(&applied(2, 100)));
+        assert_eq!(keys.get("a", 3, 300), None);
+        // a retry later than the ttl is a new write
+        assert_eq!(keys.get("a", 1, 101 + IDEMPOTENCY_KEY_TTL), None);
+
+        // the writes expired at the latest write time of vnode 1 are forgotten
+        keys.insert("c".to_string(), 1, applied(4, 150 + IDEMPOTENCY_KEY_TTL));
+        assert_eq!(
+            keys.vnode_keys(1),
+            vec![
+                ("b".to_string(), applied(3, 200)),
+                ("c".to_string(), applied(4, 150 + IDEMPOTENCY_KEY_TTL)),
+            ]
+        );
+        assert_eq!(keys.vnode_keys(2), vec![("a".to_string(), applied(2, 100))]);
+
+        keys.restore(2, vec![("d".to_string(), applied(5, 100))]);
+        assert_eq!(keys.get("a", 2, 100), None);
+        assert_eq!(keys.get("d", 2, 100), Some(&applied(5, 100)));
+
+        keys.remove_vnode(1);
+        assert!(keys.vnode_keys(1).is_empty());
+        assert_eq!(keys.len(), 1);
+    }
+}
@@ -9,13 +9,14 @@ use models::meta_data::{NodeId, VnodeId};
 
 use crate::TseriesFamilyId;
 
-const SUMMARY_PATH: &str = "summary";
+pub const SUMMARY_PATH: &str = "summary";
 pub const INDEX_PATH: &str = "index";
-const DATA_PATH: &str = "data";
+pub const DATA_PATH: &str = "data";
 pub const TSM_PATH: &str = "tsm";
 pub const DELTA_PATH: &str = "delta";
 pub const MOVE_PATH: &str = "move";
 pub const T_SERIES_FAMILY_SNAPSHOT_PATH: &str = "snapshot";
+pub const IMPORT_PATH: &str = "import";
 
 #[derive(Debug, Clone)]
 pub struct Options {
@@ -48,6 +49,7 @@ pub struct StorageOptions {
     pub compact_trigger_file_num: u32,
     pub compact_trigger_cold_duration: Duration,
     pub max_compact_size: u64,
+    pub late_compact_interval: Duration,
     pub max_concurrent_compaction: u16,
     pub strict_write: bool,
 }
@@ -105,6 +107,30 @@ impl StorageOptions {
             .join(snapshot_id)
     }
 
+    /// The directory of the files staged by a bulk import, relative to the storage path.
+    pub fn fmt_import_dir(
+        database: &str,
+        ts_family_id: TseriesFamilyId,
+        import_id: &str,
+    ) -> PathBuf {
+        PathBuf::from(DATA_PATH)
+            .join(database)
+            .join(ts_family_id.to_string())
+            .join(IMPORT_PATH)
+            .join(import_id)
+    }
+
+    pub fn import_dir(
+        &self,
+        database: &str,
+        ts_family_id: TseriesFamilyId,
+        import_id: &str,
+    ) -> PathBuf {
+        self.ts_family_dir(database, ts_family_id)
+            .join(IMPORT_PATH)
+            .join(import_id)
+    }
+
     pub fn snapshot_dir(&self, database: &str, ts_family_id: TseriesFamilyId) -> PathBuf {
         self.ts_family_dir(database, ts_family_id)
             .join(T_SERIES_FAMILY_SNAPSHOT_PATH)
@@ -163,6 +189,7 @@ impl From<&Config> for StorageOptions {
             compact_trigger_file_num: config.storage.compact_trigger_file_num,
             compact_trigger_cold_duration: config.storage.compact_trigger_cold_duration,
             max_compact_size: config.storage.max_compact_size,
+            late_compact_interval: config.storage.late_compact_interval,
             max_concurrent_compaction: config.storage.max_concurrent_compaction,
             strict_write: config.storage.strict_write,
         }
@@ -179,6 +206,13 @@ pub struct QueryOptions {
     pub write_timeout: Duration,
     pub stream_trigger_cpu: usize,
     pub stream_executor_cpu: usize,
+    pub query_executor_cpu: usize,
+    pub enable_spill: bool,
+    pub spill_hash_operators: bool,
+    pub spill_dir: String,
+    pub max_spill_size: u64,
+    pub alert_webhook: String,
+    pub alert_sync_interval: Duration,
 }
 
 impl From<&Config> for QueryOptions {
@@ -192,6 +226,13 @@ impl From<&Config> for QueryOptions {
             write_timeout: config.query.write_timeout,
             stream_trigger_cpu: config.query.stream_trigger_cpu,
             stream_executor_cpu: config.query.stream_executor_cpu,
+            query_executor_cpu: config.query.query_executor_cpu,
+            enable_spill: config.query.enable_spill,
+            spill_hash_operators: config.query.spill_hash_operators,
+            spill_dir: config.query.spill_dir.clone(),
+            max_spill_size: config.query.max_spill_size,
+            alert_webhook: config.query.alert_webhook.clone(),
+            alert_sync_interval: config.query.alert_sync_interval,
         }
     }
 }
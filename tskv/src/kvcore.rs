@@ -21,6 +21,7 @@ use crate::database::Database;
 use crate::error::Result;
 use crate::file_system::file_manager;
 use crate::kv_option::{Options, StorageOptions};
+use crate::stats::{self, StorageHistory, StorageStatsKind};
 use crate::summary::{Summary, SummaryTask};
 use crate::tseries_family::{SuperVersion, TseriesFamily};
 use crate::version_set::VersionSet;
@@ -73,6 +74,7 @@ impl TsKv {
             summary_task_sender,
             options: shared_options.clone(),
             global_ctx: summary.global_context(),
+            storage_history: StorageHistory::default(),
         });
 
         let (close_sender, _close_receiver) = broadcast::channel(1);
@@ -238,6 +240,7 @@ impl Engine for TsKv {
         let db = self.get_db_or_else_create(tenant, db_name).await?;
 
         let ts_index = db.write().await.get_ts_index_or_add(vnode_id).await?;
+        let idempotency_keys = db.read().await.idempotency_keys();
         let ts_family = self
             .get_tsfamily_or_else_create(vnode_id, db.clone())
             .await?;
@@ -248,6 +251,7 @@ impl Engine for TsKv {
             ts_family,
             id: vnode_id,
             ctx: self.ctx.clone(),
+            idempotency_keys,
         })
     }
 
@@ -442,6 +446,10 @@ impl Engine for TsKv {
         Ok(RecordBatch::new_empty(check::vnode_table_checksum_schema()))
     }
 
+    async fn get_storage_stats(&self, kind: StorageStatsKind) -> Result<RecordBatch> {
+        stats::storage_stats(self.meta_manager.node_id(), &self.ctx, kind).await
+    }
+
     async fn close(&self) {
         let (tx, mut rx) = mpsc::channel(1);
         if let Err(e) = self.close_sender.send(tx) {
@@ -0,0 +1,474 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet, LinkedList};
+use std::sync::Arc;
+
+use memory_pool::{MemoryConsumer, MemoryPoolRef, MemoryReservation};
+use models::field_value::FieldVal;
+use models::predicate::domain::{LastValueScan, TimeRange, TimeRanges};
+use models::schema::{TskvTableSchema, TskvTableSchemaRef};
+use models::{ColumnId, SeriesId, SeriesKey, Timestamp};
+use parking_lot::{Mutex, RwLock};
+
+use crate::memcache::{OrderedRowsData, RowData, RowGroup, SeriesData};
+use crate::schema::schemas::DBschemas;
+use crate::tseries_family::Version;
+use crate::Result;
+
+/// The last values of the fields of a series.
+#[derive(Debug)]
+struct SeriesLastValues {
+    /// The points at or before this timestamp may be missed by the cache,
+    /// it's `Timestamp::MIN` if none of the points of the series is missed.
+    unknown_until: Timestamp,
+    fields: HashMap<ColumnId, (Timestamp, FieldVal)>,
+}
+
+impl SeriesLastValues {
+    fn new(unknown_until: Timestamp) -> Self {
+        Self {
+            unknown_until,
+            fields: HashMap::new(),
+        }
+    }
+
+    /// The memory used by the series in the cache
+    fn size(&self) -> usize {
+        std::mem::size_of::<(SeriesId, Self)>()
+            + self.fields.capacity() * std::mem::size_of::<(ColumnId, (Timestamp, FieldVal))>()
+            + self
+                .fields
+                .values()
+                .map(|(_, value)| value.heap_size())
+                .sum::<usize>()
+    }
+
+    fn update(&mut self, column_id: ColumnId, ts: Timestamp, value: FieldVal) {
+        match self.fields.get(&column_id) {
+            Some((last_ts, _)) if *last_ts > ts => {}
+            _ => {
+                self.fields.insert(column_id, (ts, value));
+            }
+        }
+    }
+
+    /// Forget the cached points, they may be deleted.
+    fn invalidate(&mut self) {
+        if let Some(max_ts) = self.fields.values().map(|(ts, _)| *ts).max() {
+            self.unknown_until = self.unknown_until.max(max_ts);
+        }
+        self.fields.clear();
+    }
+
+    /// The rows of the cached points of the fields in the time ranges,
+    /// returns None if the cached points may be not the latest ones.
+    fn rows(
+        &self,
+        schema: &TskvTableSchema,
+        time_ranges: &TimeRanges,
+        scan: LastValueScan,
+    ) -> Option<Vec<RowData>> {
+        let fields_id = schema.fields_id();
+        let is_latest = |ts: Timestamp| ts > self.unknown_until && time_ranges.contains(ts);
+        let mut rows: BTreeMap<Timestamp, Vec<Option<FieldVal>>> = BTreeMap::new();
+
+        match scan {
+            LastValueScan::Row => {
+                let max_ts = fields_id
+                    .keys()
+                    .filter_map(|id| self.fields.get(id))
+                    .map(|(ts, _)| *ts)
+                    .max()?;
+                if !is_latest(max_ts) {
+                    return None;
+                }
+                let row = rows
+                    .entry(max_ts)
+                    .or_insert_with(|| vec![None; fields_id.len()]);
+                for (id, idx) in fields_id.iter() {
+                    if let Some((ts, value)) = self.fields.get(id) {
+                        if *ts == max_ts {
+                            row[*idx] = Some(value.clone());
+                        }
+                    }
+                }
+            }
+            LastValueScan::Fields => {
+                for (id, idx) in fields_id.iter() {
+                    match self.fields.get(id) {
+                        Some((ts, value)) => {
+                            if !is_latest(*ts) {
+                                return None;
+                            }
+                            rows.entry(*ts)
+                                .or_insert_with(|| vec![None; fields_id.len()])[*idx] =
+                                Some(value.clone());
+                        }
+                        // The field has no points
+                        None if self.unknown_until == Timestamp::MIN => {}
+                        None => return None,
+                    }
+                }
+            }
+        }
+
+        Some(
+            rows.into_iter()
+                .map(|(ts, fields)| RowData { ts, fields })
+                .collect(),
+        )
+    }
+}
+
+/// The last value of each field of each series of a vnode, only the series of
+/// the tables created with `LAST_VALUE_CACHE = true` are cached.
+///
+/// The cache is updated when the points are written, and recovered from the tsm files
+/// when the vnode is opened. A series may be not cached, or its cached points may
+/// be not the latest ones after deleting, then it should be read from the data.
+///
+/// The memory of the cache is reserved from the memory pool, a series is evicted from
+/// the cache if the pool is exhausted.
+#[derive(Debug)]
+pub struct LastValueCache {
+    series: RwLock<HashMap<SeriesId, SeriesLastValues>>,
+    memory: Mutex<MemoryReservation>,
+    /// The series invalidated during the recovery, their recovered points may be deleted.
+    recovering: Mutex<Option<HashSet<SeriesId>>>,
+}
+
+impl LastValueCache {
+    pub fn new(tf_id: u32, pool: &MemoryPoolRef) -> Self {
+        Self {
+            series: RwLock::new(HashMap::new()),
+            memory: Mutex::new(
+                MemoryConsumer::new(format!("last-value-cache-{}", tf_id)).register(pool),
+            ),
+            recovering: Mutex::new(None),
+        }
+    }
+
+    /// Resizes the memory of a series, returns false if the memory pool is exhausted.
+    fn resize(memory: &mut MemoryReservation, old_size: usize, new_size: usize) -> bool {
+        if new_size > old_size {
+            memory.try_grow(new_size - old_size).is_ok()
+        } else {
+            memory.shrink(old_size - new_size);
+            true
+        }
+    }
+
+    /// Update the last values of the series by the written rows.
+    ///
+    /// If the series is not cached, `unknown_until` is called to get the max timestamp
+    /// of the points of the series which are not written by this cache.
+    pub fn update(
+        &self,
+        sid: SeriesId,
+        group: &RowGroup,
+        unknown_until: impl FnOnce() -> Timestamp,
+    ) {
+        if !group.schema.options().last_value_cache {
+            return;
+        }
+
+        let fields_id = group.schema.fields_id();
+        let mut series = self.series.write();
+        let (values, old_size) = match series.entry(sid) {
+            Entry::Occupied(entry) => {
+                let size = entry.get().size();
+                (entry.into_mut(), size)
+            }
+            Entry::Vacant(entry) => (entry.insert(SeriesLastValues::new(unknown_until())), 0),
+        };
+        for row in group.rows.get_ref_rows().iter() {
+            for (column_id, idx) in fields_id.iter() {
+                if let Some(Some(value)) = row.fields.get(*idx) {
+                    values.update(*column_id, row.ts, value.clone());
+                }
+            }
+        }
+
+        let new_size = values.size();
+        let mut memory = self.memory.lock();
+        if !Self::resize(&mut memory, old_size, new_size) {
+            // The points of the evicted series are read from the data.
+            series.remove(&sid);
+            memory.shrink(old_size);
+        }
+    }
+
+    pub fn invalidate(&self, sids: &[SeriesId]) {
+        self.invalidate_until(sids, Timestamp::MIN);
+    }
+
+    /// Forget the cached points of the series, and mark the points at or before
+    /// `until` as missed, they are not written by this cache.
+    pub fn invalidate_until(&self, sids: &[SeriesId], until: Timestamp) {
+        let mut series = self.series.write();
+        let mut memory = self.memory.lock();
+        for sid in sids {
+            if let Some(values) = series.get_mut(sid) {
+                let old_size = values.size();
+                values.invalidate();
+                values.unknown_until = values.unknown_until.max(until);
+                Self::resize(&mut memory, old_size, values.size());
+            }
+        }
+        if let Some(invalidated) = self.recovering.lock().as_mut() {
+            invalidated.extend(sids);
+        }
+    }
+
+    /// Returns the cached points of the series as the data in memcache,
+    /// or None if they are not cached or may be not the latest ones.
+    pub fn series_data(
+        &self,
+        sid: SeriesId,
+        series_key: SeriesKey,
+        schema: TskvTableSchemaRef,
+        time_ranges: &TimeRanges,
+        scan: LastValueScan,
+    ) -> Option<Arc<RwLock<SeriesData>>> {
+        let rows = self
+            .series
+            .read()
+            .get(&sid)?
+            .rows(&schema, time_ranges, scan)?;
+
+        let mut range = TimeRange::none();
+        let mut ordered_rows = OrderedRowsData::new();
+        for row in rows {
+            range.merge(&TimeRange::new(row.ts, row.ts));
+            ordered_rows.insert(row);
+        }
+        let group = RowGroup {
+            schema,
+            range,
+            rows: ordered_rows,
+            size: 0,
+            late: false,
+        };
+
+        Some(Arc::new(RwLock::new(SeriesData {
+            series_id: sid,
+            series_key,
+            range,
+            groups: LinkedList::from([group]),
+        })))
+    }
+
+    /// Recover the last values from the tsm files of the version, by reading the last
+    /// column group of the chunks of each series. The series already cached, and the
+    /// series invalidated during the recovery are skipped.
+    ///
+    /// The table options are not stored in the tsm files, they are read from `schemas`.
+    pub async fn recover(&self, version: &Version, schemas: &DBschemas) -> Result<()> {
+        *self.recovering.lock() = Some(HashSet::new());
+        let result = self.recover_series(version, schemas).await;
+        let invalidated = self.recovering.lock().take().unwrap_or_default();
+
+        let mut series = self.series.write();
+        let mut memory = self.memory.lock();
+        for (sid, values) in result? {
+            if invalidated.contains(&sid) {
+                continue;
+            }
+            if let Entry::Vacant(entry) = series.entry(sid) {
+                if !Self::resize(&mut memory, 0, values.size()) {
+                    break;
+                }
+                entry.insert(values);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn recover_series(
+        &self,
+        version: &Version,
+        schemas: &DBschemas,
+    ) -> Result<HashMap<SeriesId, SeriesLastValues>> {
+        let mut cached_tables: HashMap<String, bool> = HashMap::new();
+        let mut recovered: HashMap<SeriesId, SeriesLastValues> = HashMap::new();
+        // The files of the lower levels are newer, their points overwrite the older ones.
+        for level in version.levels_info().iter().rev() {
+            let mut files = level.files.clone();
+            files.sort_by_key(|f| f.file_id());
+            for file in files {
+                let reader = version.get_tsm_reader(file.file_path()).await?;
+                for (sid, chunk) in reader.chunk().iter() {
+                    let table = chunk.table_name();
+                    let is_cached = match cached_tables.get(table) {
+                        Some(is_cached) => *is_cached,
+                        None => {
+                            let is_cached = schemas
+                                .get_table_schema(table)
+                                .await?
+                                .map(|schema| schema.options().last_value_cache)
+                                .unwrap_or_default();
+                            cached_tables.insert(table.to_string(), is_cached);
+                            is_cached
+                        }
+                    };
+                    if !is_cached {
+                        continue;
+                    }
+
+                    let values = recovered
+                        .entry(*sid)
+                        .or_insert_with(|| SeriesLastValues::new(Timestamp::MIN));
+                    let Some(last_group) = chunk
+                        .column_group()
+                        .values()
+                        .max_by_key(|cg| cg.time_range().max_ts)
+                    else {
+                        continue;
+                    };
+                    // The points of the other column groups are not read.
+                    for cg in chunk.column_group().values() {
+                        if cg.column_group_id() != last_group.column_group_id() {
+                            values.unknown_until = values.unknown_until.max(cg.time_range().max_ts);
+                        }
+                    }
+                    // The points may be deleted by the tombstone.
+                    if reader.has_tombstone() {
+                        values.unknown_until = values.unknown_until.max(chunk.max_ts());
+                        continue;
+                    }
+
+                    let block = reader
+                        .read_datablock(*sid, last_group.column_group_id())
+                        .await?;
+                    for (column_id, ts, value) in block.last_values() {
+                        values.update(column_id, ts, value);
+                    }
+                }
+            }
+        }
+
+        Ok(recovered)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::datatypes::TimeUnit;
+    use memory_pool::{GreedyMemoryPool, MemoryPool, MemoryPoolRef};
+    use models::codec::Encoding;
+    use models::field_value::FieldVal;
+    use models::predicate::domain::{LastValueScan, TimeRange, TimeRanges};
+    use models::schema::{ColumnType, TableColumn, TableOptions, TskvTableSchema};
+    use models::value_type::ValueType;
+    use models::Timestamp;
+
+    use super::{LastValueCache, SeriesLastValues};
+    use crate::memcache::{OrderedRowsData, RowData, RowGroup};
+
+    fn schema() -> TskvTableSchema {
+        TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "test".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(1, "t".to_string()),
+                TableColumn::new(
+                    2,
+                    "f1".to_string(),
+                    ColumnType::Field(ValueType::Integer),
+                    Encoding::Default,
+                ),
+                TableColumn::new(
+                    3,
+                    "f2".to_string(),
+                    ColumnType::Field(ValueType::Integer),
+                    Encoding::Default,
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_series_last_values() {
+        let schema = schema();
+        let all = TimeRanges::all();
+        let mut values = SeriesLastValues::new(Timestamp::MIN);
+        values.update(2, 1, FieldVal::Integer(1));
+        values.update(3, 1, FieldVal::Integer(2));
+        values.update(2, 3, FieldVal::Integer(3));
+        values.update(2, 2, FieldVal::Integer(4));
+
+        let rows = values.rows(&schema, &all, LastValueScan::Row).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].ts, 3);
+        assert_eq!(rows[0].fields.iter().flatten().count(), 1);
+
+        let rows = values.rows(&schema, &all, LastValueScan::Fields).unwrap();
+        assert_eq!(rows.iter().map(|r| r.ts).collect::<Vec<_>>(), vec![1, 3]);
+
+        // The cached points are out of the time ranges.
+        let before = TimeRanges::new(vec![TimeRange::new(0, 2)]);
+        assert!(values.rows(&schema, &before, LastValueScan::Row).is_none());
+
+        // The cached points may be deleted.
+        values.invalidate();
+        assert!(values.rows(&schema, &all, LastValueScan::Fields).is_none());
+        values.update(3, 4, FieldVal::Integer(5));
+        assert!(values.rows(&schema, &all, LastValueScan::Row).is_some());
+        assert!(values.rows(&schema, &all, LastValueScan::Fields).is_none());
+    }
+
+    #[test]
+    fn test_last_value_cache_memory() {
+        let schema = Arc::new(schema().with_options(TableOptions {
+            last_value_cache: true,
+            ..Default::default()
+        }));
+        let group = |ts: Timestamp| {
+            let mut rows = OrderedRowsData::new();
+            rows.insert(RowData {
+                ts,
+                fields: vec![Some(FieldVal::Integer(1)), Some(FieldVal::Integer(2))],
+            });
+            RowGroup {
+                schema: schema.clone(),
+                range: TimeRange::new(ts, ts),
+                rows,
+                size: 0,
+                late: false,
+            }
+        };
+        let all = TimeRanges::all();
+
+        let pool: MemoryPoolRef = Arc::new(GreedyMemoryPool::new(1024 * 1024));
+        let cache = LastValueCache::new(1, &pool);
+        cache.update(1, &group(1), || Timestamp::MIN);
+        let size = pool.reserved();
+        assert!(size > 0);
+        cache.update(1, &group(2), || Timestamp::MIN);
+        assert_eq!(pool.reserved(), size);
+        assert!(cache
+            .series_data(
+                1,
+                Default::default(),
+                schema.clone(),
+                &all,
+                LastValueScan::Row
+            )
+            .is_some());
+        drop(cache);
+        assert_eq!(pool.reserved(), 0);
+
+        // The series is evicted if the memory pool is exhausted.
+        let pool: MemoryPoolRef = Arc::new(GreedyMemoryPool::new(size - 1));
+        let cache = LastValueCache::new(1, &pool);
+        cache.update(1, &group(1), || Timestamp::MIN);
+        assert_eq!(pool.reserved(), 0);
+        assert!(cache
+            .series_data(1, Default::default(), schema, &all, LastValueScan::Row)
+            .is_none());
+    }
+}
@@ -11,10 +11,12 @@ use compaction::{CompactTask, FlushReq};
 use context::GlobalContext;
 use datafusion::arrow::record_batch::RecordBatch;
 use file_system::file_info::FileInfo;
+use idempotency::AppliedWrite;
 use models::meta_data::{NodeId, VnodeId};
 use models::predicate::domain::ColumnDomains;
 use models::{SeriesId, SeriesKey};
 use serde::{Deserialize, Serialize};
+use stats::{StorageHistory, StorageStatsKind};
 use summary::SummaryTask;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
@@ -31,6 +33,7 @@ use crate::tseries_family::SuperVersion;
 // pub use crate::tsm::print_tsm_statistics;
 pub use crate::wal::print_wal_statistics;
 
+pub mod bulk;
 pub mod byte_utils;
 mod compaction;
 mod compute;
@@ -40,15 +43,19 @@ pub mod engine_mock;
 pub mod error;
 pub mod file_system;
 pub mod file_utils;
+pub mod idempotency;
 pub mod index;
 pub mod kv_option;
 mod kvcore;
+mod last_value_cache;
 mod memcache;
 // TODO supposedly private
 pub mod reader;
 mod record_file;
 mod schema;
+pub mod stats;
 mod summary;
+pub mod tools;
 mod tseries_family;
 pub mod tsm;
 mod version_set;
@@ -125,6 +132,9 @@ pub trait Engine: Send + Sync + Debug {
     /// Get a compressed hash_tree(ID and checksum of each vnode) of engine.
     async fn get_vnode_hash_tree(&self, vnode_id: VnodeId) -> Result<RecordBatch>;
 
+    /// Get the storage statistics of all vnodes of engine, in the schema of `kind`.
+    async fn get_storage_stats(&self, kind: StorageStatsKind) -> Result<RecordBatch>;
+
     /// Close all background jobs of engine.
     async fn close(&self);
 }
@@ -138,6 +148,8 @@ pub struct TsKvContext {
     pub flush_task_sender: Sender<FlushReq>,
     pub compact_task_sender: Sender<CompactTask>,
     pub summary_task_sender: Sender<SummaryTask>,
+
+    pub storage_history: StorageHistory,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -148,6 +160,111 @@ pub struct VnodeSnapshot {
     pub last_seq_no: u64,
     pub files_info: Vec<FileInfo>,
     pub version_edit: VersionEdit,
+    /// The idempotency keys and the writes applied on the vnode with the keys, from the
+    /// oldest to the latest, since `VnodeSnapshot::VERSION` 3.
+    pub idempotency_keys: Vec<(String, AppliedWrite)>,
+}
+
+impl VnodeSnapshot {
+    /// The header of the encoded snapshots since version 2, the snapshots of version 1
+    /// are encoded without a header.
+    const MAGIC: [u8; 4] = *b"VSNP";
+    const VERSION: u8 = 3;
+
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut buf = Self::MAGIC.to_vec();
+        buf.push(Self::VERSION);
+        bincode::serialize_into(&mut buf, self)
+            .map_err(|e| Error::Serialize { source: e.into() })?;
+        Ok(buf)
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self> {
+        let header_len = Self::MAGIC.len() + 1;
+        if buf.len() < header_len || buf[..Self::MAGIC.len()] != Self::MAGIC {
+            let snapshot: v1::VnodeSnapshot =
+                bincode::deserialize(buf).map_err(|e| Error::Deserialize { source: e.into() })?;
+            return Ok(snapshot.into());
+        }
+        match buf[Self::MAGIC.len()] {
+            Self::VERSION => bincode::deserialize(&buf[header_len..])
+                .map_err(|e| Error::Deserialize { source: e.into() }),
+            // the write times of the keys are unknown, so the keys are not restored
+            2 => bincode::deserialize::<v2::VnodeSnapshot>(&buf[header_len..])
+                .map(Into::into)
+                .map_err(|e| Error::Deserialize { source: e.into() }),
+            version => Err(Error::CommonError {
+                reason: format!("unknown vnode snapshot version {version}"),
+            }),
+        }
+    }
+}
+
+/// The layout of the vnode snapshots of version 1.
+mod v1 {
+    use models::meta_data::{NodeId, VnodeId};
+    use serde::Deserialize;
+
+    use crate::file_system::file_info::FileInfo;
+    use crate::summary;
+
+    #[derive(Deserialize)]
+    pub struct VnodeSnapshot {
+        pub snapshot_id: String,
+        pub node_id: NodeId,
+        pub vnode_id: VnodeId,
+        pub last_seq_no: u64,
+        pub files_info: Vec<FileInfo>,
+        pub version_edit: summary::v1::VersionEdit,
+    }
+
+    impl From<VnodeSnapshot> for super::VnodeSnapshot {
+        fn from(snapshot: VnodeSnapshot) -> Self {
+            Self {
+                snapshot_id: snapshot.snapshot_id,
+                node_id: snapshot.node_id,
+                vnode_id: snapshot.vnode_id,
+                last_seq_no: snapshot.last_seq_no,
+                files_info: snapshot.files_info,
+                version_edit: snapshot.version_edit.into(),
+                idempotency_keys: vec![],
+            }
+        }
+    }
+}
+
+/// The layout of the vnode snapshots of version 2.
+mod v2 {
+    use models::meta_data::{NodeId, VnodeId};
+    use serde::Deserialize;
+
+    use crate::file_system::file_info::FileInfo;
+    use crate::VersionEdit;
+
+    #[derive(Deserialize)]
+    pub struct VnodeSnapshot {
+        pub snapshot_id: String,
+        pub node_id: NodeId,
+        pub vnode_id: VnodeId,
+        pub last_seq_no: u64,
+        pub files_info: Vec<FileInfo>,
+        pub version_edit: VersionEdit,
+        pub idempotency_keys: Vec<(String, u32)>,
+    }
+
+    impl From<VnodeSnapshot> for super::VnodeSnapshot {
+        fn from(snapshot: VnodeSnapshot) -> Self {
+            Self {
+                snapshot_id: snapshot.snapshot_id,
+                node_id: snapshot.node_id,
+                vnode_id: snapshot.vnode_id,
+                last_seq_no: snapshot.last_seq_no,
+                files_info: snapshot.files_info,
+                version_edit: snapshot.version_edit,
+                idempotency_keys: vec![],
+            }
+        }
+    }
 }
 
 pub mod test {
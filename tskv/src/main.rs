@@ -1,20 +1,38 @@
 use std::env;
 
+use serde::Serialize;
+
 const ARG_PRINT: &str = "print"; // To print something
 const ARG_REPAIR: &str = "repair"; // To repair something
+const ARG_VERIFY: &str = "verify"; // To verify files in a directory
+const ARG_SALVAGE: &str = "salvage"; // To rewrite a damaged .tsm file
+const ARG_REBUILD_SUMMARY: &str = "rebuild-summary"; // To rebuild the summary file
 const ARG_TSM: &str = "--tsm"; // To print a .tsm file
 const ARG_TOMBSTONE: &str = "--tombstone"; // To print a .tsm file with tombsotne
 const ARG_SUMMARY: &str = "--summary"; // To print a summary file
 const ARG_WAL: &str = "--wal"; // To print a wal file
 const ARG_INDEX: &str = "--index"; // To print a wal file
+const ARG_OUT: &str = "--out"; // Output directory of salvaged files
+const ARG_STORAGE: &str = "--storage"; // Storage path to rebuild summary
+const ARG_FORCE: &str = "--force"; // To overwrite the existing summary file
 
 /// # Example
 /// tskv print [--tsm <tsm_path>] [--tombstone]
 /// tskv print [--summary <summary_path>]
 /// tskv print [--wal <wal_path>]
 /// tskv repair [--index <file_name>]
+/// tskv verify <dir>
+/// tskv salvage --tsm <tsm_path> --out <out_dir>
+/// tskv rebuild-summary --storage <storage_path> [--force]
 /// - --tsm <tsm_path> print statistics for .tsm file at <tsm_path> .
 /// - --tombstone also print tombstone for every field_id in .tsm file.
+/// - verify <dir> check crc of all tsm, delta, tombstone, summary, wal and index binlog
+///   files under <dir>.
+/// - salvage rewrite .tsm or .delta file at <tsm_path> into <out_dir>, dropping damaged blocks.
+/// - rebuild-summary rebuild the summary file from the .tsm and .delta files of <storage_path>.
+///
+/// verify, salvage and rebuild-summary print JSON reports and exit with code 1 on errors
+/// or invalid arguments, verify exits with code 2 if any damaged file is found.
 #[tokio::main]
 async fn main() {
     let mut args = env::args().peekable();
@@ -32,6 +50,16 @@ async fn main() {
     let mut repair_index = false;
     let mut index_file: Option<String> = None;
 
+    let mut verify_path: Option<String> = None;
+
+    let mut salvage = false;
+    let mut salvage_path: Option<String> = None;
+    let mut out_dir: Option<String> = None;
+
+    let mut rebuild_summary = false;
+    let mut storage_path: Option<String> = None;
+    let mut force = false;
+
     while let Some(arg) = args.peek() {
         // --print [--tsm <path>]
         if arg.as_str() == ARG_PRINT {
@@ -74,6 +102,36 @@ async fn main() {
                     }
                 }
             }
+        } else if arg.as_str() == ARG_VERIFY {
+            args.next();
+            verify_path = args.next();
+            if verify_path.is_none() {
+                invalid_arguments("verify <dir>");
+            }
+        } else if arg.as_str() == ARG_SALVAGE {
+            salvage = true;
+            while let Some(salvage_arg) = args.next() {
+                match salvage_arg.as_str() {
+                    ARG_TSM => salvage_path = args.next(),
+                    ARG_OUT => out_dir = args.next(),
+                    _ => {}
+                }
+            }
+            if salvage_path.is_none() || out_dir.is_none() {
+                invalid_arguments("salvage --tsm <tsm_path> --out <out_dir>");
+            }
+        } else if arg.as_str() == ARG_REBUILD_SUMMARY {
+            rebuild_summary = true;
+            while let Some(rebuild_arg) = args.next() {
+                match rebuild_arg.as_str() {
+                    ARG_STORAGE => storage_path = args.next(),
+                    ARG_FORCE => force = true,
+                    _ => {}
+                }
+            }
+            if storage_path.is_none() {
+                invalid_arguments("rebuild-summary --storage <storage_path> [--force]");
+            }
         }
         args.next();
     }
@@ -106,4 +164,48 @@ async fn main() {
             println!("repair index result: {:?}", result);
         }
     }
+
+    if let Some(p) = verify_path {
+        let report = tskv::tools::verify(p).await;
+        let damaged = matches!(&report, Ok(r) if r.damaged_files > 0);
+        print_report(report);
+        if damaged {
+            std::process::exit(2);
+        }
+    }
+
+    if salvage {
+        if let (Some(p), Some(out)) = (salvage_path, out_dir) {
+            print_report(tskv::tools::salvage_tsm(p, out).await);
+        }
+    }
+
+    if rebuild_summary {
+        if let Some(p) = storage_path {
+            print_report(tskv::tools::rebuild_summary(p, force).await);
+        }
+    }
+}
+
+/// Prints the report as JSON, or the error as a JSON object and exits with code 1.
+fn print_report<T: Serialize>(report: tskv::Result<T>) {
+    match report {
+        Ok(r) => match serde_json::to_string_pretty(&r) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                eprintln!("Failed to serialize report: {e}");
+                std::process::exit(1);
+            }
+        },
+        Err(e) => {
+            println!("{}", serde_json::json!({ "error": e.to_string() }));
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints the usage of the invalid arguments to stderr and exits with code 1.
+fn invalid_arguments(usage: &str) -> ! {
+    eprintln!("Invalid arguments: {usage}");
+    std::process::exit(1);
 }
@@ -258,6 +258,9 @@ pub struct RowGroup {
     pub rows: OrderedRowsData,
     /// total size in stack and heap
     pub size: usize,
+    /// The rows are late points when they are written, they are flushed to the
+    /// late delta file, see `LateArrivalPolicy::LateTier`.
+    pub late: bool,
 }
 
 #[derive(Debug)]
@@ -285,7 +288,8 @@ impl SeriesData {
         self.range.merge(&group.range);
 
         for item in self.groups.iter_mut() {
-            if item.schema.schema_version == group.schema.schema_version {
+            if item.schema.schema_version == group.schema.schema_version && item.late == group.late
+            {
                 item.range.merge(&group.range);
                 group.rows.get_rows().into_iter().for_each(|row| {
                     item.rows.insert(row);
@@ -510,52 +514,75 @@ impl SeriesData {
         }
         None
     }
+    /// Build the data blocks of the tsm file, the delta file and the late delta file,
+    /// see `LateArrivalPolicy::LateTier`.
     pub fn build_data_block(
         &self,
         version: Arc<Version>,
-    ) -> Result<Option<(String, DataBlock, DataBlock)>> {
+    ) -> Result<Option<(String, DataBlock, DataBlock, DataBlock)>> {
         if let Some(schema) = self.get_schema() {
             let field_ids = schema.fields_id();
 
-            let mut cols = schema
+            let cols = schema
                 .fields()
                 .iter()
                 .map(|col| ColumnData::empty(col.column_type.to_physical_type()))
                 .collect::<Result<Vec<_>>>()?;
-            let mut delta_cols = cols.clone();
-            let mut time_array = ColumnData::empty(PhysicalCType::Time(TimeUnit::from(
+            let time_array = ColumnData::empty(PhysicalCType::Time(TimeUnit::from(
                 schema.time_column_precision(),
             )))?;
-
-            let mut delta_time_array = time_array.clone();
+            // The timestamps and fields of the tsm, delta and late delta blocks
+            let mut time_arrays: [ColumnData; 3] = std::array::from_fn(|_| time_array.clone());
+            let mut cols_arrays: [Vec<ColumnData>; 3] = std::array::from_fn(|_| cols.clone());
+
+            // The rows of the groups going into the same block are merged, a block is
+            // sorted by the timestamps without duplicates.
+            let max_level_ts = version.max_level_ts();
+            let mut block_rows: [Vec<RowData>; 3] = Default::default();
             let mut cols_desc = vec![None; schema.field_num()];
-            for (schema, rows) in self.flat_groups() {
-                let values = dedup_and_sort_row_data(rows);
-                for row in values {
-                    match row.ts.cmp(&version.max_level_ts()) {
-                        cmp::Ordering::Greater => {
-                            time_array.push(Some(FieldVal::Integer(row.ts)));
-                        }
-                        _ => {
-                            delta_time_array.push(Some(FieldVal::Integer(row.ts)));
-                        }
-                    }
+            for group in self.groups.iter() {
+                let schema = &group.schema;
+                for row in dedup_and_sort_row_data(&group.rows) {
+                    let block_idx = if row.ts > max_level_ts {
+                        0
+                    } else if group.late {
+                        2
+                    } else {
+                        1
+                    };
+                    let mut fields = vec![None; cols.len()];
                     for col in schema.fields().iter() {
                         if let Some(index) = field_ids.get(&col.id) {
-                            let field = row.fields.get(*index).and_then(|v| v.clone());
-                            match row.ts.cmp(&version.max_level_ts()) {
-                                cmp::Ordering::Greater => {
-                                    cols[*index].push(field);
-                                }
-                                _ => {
-                                    delta_cols[*index].push(field);
-                                }
-                            }
+                            fields[*index] = row.fields.get(*index).and_then(|v| v.clone());
                             if cols_desc[*index].is_none() {
                                 cols_desc[*index] = Some(col.clone());
                             }
                         }
                     }
+                    block_rows[block_idx].push(RowData { ts: row.ts, fields });
+                }
+            }
+            for (block_idx, mut rows) in block_rows.into_iter().enumerate() {
+                // stable, the fields of the later groups overwrite the earlier ones
+                rows.sort_by_key(|row| row.ts);
+                let mut merged: Vec<RowData> = Vec::with_capacity(rows.len());
+                for row in rows {
+                    match merged.last_mut() {
+                        Some(last) if last.ts == row.ts => {
+                            for (field, value) in last.fields.iter_mut().zip(row.fields) {
+                                if value.is_some() {
+                                    *field = value;
+                                }
+                            }
+                        }
+                        _ => merged.push(row),
+                    }
+                }
+                for row in merged {
+                    time_arrays[block_idx].push(Some(FieldVal::Integer(row.ts)));
+                    for (index, field) in row.fields.into_iter().enumerate() {
+                        cols_arrays[block_idx][index].push(field);
+                    }
                 }
             }
 
@@ -566,27 +593,27 @@ impl SeriesData {
                 });
             }
 
-            if !time_array.is_all_set() || !delta_time_array.is_all_set() {
+            if time_arrays.iter().any(|array| !array.is_all_set()) {
                 return Err(Error::CommonError {
                     reason: "Invalid time array in DataBlock".to_string(),
                 });
             }
-            return Ok(Some((
-                schema.name.clone(),
+            let build_block = |time_array, cols| {
                 DataBlock::new(
                     schema.clone(),
                     time_array,
                     schema.time_column(),
                     cols,
                     cols_desc.clone(),
-                ),
-                DataBlock::new(
-                    schema.clone(),
-                    delta_time_array,
-                    schema.time_column(),
-                    delta_cols,
-                    cols_desc,
-                ),
+                )
+            };
+            let [time_array, delta_time_array, late_time_array] = time_arrays;
+            let [cols, delta_cols, late_cols] = cols_arrays;
+            return Ok(Some((
+                schema.name.clone(),
+                build_block(time_array, cols),
+                build_block(delta_time_array, delta_cols),
+                build_block(late_time_array, late_cols),
             )));
         }
         Ok(None)
@@ -624,7 +651,11 @@ pub struct MemCache {
 }
 
 impl MemCache {
-    pub fn to_chunk_group(&self, version: Arc<Version>) -> Result<(TsmWriteData, TsmWriteData)> {
+    /// Returns the data of the tsm file, the delta file and the late delta file.
+    pub fn to_chunk_group(
+        &self,
+        version: Arc<Version>,
+    ) -> Result<(TsmWriteData, TsmWriteData, TsmWriteData)> {
         let partions: HashMap<SeriesId, Arc<RwLock<SeriesData>>> = self
             .partions
             .iter()
@@ -640,36 +671,30 @@ impl MemCache {
 
         let mut chunk_group: TsmWriteData = BTreeMap::new();
         let mut delta_chunk_group: TsmWriteData = BTreeMap::new();
+        let mut late_chunk_group: TsmWriteData = BTreeMap::new();
         partions
             .iter()
             .try_for_each(|(series_id, v)| -> Result<()> {
                 let data = v.read();
-                if let Some((table, datablock, delta_datablock)) =
+                if let Some((table, datablock, delta_datablock, late_datablock)) =
                     data.build_data_block(version.clone())?
                 {
-                    if !datablock.is_empty() {
-                        if let Some(chunk) = chunk_group.get_mut(&table) {
-                            chunk.insert(*series_id, (data.series_key.clone(), datablock));
-                        } else {
-                            let mut chunk = BTreeMap::new();
-                            chunk.insert(*series_id, (data.series_key.clone(), datablock));
-                            chunk_group.insert(table.clone(), chunk);
-                        }
-                    }
-
-                    if !delta_datablock.is_empty() {
-                        if let Some(chunk) = delta_chunk_group.get_mut(&table) {
-                            chunk.insert(*series_id, (data.series_key.clone(), delta_datablock));
-                        } else {
-                            let mut chunk = BTreeMap::new();
-                            chunk.insert(*series_id, (data.series_key.clone(), delta_datablock));
-                            delta_chunk_group.insert(table, chunk);
+                    for (group, block) in [
+                        (&mut chunk_group, datablock),
+                        (&mut delta_chunk_group, delta_datablock),
+                        (&mut late_chunk_group, late_datablock),
+                    ] {
+                        if !block.is_empty() {
+                            group
+                                .entry(table.clone())
+                                .or_default()
+                                .insert(*series_id, (data.series_key.clone(), block));
                         }
                     }
                 }
                 Ok(())
             })?;
-        Ok((chunk_group, delta_chunk_group))
+        Ok((chunk_group, delta_chunk_group, late_chunk_group))
     }
     pub fn new(
         tf_id: TseriesFamilyId,
@@ -920,6 +945,7 @@ pub(crate) mod test {
             range: TimeRange::from(time_range),
             rows,
             size: size_of::<RowGroup>() + size,
+            late: false,
         };
         cache
             .write_group(series_id, SeriesKey::default(), 1, row_group)
@@ -990,6 +1016,7 @@ pub fn dedup_and_sort_row_data(data: &OrderedRowsData) -> Vec<RowData> {
 mod test_memcache {
     use std::sync::Arc;
 
+    use cache::ShardedAsyncCache;
     use datafusion::arrow::datatypes::TimeUnit;
     use memory_pool::{GreedyMemoryPool, MemoryPool};
     use models::field_value::FieldVal;
@@ -997,7 +1024,9 @@ mod test_memcache {
     use models::schema::{ColumnType, TableColumn, TskvTableSchema};
     use models::{SeriesId, SeriesKey, ValueType};
 
-    use super::{MemCache, OrderedRowsData, RowData, RowGroup};
+    use super::{ColumnData, DataBlock, MemCache, OrderedRowsData, RowData, RowGroup, SeriesData};
+    use crate::compaction::test::create_options;
+    use crate::tseries_family::{LevelInfo, Version};
 
     #[test]
     fn test_write_group() {
@@ -1037,6 +1066,7 @@ mod test_memcache {
             range: TimeRange::new(1, 3),
             rows,
             size: 10,
+            late: false,
         };
         mem_cache
             .write_group(sid, SeriesKey::default(), 1, row_group_1.clone())
@@ -1079,6 +1109,7 @@ mod test_memcache {
             range: TimeRange::new(3, 5),
             rows,
             size: 10,
+            late: false,
         };
         mem_cache
             .write_group(sid, SeriesKey::default(), 2, row_group_2.clone())
@@ -1093,5 +1124,142 @@ mod test_memcache {
             assert_eq!(2, series_data.groups.len());
             assert_eq!(row_group_2, series_data.groups.back().unwrap().clone());
         }
+
+        // The late points are not merged into the group of the same schema version.
+        let mut row_group_3 = row_group_2.clone();
+        row_group_3.late = true;
+        mem_cache
+            .write_group(sid, SeriesKey::default(), 3, row_group_3.clone())
+            .unwrap();
+        {
+            let series_part = &mem_cache.partions[sid as usize].read();
+            let series_data = series_part.get(&sid).unwrap().read();
+            assert_eq!(3, series_data.groups.len());
+            assert_eq!(row_group_3, series_data.groups.back().unwrap().clone());
+        }
+    }
+
+    #[test]
+    fn test_build_data_block() {
+        #[rustfmt::skip]
+        let mut schema_1 = TskvTableSchema::new(
+            "test_tenant".to_string(), "test_db".to_string(), "test_table".to_string(),
+            vec![
+                TableColumn::new_time_column(1, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(2, "tag_col_1".to_string()),
+                TableColumn::new(3, "f_col_1".to_string(), ColumnType::Field(ValueType::Float), Default::default()),
+            ],
+        );
+        schema_1.schema_version = 1;
+        let mut schema_2 = schema_1.clone();
+        schema_2.add_column(TableColumn::new(
+            4,
+            "f_col_2".to_string(),
+            ColumnType::Field(ValueType::Integer),
+            Default::default(),
+        ));
+        schema_2.schema_version = 2;
+        let (schema_1, schema_2) = (Arc::new(schema_1), Arc::new(schema_2));
+
+        let make_group = |schema: &Arc<TskvTableSchema>, rows: Vec<RowData>, late: bool| {
+            let mut ordered_rows = OrderedRowsData::new();
+            let mut range = TimeRange::none();
+            for row in rows {
+                range.merge(&TimeRange::new(row.ts, row.ts));
+                ordered_rows.insert(row);
+            }
+            RowGroup {
+                schema: schema.clone(),
+                range,
+                rows: ordered_rows,
+                size: 10,
+                late,
+            }
+        };
+        let mut series_data = SeriesData::new(1, SeriesKey::default());
+        series_data.write(make_group(
+            &schema_1,
+            vec![
+                RowData {
+                    ts: 1,
+                    fields: vec![Some(FieldVal::Float(1.0))],
+                },
+                RowData {
+                    ts: 3,
+                    fields: vec![Some(FieldVal::Float(3.0))],
+                },
+            ],
+            false,
+        ));
+        series_data.write(make_group(
+            &schema_2,
+            vec![
+                RowData {
+                    ts: 2,
+                    fields: vec![Some(FieldVal::Float(2.0)), Some(FieldVal::Integer(2))],
+                },
+                RowData {
+                    ts: 3,
+                    fields: vec![None, Some(FieldVal::Integer(3))],
+                },
+                RowData {
+                    ts: 20,
+                    fields: vec![Some(FieldVal::Float(20.0)), Some(FieldVal::Integer(20))],
+                },
+            ],
+            false,
+        ));
+        series_data.write(make_group(
+            &schema_2,
+            vec![RowData {
+                ts: 4,
+                fields: vec![Some(FieldVal::Float(4.0)), Some(FieldVal::Integer(4))],
+            }],
+            true,
+        ));
+
+        let opt = create_options("/tmp/test/memcache/build_data_block".to_string());
+        let database = Arc::new("test_db".to_string());
+        let version = Arc::new(Version::new(
+            1,
+            database.clone(),
+            opt.storage.clone(),
+            1,
+            LevelInfo::init_levels(database, 1, opt.storage.clone()),
+            10,
+            Arc::new(ShardedAsyncCache::create_lru_sharded_cache(1)),
+        ));
+        let (table, tsm_block, delta_block, late_block) =
+            series_data.build_data_block(version).unwrap().unwrap();
+
+        let expected_block = |rows: Vec<(i64, Option<f64>, Option<i64>)>| {
+            let time_column = schema_2.time_column();
+            let fields = schema_2.fields();
+            let mut time_array =
+                ColumnData::empty(time_column.column_type.to_physical_type()).unwrap();
+            let mut cols = fields
+                .iter()
+                .map(|col| ColumnData::empty(col.column_type.to_physical_type()).unwrap())
+                .collect::<Vec<_>>();
+            for (ts, f_1, f_2) in rows {
+                time_array.push(Some(FieldVal::Integer(ts)));
+                cols[0].push(f_1.map(FieldVal::Float));
+                cols[1].push(f_2.map(FieldVal::Integer));
+            }
+            DataBlock::new(schema_2.clone(), time_array, time_column, cols, fields)
+        };
+        assert_eq!(table, "test_table");
+        assert_eq!(tsm_block, expected_block(vec![(20, Some(20.0), Some(20))]));
+        // The rows of the two groups are merged and sorted.
+        assert_eq!(
+            delta_block,
+            expected_block(vec![
+                (1, Some(1.0), None),
+                (2, Some(2.0), Some(2)),
+                (3, Some(3.0), Some(3)),
+            ])
+        );
+        // The late points are flushed to the late block.
+        assert_eq!(late_block, expected_block(vec![(4, Some(4.0), Some(4))]));
     }
 }
@@ -2,11 +2,12 @@ use std::sync::Arc;
 
 use arrow::datatypes::SchemaRef;
 use datafusion::physical_optimizer::pruning::PruningPredicate;
+use models::predicate::domain::SpatialFilter;
 
 use super::column_group::statistics::ColumnGroupsStatisticsWrapper;
 use super::Predicate;
 use crate::reader::utils::reassign_predicate_columns;
-use crate::tsm::page::ColumnGroup;
+use crate::tsm::page::{ColumnGroup, PageStatistics};
 use crate::Result;
 
 pub fn filter_column_groups(
@@ -32,20 +33,53 @@ fn filter_column_groups_indices(
     predicate: &Option<Arc<Predicate>>,
     chunk_schema: SchemaRef,
 ) -> Result<Option<Vec<bool>>> {
-    if let Some(predicate) = predicate {
-        let new_predicate = reassign_predicate_columns(predicate.clone(), chunk_schema.clone())?;
-        let statistics = ColumnGroupsStatisticsWrapper(cgs);
-
-        if let Some(expr) = new_predicate {
+    let predicate = match predicate {
+        Some(predicate) => predicate,
+        None => return Ok(None),
+    };
+
+    let new_predicate = reassign_predicate_columns(predicate.clone(), chunk_schema.clone())?;
+    let mut indices = match new_predicate {
+        Some(expr) => {
+            let statistics = ColumnGroupsStatisticsWrapper(cgs);
             let pruning_predicate = PruningPredicate::try_new(expr, chunk_schema)?;
-            let indices = pruning_predicate.prune(&statistics)?;
-            Ok(Some(indices))
-        } else {
-            Ok(None)
+            Some(pruning_predicate.prune(&statistics)?)
         }
-    } else {
-        Ok(None)
+        None => None,
+    };
+
+    let spatial_filters = predicate.spatial_filters();
+    if !spatial_filters.is_empty() {
+        let spatial_indices = cgs
+            .iter()
+            .map(|cg| match_spatial_filters(cg, spatial_filters))
+            .collect::<Vec<_>>();
+        indices = Some(match indices {
+            Some(indices) => indices
+                .into_iter()
+                .zip(spatial_indices)
+                .map(|(a, b)| a && b)
+                .collect(),
+            None => spatial_indices,
+        });
     }
+
+    Ok(indices)
+}
+
+/// Returns false if the bounding box of a geometry column of the column group
+/// does not intersect the spatial filter on it.
+fn match_spatial_filters(cg: &ColumnGroup, spatial_filters: &[SpatialFilter]) -> bool {
+    spatial_filters.iter().all(|filter| {
+        let page = cg
+            .pages()
+            .iter()
+            .find(|p| p.meta().column.name == filter.column);
+        match page.map(|p| &p.meta().statistics) {
+            Some(PageStatistics::Geometry(_, Some(bbox))) => bbox.intersects(&filter.bbox),
+            _ => true,
+        }
+    })
 }
 
 #[cfg(test)]
@@ -58,6 +92,10 @@ mod tests {
     use datafusion::physical_plan::expressions::{lit, BinaryExpr, Column};
     use datafusion::physical_plan::functions::create_physical_expr;
     use datafusion::scalar::ScalarValue;
+    use models::codec::Encoding;
+    use models::gis::bbox::BoundingBox;
+    use models::gis::data_type::{Geometry, GeometryType};
+    use models::predicate::domain::SpatialFilter;
     use models::schema::{ColumnType, TableColumn};
     use models::ValueType;
 
@@ -296,4 +334,54 @@ mod tests {
 
         assert!(cgs.is_err());
     }
+
+    #[test]
+    fn test_filter_geometry_column_groups_indices() {
+        let schema = Arc::new(Schema::new(vec![Field::new("geo", DataType::Utf8, true)]));
+        let geo_column = TableColumn::new(
+            1,
+            "geo".to_string(),
+            ColumnType::Field(ValueType::Geometry(Geometry::new_with_srid(
+                GeometryType::Point,
+                0,
+            ))),
+            Encoding::default(),
+        );
+        let bboxes = vec![
+            Some(BoundingBox::new(0.0, 0.0, 1.0, 1.0)),
+            Some(BoundingBox::new(5.0, 5.0, 6.0, 6.0)),
+            None,
+        ];
+        let data = bboxes
+            .into_iter()
+            .enumerate()
+            .map(|(idx, bbox)| {
+                let mut cg = ColumnGroup::new(idx);
+                cg.push(PageWriteSpec::new(
+                    0,
+                    0,
+                    PageMeta {
+                        num_values: 1,
+                        column: geo_column.clone(),
+                        statistics: PageStatistics::Geometry(
+                            ValueStatistics::new(None, None, None, 1),
+                            bbox,
+                        ),
+                    },
+                ));
+                Arc::new(cg)
+            })
+            .collect::<Vec<_>>();
+
+        let spatial_filter = SpatialFilter::new("geo", BoundingBox::new(0.5, 0.5, 2.0, 2.0));
+        let predicate = Arc::new(
+            Predicate::new(None, schema.clone(), None).with_spatial_filters(vec![spatial_filter]),
+        );
+
+        let cgs = filter_column_groups_indices(&data, &Some(predicate), schema)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(cgs, vec![true, false, true]);
+    }
 }
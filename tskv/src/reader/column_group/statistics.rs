@@ -29,7 +29,7 @@ impl<'a> PruningStatistics for ColumnGroupsStatisticsWrapper<'a> {
                     PageStatistics::F64(v) => ScalarValue::from(*v.min()),
                     PageStatistics::I64(v) => ScalarValue::from(*v.min()),
                     PageStatistics::U64(v) => ScalarValue::from(*v.min()),
-                    PageStatistics::Bytes(v) => {
+                    PageStatistics::Bytes(v) | PageStatistics::Geometry(v, _) => {
                         let str = v.min().as_ref().and_then(|e| std::str::from_utf8(e).ok());
                         ScalarValue::from(str)
                     }
@@ -59,7 +59,7 @@ impl<'a> PruningStatistics for ColumnGroupsStatisticsWrapper<'a> {
                     PageStatistics::F64(v) => ScalarValue::from(*v.max()),
                     PageStatistics::I64(v) => ScalarValue::from(*v.max()),
                     PageStatistics::U64(v) => ScalarValue::from(*v.max()),
-                    PageStatistics::Bytes(v) => {
+                    PageStatistics::Bytes(v) | PageStatistics::Geometry(v, _) => {
                         let str = v.max().as_ref().and_then(|e| std::str::from_utf8(e).ok());
                         ScalarValue::from(str)
                     }
@@ -9,18 +9,24 @@ use datafusion::arrow::array::{
     TimestampMillisecondBuilder, TimestampNanosecondBuilder, TimestampSecondBuilder, UInt64Builder,
 };
 use datafusion::arrow::datatypes::{
-    ArrowPrimitiveType, Float64Type, Int64Type, TimeUnit, TimestampMicrosecondType,
-    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt64Type,
+    ArrowPrimitiveType, DataType as ArrowDataType, Float64Type, Int64Type, TimeUnit,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, UInt64Type,
 };
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::Operator;
+use datafusion::physical_expr::PhysicalExpr;
+use datafusion::physical_plan::expressions::{binary, col, lit};
 use datafusion::physical_plan::metrics::{
     self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
 };
+use datafusion::scalar::ScalarValue;
 use datafusion_proto::physical_plan::from_proto::parse_physical_expr;
 use models::field_value::DataType;
 use models::meta_data::VnodeId;
-use models::predicate::domain::{self, QueryArgs, QueryExpr, TimeRanges};
+use models::predicate::domain::{self, QueryArgs, QueryExpr, TimeRange, TimeRanges};
 use models::predicate::PlacedSplit;
+use models::query_resource::QueryResourceTracker;
 use models::schema::{PhysicalCType, TableColumn, TskvTableSchemaRef};
 use models::{ColumnId, PhysicalDType, SeriesId, SeriesKey, Timestamp};
 use protos::kv_service::QueryRecordBatchRequest;
@@ -32,6 +38,7 @@ use trace::{debug, error, SpanRecorder};
 use super::display::DisplayableBatchReader;
 use super::memcache_reader::MemCacheReader;
 use super::merge::DataMerger;
+use super::prior_point::PriorPointReader;
 use super::series::SeriesReader;
 use super::trace::Recorder;
 use super::{
@@ -393,6 +400,22 @@ impl SeriesGroupBatchReaderFactory {
             }
         }
 
+        // 需要查询时间范围之前的最后一个点时，获取时间范围之前的所有文件的 reader
+        let prior_point = match self.prior_point_time_ranges() {
+            Some(lookback_time_ranges) => {
+                let lookback_files = super_version
+                    .column_files_by_sid_and_time(series_ids, lookback_time_ranges.as_ref());
+                let _timer = metrics.elapsed_get_tsm_readers_time().timer();
+                let mut lookback_files_with_reader = Vec::with_capacity(lookback_files.len());
+                for f in lookback_files {
+                    let reader = super_version.version.get_tsm_reader(f.file_path()).await?;
+                    lookback_files_with_reader.push((f, reader));
+                }
+                Some((lookback_time_ranges, lookback_files_with_reader))
+            }
+            None => None,
+        };
+
         // 通过sid获取serieskey
         let sid_keys = {
             let _timer = metrics.elapsed_get_series_keys_time().timer();
@@ -402,6 +425,20 @@ impl SeriesGroupBatchReaderFactory {
         // 获取所有的符合条件的chunk Vec<(SeriesKey, Vec<DataReference>)>
         let mut series_chunk_readers = Vec::with_capacity(series_ids.len());
         for (sid, series_key) in series_ids.iter().zip(sid_keys) {
+            // 最新的点在 last value cache 中时，直接读取缓存
+            if let Some(scan) = self.query_option.split.last_value() {
+                if let Some(series_data) = super_version.last_values.series_data(
+                    *sid,
+                    series_key.clone(),
+                    kv_schema.clone(),
+                    time_ranges.as_ref(),
+                    scan,
+                ) {
+                    let chunks = vec![DataReference::Memcache(series_data, time_ranges.clone())];
+                    series_chunk_readers.push((series_key, chunks, vec![]));
+                    continue;
+                }
+            }
             // 选择含有series的所有chunk Vec<DataReference::Chunk(chunk, reader)>
             let mut chunks = Self::filter_chunks(&column_files_with_reader, *sid).await?;
             // 获取所有符合条件的 memcache rowgroup Vec<DataReference::Memcache(rowgroup)>)
@@ -414,28 +451,74 @@ impl SeriesGroupBatchReaderFactory {
             chunks.retain(|d| {
                 d.time_range().is_none().not() && time_ranges.overlaps(&d.time_range())
             });
-            series_chunk_readers.push((series_key, chunks));
+            // 选择查询时间范围之前的最后一个点所在的chunk
+            let prior_chunks = match &prior_point {
+                Some((lookback_time_ranges, lookback_files_with_reader)) => {
+                    let mut prior_chunks =
+                        Self::filter_chunks(lookback_files_with_reader, *sid).await?;
+                    prior_chunks.append(
+                        Self::filter_rowgroups(
+                            super_version.caches.clone(),
+                            *sid,
+                            lookback_time_ranges.clone(),
+                        )
+                        .await?
+                        .as_mut(),
+                    );
+                    select_prior_chunks(prior_chunks, lookback_time_ranges.max_ts())
+                }
+                None => vec![],
+            };
+            series_chunk_readers.push((series_key, chunks, prior_chunks));
         }
 
         metrics
             .chunk_nums()
-            .set(series_chunk_readers.iter().map(|(_, e)| e.len()).sum());
+            .set(series_chunk_readers.iter().map(|(_, e, _)| e.len()).sum());
+        metrics.prior_point_chunk_nums().set(
+            series_chunk_readers
+                .iter()
+                .flat_map(|(_, _, p)| p.iter().map(|(_, e)| e.len()))
+                .sum(),
+        );
 
         let series_readers = series_chunk_readers
             .into_iter()
-            .filter_map(|(series_key, chunks)| {
-                self.build_series_reader(
-                    series_key,
-                    chunks,
-                    self.query_option.batch_size,
-                    self.query_option.table_schema.clone(),
-                    &projection,
-                    &predicate,
-                    schema.clone(),
-                    time_fields_schema.clone(),
-                    &metrics,
-                )
-                .transpose()
+            .filter_map(|(series_key, chunks, prior_chunks)| {
+                let prior_point_reader = self
+                    .build_prior_point_reader(
+                        series_key.clone(),
+                        prior_chunks,
+                        &projection,
+                        schema.clone(),
+                        time_fields_schema.clone(),
+                        &metrics,
+                    )
+                    .transpose();
+                let series_reader = self
+                    .build_series_reader(
+                        series_key,
+                        chunks,
+                        self.query_option.batch_size,
+                        self.query_option.table_schema.clone(),
+                        &projection,
+                        &predicate,
+                        schema.clone(),
+                        time_fields_schema.clone(),
+                        &metrics,
+                    )
+                    .transpose();
+
+                match (prior_point_reader, series_reader) {
+                    // 先输出查询时间范围之前的最后一个点，保证同一个series的数据按时间有序
+                    (Some(prior), Some(series)) => Some(prior.and_then(|prior| {
+                        series.map(|series| {
+                            Arc::new(CombinedBatchReader::new(vec![prior, series]))
+                                as BatchReaderRef
+                        })
+                    })),
+                    (prior, series) => prior.or(series),
+                }
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -481,6 +564,119 @@ impl SeriesGroupBatchReaderFactory {
         Ok(Some(reader))
     }
 
+    /// 需要查找最后一个点的时间范围（早于 split 指定的时间点），不需要时返回 None
+    fn prior_point_time_ranges(&self) -> Option<Arc<TimeRanges>> {
+        let split = &self.query_option.split;
+        let time_ranges = split.time_ranges();
+        let before = split.prior_point()?;
+        if time_ranges.is_empty() || before == Timestamp::MIN {
+            return None;
+        }
+
+        Some(Arc::new(TimeRanges::new(vec![TimeRange::new(
+            Timestamp::MIN,
+            before.min(time_ranges.min_ts()) - 1,
+        )])))
+    }
+
+    /// 读取 series 在查询时间范围之前每个 field 列的最后一个非空值
+    #[allow(clippy::too_many_arguments)]
+    fn build_prior_point_reader(
+        &self,
+        series_key: SeriesKey,
+        tiers: Vec<(TimeRange, Vec<DataReference>)>,
+        projection: &Projection,
+        schema: SchemaRef,
+        time_fields_schema: SchemaRef,
+        metrics: &SeriesGroupBatchReaderMetrics,
+    ) -> Result<Option<BatchReaderRef>> {
+        if tiers.is_empty() {
+            return Ok(None);
+        }
+
+        let table_schema = &self.query_option.table_schema;
+        let arrow_schema = table_schema.to_arrow_schema();
+        let time_column = table_schema.time_column();
+        let time_type = arrow_schema
+            .field_with_name(&time_column.name)?
+            .data_type()
+            .clone();
+        let bound = |ts: Timestamp| -> Arc<dyn PhysicalExpr> {
+            let value = match &time_type {
+                ArrowDataType::Timestamp(TimeUnit::Second, tz) => {
+                    ScalarValue::TimestampSecond(Some(ts), tz.clone())
+                }
+                ArrowDataType::Timestamp(TimeUnit::Millisecond, tz) => {
+                    ScalarValue::TimestampMillisecond(Some(ts), tz.clone())
+                }
+                ArrowDataType::Timestamp(TimeUnit::Microsecond, tz) => {
+                    ScalarValue::TimestampMicrosecond(Some(ts), tz.clone())
+                }
+                ArrowDataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+                    ScalarValue::TimestampNanosecond(Some(ts), tz.clone())
+                }
+                _ => ScalarValue::Int64(Some(ts)),
+            };
+            lit(value)
+        };
+
+        // 从最新的时间范围开始读取，直到所有 field 列都找到非空值
+        let mut readers = Vec::with_capacity(tiers.len());
+        for (time_range, chunks) in tiers {
+            // time >= min_ts AND time <= max_ts
+            let time = col(&time_column.name, &arrow_schema)?;
+            let expr = binary(
+                binary(
+                    time.clone(),
+                    Operator::GtEq,
+                    bound(time_range.min_ts),
+                    &arrow_schema,
+                )?,
+                Operator::And,
+                binary(
+                    time,
+                    Operator::LtEq,
+                    bound(time_range.max_ts),
+                    &arrow_schema,
+                )?,
+                &arrow_schema,
+            )?;
+            let predicate = Some(PredicateRef::new(Predicate::new(
+                Some(expr),
+                arrow_schema.clone(),
+                None,
+            )));
+
+            let reader = self.build_series_reader(
+                series_key.clone(),
+                chunks,
+                self.query_option.batch_size,
+                table_schema.clone(),
+                projection,
+                &predicate,
+                schema.clone(),
+                time_fields_schema.clone(),
+                metrics,
+            )?;
+            readers.extend(reader);
+        }
+
+        let time_index = schema.index_of(&time_column.name)?;
+        let field_indices = time_fields_schema
+            .fields()
+            .iter()
+            .filter(|f| f.name() != &time_column.name)
+            .map(|f| schema.index_of(f.name()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(Some(Arc::new(PriorPointReader::new(
+            schema,
+            readers,
+            time_index,
+            field_indices,
+        ))))
+    }
+
     /// 返回指定series的serieskey
     async fn series_keys(
         &self,
@@ -746,6 +942,47 @@ impl Drop for SeriesGroupBatchReaderFactory {
     }
 }
 
+/// Splits the chunks not later than `end` into disjoint time ranges from the latest to the
+/// earliest, only by their time ranges in the TSM index, without reading any data.
+///
+/// The last point of a chunk which ends before `end` is at the end of the chunk, so the
+/// latest point is not earlier than the latest end of these chunks. The last non-null value
+/// of a sparse field may be earlier, it is then looked up in the following time ranges.
+/// Returns the time ranges and the chunks overlapping each of them.
+fn select_prior_chunks(
+    mut chunks: Vec<DataReference>,
+    mut end: Timestamp,
+) -> Vec<(TimeRange, Vec<DataReference>)> {
+    chunks.retain(|d| {
+        let time_range = d.time_range();
+        time_range.is_none().not() && time_range.min_ts <= end
+    });
+
+    let mut tiers = vec![];
+    while !chunks.is_empty() {
+        let min_ts = chunks
+            .iter()
+            .map(|d| d.time_range().max_ts)
+            .filter(|max_ts| *max_ts <= end)
+            .max()
+            .unwrap_or(Timestamp::MIN);
+        let tier_chunks = chunks
+            .iter()
+            .filter(|d| d.time_range().max_ts >= min_ts)
+            .cloned()
+            .collect::<Vec<_>>();
+        tiers.push((TimeRange::new(min_ts, end), tier_chunks));
+
+        if min_ts == Timestamp::MIN {
+            break;
+        }
+        end = min_ts - 1;
+        chunks.retain(|d| d.time_range().min_ts <= end);
+    }
+
+    tiers
+}
+
 /// Extracts columns from the provided table schema and schema reference, excluding tag columns.
 /// Returns a new schema reference containing the extracted columns.
 ///
@@ -790,6 +1027,7 @@ pub struct SeriesGroupBatchReaderMetrics {
     grouped_chunk_nums: metrics::Count,
     column_group_nums: metrics::Count,
     filtered_column_group_nums: metrics::Count,
+    prior_point_chunk_nums: metrics::Gauge,
 }
 
 impl SeriesGroupBatchReaderMetrics {
@@ -822,6 +1060,9 @@ impl SeriesGroupBatchReaderMetrics {
         let filtered_column_group_nums =
             MetricBuilder::new(metrics).counter("filtered_column_group_nums", partition);
 
+        let prior_point_chunk_nums =
+            MetricBuilder::new(metrics).gauge("prior_point_chunk_nums", partition);
+
         Self {
             elapsed_get_series_keys_time,
             elapsed_get_tsm_readers_time,
@@ -833,6 +1074,7 @@ impl SeriesGroupBatchReaderMetrics {
             grouped_chunk_nums,
             column_group_nums,
             filtered_column_group_nums,
+            prior_point_chunk_nums,
         }
     }
 
@@ -875,6 +1117,10 @@ impl SeriesGroupBatchReaderMetrics {
     pub fn filtered_column_group_nums(&self) -> &metrics::Count {
         &self.filtered_column_group_nums
     }
+
+    pub fn prior_point_chunk_nums(&self) -> &metrics::Gauge {
+        &self.prior_point_chunk_nums
+    }
 }
 
 // 1. Tsm文件遍历： KeyCursor
@@ -897,6 +1143,8 @@ pub struct QueryOption {
     pub df_schema: SchemaRef,
     pub table_schema: TskvTableSchemaRef,
     pub aggregates: Option<Vec<TableColumn>>, // TODO: Use PushedAggregateFunction
+    /// Accounts the rows and bytes read from each vnode, not sent to the remote nodes
+    pub resource_tracker: Option<Arc<QueryResourceTracker>>,
 }
 
 impl QueryOption {
@@ -914,9 +1162,15 @@ impl QueryOption {
             aggregates,
             df_schema,
             table_schema,
+            resource_tracker: None,
         }
     }
 
+    pub fn with_resource_tracker(mut self, tracker: Option<Arc<QueryResourceTracker>>) -> Self {
+        self.resource_tracker = tracker;
+        self
+    }
+
     pub fn tenant_name(&self) -> &str {
         &self.table_schema.tenant
     }
@@ -1184,11 +1438,10 @@ async fn build_stream(
         Some(parse_physical_expr(expr, &NoRegistry, &arrow_schema)?)
     };
 
-    let predicate = PredicateRef::new(Predicate::new(
-        physical_expr,
-        arrow_schema,
-        query_option.split.limit(),
-    ));
+    let predicate = PredicateRef::new(
+        Predicate::new(physical_expr, arrow_schema, query_option.split.limit())
+            .with_spatial_filters(query_option.split.spatial_filters().to_vec()),
+    );
 
     if series_ids.is_empty() {
         return Ok(Box::pin(EmptySchemableTskvRecordBatchStream::new(
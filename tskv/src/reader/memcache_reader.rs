@@ -309,6 +309,7 @@ mod tests {
             range: TimeRange::new(1, 3),
             rows,
             size: 2,
+            late: false,
         };
 
         let sid: SeriesId = 1;
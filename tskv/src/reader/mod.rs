@@ -14,7 +14,7 @@ use futures::stream::BoxStream;
 use futures::{Stream, StreamExt};
 pub use iterator::QueryOption;
 use models::field_value::DataType;
-use models::predicate::domain::{TimeRange, TimeRanges};
+use models::predicate::domain::{SpatialFilter, TimeRange, TimeRanges};
 use models::schema::{PhysicalCType, TskvTableSchema, TIME_FIELD_NAME};
 use models::ColumnId;
 use parking_lot::RwLock;
@@ -39,6 +39,7 @@ mod metrics;
 mod page;
 mod paralle_merge;
 mod partitioned_stream;
+mod prior_point;
 mod schema_alignmenter;
 mod series;
 mod trace;
@@ -59,6 +60,7 @@ pub struct Predicate {
     expr: Option<Arc<dyn PhysicalExpr>>,
     schema: SchemaRef,
     limit: Option<usize>,
+    spatial_filters: Vec<SpatialFilter>,
 }
 
 impl Predicate {
@@ -71,9 +73,15 @@ impl Predicate {
             expr,
             schema,
             limit,
+            spatial_filters: vec![],
         }
     }
 
+    pub fn with_spatial_filters(mut self, spatial_filters: Vec<SpatialFilter>) -> Self {
+        self.spatial_filters = spatial_filters;
+        self
+    }
+
     pub fn schema(&self) -> SchemaRef {
         self.schema.clone()
     }
@@ -85,6 +93,10 @@ impl Predicate {
     pub fn limit(&self) -> Option<usize> {
         self.limit
     }
+
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        &self.spatial_filters
+    }
 }
 
 #[derive(Debug, Clone)]
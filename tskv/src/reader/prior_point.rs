@@ -0,0 +1,323 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use arrow::compute::{cast, concat};
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use arrow_array::{new_null_array, Array, ArrayRef, RecordBatch};
+use futures::{ready, Stream, StreamExt};
+
+use super::{
+    BatchReader, BatchReaderRef, SchemableTskvRecordBatchStream,
+    SendableSchemableTskvRecordBatchStream,
+};
+use crate::Result;
+
+/// 输出每个 field 列在输入中的最后一个非空值
+///
+/// 输入为单个 series 在查询时间范围之前的数据，按时间范围从新到旧分为多个 reader，
+/// 每个 reader 内按时间排序。依次读取，直到所有 field 列都找到非空值。
+/// 输出按时间排序，每个时间点一行，只有最后一个非空值在该时间点的 field 列不为空，
+/// 用于填充查询时间范围开始处的空缺
+pub struct PriorPointReader {
+    schema: SchemaRef,
+    inputs: Vec<BatchReaderRef>,
+    time_index: usize,
+    field_indices: Vec<usize>,
+}
+
+impl PriorPointReader {
+    pub fn new(
+        schema: SchemaRef,
+        inputs: Vec<BatchReaderRef>,
+        time_index: usize,
+        field_indices: Vec<usize>,
+    ) -> Self {
+        Self {
+            schema,
+            inputs,
+            time_index,
+            field_indices,
+        }
+    }
+}
+
+impl BatchReader for PriorPointReader {
+    fn process(&self) -> Result<SendableSchemableTskvRecordBatchStream> {
+        Ok(Box::pin(PriorPointStream {
+            schema: self.schema.clone(),
+            inputs: self.inputs.iter().cloned().collect(),
+            current: None,
+            time_index: self.time_index,
+            last_values: vec![None; self.field_indices.len()],
+            resolved: vec![false; self.field_indices.len()],
+            field_indices: self.field_indices.clone(),
+            last_row: None,
+            done: false,
+        }))
+    }
+
+    fn fmt_as(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "PriorPointReader:")
+    }
+
+    fn children(&self) -> Vec<BatchReaderRef> {
+        self.inputs.clone()
+    }
+}
+
+/// 某个 field 列的最后一个非空值
+#[derive(Clone)]
+struct LastValue {
+    ts: i64,
+    time: ArrayRef,
+    value: ArrayRef,
+}
+
+struct PriorPointStream {
+    schema: SchemaRef,
+    inputs: VecDeque<BatchReaderRef>,
+    current: Option<SendableSchemableTskvRecordBatchStream>,
+    time_index: usize,
+    field_indices: Vec<usize>,
+    last_values: Vec<Option<LastValue>>,
+    // 在更新的时间范围中已经找到非空值的 field 列
+    resolved: Vec<bool>,
+    // 任意一行数据，用于输出 tag 列
+    last_row: Option<RecordBatch>,
+    done: bool,
+}
+
+impl PriorPointStream {
+    fn update(&mut self, batch: &RecordBatch) -> Result<()> {
+        let num_rows = batch.num_rows();
+        if num_rows == 0 {
+            return Ok(());
+        }
+        self.last_row = Some(batch.slice(num_rows - 1, 1));
+
+        let time_array = batch.column(self.time_index);
+        let times = cast(time_array, &DataType::Int64)?;
+        let times = times.as_primitive::<Int64Type>();
+        for (i, idx) in self.field_indices.iter().enumerate() {
+            if self.resolved[i] {
+                continue;
+            }
+            let array = batch.column(*idx);
+            if let Some(row) = (0..num_rows)
+                .rev()
+                .find(|row| array.is_valid(*row) && times.is_valid(*row))
+            {
+                self.last_values[i] = Some(LastValue {
+                    ts: times.value(row),
+                    time: time_array.slice(row, 1),
+                    value: array.slice(row, 1),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish_input(&mut self) {
+        self.current = None;
+        for (resolved, last_value) in self.resolved.iter_mut().zip(&self.last_values) {
+            *resolved = last_value.is_some();
+        }
+    }
+
+    fn build_output(&mut self) -> Result<Option<RecordBatch>> {
+        let Some(last_row) = self.last_row.take() else {
+            return Ok(None);
+        };
+        let mut times = self
+            .last_values
+            .iter()
+            .flatten()
+            .map(|v| (v.ts, v.time.clone()))
+            .collect::<Vec<_>>();
+        if times.is_empty() {
+            return Ok(None);
+        }
+        times.sort_by_key(|(ts, _)| *ts);
+        times.dedup_by_key(|(ts, _)| *ts);
+
+        let mut columns = Vec::with_capacity(self.schema.fields().len());
+        for (idx, field) in self.schema.fields().iter().enumerate() {
+            let arrays = if idx == self.time_index {
+                times.iter().map(|(_, time)| time.clone()).collect::<Vec<_>>()
+            } else if let Some(i) = self.field_indices.iter().position(|e| *e == idx) {
+                times
+                    .iter()
+                    .map(|(ts, _)| match &self.last_values[i] {
+                        Some(v) if v.ts == *ts => v.value.clone(),
+                        _ => new_null_array(field.data_type(), 1),
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                // tag 列在同一个 series 中都相同
+                vec![last_row.column(idx).clone(); times.len()]
+            };
+            let arrays = arrays.iter().map(|a| a.as_ref()).collect::<Vec<_>>();
+            columns.push(concat(&arrays)?);
+        }
+
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), columns)?))
+    }
+}
+
+impl SchemableTskvRecordBatchStream for PriorPointStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for PriorPointStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            let Some(current) = self.current.as_mut() else {
+                let next_input = if self.resolved.iter().all(|e| *e) {
+                    None
+                } else {
+                    self.inputs.pop_front()
+                };
+                match next_input.map(|r| r.process()) {
+                    Some(Ok(stream)) => {
+                        self.current = Some(stream);
+                        continue;
+                    }
+                    Some(Err(err)) => {
+                        self.done = true;
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                    None => {
+                        self.done = true;
+                        return Poll::Ready(self.build_output().transpose());
+                    }
+                }
+            };
+
+            match ready!(current.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    if let Err(err) = self.update(&batch) {
+                        self.done = true;
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                }
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => self.finish_input(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use arrow_array::{Float64Array, RecordBatch, StringArray, TimestampNanosecondArray};
+    use futures::TryStreamExt;
+
+    use super::PriorPointReader;
+    use crate::reader::{BatchReader, BatchReaderRef, MemoryBatchReader};
+
+    #[tokio::test]
+    async fn test_prior_point_reader() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("t0", DataType::Utf8, true),
+            Field::new("f0", DataType::Float64, true),
+            Field::new("f1", DataType::Float64, true),
+        ]));
+        let batch = |times: Vec<i64>, f0: Vec<Option<f64>>, f1: Vec<Option<f64>>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(TimestampNanosecondArray::from(times.clone())),
+                    Arc::new(StringArray::from(vec!["a"; times.len()])),
+                    Arc::new(Float64Array::from(f0)),
+                    Arc::new(Float64Array::from(f1)),
+                ],
+            )
+            .unwrap()
+        };
+        let reader = |inputs: Vec<Vec<RecordBatch>>| {
+            let inputs = inputs
+                .into_iter()
+                .map(|batches| {
+                    Arc::new(MemoryBatchReader::new(schema.clone(), batches)) as BatchReaderRef
+                })
+                .collect();
+            PriorPointReader::new(schema.clone(), inputs, 0, vec![2, 3])
+        };
+
+        // f1 is sparse, its last value is in an earlier input
+        let result = reader(vec![
+            vec![
+                batch(vec![5, 6], vec![Some(5.0), Some(6.0)], vec![None, None]),
+                batch(vec![7], vec![None], vec![None]),
+            ],
+            vec![batch(
+                vec![1, 2, 3],
+                vec![Some(1.0), Some(2.0), Some(3.0)],
+                vec![Some(1.0), Some(2.0), None],
+            )],
+        ])
+        .process()
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+        assert_eq!(
+            result,
+            vec![batch(
+                vec![2, 6],
+                vec![None, Some(6.0)],
+                vec![Some(2.0), None]
+            )]
+        );
+
+        // the earlier inputs are not read once all fields are found
+        let result = reader(vec![
+            vec![batch(vec![3, 4], vec![Some(3.0), None], vec![None, Some(4.0)])],
+            vec![batch(vec![1], vec![Some(1.0)], vec![Some(1.0)])],
+        ])
+        .process()
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+        assert_eq!(
+            result,
+            vec![batch(
+                vec![3, 4],
+                vec![Some(3.0), None],
+                vec![None, Some(4.0)]
+            )]
+        );
+
+        let result = reader(vec![])
+            .process()
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert!(result.is_empty());
+    }
+}
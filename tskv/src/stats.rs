@@ -0,0 +1,552 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use cache::AsyncCache;
+use datafusion::arrow::array::{
+    ArrayRef, BooleanBuilder, Float64Builder, Int64Builder, StringBuilder,
+    TimestampNanosecondBuilder, UInt32Builder, UInt64Builder,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use lazy_static::lazy_static;
+use models::meta_data::{NodeId, VnodeId};
+use models::schema::split_owner;
+use parking_lot::Mutex;
+use tokio::sync::RwLock;
+
+use crate::error::{Error, Result};
+use crate::tseries_family::TseriesFamily;
+use crate::wal::VnodeWal;
+use crate::{LevelId, TsKvContext};
+
+/// Max count of the compaction or flush records kept in memory.
+pub const STORAGE_HISTORY_CAPACITY: usize = 1024;
+
+/// The kinds of storage statistics of a node, each of them is a table of `information_schema`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageStatsKind {
+    Vnodes,
+    TsmFiles,
+    Compactions,
+    Flushes,
+    Wals,
+    TsmReaderCaches,
+}
+
+impl StorageStatsKind {
+    pub fn schema(&self) -> SchemaRef {
+        match self {
+            Self::Vnodes => VNODE_STORAGE_SCHEMA.clone(),
+            Self::TsmFiles => TSM_FILES_SCHEMA.clone(),
+            Self::Compactions => COMPACTION_HISTORY_SCHEMA.clone(),
+            Self::Flushes => FLUSH_HISTORY_SCHEMA.clone(),
+            Self::Wals => WAL_FILES_SCHEMA.clone(),
+            Self::TsmReaderCaches => TSM_READER_CACHE_SCHEMA.clone(),
+        }
+    }
+}
+
+impl Display for StorageStatsKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Vnodes => write!(f, "vnodes"),
+            Self::TsmFiles => write!(f, "tsm_files"),
+            Self::Compactions => write!(f, "compactions"),
+            Self::Flushes => write!(f, "flushes"),
+            Self::Wals => write!(f, "wals"),
+            Self::TsmReaderCaches => write!(f, "tsm_reader_caches"),
+        }
+    }
+}
+
+impl FromStr for StorageStatsKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let kind = match s {
+            "vnodes" => Self::Vnodes,
+            "tsm_files" => Self::TsmFiles,
+            "compactions" => Self::Compactions,
+            "flushes" => Self::Flushes,
+            "wals" => Self::Wals,
+            "tsm_reader_caches" => Self::TsmReaderCaches,
+            _ => return Err(format!("unknown storage statistics '{s}'")),
+        };
+        Ok(kind)
+    }
+}
+
+pub const STATS_NODE_ID: &str = "node_id";
+pub const STATS_TENANT_NAME: &str = "tenant_name";
+pub const STATS_DATABASE_NAME: &str = "database_name";
+pub const STATS_VNODE_ID: &str = "vnode_id";
+
+fn vnode_fields(fields: Vec<Field>) -> SchemaRef {
+    let mut all_fields = vec![
+        Field::new(STATS_NODE_ID, DataType::UInt64, false),
+        Field::new(STATS_TENANT_NAME, DataType::Utf8, false),
+        Field::new(STATS_DATABASE_NAME, DataType::Utf8, false),
+        Field::new(STATS_VNODE_ID, DataType::UInt32, false),
+    ];
+    all_fields.extend(fields);
+    Arc::new(Schema::new(all_fields))
+}
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Nanosecond, None)
+}
+
+lazy_static! {
+    pub static ref VNODE_STORAGE_SCHEMA: SchemaRef = vnode_fields(vec![
+        Field::new("disk_storage", DataType::UInt64, false),
+        Field::new("cache_size", DataType::UInt64, false),
+        Field::new("tsm_files", DataType::UInt64, false),
+        Field::new("delta_files", DataType::UInt64, false),
+        Field::new("last_seq", DataType::UInt64, false),
+        Field::new("max_level_ts", DataType::Int64, false),
+    ]);
+    pub static ref TSM_FILES_SCHEMA: SchemaRef = vnode_fields(vec![
+        Field::new("level", DataType::UInt32, false),
+        Field::new("file_id", DataType::UInt64, false),
+        Field::new("is_delta", DataType::Boolean, false),
+        Field::new("min_ts", DataType::Int64, false),
+        Field::new("max_ts", DataType::Int64, false),
+        Field::new("size", DataType::UInt64, false),
+        Field::new("compacting", DataType::Boolean, false),
+    ]);
+    pub static ref COMPACTION_HISTORY_SCHEMA: SchemaRef = vnode_fields(vec![
+        Field::new("out_level", DataType::UInt32, false),
+        Field::new("start_time", timestamp_type(), false),
+        Field::new("duration", DataType::Float64, false),
+        Field::new("input_files", DataType::UInt64, false),
+        Field::new("input_bytes", DataType::UInt64, false),
+        Field::new("output_files", DataType::UInt64, false),
+        Field::new("output_bytes", DataType::UInt64, false),
+        Field::new("success", DataType::Boolean, false),
+        Field::new("error", DataType::Utf8, true),
+    ]);
+    pub static ref FLUSH_HISTORY_SCHEMA: SchemaRef = vnode_fields(vec![
+        Field::new("start_time", timestamp_type(), false),
+        Field::new("duration", DataType::Float64, false),
+        Field::new("low_seq_no", DataType::UInt64, false),
+        Field::new("high_seq_no", DataType::UInt64, false),
+        Field::new("output_files", DataType::UInt64, false),
+        Field::new("output_bytes", DataType::UInt64, false),
+        Field::new("success", DataType::Boolean, false),
+        Field::new("error", DataType::Utf8, true),
+    ]);
+    pub static ref WAL_FILES_SCHEMA: SchemaRef = vnode_fields(vec![
+        Field::new("wal_files", DataType::UInt64, false),
+        Field::new("wal_size", DataType::UInt64, false),
+    ]);
+    pub static ref TSM_READER_CACHE_SCHEMA: SchemaRef = vnode_fields(vec![
+        Field::new("capacity", DataType::UInt64, false),
+        Field::new("usage", DataType::UInt64, false),
+        Field::new("hits", DataType::UInt64, false),
+        Field::new("misses", DataType::UInt64, false),
+        Field::new("hit_rate", DataType::Float64, false),
+    ]);
+}
+
+#[derive(Debug, Clone)]
+pub struct CompactionRecord {
+    pub owner: Arc<String>,
+    pub vnode_id: VnodeId,
+    pub out_level: LevelId,
+    /// Unix timestamp in nanoseconds.
+    pub start_time: i64,
+    pub duration: f64,
+    pub input_files: u64,
+    pub input_bytes: u64,
+    pub output_files: u64,
+    pub output_bytes: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FlushRecord {
+    pub owner: Arc<String>,
+    pub vnode_id: VnodeId,
+    /// Unix timestamp in nanoseconds.
+    pub start_time: i64,
+    pub duration: f64,
+    pub low_seq_no: u64,
+    pub high_seq_no: u64,
+    pub output_files: u64,
+    pub output_bytes: u64,
+    pub error: Option<String>,
+}
+
+/// The latest compactions and flushes of the engine, the oldest records are
+/// dropped when there are more than `STORAGE_HISTORY_CAPACITY`.
+#[derive(Debug, Default)]
+pub struct StorageHistory {
+    compactions: Mutex<VecDeque<CompactionRecord>>,
+    flushes: Mutex<VecDeque<FlushRecord>>,
+}
+
+impl StorageHistory {
+    pub fn record_compaction(&self, record: CompactionRecord) {
+        push_with_capacity(&mut self.compactions.lock(), record);
+    }
+
+    pub fn record_flush(&self, record: FlushRecord) {
+        push_with_capacity(&mut self.flushes.lock(), record);
+    }
+
+    pub fn compactions(&self) -> Vec<CompactionRecord> {
+        self.compactions.lock().iter().cloned().collect()
+    }
+
+    pub fn flushes(&self) -> Vec<FlushRecord> {
+        self.flushes.lock().iter().cloned().collect()
+    }
+}
+
+fn push_with_capacity<T>(records: &mut VecDeque<T>, record: T) {
+    if records.len() >= STORAGE_HISTORY_CAPACITY {
+        records.pop_front();
+    }
+    records.push_back(record);
+}
+
+/// Columns `node_id`, `tenant_name`, `database_name` and `vnode_id` shared by all the kinds.
+#[derive(Default)]
+struct VnodeColumns {
+    node_ids: UInt64Builder,
+    tenant_names: StringBuilder,
+    database_names: StringBuilder,
+    vnode_ids: UInt32Builder,
+}
+
+impl VnodeColumns {
+    fn append(&mut self, node_id: NodeId, owner: &str, vnode_id: VnodeId) {
+        let (tenant, database) = split_owner(owner);
+        self.node_ids.append_value(node_id);
+        self.tenant_names.append_value(tenant);
+        self.database_names.append_value(database);
+        self.vnode_ids.append_value(vnode_id);
+    }
+
+    fn finish(mut self, columns: Vec<ArrayRef>) -> Vec<ArrayRef> {
+        let mut all_columns: Vec<ArrayRef> = vec![
+            Arc::new(self.node_ids.finish()),
+            Arc::new(self.tenant_names.finish()),
+            Arc::new(self.database_names.finish()),
+            Arc::new(self.vnode_ids.finish()),
+        ];
+        all_columns.extend(columns);
+        all_columns
+    }
+}
+
+/// Builds the storage statistics of all vnodes on this node.
+pub async fn storage_stats(
+    node_id: NodeId,
+    ctx: &TsKvContext,
+    kind: StorageStatsKind,
+) -> Result<RecordBatch> {
+    let columns = match kind {
+        StorageStatsKind::Vnodes => vnodes_columns(node_id, ctx).await,
+        StorageStatsKind::TsmFiles => tsm_files_columns(node_id, ctx).await,
+        StorageStatsKind::Compactions => compactions_columns(node_id, ctx),
+        StorageStatsKind::Flushes => flushes_columns(node_id, ctx),
+        StorageStatsKind::Wals => wals_columns(node_id, ctx).await?,
+        StorageStatsKind::TsmReaderCaches => tsm_reader_caches_columns(node_id, ctx).await,
+    };
+    Ok(RecordBatch::try_new(kind.schema(), columns)?)
+}
+
+async fn ts_families(ctx: &TsKvContext) -> Vec<(Arc<String>, Arc<RwLock<TseriesFamily>>)> {
+    let databases = ctx
+        .version_set
+        .read()
+        .await
+        .get_all_db()
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut ts_families = vec![];
+    for database in databases {
+        let db = database.read().await;
+        for ts_family in db.ts_families().values() {
+            ts_families.push((db.owner(), ts_family.clone()));
+        }
+    }
+    ts_families
+}
+
+async fn vnodes_columns(node_id: NodeId, ctx: &TsKvContext) -> Vec<ArrayRef> {
+    let mut vnode_columns = VnodeColumns::default();
+    let mut disk_storages = UInt64Builder::new();
+    let mut cache_sizes = UInt64Builder::new();
+    let mut tsm_files = UInt64Builder::new();
+    let mut delta_files = UInt64Builder::new();
+    let mut last_seqs = UInt64Builder::new();
+    let mut max_level_tss = Int64Builder::new();
+
+    for (owner, ts_family) in ts_families(ctx).await {
+        let ts_family = ts_family.read().await;
+        let version = ts_family.version();
+        let (delta, tsm) = version
+            .levels_info()
+            .iter()
+            .flat_map(|level| level.files.iter())
+            .fold((0, 0), |(delta, tsm), file| match file.is_delta() {
+                true => (delta + 1, tsm),
+                false => (delta, tsm + 1),
+            });
+
+        vnode_columns.append(node_id, &owner, ts_family.tf_id());
+        disk_storages.append_value(ts_family.disk_storage());
+        cache_sizes.append_value(ts_family.cache_size());
+        tsm_files.append_value(tsm);
+        delta_files.append_value(delta);
+        last_seqs.append_value(version.last_seq());
+        max_level_tss.append_value(version.max_level_ts());
+    }
+
+    vnode_columns.finish(vec![
+        Arc::new(disk_storages.finish()),
+        Arc::new(cache_sizes.finish()),
+        Arc::new(tsm_files.finish()),
+        Arc::new(delta_files.finish()),
+        Arc::new(last_seqs.finish()),
+        Arc::new(max_level_tss.finish()),
+    ])
+}
+
+async fn tsm_files_columns(node_id: NodeId, ctx: &TsKvContext) -> Vec<ArrayRef> {
+    let mut vnode_columns = VnodeColumns::default();
+    let mut levels = UInt32Builder::new();
+    let mut file_ids = UInt64Builder::new();
+    let mut is_deltas = BooleanBuilder::new();
+    let mut min_tss = Int64Builder::new();
+    let mut max_tss = Int64Builder::new();
+    let mut sizes = UInt64Builder::new();
+    let mut compactings = BooleanBuilder::new();
+
+    for (owner, ts_family) in ts_families(ctx).await {
+        let (vnode_id, version) = {
+            let ts_family = ts_family.read().await;
+            (ts_family.tf_id(), ts_family.version())
+        };
+        for level in version.levels_info() {
+            for file in level.files.iter() {
+                vnode_columns.append(node_id, &owner, vnode_id);
+                levels.append_value(file.level());
+                file_ids.append_value(file.file_id());
+                is_deltas.append_value(file.is_delta());
+                min_tss.append_value(file.time_range().min_ts);
+                max_tss.append_value(file.time_range().max_ts);
+                sizes.append_value(file.size());
+                compactings.append_value(file.is_compacting());
+            }
+        }
+    }
+
+    vnode_columns.finish(vec![
+        Arc::new(levels.finish()),
+        Arc::new(file_ids.finish()),
+        Arc::new(is_deltas.finish()),
+        Arc::new(min_tss.finish()),
+        Arc::new(max_tss.finish()),
+        Arc::new(sizes.finish()),
+        Arc::new(compactings.finish()),
+    ])
+}
+
+fn compactions_columns(node_id: NodeId, ctx: &TsKvContext) -> Vec<ArrayRef> {
+    let mut vnode_columns = VnodeColumns::default();
+    let mut out_levels = UInt32Builder::new();
+    let mut start_times = TimestampNanosecondBuilder::new();
+    let mut durations = Float64Builder::new();
+    let mut input_files = UInt64Builder::new();
+    let mut input_bytes = UInt64Builder::new();
+    let mut output_files = UInt64Builder::new();
+    let mut output_bytes = UInt64Builder::new();
+    let mut successes = BooleanBuilder::new();
+    let mut errors = StringBuilder::new();
+
+    for record in ctx.storage_history.compactions() {
+        vnode_columns.append(node_id, &record.owner, record.vnode_id);
+        out_levels.append_value(record.out_level);
+        start_times.append_value(record.start_time);
+        durations.append_value(record.duration);
+        input_files.append_value(record.input_files);
+        input_bytes.append_value(record.input_bytes);
+        output_files.append_value(record.output_files);
+        output_bytes.append_value(record.output_bytes);
+        successes.append_value(record.error.is_none());
+        errors.append_option(record.error);
+    }
+
+    vnode_columns.finish(vec![
+        Arc::new(out_levels.finish()),
+        Arc::new(start_times.finish()),
+        Arc::new(durations.finish()),
+        Arc::new(input_files.finish()),
+        Arc::new(input_bytes.finish()),
+        Arc::new(output_files.finish()),
+        Arc::new(output_bytes.finish()),
+        Arc::new(successes.finish()),
+        Arc::new(errors.finish()),
+    ])
+}
+
+fn flushes_columns(node_id: NodeId, ctx: &TsKvContext) -> Vec<ArrayRef> {
+    let mut vnode_columns = VnodeColumns::default();
+    let mut start_times = TimestampNanosecondBuilder::new();
+    let mut durations = Float64Builder::new();
+    let mut low_seq_nos = UInt64Builder::new();
+    let mut high_seq_nos = UInt64Builder::new();
+    let mut output_files = UInt64Builder::new();
+    let mut output_bytes = UInt64Builder::new();
+    let mut successes = BooleanBuilder::new();
+    let mut errors = StringBuilder::new();
+
+    for record in ctx.storage_history.flushes() {
+        vnode_columns.append(node_id, &record.owner, record.vnode_id);
+        start_times.append_value(record.start_time);
+        durations.append_value(record.duration);
+        low_seq_nos.append_value(record.low_seq_no);
+        high_seq_nos.append_value(record.high_seq_no);
+        output_files.append_value(record.output_files);
+        output_bytes.append_value(record.output_bytes);
+        successes.append_value(record.error.is_none());
+        errors.append_option(record.error);
+    }
+
+    vnode_columns.finish(vec![
+        Arc::new(start_times.finish()),
+        Arc::new(durations.finish()),
+        Arc::new(low_seq_nos.finish()),
+        Arc::new(high_seq_nos.finish()),
+        Arc::new(output_files.finish()),
+        Arc::new(output_bytes.finish()),
+        Arc::new(successes.finish()),
+        Arc::new(errors.finish()),
+    ])
+}
+
+async fn wals_columns(node_id: NodeId, ctx: &TsKvContext) -> Result<Vec<ArrayRef>> {
+    let mut vnodes = vec![];
+    for (owner, ts_family) in ts_families(ctx).await {
+        let vnode_id = ts_family.read().await.tf_id();
+        let wal_dir = ctx.options.wal.wal_dir(&owner, vnode_id);
+        vnodes.push((owner, vnode_id, wal_dir));
+    }
+
+    // The wal directories are listed on the blocking threads, not to block the runtime.
+    let vnodes = tokio::task::spawn_blocking(move || {
+        vnodes
+            .into_iter()
+            .map(|(owner, vnode_id, wal_dir)| {
+                let (count, size) = VnodeWal::wal_dir_usage(&wal_dir);
+                (owner, vnode_id, count, size)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| Error::CommonError {
+        reason: format!("failed to list the wal directories: {e}"),
+    })?;
+
+    let mut vnode_columns = VnodeColumns::default();
+    let mut wal_files = UInt64Builder::new();
+    let mut wal_sizes = UInt64Builder::new();
+    for (owner, vnode_id, count, size) in vnodes {
+        vnode_columns.append(node_id, &owner, vnode_id);
+        wal_files.append_value(count);
+        wal_sizes.append_value(size);
+    }
+
+    Ok(vnode_columns.finish(vec![
+        Arc::new(wal_files.finish()),
+        Arc::new(wal_sizes.finish()),
+    ]))
+}
+
+async fn tsm_reader_caches_columns(node_id: NodeId, ctx: &TsKvContext) -> Vec<ArrayRef> {
+    let mut vnode_columns = VnodeColumns::default();
+    let mut capacities = UInt64Builder::new();
+    let mut usages = UInt64Builder::new();
+    let mut hits = UInt64Builder::new();
+    let mut misses = UInt64Builder::new();
+    let mut hit_rates = Float64Builder::new();
+
+    for (owner, ts_family) in ts_families(ctx).await {
+        let (vnode_id, version) = {
+            let ts_family = ts_family.read().await;
+            (ts_family.tf_id(), ts_family.version())
+        };
+        let cache = version.tsm_reader_cache();
+        let (hit, miss) = (cache.hit_count(), cache.miss_count());
+        let hit_rate = match hit + miss {
+            0 => 0.0,
+            total => hit as f64 / total as f64,
+        };
+
+        vnode_columns.append(node_id, &owner, vnode_id);
+        capacities.append_value(cache.get_capacity().await as u64);
+        usages.append_value(cache.get_usage().await as u64);
+        hits.append_value(hit);
+        misses.append_value(miss);
+        hit_rates.append_value(hit_rate);
+    }
+
+    vnode_columns.finish(vec![
+        Arc::new(capacities.finish()),
+        Arc::new(usages.finish()),
+        Arc::new(hits.finish()),
+        Arc::new(misses.finish()),
+        Arc::new(hit_rates.finish()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{FlushRecord, StorageHistory, StorageStatsKind, STORAGE_HISTORY_CAPACITY};
+
+    #[test]
+    fn test_storage_stats_kind() {
+        for kind in [
+            StorageStatsKind::Vnodes,
+            StorageStatsKind::TsmFiles,
+            StorageStatsKind::Compactions,
+            StorageStatsKind::Flushes,
+            StorageStatsKind::Wals,
+            StorageStatsKind::TsmReaderCaches,
+        ] {
+            assert_eq!(kind.to_string().parse::<StorageStatsKind>(), Ok(kind));
+        }
+        assert!("levels".parse::<StorageStatsKind>().is_err());
+    }
+
+    #[test]
+    fn test_storage_history_capacity() {
+        let history = StorageHistory::default();
+        for seq in 0..STORAGE_HISTORY_CAPACITY as u64 + 10 {
+            history.record_flush(FlushRecord {
+                owner: Arc::new("cnosdb.public".to_string()),
+                vnode_id: 1,
+                start_time: 0,
+                duration: 0.0,
+                low_seq_no: seq,
+                high_seq_no: seq,
+                output_files: 1,
+                output_bytes: 1,
+                error: None,
+            });
+        }
+
+        let flushes = history.flushes();
+        assert_eq!(flushes.len(), STORAGE_HISTORY_CAPACITY);
+        assert_eq!(flushes[0].low_seq_no, 10);
+    }
+}
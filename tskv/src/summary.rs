@@ -8,6 +8,7 @@ use cache::ShardedAsyncCache;
 use memory_pool::MemoryPoolRef;
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
+use models::utils::now_timestamp_nanos;
 use models::Timestamp;
 use parking_lot::RwLock as SyncRwLock;
 use serde::{Deserialize, Serialize};
@@ -38,6 +39,13 @@ pub struct CompactMeta {
     pub min_ts: Timestamp,
     pub max_ts: Timestamp,
     pub is_delta: bool,
+    /// The delta file holds the late points of the tables with
+    /// `LateArrivalPolicy::LateTier`, it's compacted on a slower schedule.
+    /// Since `RecordDataVersion::V2`.
+    pub is_late: bool,
+    /// The wall-clock time in nanoseconds when the file is written, it's 0 if unknown.
+    /// Since `RecordDataVersion::V2`.
+    pub create_time: Timestamp,
 }
 
 impl Default for CompactMeta {
@@ -50,6 +58,8 @@ impl Default for CompactMeta {
             min_ts: Timestamp::MAX,
             max_ts: Timestamp::MIN,
             is_delta: false,
+            is_late: false,
+            create_time: 0,
         }
     }
 }
@@ -67,6 +77,8 @@ impl From<&ColumnFile> for CompactMeta {
             min_ts: file.time_range().min_ts,
             max_ts: file.time_range().max_ts,
             is_delta: file.is_delta(),
+            is_late: file.is_late(),
+            create_time: file.create_time(),
             ..Default::default()
         }
     }
@@ -144,6 +156,8 @@ impl CompactMetaBuilder {
             min_ts,
             max_ts,
             is_delta: level == 0,
+            is_late: false,
+            create_time: now_timestamp_nanos(),
         }
     }
 }
@@ -233,6 +247,19 @@ impl VersionEdit {
         bincode::deserialize(buf).map_err(|e| Error::RecordFileDecode { source: (e) })
     }
 
+    /// Decode the version edit of a summary record written in `data_version`.
+    pub fn decode_with_version(data_version: u8, buf: &[u8]) -> Result<Self> {
+        match RecordDataVersion::try_from(data_version) {
+            Ok(RecordDataVersion::V1) => bincode::deserialize::<v1::VersionEdit>(buf)
+                .map(Self::from)
+                .map_err(|e| Error::RecordFileDecode { source: (e) }),
+            Ok(RecordDataVersion::V2) => Self::decode(buf),
+            Err(_) => Err(Error::CommonError {
+                reason: format!("unknown summary record version {data_version}"),
+            }),
+        }
+    }
+
     pub fn encode_vec(data: &[Self]) -> Result<Vec<u8>> {
         let mut buf: Vec<u8> = Vec::with_capacity(data.len() * 32);
         for ve in data {
@@ -283,6 +310,71 @@ impl VersionEdit {
     }
 }
 
+/// The layouts of the summary records written in `RecordDataVersion::V1`.
+pub(crate) mod v1 {
+    use models::Timestamp;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{ColumnFileId, LevelId, TseriesFamilyId};
+
+    #[derive(Serialize, Deserialize)]
+    pub struct CompactMeta {
+        pub file_id: ColumnFileId,
+        pub file_size: u64,
+        pub tsf_id: TseriesFamilyId,
+        pub level: LevelId,
+        pub min_ts: Timestamp,
+        pub max_ts: Timestamp,
+        pub is_delta: bool,
+    }
+
+    impl From<CompactMeta> for super::CompactMeta {
+        fn from(meta: CompactMeta) -> Self {
+            Self {
+                file_id: meta.file_id,
+                file_size: meta.file_size,
+                tsf_id: meta.tsf_id,
+                level: meta.level,
+                min_ts: meta.min_ts,
+                max_ts: meta.max_ts,
+                is_delta: meta.is_delta,
+                is_late: false,
+                create_time: 0,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct VersionEdit {
+        pub seq_no: u64,
+        pub file_id: u64,
+        pub max_level_ts: Timestamp,
+        pub add_files: Vec<CompactMeta>,
+        pub del_files: Vec<CompactMeta>,
+
+        pub del_tsf: bool,
+        pub add_tsf: bool,
+        pub tsf_id: TseriesFamilyId,
+        pub tsf_name: String,
+    }
+
+    impl From<VersionEdit> for super::VersionEdit {
+        fn from(edit: VersionEdit) -> Self {
+            Self {
+                seq_no: edit.seq_no,
+                file_id: edit.file_id,
+                max_level_ts: edit.max_level_ts,
+                add_files: edit.add_files.into_iter().map(Into::into).collect(),
+                del_files: edit.del_files.into_iter().map(Into::into).collect(),
+                del_tsf: edit.del_tsf,
+                add_tsf: edit.add_tsf,
+                tsf_id: edit.tsf_id,
+                tsf_name: edit.tsf_name,
+            }
+        }
+    }
+}
+
 impl Display for VersionEdit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "seq_no: {}, file_id: {}, add_files: {}, del_files: {}, del_tsf: {}, add_tsf: {}, tsf_id: {}, tsf_name: {}, max_level_ts: {}",
@@ -316,7 +408,7 @@ impl Summary {
         let buf = db.encode()?;
         let _ = w
             .write_record(
-                RecordDataVersion::V1.into(),
+                RecordDataVersion::V2.into(),
                 RecordDataType::Summary.into(),
                 &[&buf],
             )
@@ -410,7 +502,7 @@ impl Summary {
             let res = reader.read_record().await;
             match res {
                 Ok(result) => {
-                    let ed = VersionEdit::decode(&result.data)?;
+                    let ed = VersionEdit::decode_with_version(result.data_version, &result.data)?;
                     if ed.add_tsf {
                         let db_ref = database_map
                             .entry(ed.tsf_name.clone())
@@ -501,7 +593,7 @@ impl Summary {
         let _ = self
             .writer
             .write_record(
-                RecordDataVersion::V1.into(),
+                RecordDataVersion::V2.into(),
                 RecordDataType::Summary.into(),
                 &[&buf],
             )
@@ -575,7 +667,8 @@ pub async fn print_summary_statistics(path: impl AsRef<Path>) {
     loop {
         match reader.read_record().await {
             Ok(record) => {
-                let ve = VersionEdit::decode(&record.data).unwrap();
+                let ve =
+                    VersionEdit::decode_with_version(record.data_version, &record.data).unwrap();
                 println!("VersionEdit #{}, vnode_id: {}", i, ve.tsf_id);
                 println!("------------------------------------------------------------");
                 i += 1;
@@ -683,7 +776,8 @@ mod test {
     use utils::BloomFilter;
 
     use crate::kv_option::{self, Options};
-    use crate::summary::{CompactMeta, Summary, SummaryTask, VersionEdit};
+    use crate::record_file::RecordDataVersion;
+    use crate::summary::{v1, CompactMeta, Summary, SummaryTask, VersionEdit};
     use crate::{Engine, TsKv, TseriesFamilyId};
 
     #[test]
@@ -712,6 +806,43 @@ mod test {
         assert_eq!(ves, ves_2);
     }
 
+    #[test]
+    fn test_version_edit_v1() {
+        let ve_v1 = v1::VersionEdit {
+            seq_no: 1,
+            file_id: 100,
+            max_level_ts: 100_000_000,
+            add_files: vec![v1::CompactMeta {
+                file_id: 100,
+                file_size: 1024,
+                tsf_id: 1,
+                level: 0,
+                min_ts: 1,
+                max_ts: 100_000_000,
+                is_delta: true,
+            }],
+            del_files: vec![],
+            del_tsf: false,
+            add_tsf: false,
+            tsf_id: 1,
+            tsf_name: "cnosdb.public".to_string(),
+        };
+        let buf = bincode::serialize(&ve_v1).unwrap();
+
+        let ve = VersionEdit::decode_with_version(RecordDataVersion::V1.into(), &buf).unwrap();
+        assert_eq!(ve.tsf_name, "cnosdb.public");
+        assert_eq!(ve.add_files.len(), 1);
+        assert_eq!(ve.add_files[0].file_size, 1024);
+        assert!(ve.add_files[0].is_delta);
+        assert!(!ve.add_files[0].is_late);
+        assert_eq!(ve.add_files[0].create_time, 0);
+
+        // The records of V2 are decoded as they are.
+        let buf = ve.encode().unwrap();
+        let ve2 = VersionEdit::decode_with_version(RecordDataVersion::V2.into(), &buf).unwrap();
+        assert_eq!(ve2, ve);
+    }
+
     struct SummaryTestHelper {
         tskv: TsKv,
         config: Config,
@@ -949,6 +1080,8 @@ mod test {
                 min_ts: 1,
                 max_ts: 1,
                 tsf_id: VNODE_ID,
+                is_late: false,
+                create_time: 0,
             };
 
             let mut version = version.inner();
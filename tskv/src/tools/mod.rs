@@ -0,0 +1,353 @@
+//! Offline tools of the `tskv` binary, they inspect and repair the files of a
+//! storage directory while the server is stopped.
+//!
+//! Every tool returns a report that can be serialized to JSON.
+
+mod rebuild_summary;
+mod salvage;
+mod verify;
+
+pub use rebuild_summary::{rebuild_summary, RebuildSummaryReport, VnodeSummaryReport};
+pub use salvage::{salvage_tsm, SalvageReport};
+pub use verify::{verify, FileVerifyReport, VerifyReport};
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use serde::Serialize;
+
+use crate::file_system::file::async_file::AsyncFile;
+use crate::file_system::file::IFile;
+use crate::file_system::file_manager;
+use crate::tsm::page::{Chunk, ChunkGroup, ChunkGroupMeta, Footer, Page, PageWriteSpec};
+use crate::tsm::reader::TsmMetaData;
+use crate::tsm::writer::{HEADER_LEN, TSM_MAGIC, VERSION};
+use crate::tsm::{FOOTER_SIZE, TOMBSTONE_FILE_SUFFIX};
+use crate::{file_utils, LevelId};
+
+/// Errors of a file are truncated to this count in the reports.
+const MAX_ERRORS_PER_FILE: usize = 100;
+
+/// The kind of a file in the storage directory, detected by its file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    Tsm,
+    Delta,
+    Tombstone,
+    Summary,
+    Wal,
+    IndexBinlog,
+}
+
+impl FileKind {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        if file_utils::check_summary_file_name(file_name) {
+            Some(Self::Summary)
+        } else if file_utils::check_wal_file_name(file_name) {
+            Some(Self::Wal)
+        } else if file_utils::check_index_binlog_file_name(file_name) {
+            Some(Self::IndexBinlog)
+        } else if file_name.starts_with('_') {
+            match path.extension()?.to_str()? {
+                "tsm" => Some(Self::Tsm),
+                "delta" => Some(Self::Delta),
+                TOMBSTONE_FILE_SUFFIX => Some(Self::Tombstone),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn is_tsm(&self) -> bool {
+        matches!(self, Self::Tsm | Self::Delta)
+    }
+}
+
+/// Collects the errors of a file, keeps at most `MAX_ERRORS_PER_FILE` of them.
+fn push_error(errors: &mut Vec<String>, error: String) {
+    if errors.len() < MAX_ERRORS_PER_FILE {
+        errors.push(error);
+    }
+}
+
+/// Reads `size` bytes at `offset`, returns an error instead of reading out of
+/// the file if the offset or the size is damaged.
+async fn read_exact_at(
+    file: &AsyncFile,
+    offset: u64,
+    size: usize,
+    what: &str,
+) -> Result<Vec<u8>, String> {
+    match offset.checked_add(size as u64) {
+        Some(end) if end <= file.len() => {}
+        _ => {
+            return Err(format!(
+                "{what} at offset {offset} with size {size} exceeds the file size {}",
+                file.len()
+            ))
+        }
+    }
+    let mut buf = vec![0_u8; size];
+    file.read_at(offset, &mut buf)
+        .await
+        .map_err(|e| format!("failed to read {what} at offset {offset}: {e}"))?;
+    Ok(buf)
+}
+
+/// Opens a tsm file and reads the header, footer, chunk group meta, chunk groups
+/// and chunks of it with bounds checks.
+async fn read_tsm_meta(path: &Path) -> Result<(Arc<AsyncFile>, TsmMetaData), String> {
+    let file = file_manager::open_file(path)
+        .await
+        .map_err(|e| format!("failed to open file: {e}"))?;
+    if file.len() < HEADER_LEN + FOOTER_SIZE as u64 {
+        return Err(format!(
+            "file size {} is less than the size of header and footer",
+            file.len()
+        ));
+    }
+
+    let header = read_exact_at(&file, 0, HEADER_LEN as usize, "header").await?;
+    if header[..TSM_MAGIC.len()] != TSM_MAGIC || header[TSM_MAGIC.len()..] != VERSION {
+        return Err(format!("invalid header {header:?}"));
+    }
+
+    let buf = read_exact_at(
+        &file,
+        file.len() - FOOTER_SIZE as u64,
+        FOOTER_SIZE,
+        "footer",
+    )
+    .await?;
+    let footer = Footer::deserialize(&buf).map_err(|e| format!("invalid footer: {e}"))?;
+
+    let buf = read_exact_at(
+        &file,
+        footer.table().chunk_group_offset(),
+        footer.table().chunk_group_size(),
+        "chunk group meta",
+    )
+    .await?;
+    let chunk_group_meta = ChunkGroupMeta::deserialize(&buf, footer.version())
+        .map_err(|e| format!("invalid chunk group meta: {e}"))?;
+
+    let mut chunk_groups = BTreeMap::new();
+    for (table, spec) in chunk_group_meta.tables() {
+        let what = format!("chunk group of table '{table}'");
+        let buf = read_exact_at(
+            &file,
+            spec.chunk_group_offset(),
+            spec.chunk_group_size(),
+            &what,
+        )
+        .await?;
+        let chunk_group =
+            ChunkGroup::deserialize(&buf).map_err(|e| format!("invalid {what}: {e}"))?;
+        chunk_groups.insert(table.clone(), Arc::new(chunk_group));
+    }
+
+    let mut chunks = BTreeMap::new();
+    for chunk_group in chunk_groups.values() {
+        for spec in chunk_group.chunks() {
+            let what = format!("chunk of series {}", spec.series_id());
+            let buf = read_exact_at(&file, spec.chunk_offset(), spec.chunk_size(), &what).await?;
+            let chunk = Chunk::deserialize(&buf).map_err(|e| format!("invalid {what}: {e}"))?;
+            chunks.insert(spec.series_id(), Arc::new(chunk));
+        }
+    }
+
+    let meta = TsmMetaData::new(
+        Arc::new(footer),
+        Arc::new(chunk_group_meta),
+        chunk_groups,
+        chunks,
+    );
+    Ok((Arc::new(file), meta))
+}
+
+/// Returns the level recorded in the tsm file, files of footer version 3 and before
+/// don't record it.
+fn recorded_level(meta: &TsmMetaData) -> Option<LevelId> {
+    if meta.footer().version() < 4 {
+        return None;
+    }
+    Some(meta.chunk_group_meta().level())
+}
+
+/// Reads a page of a tsm file and validates the crc of it.
+async fn read_page(file: &AsyncFile, spec: &PageWriteSpec) -> Result<Page, String> {
+    // 4 bytes bitset len, 8 bytes data len and 4 bytes crc32.
+    const PAGE_HEADER_LEN: usize = 16;
+
+    let buf = read_exact_at(file, spec.offset(), spec.size(), "page").await?;
+    if buf.len() < PAGE_HEADER_LEN {
+        return Err(format!(
+            "page at offset {} is too small: {} bytes",
+            spec.offset(),
+            buf.len()
+        ));
+    }
+    let bitset_len = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize;
+    if PAGE_HEADER_LEN + bitset_len > buf.len() {
+        return Err(format!(
+            "page at offset {} has an invalid bitset length {bitset_len}",
+            spec.offset()
+        ));
+    }
+    Page::new(Bytes::from(buf), spec.meta().clone())
+        .crc_validation()
+        .map_err(|e| format!("page at offset {}: {e}", spec.offset()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use arrow::datatypes::TimeUnit;
+    use models::codec::Encoding;
+    use models::field_value::FieldVal;
+    use models::schema::{ColumnType, PhysicalCType, TableColumn, TskvTableSchema};
+    use models::{PhysicalDType, SeriesKey, ValueType};
+
+    use super::{read_tsm_meta, rebuild_summary, salvage_tsm, verify, FileKind};
+    use crate::record_file::Reader;
+    use crate::tsm::writer::{Column, DataBlock, TsmWriter};
+    use crate::VersionEdit;
+
+    fn i64_column(ctype: PhysicalCType, data: Vec<i64>) -> Column {
+        let mut col = Column::empty(ctype).unwrap();
+        for datum in data {
+            col.push(Some(FieldVal::Integer(datum)))
+        }
+        col
+    }
+
+    /// Writes a tsm file of 2 series to level 3 and returns the path of it.
+    async fn write_tsm_file(dir: &Path) -> PathBuf {
+        let schema = Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "test0".to_string(),
+            vec![
+                TableColumn::new(
+                    0,
+                    "time".to_string(),
+                    ColumnType::Time(TimeUnit::Nanosecond),
+                    Encoding::default(),
+                ),
+                TableColumn::new(
+                    1,
+                    "f1".to_string(),
+                    ColumnType::Field(ValueType::Integer),
+                    Encoding::default(),
+                ),
+            ],
+        ));
+        let mut writer = TsmWriter::open(&dir, 1, 0, false).await.unwrap();
+        writer.set_level(3);
+        for series_id in [1, 2] {
+            let block = DataBlock::new(
+                schema.clone(),
+                i64_column(PhysicalCType::Time(TimeUnit::Nanosecond), vec![1, 2, 3]),
+                schema.time_column(),
+                vec![i64_column(
+                    PhysicalCType::Field(PhysicalDType::Integer),
+                    vec![1, 2, 3],
+                )],
+                vec![schema.column("f1").cloned().unwrap()],
+            );
+            writer
+                .write_datablock(series_id, SeriesKey::default(), block)
+                .await
+                .unwrap();
+        }
+        writer.finish().await.unwrap();
+        writer.path().to_path_buf()
+    }
+
+    #[test]
+    fn test_file_kind() {
+        for (path, kind) in [
+            ("/data/db/1/tsm/_000001.tsm", Some(FileKind::Tsm)),
+            ("/data/db/1/delta/_000002.delta", Some(FileKind::Delta)),
+            (
+                "/data/db/1/tsm/_000001.tombstone",
+                Some(FileKind::Tombstone),
+            ),
+            ("/summary/summary-000000", Some(FileKind::Summary)),
+            ("/wal/1/_000003.wal", Some(FileKind::Wal)),
+            (
+                "/data/db/1/index/_000004.binlog",
+                Some(FileKind::IndexBinlog),
+            ),
+            ("/data/db/1/index/series.dat", None),
+        ] {
+            assert_eq!(FileKind::from_path(Path::new(path)), kind, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_salvage_and_rebuild_summary() {
+        let storage_dir = Path::new("/tmp/test/tools/salvage");
+        let _ = std::fs::remove_dir_all(storage_dir);
+        let tsm_dir = storage_dir.join("data/cnosdb.public/1/tsm");
+        let out_dir = Path::new("/tmp/test/tools/salvage_out");
+        let _ = std::fs::remove_dir_all(out_dir);
+
+        let tsm_path = write_tsm_file(&tsm_dir).await;
+        let report = verify(&tsm_dir).await.unwrap();
+        assert_eq!(report.damaged_files, 0);
+        assert_eq!(report.files[0].checked, 4);
+
+        // Damage the last page of series 2.
+        let (_, meta) = read_tsm_meta(&tsm_path).await.unwrap();
+        let page = meta.chunk()[&2].column_group()[&0].pages()[1].clone();
+        let mut bytes = std::fs::read(&tsm_path).unwrap();
+        let pos = (page.offset() + page.size() as u64 - 1) as usize;
+        bytes[pos] = !bytes[pos];
+        std::fs::write(&tsm_path, bytes).unwrap();
+
+        let report = verify(&tsm_dir).await.unwrap();
+        assert_eq!(report.damaged_files, 1);
+        assert_eq!(report.files[0].damaged, 1);
+
+        let report = salvage_tsm(&tsm_path, out_dir).await.unwrap();
+        assert!(report.recoverable);
+        assert_eq!(report.series, 2);
+        assert_eq!(report.dropped_series, 1);
+        assert_eq!(report.kept_column_groups, 1);
+        assert_eq!(report.dropped_column_groups, 1);
+        let (_, meta) = read_tsm_meta(&out_dir.join("_000001.tsm")).await.unwrap();
+        assert_eq!(meta.chunk_group_meta().level(), 3);
+        let report = verify(out_dir).await.unwrap();
+        assert_eq!(report.checked_files, 1);
+        assert_eq!(report.damaged_files, 0);
+
+        let report = rebuild_summary(storage_dir, false).await.unwrap();
+        assert_eq!(report.vnodes.len(), 1);
+        assert_eq!(report.vnodes[0].owner, "cnosdb.public");
+        assert_eq!(report.vnodes[0].vnode_id, 1);
+        assert_eq!(report.vnodes[0].tsm_files, 1);
+        assert!(rebuild_summary(storage_dir, false).await.is_err());
+
+        let mut reader = Reader::open(&report.summary_path).await.unwrap();
+        let record = reader.read_record().await.unwrap();
+        let add_vnode =
+            VersionEdit::decode_with_version(record.data_version, &record.data).unwrap();
+        assert!(add_vnode.add_tsf);
+        let record = reader.read_record().await.unwrap();
+        let update_vnode =
+            VersionEdit::decode_with_version(record.data_version, &record.data).unwrap();
+        assert_eq!(update_vnode.add_files.len(), 1);
+        assert!(!update_vnode.add_files[0].is_late);
+        assert_eq!(update_vnode.add_files[0].file_id, 1);
+        assert_eq!(update_vnode.add_files[0].level, 3);
+        assert_eq!(update_vnode.add_files[0].max_ts, 3);
+    }
+}
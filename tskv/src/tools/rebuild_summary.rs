@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use models::Timestamp;
+use serde::Serialize;
+
+use super::{read_tsm_meta, recorded_level, FileKind};
+use crate::file_system::file::IFile;
+use crate::file_system::file_manager;
+use crate::kv_option::{DATA_PATH, DELTA_PATH, SUMMARY_PATH, TSM_PATH};
+use crate::record_file::{RecordDataType, RecordDataVersion, Writer};
+use crate::summary::{CompactMeta, VersionEdit};
+use crate::{file_utils, Error, Result, TseriesFamilyId};
+
+#[derive(Debug, Default, Serialize)]
+pub struct RebuildSummaryReport {
+    pub summary_path: PathBuf,
+    pub vnodes: Vec<VnodeSummaryReport>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct VnodeSummaryReport {
+    pub owner: String,
+    pub vnode_id: TseriesFamilyId,
+    pub tsm_files: u64,
+    pub delta_files: u64,
+    pub max_file_id: u64,
+    pub max_level_ts: i64,
+    /// Files that are skipped because their meta blocks are damaged, with the reasons.
+    pub skipped_files: Vec<String>,
+}
+
+/// Reconstructs the summary file of the storage path from the tsm and delta files in
+/// `<storage_path>/data/<owner>/<vnode_id>/{tsm,delta}`.
+///
+/// Files are added to the level recorded in their chunk group meta, files written
+/// before the level was recorded are added to level 0 if they are delta files and to
+/// level 1 otherwise. Late delta files are also recognized by the chunk group meta.
+/// The sequence number of the vnodes are set to 0 so that the whole wal is replayed
+/// at the next start. The existing summary file is only overwritten if `force` is set.
+pub async fn rebuild_summary(
+    storage_path: impl AsRef<Path>,
+    force: bool,
+) -> Result<RebuildSummaryReport> {
+    let storage_path = storage_path.as_ref();
+    let summary_path = file_utils::make_summary_file(storage_path.join(SUMMARY_PATH), 0);
+    if !force && file_manager::try_exists(&summary_path) {
+        return Err(Error::CommonError {
+            reason: format!(
+                "summary file '{}' already exists, use --force to overwrite it",
+                summary_path.display()
+            ),
+        });
+    }
+
+    let mut report = RebuildSummaryReport {
+        summary_path: summary_path.clone(),
+        vnodes: vec![],
+    };
+    let mut edits = vec![];
+    for (owner, vnode_id, vnode_dir) in list_vnode_dirs(&storage_path.join(DATA_PATH))? {
+        let mut vnode_report = VnodeSummaryReport {
+            owner: owner.clone(),
+            vnode_id,
+            ..Default::default()
+        };
+        let mut edit = VersionEdit::new_update_vnode(vnode_id, owner.clone(), 0);
+        for (dir, default_level) in [(DELTA_PATH, 0), (TSM_PATH, 1)] {
+            for path in list_files(&vnode_dir.join(dir)) {
+                let kind = match FileKind::from_path(&path) {
+                    Some(kind) if kind.is_tsm() => kind,
+                    _ => continue,
+                };
+                let (file_size, footer, is_late, level) = match read_tsm_meta(&path).await {
+                    Ok((file, meta)) => (
+                        file.len(),
+                        meta.footer(),
+                        meta.chunk_group_meta().is_late(),
+                        recorded_level(&meta).unwrap_or(default_level),
+                    ),
+                    Err(e) => {
+                        vnode_report
+                            .skipped_files
+                            .push(format!("{}: {e}", path.display()));
+                        continue;
+                    }
+                };
+                let compact_meta = CompactMeta {
+                    file_id: file_utils::get_tsm_file_id_by_path(&path)?,
+                    file_size,
+                    tsf_id: vnode_id,
+                    level,
+                    min_ts: footer.time_range().min_ts,
+                    max_ts: footer.time_range().max_ts,
+                    is_delta: kind == FileKind::Delta,
+                    is_late,
+                    create_time: file_create_time(&path),
+                };
+                if compact_meta.is_delta {
+                    vnode_report.delta_files += 1;
+                } else {
+                    vnode_report.tsm_files += 1;
+                }
+                let max_ts = compact_meta.max_ts;
+                edit.add_file(compact_meta, max_ts);
+            }
+        }
+        vnode_report.max_file_id = edit.file_id;
+        vnode_report.max_level_ts = edit.max_level_ts;
+
+        edits.push(VersionEdit::new_add_vnode(vnode_id, owner, 0));
+        edits.push(edit);
+        report.vnodes.push(vnode_report);
+    }
+
+    std::fs::create_dir_all(storage_path.join(SUMMARY_PATH))?;
+    file_manager::remove_if_exists(&summary_path)?;
+    let mut writer = Writer::open(&summary_path, RecordDataType::Summary).await?;
+    for edit in edits {
+        writer
+            .write_record(
+                RecordDataVersion::V2.into(),
+                RecordDataType::Summary.into(),
+                &[&edit.encode()?],
+            )
+            .await?;
+    }
+    writer.sync().await?;
+
+    Ok(report)
+}
+
+/// Returns (owner, vnode_id, vnode_dir) of the vnode directories in the data directory.
+fn list_vnode_dirs(data_dir: &Path) -> Result<Vec<(String, TseriesFamilyId, PathBuf)>> {
+    let mut vnode_dirs = vec![];
+    for owner_dir in list_dirs(data_dir)? {
+        let owner = match owner_dir.file_name().and_then(|n| n.to_str()) {
+            Some(owner) => owner.to_string(),
+            None => continue,
+        };
+        for vnode_dir in list_dirs(&owner_dir)? {
+            let vnode_id = match vnode_dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.parse::<TseriesFamilyId>().ok())
+            {
+                Some(vnode_id) => vnode_id,
+                None => continue,
+            };
+            vnode_dirs.push((owner.clone(), vnode_id, vnode_dir));
+        }
+    }
+    Ok(vnode_dirs)
+}
+
+/// Returns the modification time of the file in nanoseconds, the file is not modified
+/// after it's written, or 0 if it's unknown.
+fn file_create_time(path: &Path) -> Timestamp {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as Timestamp)
+        .unwrap_or(0)
+}
+
+fn list_dirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            dirs.push(entry.path());
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn list_files(dir: &Path) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect()
+}
@@ -0,0 +1,146 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::{push_error, read_page, read_tsm_meta, recorded_level, FileKind};
+use crate::file_system::file_manager;
+use crate::file_utils;
+use crate::tsm::writer::TsmWriter;
+use crate::{Error, Result};
+
+#[derive(Debug, Default, Serialize)]
+pub struct SalvageReport {
+    pub source: PathBuf,
+    /// The rewritten file, none if nothing could be salvaged.
+    pub output: Option<PathBuf>,
+    /// False if the meta blocks of the file are damaged, no data can be salvaged then.
+    pub recoverable: bool,
+    pub series: u64,
+    pub dropped_series: u64,
+    pub kept_column_groups: u64,
+    pub dropped_column_groups: u64,
+    pub kept_pages: u64,
+    pub dropped_pages: u64,
+    pub tombstone_copied: bool,
+    pub errors: Vec<String>,
+}
+
+/// Rewrites a damaged tsm or delta file into `out_dir` with the same file name.
+///
+/// Column groups that contain a damaged page are dropped, the others are copied
+/// to the new file. The tombstone file of the tsm file is copied as it is.
+pub async fn salvage_tsm(
+    tsm_path: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> Result<SalvageReport> {
+    let (tsm_path, out_dir) = (tsm_path.as_ref(), out_dir.as_ref());
+    let is_delta = match FileKind::from_path(tsm_path) {
+        Some(FileKind::Tsm) => false,
+        Some(FileKind::Delta) => true,
+        _ => {
+            return Err(Error::CommonError {
+                reason: format!("'{}' is not a tsm or delta file", tsm_path.display()),
+            })
+        }
+    };
+    if tsm_path.parent() == Some(out_dir) {
+        return Err(Error::CommonError {
+            reason: "the output directory must not be the directory of the tsm file".to_string(),
+        });
+    }
+    let file_id = file_utils::get_tsm_file_id_by_path(tsm_path)?;
+
+    let mut report = SalvageReport {
+        source: tsm_path.to_path_buf(),
+        ..Default::default()
+    };
+    let (file, meta) = match read_tsm_meta(tsm_path).await {
+        Ok(res) => res,
+        Err(e) => {
+            push_error(&mut report.errors, e);
+            return Ok(report);
+        }
+    };
+    report.recoverable = true;
+
+    tokio::fs::create_dir_all(out_dir).await?;
+    let mut writer = TsmWriter::open(&out_dir, file_id, 0, is_delta).await?;
+    writer.set_late(meta.chunk_group_meta().is_late());
+    if let Some(level) = recorded_level(&meta) {
+        writer.set_level(level);
+    }
+    for (series_id, chunk) in meta.chunk() {
+        report.series += 1;
+        let schema = match meta.table_schema_by_sid(*series_id) {
+            Some(schema) => schema,
+            None => {
+                report.dropped_series += 1;
+                push_error(
+                    &mut report.errors,
+                    format!("series {series_id}: table schema not found"),
+                );
+                continue;
+            }
+        };
+
+        let mut kept_column_groups = 0;
+        'column_groups: for column_group in chunk.column_group().values() {
+            let mut pages = Vec::with_capacity(column_group.pages().len());
+            for spec in column_group.pages() {
+                match read_page(&file, spec).await {
+                    Ok(page) => pages.push(page),
+                    Err(e) => {
+                        report.dropped_column_groups += 1;
+                        report.dropped_pages += column_group.pages().len() as u64;
+                        push_error(
+                            &mut report.errors,
+                            format!(
+                                "series {series_id} column group {} dropped: {e}",
+                                column_group.column_group_id()
+                            ),
+                        );
+                        continue 'column_groups;
+                    }
+                }
+            }
+
+            report.kept_pages += pages.len() as u64;
+            writer
+                .write_pages(
+                    schema.clone(),
+                    *series_id,
+                    chunk.series_key().clone(),
+                    pages,
+                    *column_group.time_range(),
+                )
+                .await?;
+            kept_column_groups += 1;
+        }
+        report.kept_column_groups += kept_column_groups;
+        if kept_column_groups == 0 {
+            report.dropped_series += 1;
+        }
+    }
+
+    if report.kept_column_groups == 0 {
+        file_manager::remove_if_exists(writer.path())?;
+    } else {
+        writer.finish().await?;
+        report.output = Some(writer.path().to_path_buf());
+
+        let tombstone_path = file_utils::make_tsm_tombstone_file(
+            tsm_path.parent().unwrap_or_else(|| Path::new("/")),
+            file_id,
+        );
+        if tombstone_path.exists() {
+            tokio::fs::copy(
+                &tombstone_path,
+                file_utils::make_tsm_tombstone_file(out_dir, file_id),
+            )
+            .await?;
+            report.tombstone_copied = true;
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,244 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::{push_error, read_exact_at, read_page, read_tsm_meta, FileKind};
+use crate::file_system::file::async_file::AsyncFile;
+use crate::file_system::file::IFile;
+use crate::file_system::file_manager;
+use crate::record_file::{self, FILE_MAGIC_NUMBER, FILE_MAGIC_NUMBER_LEN};
+use crate::{Error, Result};
+
+#[derive(Debug, Default, Serialize)]
+pub struct VerifyReport {
+    pub path: PathBuf,
+    pub checked_files: u64,
+    pub damaged_files: u64,
+    /// Files that are not tsm, delta, tombstone, summary, wal or index binlog files.
+    pub skipped_files: u64,
+    pub files: Vec<FileVerifyReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileVerifyReport {
+    pub path: PathBuf,
+    pub kind: FileKind,
+    pub size: u64,
+    pub ok: bool,
+    /// Records of a record file, or pages of a tsm file, that were checked.
+    pub checked: u64,
+    /// Records or pages that failed the check.
+    pub damaged: u64,
+    pub errors: Vec<String>,
+}
+
+impl FileVerifyReport {
+    fn new(path: &Path, kind: FileKind) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            kind,
+            size: 0,
+            ok: true,
+            checked: 0,
+            damaged: 0,
+            errors: vec![],
+        }
+    }
+}
+
+/// Checks the crc of every record of the record files (summary, wal, tombstone and
+/// index binlog files), and the header, footer, meta blocks and the crc of every page
+/// of the tsm and delta files under the path.
+pub async fn verify(path: impl AsRef<Path>) -> Result<VerifyReport> {
+    let path = path.as_ref();
+    if !file_manager::try_exists(path) {
+        return Err(Error::CommonError {
+            reason: format!("path '{}' does not exist", path.display()),
+        });
+    }
+
+    let mut report = VerifyReport {
+        path: path.to_path_buf(),
+        ..Default::default()
+    };
+    for entry in walkdir::WalkDir::new(path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let file_path = entry.path();
+        let kind = match FileKind::from_path(file_path) {
+            Some(kind) => kind,
+            None => {
+                report.skipped_files += 1;
+                continue;
+            }
+        };
+        let file_report = verify_file(file_path, kind).await;
+        report.checked_files += 1;
+        if !file_report.ok {
+            report.damaged_files += 1;
+        }
+        report.files.push(file_report);
+    }
+
+    Ok(report)
+}
+
+async fn verify_file(path: &Path, kind: FileKind) -> FileVerifyReport {
+    let mut report = FileVerifyReport::new(path, kind);
+    match file_manager::open_file(path).await {
+        Ok(file) => {
+            report.size = file.len();
+            if kind.is_tsm() {
+                verify_tsm_file(path, &mut report).await;
+            } else {
+                verify_record_file(path, &file, &mut report).await;
+            }
+        }
+        Err(e) => push_error(&mut report.errors, format!("failed to open file: {e}")),
+    }
+    report.ok = report.damaged == 0 && report.errors.is_empty();
+    report
+}
+
+async fn verify_record_file(path: &Path, file: &AsyncFile, report: &mut FileVerifyReport) {
+    match read_exact_at(file, 0, FILE_MAGIC_NUMBER_LEN, "file magic").await {
+        Ok(magic) if magic == FILE_MAGIC_NUMBER.to_be_bytes() => {}
+        Ok(magic) => push_error(&mut report.errors, format!("invalid file magic {magic:?}")),
+        Err(e) => {
+            push_error(&mut report.errors, e);
+            return;
+        }
+    }
+
+    let mut reader = match record_file::Reader::open(path).await {
+        Ok(r) => r,
+        Err(e) => {
+            push_error(
+                &mut report.errors,
+                format!("failed to open record file: {e}"),
+            );
+            return;
+        }
+    };
+    loop {
+        match reader.read_record().await {
+            Ok(_) => report.checked += 1,
+            Err(Error::Eof) => break,
+            Err(Error::RecordFileHashCheckFailed {
+                crc,
+                crc_calculated,
+                record,
+            }) => {
+                // The reader continues to find the next record after the damaged one.
+                report.checked += 1;
+                report.damaged += 1;
+                push_error(
+                    &mut report.errors,
+                    format!(
+                        "record at {} crc not match, expected: {crc}, calculated: {crc_calculated}",
+                        record.pos
+                    ),
+                );
+            }
+            Err(Error::RecordFileInvalidDataSize { pos, len }) => {
+                report.damaged += 1;
+                push_error(
+                    &mut report.errors,
+                    format!("record at {pos} with data size {len} exceeds the file size"),
+                );
+                break;
+            }
+            Err(e) => {
+                report.damaged += 1;
+                push_error(
+                    &mut report.errors,
+                    format!("failed to read record at {}: {e}", reader.pos()),
+                );
+                break;
+            }
+        }
+    }
+}
+
+async fn verify_tsm_file(path: &Path, report: &mut FileVerifyReport) {
+    let (file, meta) = match read_tsm_meta(path).await {
+        Ok(res) => res,
+        Err(e) => {
+            push_error(&mut report.errors, e);
+            return;
+        }
+    };
+
+    for (series_id, chunk) in meta.chunk() {
+        for column_group in chunk.column_group().values() {
+            for spec in column_group.pages() {
+                report.checked += 1;
+                if let Err(e) = read_page(&file, spec).await {
+                    report.damaged += 1;
+                    push_error(
+                        &mut report.errors,
+                        format!(
+                            "series {series_id} column group {}: {e}",
+                            column_group.column_group_id()
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::verify;
+    use crate::record_file::{RecordDataType, RecordDataVersion, Writer};
+    use crate::tools::FileKind;
+
+    async fn write_wal_records(path: &Path, records: &[&[u8]]) {
+        let mut writer = Writer::open(path, RecordDataType::Wal).await.unwrap();
+        for data in records {
+            writer
+                .write_record(
+                    RecordDataVersion::V1.into(),
+                    RecordDataType::Wal.into(),
+                    &[data],
+                )
+                .await
+                .unwrap();
+        }
+        writer.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_record_file() {
+        let dir = "/tmp/test/tools/verify_record_file";
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).unwrap();
+        let path = Path::new(dir).join("_000001.wal");
+        write_wal_records(&path, &[b"hello", b"world", b"cnosdb"]).await;
+        std::fs::write(Path::new(dir).join("README"), b"not a storage file").unwrap();
+
+        let report = verify(dir).await.unwrap();
+        assert_eq!(report.checked_files, 1);
+        assert_eq!(report.damaged_files, 0);
+        assert_eq!(report.skipped_files, 1);
+        assert_eq!(report.files[0].kind, FileKind::Wal);
+        assert_eq!(report.files[0].checked, 3);
+
+        // Damage the data of the second record.
+        let mut bytes = std::fs::read(&path).unwrap();
+        let pos = bytes.windows(5).position(|w| w == b"world").unwrap();
+        bytes[pos] = b'W';
+        std::fs::write(&path, bytes).unwrap();
+
+        let report = verify(dir).await.unwrap();
+        assert_eq!(report.damaged_files, 1);
+        assert!(!report.files[0].ok);
+        assert_eq!(report.files[0].damaged, 1);
+    }
+}
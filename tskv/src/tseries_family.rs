@@ -2,6 +2,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use arrow_array::RecordBatch;
 use cache::{AsyncCache, ShardedAsyncCache};
@@ -11,6 +12,7 @@ use metrics::metric_register::MetricsRegister;
 use models::meta_data::VnodeStatus;
 use models::predicate::domain::{TimeRange, TimeRanges};
 use models::schema::{split_owner, TableColumn};
+use models::utils::now_timestamp_nanos;
 use models::{ColumnId, FieldId, SeriesId, SeriesKey, Timestamp};
 use parking_lot::RwLock;
 use snafu::ResultExt as _;
@@ -26,7 +28,9 @@ use crate::file_system::file_manager;
 use crate::file_utils::{self, make_delta_file, make_tsm_file};
 use crate::index::ts_index::TSIndex;
 use crate::kv_option::{CacheOptions, StorageOptions};
+use crate::last_value_cache::LastValueCache;
 use crate::memcache::{MemCache, MemCacheStatistics, RowGroup};
+use crate::schema::schemas::DBschemas;
 use crate::summary::{CompactMeta, VersionEdit};
 use crate::tsm::page::PageMeta;
 use crate::tsm::reader::TsmReader;
@@ -39,11 +43,14 @@ pub struct ColumnFile {
     file_id: ColumnFileId,
     level: LevelId,
     is_delta: bool,
+    is_late: bool,
     time_range: TimeRange,
     size: u64,
     series_id_filter: Arc<BloomFilter>,
     deleted: AtomicBool,
     compacting: AtomicBool,
+    /// The wall-clock time in nanoseconds when the file is written.
+    create_time: Timestamp,
 
     path: PathBuf,
     tsm_reader_cache: Weak<ShardedAsyncCache<String, Arc<TsmReader>>>,
@@ -60,11 +67,13 @@ impl ColumnFile {
             file_id: meta.file_id,
             level: meta.level,
             is_delta: meta.is_delta,
+            is_late: meta.is_late,
             time_range: TimeRange::new(meta.min_ts, meta.max_ts),
             size: meta.file_size,
             series_id_filter,
             deleted: AtomicBool::new(false),
             compacting: AtomicBool::new(false),
+            create_time: meta.create_time,
             path: path.as_ref().into(),
             tsm_reader_cache,
         }
@@ -82,6 +91,19 @@ impl ColumnFile {
         self.is_delta
     }
 
+    pub fn is_late(&self) -> bool {
+        self.is_late
+    }
+
+    pub fn create_time(&self) -> Timestamp {
+        self.create_time
+    }
+
+    /// Returns true if the file was written at least `duration` ago.
+    pub fn is_older_than(&self, duration: Duration) -> bool {
+        now_timestamp_nanos().saturating_sub(self.create_time) >= duration.as_nanos() as i64
+    }
+
     pub fn time_range(&self) -> &TimeRange {
         &self.time_range
     }
@@ -224,16 +246,23 @@ impl ColumnFile {
             file_id,
             level,
             is_delta,
+            is_late: false,
             time_range,
             size,
             series_id_filter: Arc::new(BloomFilter::default()),
             deleted: AtomicBool::new(false),
             compacting: AtomicBool::new(false),
+            create_time: now_timestamp_nanos(),
             path: path.as_ref().into(),
             tsm_reader_cache: Weak::new(),
         }
     }
 
+    pub fn set_late(&mut self, create_time: Timestamp) {
+        self.is_late = true;
+        self.create_time = create_time;
+    }
+
     pub fn set_field_id_filter(&mut self, field_id_filter: Arc<BloomFilter>) {
         self.series_id_filter = field_id_filter;
     }
@@ -504,14 +533,11 @@ impl Version {
         let path = path.as_ref().display().to_string();
         let tsm_reader = match self.tsm_reader_cache.get(&path).await {
             Some(val) => val,
-            None => match self.tsm_reader_cache.get(&path).await {
-                Some(val) => val,
-                None => {
-                    let tsm_reader = Arc::new(TsmReader::open(&path).await?);
-                    self.tsm_reader_cache.insert(path, tsm_reader.clone()).await;
-                    tsm_reader
-                }
-            },
+            None => {
+                let tsm_reader = Arc::new(TsmReader::open(&path).await?);
+                self.tsm_reader_cache.insert(path, tsm_reader.clone()).await;
+                tsm_reader
+            }
         };
         Ok(tsm_reader)
     }
@@ -638,6 +664,7 @@ pub struct SuperVersion {
     pub caches: CacheGroup,
     pub version: Arc<Version>,
     pub version_number: u64,
+    pub last_values: Arc<LastValueCache>,
 }
 
 impl SuperVersion {
@@ -647,6 +674,7 @@ impl SuperVersion {
         caches: CacheGroup,
         version: Arc<Version>,
         version_number: u64,
+        last_values: Arc<LastValueCache>,
     ) -> Self {
         Self {
             ts_family_id,
@@ -654,6 +682,7 @@ impl SuperVersion {
             caches,
             version,
             version_number,
+            last_values,
         }
     }
 
@@ -807,6 +836,7 @@ impl TsfFactory {
         )));
         let tsf_metrics =
             TsfMetrics::new(&self.metrics_register, self.database.as_str(), tf_id as u64);
+        let last_values = Arc::new(LastValueCache::new(tf_id, &self.memory_pool));
         let super_version = Arc::new(SuperVersion::new(
             tf_id,
             self.options.storage.clone(),
@@ -816,6 +846,7 @@ impl TsfFactory {
             },
             version.clone(),
             0,
+            last_values.clone(),
         ));
 
         TseriesFamily {
@@ -831,6 +862,7 @@ impl TsfFactory {
             memory_pool: self.memory_pool.clone(),
             tsf_metrics,
             status: VnodeStatus::Running,
+            last_values,
         }
     }
 }
@@ -849,6 +881,7 @@ pub struct TseriesFamily {
     memory_pool: MemoryPoolRef,
     tsf_metrics: TsfMetrics,
     status: VnodeStatus,
+    last_values: Arc<LastValueCache>,
 }
 
 impl TseriesFamily {
@@ -866,6 +899,7 @@ impl TseriesFamily {
         register: &Arc<MetricsRegister>,
     ) -> Self {
         let mm = Arc::new(RwLock::new(cache));
+        let last_values = Arc::new(LastValueCache::new(tf_id, &memory_pool));
 
         Self {
             tf_id,
@@ -881,6 +915,7 @@ impl TseriesFamily {
                 },
                 version.clone(),
                 0,
+                last_values.clone(),
             )),
             super_version_id: AtomicU64::new(0),
             cache_opt,
@@ -889,6 +924,7 @@ impl TseriesFamily {
             memory_pool,
             tsf_metrics: TsfMetrics::new(register, tenant_database.as_str(), tf_id as u64),
             status: VnodeStatus::Running,
+            last_values,
         }
     }
 
@@ -905,6 +941,7 @@ impl TseriesFamily {
             },
             version,
             self.super_version_id.load(Ordering::SeqCst),
+            self.last_values.clone(),
         ))
     }
 
@@ -1007,7 +1044,7 @@ impl TseriesFamily {
     pub fn put_points(
         &self,
         seq: u64,
-        points: HashMap<SeriesId, (SeriesKey, RowGroup)>,
+        points: HashMap<SeriesId, (SeriesKey, Vec<RowGroup>)>,
     ) -> Result<u64> {
         if self.status == VnodeStatus::Copying {
             return Err(CommonError {
@@ -1015,14 +1052,53 @@ impl TseriesFamily {
             });
         }
         let mut res = 0;
-        for (sid, (series_key, group)) in points {
-            let mem = self.mut_cache.read();
-            res += group.rows.get_ref_rows().len();
-            mem.write_group(sid, series_key, seq, group)?;
+        for (sid, (series_key, groups)) in points {
+            for group in groups {
+                self.last_values
+                    .update(sid, &group, || self.max_ts_in_files(sid));
+                let mem = self.mut_cache.read();
+                res += group.rows.get_ref_rows().len();
+                mem.write_group(sid, series_key.clone(), seq, group)?;
+            }
         }
         Ok(res as u64)
     }
 
+    /// The max timestamp of the files which may contain the series,
+    /// `Timestamp::MIN` if there is no such file.
+    fn max_ts_in_files(&self, sid: SeriesId) -> Timestamp {
+        self.version()
+            .levels_info()
+            .iter()
+            .flat_map(|level| level.files.iter())
+            .filter(|f| f.maybe_contains_series_id(sid))
+            .map(|f| f.time_range().max_ts)
+            .max()
+            .unwrap_or(Timestamp::MIN)
+    }
+
+    /// Forget the last values of the series whose points at or before `max_ts`
+    /// are not written by `put_points`.
+    pub fn invalidate_last_values(&self, sids: &[SeriesId], max_ts: Timestamp) {
+        self.last_values.invalidate_until(sids, max_ts);
+    }
+
+    /// Recover the last value cache from the tsm files of current version in background,
+    /// the series are read from the data until they are recovered.
+    pub fn recover_last_values(&self, schemas: Arc<DBschemas>) {
+        let tf_id = self.tf_id;
+        let last_values = self.last_values.clone();
+        let version = self.version();
+        tokio::spawn(async move {
+            if let Err(e) = last_values.recover(&version, &schemas).await {
+                error!(
+                    "failed to recover last value cache of vnode {}, {:?}",
+                    tf_id, e
+                );
+            }
+        });
+    }
+
     pub async fn check_to_flush(&mut self, sender: Sender<FlushReq>) {
         if self.mut_cache.read().is_full() {
             info!(
@@ -1072,6 +1148,7 @@ impl TseriesFamily {
     }
 
     pub fn delete_series(&self, sids: &[SeriesId], time_range: &TimeRange) {
+        self.last_values.invalidate(sids);
         self.mut_cache.read().delete_series(sids, time_range);
         for memcache in self.immut_cache.iter() {
             memcache.read().delete_series(sids, time_range);
@@ -1079,6 +1156,7 @@ impl TseriesFamily {
     }
 
     pub fn delete_series_by_time_ranges(&self, sids: &[SeriesId], time_ranges: &TimeRanges) {
+        self.last_values.invalidate(sids);
         self.mut_cache
             .read()
             .delete_series_by_time_ranges(sids, time_ranges);
@@ -1365,6 +1443,8 @@ pub mod test_tseries_family {
                 min_ts: 3051,
                 max_ts: 3150,
                 is_delta: false,
+                is_late: false,
+                create_time: 0,
             },
             3100,
         );
@@ -1458,6 +1538,8 @@ pub mod test_tseries_family {
                 min_ts: 3001,
                 max_ts: 3150,
                 is_delta: false,
+                is_late: false,
+                create_time: 0,
             },
             3150,
         );
@@ -1471,6 +1553,8 @@ pub mod test_tseries_family {
                 min_ts: 1,
                 max_ts: 2000,
                 is_delta: false,
+                is_late: false,
+                create_time: 0,
             },
             3150,
         );
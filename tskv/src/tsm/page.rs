@@ -4,6 +4,7 @@ use std::sync::Arc;
 use arrow::datatypes::{Field, Schema, SchemaRef};
 use datafusion::parquet::data_type::AsBytes;
 use models::field_value::FieldVal;
+use models::gis::bbox::BoundingBox;
 use models::predicate::domain::TimeRange;
 use models::schema::{PhysicalCType, TableColumn, TskvTableSchema, TskvTableSchemaRef};
 use models::{PhysicalDType, SeriesId, SeriesKey};
@@ -19,7 +20,7 @@ use crate::tsm::codec::{
 };
 use crate::tsm::writer::Column;
 use crate::tsm::ColumnGroupID;
-use crate::Error;
+use crate::{Error, LevelId};
 
 #[derive(Debug)]
 pub struct Page {
@@ -219,6 +220,9 @@ pub enum PageStatistics {
     I64(ValueStatistics<i64>),
     U64(ValueStatistics<u64>),
     Bytes(ValueStatistics<Vec<u8>>),
+    /// Statistics of the WKT texts of a geometry column and the bounding box of the
+    /// geometries, the bounding box is `None` if it's unknown.
+    Geometry(ValueStatistics<Vec<u8>>, Option<BoundingBox>),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -524,6 +528,7 @@ pub type TableId = u64;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChunkGroupWriteSpec {
     // pub(crate) id: TableId,
+    #[serde(with = "models::schema::tsm_layout")]
     pub(crate) table_schema: Arc<TskvTableSchema>,
     pub(crate) chunk_group_offset: u64,
     pub(crate) chunk_group_size: usize,
@@ -569,10 +574,31 @@ impl ChunkGroupWriteSpec {
     }
 }
 
+/// The footer version of tsm files written by this version, the chunk group meta
+/// of files before version 3 has no `is_late` flag and before version 4 no `level`.
+pub const FOOTER_VERSION: u8 = 4;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChunkGroupMeta {
     // table name -> chunk group meta
     tables: BTreeMap<String, ChunkGroupWriteSpec>,
+    /// Whether the file only holds late points, since footer version 3.
+    is_late: bool,
+    /// The level the file is written to, since footer version 4.
+    level: LevelId,
+}
+
+/// The chunk group meta of footer version 2 and before.
+#[derive(Deserialize)]
+struct ChunkGroupMetaV2 {
+    tables: BTreeMap<String, ChunkGroupWriteSpec>,
+}
+
+/// The chunk group meta of footer version 3.
+#[derive(Deserialize)]
+struct ChunkGroupMetaV3 {
+    tables: BTreeMap<String, ChunkGroupWriteSpec>,
+    is_late: bool,
 }
 
 impl Default for ChunkGroupMeta {
@@ -585,14 +611,39 @@ impl ChunkGroupMeta {
     pub fn new() -> Self {
         Self {
             tables: BTreeMap::new(),
+            is_late: false,
+            level: 0,
         }
     }
     pub fn serialize(&self) -> Result<Vec<u8>> {
         bincode::serialize(&self).map_err(|e| Error::Serialize { source: e.into() })
     }
 
-    pub fn deserialize(bytes: &[u8]) -> Result<Self> {
-        bincode::deserialize(bytes).map_err(|e| Error::Deserialize { source: e.into() })
+    /// Deserializes the chunk group meta written with the footer `version`, the
+    /// level of files before version 4 is 0.
+    pub fn deserialize(bytes: &[u8], version: u8) -> Result<Self> {
+        let deserialize_err = |e: bincode::Error| Error::Deserialize { source: e.into() };
+        match version {
+            v if v < 3 => {
+                let meta: ChunkGroupMetaV2 =
+                    bincode::deserialize(bytes).map_err(deserialize_err)?;
+                Ok(Self {
+                    tables: meta.tables,
+                    is_late: false,
+                    level: 0,
+                })
+            }
+            3 => {
+                let meta: ChunkGroupMetaV3 =
+                    bincode::deserialize(bytes).map_err(deserialize_err)?;
+                Ok(Self {
+                    tables: meta.tables,
+                    is_late: meta.is_late,
+                    level: 0,
+                })
+            }
+            _ => bincode::deserialize(bytes).map_err(deserialize_err),
+        }
     }
 
     pub fn push(&mut self, table: ChunkGroupWriteSpec) {
@@ -616,6 +667,22 @@ impl ChunkGroupMeta {
     pub fn table_schema(&self, table_name: &str) -> Option<Arc<TskvTableSchema>> {
         self.tables.get(table_name).map(|t| t.table_schema.clone())
     }
+
+    pub fn is_late(&self) -> bool {
+        self.is_late
+    }
+
+    pub fn set_late(&mut self, is_late: bool) {
+        self.is_late = is_late;
+    }
+
+    pub fn level(&self) -> LevelId {
+        self.level
+    }
+
+    pub fn set_level(&mut self, level: LevelId) {
+        self.level = level;
+    }
 }
 
 // pub const FOOTER_SIZE: i64 = ;
@@ -339,7 +339,7 @@ pub async fn read_chunk_group_meta(
     let pos = footer.table.chunk_group_offset();
     let mut buffer = vec![0u8; footer.table.chunk_group_size()];
     reader.read_at(pos, &mut buffer).await?; // read chunk group meta
-    let specs = ChunkGroupMeta::deserialize(&buffer)?;
+    let specs = ChunkGroupMeta::deserialize(&buffer, footer.version())?;
     Ok(specs)
 }
 
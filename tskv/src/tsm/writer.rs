@@ -7,9 +7,10 @@ use std::sync::Arc;
 use minivec::MiniVec;
 use models::codec::Encoding;
 use models::field_value::FieldVal;
+use models::gis::bbox::BoundingBox;
 use models::predicate::domain::TimeRange;
-use models::schema::{PhysicalCType, TableColumn, TskvTableSchemaRef};
-use models::{ColumnId, PhysicalDType, SeriesId, SeriesKey};
+use models::schema::{ColumnType, PhysicalCType, TableColumn, TskvTableSchemaRef};
+use models::{ColumnId, PhysicalDType, SeriesId, SeriesKey, Timestamp, ValueType};
 use num_traits::ToBytes;
 use snafu::ResultExt;
 use utils::bitset::BitSet;
@@ -27,9 +28,10 @@ use crate::tsm::codec::{
 use crate::tsm::page::{
     Chunk, ChunkGroup, ChunkGroupMeta, ChunkGroupWriteSpec, ChunkStatics, ChunkWriteSpec,
     ColumnGroup, Footer, Page, PageMeta, PageStatistics, PageWriteSpec, SeriesMeta, TableMeta,
+    FOOTER_VERSION,
 };
 use crate::tsm::{TsmTombstone, TsmWriteData, BLOOM_FILTER_BITS};
-use crate::{Error, Result};
+use crate::{Error, LevelId, Result};
 
 // #[derive(Debug, Clone)]
 // pub enum Array {
@@ -354,12 +356,18 @@ impl Column {
                 encoder
                     .encode(&target_array, &mut buf)
                     .map_err(|e| Error::Encode { source: e })?;
-                PageStatistics::Bytes(ValueStatistics::new(
+                let statistics = ValueStatistics::new(
                     Some(min.as_bytes().to_vec()),
                     Some(max.as_bytes().to_vec()),
                     None,
                     null_count,
-                ))
+                );
+                match desc.column_type {
+                    ColumnType::Field(ValueType::Geometry(_)) => {
+                        PageStatistics::Geometry(statistics, geometries_bbox(&target_array))
+                    }
+                    _ => PageStatistics::Bytes(statistics),
+                }
             }
             ColumnData::Bool(array, min, max) => {
                 let target_array = array
@@ -444,6 +452,17 @@ impl Column {
     }
 }
 
+/// Returns the bounding box of the WKT geometries, `None` if any of them can't be parsed.
+fn geometries_bbox(values: &[&[u8]]) -> Option<BoundingBox> {
+    let mut bbox: Option<BoundingBox> = None;
+    for value in values {
+        let wkt = std::str::from_utf8(value).ok()?;
+        let value_bbox = BoundingBox::from_wkt(wkt)?;
+        bbox = Some(bbox.map_or(value_bbox, |b| b.union(&value_bbox)));
+    }
+    bbox
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ColumnData {
     ///   array   min, max
@@ -702,6 +721,22 @@ impl DataBlock {
         Ok(())
     }
 
+    /// The last non-null value of each field column, with its timestamp.
+    pub fn last_values(&self) -> Vec<(ColumnId, Timestamp, FieldVal)> {
+        let mut values = Vec::with_capacity(self.cols.len());
+        for (column, desc) in self.cols.iter().zip(self.cols_desc.iter()) {
+            for index in (0..column.len()).rev() {
+                if let (Some(FieldVal::Integer(ts)), Some(value)) =
+                    (self.ts.get(index), column.get(index))
+                {
+                    values.push((desc.id, ts, value));
+                    break;
+                }
+            }
+        }
+        values
+    }
+
     pub fn column(&self, id: ColumnId) -> Option<&Column> {
         for (index, col_des) in self.cols_desc.iter().enumerate() {
             if col_des.id == id {
@@ -808,9 +843,9 @@ impl Default for WriteOptions {
     }
 }
 
-const HEADER_LEN: u64 = 5;
-const TSM_MAGIC: [u8; 4] = 0x12CDA16_u32.to_be_bytes();
-const VERSION: [u8; 1] = [1];
+pub(crate) const HEADER_LEN: u64 = 5;
+pub(crate) const TSM_MAGIC: [u8; 4] = 0x12CDA16_u32.to_be_bytes();
+pub(crate) const VERSION: [u8; 1] = [1];
 
 pub struct TsmWriter {
     file_id: u64,
@@ -851,7 +886,8 @@ impl TsmWriter {
             make_tsm_file(path_buf, file_id)
         };
         let file_cursor = file_manager::create_file(&file_path).await?;
-        let writer = Self::new(file_path, file_cursor.into(), file_id, max_size);
+        let mut writer = Self::new(file_path, file_cursor.into(), file_id, max_size);
+        writer.set_level(if is_delta { 0 } else { 1 });
         Ok(writer)
     }
     fn new(path: PathBuf, writer: FileCursor, file_id: u64, max_size: u64) -> Self {
@@ -902,6 +938,17 @@ impl TsmWriter {
         self.state == State::Finished
     }
 
+    /// Marks the file as a late delta file, see `summary::CompactMeta::is_late`.
+    pub fn set_late(&mut self, is_late: bool) {
+        self.chunk_group_specs.set_late(is_late);
+    }
+
+    /// Sets the level recorded in the file, delta files are written to level 0 and tsm
+    /// files to level 1 by default.
+    pub fn set_level(&mut self, level: LevelId) {
+        self.chunk_group_specs.set_level(level);
+    }
+
     pub async fn write_header(&mut self) -> Result<usize> {
         let size = self
             .writer
@@ -953,7 +1000,7 @@ impl TsmWriter {
         self.size += chunk_group_specs_size;
         let time_range = self.chunk_group_specs.time_range();
         let footer = Footer {
-            version: 2_u8,
+            version: FOOTER_VERSION,
             time_range,
             table: TableMeta::new(chunk_group_specs_offset, chunk_group_specs_size),
             series,
@@ -1188,12 +1235,16 @@ mod test {
     use std::sync::Arc;
 
     use arrow::datatypes::TimeUnit;
+    use minivec::MiniVec;
     use models::codec::Encoding;
     use models::field_value::FieldVal;
+    use models::gis::bbox::BoundingBox;
+    use models::gis::data_type::{Geometry, GeometryType};
     use models::predicate::domain::TimeRange;
     use models::schema::{ColumnType, PhysicalCType, TableColumn, TskvTableSchema};
     use models::{PhysicalDType, SeriesKey, ValueType};
 
+    use crate::tsm::page::PageStatistics;
     use crate::tsm::reader::TsmReader;
     use crate::tsm::writer::{Column, DataBlock, TsmWriter};
 
@@ -1213,6 +1264,40 @@ mod test {
         col
     }
 
+    #[test]
+    fn test_geometry_page_bbox() {
+        let desc = TableColumn::new(
+            1,
+            "geo".to_string(),
+            ColumnType::Field(ValueType::Geometry(Geometry::new_with_srid(
+                GeometryType::Point,
+                0,
+            ))),
+            Encoding::default(),
+        );
+        let mut col = Column::empty(PhysicalCType::Field(PhysicalDType::String)).unwrap();
+        for wkt in ["POINT(1 5)", "POINT(-2 3)", "LINESTRING(0 0, 4 1)"] {
+            col.push(Some(FieldVal::Bytes(MiniVec::from(wkt.as_bytes()))));
+        }
+        col.push(None);
+
+        let page = col.col_to_page(&desc).unwrap();
+        match &page.meta().statistics {
+            PageStatistics::Geometry(_, bbox) => {
+                assert_eq!(*bbox, Some(BoundingBox::new(-2.0, 0.0, 4.0, 5.0)))
+            }
+            s => panic!("expected geometry statistics, got {s:?}"),
+        }
+
+        let mut col = Column::empty(PhysicalCType::Field(PhysicalDType::String)).unwrap();
+        col.push(Some(FieldVal::Bytes(MiniVec::from("POINT EMPTY"))));
+        let page = col.col_to_page(&desc).unwrap();
+        assert!(matches!(
+            page.meta().statistics,
+            PageStatistics::Geometry(_, None)
+        ));
+    }
+
     #[tokio::test]
     async fn test_write_and_read() {
         let schema = TskvTableSchema::new(
@@ -85,7 +85,7 @@ impl VersionSet {
             )));
 
             let tf_id = ver.tf_id();
-            db.write().await.open_tsfamily(ver);
+            db.write().await.open_tsfamily(ver).await;
             db.write().await.get_ts_index_or_add(tf_id).await?;
         }
 
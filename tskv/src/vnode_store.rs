@@ -6,6 +6,7 @@ use models::meta_data::VnodeId;
 use models::predicate::domain::{ResolvedPredicate, TimeRange, TimeRanges};
 use models::schema::Precision;
 use models::{ColumnId, SeriesId, SeriesKey};
+use prost::Message;
 use protos::kv_service::{raft_write_command, WritePointsResponse, *};
 use snafu::ResultExt;
 use tokio::sync::RwLock;
@@ -15,6 +16,7 @@ use crate::compaction::run_flush_memtable_job;
 use crate::database::Database;
 use crate::error::Result;
 use crate::file_system::file_info;
+use crate::idempotency::{AppliedWrite, IdempotencyKeys};
 use crate::index::ts_index::TSIndex;
 use crate::schema::error::SchemaError;
 use crate::tseries_family::TseriesFamily;
@@ -27,9 +29,69 @@ pub struct VnodeStorage {
     pub db: Arc<RwLock<Database>>,
     pub ts_index: Arc<TSIndex>,
     pub ts_family: Arc<RwLock<TseriesFamily>>,
+    pub idempotency_keys: Arc<parking_lot::Mutex<IdempotencyKeys>>,
 }
 
 impl VnodeStorage {
+    /// Apply the raft write command. The command is skipped and the result of the applied
+    /// command is returned if its idempotency key has been applied on the vnode with the same
+    /// command, the command reusing the idempotency key of another command is rejected.
+    pub async fn apply_request(
+        &self,
+        ctx: &replication::ApplyContext,
+        mut request: RaftWriteCommand,
+    ) -> Result<Vec<u8>> {
+        let key = std::mem::take(&mut request.idempotency_key);
+        if key.is_empty() {
+            return match request.command {
+                Some(command) => self.apply(ctx, command).await,
+                None => Ok(vec![]),
+            };
+        }
+
+        let (checksum, write_time) = write_checksum(&mut request);
+        let applied = self
+            .idempotency_keys
+            .lock()
+            .get(&key, self.id, write_time)
+            .map(|applied| (applied.checksum, applied.result.clone()));
+        match applied {
+            Some((applied, result)) if applied == checksum => {
+                debug!(
+                    "Vnode {} skip applied write with idempotency key {}",
+                    self.id, key
+                );
+                return Ok(result);
+            }
+            Some(_) => {
+                // the command was rejected when it was applied, so nothing to recover
+                if ctx.apply_type == replication::APPLY_TYPE_WAL {
+                    return Ok(vec![]);
+                }
+                return Err(Error::CommonError {
+                    reason: format!("idempotency key {} is used by another write", key),
+                });
+            }
+            None => {}
+        }
+
+        let result = match request.command {
+            Some(command) => self.apply(ctx, command).await?,
+            None => vec![],
+        };
+        self.idempotency_keys.lock().insert(
+            key,
+            self.id,
+            AppliedWrite {
+                checksum,
+                write_time,
+                result: result.clone(),
+            },
+        );
+
+        Ok(result)
+    }
+
     pub async fn apply(
         &self,
         ctx: &replication::ApplyContext,
@@ -38,7 +100,10 @@ impl VnodeStorage {
         match command {
             raft_write_command::Command::WriteData(cmd) => {
                 let precision = Precision::from(cmd.precision as u8);
-                if let Err(err) = self.write(ctx, cmd.data, precision, None).await {
+                if let Err(err) = self
+                    .write(ctx, cmd.data, precision, cmd.write_time, None)
+                    .await
+                {
                     if ctx.apply_type == replication::APPLY_TYPE_WAL {
                         info!("recover: write points: {}", err);
                     } else {
@@ -74,14 +139,78 @@ impl VnodeStorage {
                 self.delete_from_table(&cmd).await?;
                 Ok(vec![])
             }
+
+            raft_write_command::Command::ImportSeries(cmd) => {
+                self.import_series(&cmd.series_keys).await?;
+                Ok(vec![])
+            }
+
+            raft_write_command::Command::ImportFiles(cmd) => {
+                self.import_files(ctx, &cmd.import_id, &cmd.files).await?;
+                Ok(vec![])
+            }
+
+            raft_write_command::Command::AbortImport(cmd) => {
+                crate::bulk::abort_import(self, &cmd.import_id).await?;
+                Ok(vec![])
+            }
         }
     }
 
+    /// Add the series of a bulk import to the index, the series are added in the order
+    /// of the raft log, so they get the same ids on all the replicas.
+    async fn import_series(&self, series_keys: &[Vec<u8>]) -> Result<()> {
+        let series_keys = series_keys
+            .iter()
+            .map(|key| SeriesKey::decode(key))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::CommonError {
+                reason: format!("decode series key of import failed: {e}"),
+            })?;
+        self.ts_index.add_series_if_not_exists(series_keys).await?;
+
+        Ok(())
+    }
+
+    /// Add the files staged by a bulk import to the vnode. The memcache is flushed
+    /// before the import, so the files of the import are added to the version with the
+    /// index of the raft entry, and the import is skipped in WAL recovery if the version
+    /// is already after it.
+    async fn import_files(
+        &self,
+        ctx: &replication::ApplyContext,
+        import_id: &str,
+        files: &[ImportFile],
+    ) -> Result<()> {
+        if ctx.apply_type == replication::APPLY_TYPE_WAL
+            && ctx.index <= self.ts_family.read().await.version().last_seq()
+        {
+            debug!(
+                "Vnode {} skip applied import {} at {}",
+                self.id, import_id, ctx.index
+            );
+            return Ok(());
+        }
+
+        let flush_req = {
+            let mut vnode_wlock = self.ts_family.write().await;
+            vnode_wlock.switch_to_immutable();
+
+            vnode_wlock.build_flush_req(true)
+        };
+        if let Some(flush_req) = flush_req {
+            run_flush_memtable_job(flush_req, self.ctx.clone(), false).await?;
+        }
+
+        crate::bulk::apply_import(self, import_id, files, ctx.index).await
+    }
+
     async fn write(
         &self,
         ctx: &replication::ApplyContext,
         points: Vec<u8>,
         precision: Precision,
+        write_time: i64,
         span_ctx: Option<&SpanContext>,
     ) -> Result<WritePointsResponse> {
         let span_recorder = SpanRecorder::new(span_ctx.child_span("tskv engine write cache"));
@@ -101,6 +230,7 @@ impl VnodeStorage {
                 .await
                 .build_write_group(
                     precision,
+                    write_time,
                     tables,
                     self.ts_index.clone(),
                     recover_from_wal,
@@ -391,6 +521,7 @@ impl VnodeStorage {
             files_info,
             node_id: opt.node_id,
             version_edit: ve_snapshot,
+            idempotency_keys: self.idempotency_keys.lock().vnode_keys(vnode_id),
         };
         info!("Snapshot: created snapshot: {snapshot:?}");
 
@@ -438,10 +569,16 @@ impl VnodeStorage {
 
         self.ts_index = ts_index;
         self.ts_family = ts_family;
+        self.restore_idempotency_keys(snapshot.idempotency_keys);
 
         Ok(())
     }
 
+    /// Replace the idempotency keys of the vnode with the ones saved in a snapshot.
+    pub fn restore_idempotency_keys(&self, keys: Vec<(String, AppliedWrite)>) {
+        self.idempotency_keys.lock().restore(self.id, keys);
+    }
+
     /// Delete the snapshot directory of a Vnode, all snapshots will be deleted.
     pub async fn delete_snapshot(&self) -> Result<()> {
         debug!("Snapshot: delete snapshot on vnode: {}", self.id);
@@ -532,3 +669,18 @@ impl VnodeStorage {
         Ok(())
     }
 }
+
+/// Returns the checksum and the write time of the raft write command. The write time is
+/// not in the checksum, since the retries of a write take new write times.
+fn write_checksum(request: &mut RaftWriteCommand) -> (u32, i64) {
+    let write_time = match request.command.as_mut() {
+        Some(raft_write_command::Command::WriteData(cmd)) => std::mem::take(&mut cmd.write_time),
+        _ => 0,
+    };
+    let checksum = crc32fast::hash(&request.encode_to_vec());
+    if let Some(raft_write_command::Command::WriteData(cmd)) = request.command.as_mut() {
+        cmd.write_time = write_time;
+    }
+
+    (checksum, write_time)
+}
@@ -320,6 +320,22 @@ impl VnodeWal {
     pub fn sync_interval(&self) -> std::time::Duration {
         self.config.sync_interval
     }
+
+    /// Returns the count and the total size of the wal files in the directory.
+    pub fn wal_dir_usage(wal_dir: &Path) -> (u64, u64) {
+        let mut count = 0;
+        let mut size = 0;
+        for file_name in file_manager::list_file_names(wal_dir) {
+            if file_utils::get_wal_file_id(&file_name).is_err() {
+                continue;
+            }
+            if let Ok(meta) = std::fs::metadata(wal_dir.join(&file_name)) {
+                count += 1;
+                size += meta.len();
+            }
+        }
+        (count, size)
+    }
 }
 
 pub struct WalEntryCodec {
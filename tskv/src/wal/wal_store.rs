@@ -400,9 +400,7 @@ impl RaftEntryStorageInner {
                                 };
 
                                 let request = parse_prost_bytes::<RaftWriteCommand>(req).unwrap();
-                                if let Some(command) = request.command {
-                                    vode_store.apply(&ctx, command).await.unwrap();
-                                }
+                                vode_store.apply_request(&ctx, request).await.unwrap();
                             }
 
                             self.mark_write_wal(entry, wal_id, r.pos);
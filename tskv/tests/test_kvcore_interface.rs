@@ -10,14 +10,18 @@ mod tests {
     use metrics::metric_register::MetricsRegister;
     use models::meta_data::VnodeId;
     use models::schema::{make_owner, Precision, TenantOptions};
-    use protos::kv_service::{raft_write_command, WriteDataRequest};
+    use protos::kv_service::{
+        raft_write_command, AbortImportRequest, ImportFilesRequest, ImportSeriesRequest,
+        RaftWriteCommand, WriteDataRequest,
+    };
     use protos::models_helper;
     use serial_test::serial;
     use tokio::runtime;
     use tokio::runtime::Runtime;
     use trace::{debug, error, info, init_default_global_tracing, warn};
     use tskv::file_system::file_manager;
-    use tskv::{file_utils, kv_option, Engine, TsKv};
+    use tskv::vnode_store::VnodeStorage;
+    use tskv::{bulk, file_utils, kv_option, Engine, EngineRef, TsKv, VnodeSnapshot};
 
     /// Initializes a TsKv instance in specified directory, with an optional runtime,
     /// returns the TsKv and runtime.
@@ -118,6 +122,7 @@ mod tests {
         let request = WriteDataRequest {
             data: points,
             precision: Precision::NS as u32,
+            write_time: 0,
         };
 
         tskv_write(rt.clone(), &tskv, "cnosdb", "public", 0, 1, request);
@@ -155,6 +160,7 @@ mod tests {
         let request = WriteDataRequest {
             data: points,
             precision: Precision::NS as u32,
+            write_time: 0,
         };
 
         tskv_write(rt.clone(), &tskv, "cnosdb", "db", 0, 1, request.clone());
@@ -201,6 +207,7 @@ mod tests {
             let request = WriteDataRequest {
                 data: points,
                 precision: Precision::NS as u32,
+                write_time: 0,
             };
 
             tskv_write(rt.clone(), &tskv, "cnosdb", "public", 0, i, request.clone());
@@ -228,6 +235,7 @@ mod tests {
         let request = WriteDataRequest {
             data: points,
             precision: Precision::NS as u32,
+            write_time: 0,
         };
 
         tskv_write(rt.clone(), &tskv, "cnosdb", database, 0, 1, request.clone());
@@ -294,6 +302,7 @@ mod tests {
         let request = WriteDataRequest {
             data: points,
             precision: Precision::NS as u32,
+            write_time: 0,
         };
 
         tskv_write(rt.clone(), &tskv, "cnosdb", "public", 0, 1, request.clone());
@@ -334,6 +343,7 @@ mod tests {
             let request = WriteDataRequest {
                 data: fbb.finished_data().to_vec(),
                 precision: Precision::NS as u32,
+                write_time: 0,
             };
 
             tskv_write(
@@ -431,6 +441,352 @@ mod tests {
         println!("Leave serial test: test_kvcore_snapshot_create_apply_delete");
     }
 
+    #[test]
+    #[serial]
+    fn test_kvcore_idempotent_write() {
+        println!("Enter serial test: test_kvcore_idempotent_write");
+        let dir = PathBuf::from("/tmp/test/kvcore/kvcore_idempotent_write");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        init_default_global_tracing(dir.join("log"), "tskv.log", "debug");
+        let tenant = "cnosdb";
+        let database = "db_test_idempotent";
+        let table = "tab_test_idempotent";
+        let vnode_id = 21;
+
+        let (runtime, tskv) = get_tskv(&dir, None);
+        let storage_opt = tskv.get_storage_options();
+        let vnode_snapshot_dir = storage_opt.snapshot_dir(&make_owner(tenant, database), vnode_id);
+        let vnode_backup_dir = dir.join("backup_for_test");
+
+        // The coordinator takes a write time for each client request, so a retry of a write
+        // has a later write time.
+        let write_time = 1_700_000_000_000_000_000_i64;
+        let command = |index: u64, key: &str, data: Vec<u8>, write_time: i64| {
+            let ctx = replication::ApplyContext {
+                index,
+                raft_id: vnode_id.into(),
+                apply_type: replication::APPLY_TYPE_WRITE,
+            };
+            let request = RaftWriteCommand {
+                tenant: tenant.to_string(),
+                db_name: database.to_string(),
+                replica_id: 0,
+                idempotency_key: key.to_string(),
+                command: Some(raft_write_command::Command::WriteData(WriteDataRequest {
+                    data,
+                    precision: Precision::NS as u32,
+                    write_time,
+                })),
+            };
+            (ctx, request)
+        };
+        let random_points = || {
+            let mut fbb = flatbuffers::FlatBufferBuilder::new();
+            let points =
+                models_helper::create_random_points_include_delta(&mut fbb, database, table, 20);
+            fbb.finish(points, None);
+            fbb.finished_data().to_vec()
+        };
+        let points = random_points();
+        let other_points = random_points();
+
+        let vnode = runtime
+            .block_on(tskv.open_tsfamily(tenant, database, vnode_id))
+            .unwrap();
+        let (ctx, request) = command(1, "key_1", points.clone(), write_time);
+        let result = runtime
+            .block_on(vnode.apply_request(&ctx, request))
+            .unwrap();
+
+        // The retry is skipped as the key has been applied, and the result is returned again.
+        let (ctx, request) = command(2, "key_1", points.clone(), write_time + 1_000_000_000);
+        let retry_result = runtime
+            .block_on(vnode.apply_request(&ctx, request))
+            .unwrap();
+        assert_eq!(retry_result, result);
+        // The other write reusing the key is rejected.
+        let (ctx, request) = command(3, "key_1", other_points.clone(), write_time);
+        assert!(runtime
+            .block_on(vnode.apply_request(&ctx, request))
+            .is_err());
+
+        // The keys are shared by the vnodes of the database, the writes of a key are kept
+        // for each vnode.
+        let new_vnode_id = 22;
+        let mut new_vnode = runtime
+            .block_on(tskv.open_tsfamily(tenant, database, new_vnode_id))
+            .unwrap();
+        assert!(Arc::ptr_eq(
+            &vnode.idempotency_keys,
+            &new_vnode.idempotency_keys
+        ));
+        let (ctx, request) = command(1, "key_1", other_points.clone(), write_time);
+        runtime
+            .block_on(new_vnode.apply_request(&ctx, request))
+            .unwrap();
+
+        // The keys are restored with the snapshot.
+        sleep_in_runtime(runtime.clone(), Duration::from_secs(3));
+        let vnode_snapshot = runtime.block_on(vnode.create_snapshot()).unwrap();
+        let keys = vnode_snapshot
+            .idempotency_keys
+            .iter()
+            .map(|(key, applied)| (key.as_str(), applied.write_time))
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![("key_1", write_time)]);
+        let vnode_snapshot = VnodeSnapshot::decode(&vnode_snapshot.encode().unwrap()).unwrap();
+        dircpy::copy_dir(
+            vnode_snapshot_dir.join(&vnode_snapshot.snapshot_id),
+            &vnode_backup_dir,
+        )
+        .unwrap();
+
+        runtime
+            .block_on(new_vnode.apply_snapshot(vnode_snapshot, &vnode_backup_dir))
+            .unwrap();
+        let (ctx, request) = command(2, "key_1", points, write_time + 2_000_000_000);
+        runtime
+            .block_on(new_vnode.apply_request(&ctx, request))
+            .unwrap();
+        let (ctx, request) = command(3, "key_1", other_points, write_time);
+        assert!(runtime
+            .block_on(new_vnode.apply_request(&ctx, request))
+            .is_err());
+
+        runtime.block_on(tskv.close());
+        println!("Leave serial test: test_kvcore_idempotent_write");
+    }
+
+    fn apply_command(
+        runtime: &Runtime,
+        vnode: &VnodeStorage,
+        index: u64,
+        command: raft_write_command::Command,
+    ) -> tskv::Result<Vec<u8>> {
+        let ctx = replication::ApplyContext {
+            index,
+            raft_id: vnode.id.into(),
+            apply_type: replication::APPLY_TYPE_WRITE,
+        };
+        runtime.block_on(vnode.apply(&ctx, command))
+    }
+
+    /// Writes random points into the vnode and exports it into `dir`, returns the number
+    /// of the exported rows.
+    fn export_random_points(
+        runtime: Arc<Runtime>,
+        engine: EngineRef,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        dir: &Path,
+    ) -> u64 {
+        let mut fbb = flatbuffers::FlatBufferBuilder::new();
+        let points =
+            models_helper::create_random_points_include_delta(&mut fbb, database, "tab_import", 20);
+        fbb.finish(points, None);
+        let vnode = runtime
+            .block_on(engine.open_tsfamily(tenant, database, vnode_id))
+            .unwrap();
+        let command = raft_write_command::Command::WriteData(WriteDataRequest {
+            data: fbb.finished_data().to_vec(),
+            precision: Precision::NS as u32,
+            write_time: 0,
+        });
+        apply_command(&runtime, &vnode, 1, command).unwrap();
+
+        let report = runtime
+            .block_on(bulk::export_vnode(
+                runtime.clone(),
+                engine,
+                tenant,
+                database,
+                vnode_id,
+                dir,
+            ))
+            .unwrap();
+        assert!(report.rows > 0);
+        report.rows
+    }
+
+    /// Adds the series of the Parquet files under `dir` to the vnodes, and stages the
+    /// import on `leader`, the staged files are copied into the import directory of the
+    /// other vnodes as they are fetched from the leader.
+    fn stage_import(
+        runtime: &Runtime,
+        leader: &VnodeStorage,
+        vnodes: &[&VnodeStorage],
+        dir: &Path,
+        import_id: &str,
+    ) -> ImportFilesRequest {
+        let series_keys = runtime
+            .block_on(bulk::import_series_keys(leader, dir))
+            .unwrap();
+        let command = raft_write_command::Command::ImportSeries(ImportSeriesRequest {
+            series_keys: series_keys.iter().map(|key| key.encode()).collect(),
+        });
+        for vnode in vnodes {
+            apply_command(runtime, vnode, 1, command.clone()).unwrap();
+        }
+
+        let report = runtime
+            .block_on(bulk::stage_import(leader, dir, import_id))
+            .unwrap();
+        assert!(!report.import_files.is_empty());
+        let leader_import_dir = import_dir(leader, import_id);
+        for vnode in vnodes.iter().filter(|v| v.id != leader.id) {
+            dircpy::copy_dir(&leader_import_dir, import_dir(vnode, import_id)).unwrap();
+        }
+
+        ImportFilesRequest {
+            import_id: import_id.to_string(),
+            files: report.import_files,
+        }
+    }
+
+    fn import_dir(vnode: &VnodeStorage, import_id: &str) -> PathBuf {
+        let tsf = vnode.ts_family.blocking_read();
+        tsf.storage_opt()
+            .import_dir(&tsf.tenant_database(), vnode.id, import_id)
+    }
+
+    #[test]
+    #[serial]
+    fn test_kvcore_import_on_follower() {
+        println!("Enter serial test: test_kvcore_import_on_follower");
+        let dir = PathBuf::from("/tmp/test/kvcore/kvcore_import_on_follower");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        init_default_global_tracing(dir.join("log"), "tskv.log", "debug");
+        let tenant = "cnosdb";
+        let database = "db_test_import";
+        let (runtime, tskv) = get_tskv(&dir, None);
+        let engine: EngineRef = Arc::new(tskv);
+        let export_dir = dir.join("export");
+        let rows = export_random_points(
+            runtime.clone(),
+            engine.clone(),
+            tenant,
+            database,
+            31,
+            &export_dir,
+        );
+
+        let open = |vnode_id| {
+            runtime
+                .block_on(engine.open_tsfamily(tenant, database, vnode_id))
+                .unwrap()
+        };
+        let (leader, follower) = (open(32), open(33));
+        let import = stage_import(
+            &runtime,
+            &leader,
+            &[&leader, &follower],
+            &export_dir,
+            "32_1",
+        );
+
+        // The follower adds the files fetched from the leader when it applies the entry.
+        for vnode in [&follower, &leader] {
+            let command = raft_write_command::Command::ImportFiles(import.clone());
+            apply_command(&runtime, vnode, 2, command).unwrap();
+            assert!(!file_manager::try_exists(import_dir(vnode, "32_1")));
+
+            let report = runtime
+                .block_on(bulk::export_vnode(
+                    runtime.clone(),
+                    engine.clone(),
+                    tenant,
+                    database,
+                    vnode.id,
+                    dir.join(format!("export_{}", vnode.id)),
+                ))
+                .unwrap();
+            assert_eq!(report.rows, rows);
+        }
+
+        runtime.block_on(engine.close());
+        println!("Leave serial test: test_kvcore_import_on_follower");
+    }
+
+    #[test]
+    #[serial]
+    fn test_kvcore_import_leader_change() {
+        println!("Enter serial test: test_kvcore_import_leader_change");
+        let dir = PathBuf::from("/tmp/test/kvcore/kvcore_import_leader_change");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        init_default_global_tracing(dir.join("log"), "tskv.log", "debug");
+        let tenant = "cnosdb";
+        let database = "db_test_import_leader_change";
+        let (runtime, tskv) = get_tskv(&dir, None);
+        let engine: EngineRef = Arc::new(tskv);
+        let export_dir = dir.join("export");
+        let rows = export_random_points(
+            runtime.clone(),
+            engine.clone(),
+            tenant,
+            database,
+            41,
+            &export_dir,
+        );
+        let open = |vnode_id| {
+            runtime
+                .block_on(engine.open_tsfamily(tenant, database, vnode_id))
+                .unwrap()
+        };
+        let export_rows = |vnode: &VnodeStorage, name: &str| {
+            runtime
+                .block_on(bulk::export_vnode(
+                    runtime.clone(),
+                    engine.clone(),
+                    tenant,
+                    database,
+                    vnode.id,
+                    dir.join(name),
+                ))
+                .unwrap()
+                .rows
+        };
+        let abort = |import_id: &str| {
+            raft_write_command::Command::AbortImport(AbortImportRequest {
+                import_id: import_id.to_string(),
+            })
+        };
+
+        // The leader changes from vnode 42 to vnode 43 after the import is staged, the
+        // write of the import entry fails on the old leader, but the entry is committed
+        // by the new leader, so the import is aborted after it is applied.
+        let (old_leader, new_leader) = (open(42), open(43));
+        let vnodes = [&old_leader, &new_leader];
+        let import = stage_import(&runtime, &old_leader, &vnodes, &export_dir, "42_1");
+        for vnode in [&new_leader, &old_leader] {
+            let command = raft_write_command::Command::ImportFiles(import.clone());
+            apply_command(&runtime, vnode, 2, command).unwrap();
+            apply_command(&runtime, vnode, 3, abort("42_1")).unwrap();
+            assert_eq!(export_rows(vnode, &format!("export_{}", vnode.id)), rows);
+        }
+
+        // The entry is not committed by the new leader, so only the abort is applied and
+        // the staged files are removed on all the vnodes.
+        let (old_leader, new_leader) = (open(44), open(45));
+        let vnodes = [&old_leader, &new_leader];
+        stage_import(&runtime, &old_leader, &vnodes, &export_dir, "44_1");
+        for vnode in vnodes {
+            assert!(file_manager::try_exists(import_dir(vnode, "44_1")));
+            apply_command(&runtime, vnode, 2, abort("44_1")).unwrap();
+            assert!(!file_manager::try_exists(import_dir(vnode, "44_1")));
+            assert_eq!(export_rows(vnode, &format!("export_{}", vnode.id)), 0);
+        }
+
+        runtime.block_on(engine.close());
+        println!("Leave serial test: test_kvcore_import_leader_change");
+    }
+
     fn sleep_in_runtime(runtime: Arc<Runtime>, duration: Duration) {
         let rt = runtime.clone();
         runtime.block_on(async move {